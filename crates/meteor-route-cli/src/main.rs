@@ -0,0 +1,636 @@
+//! meteor-route-cli: set up a fee router vault and run its daily crank
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_interface::TokenAccount;
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Parser, Subcommand};
+use meteor_route_client::{
+    investors,
+    pages::{crank_day_epoch, next_page_index, page_accounts, paginate, PayoutDestination},
+    rpc::{self, CrankParams, VaultState},
+    mint_risk, CpAmmPositionAccounts, InitializePolicyArgs, PolicyInit, Vault, AUTO_TICK_RANGE,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    signature::{read_keypair_file, Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+#[derive(Parser)]
+#[command(name = "meteor-route-cli", about = "Set up a MeteorRoute vault and run its daily crank")]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, env = "METEOR_ROUTE_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Fee payer and signer keypair file
+    #[arg(long, env = "METEOR_ROUTE_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    /// Vault seed the router PDAs are derived from
+    #[arg(long, env = "METEOR_ROUTE_VAULT_SEED")]
+    vault_seed: String,
+    /// Quote mint of the vault
+    #[arg(long, env = "METEOR_ROUTE_QUOTE_MINT")]
+    quote_mint: Pubkey,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the policy PDA (signer becomes the policy authority)
+    InitPolicy {
+        #[arg(long)]
+        base_mint: Pubkey,
+        /// CP-AMM pool
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long, default_value_t = cp_amm::ID)]
+        cp_amm_program: Pubkey,
+        #[arg(long)]
+        investor_fee_share_bps: u16,
+        /// Daily investor cap in quote lamports (0 = no cap)
+        #[arg(long, default_value_t = 0)]
+        daily_cap: u64,
+        #[arg(long, default_value_t = 0)]
+        min_payout: u64,
+        /// Create missing investor quote ATAs from the position owner PDA's lamports
+        #[arg(long)]
+        fund_missing_ata: bool,
+        /// Total investor allocation at TGE (Y0)
+        #[arg(long)]
+        y0_total_allocation: u128,
+        /// Accept a quote mint with a freeze authority
+        #[arg(long)]
+        allow_freeze_authority: bool,
+        /// Accept a Token-2022 quote mint with a permanent delegate
+        #[arg(long)]
+        allow_permanent_delegate: bool,
+        /// Accept a pausable Token-2022 quote mint
+        #[arg(long)]
+        allow_pausable: bool,
+        /// Accept a Token-2022 quote mint with confidential transfer or mint/burn extensions
+        #[arg(long)]
+        allow_confidential: bool,
+    },
+    /// Create the progress PDA
+    InitProgress,
+    /// Open the honorary quote-only CP-AMM position in the policy's pool
+    ///
+    /// Without a range the program derives the widest quote-only one at the live price.
+    InitPosition {
+        #[arg(long, allow_hyphen_values = true, requires = "tick_upper")]
+        tick_lower: Option<i32>,
+        #[arg(long, allow_hyphen_values = true, requires = "tick_lower")]
+        tick_upper: Option<i32>,
+    },
+    /// Run the crank over every remaining page of the investor list
+    Crank(CrankArgs),
+    /// Print policy and progress state
+    Status,
+    /// Print the program's view of the day's distribution (simulated `get_distribution_params`)
+    Params,
+    /// Create the on-chain crank log; every later crank is recorded in it (policy authority)
+    InitCrankLog,
+    /// Print the most recent cranks from the crank log
+    CrankLog,
+    /// Force-finalize a stalled day
+    Finalize {
+        #[arg(long)]
+        creator_quote_ata: Pubkey,
+        /// Creator wallet, to create a missing creator quote ATA (policy.fund_missing_creator_ata)
+        #[arg(long)]
+        creator_wallet: Option<Pubkey>,
+        /// State account forwarded to the post-finalize hook (policy.hook_program)
+        #[arg(long)]
+        hook_state: Option<Pubkey>,
+    },
+    /// Stop distribution until `resume` (policy authority)
+    Pause,
+    /// Resume a paused vault (policy authority)
+    Resume,
+    /// Restart a paused vault under its next generation (policy authority)
+    Reinitialize,
+    /// Override one investor's minimum payout, or remove the override without --min-payout
+    /// (policy authority; creates the override table on first use)
+    SetMinPayout {
+        #[arg(long)]
+        investor: Pubkey,
+        #[arg(long)]
+        min_payout: Option<u64>,
+    },
+    /// Give one investor a fixed weight on top of their locked tokens, or remove it without
+    /// --weight (policy authority; only between days; creates the weight table on first use)
+    SetWeight {
+        #[arg(long)]
+        investor: Pubkey,
+        #[arg(long)]
+        weight: Option<u64>,
+    },
+    /// Commit the day's page count and the investors its pages cover before any payouts
+    /// (policy authority; starts the day if its gate has opened)
+    SetExpectedPages {
+        #[arg(long)]
+        pages: u64,
+        /// Investors the pages must cover in total (0 = unchecked)
+        #[arg(long, default_value_t = 0)]
+        investors: u32,
+    },
+    /// Add lamports to the reserve that repays the crank for missing ATA and escrow rent
+    FundAtaReserve {
+        #[arg(long)]
+        lamports: u64,
+    },
+    /// Pay an investor their escrowed payouts whose dispute window has passed
+    WithdrawEscrow {
+        #[arg(long)]
+        investor: Pubkey,
+    },
+    /// Claw back an investor's escrowed payout for a day while its dispute window is open
+    /// (policy authority; the amount joins the next day's investor pool)
+    ClawBack {
+        #[arg(long)]
+        investor: Pubkey,
+        #[arg(long)]
+        day_epoch: u64,
+    },
+    /// Publish an investor's off-chain locked amount (policy lock oracle; the payer signs as oracle)
+    PublishLocked {
+        #[arg(long)]
+        investor: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        /// When the amount was observed (default: now)
+        #[arg(long)]
+        as_of_ts: Option<u64>,
+    },
+    /// Hand the policy authority to another key (policy authority)
+    SetAuthority {
+        #[arg(long)]
+        new_authority: Pubkey,
+        /// Realms governance account the new authority is (or whose native treasury it is)
+        #[arg(long)]
+        governance: Option<Pubkey>,
+    },
+}
+
+#[derive(Args)]
+struct CrankArgs {
+    /// JSON array of { "stream", "investor", "index" }
+    #[arg(long)]
+    investors: PathBuf,
+    /// Investors per page (one page per transaction)
+    #[arg(long, default_value_t = 4)]
+    page_size: usize,
+    /// Creator quote token account receiving the day's remainder
+    #[arg(long)]
+    creator_quote_ata: Pubkey,
+    /// Creator wallet, to create a missing creator quote ATA (policy.fund_missing_creator_ata)
+    #[arg(long)]
+    creator_wallet: Option<Pubkey>,
+    /// State account forwarded to the post-finalize hook (policy.hook_program)
+    #[arg(long)]
+    hook_state: Option<Pubkey>,
+    /// Locker program the stream accounts belong to (default: Streamflow)
+    #[arg(long)]
+    locker_program: Option<Pubkey>,
+    /// Acquire (or renew) the crank lease in every transaction
+    #[arg(long)]
+    lease: bool,
+    #[arg(long, default_value_t = 1_400_000)]
+    compute_units: u32,
+}
+
+struct Ctx {
+    rpc: RpcClient,
+    payer: Keypair,
+    vault: Vault,
+}
+
+impl Ctx {
+    fn send(&self, ixs: &[Instruction], extra_signers: &[&Keypair]) -> Result<Signature> {
+        let mut signers: Vec<&dyn Signer> = vec![&self.payer];
+        signers.extend(extra_signers.iter().map(|k| *k as &dyn Signer));
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&self.payer.pubkey()), &signers, blockhash);
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+
+    fn state(&self) -> Result<VaultState> {
+        Ok(rpc::vault_state(&self.rpc, &self.vault)?)
+    }
+
+    /// Latest slot and cluster time, the clocks the day gate is evaluated against
+    fn clock(&self) -> Result<(u64, u64)> {
+        Ok(rpc::slot_and_time(&self.rpc)?)
+    }
+
+    /// Owner of the creator quote account, or `fallback` while the account doesn't exist
+    fn creator_wallet(&self, creator_quote_ata: &Pubkey, fallback: Option<Pubkey>) -> Result<Pubkey> {
+        let account: Option<TokenAccount> = rpc::fetch_optional(&self.rpc, creator_quote_ata)?;
+        match (account, fallback) {
+            (Some(account), _) => Ok(account.owner),
+            (None, Some(wallet)) => Ok(wallet),
+            (None, None) => bail!("creator quote account {} does not exist; pass --creator-wallet", creator_quote_ata),
+        }
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keypair_path = expand_home(&cli.keypair);
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("reading keypair {}: {}", keypair_path.display(), err))?;
+    let ctx = Ctx {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        payer,
+        vault: Vault::new(cli.vault_seed, cli.quote_mint),
+    };
+    let authority = ctx.payer.pubkey();
+
+    match cli.command {
+        Command::InitPolicy {
+            base_mint,
+            pool,
+            cp_amm_program,
+            investor_fee_share_bps,
+            daily_cap,
+            min_payout,
+            fund_missing_ata,
+            y0_total_allocation,
+            allow_freeze_authority,
+            allow_permanent_delegate,
+            allow_pausable,
+            allow_confidential,
+        } => {
+            let allowed_quote_mint_risks = [
+                (allow_freeze_authority, mint_risk::FREEZE_AUTHORITY),
+                (allow_permanent_delegate, mint_risk::PERMANENT_DELEGATE),
+                (allow_pausable, mint_risk::PAUSABLE),
+                (allow_confidential, mint_risk::CONFIDENTIAL),
+            ]
+            .iter()
+            .filter(|(allow, _)| *allow)
+            .fold(0, |bits, (_, risk)| bits | risk);
+            let args = PolicyInit {
+                args: InitializePolicyArgs {
+                    investor_fee_share_bps,
+                    daily_cap_quote_lamports: daily_cap,
+                    min_payout_lamports: min_payout,
+                    policy_fund_missing_ata: fund_missing_ata,
+                    y0_total_allocation,
+                    allowed_quote_mint_risks,
+                },
+                quote_token_program: rpc::owner(&ctx.rpc, &ctx.vault.quote_mint)?,
+            };
+            let ix = ctx.vault.initialize_policy(authority, authority, base_mint, pool, cp_amm_program, &args);
+            let sig = ctx.send(&[ix], &[])?;
+            println!("policy {} initialized: {}", ctx.vault.policy(), sig);
+        }
+        Command::InitProgress => {
+            let sig = ctx.send(&[ctx.vault.initialize_progress(authority, authority)], &[])?;
+            println!("progress {} initialized: {}", ctx.vault.progress(), sig);
+        }
+        Command::InitPosition { tick_lower, tick_upper } => {
+            let policy = ctx.state()?.policy;
+            let pool: cp_amm::state::Pool = rpc::fetch_zero_copy(&ctx.rpc, &policy.pool_pubkey)?;
+            let position_mint = Keypair::new();
+            let accounts = CpAmmPositionAccounts {
+                cp_amm_program: policy.cp_amm_program_id,
+                pool: policy.pool_pubkey,
+                pool_token_vault_0: pool.token_a_vault,
+                pool_token_vault_1: pool.token_b_vault,
+                base_mint: policy.base_mint,
+                quote_token_program: rpc::owner(&ctx.rpc, &policy.quote_mint)?,
+                base_token_program: rpc::owner(&ctx.rpc, &policy.base_mint)?,
+                position_mint: position_mint.pubkey(),
+            };
+            let (tick_lower, tick_upper) = tick_lower.zip(tick_upper).unwrap_or(AUTO_TICK_RANGE);
+            let ix = ctx
+                .vault
+                .initialize_honorary_position(authority, authority, &accounts, tick_lower, tick_upper);
+            let sig = ctx.send(&[ix], &[&position_mint])?;
+            println!("honorary position (nft mint {}) opened: {}", position_mint.pubkey(), sig);
+        }
+        Command::Crank(args) => crank(&ctx, &args)?,
+        Command::Status => status(&ctx)?,
+        Command::Params => params(&ctx)?,
+        Command::InitCrankLog => {
+            let sig = ctx.send(&[ctx.vault.initialize_crank_log(authority, authority)], &[])?;
+            println!("crank log initialized: {}", sig);
+        }
+        Command::CrankLog => crank_log(&ctx)?,
+        Command::Finalize { creator_quote_ata, creator_wallet, hook_state } => {
+            let state = ctx.state()?;
+            let params = CrankParams {
+                crank_caller: authority,
+                creator_quote_ata,
+                locker_program: None,
+                creator_wallet: ctx.creator_wallet(&creator_quote_ata, creator_wallet)?,
+            };
+            let mut crank_accounts = rpc::crank_accounts(&ctx.rpc, &ctx.vault, &state, &params)?;
+            crank_accounts.options.creator_stream_day = state
+                .progress
+                .as_ref()
+                .filter(|_| state.policy.creator_vesting_secs > 0)
+                .map(|progress| progress.day_epoch);
+            crank_accounts.options.hook_state = hook_state;
+            let ix = ctx.vault.force_finalize_day(
+                authority,
+                creator_quote_ata,
+                crank_accounts.token_program,
+                &crank_accounts.options,
+            );
+            let sig = ctx.send(&[ix], &[])?;
+            println!("day force-finalized: {}", sig);
+        }
+        Command::Pause | Command::Resume => {
+            let paused = matches!(cli.command, Command::Pause);
+            let sig = ctx.send(&[ctx.vault.set_policy_paused(authority, paused)], &[])?;
+            println!("policy {}: {}", if paused { "paused" } else { "resumed" }, sig);
+        }
+        Command::Reinitialize => {
+            let policy = ctx.state()?.policy;
+            let ix = ctx.vault.reinitialize_vault(authority, policy.track_paid_investors);
+            let sig = ctx.send(&[ix], &[])?;
+            println!("vault reinitialized: {}", sig);
+        }
+        Command::SetMinPayout { investor, min_payout } => {
+            let policy = ctx.state()?.policy;
+            let mut ixs = Vec::new();
+            if !policy.payout_overrides {
+                ixs.push(ctx.vault.initialize_payout_overrides(authority, authority));
+            }
+            ixs.push(ctx.vault.set_payout_override(authority, investor, min_payout));
+            let sig = ctx.send(&ixs, &[])?;
+            println!("min payout override for {} set to {:?}: {}", investor, min_payout, sig);
+        }
+        Command::SetWeight { investor, weight } => {
+            let policy = ctx.state()?.policy;
+            let mut ixs = Vec::new();
+            if !policy.weight_overrides {
+                ixs.push(ctx.vault.initialize_weight_overrides(authority, authority));
+            }
+            ixs.push(ctx.vault.set_weight_override(authority, investor, weight));
+            let sig = ctx.send(&ixs, &[])?;
+            println!("weight override for {} set to {:?}: {}", investor, weight, sig);
+        }
+        Command::SetExpectedPages { pages, investors } => {
+            let sig = ctx.send(&[ctx.vault.set_expected_pages(authority, pages, investors)], &[])?;
+            println!("expected pages {} and investors {} committed: {}", pages, investors, sig);
+        }
+        Command::FundAtaReserve { lamports } => {
+            let sig = ctx.send(&[ctx.vault.fund_ata_reserve(authority, lamports)], &[])?;
+            println!("ATA rent reserve funded with {} lamports: {}", lamports, sig);
+        }
+        Command::WithdrawEscrow { investor } => {
+            let token_program = rpc::owner(&ctx.rpc, &ctx.vault.quote_mint)?;
+            let ix = ctx.vault.withdraw_escrowed_payouts(authority, investor, token_program);
+            let sig = ctx.send(&[ix], &[])?;
+            println!("escrowed payouts withdrawn for {}: {}", investor, sig);
+        }
+        Command::ClawBack { investor, day_epoch } => {
+            let sig = ctx.send(&[ctx.vault.claw_back_escrowed_payout(authority, investor, day_epoch)], &[])?;
+            println!("escrowed payout of {} for day {} clawed back: {}", investor, day_epoch, sig);
+        }
+        Command::PublishLocked { investor, amount, as_of_ts } => {
+            let as_of_ts = match as_of_ts {
+                Some(ts) => ts,
+                None => ctx.clock()?.1,
+            };
+            let ix = ctx.vault.publish_oracle_locked(authority, authority, investor, amount, as_of_ts);
+            let sig = ctx.send(&[ix], &[])?;
+            println!("locked amount of {} published as {} at {}: {}", investor, amount, as_of_ts, sig);
+        }
+        Command::SetAuthority { new_authority, governance } => {
+            let ix = ctx.vault.set_policy_authority(authority, new_authority, governance);
+            let sig = ctx.send(&[ix], &[])?;
+            println!("policy authority set to {}: {}", new_authority, sig);
+        }
+    }
+    Ok(())
+}
+
+fn crank(ctx: &Ctx, args: &CrankArgs) -> Result<()> {
+    let mut investors = investors::load(&args.investors).map_err(|err| anyhow!(err))?;
+    // Snapshot and pages both go in stream-key order, which exactly-once tracking requires
+    investors.sort_by_key(|inv| inv.stream);
+    let caller = ctx.payer.pubkey();
+    let state = ctx.state()?;
+    let page_size = state.policy.page_size(args.page_size);
+    let Some(progress) = state.progress.as_ref() else {
+        bail!("progress PDA {} is not initialized", ctx.vault.progress());
+    };
+    let params = CrankParams {
+        crank_caller: caller,
+        creator_quote_ata: args.creator_quote_ata,
+        locker_program: args.locker_program,
+        creator_wallet: ctx.creator_wallet(&args.creator_quote_ata, args.creator_wallet)?,
+    };
+    let mut crank_accounts = rpc::crank_accounts(&ctx.rpc, &ctx.vault, &state, &params)?;
+    crank_accounts.options.hook_state = args.hook_state;
+
+    let (slot, now) = ctx.clock()?;
+    let day_gate = state.policy.day_gate();
+    let new_day = progress.is_new_day_under(day_gate, now, slot);
+    if !new_day && progress.day_finalized_flag {
+        println!("day {} is already finalized", progress.day_epoch);
+        return Ok(());
+    }
+    // A vesting policy opens the creator stream of the day being cranked
+    let day_epoch = crank_day_epoch(progress, day_gate, now, slot);
+    crank_accounts.options.creator_stream_day = (state.policy.creator_vesting_secs > 0).then_some(day_epoch);
+    let preamble = |ixs: &mut Vec<Instruction>| {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(args.compute_units));
+        if args.lease {
+            ixs.push(ctx.vault.acquire_crank_lease(caller));
+        }
+    };
+
+    // The authority commits the day's counts before the snapshot completes; anyone else cranks the
+    // day uncommitted unless the authority already has
+    let uncommitted = new_day || (!progress.snapshot_complete && progress.committed_investor_count == 0);
+    if uncommitted && !investors.is_empty() && state.policy.authority == caller {
+        let pages = investors.len().div_ceil(page_size.max(1)) as u64;
+        let ix = ctx.vault.set_expected_pages(caller, pages, investors.len() as u32);
+        let sig = ctx.send(&[ix], &[])?;
+        println!("committed {} pages / {} investors: {}", pages, investors.len(), sig);
+    }
+
+    // Investor pages are weighed against the day's snapshot; resume a partial one from where it stopped
+    if new_day || !progress.snapshot_complete {
+        let done = if new_day { 0 } else { progress.snapshot_investor_count as usize };
+        let pending = investors.get(done..).unwrap_or_default();
+        let chunks: Vec<_> = pending.chunks(page_size).collect();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut ixs = Vec::new();
+            preamble(&mut ixs);
+            let is_final_chunk = i + 1 == chunks.len();
+            ixs.push(ctx.vault.snapshot_locked(caller, chunk.to_vec(), is_final_chunk, crank_accounts.options.weight_overrides));
+            let sig = ctx.send(&ixs, &[])?;
+            println!("snapshot chunk {}/{}: {}", i + 1, chunks.len(), sig);
+        }
+    }
+
+    let cursor = next_page_index(progress, day_gate, now, slot);
+    // Unordered pages another keeper already completed today are skipped
+    let unordered = state.policy.unordered_pages && !new_day;
+    let pages: Vec<_> = paginate(&ctx.vault.vault_seed, day_epoch, progress.generation, &investors, page_size, 0)
+        .into_iter()
+        .filter(|page| page.page_index >= cursor && !(unordered && progress.page_processed(page.page_index)))
+        .collect();
+    // Nothing locked in the snapshot: the creator takes the day without pages
+    let zero_locked = ctx
+        .state()?
+        .progress
+        .is_some_and(|progress| progress.zero_locked_day() && progress.first_page_pending());
+    if pages.is_empty() || zero_locked {
+        // Nothing left to pay: one final call claims and closes the day
+        let mut ixs = Vec::new();
+        preamble(&mut ixs);
+        ixs.push(ctx.vault.distribute_fees(&crank_accounts, vec![], true, vec![]));
+        let sig = ctx.send(&ixs, &[])?;
+        println!("day finalized: {}", sig);
+        return Ok(());
+    }
+
+    let last = pages.len() - 1;
+    let attestation = state.policy.attestation_gate();
+    let destination = PayoutDestination::for_policy(&state.policy, &ctx.vault.vault_seed);
+    for (i, page) in pages.into_iter().enumerate() {
+        let page_index = page.page_index;
+        let remaining = page_accounts(
+            &page.investors,
+            &ctx.vault.quote_mint,
+            &crank_accounts.token_program,
+            attestation.as_ref(),
+            &destination,
+        );
+        // An unordered day finalizes when its last page lands, whichever keeper sends it
+        let is_final = i == last && !state.policy.unordered_pages;
+        let mut ixs = Vec::new();
+        preamble(&mut ixs);
+        ixs.push(ctx.vault.distribute_fees(&crank_accounts, vec![page], is_final, remaining));
+        let sig = ctx.send(&ixs, &[])?;
+        println!("page {}{}: {}", page_index, if is_final { " (final)" } else { "" }, sig);
+    }
+    Ok(())
+}
+
+fn crank_log(ctx: &Ctx) -> Result<()> {
+    let Some(log) = rpc::crank_log(&ctx.rpc, &ctx.vault)? else {
+        println!("crank log: not initialized");
+        return Ok(());
+    };
+    println!("crank log ({} calls recorded, newest first)", log.total_calls);
+    for entry in log.recent() {
+        println!(
+            "  ts {} day {}: caller {} pages={} investors={} distributed={}{}",
+            entry.timestamp,
+            entry.day_epoch,
+            entry.caller,
+            entry.pages,
+            entry.investors_processed,
+            entry.distributed,
+            if entry.day_finalized { " (day finalized)" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+fn params(ctx: &Ctx) -> Result<()> {
+    let params = rpc::distribution_params(&ctx.rpc, &ctx.vault, &ctx.payer.pubkey())?;
+    println!("day {} (finalized: {})", params.day_epoch, params.day_finalized);
+    println!("  locked_total:           {}", params.locked_total);
+    println!("  eligible_bps:           {}", params.eligible_bps);
+    match params.remaining_daily_cap {
+        u64::MAX => println!("  remaining_daily_cap:    uncapped"),
+        cap => println!("  remaining_daily_cap:    {}", cap),
+    }
+    println!(
+        "  investor distributed:   {} / {}",
+        params.day_investor_distributed, params.day_investor_pool_target
+    );
+    println!("  creator_target:         {}", params.day_creator_remainder_target);
+    println!("  claimed_quote:          {}", params.day_claimed_quote);
+    println!("  pagination_cursor:      {}", params.pagination_cursor);
+    println!("  page_investor_cursor:   {}", params.page_investor_cursor);
+    println!("  pages_processed_today:  {}", params.pages_processed_today);
+    println!("  total_pages_expected:   {}", params.total_pages_expected);
+    Ok(())
+}
+
+fn status(ctx: &Ctx) -> Result<()> {
+    let state = ctx.state()?;
+    let policy = &state.policy;
+    println!("policy {}", ctx.vault.policy());
+    println!("  authority:              {}", policy.authority);
+    println!("  paused:                 {}", policy.paused);
+    println!("  pool ({:?}):        {}", policy.pool_kind, policy.pool_pubkey);
+    println!("  investor_fee_share_bps: {}", policy.investor_fee_share_bps);
+    println!("  daily_cap:              {}", policy.daily_cap_quote_lamports);
+    println!("  tranches_per_day:       {}", policy.tranches());
+    println!("  max_weight_bps:         {}", policy.max_weight_bps);
+    println!("  min_payout:             {}", policy.min_payout_lamports);
+    for params in &policy.param_schedule {
+        println!(
+            "  scheduled for day {}: daily_cap {:?}, min_payout {:?}",
+            params.effective_day_epoch, params.daily_cap_quote_lamports, params.min_payout_lamports
+        );
+    }
+    println!("  y0_total_allocation:    {}", policy.y0_total_allocation);
+    println!("  static accounts cached: {}", policy.static_accounts.is_some());
+    println!("  day gate:               {:?}", policy.day_gate());
+
+    match state.position_owner.as_ref() {
+        Some(owner) => {
+            println!("position {} (quote-only verified: {})", owner.position_pubkey, owner.verified_quote_only);
+            println!("  lifetime claimed quote: {}", owner.lifetime_claimed_quote);
+        }
+        None => println!("position: not initialized"),
+    }
+
+    let Some(progress) = state.progress.as_ref() else {
+        println!("progress: not initialized");
+        return Ok(());
+    };
+    let (slot, now) = ctx.clock()?;
+    println!("progress {}", ctx.vault.progress());
+    println!("  generation:             {}", progress.generation);
+    println!("  day_epoch:              {}", progress.day_epoch);
+    println!("  day_finalized:          {}", progress.day_finalized_flag);
+    println!("  pages_processed_today:  {}", progress.pages_processed_today);
+    println!("  pagination_cursor:      {}", progress.pagination_cursor);
+    if progress.page_in_progress_flag {
+        println!("  page_investor_cursor:   {}", progress.page_investor_cursor);
+    }
+    println!(
+        "  investor distributed:   {} / {}",
+        progress.day_investor_distributed, progress.day_investor_pool_target
+    );
+    println!("  creator_owed:           {}", progress.creator_owed);
+    println!("  buyback_pending:        {}", progress.buyback_pending);
+    println!("  last_distribution_ts:   {}", progress.last_distribution_ts);
+    println!(
+        "  next day can start:     {}",
+        progress.is_new_day_under(policy.day_gate(), now, slot)
+            && progress.can_start_new_day_under(policy.day_gate(), now)
+    );
+    println!(
+        "  stalled:                {}",
+        progress.is_stalled(now, policy.force_finalize_after_secs)
+    );
+    Ok(())
+}
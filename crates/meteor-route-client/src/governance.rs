@@ -0,0 +1,106 @@
+//! Realms (SPL Governance) proposal transactions for a DAO-owned policy authority
+//!
+//! Once `set_policy_authority` hands the policy to a governance account or its native treasury,
+//! authority instructions such as `update_policy` and `set_policy_paused` are built with that PDA
+//! as the authority and inserted into a proposal. When the proposal executes, the governance
+//! program signs for the PDA, which satisfies the router's `authority` signer.
+use anchor_lang::{
+    prelude::{borsh, Pubkey},
+    solana_program::instruction::Instruction,
+    AnchorDeserialize, AnchorSerialize,
+};
+pub use meteor_route_fee_router::governance::{native_treasury, SPL_GOVERNANCE_PROGRAM_ID};
+
+/// Realms `AccountMetaData`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccountMetaData {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Realms `InstructionData`, the instruction format `insert_transaction` stores in a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InstructionData {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMetaData>,
+    pub data: Vec<u8>,
+}
+
+impl From<&Instruction> for InstructionData {
+    fn from(ix: &Instruction) -> Self {
+        InstructionData {
+            program_id: ix.program_id,
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| AccountMetaData {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data.clone(),
+        }
+    }
+}
+
+/// Proposal transaction instructions for router instructions signed by a governance PDA
+///
+/// Every signer other than `governance_signer` must be dropped before the proposal can execute,
+/// since the governance program can only sign for its own PDAs.
+pub fn proposal_instructions(ixs: &[Instruction], governance_signer: &Pubkey) -> Option<Vec<InstructionData>> {
+    let only_governance_signs = ixs
+        .iter()
+        .flat_map(|ix| ix.accounts.iter())
+        .all(|meta| !meta.is_signer || meta.pubkey == *governance_signer);
+    only_governance_signs.then(|| ixs.iter().map(InstructionData::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UpdatePolicyArgs, Vault};
+    use meteor_route_fee_router::{governance::governance_signs_for, ID};
+
+    #[test]
+    fn test_governance_pda_signs_policy_instructions() {
+        let vault = Vault::new("vault", Pubkey::new_unique());
+        let governance = Pubkey::new_unique();
+        let treasury = native_treasury(&governance);
+        assert!(governance_signs_for(&governance, &treasury));
+
+        let update = UpdatePolicyArgs {
+            investor_fee_share_bps: Some(6000),
+            ..Default::default()
+        };
+        let ixs = [
+            vault.update_policy(treasury, &update),
+            vault.set_policy_paused(treasury, true),
+            vault.set_policy_authority(treasury, Pubkey::new_unique(), None),
+        ];
+        let proposal = proposal_instructions(&ixs, &treasury).unwrap();
+        for (ix, data) in ixs.iter().zip(proposal.iter()) {
+            assert_eq!(data.program_id, ID);
+            assert_eq!(data.data, ix.data);
+            // The governance PDA is the only signer and is not debited
+            let signers: Vec<&AccountMetaData> = data.accounts.iter().filter(|meta| meta.is_signer).collect();
+            assert_eq!(signers.len(), 1);
+            assert_eq!((signers[0].pubkey, signers[0].is_writable), (treasury, false));
+        }
+
+        // A different governance can't execute it
+        assert!(proposal_instructions(&ixs, &native_treasury(&Pubkey::new_unique())).is_none());
+    }
+
+    #[test]
+    fn test_instruction_data_layout() {
+        let ix = Vault::new("vault", Pubkey::new_unique()).set_policy_paused(Pubkey::new_unique(), false);
+        let data = InstructionData::from(&ix);
+        let bytes = data.try_to_vec().unwrap();
+        // program_id, u32 account count, 34 bytes per account, u32 data length, data
+        assert_eq!(bytes.len(), 32 + 4 + 34 * ix.accounts.len() + 4 + ix.data.len());
+        assert_eq!(&bytes[..32], ID.as_ref());
+        assert_eq!(InstructionData::try_from_slice(&bytes).unwrap(), data);
+    }
+}
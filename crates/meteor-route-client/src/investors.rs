@@ -0,0 +1,60 @@
+//! Investor list file: a JSON array of `{ "stream": .., "investor": .., "index": .. }`
+use std::{path::Path, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use meteor_route_fee_router::InvestorData;
+use serde_json::Value;
+
+fn pubkey_field(entry: &Value, field: &str, i: usize) -> Result<Pubkey, String> {
+    let text = entry[field]
+        .as_str()
+        .ok_or_else(|| format!("investor {}: missing \"{}\"", i, field))?;
+    Pubkey::from_str(text).map_err(|_| format!("investor {}: invalid \"{}\" {}", i, field, text))
+}
+
+/// Parse investors in list order; `index` defaults to the position in the list
+pub fn parse(json: &str) -> Result<Vec<InvestorData>, String> {
+    let entries: Vec<Value> = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let index = match entry.get("index") {
+                Some(index) => index
+                    .as_u64()
+                    .and_then(|index| u32::try_from(index).ok())
+                    .ok_or_else(|| format!("investor {}: invalid \"index\"", i))?,
+                None => i as u32,
+            };
+            Ok(InvestorData {
+                stream: pubkey_field(entry, "stream", i)?,
+                investor: pubkey_field(entry, "investor", i)?,
+                index,
+            })
+        })
+        .collect()
+}
+
+/// Read and parse an investor list file
+pub fn load(path: &Path) -> Result<Vec<InvestorData>, String> {
+    let json = std::fs::read_to_string(path).map_err(|err| format!("reading {}: {}", path.display(), err))?;
+    parse(&json).map_err(|err| format!("parsing {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_defaults_index_to_position() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let json = format!(
+            r#"[{{"stream":"{a}","investor":"{b}"}},{{"stream":"{b}","investor":"{a}","index":7}}]"#
+        );
+        let investors = parse(&json).unwrap();
+        assert_eq!(investors.len(), 2);
+        assert_eq!((investors[0].stream, investors[0].index), (a, 0));
+        assert_eq!((investors[1].investor, investors[1].index), (a, 7));
+        assert!(parse(r#"[{"stream":"x","investor":"y"}]"#).is_err());
+    }
+}
@@ -0,0 +1,1429 @@
+//! Instruction builders for every fee router instruction
+//!
+//! Builders fill in all PDAs and fixed program IDs; callers supply only the accounts that differ
+//! per vault or per call. Instructions that create accounts take the rent `payer` separately from
+//! the policy `authority`, so a multisig vault can be the authority without holding SOL.
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{instruction::Instruction, sysvar},
+    system_program,
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::{get_associated_token_address, get_associated_token_address_with_program_id};
+use cp_amm::constants::seeds::{POSITION_NFT_ACCOUNT_PREFIX, POSITION_PREFIX};
+use meteor_route_fee_router::{
+    accounts, cp_amm as router_cp_amm, dlmm, instruction,
+    memo::MEMO_PROGRAM_ID,
+    networks::{Network, NetworkPrograms},
+    state::{PolicyPda, StaticAccounts},
+    streamflow::{self, STREAMFLOW_FEE_ORACLE, STREAMFLOW_PROGRAM_ID, STREAMFLOW_TREASURY, STREAMFLOW_WITHDRAWOR},
+    whirlpool, InitializePolicyArgs, InvestorData, InvestorPage, UpdatePolicyArgs, ID,
+};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Arguments of `initialize_policy`
+#[derive(Clone, Debug)]
+pub struct PolicyInit {
+    pub args: InitializePolicyArgs,
+    /// Token program that owns the quote mint (SPL Token or Token-2022); passed as an account
+    pub quote_token_program: Pubkey,
+}
+
+/// CP-AMM accounts for `initialize_honorary_position`
+#[derive(Clone, Debug)]
+pub struct CpAmmPositionAccounts {
+    pub cp_amm_program: Pubkey,
+    pub pool: Pubkey,
+    pub pool_token_vault_0: Pubkey,
+    pub pool_token_vault_1: Pubkey,
+    pub base_mint: Pubkey,
+    /// Token programs that own the quote and base mints (SPL Token or Token-2022)
+    pub quote_token_program: Pubkey,
+    pub base_token_program: Pubkey,
+    /// Fresh keypair; must sign the transaction
+    pub position_mint: Pubkey,
+}
+
+/// DLMM accounts for `initialize_dlmm_position`
+#[derive(Clone, Debug)]
+pub struct DlmmPositionAccounts {
+    pub lb_pair: Pubkey,
+    pub base_mint: Pubkey,
+    /// Fresh keypair; must sign the transaction
+    pub position: Pubkey,
+}
+
+/// Whirlpool accounts for `initialize_whirlpool_position`
+#[derive(Clone, Debug)]
+pub struct WhirlpoolPositionAccounts {
+    pub whirlpool: Pubkey,
+    pub base_mint: Pubkey,
+    /// Fresh keypair; must sign the transaction
+    pub position_mint: Pubkey,
+}
+
+/// Raydium CLMM accounts for `initialize_raydium_position`
+#[derive(Clone, Debug)]
+pub struct RaydiumPositionAccounts {
+    pub pool_state: Pubkey,
+    pub personal_position: Pubkey,
+    /// Position NFT account already owned by the position owner PDA
+    pub position_nft_account: Pubkey,
+    pub base_mint: Pubkey,
+}
+
+/// Optional crank accounts, required by the matching policy flags
+#[derive(Clone, Debug, Default)]
+pub struct CrankOptions {
+    /// Creator wallet receiving unwrapped SOL (policy.unwrap_wsol_creator)
+    pub creator_wallet: Option<Pubkey>,
+    /// Creator wallet whose missing quote ATA finalize creates (policy.fund_missing_creator_ata)
+    pub creator_ata_wallet: Option<Pubkey>,
+    /// Pass the failed payout ledger (policy.continue_on_failure)
+    pub failed_payouts: bool,
+    /// Pass the paid bitmap (policy.track_paid_investors)
+    pub paid_bitmap: bool,
+    /// Pass the payout override table (policy.payout_overrides)
+    pub payout_overrides: bool,
+    /// Pass the fixed weight table to snapshots and pages (policy.weight_overrides)
+    pub weight_overrides: bool,
+    /// Pass the crank log to record the call in (policy.crank_log)
+    pub crank_log: bool,
+    /// Pass the SPL Memo program (policy.payout_memos)
+    pub payout_memos: bool,
+    /// Pass the local-testing locker config; localnet only, never set by `from_policy`
+    pub locker_test_config: bool,
+    /// Day epoch whose creator stream finalize may open (policy.creator_vesting_secs); the day
+    /// is only known at crank time, so `from_policy` leaves it unset
+    pub creator_stream_day: Option<u64>,
+    /// Base mint, passed writable so finalize can burn bought-back base (policy.buyback_burn_creator)
+    pub buyback_base_mint: Option<Pubkey>,
+    /// Wallet whose quote ATA receives the referral share (policy.referral_bps)
+    pub referral_wallet: Option<Pubkey>,
+    /// Protocol treasury whose quote ATA receives the protocol fee (ProtocolConfig); it is
+    /// program-wide, so `from_policy` leaves it unset
+    pub protocol_treasury: Option<Pubkey>,
+    /// Pass the Streamflow topup accounts, for payouts into stream escrow (policy.payout_to_stream_escrow)
+    pub stream_escrow: bool,
+    /// Program called after finalize (policy.hook_program)
+    pub hook_program: Option<Pubkey>,
+    /// Hook-owned state forwarded to the hook; integration-specific, so `from_policy` leaves it unset
+    pub hook_state: Option<Pubkey>,
+}
+
+impl CrankOptions {
+    /// Options the policy requires; `creator_wallet` is only used when unwrapping wSOL or
+    /// creating a missing creator ATA
+    pub fn from_policy(policy: &PolicyPda, creator_wallet: Pubkey) -> Self {
+        Self {
+            creator_wallet: policy.unwrap_wsol_creator.then_some(creator_wallet),
+            // An unknown wallet (default) can't have its ATA created; finalize defers instead
+            creator_ata_wallet: (policy.fund_missing_creator_ata && creator_wallet != Pubkey::default())
+                .then_some(creator_wallet),
+            failed_payouts: policy.continue_on_failure,
+            paid_bitmap: policy.track_paid_investors,
+            payout_overrides: policy.payout_overrides,
+            weight_overrides: policy.weight_overrides,
+            crank_log: policy.crank_log,
+            payout_memos: policy.payout_memos,
+            locker_test_config: false,
+            creator_stream_day: None,
+            buyback_base_mint: policy.buyback_burn_creator.then_some(policy.base_mint),
+            referral_wallet: (policy.referral_bps > 0).then_some(policy.referral_wallet),
+            protocol_treasury: None,
+            stream_escrow: policy.payout_to_stream_escrow,
+            hook_program: (policy.hook_program != Pubkey::default()).then_some(policy.hook_program),
+            hook_state: None,
+        }
+    }
+}
+
+/// Non-PDA accounts of `distribute_fees` / `distribute_fees_packed`
+#[derive(Clone, Debug)]
+pub struct CrankAccounts {
+    pub crank_caller: Pubkey,
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub position_nft_account: Option<Pubkey>,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub temp_a_account: Pubkey,
+    pub temp_b_account: Pubkey,
+    pub creator_quote_ata: Pubkey,
+    pub streamflow_program: Pubkey,
+    pub token_program: Pubkey,
+    pub token_a_program: Pubkey,
+    pub token_b_program: Pubkey,
+    pub cp_amm_program: Pubkey,
+    pub options: CrankOptions,
+}
+
+/// Non-PDA accounts of `claim_rewards`
+#[derive(Clone, Debug)]
+pub struct RewardAccounts {
+    pub crank_caller: Pubkey,
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub position_nft_account: Pubkey,
+    /// Vault of the claimed reward, from the pool's reward_infos
+    pub reward_vault: Pubkey,
+    pub reward_mint: Pubkey,
+    /// Token program of the reward mint
+    pub token_program: Pubkey,
+    pub cp_amm_program: Pubkey,
+    /// Creator's reward token account (RewardRouting::Creator)
+    pub creator_reward_account: Option<Pubkey>,
+    /// Pass the fixed weight table to investor pages (policy.weight_overrides)
+    pub weight_overrides: bool,
+}
+
+/// One fee router vault, identified by its seed and quote mint
+#[derive(Clone, Debug)]
+pub struct Vault {
+    pub vault_seed: String,
+    pub quote_mint: Pubkey,
+}
+
+impl Vault {
+    pub fn new(vault_seed: impl Into<String>, quote_mint: Pubkey) -> Self {
+        Self {
+            vault_seed: vault_seed.into(),
+            quote_mint,
+        }
+    }
+
+    pub fn policy(&self) -> Pubkey {
+        pda::policy(&self.vault_seed).0
+    }
+
+    pub fn progress(&self) -> Pubkey {
+        pda::progress(&self.vault_seed).0
+    }
+
+    pub fn position_owner(&self) -> Pubkey {
+        pda::position_owner(&self.vault_seed).0
+    }
+
+    pub fn treasury(&self) -> Pubkey {
+        pda::treasury(&self.vault_seed, &self.quote_mint).0
+    }
+
+    fn event_authority(&self) -> Pubkey {
+        pda::event_authority().0
+    }
+
+    pub fn initialize_policy(
+        &self,
+        authority: Pubkey,
+        payer: Pubkey,
+        base_mint: Pubkey,
+        pool: Pubkey,
+        cp_amm_program: Pubkey,
+        args: &PolicyInit,
+    ) -> Instruction {
+        build(
+            accounts::InitializePolicy {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                quote_mint: self.quote_mint,
+                base_mint,
+                pool,
+                cp_amm_program,
+                vault_registry: pda::vault_registry(&authority).0,
+                network_config: pda::network_config().0,
+                system_program: system_program::ID,
+                token_program: args.quote_token_program,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::InitializePolicy {
+                vault_seed: self.vault_seed.clone(),
+                args: args.args.clone(),
+            },
+        )
+    }
+
+    pub fn initialize_progress(&self, authority: Pubkey, payer: Pubkey) -> Instruction {
+        build(
+            accounts::InitializeProgress {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                system_program: system_program::ID,
+            },
+            instruction::InitializeProgress {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    pub fn initialize_honorary_position(
+        &self,
+        authority: Pubkey,
+        payer: Pubkey,
+        cp_amm: &CpAmmPositionAccounts,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Instruction {
+        let (position, _) = Pubkey::find_program_address(
+            &[POSITION_PREFIX, cp_amm.position_mint.as_ref()],
+            &cp_amm.cp_amm_program,
+        );
+        let (position_token_account, _) = Pubkey::find_program_address(
+            &[POSITION_NFT_ACCOUNT_PREFIX, cp_amm.position_mint.as_ref()],
+            &cp_amm.cp_amm_program,
+        );
+        build(
+            accounts::InitializeHonoraryPosition {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                position_owner_pda: self.position_owner(),
+                cp_amm_program: cp_amm.cp_amm_program,
+                pool_authority: router_cp_amm::derive_pool_authority(&cp_amm.cp_amm_program),
+                cp_amm_event_authority: router_cp_amm::derive_event_authority(&cp_amm.cp_amm_program),
+                pool: cp_amm.pool,
+                pool_token_vault_0: cp_amm.pool_token_vault_0,
+                pool_token_vault_1: cp_amm.pool_token_vault_1,
+                quote_mint: self.quote_mint,
+                base_mint: cp_amm.base_mint,
+                quote_treasury: self.treasury(),
+                position_mint: cp_amm.position_mint,
+                position_token_account,
+                position,
+                system_program: system_program::ID,
+                token_program: cp_amm.quote_token_program,
+                associated_token_program: anchor_spl::associated_token::ID,
+                rent: sysvar::rent::ID,
+                token_2022_program: anchor_spl::token_2022::ID,
+                base_token_program: cp_amm.base_token_program,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::InitializeHonoraryPosition {
+                vault_seed: self.vault_seed.clone(),
+                tick_lower,
+                tick_upper,
+                quote_mint: self.quote_mint,
+            },
+        )
+    }
+
+    pub fn initialize_dlmm_position(
+        &self,
+        authority: Pubkey,
+        payer: Pubkey,
+        dlmm: &DlmmPositionAccounts,
+        lower_bin_id: i32,
+        width: i32,
+    ) -> Instruction {
+        build(
+            accounts::InitializeDlmmPosition {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                position_owner_pda: self.position_owner(),
+                dlmm_program: dlmm::DLMM_PROGRAM_ID,
+                dlmm_event_authority: dlmm::derive_event_authority(),
+                lb_pair: dlmm.lb_pair,
+                quote_mint: self.quote_mint,
+                base_mint: dlmm.base_mint,
+                quote_treasury: self.treasury(),
+                position: dlmm.position,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                rent: sysvar::rent::ID,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::InitializeDlmmPosition {
+                vault_seed: self.vault_seed.clone(),
+                lower_bin_id,
+                width,
+                quote_mint: self.quote_mint,
+            },
+        )
+    }
+
+    pub fn initialize_whirlpool_position(
+        &self,
+        authority: Pubkey,
+        payer: Pubkey,
+        orca: &WhirlpoolPositionAccounts,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Instruction {
+        let position_owner = self.position_owner();
+        build(
+            accounts::InitializeWhirlpoolPosition {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                position_owner_pda: position_owner,
+                whirlpool_program: whirlpool::WHIRLPOOL_PROGRAM_ID,
+                whirlpool: orca.whirlpool,
+                quote_mint: self.quote_mint,
+                base_mint: orca.base_mint,
+                quote_treasury: self.treasury(),
+                position_mint: orca.position_mint,
+                position_token_account: get_associated_token_address(&position_owner, &orca.position_mint),
+                position: whirlpool::derive_position(&orca.position_mint).0,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                rent: sysvar::rent::ID,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::InitializeWhirlpoolPosition {
+                vault_seed: self.vault_seed.clone(),
+                tick_lower,
+                tick_upper,
+                quote_mint: self.quote_mint,
+            },
+        )
+    }
+
+    pub fn initialize_raydium_position(&self, authority: Pubkey, payer: Pubkey, raydium: &RaydiumPositionAccounts) -> Instruction {
+        build(
+            accounts::InitializeRaydiumPosition {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                position_owner_pda: self.position_owner(),
+                pool_state: raydium.pool_state,
+                personal_position: raydium.personal_position,
+                position_nft_account: raydium.position_nft_account,
+                quote_mint: self.quote_mint,
+                base_mint: raydium.base_mint,
+                quote_treasury: self.treasury(),
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                rent: sysvar::rent::ID,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::InitializeRaydiumPosition {
+                vault_seed: self.vault_seed.clone(),
+                quote_mint: self.quote_mint,
+            },
+        )
+    }
+
+    pub fn update_policy(&self, authority: Pubkey, args: &UpdatePolicyArgs) -> Instruction {
+        build(
+            accounts::UpdatePolicy {
+                authority,
+                policy_pda: self.policy(),
+                position_owner_pda: self.position_owner(),
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::UpdatePolicy {
+                vault_seed: self.vault_seed.clone(),
+                args: args.clone(),
+            },
+        )
+    }
+
+    /// Hand the policy authority to `new_authority`
+    ///
+    /// Pass the Realms `governance` account when `new_authority` is that governance or its native
+    /// treasury, so the program checks the key before the current authority gives it up.
+    pub fn set_policy_authority(&self, authority: Pubkey, new_authority: Pubkey, governance: Option<Pubkey>) -> Instruction {
+        build(
+            accounts::SetPolicyAuthority {
+                authority,
+                policy_pda: self.policy(),
+                governance,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::SetPolicyAuthority {
+                vault_seed: self.vault_seed.clone(),
+                new_authority,
+            },
+        )
+    }
+
+    /// Point the policy at another pool and mints; only before the honorary position exists.
+    /// Later instructions need a `Vault` built with the new quote mint.
+    pub fn amend_policy_targets(
+        &self,
+        authority: Pubkey,
+        quote_mint: Pubkey,
+        base_mint: Pubkey,
+        pool: Pubkey,
+        quote_token_program: Pubkey,
+    ) -> Instruction {
+        build(
+            accounts::AmendPolicyTargets {
+                authority,
+                policy_pda: self.policy(),
+                position_owner_pda: self.position_owner(),
+                quote_mint,
+                base_mint,
+                pool,
+                token_program: quote_token_program,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::AmendPolicyTargets {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    pub fn set_policy_paused(&self, authority: Pubkey, paused: bool) -> Instruction {
+        build(
+            accounts::SetPolicyPaused {
+                authority,
+                policy_pda: self.policy(),
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::SetPolicyPaused {
+                vault_seed: self.vault_seed.clone(),
+                paused,
+            },
+        )
+    }
+
+    /// Commit the day's page count and the investors its pages cover (0 = unchecked) before any payouts
+    pub fn set_expected_pages(&self, authority: Pubkey, expected_pages: u64, expected_investors: u32) -> Instruction {
+        build(
+            accounts::SetExpectedPages {
+                authority,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::SetExpectedPages {
+                vault_seed: self.vault_seed.clone(),
+                expected_pages,
+                expected_investors,
+            },
+        )
+    }
+
+    /// Restart the paused vault under its next generation; pass the paid bitmap when the policy tracks paid investors
+    pub fn reinitialize_vault(&self, authority: Pubkey, paid_bitmap: bool) -> Instruction {
+        build(
+            accounts::ReinitializeVault {
+                authority,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                paid_bitmap: paid_bitmap.then(|| pda::paid_bitmap(&self.vault_seed).0),
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::ReinitializeVault {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    /// Snapshot locked amounts; appends one vesting account per investor as remaining accounts
+    ///
+    /// `weight_overrides` passes the fixed weight table (policy.weight_overrides).
+    pub fn snapshot_locked(
+        &self,
+        crank_caller: Pubkey,
+        investors: Vec<InvestorData>,
+        is_final_chunk: bool,
+        weight_overrides: bool,
+    ) -> Instruction {
+        let streams: Vec<AccountMeta> = investors
+            .iter()
+            .map(|inv| AccountMeta::new_readonly(inv.stream, false))
+            .collect();
+        let mut ix = build(
+            accounts::SnapshotLocked {
+                crank_caller,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                locker_test_config: None,
+                network_config: pda::network_config().0,
+                weight_overrides: weight_overrides.then(|| pda::weight_overrides(&self.vault_seed).0),
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::SnapshotLocked {
+                vault_seed: self.vault_seed.clone(),
+                investors,
+                is_final_chunk,
+            },
+        );
+        ix.accounts.extend(streams);
+        ix
+    }
+
+    /// Metadata account of the creator stream the options ask finalize to open
+    fn creator_stream(&self, options: &CrankOptions) -> Option<Pubkey> {
+        options
+            .creator_stream_day
+            .map(|day| pda::creator_stream(&self.vault_seed, day).0)
+    }
+
+    /// Quote ATA of the referral wallet the options name
+    fn referral_quote_ata(&self, options: &CrankOptions, token_program: &Pubkey) -> Option<Pubkey> {
+        options
+            .referral_wallet
+            .map(|wallet| get_associated_token_address_with_program_id(&wallet, &self.quote_mint, token_program))
+    }
+
+    /// Quote ATA of the protocol treasury the options name
+    fn protocol_fee_ata(&self, options: &CrankOptions, token_program: &Pubkey) -> Option<Pubkey> {
+        options
+            .protocol_treasury
+            .map(|treasury| get_associated_token_address_with_program_id(&treasury, &self.quote_mint, token_program))
+    }
+
+    fn distribute_fees_accounts(&self, crank: &CrankAccounts) -> accounts::DistributeFees {
+        let options = &crank.options;
+        let stream = self.creator_stream(options);
+        let streamflow = stream.is_some() || options.stream_escrow;
+        accounts::DistributeFees {
+            crank_caller: crank.crank_caller,
+            policy_pda: self.policy(),
+            progress_pda: self.progress(),
+            position_owner_pda: self.position_owner(),
+            pool: crank.pool,
+            position: crank.position,
+            position_nft_account: crank.position_nft_account,
+            pool_authority: router_cp_amm::derive_pool_authority(&crank.cp_amm_program),
+            token_a_vault: crank.token_a_vault,
+            token_b_vault: crank.token_b_vault,
+            token_a_mint: crank.token_a_mint,
+            token_b_mint: crank.token_b_mint,
+            quote_mint: self.quote_mint,
+            temp_a_account: crank.temp_a_account,
+            temp_b_account: crank.temp_b_account,
+            quote_treasury: self.treasury(),
+            creator_quote_ata: crank.creator_quote_ata,
+            streamflow_program: crank.streamflow_program,
+            token_program: crank.token_program,
+            token_a_program: crank.token_a_program,
+            token_b_program: crank.token_b_program,
+            associated_token_program: anchor_spl::associated_token::ID,
+            cp_amm_program: crank.cp_amm_program,
+            cp_amm_event_authority: router_cp_amm::derive_event_authority(&crank.cp_amm_program),
+            system_program: system_program::ID,
+            creator_wsol_temp: options
+                .creator_wallet
+                .map(|_| pda::creator_wsol(&self.vault_seed).0),
+            creator_wallet: options.creator_wallet.or(options.creator_ata_wallet),
+            failed_payouts: options
+                .failed_payouts
+                .then(|| pda::failed_payouts(&self.vault_seed).0),
+            paid_bitmap: options.paid_bitmap.then(|| pda::paid_bitmap(&self.vault_seed).0),
+            payout_overrides: options
+                .payout_overrides
+                .then(|| pda::payout_overrides(&self.vault_seed).0),
+            weight_overrides: options
+                .weight_overrides
+                .then(|| pda::weight_overrides(&self.vault_seed).0),
+            crank_log: options.crank_log.then(|| pda::crank_log(&self.vault_seed).0),
+            memo_program: options.payout_memos.then_some(MEMO_PROGRAM_ID),
+            locker_test_config: options
+                .locker_test_config
+                .then(|| pda::locker_test_config().0),
+            creator_stream_metadata: stream,
+            creator_stream_escrow: stream.map(|metadata| streamflow::derive_escrow_tokens(&metadata)),
+            streamflow_withdrawor: streamflow.then_some(STREAMFLOW_WITHDRAWOR),
+            streamflow_fee_oracle: stream.map(|_| STREAMFLOW_FEE_ORACLE),
+            streamflow_timelock_program: streamflow.then_some(STREAMFLOW_PROGRAM_ID),
+            streamflow_treasury: options.stream_escrow.then_some(STREAMFLOW_TREASURY),
+            streamflow_treasury_tokens: options.stream_escrow.then(|| {
+                get_associated_token_address_with_program_id(&STREAMFLOW_TREASURY, &self.quote_mint, &crank.token_program)
+            }),
+            rent: stream.map(|_| sysvar::rent::ID),
+            buyback_base_mint: options.buyback_base_mint,
+            referral_quote_ata: self.referral_quote_ata(options, &crank.token_program),
+            hook_program: options.hook_program,
+            hook_authority: options.hook_program.map(|_| pda::hook_authority(&self.vault_seed).0),
+            hook_state: options.hook_state,
+            protocol_config: pda::protocol_config().0,
+            protocol_fee_ata: self.protocol_fee_ata(options, &crank.token_program),
+            network_config: pda::network_config().0,
+            event_authority: self.event_authority(),
+            program: ID,
+        }
+    }
+
+    /// Run one crank step
+    ///
+    /// `remaining` is the backend claim accounts (if any) followed by
+    /// [`crate::pages::investor_accounts`] for every investor in `investor_pages`.
+    pub fn distribute_fees(
+        &self,
+        crank: &CrankAccounts,
+        investor_pages: Vec<InvestorPage>,
+        is_final_page: bool,
+        remaining: Vec<AccountMeta>,
+    ) -> Instruction {
+        let mut ix = build(
+            self.distribute_fees_accounts(crank),
+            instruction::DistributeFees {
+                vault_seed: self.vault_seed.clone(),
+                investor_pages,
+                is_final_page,
+            },
+        );
+        ix.accounts.extend(remaining);
+        ix
+    }
+
+    /// Same as [`Vault::distribute_fees`] with pages encoded by [`crate::pages::pack_pages`]
+    pub fn distribute_fees_packed(
+        &self,
+        crank: &CrankAccounts,
+        packed_pages: Vec<u8>,
+        is_final_page: bool,
+        remaining: Vec<AccountMeta>,
+    ) -> Instruction {
+        let mut ix = build(
+            self.distribute_fees_accounts(crank),
+            instruction::DistributeFeesPacked {
+                vault_seed: self.vault_seed.clone(),
+                packed_pages,
+                is_final_page,
+            },
+        );
+        ix.accounts.extend(remaining);
+        ix
+    }
+
+    /// Claim the position's CP-AMM reward `reward_index` and route it per policy.reward_routing
+    ///
+    /// With investor routing, `remaining` is [`crate::pages::investor_accounts`] for every investor
+    /// in `investor_pages`, built with the reward mint and its token program in place of the quote's.
+    pub fn claim_rewards(
+        &self,
+        rewards: &RewardAccounts,
+        reward_index: u8,
+        investor_pages: Vec<InvestorPage>,
+        is_final_page: bool,
+        remaining: Vec<AccountMeta>,
+    ) -> Instruction {
+        let mut ix = build(
+            accounts::ClaimRewards {
+                crank_caller: rewards.crank_caller,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                position_owner_pda: self.position_owner(),
+                pool: rewards.pool,
+                position: rewards.position,
+                position_nft_account: rewards.position_nft_account,
+                pool_authority: router_cp_amm::derive_pool_authority(&rewards.cp_amm_program),
+                reward_vault: rewards.reward_vault,
+                reward_mint: rewards.reward_mint,
+                reward_treasury: pda::reward_treasury(&self.vault_seed, &rewards.reward_mint).0,
+                reward_round: pda::reward_round(&self.vault_seed, &rewards.reward_mint).0,
+                creator_reward_account: rewards.creator_reward_account,
+                weight_overrides: rewards
+                    .weight_overrides
+                    .then(|| pda::weight_overrides(&self.vault_seed).0),
+                locker_test_config: None,
+                network_config: pda::network_config().0,
+                token_program: rewards.token_program,
+                cp_amm_program: rewards.cp_amm_program,
+                cp_amm_event_authority: router_cp_amm::derive_event_authority(&rewards.cp_amm_program),
+                system_program: system_program::ID,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::ClaimRewards {
+                vault_seed: self.vault_seed.clone(),
+                reward_index,
+                investor_pages,
+                is_final_page,
+            },
+        );
+        ix.accounts.extend(remaining);
+        ix
+    }
+
+    /// This vault's `distribute_fees_multi` remaining-account group, in the order the program reads it
+    pub fn multi_claim_accounts(&self, crank: &CrankAccounts) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.policy(), false),
+            AccountMeta::new(self.progress(), false),
+            AccountMeta::new(self.position_owner(), false),
+            AccountMeta::new(crank.position, false),
+            AccountMeta::new_readonly(crank.position_nft_account.unwrap_or(ID), false),
+            AccountMeta::new(crank.pool, false),
+            AccountMeta::new(crank.token_a_vault, false),
+            AccountMeta::new(crank.token_b_vault, false),
+            AccountMeta::new_readonly(crank.token_a_mint, false),
+            AccountMeta::new_readonly(crank.token_b_mint, false),
+            AccountMeta::new(crank.temp_a_account, false),
+            AccountMeta::new(crank.temp_b_account, false),
+            AccountMeta::new(self.treasury(), false),
+            AccountMeta::new_readonly(crank.token_a_program, false),
+            AccountMeta::new_readonly(crank.token_b_program, false),
+            AccountMeta::new(self.protocol_fee_ata(&crank.options, &crank.token_program).unwrap_or(ID), false),
+        ]
+    }
+
+    pub fn force_finalize_day(
+        &self,
+        caller: Pubkey,
+        creator_quote_ata: Pubkey,
+        token_program: Pubkey,
+        options: &CrankOptions,
+    ) -> Instruction {
+        let stream = self.creator_stream(options);
+        build(
+            accounts::ForceFinalizeDay {
+                caller,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                position_owner_pda: self.position_owner(),
+                quote_mint: self.quote_mint,
+                quote_treasury: self.treasury(),
+                creator_quote_ata,
+                token_program,
+                system_program: system_program::ID,
+                creator_wsol_temp: options
+                    .creator_wallet
+                    .map(|_| pda::creator_wsol(&self.vault_seed).0),
+                creator_wallet: options.creator_wallet.or(options.creator_ata_wallet),
+                memo_program: options.payout_memos.then_some(MEMO_PROGRAM_ID),
+                creator_stream_metadata: stream,
+                creator_stream_escrow: stream.map(|metadata| streamflow::derive_escrow_tokens(&metadata)),
+                streamflow_withdrawor: stream.map(|_| STREAMFLOW_WITHDRAWOR),
+                streamflow_fee_oracle: stream.map(|_| STREAMFLOW_FEE_ORACLE),
+                streamflow_timelock_program: stream.map(|_| STREAMFLOW_PROGRAM_ID),
+                rent: stream.map(|_| sysvar::rent::ID),
+                referral_quote_ata: self.referral_quote_ata(options, &token_program),
+                associated_token_program: options
+                    .creator_ata_wallet
+                    .map(|_| anchor_spl::associated_token::ID),
+                hook_program: options.hook_program,
+                hook_authority: options.hook_program.map(|_| pda::hook_authority(&self.vault_seed).0),
+                hook_state: options.hook_state,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::ForceFinalizeDay {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    pub fn acquire_crank_lease(&self, cranker: Pubkey) -> Instruction {
+        build(
+            accounts::AcquireCrankLease {
+                cranker,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::AcquireCrankLease {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    pub fn initialize_failed_payouts(&self, authority: Pubkey, payer: Pubkey) -> Instruction {
+        build(
+            accounts::InitializeFailedPayouts {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                failed_payouts: pda::failed_payouts(&self.vault_seed).0,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeFailedPayouts {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    /// Retry failed payouts into the given investor quote token accounts
+    pub fn retry_failed_payouts(&self, crank_caller: Pubkey, token_program: Pubkey, quote_atas: &[Pubkey]) -> Instruction {
+        let mut ix = build(
+            accounts::RetryFailedPayouts {
+                crank_caller,
+                policy_pda: self.policy(),
+                position_owner_pda: self.position_owner(),
+                failed_payouts: pda::failed_payouts(&self.vault_seed).0,
+                quote_mint: self.quote_mint,
+                quote_treasury: self.treasury(),
+                token_program,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::RetryFailedPayouts {
+                vault_seed: self.vault_seed.clone(),
+            },
+        );
+        ix.accounts
+            .extend(quote_atas.iter().map(|ata| AccountMeta::new(*ata, false)));
+        ix
+    }
+
+    /// Add `lamports` from `funder` to the reserve that repays crank-paid ATA and escrow rent
+    pub fn fund_ata_reserve(&self, funder: Pubkey, lamports: u64) -> Instruction {
+        build(
+            accounts::FundAtaReserve {
+                funder,
+                position_owner_pda: self.position_owner(),
+                system_program: system_program::ID,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::FundAtaReserve {
+                vault_seed: self.vault_seed.clone(),
+                lamports,
+            },
+        )
+    }
+
+    /// Pay `investor` the escrowed payouts whose dispute window has passed into their quote ATA
+    pub fn withdraw_escrowed_payouts(&self, crank_caller: Pubkey, investor: Pubkey, token_program: Pubkey) -> Instruction {
+        build(
+            accounts::WithdrawEscrowedPayouts {
+                crank_caller,
+                policy_pda: self.policy(),
+                position_owner_pda: self.position_owner(),
+                payout_escrow: pda::payout_escrow(&self.vault_seed, &investor).0,
+                quote_mint: self.quote_mint,
+                quote_treasury: self.treasury(),
+                investor_quote_ata: get_associated_token_address_with_program_id(&investor, &self.quote_mint, &token_program),
+                token_program,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::WithdrawEscrowedPayouts {
+                vault_seed: self.vault_seed.clone(),
+                investor,
+            },
+        )
+    }
+
+    /// Claw back `investor`'s escrowed payout for `day_epoch` while its dispute window is open
+    pub fn claw_back_escrowed_payout(&self, authority: Pubkey, investor: Pubkey, day_epoch: u64) -> Instruction {
+        build(
+            accounts::ClawBackEscrowedPayout {
+                authority,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                payout_escrow: pda::payout_escrow(&self.vault_seed, &investor).0,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::ClawBackEscrowedPayout {
+                vault_seed: self.vault_seed.clone(),
+                investor,
+                day_epoch,
+            },
+        )
+    }
+
+    /// Publish `investor`'s locked amount as of `as_of_ts`; `oracle` is the policy's lock oracle
+    pub fn publish_oracle_locked(
+        &self,
+        oracle: Pubkey,
+        payer: Pubkey,
+        investor: Pubkey,
+        locked_amount: u64,
+        as_of_ts: u64,
+    ) -> Instruction {
+        build(
+            accounts::PublishOracleLocked {
+                oracle,
+                payer,
+                policy_pda: self.policy(),
+                oracle_locked: pda::oracle_locked(&self.vault_seed, &investor).0,
+                system_program: system_program::ID,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::PublishOracleLocked {
+                vault_seed: self.vault_seed.clone(),
+                investor,
+                locked_amount,
+                as_of_ts,
+            },
+        )
+    }
+
+    /// Read-only view of the distribution state; simulate it and decode the return data as
+    /// `DistributionParams` (see `rpc::distribution_params`)
+    pub fn get_distribution_params(&self) -> Instruction {
+        build(
+            accounts::GetDistributionParams {
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+            },
+            instruction::GetDistributionParams {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    /// Pay the creator remainders deferred at finalize into `creator_quote_ata`
+    pub fn settle_creator_payout(&self, crank_caller: Pubkey, creator_quote_ata: Pubkey, token_program: Pubkey) -> Instruction {
+        build(
+            accounts::SettleCreatorPayout {
+                crank_caller,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                position_owner_pda: self.position_owner(),
+                quote_mint: self.quote_mint,
+                quote_treasury: self.treasury(),
+                creator_quote_ata,
+                token_program,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::SettleCreatorPayout {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    pub fn initialize_paid_bitmap(&self, authority: Pubkey, payer: Pubkey) -> Instruction {
+        build(
+            accounts::InitializePaidBitmap {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                paid_bitmap: pda::paid_bitmap(&self.vault_seed).0,
+                system_program: system_program::ID,
+            },
+            instruction::InitializePaidBitmap {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    pub fn initialize_payout_overrides(&self, authority: Pubkey, payer: Pubkey) -> Instruction {
+        build(
+            accounts::InitializePayoutOverrides {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                payout_overrides: pda::payout_overrides(&self.vault_seed).0,
+                system_program: system_program::ID,
+            },
+            instruction::InitializePayoutOverrides {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    /// Set `investor`'s minimum payout, or remove the override with `None`
+    pub fn set_payout_override(&self, authority: Pubkey, investor: Pubkey, min_payout_lamports: Option<u64>) -> Instruction {
+        build(
+            accounts::SetPayoutOverride {
+                authority,
+                policy_pda: self.policy(),
+                payout_overrides: pda::payout_overrides(&self.vault_seed).0,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::SetPayoutOverride {
+                vault_seed: self.vault_seed.clone(),
+                investor,
+                min_payout_lamports,
+            },
+        )
+    }
+
+    pub fn initialize_weight_overrides(&self, authority: Pubkey, payer: Pubkey) -> Instruction {
+        build(
+            accounts::InitializeWeightOverrides {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                weight_overrides: pda::weight_overrides(&self.vault_seed).0,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeWeightOverrides {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    /// Create the crank log; every later crank must pass it (`CrankOptions::crank_log`)
+    pub fn initialize_crank_log(&self, authority: Pubkey, payer: Pubkey) -> Instruction {
+        build(
+            accounts::InitializeCrankLog {
+                authority,
+                payer,
+                policy_pda: self.policy(),
+                crank_log: pda::crank_log(&self.vault_seed).0,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeCrankLog {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+
+    /// Set `investor`'s fixed weight, or remove the override with `None`; fails while a day is open
+    pub fn set_weight_override(&self, authority: Pubkey, investor: Pubkey, weight: Option<u64>) -> Instruction {
+        build(
+            accounts::SetWeightOverride {
+                authority,
+                policy_pda: self.policy(),
+                progress_pda: self.progress(),
+                weight_overrides: pda::weight_overrides(&self.vault_seed).0,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::SetWeightOverride {
+                vault_seed: self.vault_seed.clone(),
+                investor,
+                weight,
+            },
+        )
+    }
+
+    pub fn cache_static_accounts(&self, authority: Pubkey, statics: &StaticAccounts) -> Instruction {
+        build(
+            accounts::CacheStaticAccounts {
+                authority,
+                policy_pda: self.policy(),
+                token_a_vault: statics.token_a_vault,
+                token_b_vault: statics.token_b_vault,
+                token_a_mint: statics.token_a_mint,
+                token_b_mint: statics.token_b_mint,
+                quote_treasury: statics.quote_treasury,
+                pool_authority: statics.pool_authority,
+                cp_amm_event_authority: statics.cp_amm_event_authority,
+                event_authority: self.event_authority(),
+                program: ID,
+            },
+            instruction::CacheStaticAccounts {
+                vault_seed: self.vault_seed.clone(),
+            },
+        )
+    }
+}
+
+/// Claim the position fees of several CP-AMM vaults in one instruction
+///
+/// Every vault must live in `cp_amm_program`; each claim is held for the vault's next
+/// `distribute_fees` first page.
+pub fn distribute_fees_multi(crank_caller: Pubkey, cp_amm_program: Pubkey, vaults: &[(&Vault, &CrankAccounts)]) -> Instruction {
+    let mut ix = build(
+        accounts::DistributeFeesMulti {
+            crank_caller,
+            cp_amm_program,
+            pool_authority: router_cp_amm::derive_pool_authority(&cp_amm_program),
+            cp_amm_event_authority: router_cp_amm::derive_event_authority(&cp_amm_program),
+            protocol_config: pda::protocol_config().0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::DistributeFeesMulti {
+            vault_seeds: vaults.iter().map(|(vault, _)| vault.vault_seed.clone()).collect(),
+        },
+    );
+    for (vault, crank) in vaults {
+        ix.accounts.extend(vault.multi_claim_accounts(crank));
+    }
+    ix
+}
+
+/// Turn the local-testing locker bypass on or off; `authority` is the program's upgrade authority
+pub fn set_locker_test_config(authority: Pubkey, payer: Pubkey, relax_locker_checks: bool) -> Instruction {
+    build(
+        accounts::SetLockerTestConfig {
+            authority,
+            payer,
+            locker_test_config: pda::locker_test_config().0,
+            router_program: ID,
+            program_data: pda::program_data().0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetLockerTestConfig { relax_locker_checks },
+    )
+}
+
+/// Select the deployment's cluster; `programs` overrides the network's IDs for a fork.
+/// `authority` is the program's upgrade authority
+pub fn set_network_config(
+    authority: Pubkey,
+    payer: Pubkey,
+    network: Network,
+    programs: Option<NetworkPrograms>,
+) -> Instruction {
+    build(
+        accounts::SetNetworkConfig {
+            authority,
+            payer,
+            network_config: pda::network_config().0,
+            router_program: ID,
+            program_data: pda::program_data().0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetNetworkConfig { network, programs },
+    )
+}
+
+/// Create the program-wide protocol fee config; `authority` is the program's upgrade authority
+pub fn initialize_protocol_config(
+    authority: Pubkey,
+    payer: Pubkey,
+    admin: Pubkey,
+    protocol_fee_bps: u16,
+    protocol_treasury: Pubkey,
+) -> Instruction {
+    build(
+        accounts::InitializeProtocolConfig {
+            authority,
+            payer,
+            protocol_config: pda::protocol_config().0,
+            router_program: ID,
+            program_data: pda::program_data().0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::InitializeProtocolConfig {
+            admin,
+            protocol_fee_bps,
+            protocol_treasury,
+        },
+    )
+}
+
+/// Change the protocol fee config; None leaves a field unchanged
+pub fn update_protocol_config(
+    admin: Pubkey,
+    new_admin: Option<Pubkey>,
+    new_protocol_fee_bps: Option<u16>,
+    new_protocol_treasury: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::UpdateProtocolConfig {
+            admin,
+            protocol_config: pda::protocol_config().0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::UpdateProtocolConfig {
+            new_admin,
+            new_protocol_fee_bps,
+            new_protocol_treasury,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use meteor_route_fee_router::instructions::distribute_fees_multi::MULTI_VAULT_ACCOUNTS;
+
+    #[test]
+    fn test_optional_crank_accounts_follow_options() {
+        let vault = Vault::new("vault", Pubkey::new_unique());
+        let options = CrankOptions {
+            paid_bitmap: true,
+            ..Default::default()
+        };
+        let ix = vault.force_finalize_day(Pubkey::new_unique(), Pubkey::new_unique(), anchor_spl::token::ID, &options);
+        // Absent optional accounts are passed as the program ID
+        assert_eq!(ix.accounts[9].pubkey, ID);
+        assert_eq!(ix.accounts[1].pubkey, vault.policy());
+        assert_eq!(ix.accounts.last().unwrap().pubkey, ID);
+        assert_eq!(ix.accounts[ix.accounts.len() - 2].pubkey, pda::event_authority().0);
+    }
+
+    #[test]
+    fn test_creator_ata_wallet_skips_wsol_temp() {
+        let vault = Vault::new("vault", Pubkey::new_unique());
+        let wallet = Pubkey::new_unique();
+        let options = CrankOptions {
+            creator_ata_wallet: Some(wallet),
+            ..Default::default()
+        };
+        let ix = vault.force_finalize_day(Pubkey::new_unique(), Pubkey::new_unique(), anchor_spl::token::ID, &options);
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == wallet));
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == anchor_spl::associated_token::ID));
+        assert!(!ix.accounts.iter().any(|meta| meta.pubkey == pda::creator_wsol("vault").0));
+    }
+
+    #[test]
+    fn test_hook_accounts_follow_options() {
+        let vault = Vault::new("vault", Pubkey::new_unique());
+        let (hook_program, hook_state) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let options = CrankOptions {
+            hook_program: Some(hook_program),
+            hook_state: Some(hook_state),
+            ..Default::default()
+        };
+        let ix = vault.force_finalize_day(Pubkey::new_unique(), Pubkey::new_unique(), anchor_spl::token::ID, &options);
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == hook_program && !meta.is_writable));
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == pda::hook_authority("vault").0 && !meta.is_signer));
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == hook_state && meta.is_writable));
+    }
+
+    #[test]
+    fn test_creator_stream_accounts_follow_day() {
+        let vault = Vault::new("vault", Pubkey::new_unique());
+        let options = CrankOptions {
+            creator_stream_day: Some(20_000),
+            ..Default::default()
+        };
+        let ix = vault.force_finalize_day(Pubkey::new_unique(), Pubkey::new_unique(), anchor_spl::token::ID, &options);
+        let metadata = pda::creator_stream("vault", 20_000).0;
+        let escrow = streamflow::derive_escrow_tokens(&metadata);
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == metadata && meta.is_writable));
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == escrow && meta.is_writable));
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == STREAMFLOW_PROGRAM_ID));
+    }
+
+    #[test]
+    fn test_referral_quote_ata_follows_token_program() {
+        let vault = Vault::new("vault", Pubkey::new_unique());
+        let wallet = Pubkey::new_unique();
+        let options = CrankOptions {
+            referral_wallet: Some(wallet),
+            ..Default::default()
+        };
+        let ix = vault.force_finalize_day(Pubkey::new_unique(), Pubkey::new_unique(), anchor_spl::token_2022::ID, &options);
+        let ata = get_associated_token_address_with_program_id(&wallet, &vault.quote_mint, &anchor_spl::token_2022::ID);
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == ata && meta.is_writable));
+    }
+
+    #[test]
+    fn test_multi_claim_account_groups() {
+        let crank = |options: CrankOptions| CrankAccounts {
+            crank_caller: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            position: Pubkey::new_unique(),
+            position_nft_account: Some(Pubkey::new_unique()),
+            token_a_vault: Pubkey::new_unique(),
+            token_b_vault: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            temp_a_account: Pubkey::new_unique(),
+            temp_b_account: Pubkey::new_unique(),
+            creator_quote_ata: Pubkey::new_unique(),
+            streamflow_program: STREAMFLOW_PROGRAM_ID,
+            token_program: anchor_spl::token::ID,
+            token_a_program: anchor_spl::token::ID,
+            token_b_program: anchor_spl::token::ID,
+            cp_amm_program: cp_amm::ID,
+            options,
+        };
+        let (first, second) = (Vault::new("first", Pubkey::new_unique()), Vault::new("second", Pubkey::new_unique()));
+        let treasury = Pubkey::new_unique();
+        let with_fee = crank(CrankOptions {
+            protocol_treasury: Some(treasury),
+            ..Default::default()
+        });
+        let ix = distribute_fees_multi(
+            Pubkey::new_unique(),
+            cp_amm::ID,
+            &[(&first, &crank(CrankOptions::default())), (&second, &with_fee)],
+        );
+
+        let groups = &ix.accounts[ix.accounts.len() - 2 * MULTI_VAULT_ACCOUNTS..];
+        for (group, vault) in groups.chunks(MULTI_VAULT_ACCOUNTS).zip([&first, &second]) {
+            assert_eq!(group[0].pubkey, vault.policy());
+            assert!(group[1].is_writable && group[1].pubkey == vault.progress());
+            assert!(group[2].is_writable && group[2].pubkey == vault.position_owner());
+            assert_eq!(group[12].pubkey, vault.treasury());
+            assert!(group.iter().all(|meta| !meta.is_signer));
+        }
+        // No protocol treasury: the fee ATA slot holds the program ID
+        assert_eq!(groups[MULTI_VAULT_ACCOUNTS - 1].pubkey, ID);
+        let fee_ata = get_associated_token_address_with_program_id(&treasury, &second.quote_mint, &anchor_spl::token::ID);
+        assert_eq!(groups[2 * MULTI_VAULT_ACCOUNTS - 1].pubkey, fee_ata);
+    }
+
+    #[test]
+    fn test_honorary_position_takes_each_mint_program() {
+        let vault = Vault::new("vault", Pubkey::new_unique());
+        let accounts = CpAmmPositionAccounts {
+            cp_amm_program: cp_amm::ID,
+            pool: Pubkey::new_unique(),
+            pool_token_vault_0: Pubkey::new_unique(),
+            pool_token_vault_1: Pubkey::new_unique(),
+            base_mint: Pubkey::new_unique(),
+            quote_token_program: anchor_spl::token_2022::ID,
+            base_token_program: anchor_spl::token::ID,
+            position_mint: Pubkey::new_unique(),
+        };
+        let ix = vault.initialize_honorary_position(Pubkey::new_unique(), Pubkey::new_unique(), &accounts, -100, 100);
+        // token_program follows the Token-2022 quote mint; base_token_program the classic base mint
+        assert_eq!(ix.accounts[17].pubkey, anchor_spl::token_2022::ID);
+        assert_eq!(ix.accounts[21].pubkey, anchor_spl::token::ID);
+    }
+
+    #[test]
+    fn test_authority_and_payer_are_separate_signers() {
+        // A multisig vault signs as authority without paying; the payer funds the new accounts
+        let vault = Vault::new("vault", Pubkey::new_unique());
+        let (authority, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let args = PolicyInit {
+            args: InitializePolicyArgs {
+                investor_fee_share_bps: 7000,
+                min_payout_lamports: 1000,
+                policy_fund_missing_ata: true,
+                y0_total_allocation: 1_000_000,
+                ..InitializePolicyArgs::default()
+            },
+            quote_token_program: anchor_spl::token::ID,
+        };
+        let position = CpAmmPositionAccounts {
+            cp_amm_program: cp_amm::ID,
+            pool: Pubkey::new_unique(),
+            pool_token_vault_0: Pubkey::new_unique(),
+            pool_token_vault_1: Pubkey::new_unique(),
+            base_mint: Pubkey::new_unique(),
+            quote_token_program: anchor_spl::token::ID,
+            base_token_program: anchor_spl::token::ID,
+            position_mint: Pubkey::new_unique(),
+        };
+        let ixs = [
+            vault.initialize_policy(authority, payer, Pubkey::new_unique(), Pubkey::new_unique(), cp_amm::ID, &args),
+            vault.initialize_honorary_position(authority, payer, &position, -100, 100),
+            vault.initialize_progress(authority, payer),
+            vault.initialize_failed_payouts(authority, payer),
+            vault.initialize_paid_bitmap(authority, payer),
+            vault.initialize_payout_overrides(authority, payer),
+            vault.initialize_weight_overrides(authority, payer),
+            vault.initialize_crank_log(authority, payer),
+            initialize_protocol_config(authority, payer, Pubkey::new_unique(), 0, Pubkey::new_unique()),
+        ];
+        for ix in ixs.iter() {
+            let (auth, pay) = (&ix.accounts[0], &ix.accounts[1]);
+            assert_eq!((auth.pubkey, auth.is_signer, auth.is_writable), (authority, true, false));
+            assert_eq!((pay.pubkey, pay.is_signer, pay.is_writable), (payer, true, true));
+        }
+
+        // Updates need only the authority's signature, and it is not debited
+        let update = vault.update_policy(authority, &UpdatePolicyArgs::default());
+        assert!(update.accounts[0].is_signer && !update.accounts[0].is_writable);
+        assert_eq!(update.accounts.iter().filter(|meta| meta.is_signer).count(), 1);
+    }
+}
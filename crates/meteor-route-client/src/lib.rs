@@ -0,0 +1,22 @@
+//! Rust client for the MeteorRoute fee router
+//!
+//! PDA derivation, instruction builders and investor page hashing that match the on-chain
+//! program, so integrators don't hand-roll seeds or hashes.
+pub mod governance;
+pub mod investors;
+pub mod ix;
+pub mod pages;
+pub mod pda;
+pub mod preview;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+pub use ix::{
+    CpAmmPositionAccounts, CrankAccounts, CrankOptions, DlmmPositionAccounts, PolicyInit,
+    RaydiumPositionAccounts, RewardAccounts, Vault, WhirlpoolPositionAccounts,
+};
+pub use meteor_route_fee_router::cp_amm::{quote_only_tick_range, AUTO_TICK_RANGE};
+pub use meteor_route_fee_router::mint_safety::mint_risk;
+pub use meteor_route_fee_router::{InitializePolicyArgs, InvestorData, InvestorPage, UpdatePolicyArgs, ID};
+pub use pages::{page_hash, paginate};
+pub use preview::{preview_day, DayInputs, DayPreview};
@@ -0,0 +1,380 @@
+//! Investor page construction: page hashes, the packed wire format and remaining accounts
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use meteor_route_fee_router::{
+    attestation::AttestationGate,
+    investor_page::{PageDomain, PACKED_HEADER_LEN, PACKED_INVESTOR_LEN},
+    locker::StreamLocked,
+    state::{DayGate, PolicyPda, ProgressPda},
+    streamflow,
+    InvestorData, InvestorPage,
+};
+
+use crate::pda;
+
+/// Page hash exactly as `distribute_fees` recomputes it
+///
+/// H(domain || page_index LE || investors[i].stream || investors[i].investor || investors[i].index LE
+/// for all i), built by
+/// the same `meteor-route-core` preimage the program hashes. The domain binds the page to this
+/// program, `vault_seed`, `day_epoch` and the vault's `generation` (`ProgressPda::generation`), so
+/// pages must be rebuilt for every day and after every `reinitialize_vault`.
+pub fn page_hash(
+    vault_seed: &str,
+    day_epoch: u64,
+    generation: u16,
+    page_index: u64,
+    investors: &[InvestorData],
+) -> [u8; 32] {
+    let program_id = meteor_route_fee_router::ID.to_bytes();
+    meteor_route_core::page_hash(
+        &PageDomain::new(&program_id, vault_seed, day_epoch, generation),
+        page_index,
+        investors.iter().map(|inv| (inv.stream.as_array(), inv.investor.as_array(), inv.index)),
+    )
+}
+
+/// Build one page of `vault_seed`'s day `day_epoch` in `generation` with its hash filled in
+pub fn page(vault_seed: &str, day_epoch: u64, generation: u16, page_index: u64, investors: &[InvestorData]) -> InvestorPage {
+    InvestorPage {
+        page_index,
+        page_hash: page_hash(vault_seed, day_epoch, generation, page_index, investors),
+        investors: investors.to_vec(),
+    }
+}
+
+/// Split the investor list into hashed pages of at most `page_size` investors
+///
+/// Page indexes start at `first_page_index` and increase by one per page.
+pub fn paginate(
+    vault_seed: &str,
+    day_epoch: u64,
+    generation: u16,
+    investors: &[InvestorData],
+    page_size: usize,
+    first_page_index: u64,
+) -> Vec<InvestorPage> {
+    investors
+        .chunks(page_size.max(1))
+        .enumerate()
+        .map(|(i, chunk)| page(vault_seed, day_epoch, generation, first_page_index + i as u64, chunk))
+        .collect()
+}
+
+/// Page index the next `distribute_fees` call must start at
+///
+/// A crank once the policy's day gate has opened (`PolicyPda::day_gate`) starts a new day, which
+/// resets the cursor to zero.
+pub fn next_page_index(progress: &ProgressPda, gate: DayGate, now: u64, slot: u64) -> u64 {
+    if progress.is_new_day_under(gate, now, slot) {
+        0
+    } else {
+        progress.pagination_cursor
+    }
+}
+
+/// Day epoch the next `distribute_fees` call cranks, which its page hashes must be built for
+pub fn crank_day_epoch(progress: &ProgressPda, gate: DayGate, now: u64, slot: u64) -> u64 {
+    if progress.is_new_day_under(gate, now, slot) {
+        progress.next_day_epoch(gate, now)
+    } else {
+        progress.day_epoch
+    }
+}
+
+/// Encode pages in the packed format accepted by `distribute_fees_packed`
+///
+/// Packed records carry no index, so each page's investors must have consecutive indexes;
+/// returns None otherwise.
+pub fn pack_pages(pages: &[InvestorPage]) -> Option<Vec<u8>> {
+    let records: usize = pages.iter().map(|p| p.investors.len()).sum();
+    let mut out = Vec::with_capacity(pages.len() * PACKED_HEADER_LEN + records * PACKED_INVESTOR_LEN);
+    for page in pages {
+        let first_index = page.investors.first().map_or(0, |inv| inv.index);
+        let consecutive = page
+            .investors
+            .iter()
+            .enumerate()
+            .all(|(i, inv)| Some(inv.index) == first_index.checked_add(i as u32));
+        if !consecutive {
+            return None;
+        }
+        out.extend_from_slice(&page.page_index.to_le_bytes());
+        out.extend_from_slice(&page.page_hash);
+        out.extend_from_slice(&first_index.to_le_bytes());
+        out.extend_from_slice(&(page.investors.len() as u32).to_le_bytes());
+        for inv in page.investors.iter() {
+            out.extend_from_slice(inv.stream.as_ref());
+            out.extend_from_slice(inv.investor.as_ref());
+        }
+    }
+    Some(out)
+}
+
+/// Per-investor remaining accounts for `distribute_fees`: [stream, quote ATA, investor] each,
+/// followed by the investor's attestation PDA while the policy has an attestation gate
+///
+/// Backend claim accounts (DLMM, Whirlpool, Raydium CLMM) go before these.
+pub fn investor_accounts(
+    investors: &[InvestorData],
+    quote_mint: &Pubkey,
+    quote_token_program: &Pubkey,
+    attestation: Option<&AttestationGate>,
+) -> Vec<AccountMeta> {
+    let mut metas = Vec::with_capacity(investors.len() * 4);
+    for inv in investors {
+        let quote_ata =
+            get_associated_token_address_with_program_id(&inv.investor, quote_mint, quote_token_program);
+        metas.push(AccountMeta::new_readonly(inv.stream, false));
+        metas.push(AccountMeta::new(quote_ata, false));
+        metas.push(AccountMeta::new_readonly(inv.investor, false));
+        if let Some(gate) = attestation {
+            metas.push(AccountMeta::new_readonly(gate.address(&inv.investor), false));
+        }
+    }
+    metas
+}
+
+/// [`investor_accounts`] for a policy paying into stream escrow (policy.payout_to_stream_escrow):
+/// each stream is writable and its escrow token account takes the quote ATA's place
+pub fn investor_escrow_accounts(investors: &[InvestorData], attestation: Option<&AttestationGate>) -> Vec<AccountMeta> {
+    let mut metas = Vec::with_capacity(investors.len() * 4);
+    for inv in investors {
+        metas.push(AccountMeta::new(inv.stream, false));
+        metas.push(AccountMeta::new(streamflow::derive_escrow_tokens(&inv.stream), false));
+        metas.push(AccountMeta::new_readonly(inv.investor, false));
+        if let Some(gate) = attestation {
+            metas.push(AccountMeta::new_readonly(gate.address(&inv.investor), false));
+        }
+    }
+    metas
+}
+
+/// [`investor_accounts`] for a policy holding payouts for a dispute window
+/// (policy.payout_dispute_window_secs): each investor's payout escrow takes the quote ATA's place
+pub fn investor_payout_escrow_accounts(
+    vault_seed: &str,
+    investors: &[InvestorData],
+    attestation: Option<&AttestationGate>,
+) -> Vec<AccountMeta> {
+    let mut metas = Vec::with_capacity(investors.len() * 4);
+    for inv in investors {
+        let (escrow, _) = pda::payout_escrow(vault_seed, &inv.investor);
+        metas.push(AccountMeta::new_readonly(inv.stream, false));
+        metas.push(AccountMeta::new(escrow, false));
+        metas.push(AccountMeta::new_readonly(inv.investor, false));
+        if let Some(gate) = attestation {
+            metas.push(AccountMeta::new_readonly(gate.address(&inv.investor), false));
+        }
+    }
+    metas
+}
+
+/// What takes each investor's quote ATA slot in a page, following the policy's payout mode
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PayoutDestination {
+    /// The investor's quote ATA
+    QuoteAta,
+    /// The stream's escrow token account (policy.payout_to_stream_escrow)
+    StreamEscrow,
+    /// The investor's payout escrow in this vault (policy.payout_dispute_window_secs)
+    PayoutEscrow { vault_seed: String },
+}
+
+impl PayoutDestination {
+    pub fn for_policy(policy: &PolicyPda, vault_seed: &str) -> Self {
+        if policy.payout_dispute_window_secs > 0 {
+            Self::PayoutEscrow { vault_seed: vault_seed.to_string() }
+        } else if policy.payout_to_stream_escrow {
+            Self::StreamEscrow
+        } else {
+            Self::QuoteAta
+        }
+    }
+}
+
+/// Remaining accounts of one page of `investors` paid to `destination`
+pub fn page_accounts(
+    investors: &[InvestorData],
+    quote_mint: &Pubkey,
+    quote_token_program: &Pubkey,
+    attestation: Option<&AttestationGate>,
+    destination: &PayoutDestination,
+) -> Vec<AccountMeta> {
+    match destination {
+        PayoutDestination::QuoteAta => investor_accounts(investors, quote_mint, quote_token_program, attestation),
+        PayoutDestination::StreamEscrow => investor_escrow_accounts(investors, attestation),
+        PayoutDestination::PayoutEscrow { vault_seed } => {
+            investor_payout_escrow_accounts(vault_seed, investors, attestation)
+        }
+    }
+}
+
+/// One `distribute_fees` page with the remaining accounts it needs
+#[derive(Clone, Debug)]
+pub struct PagePayload {
+    pub page: InvestorPage,
+    pub remaining_accounts: Vec<AccountMeta>,
+    /// Last page of the day; send it with `is_final_page`
+    pub is_final: bool,
+}
+
+/// Pages for the investors with something locked, ready for `Vault::distribute_fees`
+///
+/// `locked[i]` is `investors[i]`'s stream read (see `preview::stream_locked`). Closed, canceled
+/// and zero-locked streams are dropped, since they pay nothing. The rest are ordered by stream key,
+/// as `policy.track_paid_investors` requires, so the same inputs always produce the same pages and
+/// hashes for a given vault, day and generation. Each page's accounts follow `destination` (see [`page_accounts`]).
+#[allow(clippy::too_many_arguments)]
+pub fn page_payloads(
+    vault_seed: &str,
+    day_epoch: u64,
+    generation: u16,
+    investors: &[InvestorData],
+    locked: &[StreamLocked],
+    quote_mint: &Pubkey,
+    quote_token_program: &Pubkey,
+    attestation: Option<&AttestationGate>,
+    destination: &PayoutDestination,
+    page_size: usize,
+) -> Vec<PagePayload> {
+    let mut live: Vec<InvestorData> = investors
+        .iter()
+        .zip(locked)
+        .filter(|(_, locked)| matches!(locked, StreamLocked::Locked(amount) if *amount > 0))
+        .map(|(inv, _)| inv.clone())
+        .collect();
+    live.sort_by_key(|inv| (inv.stream, inv.index));
+
+    let pages = paginate(vault_seed, day_epoch, generation, &live, page_size, 0);
+    let last = pages.len().saturating_sub(1);
+    pages
+        .into_iter()
+        .enumerate()
+        .map(|(i, page)| PagePayload {
+            remaining_accounts: page_accounts(&page.investors, quote_mint, quote_token_program, attestation, destination),
+            page,
+            is_final: i == last,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use meteor_route_fee_router::investor_page::{parse_packed_pages, InvestorPageView};
+
+    fn investors(count: u32) -> Vec<InvestorData> {
+        (0..count)
+            .map(|index| InvestorData {
+                stream: Pubkey::new_unique(),
+                investor: Pubkey::new_unique(),
+                index,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_paginate_and_pack() {
+        let all = investors(5);
+        let pages = paginate("vault", 7, 0, &all, 2, 0);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[2].page_index, 2);
+        assert_eq!(pages[2].investors.len(), 1);
+        let program_id = meteor_route_fee_router::ID.to_bytes();
+        let domain = PageDomain::new(&program_id, "vault", 7, 0);
+        for p in pages.iter() {
+            assert_eq!(p.page_hash, p.computed_hash(&domain));
+        }
+        // The same page doesn't verify on another day or after the vault is reinitialized
+        assert_ne!(pages[0].page_hash, page("vault", 8, 0, 0, &pages[0].investors).page_hash);
+        assert_ne!(pages[0].page_hash, page("vault", 7, 1, 0, &pages[0].investors).page_hash);
+
+        // The program parses the packed bytes back into the same pages and hashes
+        let packed = pack_pages(&pages).unwrap();
+        let parsed = parse_packed_pages(&packed).unwrap();
+        assert_eq!(parsed.len(), 3);
+        for (packed_page, page) in parsed.iter().zip(pages.iter()) {
+            assert_eq!(packed_page.computed_hash(&domain), page.page_hash);
+            assert_eq!(packed_page.investor(0).index, page.investors[0].index);
+        }
+
+        // Gaps in the investor indexes cannot be packed
+        let gapped = page("vault", 7, 0, 0, &[all[0].clone(), all[2].clone()]);
+        assert!(pack_pages(&[gapped]).is_none());
+    }
+
+    #[test]
+    fn test_page_payloads_filter_and_order() {
+        let mut all = investors(5);
+        all.reverse();
+        let locked = [
+            StreamLocked::Locked(10),
+            StreamLocked::Locked(0),
+            StreamLocked::Ignored(meteor_route_fee_router::locker::StreamIgnoredReason::AccountClosed),
+            StreamLocked::Locked(30),
+            StreamLocked::Locked(40),
+        ];
+        let payloads = page_payloads("vault", 7, 0, &all, &locked, &Pubkey::new_unique(), &anchor_spl::token::ID, None, &PayoutDestination::QuoteAta, 2);
+
+        // Indexes 4, 1 and 0 survive, re-ordered by stream key
+        assert_eq!(payloads.len(), 2);
+        let survivors: Vec<&InvestorData> = payloads.iter().flat_map(|p| p.page.investors.iter()).collect();
+        let mut indexes: Vec<u32> = survivors.iter().map(|inv| inv.index).collect();
+        indexes.sort();
+        assert_eq!(indexes, vec![0, 1, 4]);
+        assert!(survivors.windows(2).all(|pair| pair[0].stream < pair[1].stream));
+        assert!(!payloads[0].is_final && payloads[1].is_final);
+        assert_eq!(payloads[0].remaining_accounts.len(), 6);
+        assert_eq!(payloads[1].page.page_hash, page_hash("vault", 7, 0, 1, &payloads[1].page.investors));
+
+        // Input order does not change the result
+        let mut shuffled = all.clone();
+        shuffled.swap(0, 4);
+        let mut shuffled_locked = locked;
+        shuffled_locked.swap(0, 4);
+        let again = page_payloads("vault", 7, 0, &shuffled, &shuffled_locked, &Pubkey::new_unique(), &anchor_spl::token::ID, None, &PayoutDestination::QuoteAta, 2);
+        assert_eq!(again[0].page.page_hash, payloads[0].page.page_hash);
+    }
+
+    #[test]
+    fn test_investor_accounts_layout() {
+        let all = investors(2);
+        let metas = investor_accounts(&all, &Pubkey::new_unique(), &anchor_spl::token::ID, None);
+        assert_eq!(metas.len(), 6);
+        assert_eq!(metas[3].pubkey, all[1].stream);
+        assert!(metas[4].is_writable);
+        assert_eq!(metas[5].pubkey, all[1].investor);
+
+        // An attestation gate adds each investor's attestation PDA after their three accounts
+        let mut seed_prefix = [0u8; 16];
+        seed_prefix[..3].copy_from_slice(b"kyc");
+        let gate = AttestationGate {
+            program_id: Pubkey::new_unique(),
+            layout: meteor_route_fee_router::attestation::AttestationLayout {
+                seed_prefix,
+                ..Default::default()
+            },
+        };
+        let metas = investor_accounts(&all, &Pubkey::new_unique(), &anchor_spl::token::ID, Some(&gate));
+        assert_eq!(metas.len(), 8);
+        assert_eq!(metas[4].pubkey, all[1].stream);
+        let (expected, _) = Pubkey::find_program_address(&[b"kyc", all[1].investor.as_ref()], &gate.program_id);
+        assert_eq!((metas[7].pubkey, metas[7].is_writable), (expected, false));
+
+        // Paying into stream escrow swaps the quote ATA for the stream's escrow and writes the stream
+        let metas = investor_escrow_accounts(&all, None);
+        assert_eq!(metas.len(), 6);
+        assert!(metas[3].is_writable);
+        assert_eq!(metas[4].pubkey, streamflow::derive_escrow_tokens(&all[1].stream));
+
+        // A dispute window swaps it for the investor's payout escrow PDA
+        let destination = PayoutDestination::PayoutEscrow { vault_seed: "vault".to_string() };
+        let metas = page_accounts(&all, &Pubkey::new_unique(), &anchor_spl::token::ID, None, &destination);
+        let (escrow, _) = Pubkey::find_program_address(
+            &[b"vault", b"payout_escrow", all[1].investor.as_ref()],
+            &meteor_route_fee_router::ID,
+        );
+        assert_eq!((metas[4].pubkey, metas[4].is_writable, metas[3].is_writable), (escrow, true, false));
+    }
+}
@@ -0,0 +1,143 @@
+//! PDA derivation using the same seeds as the program's account constraints
+use anchor_lang::prelude::Pubkey;
+use meteor_route_fee_router::{
+    cp_amm::treasury_seeds,
+    event_cpi::EVENT_AUTHORITY_SEED,
+    hook::HOOK_AUTHORITY_SEED,
+    streamflow::CREATOR_STREAM_SEED,
+    state::{CrankLogPda, FailedPayoutsPda, InvestorFeePositionOwnerPda, LockerTestConfig, NetworkConfig, OracleLockedPda, PaidBitmapPda, PayoutEscrowPda, PayoutOverridesPda, PolicyPda, ProgressPda, ProtocolConfig, RewardRoundPda, VaultRegistry, WeightOverridesPda},
+    ID,
+};
+
+fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &ID)
+}
+
+/// Policy PDA: [vault_seed, "policy"]
+pub fn policy(vault_seed: &str) -> (Pubkey, u8) {
+    find(&PolicyPda::seeds(vault_seed))
+}
+
+/// Progress PDA: [vault_seed, "progress"]
+pub fn progress(vault_seed: &str) -> (Pubkey, u8) {
+    find(&ProgressPda::seeds(vault_seed))
+}
+
+/// Honorary position owner PDA (treasury authority): [vault_seed, "investor_fee_pos_owner"]
+pub fn position_owner(vault_seed: &str) -> (Pubkey, u8) {
+    find(&InvestorFeePositionOwnerPda::seeds(vault_seed))
+}
+
+/// Quote treasury token account: [vault_seed, "treasury", quote_mint]
+pub fn treasury(vault_seed: &str, quote_mint: &Pubkey) -> (Pubkey, u8) {
+    find(&treasury_seeds(vault_seed, quote_mint))
+}
+
+/// Failed payout ledger: [vault_seed, "failed_payouts"]
+pub fn failed_payouts(vault_seed: &str) -> (Pubkey, u8) {
+    find(&FailedPayoutsPda::seeds(vault_seed))
+}
+
+/// An investor's oracle-published locked amount: [vault_seed, "oracle_locked", investor]
+pub fn oracle_locked(vault_seed: &str, investor: &Pubkey) -> (Pubkey, u8) {
+    find(&OracleLockedPda::seeds(vault_seed, investor))
+}
+
+/// An investor's payout escrow: [vault_seed, "payout_escrow", investor]
+pub fn payout_escrow(vault_seed: &str, investor: &Pubkey) -> (Pubkey, u8) {
+    find(&PayoutEscrowPda::seeds(vault_seed, investor))
+}
+
+/// Per-day paid bitmap: [vault_seed, "paid_bitmap"]
+pub fn paid_bitmap(vault_seed: &str) -> (Pubkey, u8) {
+    find(&PaidBitmapPda::seeds(vault_seed))
+}
+
+/// Per-investor minimum payouts: [vault_seed, "payout_overrides"]
+pub fn payout_overrides(vault_seed: &str) -> (Pubkey, u8) {
+    find(&PayoutOverridesPda::seeds(vault_seed))
+}
+
+/// Fixed investor weights: [vault_seed, "weight_overrides"]
+pub fn weight_overrides(vault_seed: &str) -> (Pubkey, u8) {
+    find(&WeightOverridesPda::seeds(vault_seed))
+}
+
+/// Log of recent cranks: [vault_seed, "crank_log"]
+pub fn crank_log(vault_seed: &str) -> (Pubkey, u8) {
+    find(&CrankLogPda::seeds(vault_seed))
+}
+
+/// Reward treasury token account CP-AMM rewards are claimed into: [vault_seed, "reward_treasury", reward_mint]
+pub fn reward_treasury(vault_seed: &str, reward_mint: &Pubkey) -> (Pubkey, u8) {
+    find(&[vault_seed.as_bytes(), b"reward_treasury", reward_mint.as_ref()])
+}
+
+/// Investor reward round of a reward mint: [vault_seed, "reward_round", reward_mint]
+pub fn reward_round(vault_seed: &str, reward_mint: &Pubkey) -> (Pubkey, u8) {
+    find(&RewardRoundPda::seeds(vault_seed, reward_mint))
+}
+
+/// Temporary wSOL account used to unwrap the creator payout: [vault_seed, "creator_wsol"]
+pub fn creator_wsol(vault_seed: &str) -> (Pubkey, u8) {
+    find(&[vault_seed.as_bytes(), b"creator_wsol"])
+}
+
+/// Signer of the post-finalize hook call: [vault_seed, "hook_authority"]
+pub fn hook_authority(vault_seed: &str) -> (Pubkey, u8) {
+    find(&[vault_seed.as_bytes(), HOOK_AUTHORITY_SEED])
+}
+
+/// Streamflow metadata account of a day's creator stream: [vault_seed, "creator_stream", day_epoch]
+pub fn creator_stream(vault_seed: &str, day_epoch: u64) -> (Pubkey, u8) {
+    find(&[vault_seed.as_bytes(), CREATOR_STREAM_SEED, &day_epoch.to_le_bytes()])
+}
+
+/// Program-wide local-testing locker config: ["locker_test_config"]
+pub fn locker_test_config() -> (Pubkey, u8) {
+    find(&[LockerTestConfig::SEED])
+}
+
+/// Program-wide cluster selection: ["network_config"]
+pub fn network_config() -> (Pubkey, u8) {
+    find(&[NetworkConfig::SEED])
+}
+
+/// Program-wide protocol fee config: ["protocol_config"]
+pub fn protocol_config() -> (Pubkey, u8) {
+    find(&[ProtocolConfig::SEED])
+}
+
+/// Vaults created by an authority: ["vault_registry", authority]
+pub fn vault_registry(authority: &Pubkey) -> (Pubkey, u8) {
+    find(&VaultRegistry::seeds(authority))
+}
+
+/// The router's ProgramData account under the upgradeable loader
+pub fn program_data() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ID.as_ref()], &anchor_lang::solana_program::bpf_loader_upgradeable::ID)
+}
+
+/// Event CPI authority: ["__event_authority"]
+pub fn event_authority() -> (Pubkey, u8) {
+    find(&[EVENT_AUTHORITY_SEED])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeds_match_program() {
+        let (expected, _) = Pubkey::find_program_address(&[b"vault".as_ref(), b"progress"], &ID);
+        assert_eq!(progress("vault").0, expected);
+
+        let quote_mint = Pubkey::new_unique();
+        let (expected, _) = Pubkey::find_program_address(
+            &[b"vault".as_ref(), b"treasury", quote_mint.as_ref()],
+            &ID,
+        );
+        assert_eq!(treasury("vault", &quote_mint).0, expected);
+        assert_ne!(policy("vault").0, policy("other").0);
+    }
+}
@@ -0,0 +1,454 @@
+//! Off-chain preview of a distribution day: per-investor payouts, dust and creator remainder
+//!
+//! Runs the same locker adapters and `DistributionMath` as `distribute_fees`, with the same
+//! floor rounding and truncating casts, so a preview matches what the crank pays given the same
+//! claim and stream accounts.
+use anchor_lang::prelude::{AccountInfo, Pubkey, Result};
+use meteor_route_core::{DistributionMath, WeightCap};
+use meteor_route_fee_router::{
+    error::FeeRouterError,
+    locker::{LockerSources, StreamIgnoredReason, StreamLocked},
+    state::{PayoutOverride, PayoutOverridesPda, PolicyPda, ProgressPda, WeightOverride, WeightOverridesPda},
+    InvestorData,
+};
+
+/// A fetched stream account; closed accounts are passed as None
+#[derive(Clone, Debug)]
+pub struct StreamAccount {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+/// Read each investor's locked amount at `at_ts` exactly as the crank does
+///
+/// `streams[i]` is the account at `investors[i].stream`. Errors are the ones the crank would
+/// fail with, e.g. a stream whose recipient is not the investor.
+pub fn stream_locked(
+    policy: &PolicyPda,
+    locker_program: Pubkey,
+    investors: &[InvestorData],
+    streams: &[Option<StreamAccount>],
+    at_ts: u64,
+) -> Result<Vec<StreamLocked>> {
+    let lockers = LockerSources::from_policy(policy, locker_program);
+    investors
+        .iter()
+        .zip(streams)
+        .map(|(inv, stream)| investor_locked(&lockers, inv, stream.as_ref(), at_ts))
+        .collect()
+}
+
+/// One investor's locked amount at `at_ts`, read through the policy's locker adapters
+pub fn investor_locked(
+    lockers: &LockerSources,
+    investor: &InvestorData,
+    stream: Option<&StreamAccount>,
+    at_ts: u64,
+) -> Result<StreamLocked> {
+    let system_program = Pubkey::default();
+    let (owner, mut lamports, mut data) = match stream {
+        Some(account) => (account.owner, account.lamports, account.data.clone()),
+        None => (system_program, 0, Vec::new()),
+    };
+    let info = AccountInfo::new(&investor.stream, false, false, &mut lamports, &mut data, &owner, false, 0);
+    lockers.read_investor_locked(&info, investor, at_ts)
+}
+
+/// Policy settings and carried-over amounts a day's distribution depends on
+#[derive(Clone, Debug, Default)]
+pub struct DayInputs {
+    pub investor_fee_share_bps: u16,
+    pub y0_total_allocation: u128,
+    pub daily_cap_quote_lamports: u64,
+    pub min_payout_lamports: u64,
+    pub min_daily_claim_lamports: u64,
+    pub roll_capped_excess: bool,
+    /// Largest share of the pool one investor may take (0 = off)
+    pub max_weight_bps: u16,
+    /// Quote held back from deferred days, claimed again on the next day's first page
+    pub rollover_claimed_quote: u64,
+    /// Investor share capped out on earlier days, added to the next day's investor pool
+    pub capped_excess_rollover: u64,
+    /// Daily cap left unused on earlier days, added to the next day's cap
+    ///
+    /// Days skipped before the next crank bank their cap on-chain too; this is the banked amount as of now.
+    pub unused_cap_allowance: u64,
+    /// Most unused cap that can be banked (`cap_carry_days` days of the daily cap; 0 = off)
+    pub max_banked_cap: u64,
+    /// Per-investor minimum payouts replacing `min_payout_lamports`
+    pub payout_overrides: Vec<PayoutOverride>,
+    /// Fixed weights added to investors' locked amounts
+    pub weight_overrides: Vec<WeightOverride>,
+}
+
+impl DayInputs {
+    /// Inputs for day (or tranche) `day_epoch`, with the daily amounts split evenly over tranches
+    /// and the policy's scheduled params applied (see `pages::crank_day_epoch` for the next day)
+    pub fn new(policy: &PolicyPda, progress: &ProgressPda, day_epoch: u64) -> Self {
+        Self {
+            investor_fee_share_bps: policy.investor_fee_share_bps,
+            y0_total_allocation: policy.y0_total_allocation,
+            daily_cap_quote_lamports: policy.tranche_cap_quote_lamports(day_epoch),
+            min_payout_lamports: policy.min_payout_at(day_epoch),
+            min_daily_claim_lamports: policy.tranche_min_claim_lamports(),
+            roll_capped_excess: policy.roll_capped_excess,
+            max_weight_bps: policy.max_weight_bps,
+            rollover_claimed_quote: progress.rollover_claimed_quote,
+            capped_excess_rollover: progress.capped_excess_rollover,
+            unused_cap_allowance: progress.unused_cap_allowance,
+            max_banked_cap: policy.max_banked_cap_quote_lamports(day_epoch),
+            payout_overrides: Vec::new(),
+            weight_overrides: Vec::new(),
+        }
+    }
+
+    /// Apply the vault's payout override table (policy.payout_overrides)
+    pub fn with_payout_overrides(mut self, table: &PayoutOverridesPda) -> Self {
+        self.payout_overrides = table.entries.clone();
+        self
+    }
+
+    /// Apply the vault's fixed weight table (policy.weight_overrides)
+    pub fn with_weight_overrides(mut self, table: &WeightOverridesPda) -> Self {
+        self.weight_overrides = table.entries.clone();
+        self
+    }
+
+    fn weight_for(&self, investor: &Pubkey) -> u64 {
+        self.weight_overrides
+            .iter()
+            .find(|entry| entry.investor == *investor)
+            .map_or(0, |entry| entry.weight)
+    }
+
+    fn min_payout_for(&self, investor: &Pubkey) -> u64 {
+        self.payout_overrides
+            .iter()
+            .find(|entry| entry.investor == *investor)
+            .map_or(self.min_payout_lamports, |entry| entry.min_payout_lamports)
+    }
+}
+
+/// What the crank would do for one investor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayoutPreview {
+    Paid(u64),
+    /// Payout below `min_payout_lamports`; the amount stays in the treasury as dust
+    BelowMinPayout(u64),
+    ZeroLocked,
+    Ignored(StreamIgnoredReason),
+}
+
+#[derive(Clone, Debug)]
+pub struct InvestorPreview {
+    pub investor: InvestorData,
+    pub locked: u64,
+    pub payout: PayoutPreview,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DayPreview {
+    /// Claimable quote plus any rollover from deferred days
+    pub claimed_quote: u64,
+    /// The claim is below `min_daily_claim_lamports`; the day would be deferred without payouts
+    pub deferred: bool,
+    pub total_locked: u64,
+    pub eligible_bps: u16,
+    /// Investor share before the daily cap, including capped excess rolled in from earlier days
+    pub investor_fee_quote: u64,
+    /// Investor share after the daily cap
+    pub investor_pool: u64,
+    /// Capped-out investor share held for the next day (`roll_capped_excess`)
+    pub capped_excess_rolled: u64,
+    /// Daily cap left unused, banked for later days (`cap_carry_days`)
+    pub unused_cap_banked: u64,
+    /// Empty when the day is deferred or nothing was claimed
+    pub investors: Vec<InvestorPreview>,
+    pub distributed: u64,
+    pub dust: u64,
+    pub creator_remainder: u64,
+}
+
+/// Project the next distribution day as cranked from its first page
+///
+/// `claimable_quote` is the quote fee the honorary position would claim, net of any transfer fee
+/// into the treasury. Every investor is evaluated against the full day's locked total, as with a
+/// locked snapshot, and the pool is apportioned as if the day were one page: the crank apportions
+/// per page, so an investor's payout can differ by a lamport while totals match. Not modeled: fees accruing between pages, and payouts to missing or frozen
+/// investor ATAs, which the crank sends to dust or the failed payout ledger depending on policy.
+pub fn preview_day(
+    inputs: &DayInputs,
+    claimable_quote: u64,
+    investors: &[InvestorData],
+    locked: &[StreamLocked],
+) -> Result<DayPreview> {
+    let claimed_quote = claimable_quote
+        .checked_add(inputs.rollover_claimed_quote)
+        .ok_or(FeeRouterError::Overflow)?;
+    let mut preview = DayPreview {
+        claimed_quote,
+        ..DayPreview::default()
+    };
+    if inputs.min_daily_claim_lamports > 0 && claimed_quote < inputs.min_daily_claim_lamports {
+        preview.deferred = true;
+        return Ok(preview);
+    }
+    if claimed_quote == 0 {
+        return Ok(preview);
+    }
+
+    // Fixed weights count as locked tokens, as the snapshot records them
+    let locked = investors
+        .iter()
+        .zip(locked)
+        .map(|(investor, entry)| entry.with_weight(inputs.weight_for(&investor.investor)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut total_locked = 0u128;
+    for entry in &locked {
+        if let StreamLocked::Locked(amount) = entry {
+            total_locked = total_locked
+                .checked_add(*amount as u128)
+                .ok_or(FeeRouterError::Overflow)?;
+        }
+    }
+
+    let locked_fraction = DistributionMath::calculate_locked_fraction(total_locked, inputs.y0_total_allocation)
+        .map_err(FeeRouterError::from)?;
+    let eligible_bps = DistributionMath::calculate_eligible_bps(
+        total_locked,
+        inputs.y0_total_allocation,
+        inputs.investor_fee_share_bps,
+    )
+    .map_err(FeeRouterError::from)?;
+    let investor_fee_quote =
+        DistributionMath::calculate_investor_fee_quote(claimed_quote as u128, locked_fraction, inputs.investor_fee_share_bps)
+            .map_err(FeeRouterError::from)?
+        .checked_add(inputs.capped_excess_rollover as u128)
+        .ok_or(FeeRouterError::Overflow)?;
+    let (investor_pool, unused_cap_banked) = DistributionMath::apply_carried_daily_cap(
+        investor_fee_quote,
+        inputs.daily_cap_quote_lamports,
+        inputs.unused_cap_allowance,
+        inputs.max_banked_cap,
+    );
+    let capped_excess_rolled = if inputs.roll_capped_excess {
+        investor_fee_quote.saturating_sub(investor_pool) as u64
+    } else {
+        0
+    };
+
+    let locked_amounts: Vec<u64> = locked
+        .iter()
+        .map(|entry| match entry {
+            StreamLocked::Locked(amount) => *amount,
+            StreamLocked::Ignored(_) => 0,
+        })
+        .collect();
+    // The snapshot solves the cap over its largest amounts; sorting them all gives the same cap
+    let mut largest_locked = locked_amounts.clone();
+    largest_locked.sort_unstable_by(|a, b| b.cmp(a));
+    let weight_cap = WeightCap::solve(inputs.max_weight_bps, &largest_locked, total_locked)
+        .map_err(FeeRouterError::from)?;
+    let payout_weights = locked_amounts
+        .iter()
+        .map(|amount| weight_cap.payout_weight(*amount as u128))
+        .collect::<core::result::Result<Vec<u128>, _>>()
+        .map_err(FeeRouterError::from)?;
+    let total_weight = weight_cap.total_weight(total_locked).map_err(FeeRouterError::from)?;
+    let allocation = DistributionMath::calculate_page_allocation(
+        0,
+        payout_weights.iter().sum(),
+        total_weight,
+        investor_pool,
+    )
+    .map_err(FeeRouterError::from)?;
+    let payouts =
+        DistributionMath::apportion_largest_remainder(&payout_weights, total_weight, investor_pool, allocation)
+            .map_err(FeeRouterError::from)?;
+
+    let mut distributed = 0u128;
+    let mut dust = 0u64;
+    for ((investor, entry), raw_payout) in investors.iter().zip(&locked).zip(payouts) {
+        let (locked_amount, payout) = match *entry {
+            StreamLocked::Ignored(reason) => (0, PayoutPreview::Ignored(reason)),
+            StreamLocked::Locked(0) => (0, PayoutPreview::ZeroLocked),
+            StreamLocked::Locked(amount) => {
+                if raw_payout < inputs.min_payout_for(&investor.investor) as u128 {
+                    dust += raw_payout as u64;
+                    (amount, PayoutPreview::BelowMinPayout(raw_payout as u64))
+                } else {
+                    distributed += raw_payout;
+                    (amount, PayoutPreview::Paid(raw_payout as u64))
+                }
+            }
+        };
+        preview.investors.push(InvestorPreview {
+            investor: investor.clone(),
+            locked: locked_amount,
+            payout,
+        });
+    }
+
+    preview.creator_remainder = (claimed_quote as u128)
+        .saturating_add(inputs.capped_excess_rollover as u128)
+        .saturating_sub(distributed)
+        .saturating_sub(dust as u128)
+        .saturating_sub(capped_excess_rolled as u128) as u64;
+    preview.total_locked = total_locked as u64;
+    preview.eligible_bps = eligible_bps;
+    preview.investor_fee_quote = investor_fee_quote as u64;
+    preview.investor_pool = investor_pool as u64;
+    preview.capped_excess_rolled = capped_excess_rolled;
+    preview.unused_cap_banked = unused_cap_banked;
+    preview.distributed = distributed as u64;
+    preview.dust = dust;
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn investors(count: u32) -> Vec<InvestorData> {
+        (0..count)
+            .map(|index| InvestorData {
+                stream: Pubkey::new_unique(),
+                investor: Pubkey::new_unique(),
+                index,
+            })
+            .collect()
+    }
+
+    fn inputs() -> DayInputs {
+        DayInputs {
+            investor_fee_share_bps: 9000,
+            y0_total_allocation: 1_000,
+            min_payout_lamports: 100,
+            ..DayInputs::default()
+        }
+    }
+
+    #[test]
+    fn test_preview_splits_dust_and_remainder() {
+        let all = investors(4);
+        let locked = [
+            StreamLocked::Locked(500),
+            StreamLocked::Locked(1),
+            StreamLocked::Locked(0),
+            StreamLocked::Ignored(StreamIgnoredReason::Canceled),
+        ];
+        let preview = preview_day(&inputs(), 1_000_000, &all, &locked).unwrap();
+
+        // 501/1000 locked -> 5010 bps of 1_000_000
+        assert_eq!(preview.total_locked, 501);
+        assert_eq!(preview.eligible_bps, 5010);
+        assert_eq!(preview.investor_pool, 501_000);
+        assert_eq!(preview.investors[0].payout, PayoutPreview::Paid(500_000));
+        // 1 * 501_000 / 501 = 1_000 >= 100
+        assert_eq!(preview.investors[1].payout, PayoutPreview::Paid(1_000));
+        assert_eq!(preview.investors[2].payout, PayoutPreview::ZeroLocked);
+        assert_eq!(
+            preview.investors[3].payout,
+            PayoutPreview::Ignored(StreamIgnoredReason::Canceled)
+        );
+        assert_eq!(preview.distributed, 501_000);
+        assert_eq!(preview.creator_remainder, 499_000);
+
+        // A fixed weight lets an investor without a live stream share, and counts toward the locked total
+        let weighted = DayInputs {
+            weight_overrides: vec![WeightOverride { investor: all[3].investor, weight: 499 }],
+            ..inputs()
+        };
+        let preview = preview_day(&weighted, 1_000_000, &all, &locked).unwrap();
+        assert_eq!(preview.total_locked, 1_000);
+        assert_eq!(preview.investor_pool, 900_000);
+        assert_eq!(preview.investors[3].locked, 499);
+        assert_eq!(preview.investors[3].payout, PayoutPreview::Paid(449_100));
+
+        // A 45% cap clips both large investors, and the clipped excess goes to the small one
+        let capped = DayInputs {
+            max_weight_bps: 4500,
+            ..weighted
+        };
+        let preview = preview_day(&capped, 1_000_000, &all, &locked).unwrap();
+        assert_eq!(preview.investors[0].payout, PayoutPreview::Paid(405_000));
+        assert_eq!(preview.investors[3].payout, PayoutPreview::Paid(405_000));
+        assert_eq!(preview.investors[1].payout, PayoutPreview::Paid(90_000));
+        assert_eq!(preview.distributed, 900_000);
+
+        // A higher threshold sends the small payout to dust, which stays out of the creator remainder
+        let strict = DayInputs {
+            min_payout_lamports: 2_000,
+            ..inputs()
+        };
+        let preview = preview_day(&strict, 1_000_000, &all, &locked).unwrap();
+        assert_eq!(preview.investors[1].payout, PayoutPreview::BelowMinPayout(1_000));
+        assert_eq!(preview.dust, 1_000);
+        assert_eq!(preview.creator_remainder, 499_000);
+
+        // An override lowers the threshold for that investor only
+        let overridden = DayInputs {
+            payout_overrides: vec![PayoutOverride { investor: all[1].investor, min_payout_lamports: 500 }],
+            ..strict
+        };
+        let preview = preview_day(&overridden, 1_000_000, &all, &locked).unwrap();
+        assert_eq!(preview.investors[1].payout, PayoutPreview::Paid(1_000));
+        assert_eq!(preview.dust, 0);
+    }
+
+    #[test]
+    fn test_preview_cap_rollover_and_deferral() {
+        let all = investors(2);
+        let locked = [StreamLocked::Locked(600), StreamLocked::Locked(400)];
+        let capped = DayInputs {
+            daily_cap_quote_lamports: 500_000,
+            roll_capped_excess: true,
+            capped_excess_rollover: 50_000,
+            ..inputs()
+        };
+        let preview = preview_day(&capped, 1_000_000, &all, &locked).unwrap();
+        assert_eq!(preview.investor_fee_quote, 950_000);
+        assert_eq!(preview.investor_pool, 500_000);
+        assert_eq!(preview.capped_excess_rolled, 450_000);
+        assert_eq!(preview.distributed, 500_000);
+        // 1_000_000 + 50_000 rolled in - 500_000 paid - 450_000 rolled out
+        assert_eq!(preview.creator_remainder, 100_000);
+
+        // Cap banked on quiet days lifts the cap, and nothing is banked once it binds
+        let carried = DayInputs {
+            unused_cap_allowance: 200_000,
+            max_banked_cap: 1_000_000,
+            ..capped.clone()
+        };
+        let preview = preview_day(&carried, 1_000_000, &all, &locked).unwrap();
+        assert_eq!(preview.investor_pool, 700_000);
+        assert_eq!((preview.capped_excess_rolled, preview.unused_cap_banked), (250_000, 0));
+
+        // Largest-remainder rounding leaves no dust: 1/3 each of 1_000 -> 334, 333, 333
+        let thirds = investors(3);
+        let even = [StreamLocked::Locked(100), StreamLocked::Locked(100), StreamLocked::Locked(100)];
+        let share_all = DayInputs {
+            investor_fee_share_bps: 10_000,
+            y0_total_allocation: 300,
+            min_payout_lamports: 0,
+            ..inputs()
+        };
+        let preview = preview_day(&share_all, 1_000, &thirds, &even).unwrap();
+        assert_eq!(preview.investors[0].payout, PayoutPreview::Paid(334));
+        assert_eq!(preview.investors[2].payout, PayoutPreview::Paid(333));
+        assert_eq!((preview.distributed, preview.dust, preview.creator_remainder), (1_000, 0, 0));
+
+        // Below the minimum daily claim, rollover included, the day is deferred
+        let deferring = DayInputs {
+            min_daily_claim_lamports: 1_000,
+            rollover_claimed_quote: 400,
+            ..inputs()
+        };
+        let preview = preview_day(&deferring, 500, &all, &locked).unwrap();
+        assert!(preview.deferred);
+        assert_eq!(preview.claimed_quote, 900);
+        assert!(preview.investors.is_empty());
+    }
+}
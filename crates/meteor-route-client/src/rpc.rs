@@ -0,0 +1,325 @@
+//! Account reads and crank account assembly over RPC (feature `rpc`)
+use std::fmt;
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, AnchorDeserialize, Discriminator};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytemuck::Pod;
+use meteor_route_fee_router::{
+    instructions::{DistributeFeesResult, DistributionParams},
+    locker::{LockerSources, StreamLocked},
+    state::{CrankLogPda, InvestorFeePositionOwnerPda, PolicyPda, PoolKind, ProgressPda, ProtocolConfig, VaultRegistry},
+    streamflow::STREAMFLOW_PROGRAM_ID,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    client_error::Error as ClientError,
+    config::{RpcSimulateTransactionConfig, RpcTransactionConfig},
+};
+use solana_signature::Signature;
+use solana_transaction::Transaction;
+use solana_transaction_status_client_types::UiTransactionReturnData;
+
+use crate::{
+    pages::{page_payloads, PagePayload, PayoutDestination},
+    preview::{investor_locked, StreamAccount},
+    pda, CrankAccounts, CrankOptions, InvestorData, Vault,
+};
+
+#[derive(Debug)]
+pub enum RpcError {
+    Client(Box<ClientError>),
+    AccountNotFound(Pubkey),
+    InvalidAccountData(Pubkey),
+    /// Crank assembly only reads CP-AMM pools; other backends need their claim accounts built by hand
+    UnsupportedPoolKind(PoolKind),
+    /// A simulated instruction failed or set no fee router return data
+    SimulationFailed(String),
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcError::Client(err) => write!(f, "rpc error: {}", err),
+            RpcError::AccountNotFound(key) => write!(f, "account {} not found", key),
+            RpcError::InvalidAccountData(key) => write!(f, "account {} has unexpected data", key),
+            RpcError::UnsupportedPoolKind(kind) => write!(f, "pool kind {:?} is not supported", kind),
+            RpcError::SimulationFailed(err) => write!(f, "simulation failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<ClientError> for RpcError {
+    fn from(err: ClientError) -> Self {
+        RpcError::Client(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, RpcError>;
+
+/// Account data and owner, or None if the account does not exist
+fn account_data(rpc: &RpcClient, address: &Pubkey) -> Result<Option<(Vec<u8>, Pubkey)>> {
+    let response = rpc.get_account_with_commitment(address, rpc.commitment())?;
+    Ok(response.value.map(|account| (account.data, account.owner)))
+}
+
+/// Fetch and deserialize an Anchor account
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    fetch_optional(rpc, address)?.ok_or(RpcError::AccountNotFound(*address))
+}
+
+/// Fetch and deserialize an Anchor account that may not exist yet
+pub fn fetch_optional<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<Option<T>> {
+    let Some((data, _)) = account_data(rpc, address)? else {
+        return Ok(None);
+    };
+    T::try_deserialize(&mut data.as_slice())
+        .map(Some)
+        .map_err(|_| RpcError::InvalidAccountData(*address))
+}
+
+/// Fetch a zero-copy account (e.g. CP-AMM pools and positions)
+pub fn fetch_zero_copy<T: Pod + Discriminator>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let (data, _) = account_data(rpc, address)?.ok_or(RpcError::AccountNotFound(*address))?;
+    let disc_len = T::DISCRIMINATOR.len();
+    let end = disc_len + std::mem::size_of::<T>();
+    if data.len() < end || &data[..disc_len] != T::DISCRIMINATOR {
+        return Err(RpcError::InvalidAccountData(*address));
+    }
+    // RPC buffers carry no alignment guarantee
+    Ok(bytemuck::pod_read_unaligned(&data[disc_len..end]))
+}
+
+/// Program that owns `address` (the token program, for mints)
+pub fn owner(rpc: &RpcClient, address: &Pubkey) -> Result<Pubkey> {
+    account_data(rpc, address)?
+        .map(|(_, owner)| owner)
+        .ok_or(RpcError::AccountNotFound(*address))
+}
+
+/// Accounts per getMultipleAccounts request
+const MULTIPLE_ACCOUNTS_LIMIT: usize = 100;
+
+/// Fetch each investor's stream account; closed streams come back as None
+pub fn fetch_streams(rpc: &RpcClient, investors: &[InvestorData]) -> Result<Vec<Option<StreamAccount>>> {
+    let mut streams = Vec::with_capacity(investors.len());
+    for chunk in investors.chunks(MULTIPLE_ACCOUNTS_LIMIT) {
+        let keys: Vec<Pubkey> = chunk.iter().map(|inv| inv.stream).collect();
+        let accounts = rpc.get_multiple_accounts_with_commitment(&keys, rpc.commitment())?.value;
+        streams.extend(accounts.into_iter().map(|account| {
+            account.map(|account| StreamAccount {
+                owner: account.owner,
+                lamports: account.lamports,
+                data: account.data,
+            })
+        }));
+    }
+    Ok(streams)
+}
+
+/// Cluster time of the latest slot
+pub fn block_time(rpc: &RpcClient) -> Result<u64> {
+    Ok(slot_and_time(rpc)?.1)
+}
+
+/// Latest slot and its cluster time, the two clocks a policy's day gate can read
+pub fn slot_and_time(rpc: &RpcClient) -> Result<(u64, u64)> {
+    let slot = rpc.get_slot()?;
+    Ok((slot, rpc.get_block_time(slot)? as u64))
+}
+
+/// Return data of a confirmed `distribute_fees` transaction
+///
+/// None if the transaction's last return data is not a fee router `DistributeFeesResult`.
+pub fn distribute_fees_result(rpc: &RpcClient, signature: &Signature) -> Result<Option<DistributeFeesResult>> {
+    let tx = rpc.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            commitment: Some(rpc.commitment()),
+            max_supported_transaction_version: Some(0),
+            ..RpcTransactionConfig::default()
+        },
+    )?;
+    let return_data: Option<UiTransactionReturnData> = tx.transaction.meta.and_then(|meta| meta.return_data.into());
+    Ok(fee_router_return_data(return_data))
+}
+
+/// Simulate `get_distribution_params` and decode the vault's `DistributionParams`
+///
+/// Nothing is signed or sent. `fee_payer` must be an existing system account; it is charged nothing.
+pub fn distribution_params(rpc: &RpcClient, vault: &Vault, fee_payer: &Pubkey) -> Result<DistributionParams> {
+    let tx = Transaction::new_with_payer(&[vault.get_distribution_params()], Some(fee_payer));
+    let simulation = rpc
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(rpc.commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )?
+        .value;
+    if let Some(err) = simulation.err {
+        return Err(RpcError::SimulationFailed(err.to_string()));
+    }
+    fee_router_return_data(simulation.return_data)
+        .ok_or_else(|| RpcError::SimulationFailed("no DistributionParams return data".to_string()))
+}
+
+/// Return data set by the fee router, decoded as `T`
+fn fee_router_return_data<T: AnchorDeserialize>(return_data: Option<UiTransactionReturnData>) -> Option<T> {
+    return_data
+        .filter(|data| data.program_id == meteor_route_fee_router::ID.to_string())
+        .and_then(|data| STANDARD.decode(&data.data.0).ok())
+        .and_then(|bytes| T::try_from_slice(&bytes).ok())
+}
+
+/// Fetch the investors' stream accounts and build ready-to-send pages
+///
+/// Streams are read at the current block time through the vault's locker adapters; closed,
+/// canceled and zero-locked streams are left out. A stream the crank could not read (e.g. its
+/// recipient is not the investor) fails with `InvalidAccountData`. See `pages::page_payloads` for
+/// the ordering. Pages are hashed for day `day_epoch` (see `pages::crank_day_epoch`) of the vault's
+/// `generation`.
+/// `locker_program` defaults to Streamflow.
+pub fn build_pages(
+    rpc: &RpcClient,
+    vault: &Vault,
+    investors: &[InvestorData],
+    day_epoch: u64,
+    generation: u16,
+    page_size: usize,
+    locker_program: Option<Pubkey>,
+) -> Result<Vec<PagePayload>> {
+    let policy: PolicyPda = fetch(rpc, &vault.policy())?;
+    let quote_token_program = owner(rpc, &vault.quote_mint)?;
+    let lockers = LockerSources::from_policy(&policy, locker_program.unwrap_or(STREAMFLOW_PROGRAM_ID));
+    let now = block_time(rpc)?;
+    let streams = fetch_streams(rpc, investors)?;
+    let locked = investors
+        .iter()
+        .zip(streams.iter())
+        .map(|(inv, stream)| {
+            investor_locked(&lockers, inv, stream.as_ref(), now).map_err(|_| RpcError::InvalidAccountData(inv.stream))
+        })
+        .collect::<Result<Vec<StreamLocked>>>()?;
+    let attestation = policy.attestation_gate();
+    Ok(page_payloads(
+        &vault.vault_seed,
+        day_epoch,
+        generation,
+        investors,
+        &locked,
+        &vault.quote_mint,
+        &quote_token_program,
+        attestation.as_ref(),
+        &PayoutDestination::for_policy(&policy, &vault.vault_seed),
+        page_size,
+    ))
+}
+
+/// On-chain state of one vault; progress and position owner are None before they are initialized
+pub struct VaultState {
+    pub policy: PolicyPda,
+    pub progress: Option<ProgressPda>,
+    pub position_owner: Option<InvestorFeePositionOwnerPda>,
+}
+
+pub fn vault_state(rpc: &RpcClient, vault: &Vault) -> Result<VaultState> {
+    Ok(VaultState {
+        policy: fetch(rpc, &vault.policy())?,
+        progress: fetch_optional(rpc, &vault.progress())?,
+        position_owner: fetch_optional(rpc, &vault.position_owner())?,
+    })
+}
+
+/// The vault's crank log, or None before `initialize_crank_log`
+pub fn crank_log(rpc: &RpcClient, vault: &Vault) -> Result<Option<CrankLogPda>> {
+    fetch_optional(rpc, &pda::crank_log(&vault.vault_seed).0)
+}
+
+/// Vaults created by `authority`, read from its vault registry (empty if it has none)
+pub fn registered_vaults(rpc: &RpcClient, authority: &Pubkey) -> Result<Vec<Vault>> {
+    let Some(registry) = fetch_optional::<VaultRegistry>(rpc, &pda::vault_registry(authority).0)? else {
+        return Ok(Vec::new());
+    };
+    let mut vaults = Vec::with_capacity(registry.vaults.len());
+    for chunk in registry.vaults.chunks(MULTIPLE_ACCOUNTS_LIMIT) {
+        let accounts = rpc.get_multiple_accounts_with_commitment(chunk, rpc.commitment())?.value;
+        for (address, account) in chunk.iter().zip(accounts) {
+            let account = account.ok_or(RpcError::AccountNotFound(*address))?;
+            let policy = PolicyPda::try_deserialize(&mut account.data.as_slice())
+                .map_err(|_| RpcError::InvalidAccountData(*address))?;
+            vaults.push(Vault::new(policy.vault_seed, policy.quote_mint));
+        }
+    }
+    Ok(vaults)
+}
+
+/// Crank inputs that are not on chain
+pub struct CrankParams {
+    pub crank_caller: Pubkey,
+    pub creator_quote_ata: Pubkey,
+    /// Locker program stream accounts are read through; defaults to Streamflow
+    pub locker_program: Option<Pubkey>,
+    /// Creator wallet, used when the policy unwraps the wSOL creator payout
+    pub creator_wallet: Pubkey,
+}
+
+/// Assemble the `distribute_fees` accounts of a CP-AMM vault from chain state
+///
+/// The temp accounts are the position owner PDA's ATAs for the pool mints and must already exist.
+pub fn crank_accounts(rpc: &RpcClient, vault: &Vault, state: &VaultState, params: &CrankParams) -> Result<CrankAccounts> {
+    let policy = &state.policy;
+    if policy.pool_kind != PoolKind::CpAmm {
+        return Err(RpcError::UnsupportedPoolKind(policy.pool_kind));
+    }
+    let position_owner = state
+        .position_owner
+        .as_ref()
+        .ok_or(RpcError::AccountNotFound(vault.position_owner()))?;
+
+    let pool: cp_amm::state::Pool = fetch_zero_copy(rpc, &policy.pool_pubkey)?;
+    let position: cp_amm::state::Position = fetch_zero_copy(rpc, &position_owner.position_pubkey)?;
+    let (position_nft_account, _) = Pubkey::find_program_address(
+        &[cp_amm::constants::seeds::POSITION_NFT_ACCOUNT_PREFIX, position.nft_mint.as_ref()],
+        &policy.cp_amm_program_id,
+    );
+
+    let token_a_program = owner(rpc, &pool.token_a_mint)?;
+    let token_b_program = owner(rpc, &pool.token_b_mint)?;
+    let token_program = if vault.quote_mint == pool.token_a_mint {
+        token_a_program
+    } else {
+        token_b_program
+    };
+    let owner_pda = vault.position_owner();
+    let mut options = CrankOptions::from_policy(policy, params.creator_wallet);
+    let protocol_config: Option<ProtocolConfig> = fetch_optional(rpc, &pda::protocol_config().0)?;
+    options.protocol_treasury = protocol_config
+        .filter(|config| config.protocol_fee_bps > 0)
+        .map(|config| config.protocol_treasury);
+
+    Ok(CrankAccounts {
+        crank_caller: params.crank_caller,
+        pool: policy.pool_pubkey,
+        position: position_owner.position_pubkey,
+        position_nft_account: Some(position_nft_account),
+        token_a_vault: pool.token_a_vault,
+        token_b_vault: pool.token_b_vault,
+        token_a_mint: pool.token_a_mint,
+        token_b_mint: pool.token_b_mint,
+        temp_a_account: get_associated_token_address_with_program_id(&owner_pda, &pool.token_a_mint, &token_a_program),
+        temp_b_account: get_associated_token_address_with_program_id(&owner_pda, &pool.token_b_mint, &token_b_program),
+        creator_quote_ata: params.creator_quote_ata,
+        streamflow_program: params.locker_program.unwrap_or(STREAMFLOW_PROGRAM_ID),
+        token_program,
+        token_a_program,
+        token_b_program,
+        cp_amm_program: policy.cp_amm_program_id,
+        options,
+    })
+}
@@ -0,0 +1,16 @@
+//! Distribution math and page-hash construction shared by the MeteorRoute program and its clients
+//!
+//! `no_std` (with `alloc`) so the same code runs on chain, in the Rust client and in wasm builds.
+//! The program and every client computing payouts or page hashes through this crate get identical
+//! results by construction.
+#![no_std]
+
+extern crate alloc;
+
+pub mod math;
+pub mod page;
+
+pub use math::{DistributionMath, MathError, WeightCap};
+#[cfg(feature = "sha2")]
+pub use page::page_hash;
+pub use page::{page_hash_preimage, PageDomain, PAGE_HASH_DOMAIN};
@@ -0,0 +1,600 @@
+//! Distribution math: eligible share, investor pool, daily cap and pro-rata payouts
+use alloc::vec::Vec;
+use core::{cmp, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    /// Y0 total allocation is zero
+    InvalidY0,
+    /// Locked total exceeds the Y0 total allocation
+    LockedExceedsAllocation,
+    Overflow,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::InvalidY0 => write!(f, "Y0 total allocation must be greater than zero"),
+            MathError::LockedExceedsAllocation => write!(f, "locked total exceeds Y0 total allocation"),
+            MathError::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+/// Distribution math utilities
+pub struct DistributionMath;
+
+impl DistributionMath {
+    /// Scale of the locked fraction: 1e9 is fully locked
+    pub const LOCKED_FRACTION_SCALE: u128 = 1_000_000_000;
+
+    /// Calculate the locked fraction at 1e9 precision
+    /// f_locked = floor(locked_total * 1e9 / Y0), with locked_total <= Y0
+    pub fn calculate_locked_fraction(locked_total: u128, y0_total_allocation: u128) -> Result<u128> {
+        if y0_total_allocation == 0 {
+            return Err(MathError::InvalidY0);
+        }
+
+        if locked_total > y0_total_allocation {
+            return Err(MathError::LockedExceedsAllocation);
+        }
+
+        locked_total
+            .checked_mul(Self::LOCKED_FRACTION_SCALE)
+            .ok_or(MathError::Overflow)?
+            .checked_div(y0_total_allocation)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// Calculate eligible investor share in basis points, for reporting
+    /// eligible_bps = min(investor_fee_share_bps, floor(f_locked * 10000))
+    pub fn calculate_eligible_bps(
+        locked_total: u128,
+        y0_total_allocation: u128,
+        investor_fee_share_bps: u16,
+    ) -> Result<u16> {
+        let f_locked = Self::calculate_locked_fraction(locked_total, y0_total_allocation)?;
+        let f_locked_bps = f_locked / (Self::LOCKED_FRACTION_SCALE / 10000);
+
+        Ok(cmp::min(investor_fee_share_bps as u128, f_locked_bps) as u16)
+    }
+
+    /// Calculate investor fee quote amount
+    /// investor_fee_quote = min(floor(claimed_quote * f_locked / 1e9), floor(claimed_quote * investor_fee_share_bps / 10000))
+    ///
+    /// The locked fraction is carried at 1e9 precision and only clamped against the policy share
+    /// here, so the pool does not lose the sub-basis-point part of the fraction.
+    pub fn calculate_investor_fee_quote(
+        claimed_quote: u128,
+        locked_fraction: u128,
+        investor_fee_share_bps: u16,
+    ) -> Result<u128> {
+        let by_locked = claimed_quote
+            .checked_mul(cmp::min(locked_fraction, Self::LOCKED_FRACTION_SCALE))
+            .ok_or(MathError::Overflow)?
+            / Self::LOCKED_FRACTION_SCALE;
+        let by_share = claimed_quote
+            .checked_mul(investor_fee_share_bps as u128)
+            .ok_or(MathError::Overflow)?
+            / 10000;
+
+        Ok(cmp::min(by_locked, by_share))
+    }
+
+    /// Apply daily cap to investor fee quote
+    pub fn apply_daily_cap(investor_fee_quote: u128, daily_cap: u64, cumulative_distributed_today: u128) -> u128 {
+        if daily_cap == 0 {
+            return investor_fee_quote; // No cap
+        }
+
+        let remaining_cap = (daily_cap as u128).saturating_sub(cumulative_distributed_today);
+        cmp::min(investor_fee_quote, remaining_cap)
+    }
+
+    /// Apply a daily cap raised by cap left unused on earlier days
+    ///
+    /// Returns the capped quote and the unused cap to carry forward, which never exceeds `max_banked`.
+    /// With `max_banked` 0 this is `apply_daily_cap` with nothing carried.
+    pub fn apply_carried_daily_cap(investor_fee_quote: u128, daily_cap: u64, banked: u64, max_banked: u64) -> (u128, u64) {
+        if daily_cap == 0 {
+            return (investor_fee_quote, 0);
+        }
+
+        let cap = daily_cap as u128 + cmp::min(banked, max_banked) as u128;
+        let capped = cmp::min(investor_fee_quote, cap);
+        (capped, cmp::min(cap - capped, max_banked as u128) as u64)
+    }
+
+    /// Calculate individual investor payout
+    /// weight_i = locked_i / locked_total
+    /// raw_payout_i = floor(investor_fee_quote * weight_i)
+    pub fn calculate_investor_payout(locked_amount: u128, locked_total: u128, investor_fee_quote: u128) -> Result<u128> {
+        if locked_total == 0 {
+            return Ok(0);
+        }
+
+        Ok(mul_div_rem(locked_amount, investor_fee_quote, locked_total)?.0)
+    }
+
+    /// Running-floor share of `investor_fee_quote` for the next `page_locked` of the day's locked total
+    ///
+    /// allocation = floor(pool * (before + page) / total) - floor(pool * before / total)
+    ///
+    /// Summed over pages that cover the whole locked total this is exactly the pool, so no
+    /// lamport is lost between pages.
+    pub fn calculate_page_allocation(
+        locked_before: u128,
+        page_locked: u128,
+        locked_total: u128,
+        investor_fee_quote: u128,
+    ) -> Result<u128> {
+        let through = locked_before.checked_add(page_locked).ok_or(MathError::Overflow)?;
+        let end = Self::calculate_investor_payout(through, locked_total, investor_fee_quote)?;
+        let start = Self::calculate_investor_payout(locked_before, locked_total, investor_fee_quote)?;
+        Ok(end.saturating_sub(start))
+    }
+
+    /// Split a page allocation by largest remainder (Hamilton)
+    ///
+    /// Each investor gets the floor of their exact share `investor_fee_quote * locked_i / locked_total`,
+    /// then the lamports left in `allocation` go one each to the largest fractional remainders, ties
+    /// to the earlier investor. With an allocation from `calculate_page_allocation` the payouts sum to
+    /// exactly the allocation.
+    pub fn apportion_largest_remainder(
+        locked_amounts: &[u128],
+        locked_total: u128,
+        investor_fee_quote: u128,
+        allocation: u128,
+    ) -> Result<Vec<u128>> {
+        let mut payouts = Vec::with_capacity(locked_amounts.len());
+        let mut remainders = Vec::with_capacity(locked_amounts.len());
+        for (i, locked) in locked_amounts.iter().enumerate() {
+            if locked_total == 0 {
+                payouts.push(0);
+                continue;
+            }
+            let (floor, remainder) = mul_div_rem(*locked, investor_fee_quote, locked_total)?;
+            payouts.push(floor);
+            if *locked > 0 {
+                remainders.push((remainder, i));
+            }
+        }
+
+        let floored = payouts.iter().try_fold(0u128, |sum, payout| sum.checked_add(*payout));
+        let leftover = allocation.saturating_sub(floored.ok_or(MathError::Overflow)?);
+        // A running-floor allocation exceeds the floors by less than one lamport per investor
+        remainders.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for (_, i) in remainders.iter().take(cmp::min(leftover, remainders.len() as u128) as usize) {
+            payouts[*i] += 1;
+        }
+        Ok(payouts)
+    }
+}
+
+/// `floor(a * b / d)` and its remainder
+///
+/// Capped payout weights scale locked amounts by up to 10_000, so `weight * pool` can outgrow
+/// u128 for u64 pools and totals. Such products go through a 256-bit long division; the quotient
+/// itself never exceeds the pool.
+fn mul_div_rem(a: u128, b: u128, d: u128) -> Result<(u128, u128)> {
+    if d == 0 {
+        return Err(MathError::Overflow);
+    }
+    if let Some(product) = a.checked_mul(b) {
+        return Ok((product / d, product % d));
+    }
+    let (high, low) = widening_mul(a, b);
+    if high >= d {
+        return Err(MathError::Overflow);
+    }
+    // Restoring division, one bit of `low` at a time; `remainder < d` holds between steps
+    let (mut quotient, mut remainder) = (0u128, high);
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= d {
+            remainder = remainder.wrapping_sub(d);
+            quotient |= 1;
+        }
+    }
+    Ok((quotient, remainder))
+}
+
+/// Full 256-bit product of `a * b` as (high, low) halves
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let middle = (low_low >> 64) + (high_low & MASK) + (low_high & MASK);
+    let low = (low_low & MASK) | (middle << 64);
+    let high = a_high * b_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    (high, low)
+}
+
+/// Largest share of the day's investor pool one investor may take
+///
+/// Investors locking at least `threshold` get exactly `max_weight_bps` of the pool and the rest
+/// split what is left pro-rata, so the excess clipped from a whale stays with the other investors.
+/// Pages apportion the pool by `payout_weight` over `total_weight` instead of by locked amount.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WeightCap {
+    /// Cap in basis points; 0 keeps payouts proportional to locked amounts
+    pub max_weight_bps: u16,
+    /// Smallest capped locked amount (0 when no investor is capped)
+    pub threshold: u64,
+    pub capped_count: u32,
+    /// Locked total of the investors below the cap
+    pub uncapped_locked: u128,
+}
+
+impl WeightCap {
+    /// Largest locked amounts `solve` needs to see for a cap of `max_weight_bps`
+    pub const fn largest_needed(max_weight_bps: u16) -> usize {
+        10_000 / max_weight_bps as usize + 1
+    }
+
+    /// Solve the cap for a day locking `total_locked`
+    ///
+    /// `largest_locked` holds the day's largest locked amounts in descending order, at least
+    /// `largest_needed(max_weight_bps)` of them or all of them. Investors are capped from the top
+    /// while one's pro-rata share of what the capped ones leave would still exceed the cap. When
+    /// every locked investor is capped the pool can't be handed out in full; the rest stays
+    /// undistributed.
+    pub fn solve(max_weight_bps: u16, largest_locked: &[u64], total_locked: u128) -> Result<Self> {
+        if max_weight_bps == 0 || max_weight_bps >= 10_000 {
+            return Ok(Self::default());
+        }
+        let bps = max_weight_bps as u128;
+        let mut cap = Self {
+            max_weight_bps,
+            ..Self::default()
+        };
+        let mut capped_locked = 0u128;
+        for &locked in largest_locked.iter().take_while(|locked| **locked > 0) {
+            // share = locked * (10_000 - capped * bps) / uncapped, against the cap's bps
+            let left_bps = 10_000u128
+                .checked_sub(cap.capped_count as u128 * bps)
+                .ok_or(MathError::Overflow)?;
+            let uncapped = total_locked.checked_sub(capped_locked).ok_or(MathError::Overflow)?;
+            let share = (locked as u128).checked_mul(left_bps).ok_or(MathError::Overflow)?;
+            if share <= bps.checked_mul(uncapped).ok_or(MathError::Overflow)? {
+                break;
+            }
+            capped_locked += locked as u128;
+            cap.capped_count += 1;
+            cap.threshold = locked;
+        }
+        cap.uncapped_locked = total_locked.checked_sub(capped_locked).ok_or(MathError::Overflow)?;
+        Ok(cap)
+    }
+
+    /// Payout weight of an investor locking `locked`
+    pub fn payout_weight(&self, locked: u128) -> Result<u128> {
+        if self.max_weight_bps == 0 {
+            return Ok(locked);
+        }
+        let bps = self.max_weight_bps as u128;
+        if self.capped_count > 0 && locked >= self.threshold as u128 {
+            bps.checked_mul(self.unit()).ok_or(MathError::Overflow)
+        } else {
+            let left_bps = 10_000u128
+                .checked_sub(self.capped_count as u128 * bps)
+                .ok_or(MathError::Overflow)?;
+            locked.checked_mul(left_bps).ok_or(MathError::Overflow)
+        }
+    }
+
+    /// Sum of every payout weight of a day locking `total_locked`
+    pub fn total_weight(&self, total_locked: u128) -> Result<u128> {
+        if self.max_weight_bps == 0 {
+            return Ok(total_locked);
+        }
+        self.unit().checked_mul(10_000).ok_or(MathError::Overflow)
+    }
+
+    // Every locked investor capped: weigh the shares against a unit pool
+    fn unit(&self) -> u128 {
+        self.uncapped_locked.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eligible_bps_errors_and_clamp() {
+        assert_eq!(DistributionMath::calculate_eligible_bps(1, 0, 5000), Err(MathError::InvalidY0));
+        assert_eq!(
+            DistributionMath::calculate_eligible_bps(1001, 1000, 5000),
+            Err(MathError::LockedExceedsAllocation)
+        );
+        // 999/1000 floors to 9990 bps, then the share caps it
+        assert_eq!(DistributionMath::calculate_eligible_bps(999, 1000, 10_000), Ok(9990));
+        assert_eq!(DistributionMath::calculate_eligible_bps(999, 1000, 5000), Ok(5000));
+        assert_eq!(
+            DistributionMath::calculate_eligible_bps(u128::MAX, u128::MAX, 5000),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_carried_daily_cap() {
+        // A quiet day banks its unused cap, which a busy day then spends
+        assert_eq!(DistributionMath::apply_carried_daily_cap(200, 1_000, 0, 3_000), (200, 800));
+        assert_eq!(DistributionMath::apply_carried_daily_cap(5_000, 1_000, 800, 3_000), (1_800, 0));
+        // The bank is bounded, on the way in and on the way out
+        assert_eq!(DistributionMath::apply_carried_daily_cap(0, 1_000, 2_500, 3_000), (0, 3_000));
+        assert_eq!(DistributionMath::apply_carried_daily_cap(9_000, 1_000, 5_000, 3_000), (4_000, 0));
+        // Without a bank or a cap it is the plain daily cap
+        assert_eq!(DistributionMath::apply_carried_daily_cap(200, 1_000, 500, 0), (200, 0));
+        assert_eq!(DistributionMath::apply_carried_daily_cap(5_000, 0, 500, 3_000), (5_000, 0));
+    }
+
+    #[test]
+    fn test_investor_fee_quote_keeps_sub_bps_precision() {
+        // 1/3 locked is 3333 bps, but the pool keeps the full third
+        let fraction = DistributionMath::calculate_locked_fraction(1, 3).unwrap();
+        assert_eq!(fraction, 333_333_333);
+        assert_eq!(DistributionMath::calculate_eligible_bps(1, 3, 10_000), Ok(3333));
+        assert_eq!(
+            DistributionMath::calculate_investor_fee_quote(1_000_000_000, fraction, 10_000),
+            Ok(333_333_333)
+        );
+
+        // The policy share clamps the finished quote
+        assert_eq!(DistributionMath::calculate_investor_fee_quote(1_000_000_000, fraction, 2500), Ok(250_000_000));
+        assert_eq!(
+            DistributionMath::calculate_investor_fee_quote(u128::MAX, fraction, 2500),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_payouts_floor_and_cap() {
+        // 1/3 of 100 floors to 33; the dust stays with the pool
+        let pool =
+            DistributionMath::calculate_investor_fee_quote(1000, DistributionMath::LOCKED_FRACTION_SCALE, 1000).unwrap();
+        assert_eq!(pool, 100);
+        let payouts: u128 = (0..3)
+            .map(|_| DistributionMath::calculate_investor_payout(1, 3, pool).unwrap())
+            .sum();
+        assert_eq!(payouts, 99);
+        assert_eq!(DistributionMath::calculate_investor_payout(5, 0, pool), Ok(0));
+
+        // Cap 0 means uncapped; a cap already reached leaves nothing
+        assert_eq!(DistributionMath::apply_daily_cap(500, 0, 10_000), 500);
+        assert_eq!(DistributionMath::apply_daily_cap(500, 300, 100), 200);
+        assert_eq!(DistributionMath::apply_daily_cap(500, 300, 400), 0);
+    }
+
+    #[test]
+    fn test_largest_remainder_pays_the_whole_pool() {
+        // Three equal investors split 100: floors 33 each, the spare lamport goes to the first
+        let payouts = DistributionMath::apportion_largest_remainder(&[1, 1, 1], 3, 100, 100).unwrap();
+        assert_eq!(payouts, [34, 33, 33]);
+
+        // Largest remainder wins: 10 * 5/9 = 5.55, 10 * 3/9 = 3.33, 10 * 1/9 = 1.11
+        let payouts = DistributionMath::apportion_largest_remainder(&[5, 3, 1], 9, 10, 10).unwrap();
+        assert_eq!(payouts, [6, 3, 1]);
+
+        // Pages allocated by running floor cover the pool exactly, whatever the page split
+        let locked = [7u128, 11, 13, 17, 19, 23];
+        let total: u128 = locked.iter().sum();
+        let pool = 1_000_003;
+        let mut before = 0;
+        let mut paid = 0;
+        for page in locked.chunks(4) {
+            let page_locked = page.iter().sum();
+            let allocation = DistributionMath::calculate_page_allocation(before, page_locked, total, pool).unwrap();
+            let payouts = DistributionMath::apportion_largest_remainder(page, total, pool, allocation).unwrap();
+            assert_eq!(payouts.iter().sum::<u128>(), allocation);
+            before += page_locked;
+            paid += allocation;
+        }
+        assert_eq!(paid, pool);
+
+        // Zero-locked investors get nothing and no spare lamport
+        let payouts = DistributionMath::apportion_largest_remainder(&[0, 1, 2], 3, 10, 10).unwrap();
+        assert_eq!(payouts, [0, 3, 7]);
+    }
+
+    #[test]
+    fn test_binding_cap_scales_every_page() {
+        // A 1_000 pool capped to 400 once at day start: both pages lose the same 60%
+        let locked = [[300u128, 200], [400, 100]];
+        let pool = DistributionMath::apply_daily_cap(1_000, 400, 0);
+        let mut before = 0;
+        let mut payouts = Vec::new();
+        for page in locked.iter() {
+            let page_locked = page.iter().sum();
+            let allocation = DistributionMath::calculate_page_allocation(before, page_locked, 1_000, pool).unwrap();
+            payouts.extend(DistributionMath::apportion_largest_remainder(page, 1_000, pool, allocation).unwrap());
+            before += page_locked;
+        }
+        assert_eq!(payouts, [120, 80, 160, 40]);
+    }
+
+    fn capped_payouts(cap: &WeightCap, locked: &[u128], pool: u128) -> Vec<u128> {
+        let total: u128 = locked.iter().sum();
+        let weights: Vec<u128> = locked.iter().map(|l| cap.payout_weight(*l).unwrap()).collect();
+        let total_weight = cap.total_weight(total).unwrap();
+        let allocation =
+            DistributionMath::calculate_page_allocation(0, weights.iter().sum(), total_weight, pool).unwrap();
+        DistributionMath::apportion_largest_remainder(&weights, total_weight, pool, allocation).unwrap()
+    }
+
+    #[test]
+    fn test_weight_cap_redistributes_excess() {
+        // 7000/1000/1000/1000 under a 40% cap: the whale takes 40%, the rest split 60% evenly
+        let cap = WeightCap::solve(4000, &[7000, 1000, 1000, 1000], 10_000).unwrap();
+        assert_eq!((cap.capped_count, cap.threshold, cap.uncapped_locked), (1, 7000, 3000));
+        assert_eq!(capped_payouts(&cap, &[1000, 7000, 1000, 1000], 1_000), [200, 400, 200, 200]);
+
+        // Redistribution can push the next investor over the cap too: 30% cap over 50/30/10/10
+        let cap = WeightCap::solve(3000, &[50, 30, 10, 10], 100).unwrap();
+        assert_eq!((cap.capped_count, cap.threshold), (2, 30));
+        assert_eq!(capped_payouts(&cap, &[50, 30, 10, 10], 1_000), [300, 300, 200, 200]);
+
+        // Nobody over the cap leaves locked-proportional payouts
+        let cap = WeightCap::solve(5000, &[40, 30, 30], 100).unwrap();
+        assert_eq!(cap.capped_count, 0);
+        assert_eq!(capped_payouts(&cap, &[40, 30, 30], 1_001), capped_payouts(&WeightCap::default(), &[40, 30, 30], 1_001));
+
+        // Too few investors to place the whole pool under the cap: each gets the cap, the rest stays
+        let cap = WeightCap::solve(2500, &[60, 40], 100).unwrap();
+        assert_eq!((cap.capped_count, cap.uncapped_locked), (2, 0));
+        assert_eq!(capped_payouts(&cap, &[60, 40], 1_000), [250, 250]);
+
+        assert_eq!(WeightCap::largest_needed(500), 21);
+    }
+
+    #[test]
+    fn test_capped_payouts_with_u64_pool_and_total() {
+        // A capped weight times a u64 pool outgrows u128; the payout still floors exactly
+        let (pool, total) = (u64::MAX as u128, u64::MAX as u128);
+        let cap = WeightCap::solve(4000, &[u64::MAX / 2, u64::MAX / 4], total).unwrap();
+        let weight = cap.payout_weight(u64::MAX as u128 / 2).unwrap();
+        let total_weight = cap.total_weight(total).unwrap();
+        assert!(weight.checked_mul(pool).is_none());
+        assert_eq!(DistributionMath::calculate_investor_payout(weight, total_weight, pool), Ok(pool * 4000 / 10_000));
+        assert_eq!(mul_div_rem(u128::MAX, u128::MAX, u128::MAX), Ok((u128::MAX, 0)));
+        assert_eq!(mul_div_rem(u128::MAX, 2, 1), Err(MathError::Overflow));
+    }
+}
+
+/// Invariants over the full u64 range of on-chain amounts
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Up to 24 locked amounts whose total still fits a u64, like `snapshot_total_locked`
+    fn locked_amounts() -> impl Strategy<Value = Vec<u64>> {
+        (1usize..24).prop_flat_map(|n| proptest::collection::vec(0..=u64::MAX / n as u64, n))
+    }
+
+    /// Pay `locked` page by page as the crank does, returning each investor's payout
+    fn pay_in_pages(locked: &[u128], total: u128, pool: u128, page_size: usize) -> Result<Vec<u128>> {
+        let mut before = 0u128;
+        let mut payouts = Vec::with_capacity(locked.len());
+        for page in locked.chunks(page_size) {
+            let page_locked = page.iter().sum();
+            let allocation = DistributionMath::calculate_page_allocation(before, page_locked, total, pool)?;
+            let page_payouts = DistributionMath::apportion_largest_remainder(page, total, pool, allocation)?;
+            assert_eq!(page_payouts.iter().sum::<u128>(), allocation);
+            payouts.extend(page_payouts);
+            before += page_locked;
+        }
+        Ok(payouts)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_pages_conserve_the_pool(locked in locked_amounts(), pool in any::<u64>(), page_size in 1usize..8) {
+            let locked: Vec<u128> = locked.into_iter().map(u128::from).collect();
+            let total: u128 = locked.iter().sum();
+            let payouts = pay_in_pages(&locked, total, pool as u128, page_size).unwrap();
+            let paid: u128 = payouts.iter().sum();
+            // Everything is paid unless nobody is locked; nobody gets more than a lamport over their share
+            prop_assert_eq!(paid, if total == 0 { 0 } else { pool as u128 });
+            for (locked_i, payout) in locked.iter().zip(payouts.iter()) {
+                let share = DistributionMath::calculate_investor_payout(*locked_i, total, pool as u128).unwrap();
+                prop_assert!(*payout == share || *payout == share + 1);
+            }
+        }
+
+        #[test]
+        fn prop_capped_pages_conserve_the_pool(
+            locked in locked_amounts(),
+            pool in any::<u64>(),
+            max_weight_bps in 1u16..10_000,
+            page_size in 1usize..8,
+        ) {
+            let total: u128 = locked.iter().map(|l| *l as u128).sum();
+            let mut largest = locked.clone();
+            largest.sort_unstable_by(|a, b| b.cmp(a));
+            let cap = WeightCap::solve(max_weight_bps, &largest, total).unwrap();
+            let weights: Vec<u128> = locked.iter().map(|l| cap.payout_weight(*l as u128).unwrap()).collect();
+            let total_weight = cap.total_weight(total).unwrap();
+            let payouts = pay_in_pages(&weights, total_weight, pool as u128, page_size).unwrap();
+
+            let paid: u128 = payouts.iter().sum();
+            prop_assert!(paid <= pool as u128);
+            let cap_share = pool as u128 * max_weight_bps as u128 / 10_000;
+            prop_assert!(payouts.iter().all(|payout| *payout <= cap_share + 1));
+            // With investors left under the cap the whole pool is paid
+            if cap.uncapped_locked > 0 {
+                prop_assert_eq!(paid, pool as u128);
+            }
+        }
+
+        #[test]
+        fn prop_investor_fee_quote_is_bounded_and_monotonic(
+            claimed in any::<u64>(),
+            extra in any::<u64>(),
+            locked_total in any::<u64>(),
+            y0 in 1u64..,
+            share_bps in 0u16..=10_000,
+        ) {
+            let locked_total = locked_total.min(y0) as u128;
+            let fraction = DistributionMath::calculate_locked_fraction(locked_total, y0 as u128).unwrap();
+            prop_assert!(fraction <= DistributionMath::LOCKED_FRACTION_SCALE);
+            let quote = DistributionMath::calculate_investor_fee_quote(claimed as u128, fraction, share_bps).unwrap();
+            prop_assert!(quote <= claimed as u128 * share_bps as u128 / 10_000);
+
+            // More claimed, more locked or a larger share never shrinks the investors' quote
+            let more_claimed = claimed as u128 + extra as u128;
+            prop_assert!(DistributionMath::calculate_investor_fee_quote(more_claimed, fraction, share_bps).unwrap() >= quote);
+            let full = DistributionMath::calculate_investor_fee_quote(claimed as u128, DistributionMath::LOCKED_FRACTION_SCALE, share_bps).unwrap();
+            prop_assert!(full >= quote);
+            let all_bps = DistributionMath::calculate_investor_fee_quote(claimed as u128, fraction, 10_000).unwrap();
+            prop_assert!(all_bps >= quote);
+
+            let bps = DistributionMath::calculate_eligible_bps(locked_total, y0 as u128, share_bps).unwrap();
+            prop_assert!(bps <= share_bps);
+            prop_assert!(DistributionMath::calculate_eligible_bps(y0 as u128, y0 as u128, share_bps).unwrap() >= bps);
+        }
+
+        #[test]
+        fn prop_daily_cap_never_exceeded(quote in any::<u64>(), cap in any::<u64>(), distributed in any::<u64>()) {
+            let capped = DistributionMath::apply_daily_cap(quote as u128, cap, distributed as u128);
+            prop_assert!(capped <= quote as u128);
+            if cap == 0 {
+                prop_assert_eq!(capped, quote as u128);
+            } else {
+                prop_assert!(capped + distributed as u128 <= (cap as u128).max(distributed as u128));
+            }
+        }
+
+        #[test]
+        fn prop_payout_monotonic_in_locked(a in any::<u64>(), b in any::<u64>(), total in any::<u64>(), pool in any::<u64>()) {
+            let (low, high) = (a.min(b) as u128, a.max(b) as u128);
+            let total = (total as u128).max(high);
+            let low_payout = DistributionMath::calculate_investor_payout(low, total, pool as u128).unwrap();
+            let high_payout = DistributionMath::calculate_investor_payout(high, total, pool as u128).unwrap();
+            prop_assert!(low_payout <= high_payout && high_payout <= pool as u128);
+        }
+
+        #[test]
+        fn prop_wide_mul_div_matches_the_product(a in any::<u128>(), b in any::<u128>(), d in 1u128..) {
+            let (high, low) = widening_mul(a, b);
+            match mul_div_rem(a, b, d) {
+                Ok((quotient, remainder)) => {
+                    // quotient * d + remainder rebuilds the 256-bit product
+                    prop_assert!(remainder < d);
+                    let (q_high, q_low) = widening_mul(quotient, d);
+                    let (sum_low, carry) = q_low.overflowing_add(remainder);
+                    prop_assert_eq!((q_high + carry as u128, sum_low), (high, low));
+                }
+                Err(_) => prop_assert!(high >= d),
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Page-hash construction
+//!
+//! A page hash is SHA-256 over
+//! `PAGE_HASH_DOMAIN || program_id || vault_seed (u32 LE length, bytes) || day_epoch LE || generation LE || page_index LE || stream_0 || investor_0 || index_0 LE || stream_1 || ...`.
+//! The domain fields bind a page to one program, vault, day and vault generation, so a page signed
+//! off for one of them does not verify on another. Each investor's list index is hashed with its keys, since it
+//! selects the investor's slot in the exactly-once bitmap. [`page_hash_preimage`] yields those bytes as slices, ready for
+//! the `hashv` syscall on chain; [`page_hash`] hashes them in software for clients.
+use alloc::vec::Vec;
+
+/// Domain-separation tag leading every page hash preimage
+pub const PAGE_HASH_DOMAIN: &[u8] = b"meteor-route:page:v2";
+
+/// What a page hash is bound to besides its investors: the program, the vault, the day and the
+/// vault's generation (bumped by each restart of the vault)
+pub struct PageDomain<'a> {
+    program_id: &'a [u8; 32],
+    vault_seed: &'a [u8],
+    vault_seed_len_le: [u8; 4],
+    day_epoch_le: [u8; 8],
+    generation_le: [u8; 2],
+}
+
+impl<'a> PageDomain<'a> {
+    pub fn new(program_id: &'a [u8; 32], vault_seed: &'a str, day_epoch: u64, generation: u16) -> Self {
+        Self {
+            program_id,
+            vault_seed: vault_seed.as_bytes(),
+            vault_seed_len_le: (vault_seed.len() as u32).to_le_bytes(),
+            day_epoch_le: day_epoch.to_le_bytes(),
+            generation_le: generation.to_le_bytes(),
+        }
+    }
+}
+
+/// The slices a page hash covers, in order: the domain tag, program id, length-prefixed vault seed,
+/// day epoch and generation, the little-endian page index, then each investor's stream and investor keys and
+/// little-endian list index
+pub fn page_hash_preimage<'a, I>(domain: &'a PageDomain<'_>, page_index_le: &'a [u8; 8], investors: I) -> Vec<&'a [u8]>
+where
+    I: IntoIterator<Item = (&'a [u8; 32], &'a [u8; 32], &'a [u8; 4])>,
+{
+    let investors = investors.into_iter();
+    let mut chunks: Vec<&[u8]> = Vec::with_capacity(7 + investors.size_hint().0 * 3);
+    chunks.push(PAGE_HASH_DOMAIN);
+    chunks.push(domain.program_id);
+    chunks.push(&domain.vault_seed_len_le);
+    chunks.push(domain.vault_seed);
+    chunks.push(&domain.day_epoch_le);
+    chunks.push(&domain.generation_le);
+    chunks.push(page_index_le);
+    for (stream, investor, index_le) in investors {
+        chunks.push(stream);
+        chunks.push(investor);
+        chunks.push(index_le);
+    }
+    chunks
+}
+
+/// Page hash of `(stream, investor, index)` entries, computed with software SHA-256
+#[cfg(feature = "sha2")]
+pub fn page_hash<'a, I>(domain: &PageDomain<'_>, page_index: u64, investors: I) -> [u8; 32]
+where
+    I: IntoIterator<Item = (&'a [u8; 32], &'a [u8; 32], u32)>,
+{
+    use sha2::{Digest, Sha256};
+
+    let index_le = page_index.to_le_bytes();
+    let entries: Vec<_> = investors
+        .into_iter()
+        .map(|(stream, investor, index)| (stream, investor, index.to_le_bytes()))
+        .collect();
+    let mut hasher = Sha256::new();
+    let investors = entries.iter().map(|(stream, investor, index_le)| (*stream, *investor, index_le));
+    for chunk in page_hash_preimage(domain, &index_le, investors) {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preimage_layout() {
+        let program_id = [9u8; 32];
+        let domain = PageDomain::new(&program_id, "vault", 20_000, 3);
+        let index_le = 7u64.to_le_bytes();
+        let (a, b, c, d) = ([1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]);
+        let (i0, i1) = (3u32.to_le_bytes(), 4u32.to_le_bytes());
+        let chunks = page_hash_preimage(&domain, &index_le, [(&a, &b, &i0), (&c, &d, &i1)]);
+        let bytes: Vec<u8> = chunks.concat();
+        let header = PAGE_HASH_DOMAIN.len() + 32 + 4 + 5 + 8 + 2;
+        assert_eq!(bytes.len(), header + 8 + 4 * 32 + 2 * 4);
+        assert_eq!(&bytes[..PAGE_HASH_DOMAIN.len()], PAGE_HASH_DOMAIN);
+        assert_eq!(&bytes[PAGE_HASH_DOMAIN.len()..PAGE_HASH_DOMAIN.len() + 32], &program_id);
+        assert_eq!(&bytes[header - 19..header - 10], &[5, 0, 0, 0, b'v', b'a', b'u', b'l', b't']);
+        assert_eq!(&bytes[header - 10..header - 2], &20_000u64.to_le_bytes());
+        assert_eq!(&bytes[header - 2..header], &[3, 0]);
+        assert_eq!(&bytes[header..header + 8], &[7, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&bytes[header + 8..header + 40], &a);
+        assert_eq!(&bytes[header + 40..header + 72], &b);
+        assert_eq!(&bytes[header + 72..header + 76], &[3, 0, 0, 0]);
+        assert_eq!(&bytes[header + 76..header + 108], &c);
+        assert_eq!(&bytes[header + 108..header + 140], &d);
+        assert_eq!(&bytes[header + 140..], &[4, 0, 0, 0]);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_page_hash_binds_domain() {
+        let program_id = [9u8; 32];
+        let (stream, investor) = ([1u8; 32], [2u8; 32]);
+        let hash = |domain: &PageDomain| page_hash(domain, 0, [(&stream, &investor, 0)]);
+        let base = hash(&PageDomain::new(&program_id, "vault", 100, 0));
+
+        // Another day, generation, vault or program yields another hash for the same page
+        assert_ne!(hash(&PageDomain::new(&program_id, "vault", 101, 0)), base);
+        assert_ne!(hash(&PageDomain::new(&program_id, "vault", 100, 1)), base);
+        assert_ne!(hash(&PageDomain::new(&program_id, "vault2", 100, 0)), base);
+        assert_ne!(hash(&PageDomain::new(&[8u8; 32], "vault", 100, 0)), base);
+        assert_eq!(hash(&PageDomain::new(&program_id, "vault", 100, 0)), base);
+
+        // So does the same investor at another list index
+        let domain = PageDomain::new(&program_id, "vault", 100, 0);
+        assert_ne!(page_hash(&domain, 0, [(&stream, &investor, 1)]), base);
+    }
+}
@@ -0,0 +1,415 @@
+//! meteor-route-keeper: runs a vault's daily crank unattended
+//!
+//! Each cycle reads the progress PDA. A day in progress is resumed from the pagination cursor;
+//! otherwise the keeper sleeps until the 24h gate opens and then cranks the whole day, ending with
+//! the final page that pays the creator and closes the day.
+mod submit;
+
+use std::{collections::HashMap, path::PathBuf, thread::sleep, time::Duration};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_interface::TokenAccount;
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use meteor_route_client::{
+    investors,
+    pages::{crank_day_epoch, next_page_index, PagePayload},
+    rpc::{self, CrankParams},
+    CrankAccounts, Vault,
+};
+use meteor_route_fee_router::{
+    instructions::DistributeFeesResult,
+    state::{DayGate, PolicyPda, ProgressPda},
+    streamflow::STREAMFLOW_PROGRAM_ID,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    signature::read_keypair_file,
+    signer::Signer,
+};
+
+use crate::submit::Submitter;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+/// Slot time the keeper assumes when sleeping toward a slot day gate; it re-checks every poll
+const NOMINAL_SLOT_MS: u64 = 400;
+
+#[derive(Parser)]
+#[command(name = "meteor-route-keeper", about = "Run a MeteorRoute vault's daily crank unattended")]
+struct Args {
+    #[arg(long, env = "METEOR_ROUTE_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    #[arg(long, env = "METEOR_ROUTE_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    #[arg(long, env = "METEOR_ROUTE_VAULT_SEED")]
+    vault_seed: String,
+    #[arg(long, env = "METEOR_ROUTE_QUOTE_MINT")]
+    quote_mint: Pubkey,
+    /// JSON array of { "stream", "investor", "index" }; re-read at the start of every day
+    #[arg(long, env = "METEOR_ROUTE_INVESTORS")]
+    investors: PathBuf,
+    /// Creator quote token account receiving the day's remainder
+    #[arg(long, env = "METEOR_ROUTE_CREATOR_QUOTE_ATA")]
+    creator_quote_ata: Pubkey,
+    /// Creator wallet, to create a missing creator quote ATA (policy.fund_missing_creator_ata)
+    #[arg(long, env = "METEOR_ROUTE_CREATOR_WALLET")]
+    creator_wallet: Option<Pubkey>,
+    /// State account forwarded to the post-finalize hook (policy.hook_program)
+    #[arg(long, env = "METEOR_ROUTE_HOOK_STATE")]
+    hook_state: Option<Pubkey>,
+    /// Locker program the stream accounts belong to (default: Streamflow)
+    #[arg(long)]
+    locker_program: Option<Pubkey>,
+    /// Investors per page
+    #[arg(long, default_value_t = 4)]
+    page_size: usize,
+    /// Pages sent per transaction; the program stops early when compute runs low
+    #[arg(long, default_value_t = 1)]
+    pages_per_call: usize,
+    /// Hold the crank lease while cranking
+    #[arg(long)]
+    lease: bool,
+    /// Force-finalize days that stall past the policy timeout
+    #[arg(long)]
+    force_finalize: bool,
+    /// Longest sleep between progress checks
+    #[arg(long, default_value_t = 60)]
+    poll_secs: u64,
+    /// Attempts per transaction before the cycle is abandoned and retried from chain state
+    #[arg(long, default_value_t = 5)]
+    max_retries: u32,
+    #[arg(long, default_value_t = 1_400_000)]
+    compute_units: u32,
+}
+
+struct Keeper {
+    args: Args,
+    vault: Vault,
+    submitter: Submitter,
+    /// Pages fixed for each day epoch, so a restarted day pages the same list
+    day_pages: HashMap<(u64, u16), Vec<PagePayload>>,
+}
+
+impl Keeper {
+    fn rpc(&self) -> &RpcClient {
+        &self.submitter.rpc
+    }
+
+    /// Latest slot and cluster time
+    fn clock(&self) -> Result<(u64, u64)> {
+        Ok(rpc::slot_and_time(self.rpc())?)
+    }
+
+    fn locker_program(&self) -> Pubkey {
+        self.args.locker_program.unwrap_or(STREAMFLOW_PROGRAM_ID)
+    }
+
+    /// Pages for the investors with something still locked
+    ///
+    /// Closed, canceled and fully vested streams pay nothing, so they are left out of the day. Pages
+    /// are held to the policy's max_investors_per_page and hashed for `day_epoch` of `generation`.
+    fn day_pages(&self, policy: &PolicyPda, day_epoch: u64, generation: u16) -> Result<Vec<PagePayload>> {
+        let all = investors::load(&self.args.investors).map_err(|err| anyhow!(err))?;
+        let pages = rpc::build_pages(
+            self.rpc(),
+            &self.vault,
+            &all,
+            day_epoch,
+            generation,
+            policy.page_size(self.args.page_size),
+            Some(self.locker_program()),
+        )?;
+        let live: usize = pages.iter().map(|p| p.page.investors.len()).sum();
+        eprintln!("{} of {} investors have locked tokens", live, all.len());
+        Ok(pages)
+    }
+
+    fn preamble(&self) -> Vec<Instruction> {
+        let mut ixs = vec![ComputeBudgetInstruction::set_compute_unit_limit(self.args.compute_units)];
+        if self.args.lease {
+            ixs.push(self.vault.acquire_crank_lease(self.submitter.payer.pubkey()));
+        }
+        ixs
+    }
+
+    fn progress(&self) -> Result<ProgressPda> {
+        Ok(rpc::fetch(self.rpc(), &self.vault.progress())?)
+    }
+
+    /// One keeper cycle; returns how long to sleep before the next
+    fn cycle(&mut self) -> Result<Duration> {
+        let state = rpc::vault_state(self.rpc(), &self.vault)?;
+        let policy = &state.policy;
+        let Some(progress) = state.progress.as_ref() else {
+            bail!("progress PDA {} is not initialized", self.vault.progress());
+        };
+        if progress.creator_owed > 0 {
+            self.settle_creator_payout(progress.creator_owed)?;
+        }
+        if policy.paused {
+            // The crank fails while paused; keep polling until the authority resumes it
+            return Ok(Duration::from_secs(self.args.poll_secs));
+        }
+        let (slot, now) = self.clock()?;
+        let day_gate = policy.day_gate();
+        let new_day = progress.is_new_day_under(day_gate, now, slot);
+
+        if !new_day && progress.day_finalized_flag {
+            let wait = match day_gate {
+                // Sleep until the later of the next UTC day and the 24h gate
+                DayGate::Timestamp { grace_secs } => {
+                    let next_epoch = (progress.day_epoch + 1) * SECONDS_PER_DAY;
+                    let gate = progress.last_distribution_ts + SECONDS_PER_DAY.saturating_sub(grace_secs);
+                    next_epoch.max(gate).saturating_sub(now)
+                }
+                // Sleep until the next aligned day or tranche boundary
+                DayGate::Aligned { .. } => day_gate.epoch_start(progress.day_epoch + 1).saturating_sub(now),
+                // Sleep for the slots left at the nominal slot time
+                DayGate::Slots(gate_slots) => {
+                    let gate = progress.day_started_slot + gate_slots;
+                    gate.saturating_sub(slot) * NOMINAL_SLOT_MS / 1_000
+                }
+            };
+            return Ok(Duration::from_secs(wait.max(1).min(self.args.poll_secs)));
+        }
+        if new_day && !progress.can_start_new_day_under(day_gate, now) {
+            return Ok(Duration::from_secs(self.args.poll_secs));
+        }
+
+        if !new_day && self.args.force_finalize && progress.is_stalled(now, policy.force_finalize_after_secs) {
+            let creator_wallet = self.creator_wallet()?;
+            let crank = self.crank_accounts(&state, creator_wallet, progress.day_epoch)?;
+            let ix = self.vault.force_finalize_day(
+                self.submitter.payer.pubkey(),
+                self.args.creator_quote_ata,
+                crank.token_program,
+                &crank.options,
+            );
+            let sig = self.submitter.submit(&[ix])?;
+            eprintln!("day {} force-finalized: {}", progress.day_epoch, sig);
+            return Ok(Duration::from_secs(1));
+        }
+
+        let day_epoch = crank_day_epoch(progress, day_gate, now, slot);
+        let day = (day_epoch, progress.generation);
+        if !self.day_pages.contains_key(&day) {
+            let pages = self.day_pages(policy, day_epoch, progress.generation)?;
+            self.day_pages.clear();
+            self.day_pages.insert(day, pages);
+        }
+        // Commit the day's counts before the snapshot completes, when this keeper is the authority
+        if new_day || (!progress.snapshot_complete && progress.committed_investor_count == 0) {
+            self.commit_day_counts(policy, &self.day_pages[&day])?;
+        }
+        let creator_wallet = self.creator_wallet()?;
+        let crank = self.crank_accounts(&state, creator_wallet, day_epoch)?;
+        let cursor = next_page_index(progress, day_gate, now, slot);
+        self.crank_day(&crank, progress, new_day, cursor, day_epoch, policy.unordered_pages)?;
+        Ok(Duration::from_secs(1))
+    }
+
+    /// Commit the day's page and investor counts
+    ///
+    /// Only the policy authority may commit them; a keeper running under another key cranks the
+    /// day uncommitted unless the authority has run `set-expected-pages` for it.
+    fn commit_day_counts(&self, policy: &PolicyPda, pages: &[PagePayload]) -> Result<()> {
+        let investors: usize = pages.iter().map(|p| p.page.investors.len()).sum();
+        let authority = self.submitter.payer.pubkey();
+        if investors == 0 || policy.authority != authority {
+            return Ok(());
+        }
+        let ix = self.vault.set_expected_pages(authority, pages.len() as u64, investors as u32);
+        let sig = self.submitter.submit(&[ix])?;
+        eprintln!("committed {} pages / {} investors: {}", pages.len(), investors, sig);
+        Ok(())
+    }
+
+    /// Owner of the creator quote account; a closed account has none, so `--creator-wallet` is used
+    /// to create it, and without that finalize defers its payout
+    fn creator_wallet(&self) -> Result<Pubkey> {
+        let account: Option<TokenAccount> = rpc::fetch_optional(self.rpc(), &self.args.creator_quote_ata)?;
+        Ok(account
+            .map(|account| account.owner)
+            .or(self.args.creator_wallet)
+            .unwrap_or_default())
+    }
+
+    /// Pay deferred creator remainders once the creator quote account can receive them again
+    fn settle_creator_payout(&self, owed: u64) -> Result<()> {
+        let account: Option<TokenAccount> = rpc::fetch_optional(self.rpc(), &self.args.creator_quote_ata)?;
+        if account.is_none_or(|account| account.is_frozen()) {
+            return Ok(());
+        }
+        let token_program = rpc::owner(self.rpc(), &self.vault.quote_mint)?;
+        let ix = self.vault.settle_creator_payout(
+            self.submitter.payer.pubkey(),
+            self.args.creator_quote_ata,
+            token_program,
+        );
+        let sig = self.submitter.submit(&[ix])?;
+        eprintln!("settled {} owed to creator: {}", owed, sig);
+        Ok(())
+    }
+
+    /// Crank accounts for `day_epoch`, whose creator stream finalize opens when the policy vests
+    fn crank_accounts(&self, state: &rpc::VaultState, creator_wallet: Pubkey, day_epoch: u64) -> Result<CrankAccounts> {
+        let params = CrankParams {
+            crank_caller: self.submitter.payer.pubkey(),
+            creator_quote_ata: self.args.creator_quote_ata,
+            locker_program: Some(self.locker_program()),
+            creator_wallet,
+        };
+        let mut crank = rpc::crank_accounts(self.rpc(), &self.vault, state, &params)?;
+        crank.options.creator_stream_day = (state.policy.creator_vesting_secs > 0).then_some(day_epoch);
+        crank.options.hook_state = self.args.hook_state;
+        Ok(crank)
+    }
+
+    /// Crank every remaining page of the day, re-reading the cursor after each confirmed page
+    fn crank_day(
+        &self,
+        crank: &CrankAccounts,
+        progress: &ProgressPda,
+        new_day: bool,
+        mut cursor: u64,
+        day_epoch: u64,
+        unordered: bool,
+    ) -> Result<()> {
+        let caller = self.submitter.payer.pubkey();
+        let pages = &self.day_pages[&(day_epoch, progress.generation)];
+        let page_size = self.args.page_size.max(1);
+
+        if new_day || !progress.snapshot_complete {
+            // Pages are weighed against the snapshot, which must cover exactly the paged investors
+            // in page order
+            let investors: Vec<_> = pages.iter().flat_map(|p| p.page.investors.iter().cloned()).collect();
+            let done = if new_day { 0 } else { progress.snapshot_investor_count as usize };
+            let pending = investors.get(done..).unwrap_or_default();
+            let chunks: Vec<_> = pending.chunks(page_size).collect();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let mut ixs = self.preamble();
+                let is_final_chunk = i + 1 == chunks.len();
+                ixs.push(self.vault.snapshot_locked(caller, chunk.to_vec(), is_final_chunk, crank.options.weight_overrides));
+                let sig = self.submitter.submit(&ixs)?;
+                eprintln!("day {}: snapshot chunk {}/{}: {}", day_epoch, i + 1, chunks.len(), sig);
+            }
+        }
+
+        // Nothing locked in the snapshot: skip every page, one empty final call pays the creator
+        let mut progress = self.progress()?;
+        if progress.zero_locked_day() && progress.first_page_pending() {
+            eprintln!("day {}: snapshot has nothing locked", day_epoch);
+            cursor = u64::MAX;
+        }
+
+        loop {
+            let mut ixs = self.preamble();
+            // Unordered pages may also be completed by other keepers; the day finalizes once the
+            // last of them lands, so no page is sent as the final one
+            let batch: Vec<_> = pages
+                .iter()
+                .filter(|p| p.page.page_index >= cursor && !(unordered && progress.page_processed(p.page.page_index)))
+                .take(self.args.pages_per_call.max(1))
+                .collect();
+            let label = match (batch.first(), batch.last()) {
+                (Some(first), Some(last)) => {
+                    let is_final = last.is_final && !unordered;
+                    ixs.push(self.vault.distribute_fees(
+                        crank,
+                        batch.iter().map(|p| p.page.clone()).collect(),
+                        is_final,
+                        batch.iter().flat_map(|p| p.remaining_accounts.clone()).collect(),
+                    ));
+                    let range = if batch.len() == 1 {
+                        format!("page {}", first.page.page_index)
+                    } else {
+                        format!("pages {}-{}", first.page.page_index, last.page.page_index)
+                    };
+                    format!("{}{}", range, if is_final { " (final)" } else { "" })
+                }
+                _ => {
+                    // Every page is paid: one final call closes the day
+                    ixs.push(self.vault.distribute_fees(crank, vec![], true, vec![]));
+                    "final".to_string()
+                }
+            };
+            let sig = self.submitter.submit(&ixs)?;
+            let result = match rpc::distribute_fees_result(self.rpc(), &sig)? {
+                Some(result) => result,
+                // No return data (e.g. the transaction is not yet visible): read the progress PDA instead
+                None => {
+                    let progress = self.progress()?;
+                    DistributeFeesResult {
+                        pagination_cursor: progress.pagination_cursor,
+                        page_investor_cursor: progress.page_investor_cursor,
+                        pages_processed_today: progress.pages_processed_today,
+                        day_finalized: progress.day_finalized_flag,
+                        ..DistributeFeesResult::default()
+                    }
+                }
+            };
+            eprintln!(
+                "day {}: {}: {} investors, {} distributed: {}",
+                day_epoch, label, result.investors_processed, result.distributed, sig
+            );
+
+            if result.page_investor_cursor > 0 {
+                // Compute ran out mid-page: the cursor stays put and the same page goes out again
+                eprintln!(
+                    "day {}: page {} suspended before investor {}",
+                    day_epoch, result.pagination_cursor, result.page_investor_cursor
+                );
+            }
+            if result.day_finalized {
+                eprintln!("day {} finalized over {} pages", day_epoch, result.pages_processed_today);
+                return Ok(());
+            }
+            cursor = result.pagination_cursor;
+            if unordered {
+                progress = self.progress()?;
+            }
+        }
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let keypair_path = expand_home(&args.keypair);
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("reading keypair {}: {}", keypair_path.display(), err))?;
+    let mut keeper = Keeper {
+        vault: Vault::new(args.vault_seed.clone(), args.quote_mint),
+        submitter: Submitter {
+            rpc: RpcClient::new_with_commitment(args.url.clone(), CommitmentConfig::confirmed()),
+            payer,
+            max_retries: args.max_retries.max(1),
+        },
+        args,
+        day_pages: HashMap::new(),
+    };
+    eprintln!(
+        "keeper started: vault {} (policy {}), cranker {}",
+        keeper.vault.vault_seed,
+        keeper.vault.policy(),
+        keeper.submitter.payer.pubkey()
+    );
+
+    loop {
+        let wait = keeper.cycle().unwrap_or_else(|err| {
+            // Chain state is the source of truth: the next cycle resumes from the cursor
+            eprintln!("cycle failed: {:#}", err);
+            Duration::from_secs(keeper.args.poll_secs)
+        });
+        sleep(wait);
+    }
+}
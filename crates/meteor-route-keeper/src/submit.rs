@@ -0,0 +1,71 @@
+//! Transaction submission with retries and confirmation tracking
+use std::{thread::sleep, time::Duration};
+
+use anyhow::{bail, Result};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+const STATUS_POLL: Duration = Duration::from_millis(500);
+
+pub struct Submitter {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    pub max_retries: u32,
+}
+
+enum Outcome {
+    Confirmed,
+    /// The transaction executed and failed; resending the same instructions would fail again
+    Failed(String),
+    /// The blockhash expired before the transaction landed
+    Expired,
+}
+
+impl Submitter {
+    /// Send, then poll until the transaction is confirmed, fails, or its blockhash expires
+    ///
+    /// Expired or unsent transactions are re-signed with a fresh blockhash, with linear backoff,
+    /// up to `max_retries` times.
+    pub fn submit(&self, ixs: &[Instruction]) -> Result<Signature> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = self.send_once(ixs);
+            match result {
+                Ok((sig, Outcome::Confirmed)) => return Ok(sig),
+                Ok((sig, Outcome::Failed(err))) => bail!("transaction {} failed: {}", sig, err),
+                Ok((sig, Outcome::Expired)) => eprintln!("transaction {} expired (attempt {})", sig, attempt),
+                Err(err) => eprintln!("send failed (attempt {}): {}", attempt, err),
+            }
+            if attempt >= self.max_retries {
+                bail!("giving up after {} attempts", attempt);
+            }
+            sleep(Duration::from_secs(2 * attempt as u64));
+        }
+    }
+
+    fn send_once(&self, ixs: &[Instruction]) -> Result<(Signature, Outcome)> {
+        let commitment = self.rpc.commitment();
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&self.payer.pubkey()), &[&self.payer], blockhash);
+        let sig = self.rpc.send_transaction(&tx)?;
+        loop {
+            sleep(STATUS_POLL);
+            if let Some(status) = self.rpc.get_signature_statuses(&[sig])?.value[0].as_ref() {
+                if let Some(err) = status.err.as_ref() {
+                    return Ok((sig, Outcome::Failed(err.to_string())));
+                }
+                if status.satisfies_commitment(commitment) {
+                    return Ok((sig, Outcome::Confirmed));
+                }
+            } else if !self.rpc.is_blockhash_valid(&blockhash, commitment)? {
+                return Ok((sig, Outcome::Expired));
+            }
+        }
+    }
+}
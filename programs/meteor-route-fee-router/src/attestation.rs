@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use crate::error::FeeRouterError;
+
+/// Where an attestation program (KYC registry, compliance attester) keeps an investor's attestation
+///
+/// Attestations are PDAs `[seed_prefix, investor]` of the attestation program, so the crank can't
+/// pass another account in place of an investor's, and an investor without one is provably
+/// unattested rather than just missing from the transaction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct AttestationLayout {
+    /// First PDA seed; trailing zero bytes are not part of it
+    pub seed_prefix: [u8; 16],
+    /// Leading account discriminator (all zero = not checked)
+    pub discriminator: [u8; 8],
+    /// Offset of the expiry (i64 unix seconds, little-endian); None when attestations don't expire
+    pub expiry_offset: Option<u32>,
+}
+
+impl AttestationLayout {
+    pub const LEN: usize = 16 + 8 + 1 + 4;
+
+    /// `seed_prefix` without its zero padding
+    pub fn seed(&self) -> &[u8] {
+        let len = self.seed_prefix.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        &self.seed_prefix[..len]
+    }
+
+    /// Whether the layout describes a usable attestation: a seed, and an expiry clear of the discriminator
+    pub fn is_valid(&self) -> bool {
+        let header = if self.discriminator == [0u8; 8] { 0 } else { 8 };
+        !self.seed().is_empty() && !matches!(self.expiry_offset, Some(offset) if offset < header)
+    }
+}
+
+/// The policy's attestation requirement (see `PolicyPda::attestation_gate`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AttestationGate {
+    pub program_id: Pubkey,
+    pub layout: AttestationLayout,
+}
+
+impl AttestationGate {
+    /// `investor`'s attestation PDA
+    pub fn address(&self, investor: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[self.layout.seed(), investor.as_ref()], &self.program_id).0
+    }
+
+    /// Whether `account_info`, which must be `investor`'s attestation PDA, holds a live attestation
+    ///
+    /// A missing, foreign-owned, mistyped or expired account is not an error; the investor is
+    /// just unattested.
+    pub fn is_attested(&self, account_info: &AccountInfo, investor: &Pubkey, now: u64) -> Result<bool> {
+        require_keys_eq!(account_info.key(), self.address(investor), FeeRouterError::AttestationMismatch);
+        if account_info.data_is_empty() || *account_info.owner != self.program_id {
+            return Ok(false);
+        }
+        let data = account_info.try_borrow_data()?;
+        if self.layout.discriminator != [0u8; 8] && (data.len() < 8 || data[..8] != self.layout.discriminator) {
+            return Ok(false);
+        }
+        Ok(match self.layout.expiry_offset {
+            Some(offset) => {
+                let offset = offset as usize;
+                data.len() >= offset + 8
+                    && i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap()) > now as i64
+            }
+            None => true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISCRIMINATOR: [u8; 8] = [9, 8, 7, 6, 5, 4, 3, 2];
+
+    fn gate(expiry_offset: Option<u32>) -> AttestationGate {
+        let mut seed_prefix = [0u8; 16];
+        seed_prefix[..3].copy_from_slice(b"kyc");
+        AttestationGate {
+            program_id: Pubkey::new_unique(),
+            layout: AttestationLayout { seed_prefix, discriminator: DISCRIMINATOR, expiry_offset },
+        }
+    }
+
+    fn attestation_data(expiry: i64) -> Vec<u8> {
+        let mut data = vec![0u8; 48];
+        data[..8].copy_from_slice(&DISCRIMINATOR);
+        data[40..48].copy_from_slice(&expiry.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_layout_validity() {
+        let layout = gate(Some(40)).layout;
+        assert_eq!(layout.seed(), b"kyc");
+        assert!(layout.is_valid());
+        assert!(!AttestationLayout { expiry_offset: Some(4), ..layout }.is_valid());
+        assert!(AttestationLayout { expiry_offset: Some(4), discriminator: [0; 8], ..layout }.is_valid());
+        assert!(!AttestationLayout::default().is_valid());
+    }
+
+    #[test]
+    fn test_is_attested() {
+        let gate = gate(Some(40));
+        let investor = Pubkey::new_unique();
+        let key = gate.address(&investor);
+        let owner = gate.program_id;
+        let check = |data: &mut Vec<u8>, owner: &Pubkey, key: &Pubkey, now: u64| {
+            let mut lamports = 1u64;
+            let info = AccountInfo::new(key, false, false, &mut lamports, data, owner, false, 0);
+            gate.is_attested(&info, &investor, now)
+        };
+
+        assert!(check(&mut attestation_data(1_000), &owner, &key, 999).unwrap());
+        // Expired, foreign-owned, mistyped and missing attestations just don't count
+        assert!(!check(&mut attestation_data(1_000), &owner, &key, 1_000).unwrap());
+        assert!(!check(&mut attestation_data(1_000), &Pubkey::new_unique(), &key, 0).unwrap());
+        let mut mistyped = attestation_data(1_000);
+        mistyped[0] = 0;
+        assert!(!check(&mut mistyped, &owner, &key, 0).unwrap());
+        assert!(!check(&mut Vec::new(), &owner, &key, 0).unwrap());
+        // Another account in place of the investor's PDA is rejected
+        assert!(check(&mut attestation_data(1_000), &owner, &Pubkey::new_unique(), 0).is_err());
+    }
+}
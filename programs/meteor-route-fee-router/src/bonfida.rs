@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use crate::{
+    error::FeeRouterError,
+    locker::{LockedAmountSource, StreamIgnoredReason, StreamLocked},
+    InvestorData,
+};
+
+/// Bonfida token-vesting program ID (mainnet)
+pub const BONFIDA_VESTING_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("CChTq6PthWU82YZkbveA3WDf7s97BWhBK4Vx9bmsT743");
+
+/// Size of the vesting account header (destination + mint + is_initialized)
+pub const BONFIDA_HEADER_LEN: usize = 65;
+
+/// Size of one (release_time, amount) schedule entry
+pub const BONFIDA_SCHEDULE_LEN: usize = 16;
+
+/// Vesting account header
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct VestingScheduleHeader {
+    /// Token account the vested tokens are released to
+    pub destination_address: Pubkey,
+    pub mint_address: Pubkey,
+    pub is_initialized: bool,
+}
+
+/// One release in the schedule; `amount` is zeroed once it has been unlocked
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
+pub struct VestingSchedule {
+    pub release_time: u64,
+    pub amount: u64,
+}
+
+/// Bonfida vesting account: a header followed by packed schedule entries
+///
+/// The account has no discriminator and no length prefix; the number of schedules
+/// follows from the account size.
+#[derive(Clone, Debug)]
+pub struct BonfidaVestingAccount {
+    pub header: VestingScheduleHeader,
+    pub schedules: Vec<VestingSchedule>,
+}
+
+impl BonfidaVestingAccount {
+    /// Amount still locked at `now`: every release scheduled strictly after `now`
+    ///
+    /// Releases that are due but not yet unlocked count as vested, so the result
+    /// does not depend on when the recipient calls `unlock`.
+    pub fn locked_at(&self, now: u64) -> Result<u64> {
+        self.schedules
+            .iter()
+            .filter(|schedule| schedule.release_time > now)
+            .try_fold(0u64, |locked, schedule| locked.checked_add(schedule.amount))
+            .ok_or(FeeRouterError::Overflow.into())
+    }
+}
+
+/// Parse a Bonfida vesting account
+pub fn parse_bonfida_account(data: &[u8]) -> Result<BonfidaVestingAccount> {
+    require!(
+        data.len() >= BONFIDA_HEADER_LEN
+            && (data.len() - BONFIDA_HEADER_LEN).is_multiple_of(BONFIDA_SCHEDULE_LEN),
+        FeeRouterError::StreamDeserializeFailed
+    );
+    let header = VestingScheduleHeader::deserialize(&mut &data[..BONFIDA_HEADER_LEN])
+        .map_err(|_| FeeRouterError::StreamDeserializeFailed)?;
+    let schedules = data[BONFIDA_HEADER_LEN..]
+        .chunks_exact(BONFIDA_SCHEDULE_LEN)
+        .map(|mut chunk| VestingSchedule::deserialize(&mut chunk))
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|_| FeeRouterError::StreamDeserializeFailed)?;
+    Ok(BonfidaVestingAccount { header, schedules })
+}
+
+/// Validate that the vesting account releases to the investor
+///
+/// Bonfida releases to a token account, so the destination must be the investor's ATA
+/// for the vested mint.
+pub fn validate_vesting_for_investor(
+    vesting: &BonfidaVestingAccount,
+    expected_investor: &Pubkey,
+) -> Result<()> {
+    require!(vesting.header.is_initialized, FeeRouterError::StreamDeserializeFailed);
+    let expected_destination = anchor_spl::associated_token::get_associated_token_address(
+        expected_investor,
+        &vesting.header.mint_address,
+    );
+    require_keys_eq!(
+        vesting.header.destination_address,
+        expected_destination,
+        FeeRouterError::StreamRecipientMismatch
+    );
+    Ok(())
+}
+
+/// Bonfida token-vesting accounts as a locked-amount source
+pub struct BonfidaSource {
+    pub program_id: Pubkey,
+    /// Local-testing bypass from `LockerTestConfig`; skips the destination check
+    pub relax_checks: bool,
+}
+
+impl LockedAmountSource for BonfidaSource {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn read_locked(
+        &self,
+        account_info: &AccountInfo,
+        investor_data: &InvestorData,
+        at_ts: u64,
+    ) -> Result<StreamLocked> {
+        require_keys_eq!(
+            account_info.key(),
+            investor_data.stream,
+            FeeRouterError::StreamKeyMismatch
+        );
+
+        if account_info.data_is_empty() {
+            return Ok(StreamLocked::Ignored(StreamIgnoredReason::AccountClosed));
+        }
+
+        require_keys_eq!(
+            *account_info.owner,
+            self.program_id,
+            FeeRouterError::InvalidLockerProgram
+        );
+
+        let vesting = parse_bonfida_account(&account_info.try_borrow_data()?)?;
+        if !self.relax_checks {
+            validate_vesting_for_investor(&vesting, &investor_data.investor)?;
+        }
+
+        Ok(StreamLocked::Locked(vesting.locked_at(at_ts)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_data(schedules: &[(u64, u64)]) -> Vec<u8> {
+        let mut data = vec![0u8; BONFIDA_HEADER_LEN];
+        data[64] = 1; // is_initialized
+        for (release_time, amount) in schedules {
+            data.extend_from_slice(&release_time.to_le_bytes());
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_schedules() {
+        let vesting = parse_bonfida_account(&account_data(&[(100, 500), (200, 500)])).unwrap();
+        assert!(vesting.header.is_initialized);
+        assert_eq!(vesting.schedules.len(), 2);
+        assert_eq!(vesting.schedules[1].release_time, 200);
+
+        // Trailing bytes that don't form a full schedule entry are rejected
+        let mut data = account_data(&[(100, 500)]);
+        data.push(0);
+        assert!(parse_bonfida_account(&data).is_err());
+        assert!(parse_bonfida_account(&data[..10]).is_err());
+    }
+
+    #[test]
+    fn test_locked_at() {
+        let vesting = parse_bonfida_account(&account_data(&[(100, 300), (200, 300), (300, 400)])).unwrap();
+        assert_eq!(vesting.locked_at(50).unwrap(), 1_000);
+        assert_eq!(vesting.locked_at(100).unwrap(), 700);
+        assert_eq!(vesting.locked_at(250).unwrap(), 400);
+        assert_eq!(vesting.locked_at(300).unwrap(), 0);
+
+        // Unlocked entries are zeroed on-chain and don't change the locked amount
+        let unlocked = parse_bonfida_account(&account_data(&[(100, 0), (200, 300), (300, 400)])).unwrap();
+        assert_eq!(unlocked.locked_at(150).unwrap(), vesting.locked_at(150).unwrap());
+    }
+}
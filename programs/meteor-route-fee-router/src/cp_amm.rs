@@ -1,12 +1,32 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use cp_amm::{
+    params::swap::TradeDirection,
+    state::fee::FeeMode,
+    u128x128_math::{mul_div_u256, Rounding},
+};
+use ruint::aliases::U256;
 pub use cp_amm;
 pub use cp_amm::const_pda;
 pub use cp_amm::constants;
 pub use cp_amm::cpi;
 
-/// CP-AMM program ID (local fork for testing)
+/// CP-AMM program ID the crate was built against (local fork for testing)
+///
+/// Vaults store their own expected program ID in `PolicyPda.cp_amm_program_id`; this is only the
+/// program whose account layouts and instruction set the router is compiled for.
 pub const CP_AMM_PROGRAM_ID: Pubkey = cp_amm::ID;
 
+/// Derive the CP-AMM pool authority PDA for `program_id`
+pub fn derive_pool_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[constants::seeds::POOL_AUTHORITY_PREFIX], program_id).0
+}
+
+/// Derive the CP-AMM event authority PDA for `program_id`
+pub fn derive_event_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program_id).0
+}
+
 /// Treasury PDA seeds helper
 pub fn treasury_seeds<'a>(vault_seed: &'a str, quote_mint: &'a Pubkey) -> [&'a [u8]; 3] {
     [vault_seed.as_bytes(), b"treasury", quote_mint.as_ref()]
@@ -23,11 +43,250 @@ pub fn derive_treasury_pda(vault_seed: &str, quote_mint: &Pubkey, program_id: &P
 // Re-export CP-AMM types for convenience
 pub use cp_amm::state::{Pool, Position};
 
-/// Validate quote-only position based on tick range and pool state
+/// Load a zero-copy CP-AMM account (pool or position) passed as an unchecked account
+///
+/// Same checks as `AccountLoader::load` (owner, discriminator and size), with the owner being the
+/// vault's configured CP-AMM program rather than the one compiled in.
+pub fn load_account<'a, T: anchor_lang::ZeroCopy>(
+    info: &'a AccountInfo,
+    program_id: &Pubkey,
+) -> Result<std::cell::Ref<'a, T>> {
+    require_keys_eq!(*info.owner, *program_id, crate::error::FeeRouterError::InvalidCpAmmProgram);
+    let data = info.try_borrow_data()?;
+    let disc_len = T::DISCRIMINATOR.len();
+    let end = disc_len + std::mem::size_of::<T>();
+    require!(
+        data.len() >= end && &data[..disc_len] == T::DISCRIMINATOR,
+        crate::error::FeeRouterError::PoolAccountMismatch
+    );
+    Ok(std::cell::Ref::map(data, |data| bytemuck::from_bytes(&data[disc_len..end])))
+}
+
+/// Liquidity that `amount` quote tokens add to a CP-AMM pool with no base token, and the quote
+/// the pool takes for it (before any transfer fee)
+///
+/// Pool liquidity spans the whole price range, so quote alone only buys liquidity while the price
+/// sits at the quote side's end of the range (the min price for quote A, the max price for
+/// quote B). At any other price the deposit would also take base tokens, which the router never
+/// holds, and this returns None.
+pub fn quote_only_liquidity(pool: &Pool, quote_is_a: bool, amount: u64) -> Option<(u128, u64)> {
+    let amount = U256::from(amount);
+    let liquidity = if quote_is_a {
+        // Δa = L (√P_max - √P) / (√P √P_max)
+        let delta = pool.sqrt_max_price.checked_sub(pool.sqrt_price)?;
+        let sqrt_product = U256::from(pool.sqrt_price) * U256::from(pool.sqrt_max_price);
+        mul_div_u256(amount, sqrt_product, U256::from(delta), Rounding::Down)?
+    } else {
+        // Δb = L (√P - √P_min) / 2^128
+        let delta = pool.sqrt_price.checked_sub(pool.sqrt_min_price)?;
+        mul_div_u256(amount, U256::from(1u8) << 128, U256::from(delta), Rounding::Down)?
+    };
+    let liquidity = u128::try_from(liquidity).ok().filter(|liquidity| *liquidity > 0)?;
+
+    let amounts = pool.get_amounts_for_modify_liquidity(liquidity, Rounding::Up).ok()?;
+    let (quote, base) = if quote_is_a {
+        (amounts.token_a_amount, amounts.token_b_amount)
+    } else {
+        (amounts.token_b_amount, amounts.token_a_amount)
+    };
+    (base == 0 && quote > 0).then_some((liquidity, quote))
+}
+
+/// Base tokens `amount` quote buys at a Q64.64 sqrt price, ignoring fees and price impact
+///
+/// The pool price is token B per token A, so quote B buys `amount / P` and quote A buys `amount * P`.
+pub fn spot_base_out(sqrt_price: u128, quote_is_a: bool, amount: u64) -> Option<u64> {
+    let price_x128 = U256::from(sqrt_price) * U256::from(sqrt_price);
+    let out = if quote_is_a {
+        mul_div_u256(U256::from(amount), price_x128, U256::from(1u8) << 128, Rounding::Down)?
+    } else {
+        mul_div_u256(U256::from(amount), U256::from(1u8) << 128, price_x128, Rounding::Down)?
+    };
+    u64::try_from(out).ok()
+}
+
+/// Base tokens a quote→base swap of `amount_in` returns from the pool, fees included
+///
+/// Same calculation as the pool's swap, on a copy of the pool. None when the swap fails or when
+/// the pool would charge the swap fee in the base token: that fee would accrue to the honorary
+/// position too, and its next claim would fail with `BaseFeeDetected`.
+pub fn simulate_quote_to_base(
+    pool: &Pool,
+    quote_is_a: bool,
+    amount_in: u64,
+    current_timestamp: u64,
+    current_point: u64,
+) -> Option<u64> {
+    let direction = if quote_is_a { TradeDirection::AtoB } else { TradeDirection::BtoA };
+    let fee_mode = FeeMode::get_fee_mode(pool.collect_fee_mode, direction, false).ok()?;
+    if fee_mode.fees_on_token_a != quote_is_a {
+        return None;
+    }
+    let mut pool = *pool;
+    pool.update_pre_swap(current_timestamp).ok()?;
+    let result = pool.get_swap_result(amount_in, &fee_mode, direction, current_point).ok()?;
+    Some(result.output_amount)
+}
+
+/// Require the pool to credit every swap fee in the quote token
+///
+/// Fees are charged in the token `collect_fee_mode` picks for each trade direction; the fee
+/// scheduler and dynamic fee only change the amount. A both-token pool pays fees in the output
+/// token, so one of the two directions would always accrue base fees to the honorary position.
+pub fn validate_quote_only_fee_mode(pool: &Pool, quote_is_a: bool) -> Result<()> {
+    for direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+        let fee_mode = FeeMode::get_fee_mode(pool.collect_fee_mode, direction, false)
+            .map_err(|_| error!(crate::error::FeeRouterError::PoolFeeModeNotQuoteOnly))?;
+        require!(
+            fee_mode.fees_on_token_a == quote_is_a,
+            crate::error::FeeRouterError::PoolFeeModeNotQuoteOnly
+        );
+    }
+    Ok(())
+}
+
+/// Probe swap, in input-token base units, used by `simulate_quote_only_fee_accrual`
+pub const FEE_ACCRUAL_PROBE_AMOUNT: u64 = 1_000_000;
+
+/// Swap `amount_in` through a copy of the live pool in each direction and require the LP fee it
+/// accrues to positions to land only in the quote token's fee-per-liquidity
+///
+/// Runs against the pool's current price, liquidity and fee scheduler state. Returns false when a
+/// direction can't be simulated (no active liquidity, or the probe runs past the price range), so
+/// nothing was checked; a base fee accrual fails with `BaseFeeDetected`.
+pub fn simulate_quote_only_fee_accrual(
+    pool: &Pool,
+    quote_is_a: bool,
+    amount_in: u64,
+    current_timestamp: u64,
+    current_point: u64,
+) -> Result<bool> {
+    if pool.liquidity == 0 {
+        return Ok(false);
+    }
+    for direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+        let Ok(fee_mode) = FeeMode::get_fee_mode(pool.collect_fee_mode, direction, false) else {
+            return Ok(false);
+        };
+        let mut pool = *pool;
+        let base_fee_before = if quote_is_a { pool.fee_b_per_liquidity } else { pool.fee_a_per_liquidity };
+        let simulated = pool.update_pre_swap(current_timestamp).is_ok()
+            && pool
+                .get_swap_result(amount_in, &fee_mode, direction, current_point)
+                .and_then(|result| pool.apply_swap_result(&result, &fee_mode, current_timestamp))
+                .is_ok();
+        if !simulated {
+            return Ok(false);
+        }
+        let base_fee_after = if quote_is_a { pool.fee_b_per_liquidity } else { pool.fee_a_per_liquidity };
+        require!(base_fee_after == base_fee_before, crate::error::FeeRouterError::BaseFeeDetected);
+    }
+    Ok(true)
+}
+
+/// Hash of the pool's fee configuration: collect-fee mode, base fee scheduler and dynamic-fee parameters
+///
+/// Leaves out the dynamic fee's volatility state, which moves with every swap.
+pub fn pool_fee_config_hash(pool: &Pool) -> [u8; 32] {
+    let base = &pool.pool_fees.base_fee;
+    let dynamic = &pool.pool_fees.dynamic_fee;
+    hashv(&[
+        &[pool.collect_fee_mode, base.fee_scheduler_mode, dynamic.initialized],
+        &base.cliff_fee_numerator.to_le_bytes(),
+        &base.number_of_period.to_le_bytes(),
+        &base.period_frequency.to_le_bytes(),
+        &base.reduction_factor.to_le_bytes(),
+        &dynamic.max_volatility_accumulator.to_le_bytes(),
+        &dynamic.variable_fee_control.to_le_bytes(),
+        &dynamic.bin_step.to_le_bytes(),
+        &dynamic.filter_period.to_le_bytes(),
+        &dynamic.decay_period.to_le_bytes(),
+        &dynamic.reduction_factor.to_le_bytes(),
+        &dynamic.bin_step_u128.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+// log_{sqrt(1.0001)}(2) in Q32.32, and the error margins of the resulting Q64.64 log
+// (constants from Orca's tick math, which share the 1.0001 tick base)
+const LOG_B_2_X32: i128 = 59_543_866_431_248;
+const LOG_B_P_ERR_MARGIN_LOWER_X64: i128 = 184_467_440_737_095_516;
+const LOG_B_P_ERR_MARGIN_UPPER_X64: i128 = 15_793_534_762_490_258_745;
+const LOG2_FRACTION_BITS: u32 = 14;
+
+/// Bounds `(low, high)` of the tick holding a Q64.64 sqrt price (`high - low <= 1`)
+///
+/// Integer-only log: the true tick is one of the two bounds, which is enough for a
+/// conservative quote-only check without a sqrt-price-from-tick table.
+pub fn tick_bounds_from_sqrt_price(sqrt_price_x64: u128) -> (i32, i32) {
+    let msb = 127 - sqrt_price_x64.leading_zeros();
+    let log2p_integer_x32 = (msb as i128 - 64) << 32;
+
+    // Normalize to [2^63, 2^64) and square repeatedly to extract fractional log2 bits
+    let mut r = if msb >= 63 {
+        sqrt_price_x64 >> (msb - 63)
+    } else {
+        sqrt_price_x64 << (63 - msb)
+    };
+    let mut bit: i128 = 1 << 63;
+    let mut log2p_fraction_x64: i128 = 0;
+    for _ in 0..LOG2_FRACTION_BITS {
+        r *= r;
+        let is_r_more_than_two = (r >> 127) as u32;
+        r >>= 63 + is_r_more_than_two;
+        log2p_fraction_x64 += bit * is_r_more_than_two as i128;
+        bit >>= 1;
+    }
+
+    let log2p_x32 = log2p_integer_x32 + (log2p_fraction_x64 >> 32);
+    let logbp_x64 = log2p_x32 * LOG_B_2_X32;
+    let tick_low = ((logbp_x64 - LOG_B_P_ERR_MARGIN_LOWER_X64) >> 64) as i32;
+    let tick_high = ((logbp_x64 + LOG_B_P_ERR_MARGIN_UPPER_X64) >> 64) as i32;
+    (tick_low, tick_high)
+}
+
+/// `(tick_lower, tick_upper)` that makes `initialize_honorary_position` derive the range itself
+///
+/// Never a valid range (`tick_lower < tick_upper`), so it can't be passed by accident.
+pub const AUTO_TICK_RANGE: (i32, i32) = (0, 0);
+
+/// Widest quote-only range at the pool's live price
+///
+/// Starts `buffer_ticks` clear of the current tick on the side `validate_quote_only_position`
+/// requires and runs to the pool's price bound. None when the price sits too close to that bound.
+pub fn quote_only_tick_range(pool: &Pool, quote_is_a: bool, buffer_ticks: u32) -> Option<(i32, i32)> {
+    quote_only_tick_range_at(pool.sqrt_price, pool.sqrt_min_price, pool.sqrt_max_price, quote_is_a, buffer_ticks)
+}
+
+fn quote_only_tick_range_at(
+    sqrt_price: u128,
+    sqrt_min_price: u128,
+    sqrt_max_price: u128,
+    quote_is_a: bool,
+    buffer_ticks: u32,
+) -> Option<(i32, i32)> {
+    if sqrt_price == 0 || sqrt_min_price == 0 || sqrt_max_price == 0 {
+        return None;
+    }
+    let (current_low, current_high) = tick_bounds_from_sqrt_price(sqrt_price);
+    let buffer = buffer_ticks as i64;
+    let (tick_lower, tick_upper) = if quote_is_a {
+        (tick_bounds_from_sqrt_price(sqrt_min_price).0 as i64, current_low as i64 - buffer - 1)
+    } else {
+        (current_high as i64 + buffer + 1, tick_bounds_from_sqrt_price(sqrt_max_price).1 as i64)
+    };
+    if tick_lower >= tick_upper {
+        return None;
+    }
+    Some((i32::try_from(tick_lower).ok()?, i32::try_from(tick_upper).ok()?))
+}
+
+/// Validate quote-only position based on tick range and the pool's live price
 /// 
-/// For CP-AMM pools, quote-only positions must be positioned to only collect fees in the quote token:
-/// - If quote is token_b, position must be above price (tick_lower > 0) - only A->B swaps generate fees
-/// - If quote is token_a, position must be below price (tick_upper < 0) - only B->A swaps generate fees
+/// For CP-AMM pools, quote-only positions must be positioned to only collect fees in the quote token.
+/// The current tick is derived from `pool.sqrt_price`, and the range must clear it by `buffer_ticks`:
+/// - If quote is token_b, position must be above price (tick_lower > current + buffer) - only A->B swaps generate fees
+/// - If quote is token_a, position must be below price (tick_upper < current - buffer) - only B->A swaps generate fees
 /// 
 /// This ensures the position only provides liquidity in one token and only collects fees in the quote token.
 pub fn validate_quote_only_position(
@@ -35,45 +294,203 @@ pub fn validate_quote_only_position(
     tick_lower: i32,
     tick_upper: i32,
     quote_mint: &Pubkey,
+    buffer_ticks: u32,
+) -> Result<()> {
+    validate_quote_only_range(
+        &pool.token_a_mint,
+        &pool.token_b_mint,
+        pool.sqrt_price,
+        tick_lower,
+        tick_upper,
+        quote_mint,
+        buffer_ticks,
+    )
+}
+
+fn validate_quote_only_range(
+    token_a_mint: &Pubkey,
+    token_b_mint: &Pubkey,
+    sqrt_price: u128,
+    tick_lower: i32,
+    tick_upper: i32,
+    quote_mint: &Pubkey,
+    buffer_ticks: u32,
 ) -> Result<()> {
     // Verify tick bounds are valid
     require!(
-        tick_lower < tick_upper,
+        tick_lower < tick_upper && sqrt_price > 0,
         crate::error::FeeRouterError::InvalidTickRange
     );
 
     // Determine which token is quote based on mint order
-    let is_token_a_quote = pool.token_a_mint == *quote_mint;
-    let is_token_b_quote = pool.token_b_mint == *quote_mint;
+    let is_token_a_quote = *token_a_mint == *quote_mint;
+    let is_token_b_quote = *token_b_mint == *quote_mint;
 
     require!(
         is_token_a_quote || is_token_b_quote,
         crate::error::FeeRouterError::InvalidQuoteMint
     );
 
-    // Quote-only validation for CP-AMM:
-    // - If token_b is quote: position must be above current price (tick_lower > 0)
+    // Quote-only validation for CP-AMM, using whichever tick bound is stricter for the side:
+    // - If token_b is quote: position must be above current price
     //   This means we only provide token_a liquidity, and only A->B swaps generate fees in B (quote)
-    // - If token_a is quote: position must be below current price (tick_upper < 0)
+    // - If token_a is quote: position must be below current price
     //   This means we only provide token_b liquidity, and only B->A swaps generate fees in A (quote)
+    let (current_low, current_high) = tick_bounds_from_sqrt_price(sqrt_price);
+    let buffer = buffer_ticks as i64;
     if is_token_b_quote {
         require!(
-            tick_lower > 0,
+            tick_lower as i64 > current_high as i64 + buffer,
             crate::error::FeeRouterError::PositionNotQuoteOnly
         );
     } else {
         require!(
-            tick_upper < 0,
+            (tick_upper as i64) < current_low as i64 - buffer,
             crate::error::FeeRouterError::PositionNotQuoteOnly
         );
     }
 
     msg!(
-        "Quote-only validation passed. Quote mint: {}, Range: [{}, {}]",
+        "Quote-only validation passed. Quote mint: {}, Range: [{}, {}], current tick: [{}, {}], buffer: {}",
         quote_mint,
         tick_lower,
-        tick_upper
+        tick_upper,
+        current_low,
+        current_high,
+        buffer_ticks
     );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const Q64: u128 = 1 << 64;
+
+    #[test]
+    fn test_tick_bounds_from_sqrt_price() {
+        // Price 1 => tick 0
+        let (low, high) = tick_bounds_from_sqrt_price(Q64);
+        assert!(low <= 0 && 0 <= high && high - low <= 1);
+
+        // Price 4 (sqrt 2) => tick = ln(4) / ln(1.0001) ~ 13_863
+        let (low, high) = tick_bounds_from_sqrt_price(2 * Q64);
+        assert!(low <= 13_863 && 13_863 <= high && high - low <= 1);
+
+        // Price 1/4 (sqrt 1/2) => tick ~ -13_864
+        let (low, high) = tick_bounds_from_sqrt_price(Q64 / 2);
+        assert!(low <= -13_864 && -13_864 <= high && high - low <= 1);
+    }
+
+    #[test]
+    fn test_quote_only_range_uses_live_price() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        // Current tick ~ 13_863: a range above zero is no longer necessarily above price
+        let sqrt_price = 2 * Q64;
+
+        assert!(validate_quote_only_range(&a, &b, sqrt_price, 100, 200, &b, 0).is_err());
+        assert!(validate_quote_only_range(&a, &b, sqrt_price, 13_900, 14_000, &b, 0).is_ok());
+        assert!(validate_quote_only_range(&a, &b, sqrt_price, 13_900, 14_000, &b, 100).is_err());
+        assert!(validate_quote_only_range(&a, &b, sqrt_price, 100, 200, &a, 0).is_ok());
+        assert!(validate_quote_only_range(&a, &b, sqrt_price, 100, 13_800, &a, 100).is_err());
+    }
+
+    fn pool(sqrt_min_price: u128, sqrt_price: u128, sqrt_max_price: u128) -> Pool {
+        let mut pool: Pool = bytemuck::Zeroable::zeroed();
+        pool.sqrt_min_price = sqrt_min_price;
+        pool.sqrt_price = sqrt_price;
+        pool.sqrt_max_price = sqrt_max_price;
+        pool
+    }
+
+    #[test]
+    fn test_spot_base_out() {
+        // Price 4 B per A: quote B buys a quarter as much A, quote A buys four times as much B
+        assert_eq!(spot_base_out(2 * Q64, false, 1_000), Some(250));
+        assert_eq!(spot_base_out(2 * Q64, true, 1_000), Some(4_000));
+        assert_eq!(spot_base_out(0, false, 1_000), None);
+    }
+
+    #[test]
+    fn test_quote_only_liquidity_at_range_edge() {
+        // Quote B buys liquidity alone at the max price, and never takes more than offered
+        let (liquidity, quote) = quote_only_liquidity(&pool(Q64, 2 * Q64, 2 * Q64), false, 1_000_000).unwrap();
+        assert!(liquidity > 0 && quote <= 1_000_000 && quote > 999_990);
+
+        // Quote A at the min price
+        let (liquidity, quote) = quote_only_liquidity(&pool(Q64, Q64, 2 * Q64), true, 1_000_000).unwrap();
+        assert!(liquidity > 0 && quote <= 1_000_000 && quote > 999_990);
+
+        // Inside the range a deposit would also take base tokens
+        assert_eq!(quote_only_liquidity(&pool(Q64, 3 * Q64 / 2, 2 * Q64), false, 1_000_000), None);
+        assert_eq!(quote_only_liquidity(&pool(Q64, 3 * Q64 / 2, 2 * Q64), true, 1_000_000), None);
+        // The wrong edge leaves nothing for quote to buy
+        assert_eq!(quote_only_liquidity(&pool(Q64, Q64, 2 * Q64), false, 1_000_000), None);
+    }
+
+    #[test]
+    fn test_quote_only_tick_range() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        for (quote_is_a, buffer) in [(false, 0), (false, 100), (true, 0), (true, 100)] {
+            let quote = if quote_is_a { a } else { b };
+            let (lower, upper) = quote_only_tick_range_at(2 * Q64, Q64 / 4, 8 * Q64, quote_is_a, buffer).unwrap();
+            assert!(validate_quote_only_range(&a, &b, 2 * Q64, lower, upper, &quote, buffer).is_ok());
+            assert_ne!((lower, upper), AUTO_TICK_RANGE);
+        }
+
+        // Price at the quote side's bound leaves no range
+        assert_eq!(quote_only_tick_range_at(2 * Q64, Q64, 2 * Q64, false, 0), None);
+        assert_eq!(quote_only_tick_range_at(Q64, Q64, 2 * Q64, true, 0), None);
+        assert_eq!(quote_only_tick_range_at(0, Q64, 2 * Q64, true, 0), None);
+    }
+
+    #[test]
+    fn test_quote_only_fee_mode() {
+        let mut pool = pool(Q64, Q64, 2 * Q64);
+        // Both-token pools charge the output token, so either quote side sees base fees
+        pool.collect_fee_mode = 0;
+        assert!(validate_quote_only_fee_mode(&pool, false).is_err());
+        assert!(validate_quote_only_fee_mode(&pool, true).is_err());
+        // Only-B pools are quote-only when quote is token B
+        pool.collect_fee_mode = 1;
+        assert!(validate_quote_only_fee_mode(&pool, false).is_ok());
+        assert!(validate_quote_only_fee_mode(&pool, true).is_err());
+        pool.collect_fee_mode = 2;
+        assert!(validate_quote_only_fee_mode(&pool, false).is_err());
+
+        // Fee parameters move the hash, volatility state doesn't
+        pool.collect_fee_mode = 1;
+        let hash = pool_fee_config_hash(&pool);
+        pool.pool_fees.dynamic_fee.volatility_accumulator = 7;
+        assert_eq!(pool_fee_config_hash(&pool), hash);
+        pool.pool_fees.base_fee.cliff_fee_numerator = 2_500_000;
+        assert_ne!(pool_fee_config_hash(&pool), hash);
+    }
+
+    #[test]
+    fn test_fee_accrual_simulation() {
+        let mut pool = pool(Q64 / 2, Q64, 2 * Q64);
+        pool.liquidity = 1u128 << 100;
+        pool.pool_fees.base_fee.cliff_fee_numerator = 2_500_000;
+        pool.pool_fees.protocol_fee_percent = 20;
+
+        // Only-B pool, quote B: both directions accrue fees in B only
+        pool.collect_fee_mode = 1;
+        assert!(simulate_quote_only_fee_accrual(&pool, false, FEE_ACCRUAL_PROBE_AMOUNT, 0, 0).unwrap());
+        // Quote A on the same pool would accrue base (B) fees
+        assert!(simulate_quote_only_fee_accrual(&pool, true, FEE_ACCRUAL_PROBE_AMOUNT, 0, 0).is_err());
+        // Both-token pools accrue base fees on one of the two directions
+        pool.collect_fee_mode = 0;
+        assert!(simulate_quote_only_fee_accrual(&pool, false, FEE_ACCRUAL_PROBE_AMOUNT, 0, 0).is_err());
+
+        // Without active liquidity there is nothing to simulate, which isn't a pass
+        pool.collect_fee_mode = 1;
+        pool.liquidity = 0;
+        assert!(!simulate_quote_only_fee_accrual(&pool, false, FEE_ACCRUAL_PROBE_AMOUNT, 0, 0).unwrap());
+    }
+
+}
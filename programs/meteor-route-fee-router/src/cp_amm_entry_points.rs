@@ -0,0 +1,110 @@
+//! CP-AMM `claim_position_fee` / `create_position` CPIs at per-vault instruction discriminators
+//!
+//! The router is compiled against one CP-AMM release and always sends that release's accounts and
+//! arguments. Only the 8-byte entry point can be overridden, so a vault can follow an instruction
+//! Meteora re-ships under a new name with the same accounts (`split_position` / `split_position2`)
+//! through `update_policy` instead of waiting for a router redeploy. Any change to accounts or
+//! arguments still needs a router release.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+use anchor_lang::Discriminator;
+
+use crate::cp_amm::cpi::accounts::{ClaimPositionFeeCtx, CreatePositionCtx};
+
+/// Instruction discriminators a vault's CP-AMM CPIs are sent to (`PolicyPda.cp_amm_entry_points`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CpAmmEntryPoints {
+    /// The discriminators of the CP-AMM release this crate is built against
+    #[default]
+    BuiltIn,
+    /// The built-in accounts and arguments, sent to these discriminators instead
+    Overrides {
+        claim_position_fee: [u8; 8],
+        create_position: [u8; 8],
+    },
+}
+
+impl CpAmmEntryPoints {
+    pub const LEN: usize = 1 + 8 + 8;
+
+    pub fn claim_position_fee_discriminator(&self) -> [u8; 8] {
+        match self {
+            CpAmmEntryPoints::BuiltIn => built_in_discriminator(cp_amm::instruction::ClaimPositionFee::DISCRIMINATOR),
+            CpAmmEntryPoints::Overrides { claim_position_fee, .. } => *claim_position_fee,
+        }
+    }
+
+    pub fn create_position_discriminator(&self) -> [u8; 8] {
+        match self {
+            CpAmmEntryPoints::BuiltIn => built_in_discriminator(cp_amm::instruction::CreatePosition::DISCRIMINATOR),
+            CpAmmEntryPoints::Overrides { create_position, .. } => *create_position,
+        }
+    }
+
+    /// Whether the two entry points are set and tell the instructions apart
+    pub fn is_valid(&self) -> bool {
+        let (claim, create) = (self.claim_position_fee_discriminator(), self.create_position_discriminator());
+        claim != [0u8; 8] && create != [0u8; 8] && claim != create
+    }
+}
+
+fn built_in_discriminator(discriminator: &[u8]) -> [u8; 8] {
+    discriminator.try_into().expect("Anchor discriminators are 8 bytes")
+}
+
+/// `claim_position_fee` at the vault's entry point
+pub fn claim_position_fee<'info>(
+    entry_points: &CpAmmEntryPoints,
+    ctx: CpiContext<'_, '_, '_, 'info, ClaimPositionFeeCtx<'info>>,
+) -> Result<()> {
+    invoke(ctx, entry_points.claim_position_fee_discriminator())
+}
+
+/// `create_position` at the vault's entry point
+pub fn create_position<'info>(
+    entry_points: &CpAmmEntryPoints,
+    ctx: CpiContext<'_, '_, '_, 'info, CreatePositionCtx<'info>>,
+) -> Result<()> {
+    invoke(ctx, entry_points.create_position_discriminator())
+}
+
+/// Same instruction the generated `cp_amm::cpi` function builds, with the discriminator swapped
+fn invoke<'info, T: ToAccountMetas + ToAccountInfos<'info>>(
+    ctx: CpiContext<'_, '_, '_, 'info, T>,
+    discriminator: [u8; 8],
+) -> Result<()> {
+    let ix = Instruction {
+        program_id: ctx.program.key(),
+        accounts: ctx.to_account_metas(None),
+        data: discriminator.to_vec(),
+    };
+    invoke_signed(&ix, &ctx.to_account_infos(), ctx.signer_seeds).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_point_discriminators() {
+        let built_in = CpAmmEntryPoints::BuiltIn;
+        assert!(built_in.is_valid());
+        assert_eq!(
+            built_in.claim_position_fee_discriminator().as_slice(),
+            cp_amm::instruction::ClaimPositionFee::DISCRIMINATOR
+        );
+
+        let overridden = CpAmmEntryPoints::Overrides {
+            claim_position_fee: [1; 8],
+            create_position: built_in.create_position_discriminator(),
+        };
+        assert!(overridden.is_valid());
+        assert_eq!(overridden.claim_position_fee_discriminator(), [1; 8]);
+        assert!(!CpAmmEntryPoints::Overrides { claim_position_fee: [0; 8], create_position: [2; 8] }.is_valid());
+        assert!(!CpAmmEntryPoints::Overrides { claim_position_fee: [2; 8], create_position: [2; 8] }.is_valid());
+
+        let mut data = Vec::new();
+        overridden.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), CpAmmEntryPoints::LEN);
+    }
+}
@@ -0,0 +1,273 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use crate::{
+    error::FeeRouterError,
+    layout::{discriminator, read_i32, read_pubkey},
+};
+
+/// Meteora DLMM program ID (mainnet)
+pub const DLMM_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+
+/// Maximum bins a single DLMM position can span
+pub const MAX_BIN_PER_POSITION: i32 = 70;
+
+/// Bins per DLMM bin array
+pub const MAX_BIN_PER_ARRAY: i32 = 70;
+
+/// Accounts the DLMM claim path expects at the front of remaining_accounts:
+/// [bin_array_lower, bin_array_upper, dlmm_program, dlmm_event_authority]
+pub const CLAIM_REMAINING_ACCOUNTS: usize = 4;
+
+// LbPair field offsets (zero-copy, discriminator included)
+const LB_PAIR_ACTIVE_ID: usize = 76;
+const LB_PAIR_TOKEN_X_MINT: usize = 88;
+const LB_PAIR_TOKEN_Y_MINT: usize = 120;
+const LB_PAIR_RESERVE_X: usize = 152;
+const LB_PAIR_RESERVE_Y: usize = 184;
+const LB_PAIR_MIN_LEN: usize = 216;
+
+// PositionV2 field offsets (zero-copy, discriminator included)
+const POSITION_LB_PAIR: usize = 8;
+const POSITION_OWNER: usize = 40;
+const POSITION_LOWER_BIN_ID: usize = 7912;
+const POSITION_UPPER_BIN_ID: usize = 7916;
+const POSITION_MIN_LEN: usize = 7920;
+
+/// Fields of a DLMM `LbPair` the router needs
+#[derive(Clone, Copy, Debug)]
+pub struct LbPairView {
+    pub active_id: i32,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+}
+
+/// Fields of a DLMM `PositionV2` the router needs
+#[derive(Clone, Copy, Debug)]
+pub struct DlmmPositionView {
+    pub lb_pair: Pubkey,
+    pub owner: Pubkey,
+    pub lower_bin_id: i32,
+    pub upper_bin_id: i32,
+}
+
+/// Check owner, discriminator and minimum size of a DLMM account
+fn check_dlmm_account(info: &AccountInfo, data: &[u8], account_name: &[u8], min_len: usize) -> Result<()> {
+    require_keys_eq!(*info.owner, DLMM_PROGRAM_ID, FeeRouterError::InvalidDlmmProgram);
+    require!(
+        data.len() >= min_len && data[..8] == discriminator(account_name),
+        FeeRouterError::PoolAccountMismatch
+    );
+    Ok(())
+}
+
+/// Read a DLMM `LbPair` account
+pub fn read_lb_pair(info: &AccountInfo) -> Result<LbPairView> {
+    let data = info.try_borrow_data()?;
+    check_dlmm_account(info, &data, b"account:LbPair", LB_PAIR_MIN_LEN)?;
+    Ok(LbPairView {
+        active_id: read_i32(&data, LB_PAIR_ACTIVE_ID),
+        token_x_mint: read_pubkey(&data, LB_PAIR_TOKEN_X_MINT),
+        token_y_mint: read_pubkey(&data, LB_PAIR_TOKEN_Y_MINT),
+        reserve_x: read_pubkey(&data, LB_PAIR_RESERVE_X),
+        reserve_y: read_pubkey(&data, LB_PAIR_RESERVE_Y),
+    })
+}
+
+/// Read a DLMM `PositionV2` account
+pub fn read_position(info: &AccountInfo) -> Result<DlmmPositionView> {
+    let data = info.try_borrow_data()?;
+    check_dlmm_account(info, &data, b"account:PositionV2", POSITION_MIN_LEN)?;
+    Ok(DlmmPositionView {
+        lb_pair: read_pubkey(&data, POSITION_LB_PAIR),
+        owner: read_pubkey(&data, POSITION_OWNER),
+        lower_bin_id: read_i32(&data, POSITION_LOWER_BIN_ID),
+        upper_bin_id: read_i32(&data, POSITION_UPPER_BIN_ID),
+    })
+}
+
+/// Validate a quote-only DLMM bin range
+///
+/// Bins above the active bin hold only token X and bins below hold only token Y. Swaps
+/// crossing into a single-sided range pay fees in the token being swapped in:
+/// - If quote is token Y, the range must sit strictly above the active bin (lower_bin_id > active_id + buffer)
+/// - If quote is token X, the range must sit strictly below the active bin (upper_bin_id < active_id - buffer)
+///
+/// The policy's tick buffer is applied in bins here.
+pub fn validate_quote_only_bins(
+    lb_pair: &LbPairView,
+    lower_bin_id: i32,
+    upper_bin_id: i32,
+    quote_mint: &Pubkey,
+    buffer_bins: u32,
+) -> Result<()> {
+    require!(
+        lower_bin_id <= upper_bin_id
+            && upper_bin_id - lower_bin_id < MAX_BIN_PER_POSITION,
+        FeeRouterError::InvalidTickRange
+    );
+
+    let is_token_x_quote = lb_pair.token_x_mint == *quote_mint;
+    let is_token_y_quote = lb_pair.token_y_mint == *quote_mint;
+    require!(
+        is_token_x_quote || is_token_y_quote,
+        FeeRouterError::InvalidQuoteMint
+    );
+
+    let active = lb_pair.active_id as i64;
+    let buffer = buffer_bins as i64;
+    if is_token_y_quote {
+        require!(
+            lower_bin_id as i64 > active + buffer,
+            FeeRouterError::PositionNotQuoteOnly
+        );
+    } else {
+        require!(
+            (upper_bin_id as i64) < active - buffer,
+            FeeRouterError::PositionNotQuoteOnly
+        );
+    }
+
+    msg!(
+        "DLMM quote-only validation passed. Quote mint: {}, bins: [{}, {}], active: {}",
+        quote_mint,
+        lower_bin_id,
+        upper_bin_id,
+        lb_pair.active_id
+    );
+
+    Ok(())
+}
+
+/// Index of the bin array holding `bin_id` (floor division)
+pub fn bin_array_index(bin_id: i32) -> i64 {
+    bin_id.div_euclid(MAX_BIN_PER_ARRAY) as i64
+}
+
+/// Derive the bin array PDA for `index`
+pub fn derive_bin_array(lb_pair: &Pubkey, index: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"bin_array", lb_pair.as_ref(), &index.to_le_bytes()],
+        &DLMM_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Derive the DLMM event authority PDA
+pub fn derive_event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &DLMM_PROGRAM_ID).0
+}
+
+/// Build the DLMM `initialize_position` instruction
+pub fn initialize_position_ix(
+    payer: Pubkey,
+    position: Pubkey,
+    lb_pair: Pubkey,
+    owner: Pubkey,
+    lower_bin_id: i32,
+    width: i32,
+) -> Instruction {
+    let mut data = discriminator(b"global:initialize_position").to_vec();
+    data.extend_from_slice(&lower_bin_id.to_le_bytes());
+    data.extend_from_slice(&width.to_le_bytes());
+
+    Instruction {
+        program_id: DLMM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(position, true),
+            AccountMeta::new_readonly(lb_pair, false),
+            AccountMeta::new_readonly(owner, true),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+            AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::rent::ID, false),
+            AccountMeta::new_readonly(derive_event_authority(), false),
+            AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Accounts for the DLMM `claim_fee` instruction
+pub struct ClaimFeeAccounts {
+    pub lb_pair: Pubkey,
+    pub position: Pubkey,
+    pub bin_array_lower: Pubkey,
+    pub bin_array_upper: Pubkey,
+    pub sender: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub user_token_x: Pubkey,
+    pub user_token_y: Pubkey,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// Build the DLMM `claim_fee` instruction
+pub fn claim_fee_ix(accounts: &ClaimFeeAccounts) -> Instruction {
+    Instruction {
+        program_id: DLMM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.lb_pair, false),
+            AccountMeta::new(accounts.position, false),
+            AccountMeta::new(accounts.bin_array_lower, false),
+            AccountMeta::new(accounts.bin_array_upper, false),
+            AccountMeta::new_readonly(accounts.sender, true),
+            AccountMeta::new(accounts.reserve_x, false),
+            AccountMeta::new(accounts.reserve_y, false),
+            AccountMeta::new(accounts.user_token_x, false),
+            AccountMeta::new(accounts.user_token_y, false),
+            AccountMeta::new_readonly(accounts.token_x_mint, false),
+            AccountMeta::new_readonly(accounts.token_y_mint, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+            AccountMeta::new_readonly(derive_event_authority(), false),
+            AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+        ],
+        data: discriminator(b"global:claim_fee").to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lb_pair(active_id: i32, token_x_mint: Pubkey, token_y_mint: Pubkey) -> LbPairView {
+        LbPairView {
+            active_id,
+            token_x_mint,
+            token_y_mint,
+            reserve_x: Pubkey::default(),
+            reserve_y: Pubkey::default(),
+        }
+    }
+
+    #[test]
+    fn test_quote_only_bins() {
+        let x = Pubkey::new_unique();
+        let y = Pubkey::new_unique();
+        let pair = lb_pair(100, x, y);
+
+        // Quote Y: range must be above the active bin
+        assert!(validate_quote_only_bins(&pair, 101, 120, &y, 0).is_ok());
+        assert!(validate_quote_only_bins(&pair, 100, 120, &y, 0).is_err());
+
+        // Quote X: range must be below the active bin
+        assert!(validate_quote_only_bins(&pair, 80, 99, &x, 0).is_ok());
+        assert!(validate_quote_only_bins(&pair, 80, 100, &x, 0).is_err());
+
+        // Width and mint checks
+        assert!(validate_quote_only_bins(&pair, 101, 101 + MAX_BIN_PER_POSITION, &y, 0).is_err());
+        assert!(validate_quote_only_bins(&pair, 101, 120, &Pubkey::new_unique(), 0).is_err());
+    }
+
+    #[test]
+    fn test_bin_array_index() {
+        assert_eq!(bin_array_index(0), 0);
+        assert_eq!(bin_array_index(69), 0);
+        assert_eq!(bin_array_index(70), 1);
+        assert_eq!(bin_array_index(-1), -1);
+        assert_eq!(bin_array_index(-70), -1);
+        assert_eq!(bin_array_index(-71), -2);
+    }
+}
@@ -17,7 +17,7 @@ pub enum FeeRouterError {
     #[msg("Distribution would exceed daily cap or already finalized.")]
     AlreadyDistributed = 6004,
     
-    #[msg("Missing required on-chain account or config.")]
+    #[msg("Optional account required by the policy's settings is missing or not the expected account.")]
     MissingRequiredInput = 6005,
     
     #[msg("Per investor payout below min_payout_lamports; added to carry.")]
@@ -73,4 +73,233 @@ pub enum FeeRouterError {
 
     #[msg("Invalid pagination state (non-contiguous pages or mismatched totals).")]
     InvalidPaginationState = 6023,
+
+    #[msg("Day gate grace period exceeds the allowed maximum.")]
+    InvalidDayGateGrace = 6024,
+
+    #[msg("Locked snapshot started but not completed for this day.")]
+    SnapshotIncomplete = 6025,
+
+    #[msg("Paid investor set does not match the day's locked snapshot.")]
+    SnapshotMismatch = 6026,
+
+    #[msg("Locked snapshot already completed for this day.")]
+    SnapshotAlreadyComplete = 6027,
+
+    #[msg("Locker kind requires a vesting program that is not configured.")]
+    InvalidLockerConfig = 6028,
+
+    #[msg("Vesting account owner is not an allowlisted locker program.")]
+    LockerNotAllowed = 6029,
+
+    #[msg("DLMM program ID or PDA mismatch.")]
+    InvalidDlmmProgram = 6030,
+
+    #[msg("Pool accounts (mints, vaults or position) do not match the pool state.")]
+    PoolAccountMismatch = 6031,
+
+    #[msg("Orca Whirlpool program ID or PDA mismatch.")]
+    InvalidWhirlpoolProgram = 6032,
+
+    #[msg("Raydium CLMM program ID or PDA mismatch.")]
+    InvalidRaydiumProgram = 6033,
+
+    #[msg("Position backend does not match the policy's pool_kind.")]
+    PoolKindMismatch = 6034,
+
+    #[msg("Creator wSOL unwrap needs a native-mint quote and the creator ATA's owner wallet.")]
+    InvalidWsolUnwrap = 6035,
+
+    #[msg("Quote treasury must be the program's treasury PDA with no delegate or close authority, and not frozen.")]
+    InvalidTreasury = 6036,
+
+    #[msg("Failed payout ledger is full; settle entries with retry_failed_payouts first.")]
+    FailedPayoutsFull = 6037,
+
+    #[msg("Investor index already paid today.")]
+    InvestorAlreadyPaid = 6038,
+
+    #[msg("Investor index exceeds the paid bitmap capacity.")]
+    InvestorIndexOutOfRange = 6039,
+
+    #[msg("Crank account does not match the policy's pinned static accounts.")]
+    StaticAccountMismatch = 6040,
+
+    #[msg("Force-finalize timeout must be at most 24h.")]
+    InvalidForceFinalizeTimeout = 6041,
+
+    #[msg("The day has not stalled long enough to be force-finalized.")]
+    ForceFinalizeTooEarly = 6042,
+
+    #[msg("Another cranker holds an unexpired crank lease.")]
+    CrankLeaseHeld = 6043,
+
+    #[msg("Crank leases are disabled for this vault.")]
+    CrankLeasesDisabled = 6044,
+
+    #[msg("Crank lease length exceeds the maximum.")]
+    InvalidCrankLeaseSlots = 6045,
+
+    #[msg("Locker program is not accepted by the policy, or does not own the vesting account.")]
+    InvalidLockerProgram = 6046,
+
+    #[msg("Vesting account does not release to the investor.")]
+    StreamRecipientMismatch = 6047,
+
+    #[msg("Investor pages need a complete locked snapshot for the day.")]
+    SnapshotRequired = 6048,
+
+    #[msg("No creator payout is owed.")]
+    NoCreatorPayoutOwed = 6049,
+
+    #[msg("Creator vesting must be at most a year, can't be combined with the wSOL unwrap, and needs the creator stream accounts.")]
+    InvalidCreatorStream = 6050,
+
+    #[msg("Auto-compounding takes at most 10000 bps and needs a CP-AMM pool.")]
+    InvalidCompoundBps = 6051,
+
+    #[msg("Buyback-and-burn needs a CP-AMM pool, can't be combined with another creator payout mode, and takes at most 10000 bps of slippage.")]
+    InvalidBuyback = 6052,
+
+    #[msg("Referral share above 10000 bps, set without a referral wallet, or referral quote ATA missing/wrong.")]
+    InvalidReferral = 6053,
+
+    #[msg("Protocol fee above the maximum or without a treasury, or protocol fee ATA missing/wrong.")]
+    InvalidProtocolConfig = 6054,
+
+    #[msg("The new authority is not a Realms governance account or its native treasury.")]
+    InvalidGovernance = 6055,
+
+    #[msg("Distribution is paused by the policy authority.")]
+    PolicyPaused = 6056,
+
+    #[msg("Multi-vault claim takes 1-4 vaults with their full account groups, each a non-compounding CP-AMM vault.")]
+    InvalidMultiVault = 6057,
+
+    #[msg("Vault registry is full; the authority has registered the maximum number of vaults.")]
+    VaultRegistryFull = 6058,
+
+    #[msg("Position NFT account must hold exactly the recorded position NFT with no delegate.")]
+    InvalidPositionNftAccount = 6059,
+
+    #[msg("Vault can only be reinitialized while paused, with no day open and nothing owed to the creator.")]
+    VaultNotReinitializable = 6060,
+
+    #[msg("Slot day gate is outside the allowed range.")]
+    InvalidDayGateSlots = 6061,
+
+    #[msg("Epoch offset must be less than one day.")]
+    InvalidEpochOffset = 6062,
+
+    #[msg("Tranches per day must divide the day evenly, up to 24, and can only be lowered while paused.")]
+    InvalidTranchesPerDay = 6063,
+
+    #[msg("Payout override table is full.")]
+    PayoutOverridesFull = 6064,
+
+    #[msg("Weight override table is full.")]
+    WeightOverridesFull = 6065,
+
+    #[msg("Weight overrides can only change while no distribution day is open.")]
+    WeightOverridesLocked = 6066,
+
+    #[msg("max_weight_bps must be 0 or between 500 and 10000.")]
+    InvalidMaxWeightBps = 6067,
+
+    #[msg("Policy targets can only be amended before the honorary position exists.")]
+    PositionAlreadyInitialized = 6068,
+
+    #[msg("Page exceeds the policy's max_investors_per_page or max_pages_per_day.")]
+    PageLimitExceeded = 6069,
+
+    #[msg("Attestation layout needs a seed prefix and an expiry offset clear of the discriminator.")]
+    InvalidAttestationConfig = 6070,
+
+    #[msg("Attestation account is not the investor's attestation PDA.")]
+    AttestationMismatch = 6071,
+
+    #[msg("Reward claiming is only supported for CP-AMM positions.")]
+    RewardsUnsupported = 6072,
+
+    #[msg("Reward pages need investor reward routing and must continue the open reward round.")]
+    InvalidRewardRound = 6073,
+
+    #[msg("CP-AMM entry point discriminators must be non-zero and distinct.")]
+    InvalidCpAmmEntryPoints = 6074,
+
+    #[msg("Network program IDs must be set and distinct.")]
+    InvalidNetworkConfig = 6075,
+
+    #[msg("Program is not the configured network's CP-AMM program.")]
+    NetworkProgramMismatch = 6076,
+
+    #[msg("Vesting account is not the stream key listed for the investor.")]
+    StreamKeyMismatch = 6077,
+
+    #[msg("Investor owner account is not the page's investor wallet.")]
+    InvestorOwnerMismatch = 6078,
+
+    #[msg("remaining_accounts count does not match the investors and the pool kind's claim accounts.")]
+    RemainingAccountsLayoutInvalid = 6079,
+
+    #[msg("Vesting account data is too short, mistyped or could not be decoded.")]
+    StreamDeserializeFailed = 6080,
+
+    #[msg("Pool's collect-fee mode can credit swap fees in the base token.")]
+    PoolFeeModeNotQuoteOnly = 6081,
+
+    #[msg("Position range is closer to the live price than min_tick_distance_from_price.")]
+    PositionTooCloseToPrice = 6082,
+
+    #[msg("cap_carry_days exceeds the maximum.")]
+    InvalidCapCarryDays = 6083,
+
+    #[msg("payout_dispute_window_secs exceeds the maximum or is combined with payout_to_stream_escrow.")]
+    InvalidDisputeWindow = 6084,
+
+    #[msg("No escrowed payout for that day is still inside its dispute window.")]
+    DisputeWindowClosed = 6085,
+
+    #[msg("A distribution day is in progress; retry once it is finalized.")]
+    DistributionInProgress = 6086,
+
+    #[msg("Escrowed payouts must be withdrawn to the investor's usable quote token account.")]
+    InvalidEscrowDestination = 6087,
+
+    #[msg("Temp fee account has a delegate or close authority, or is frozen.")]
+    TempAccountEncumbered = 6088,
+
+    #[msg("Quote mint has a freeze authority or extension the policy does not allow.")]
+    QuoteMintRiskNotAllowed = 6089,
+
+    #[msg("Hook program does not match policy.hook_program, or is the router itself.")]
+    InvalidHookProgram = 6090,
+
+    #[msg("Signer is not the policy's lock oracle, or the oracle amount is from the future.")]
+    InvalidLockOracle = 6091,
+
+    #[msg("Oracle locked amount is older than lock_oracle_max_age_secs or from a rotated oracle.")]
+    OracleLockStale = 6092,
+
+    #[msg("Scheduled params must be future-dated, in increasing day order, each setting a value, and within the maximum count.")]
+    InvalidParamSchedule = 6093,
+
+    #[msg("Stream keys must be strictly ascending across the day's pages while paid investors are tracked.")]
+    InvestorKeysNotAscending = 6094,
+
+    #[msg("Locked snapshot investor count does not match the count committed with set_expected_pages.")]
+    SnapshotInvestorCountMismatch = 6095,
+
+    #[msg("Stream vests another mint than the vault's base mint.")]
+    StreamMintMismatch = 6096,
+}
+
+impl From<meteor_route_core::MathError> for FeeRouterError {
+    fn from(err: meteor_route_core::MathError) -> Self {
+        match err {
+            meteor_route_core::MathError::InvalidY0 => FeeRouterError::InvalidY0,
+            meteor_route_core::MathError::LockedExceedsAllocation => FeeRouterError::LockedExceedsAllocation,
+            meteor_route_core::MathError::Overflow => FeeRouterError::Overflow,
+        }
+    }
 }
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+use anchor_lang::{event::EVENT_IX_TAG_LE, Event};
+
+/// Seed of the `event_authority` PDA added by `#[event_cpi]`
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// `emit_cpi!` for helpers that have no `ctx` in scope
+///
+/// Events are recorded as self-CPI instruction data, so they survive log truncation on large cranks.
+pub struct EventCpi<'info> {
+    pub authority: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+impl EventCpi<'_> {
+    pub fn emit<E: Event>(&self, event: E) -> Result<()> {
+        let data = [EVENT_IX_TAG_LE, &event.data()].concat();
+        let ix = Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![AccountMeta::new_readonly(*self.authority.key, true)],
+        );
+        invoke_signed(
+            &ix,
+            std::slice::from_ref(&self.authority),
+            &[&[EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+        Ok(())
+    }
+}
@@ -1,7 +1,95 @@
 use anchor_lang::prelude::*;
 
+use crate::attestation::AttestationLayout;
+use crate::error::FeeRouterError;
+use crate::cp_amm_entry_points::CpAmmEntryPoints;
+use crate::locker::{LockerEntry, LockerKind, StreamIgnoredReason};
+use crate::networks::Network;
+use crate::stake::StakeLayout;
+use crate::state::{PoolKind, RewardRouting, ScheduledParams, StaticAccounts, UnattestedRouting};
+
+/// Layout version of every event below; bumped whenever a field is added, removed or reordered.
+/// All events start with `schema_version` then `vault_seed`, so indexers can attribute and route
+/// an event before decoding the rest. Events that carry quote amounts follow with `quote_mint`
+/// and `decimals`, so the amounts can be priced without reading the policy.
+pub const EVENT_SCHEMA_VERSION: u8 = 3;
+
+/// Why an investor payout was recorded as failed instead of transferred
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayoutFailureReason {
+    /// Quote token account missing, closed, or not the investor's account for the quote mint
+    InvalidAta,
+    /// Quote token account is frozen
+    FrozenAta,
+    /// Stream can't take a top-up from the router (policy.payout_to_stream_escrow)
+    StreamNotToppable,
+}
+
+/// What a recoverable crank check expected, and what the call provided
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrankMismatch {
+    /// Page sent out of order; `expected` is the pagination cursor
+    PageIndex { expected: u64, provided: u64 },
+    /// `page_hash` is not the hash of the page's investors; `expected` is the recomputed hash
+    PageHash { page_index: u64, expected: [u8; 32], provided: [u8; 32] },
+    /// The suspended page at the cursor was resent with other investors
+    SuspendedPage { page_index: u64, expected: [u8; 32], provided: [u8; 32] },
+    /// Final page ends at another cursor than the page count fixed by an earlier final page
+    TotalPages { expected: u64, provided: u64 },
+    /// Page at or past the page count committed for the day
+    PageBeyondExpected { page_index: u64, expected_pages: u64 },
+    /// Investors handled today don't add up to the count committed with set_expected_pages
+    InvestorCount { expected: u32, provided: u32 },
+    /// Unordered page already completed today, or sent twice in one call
+    PageAlreadyProcessed { page_index: u64 },
+}
+
+impl CrankMismatch {
+    /// Log `CrankCheckFailed` for the mismatch and return `error` to fail the call with
+    pub fn reject(self, vault_seed: &str, day_epoch: u64, timestamp: u64, error: FeeRouterError) -> Error {
+        emit!(CrankCheckFailed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.to_string(),
+            day_epoch,
+            mismatch: self,
+            timestamp,
+        });
+        error.into()
+    }
+}
+
+/// Why an investor received nothing for a page
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Stream has nothing locked at the payout time
+    ZeroLocked,
+    /// Pro-rata payout below policy.min_payout_lamports; amount went to dust
+    BelowMinPayout,
+    /// No usable quote ATA and policy_fund_missing_ata is off; amount went to dust
+    MissingAta,
+    /// No live attestation from policy.attestation_program; amount routed per policy.unattested_routing
+    NotAttested,
+    /// Stream can't take a top-up and continue_on_failure is off; amount went to dust
+    StreamNotToppable,
+}
+
+/// A crank failed a check the keeper can fix by resending, logged right before the error
+///
+/// Emitted with `emit!` rather than as a self-CPI: a failed transaction, simulated or landed, keeps
+/// its logs but not its inner instructions.
+#[event]
+pub struct CrankCheckFailed {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub day_epoch: u64,
+    pub mismatch: CrankMismatch,
+    pub timestamp: u64,
+}
+
 #[event]
 pub struct HonoraryPositionInitialized {
+    pub schema_version: u8,
+    pub vault_seed: String,
     pub pda: Pubkey,
     pub position: Pubkey,
     pub pool: Pubkey,
@@ -13,46 +101,611 @@ pub struct HonoraryPositionInitialized {
 
 #[event]
 pub struct QuoteFeesClaimed {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
     pub claimed_quote: u128,
     pub claimed_base: u128,
+    /// Token-2022 transfer fee withheld moving the claim into the treasury (claimed_quote is net of it)
+    pub transfer_fee: u64,
     pub position: Pubkey,
     pub treasury_ata: Pubkey,
     pub timestamp: u64,
 }
 
+#[event]
+pub struct FeesCompounded {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub position: Pubkey,
+    /// Quote that reached the treasury from this claim
+    pub claimed_quote: u64,
+    pub compound_bps: u16,
+    /// Quote moved from the treasury into the position, transfer fee included
+    pub quote_compounded: u64,
+    pub liquidity_added: u128,
+    pub timestamp: u64,
+}
+
+/// Diagnostic emitted right before a claim aborts with `BaseFeeDetected`. The transaction still
+/// reverts; the event is visible in the simulation or failed-transaction inner instructions.
+#[event]
+pub struct BaseFeeObserved {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub position: Pubkey,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct InvestorPaid {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub day_epoch: u64,
+    pub investor: Pubkey,
+    pub stream: Pubkey,
+    pub locked_amount: u64,
+    pub payout: u64,
+}
+
+/// One investor's payout within an `InvestorPaidBatch`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvestorPayout {
+    pub investor: Pubkey,
+    pub stream: Pubkey,
+    pub locked_amount: u64,
+    pub payout: u64,
+}
+
+/// All successful payouts of one page, emitted instead of `InvestorPaid` when
+/// `policy.batch_investor_paid_events` is set
+#[event]
+pub struct InvestorPaidBatch {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub day_epoch: u64,
+    pub page_index: u64,
+    pub payouts: Vec<InvestorPayout>,
+}
+
 #[event]
 pub struct InvestorPayoutPage {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
     pub page_index: u64,
+    /// Investor the call started at: non-zero when it resumed a suspended page
+    pub first_investor: u32,
+    /// False when compute ran low and the page stopped partway; the next call resumes it
+    pub page_complete: bool,
     pub investors_processed: u32,
     pub successful_transfers: u32,
     pub failed_transfers: u32,
     pub total_distributed: u128,
+    /// Token-2022 transfer fees withheld from the page's payouts (investors received total_distributed minus this)
+    pub transfer_fee_withheld: u64,
     pub ata_creation_cost: u64,
+    /// Part of ata_creation_cost refunded to the crank caller from the position owner PDA
+    pub ata_reimbursed: u64,
+    /// Payouts below min_payout_lamports or without a usable ATA, added to carry
+    pub dust: u64,
+    /// Investors paid nothing without a failure: ignored stream, zero locked or below min payout
+    pub skipped_investors: u32,
+    /// Day totals including this page
+    pub day_distributed: u128,
+    pub day_pages_processed: u64,
+    /// ProgressPda.generation the page was paid under
+    pub generation: u16,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct LockedSnapshotTaken {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub day_epoch: u64,
+    pub snapshot_ts: u64,
+    pub total_locked: u64,
+    pub investor_count: u32,
+    pub locked_hash: [u8; 32],
+    pub complete: bool,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct StreamIgnored {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub stream: Pubkey,
+    pub investor: Pubkey,
+    pub reason: StreamIgnoredReason,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct InvestorSkipped {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub day_epoch: u64,
+    pub investor: Pubkey,
+    pub stream: Pubkey,
+    pub reason: SkipReason,
+    /// Payout the investor would have received (0 for ZeroLocked)
+    pub amount: u64,
+}
+
+#[event]
+pub struct InvestorAtaMissing {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub investor: Pubkey,
+    pub quote_ata: Pubkey,
+    /// Payout moved to dust because policy_fund_missing_ata is off
+    pub payout: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct FailedPayoutRecorded {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub investor: Pubkey,
+    pub quote_ata: Pubkey,
+    pub amount: u64,
+    pub reason: PayoutFailureReason,
+    /// Ledger total still owed to all investors
+    pub total_owed: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct FailedPayoutSettled {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub investor: Pubkey,
+    pub quote_ata: Pubkey,
+    pub amount: u64,
+    pub total_owed: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct CreatorPayoutDeferred {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub day_epoch: u64,
+    pub creator_quote_ata: Pubkey,
+    pub amount: u64,
+    pub reason: PayoutFailureReason,
+    /// Creator remainder still owed across days
+    pub creator_owed: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct CreatorPayoutSettled {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub creator_quote_ata: Pubkey,
+    pub amount: u64,
+    /// Token-2022 transfer fee withheld from the settled amount
+    pub transfer_fee: u64,
+    pub creator_owed: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct BuybackExecuted {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub day_epoch: u64,
+    pub pool: Pubkey,
+    /// Quote swapped out of the treasury, including remainders carried from earlier days
+    pub quote_in: u64,
+    /// Base received from the swap and burned
+    pub base_burned: u64,
+    /// quote_in / base_burned as Q64.64
+    pub average_price_x64: u128,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct ReferralFeePaid {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub day_epoch: u64,
+    pub referral_wallet: Pubkey,
+    pub referral_quote_ata: Pubkey,
+    /// Left the treasury; deducted from the creator payout
+    pub amount: u64,
+    pub referral_bps: u16,
+    /// Token-2022 transfer fee withheld from the amount
+    pub transfer_fee: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct CreatorPayoutStreamed {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub day_epoch: u64,
+    /// Streamflow metadata account of the stream
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub start_time: u64,
+    /// Time by which the whole amount is vested
+    pub end_time: u64,
     pub timestamp: u64,
 }
 
 #[event]
 pub struct CreatorPayoutDayClosed {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
     pub day_epoch: u64,
     pub total_claimed: u128,
     pub total_distributed: u128,
     pub creator_payout: u128,
+    /// Creator payout was delivered as native SOL to the creator wallet
+    pub creator_unwrapped: bool,
+    /// Creator account could not receive the payout; it was added to ProgressPda.creator_owed
+    pub creator_deferred: bool,
+    /// Creator payout was deposited into a Streamflow stream (see CreatorPayoutStreamed)
+    pub creator_streamed: bool,
+    /// ProgressPda.buyback_pending, creator payout included, was spent on a buyback (see BuybackExecuted)
+    pub creator_bought_back: bool,
+    /// Token-2022 transfer fee withheld from the creator payout
+    pub creator_transfer_fee: u64,
+    /// Token-2022 transfer fees withheld across the day's claim and payouts
+    pub transfer_fees_today: u64,
     pub carry: u64,
+    /// Investor share cut by the daily cap and held for the next day
+    pub capped_excess_rolled: u64,
+    pub pages_processed: u64,
+    /// Final hash chain over the day's page hashes (see ProgressPda::record_page_hash)
+    pub page_hash_chain: [u8; 32],
+    /// ProgressPda.generation the day was distributed under
+    pub generation: u16,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct DistributionDeferred {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub day_epoch: u64,
+    pub claimed_quote: u128,
+    pub rollover_quote: u64,
+    pub min_daily_claim_lamports: u64,
+    pub timestamp: u64,
+}
+
+/// Day closed without pages because its completed snapshot has nothing locked
+#[event]
+pub struct ZeroLockedDayClosed {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub day_epoch: u64,
+    pub snapshot_investor_count: u32,
+    pub snapshot_locked_hash: [u8; 32],
+    /// Claimed quote plus capped excess rolled in from earlier days
+    pub creator_payout: u64,
+    pub timestamp: u64,
+}
+
+/// An investor's payout was held in their escrow for the dispute window
+#[event]
+pub struct PayoutEscrowed {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub day_epoch: u64,
+    pub investor: Pubkey,
+    pub amount: u64,
+    /// Withdrawable from this timestamp; claw-back is possible until then
+    pub release_ts: u64,
+    pub total_escrowed: u64,
+}
+
+#[event]
+pub struct EscrowedPayoutsWithdrawn {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub investor: Pubkey,
+    pub quote_ata: Pubkey,
+    pub amount: u64,
+    /// Still held, inside its dispute window
+    pub total_escrowed: u64,
+    pub timestamp: u64,
+}
+
+/// The authority took back an escrowed payout; it joins the next day's investor pool
+#[event]
+pub struct EscrowedPayoutClawedBack {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub investor: Pubkey,
+    pub day_epoch: u64,
+    pub amount: u64,
+    pub total_escrowed: u64,
+    pub timestamp: u64,
+}
+
+/// The lock oracle published an investor's locked amount (LockerKind::Oracle)
+#[event]
+pub struct OracleLockedPublished {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub investor: Pubkey,
+    pub oracle: Pubkey,
+    pub locked_amount: u64,
+    pub as_of_ts: u64,
+    pub timestamp: u64,
+}
+
+/// Lamports were added to the position owner PDA's ATA rent reserve
+#[event]
+pub struct AtaReserveFunded {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub funder: Pubkey,
+    pub lamports: u64,
+    /// Lamports above the PDA's rent-exempt minimum after the deposit
+    pub reserve: u64,
+    pub timestamp: u64,
+}
+
+/// The authority committed the day's page and investor counts before any payouts
+#[event]
+pub struct ExpectedPagesCommitted {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub day_epoch: u64,
+    pub expected_pages: u64,
+    /// Investors the pages must cover in total (0 = not checked)
+    pub expected_investors: u32,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct DayForceFinalized {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub day_epoch: u64,
+    pub caller: Pubkey,
+    pub stalled_secs: u64,
+    pub pages_processed: u64,
+    pub investor_unpaid: u64,
+    pub unpaid_rolled_over: bool,
+    pub creator_payout: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct CrankLeaseAcquired {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub cranker: Pubkey,
+    pub lease_expiry_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LockerTestConfigUpdated {
+    pub schema_version: u8,
+    pub authority: Pubkey,
+    pub relax_locker_checks: bool,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct NetworkConfigUpdated {
+    pub schema_version: u8,
+    pub authority: Pubkey,
+    pub network: Network,
+    pub streamflow_program_id: Pubkey,
+    pub cp_amm_program_id: Pubkey,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct ProtocolConfigUpdated {
+    pub schema_version: u8,
+    pub admin: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub protocol_treasury: Pubkey,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct ProtocolFeeCollected {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub quote_mint: Pubkey,
+    pub decimals: u8,
+    pub protocol_treasury: Pubkey,
+    pub protocol_fee_ata: Pubkey,
+    /// Quote that reached the treasury from this claim, before the fee
+    pub claimed_quote: u64,
+    pub protocol_fee_bps: u16,
+    /// Left the treasury; taken off the claim before the split
+    pub amount: u64,
+    /// Token-2022 transfer fee withheld from the amount
+    pub transfer_fee: u64,
     pub timestamp: u64,
 }
 
 #[event]
 pub struct PolicyUpdated {
+    pub schema_version: u8,
     pub vault_seed: String,
     pub investor_fee_share_bps: u16,
     pub daily_cap_quote_lamports: u64,
     pub min_payout_lamports: u64,
     pub policy_fund_missing_ata: bool,
+    pub day_gate_grace_secs: u64,
+    pub min_daily_claim_lamports: u64,
+    pub roll_capped_excess: bool,
+    pub bonfida_program_id: Pubkey,
+    pub locker_kind: LockerKind,
+    pub locker_allowlist: Vec<LockerEntry>,
+    pub pool_kind: PoolKind,
+    pub quote_only_buffer_ticks: u32,
+    pub payout_memos: bool,
+    pub unwrap_wsol_creator: bool,
+    pub continue_on_failure: bool,
+    pub track_paid_investors: bool,
+    pub force_finalize_after_secs: u64,
+    pub crank_lease_slots: u64,
+    pub batch_investor_paid_events: bool,
+    pub creator_vesting_secs: u64,
+    pub compound_bps: u16,
+    pub buyback_burn_creator: bool,
+    pub buyback_max_slippage_bps: u16,
+    pub referral_wallet: Pubkey,
+    pub referral_bps: u16,
+    pub stake_program_id: Pubkey,
+    pub stake_layout: StakeLayout,
+    pub day_gate_slots: u64,
+    pub epoch_offset_secs: Option<u32>,
+    pub tranches_per_day: u8,
+    pub max_weight_bps: u16,
+    pub max_investors_per_page: u16,
+    pub max_pages_per_day: u32,
+    pub attestation_program: Pubkey,
+    pub attestation_layout: AttestationLayout,
+    pub reward_routing: RewardRouting,
+    pub cp_amm_entry_points: CpAmmEntryPoints,
+    pub min_tick_distance_from_price: u32,
+    pub cap_carry_days: u8,
+    pub payout_to_stream_escrow: bool,
+    pub payout_dispute_window_secs: u64,
+    pub fund_missing_creator_ata: bool,
+    pub hook_program: Pubkey,
+    pub lock_oracle: Pubkey,
+    pub lock_oracle_max_age_secs: u64,
+    pub unordered_pages: bool,
+    pub param_schedule: Vec<ScheduledParams>,
+    pub unattested_routing: UnattestedRouting,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct PolicyAuthorityChanged {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    /// Realms governance the new authority was verified against, if one was passed
+    pub governance: Option<Pubkey>,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct PolicyTargetsAmended {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub quote_mint: Pubkey,
+    pub base_mint: Pubkey,
+    pub pool: Pubkey,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct PolicyPausedSet {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub authority: Pubkey,
+    pub paused: bool,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct VaultReinitialized {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub authority: Pubkey,
+    pub generation: u16,
+    /// Quote held in the treasury for later days that carries into the new generation
+    pub carried_quote: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct PayoutOverrideSet {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub investor: Pubkey,
+    /// None when the override was removed
+    pub min_payout_lamports: Option<u64>,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct WeightOverrideSet {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub investor: Pubkey,
+    /// None when the override was removed
+    pub weight: Option<u64>,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct StaticAccountsCached {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    pub static_accounts: StaticAccounts,
     pub timestamp: u64,
 }
 
 #[event]
 pub struct PreflightVerificationCompleted {
+    pub schema_version: u8,
+    pub vault_seed: String,
     pub pool: Pubkey,
     pub quote_mint: Pubkey,
     pub tick_lower: i32,
@@ -61,3 +714,46 @@ pub struct PreflightVerificationCompleted {
     pub simulation_verified: bool,
     pub timestamp: u64,
 }
+
+#[event]
+pub struct RewardsClaimed {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub reward_mint: Pubkey,
+    pub decimals: u8,
+    /// Index of the reward in the CP-AMM pool's reward_infos
+    pub reward_index: u8,
+    pub amount: u64,
+    pub routing: RewardRouting,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct CreatorRewardsPaid {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub reward_mint: Pubkey,
+    pub decimals: u8,
+    pub creator_reward_account: Pubkey,
+    pub amount: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct InvestorRewardsPage {
+    pub schema_version: u8,
+    pub vault_seed: String,
+    /// Mint every amount below is denominated in
+    pub reward_mint: Pubkey,
+    pub decimals: u8,
+    pub day_epoch: u64,
+    pub page_index: u64,
+    pub investors_paid: u32,
+    pub distributed: u64,
+    /// Rewards the round apportions across all of its pages
+    pub round_pool: u64,
+    pub round_complete: bool,
+    pub timestamp: u64,
+}
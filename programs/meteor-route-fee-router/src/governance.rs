@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+/// SPL Governance (Realms) program ID (mainnet)
+pub const SPL_GOVERNANCE_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+/// `GovernanceAccountType` tags of governance accounts: account/program/mint/token governance, v1 and v2
+pub const GOVERNANCE_ACCOUNT_TYPES: [u8; 8] = [3, 4, 9, 10, 18, 19, 20, 21];
+
+/// Smallest governance account: account_type + realm + governance seed / governed account
+pub const GOVERNANCE_MIN_LEN: usize = 1 + 32 + 32;
+
+/// Whether an account with this owner and data is a Realms governance account
+pub fn is_governance_account(owner: &Pubkey, data: &[u8]) -> bool {
+    *owner == SPL_GOVERNANCE_PROGRAM_ID
+        && data.len() >= GOVERNANCE_MIN_LEN
+        && GOVERNANCE_ACCOUNT_TYPES.contains(&data[0])
+}
+
+/// Native SOL treasury PDA of a governance, which also signs executed proposal transactions
+pub fn native_treasury(governance: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"native-treasury", governance.as_ref()], &SPL_GOVERNANCE_PROGRAM_ID).0
+}
+
+/// Whether `authority` is one of the PDAs `governance` signs with when it executes a proposal
+pub fn governance_signs_for(governance: &Pubkey, authority: &Pubkey) -> bool {
+    authority == governance || *authority == native_treasury(governance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn governance_data(account_type: u8) -> Vec<u8> {
+        let mut data = vec![0u8; 236];
+        data[0] = account_type;
+        data
+    }
+
+    #[test]
+    fn test_is_governance_account() {
+        assert!(is_governance_account(&SPL_GOVERNANCE_PROGRAM_ID, &governance_data(18)));
+        assert!(is_governance_account(&SPL_GOVERNANCE_PROGRAM_ID, &governance_data(3)));
+        // Realm (16) and proposal (14) accounts are owned by governance too but never sign
+        assert!(!is_governance_account(&SPL_GOVERNANCE_PROGRAM_ID, &governance_data(16)));
+        assert!(!is_governance_account(&SPL_GOVERNANCE_PROGRAM_ID, &governance_data(14)));
+        assert!(!is_governance_account(&Pubkey::new_unique(), &governance_data(18)));
+        assert!(!is_governance_account(&SPL_GOVERNANCE_PROGRAM_ID, &[18u8; GOVERNANCE_MIN_LEN - 1]));
+    }
+
+    #[test]
+    fn test_governance_signs_for() {
+        let governance = Pubkey::new_unique();
+        assert!(governance_signs_for(&governance, &governance));
+        assert!(governance_signs_for(&governance, &native_treasury(&governance)));
+        assert!(!governance_signs_for(&governance, &native_treasury(&Pubkey::new_unique())));
+        assert!(!governance_signs_for(&governance, &Pubkey::new_unique()));
+    }
+}
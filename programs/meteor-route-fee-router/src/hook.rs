@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+use crate::{error::FeeRouterError, state::PolicyPda};
+
+/// Seed of the per-vault PDA that signs hook calls; it owns nothing, so the hook can't reuse it
+pub const HOOK_AUTHORITY_SEED: &[u8] = b"hook_authority";
+
+/// Anchor discriminator of `on_distribution_finalized`, `sha256("global:on_distribution_finalized")[..8]`
+pub const HOOK_DISCRIMINATOR: [u8; 8] = [0x92, 0x55, 0xf1, 0x63, 0x99, 0x69, 0x72, 0x99];
+
+/// Payload passed to `policy.hook_program` once a day is finalized
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DistributionFinalizedPayload {
+    pub vault_seed: String,
+    pub policy: Pubkey,
+    pub quote_mint: Pubkey,
+    pub day_epoch: u64,
+    pub total_claimed: u128,
+    pub total_distributed: u128,
+    pub creator_payout: u128,
+    pub carry: u64,
+    pub timestamp: u64,
+}
+
+/// Accounts of the post-finalize hook call (policy.hook_program)
+pub(crate) struct DistributionHook<'info> {
+    pub program: AccountInfo<'info>,
+    /// [vault_seed, "hook_authority"] PDA; signs so the hook can tell the call came from the router
+    pub authority: AccountInfo<'info>,
+    pub authority_bump: u8,
+    pub policy: AccountInfo<'info>,
+    /// Hook-owned state the crank passes through writable
+    pub state: Option<AccountInfo<'info>>,
+}
+
+impl<'info> DistributionHook<'info> {
+    /// Hook accounts the policy requires; `None` while `policy.hook_program` is unset
+    pub(crate) fn from_policy(
+        vault_seed: &str,
+        policy: &Account<'info, PolicyPda>,
+        program: Option<&UncheckedAccount<'info>>,
+        authority: Option<&UncheckedAccount<'info>>,
+        state: Option<&UncheckedAccount<'info>>,
+    ) -> Result<Option<Self>> {
+        if policy.hook_program == Pubkey::default() {
+            return Ok(None);
+        }
+        let (Some(program), Some(authority)) = (program, authority) else {
+            return err!(FeeRouterError::MissingRequiredInput);
+        };
+        let (expected, authority_bump) =
+            Pubkey::find_program_address(&[vault_seed.as_bytes(), HOOK_AUTHORITY_SEED], &crate::ID);
+        require_keys_eq!(authority.key(), expected, FeeRouterError::InvalidHookProgram);
+        Ok(Some(Self {
+            program: program.to_account_info(),
+            authority: authority.to_account_info(),
+            authority_bump,
+            policy: policy.to_account_info(),
+            state: state.map(|state| state.to_account_info()),
+        }))
+    }
+}
+
+/// Hook instruction: [hook_authority (signer), policy_pda, hook_state (writable, optional)]
+pub fn hook_ix(
+    hook_program: Pubkey,
+    hook_authority: Pubkey,
+    policy: Pubkey,
+    hook_state: Option<Pubkey>,
+    payload: &DistributionFinalizedPayload,
+) -> Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(hook_authority, true),
+        AccountMeta::new_readonly(policy, false),
+    ];
+    if let Some(state) = hook_state {
+        accounts.push(AccountMeta::new(state, false));
+    }
+    let mut data = HOOK_DISCRIMINATOR.to_vec();
+    payload.serialize(&mut data)?;
+    Ok(Instruction {
+        program_id: hook_program,
+        accounts,
+        data,
+    })
+}
+
+/// Call the hook with the finalized day's payload; a failing hook fails the finalize
+pub(crate) fn invoke_hook(hook: &DistributionHook, payload: &DistributionFinalizedPayload) -> Result<()> {
+    let ix = hook_ix(
+        hook.program.key(),
+        hook.authority.key(),
+        hook.policy.key(),
+        hook.state.as_ref().map(|state| state.key()),
+        payload,
+    )?;
+    let mut infos = vec![hook.authority.clone(), hook.policy.clone()];
+    infos.extend(hook.state.clone());
+    infos.push(hook.program.clone());
+    let seeds = &[payload.vault_seed.as_bytes(), HOOK_AUTHORITY_SEED, &[hook.authority_bump]];
+    invoke_signed(&ix, &infos, &[&seeds[..]])?;
+    msg!("Called distribution hook {} for day {}", hook.program.key(), payload.day_epoch);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    #[test]
+    fn test_hook_ix() {
+        assert_eq!(
+            HOOK_DISCRIMINATOR,
+            hash(b"global:on_distribution_finalized").to_bytes()[..8]
+        );
+
+        let payload = DistributionFinalizedPayload {
+            vault_seed: "vault".to_string(),
+            policy: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            day_epoch: 7,
+            total_claimed: 1_000,
+            total_distributed: 700,
+            creator_payout: 300,
+            carry: 0,
+            timestamp: 86_400 * 7,
+        };
+        let (program, authority, state) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = hook_ix(program, authority, payload.policy, Some(state), &payload).unwrap();
+        assert_eq!(ix.program_id, program);
+        assert!(ix.accounts[0].is_signer && !ix.accounts[0].is_writable);
+        assert!(ix.accounts[2].is_writable);
+        assert_eq!(&ix.data[..8], &HOOK_DISCRIMINATOR);
+        assert_eq!(DistributionFinalizedPayload::try_from_slice(&ix.data[8..]).unwrap(), payload);
+        assert_eq!(hook_ix(program, authority, payload.policy, None, &payload).unwrap().accounts.len(), 2);
+    }
+}
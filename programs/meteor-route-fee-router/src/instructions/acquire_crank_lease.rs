@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{CrankLeaseAcquired, EVENT_SCHEMA_VERSION},
+    state::{PolicyPda, ProgressPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct AcquireCrankLease<'info> {
+    /// Cranker taking the lease (permissionless)
+    pub cranker: Signer<'info>,
+
+    /// Policy configuration
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Progress tracking (holds the lease)
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+}
+
+/// Take or renew the crank lease for `policy.crank_lease_slots` slots
+///
+/// While the lease is live only its holder may call `distribute_fees`. Leases are optional: with no
+/// live lease anyone can crank, and an expired lease can be taken by anyone.
+pub fn handler(
+    ctx: Context<AcquireCrankLease>,
+    vault_seed: String,
+) -> Result<()> {
+    let lease_slots = ctx.accounts.policy_pda.crank_lease_slots;
+    require!(lease_slots > 0, FeeRouterError::CrankLeasesDisabled);
+
+    let slot = Clock::get()?.slot;
+    let cranker = ctx.accounts.cranker.key();
+    let lease_expiry_slot = ctx
+        .accounts
+        .progress_pda
+        .acquire_lease(&cranker, slot, lease_slots)?;
+
+    emit_cpi!(CrankLeaseAcquired {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed,
+        cranker,
+        lease_expiry_slot,
+        slot,
+    });
+
+    msg!("Crank lease held by {} until slot {}", cranker, lease_expiry_slot);
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use crate::{
+    error::FeeRouterError,
+    events::{PolicyTargetsAmended, EVENT_SCHEMA_VERSION},
+    state::PolicyPda,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct AmendPolicyTargets<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Created by every position initializer, so an empty account means no position yet
+    /// CHECK: Only its emptiness is read
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump,
+        constraint = position_owner_pda.data_is_empty() @ FeeRouterError::PositionAlreadyInitialized
+    )]
+    pub position_owner_pda: UncheckedAccount<'info>,
+
+    /// New quote mint (SPL Token or Token-2022)
+    #[account(mint::token_program = token_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// New base mint
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// New pool, checked against the mints when the position is initialized
+    /// CHECK: Only the address is recorded
+    pub pool: UncheckedAccount<'info>,
+
+    /// Token program of the quote mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Replace the policy's pool and mints before the honorary position exists
+///
+/// `initialize_policy` records these unverified; the position initializers are the first to check
+/// them against the pool. Once a position exists the treasury and position are bound to them, so
+/// this fails with `PositionAlreadyInitialized`. Static accounts cached for the old pool are cleared.
+pub fn handler(
+    ctx: Context<AmendPolicyTargets>,
+    vault_seed: String,
+) -> Result<()> {
+    // Same checks as initialize_policy
+    if ctx.accounts.quote_mint.key() == ctx.accounts.base_mint.key() {
+        return err!(FeeRouterError::InvalidPoolOrder);
+    }
+
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let policy_pda = &mut ctx.accounts.policy_pda;
+    policy_pda.quote_mint = ctx.accounts.quote_mint.key();
+    policy_pda.base_mint = ctx.accounts.base_mint.key();
+    policy_pda.pool_pubkey = ctx.accounts.pool.key();
+    policy_pda.static_accounts = None;
+    policy_pda.updated_at = current_timestamp;
+
+    emit_cpi!(PolicyTargetsAmended {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        quote_mint: policy_pda.quote_mint,
+        base_mint: policy_pda.base_mint,
+        pool: policy_pda.pool_pubkey,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Policy targets amended: vault_seed={}, pool={}, quote_mint={}, base_mint={}",
+        vault_seed,
+        policy_pda.pool_pubkey,
+        policy_pda.quote_mint,
+        policy_pda.base_mint
+    );
+
+    Ok(())
+}
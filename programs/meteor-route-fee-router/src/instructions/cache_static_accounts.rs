@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{
+    cp_amm::{derive_event_authority, derive_pool_authority},
+    error::FeeRouterError,
+    events::{StaticAccountsCached, EVENT_SCHEMA_VERSION},
+    state::{PolicyPda, PoolKind, StaticAccounts},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct CacheStaticAccounts<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Pool token A vault (DLMM: reserve_x)
+    #[account(token::mint = token_a_mint)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool token B vault (DLMM: reserve_y)
+    #[account(token::mint = token_b_mint)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Program quote treasury token account at the treasury PDA
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"treasury", policy_pda.quote_mint.as_ref()],
+        bump
+    )]
+    pub quote_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: CP-AMM pool authority PDA (derived and checked for CP-AMM vaults)
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: CP-AMM event authority PDA (derived and checked for CP-AMM vaults)
+    pub cp_amm_event_authority: UncheckedAccount<'info>,
+}
+
+/// Pin the crank's static accounts in the policy
+///
+/// The crank then compares them by key, and keepers can keep them in an address lookup table
+/// so more investor accounts fit in each transaction.
+pub fn handler(
+    ctx: Context<CacheStaticAccounts>,
+    vault_seed: String,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let policy_pda = &ctx.accounts.policy_pda;
+
+    // The mints must be the policy's quote/base pair, in either order
+    let (mint_a, mint_b) = (ctx.accounts.token_a_mint.key(), ctx.accounts.token_b_mint.key());
+    require!(
+        (mint_a == policy_pda.quote_mint && mint_b == policy_pda.base_mint)
+            || (mint_b == policy_pda.quote_mint && mint_a == policy_pda.base_mint),
+        FeeRouterError::PoolAccountMismatch
+    );
+
+    if policy_pda.pool_kind == PoolKind::CpAmm {
+        require!(
+            ctx.accounts.pool_authority.key() == derive_pool_authority(&policy_pda.cp_amm_program_id)
+                && ctx.accounts.cp_amm_event_authority.key()
+                    == derive_event_authority(&policy_pda.cp_amm_program_id),
+            FeeRouterError::InvalidCpAmmPda
+        );
+    }
+
+    let static_accounts = StaticAccounts {
+        token_a_vault: ctx.accounts.token_a_vault.key(),
+        token_b_vault: ctx.accounts.token_b_vault.key(),
+        token_a_mint: mint_a,
+        token_b_mint: mint_b,
+        quote_treasury: ctx.accounts.quote_treasury.key(),
+        pool_authority: ctx.accounts.pool_authority.key(),
+        cp_amm_event_authority: ctx.accounts.cp_amm_event_authority.key(),
+    };
+
+    let policy_pda = &mut ctx.accounts.policy_pda;
+    policy_pda.static_accounts = Some(static_accounts);
+    policy_pda.updated_at = current_timestamp;
+
+    emit_cpi!(StaticAccountsCached {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        static_accounts,
+        timestamp: current_timestamp,
+    });
+
+    msg!("Static crank accounts cached for vault_seed={}", vault_seed);
+
+    Ok(())
+}
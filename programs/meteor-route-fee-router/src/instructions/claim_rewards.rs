@@ -0,0 +1,469 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    cp_amm::{derive_event_authority, derive_pool_authority},
+    error::FeeRouterError,
+    event_cpi::EventCpi,
+    events::{CrankMismatch, CreatorRewardsPaid, InvestorRewardsPage, RewardsClaimed, EVENT_SCHEMA_VERSION},
+    instructions::distribute_fees::{accounts_per_investor, quote_ata_status, token_amount, QuoteAtaStatus},
+    investor_page::{InvestorPageView, PageDomain},
+    locker::{LockerSources, StreamLocked},
+    state::{
+        DistributionMath, InvestorFeePositionOwnerPda, LockerTestConfig, NetworkConfig, PolicyPda, PoolKind, ProgressPda,
+        RewardRoundPda, RewardRouting, WeightOverridesPda,
+    },
+    InvestorPage,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct ClaimRewards<'info> {
+    /// Crank caller (permissionless; pays for the reward treasury and round on first use)
+    #[account(mut)]
+    pub crank_caller: Signer<'info>,
+
+    /// Policy configuration
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        constraint = !policy_pda.paused @ FeeRouterError::PolicyPaused
+    )]
+    pub policy_pda: Box<Account<'info, PolicyPda>>,
+
+    /// Progress tracking; investor rounds pay by its locked snapshot
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Box<Account<'info, ProgressPda>>,
+
+    /// Position owner PDA (position and reward treasury authority)
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump,
+        constraint = position_owner_pda.pool_kind == PoolKind::CpAmm @ FeeRouterError::RewardsUnsupported
+    )]
+    pub position_owner_pda: Box<Account<'info, InvestorFeePositionOwnerPda>>,
+
+    /// CHECK: CP-AMM pool; must be the policy's pool, loaded by CP-AMM
+    #[account(mut, address = policy_pda.pool_pubkey @ FeeRouterError::PoolAccountMismatch)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: Honorary position; must be the position owner's, loaded by CP-AMM
+    #[account(mut, address = position_owner_pda.position_pubkey @ FeeRouterError::InvalidPositionOwner)]
+    pub position: UncheckedAccount<'info>,
+
+    /// Position NFT account (proves ownership)
+    #[account(
+        token::authority = position_owner_pda,
+        constraint = position_owner_pda.holds_position_nft(&position_nft_account) @ FeeRouterError::InvalidPositionNftAccount
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: CP-AMM pool authority PDA, derived from the policy's cp_amm_program_id
+    #[account(address = derive_pool_authority(&policy_pda.cp_amm_program_id) @ FeeRouterError::InvalidCpAmmPda)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Reward vault of `reward_index`; CP-AMM checks it against the pool's reward_infos
+    #[account(mut)]
+    pub reward_vault: UncheckedAccount<'info>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Reward treasury the position's rewards are claimed into
+    #[account(
+        init_if_needed,
+        payer = crank_caller,
+        seeds = [vault_seed.as_bytes(), b"reward_treasury", reward_mint.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program,
+    )]
+    pub reward_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Investor round state of the reward mint
+    #[account(
+        init_if_needed,
+        payer = crank_caller,
+        space = RewardRoundPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"reward_round", reward_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_round: Box<Account<'info, RewardRoundPda>>,
+
+    /// Creator's reward token account, required with RewardRouting::Creator
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::token_program = token_program,
+    )]
+    pub creator_reward_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Fixed investor weights, required with investor pages when policy.weight_overrides is set
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"weight_overrides"],
+        bump
+    )]
+    pub weight_overrides: Option<Box<Account<'info, WeightOverridesPda>>>,
+
+    /// Local-testing locker bypass; omitted on real clusters
+    #[account(seeds = [LockerTestConfig::SEED], bump)]
+    pub locker_test_config: Option<Box<Account<'info, LockerTestConfig>>>,
+
+    /// Program-wide cluster selection; while uninitialized lockers are read at Streamflow's mainnet ID
+    /// CHECK: Seeds checked; deserialized with NetworkConfig::load
+    #[account(seeds = [NetworkConfig::SEED], bump)]
+    pub network_config: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: Must be the policy's cp_amm_program_id
+    #[account(address = policy_pda.cp_amm_program_id @ FeeRouterError::InvalidCpAmmProgram)]
+    pub cp_amm_program: UncheckedAccount<'info>,
+    /// CHECK: CP-AMM event authority PDA required for CPI events
+    #[account(address = derive_event_authority(&policy_pda.cp_amm_program_id) @ FeeRouterError::InvalidCpAmmPda)]
+    pub cp_amm_event_authority: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim the honorary position's CP-AMM rewards and route them per policy.reward_routing
+///
+/// Creator routing claims and forwards the whole reward treasury to `creator_reward_account`.
+/// Investor routing starts a round with a claim, then pays the treasury balance pro-rata by the
+/// day's locked snapshot across investor pages, like `distribute_fees` pays fees. Calls that
+/// continue an open round claim nothing, so the round's pool stays fixed.
+///
+/// remaining_accounts: per investor [stream, reward token account (mut), investor], plus the
+/// investor's attestation PDA while the policy gates payouts on one.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>,
+    vault_seed: String,
+    reward_index: u8,
+    investor_pages: Vec<InvestorPage>,
+    is_final_page: bool,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let events = EventCpi {
+        authority: ctx.accounts.event_authority.to_account_info(),
+        bump: ctx.bumps.event_authority,
+    };
+    let seeds = &[
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[ctx.bumps.position_owner_pda],
+    ];
+    let signer = &[&seeds[..]];
+    let routing = ctx.accounts.policy_pda.reward_routing;
+    let day_epoch = ctx.accounts.progress_pda.day_epoch;
+    let reward_mint_key = ctx.accounts.reward_mint.key();
+    let decimals = ctx.accounts.reward_mint.decimals;
+
+    require!(
+        routing == RewardRouting::Investors || investor_pages.is_empty(),
+        FeeRouterError::InvalidRewardRound
+    );
+    if ctx.accounts.reward_round.vault_seed.is_empty() {
+        ctx.accounts.reward_round.vault_seed = vault_seed.clone();
+        ctx.accounts.reward_round.reward_mint = reward_mint_key;
+    }
+
+    // A new round (or any creator call) starts with a claim
+    let continuing = routing == RewardRouting::Investors && ctx.accounts.reward_round.is_open(day_epoch);
+    if !continuing {
+        if routing == RewardRouting::Investors {
+            require!(ctx.accounts.progress_pda.snapshot_complete, FeeRouterError::SnapshotRequired);
+        }
+        let claimed = claim_cp_amm_reward(&ctx, reward_index, signer)?;
+        events.emit(RewardsClaimed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.clone(),
+            reward_mint: reward_mint_key,
+            decimals,
+            reward_index,
+            amount: claimed,
+            routing,
+            timestamp: current_timestamp,
+        })?;
+        msg!("Claimed {} reward tokens (index {})", claimed, reward_index);
+    }
+
+    let treasury_balance = token_amount(&ctx.accounts.reward_treasury.to_account_info())?;
+    match routing {
+        RewardRouting::Creator => {
+            let creator_reward_account = ctx
+                .accounts
+                .creator_reward_account
+                .as_ref()
+                .ok_or(FeeRouterError::MissingRequiredInput)?;
+            if treasury_balance > 0 {
+                transfer_checked(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: ctx.accounts.reward_treasury.to_account_info(),
+                            mint: ctx.accounts.reward_mint.to_account_info(),
+                            to: creator_reward_account.to_account_info(),
+                            authority: ctx.accounts.position_owner_pda.to_account_info(),
+                        },
+                        signer,
+                    ),
+                    treasury_balance,
+                    decimals,
+                )?;
+            }
+            events.emit(CreatorRewardsPaid {
+                schema_version: EVENT_SCHEMA_VERSION,
+                vault_seed: vault_seed.clone(),
+                reward_mint: reward_mint_key,
+                decimals,
+                creator_reward_account: creator_reward_account.key(),
+                amount: treasury_balance,
+                timestamp: current_timestamp,
+            })?;
+            ctx.accounts.reward_round.active = false;
+            msg!("Paid {} reward tokens to the creator", treasury_balance);
+        }
+        RewardRouting::Investors => {
+            if !continuing {
+                ctx.accounts.reward_round.start(day_epoch, treasury_balance);
+                msg!("Started reward round: epoch={}, pool={}", day_epoch, treasury_balance);
+            }
+            pay_investor_pages(
+                ctx.accounts,
+                ctx.remaining_accounts,
+                &vault_seed,
+                &investor_pages,
+                is_final_page,
+                signer,
+                &events,
+                current_timestamp,
+            )?;
+        }
+    }
+
+    ctx.accounts.reward_round.updated_at = current_timestamp;
+    Ok(())
+}
+
+/// CP-AMM claim_reward CPI into the reward treasury; returns the amount received
+fn claim_cp_amm_reward<'info>(
+    ctx: &Context<'_, '_, '_, 'info, ClaimRewards<'info>>,
+    reward_index: u8,
+    signer: &[&[&[u8]]],
+) -> Result<u64> {
+    let treasury = ctx.accounts.reward_treasury.to_account_info();
+    let before = token_amount(&treasury)?;
+    cp_amm::cpi::claim_reward(
+        CpiContext::new_with_signer(
+            ctx.accounts.cp_amm_program.to_account_info(),
+            cp_amm::cpi::accounts::ClaimRewardCtx {
+                pool_authority: ctx.accounts.pool_authority.to_account_info(),
+                pool: ctx.accounts.pool.to_account_info(),
+                position: ctx.accounts.position.to_account_info(),
+                reward_vault: ctx.accounts.reward_vault.to_account_info(),
+                reward_mint: ctx.accounts.reward_mint.to_account_info(),
+                user_token_account: treasury.clone(),
+                position_nft_account: ctx.accounts.position_nft_account.to_account_info(),
+                owner: ctx.accounts.position_owner_pda.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                event_authority: ctx.accounts.cp_amm_event_authority.to_account_info(),
+                program: ctx.accounts.cp_amm_program.to_account_info(),
+            },
+            signer,
+        ),
+        reward_index,
+        0, // skip_reward: always receive the tokens
+    )?;
+    Ok(token_amount(&treasury)?.saturating_sub(before))
+}
+
+/// Pay the open round's pool to the investors of `investor_pages`
+///
+/// Investors are weighed exactly as in `distribute_fees` (snapshot time, weight overrides and the
+/// day's weight cap). A share that can't be delivered (ignored stream, missing attestation,
+/// unusable reward account) stays in the treasury for the next round.
+#[allow(clippy::too_many_arguments)]
+fn pay_investor_pages<'info>(
+    accounts: &mut ClaimRewards<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    vault_seed: &str,
+    investor_pages: &[InvestorPage],
+    is_final_page: bool,
+    signer: &[&[&[u8]]],
+    events: &EventCpi<'info>,
+    current_timestamp: u64,
+) -> Result<()> {
+    let progress = &accounts.progress_pda;
+    let policy = &accounts.policy_pda;
+    let attestation = policy.attestation_gate();
+    let stride = accounts_per_investor(attestation.as_ref());
+    require!(
+        remaining_accounts.len() == investor_pages.iter().map(|p| p.investors.len()).sum::<usize>() * stride,
+        FeeRouterError::RemainingAccountsLayoutInvalid
+    );
+    let weight_overrides = if policy.weight_overrides {
+        Some(
+            accounts
+                .weight_overrides
+                .as_deref()
+                .ok_or(FeeRouterError::MissingRequiredInput)?,
+        )
+    } else {
+        None
+    };
+    let relax_checks = accounts
+        .locker_test_config
+        .as_ref()
+        .is_some_and(|config| config.relax_locker_checks);
+    let streamflow_program_id = NetworkConfig::streamflow_program_id(NetworkConfig::load(&accounts.network_config)?.as_ref());
+    let lockers = LockerSources::from_policy(policy, streamflow_program_id).with_relaxed_checks(relax_checks);
+    let weight_cap = progress.weight_cap();
+    let total_weight = weight_cap
+        .total_weight(progress.snapshot_total_locked as u128)
+        .map_err(FeeRouterError::from)?;
+    let reward_mint_key = accounts.reward_mint.key();
+    let decimals = accounts.reward_mint.decimals;
+
+    let round = &mut accounts.reward_round;
+    let mut investor_accounts = remaining_accounts.chunks(stride);
+    for (page_number, page) in investor_pages.iter().enumerate() {
+        if page.page_index != round.pagination_cursor {
+            let mismatch = CrankMismatch::PageIndex { expected: round.pagination_cursor, provided: page.page_index };
+            return Err(mismatch.reject(vault_seed, round.day_epoch, current_timestamp, FeeRouterError::InvalidRewardRound));
+        }
+        let computed_hash = page.computed_hash(&PageDomain::new(crate::ID.as_array(), vault_seed, round.day_epoch, progress.generation));
+        if page.page_hash != computed_hash {
+            let mismatch = CrankMismatch::PageHash {
+                page_index: page.page_index,
+                expected: computed_hash,
+                provided: page.page_hash,
+            };
+            return Err(mismatch.reject(vault_seed, round.day_epoch, current_timestamp, FeeRouterError::InvalidRewardRound));
+        }
+        require!(
+            policy.page_within_limits(page.page_index, page.investors.len()),
+            FeeRouterError::PageLimitExceeded
+        );
+
+        let page_accounts: Vec<&[AccountInfo<'info>]> =
+            investor_accounts.by_ref().take(page.investors.len()).collect();
+        let stream_locked = page_accounts
+            .iter()
+            .zip(page.investors.iter())
+            .map(|(investor, investor_data)| {
+                let weight = weight_overrides.map_or(0, |table| table.weight_for(&investor_data.investor));
+                lockers
+                    .read_investor_locked(&investor[0], investor_data, progress.snapshot_ts)?
+                    .with_weight(weight)
+            })
+            .collect::<Result<Vec<StreamLocked>>>()?;
+        let locked_amounts: Vec<u64> = stream_locked
+            .iter()
+            .map(|entry| match entry {
+                StreamLocked::Locked(amount) => *amount,
+                StreamLocked::Ignored(_) => 0,
+            })
+            .collect();
+        let payout_weights = locked_amounts
+            .iter()
+            .map(|locked| weight_cap.payout_weight(*locked as u128))
+            .collect::<core::result::Result<Vec<u128>, _>>()
+            .map_err(FeeRouterError::from)?;
+        let page_weight = payout_weights
+            .iter()
+            .try_fold(0u128, |sum, weight| sum.checked_add(*weight))
+            .ok_or(FeeRouterError::Overflow)?;
+        let allocation = DistributionMath::calculate_page_allocation(
+            round.weight_processed,
+            page_weight,
+            total_weight,
+            round.pool as u128,
+        ).map_err(FeeRouterError::from)?;
+        let payouts = DistributionMath::apportion_largest_remainder(
+            &payout_weights,
+            total_weight,
+            round.pool as u128,
+            allocation,
+        ).map_err(FeeRouterError::from)?;
+
+        let mut page_distributed = 0u64;
+        let mut investors_paid = 0u32;
+        for (i, investor_data) in page.investors.iter().enumerate() {
+            round.locked_hash = ProgressPda::fold_locked_entry(
+                &round.locked_hash,
+                &investor_data.stream,
+                &investor_data.investor,
+                locked_amounts[i],
+            );
+            let payout = payouts[i] as u64;
+            if payout == 0 {
+                continue;
+            }
+            let reward_account = &page_accounts[i][1];
+            if let Some(gate) = attestation.as_ref() {
+                if !gate.is_attested(&page_accounts[i][3], &investor_data.investor, current_timestamp)? {
+                    msg!("Investor {} has no live attestation; reward share stays in treasury", investor_data.investor);
+                    continue;
+                }
+            }
+            if quote_ata_status(reward_account, &reward_mint_key, &investor_data.investor) != QuoteAtaStatus::Valid {
+                msg!("Investor {} has no usable reward account; reward share stays in treasury", investor_data.investor);
+                continue;
+            }
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: accounts.reward_treasury.to_account_info(),
+                        mint: accounts.reward_mint.to_account_info(),
+                        to: reward_account.clone(),
+                        authority: accounts.position_owner_pda.to_account_info(),
+                    },
+                    signer,
+                ),
+                payout,
+                decimals,
+            )?;
+            page_distributed = page_distributed.checked_add(payout).ok_or(FeeRouterError::Overflow)?;
+            investors_paid += 1;
+        }
+
+        round.weight_processed = round.weight_processed
+            .checked_add(page_weight)
+            .ok_or(FeeRouterError::Overflow)?;
+        round.distributed = round.distributed
+            .checked_add(page_distributed)
+            .ok_or(FeeRouterError::Overflow)?;
+        round.pagination_cursor = round.pagination_cursor
+            .checked_add(1)
+            .ok_or(FeeRouterError::Overflow)?;
+        let round_complete = is_final_page && page_number + 1 == investor_pages.len();
+        if round_complete {
+            round.complete(&progress.snapshot_locked_hash)?;
+        }
+
+        events.emit(InvestorRewardsPage {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.to_string(),
+            reward_mint: reward_mint_key,
+            decimals,
+            day_epoch: round.day_epoch,
+            page_index: page.page_index,
+            investors_paid,
+            distributed: page_distributed,
+            round_pool: round.pool,
+            round_complete,
+            timestamp: current_timestamp,
+        })?;
+        msg!(
+            "Reward page {}: paid {} to {} investors",
+            page.page_index,
+            page_distributed,
+            investors_paid
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{EscrowedPayoutClawedBack, EVENT_SCHEMA_VERSION},
+    state::{PayoutEscrowPda, PolicyPda, ProgressPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String, investor: Pubkey)]
+pub struct ClawBackEscrowedPayout<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"payout_escrow", investor.as_ref()],
+        bump
+    )]
+    pub payout_escrow: Account<'info, PayoutEscrowPda>,
+}
+
+/// Take back an investor's escrowed payout for `day_epoch` while its dispute window is open
+///
+/// The quote never left the treasury; it joins the next day's investor pool through the capped
+/// excess rollover. Only allowed between distribution days, so the amount can't be counted
+/// against a day that is already paying out.
+pub fn handler(
+    ctx: Context<ClawBackEscrowedPayout>,
+    vault_seed: String,
+    investor: Pubkey,
+    day_epoch: u64,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let progress_pda = &mut ctx.accounts.progress_pda;
+    require!(
+        progress_pda.day_finalized_flag || progress_pda.first_page_pending(),
+        FeeRouterError::DistributionInProgress
+    );
+
+    let payout_escrow = &mut ctx.accounts.payout_escrow;
+    let amount = payout_escrow
+        .claw_back(day_epoch, current_timestamp)
+        .ok_or(FeeRouterError::DisputeWindowClosed)?;
+    payout_escrow.updated_at = current_timestamp;
+    progress_pda.roll_capped_excess(amount)?;
+    progress_pda.updated_at = current_timestamp;
+
+    emit_cpi!(EscrowedPayoutClawedBack {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        investor,
+        day_epoch,
+        amount,
+        total_escrowed: payout_escrow.total_escrowed,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Clawed back {} escrowed for investor {} on day {}",
+        amount,
+        investor,
+        day_epoch
+    );
+
+    Ok(())
+}
@@ -1,23 +1,92 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{
+        burn, close_account, transfer_checked, Burn, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
 };
 use cp_amm::{
-    program::CpAmm,
+    activation_handler::ActivationHandler,
     state::{Pool, Position},
 };
 
 use crate::{
-    cp_amm::CP_AMM_PROGRAM_ID,
+    attestation::AttestationGate,
+    cp_amm::{
+        derive_event_authority, derive_pool_authority, load_account, pool_fee_config_hash, quote_only_liquidity,
+        simulate_quote_to_base, spot_base_out, validate_quote_only_fee_mode, validate_quote_only_position,
+    },
+    cp_amm_entry_points,
+    dlmm,
+    raydium_clmm,
+    whirlpool,
     error::FeeRouterError,
-    events::{QuoteFeesClaimed, InvestorPayoutPage, CreatorPayoutDayClosed},
-    state::{InvestorFeePositionOwnerPda, PolicyPda, ProgressPda, DistributionMath},
-    streamflow::{STREAMFLOW_PROGRAM_ID, parse_streamflow_account, validate_stream_for_investor, calculate_locked_amount},
-    InvestorPage,
+    events::{
+        EVENT_SCHEMA_VERSION, BaseFeeObserved, InvestorSkipped, SkipReason,
+        QuoteFeesClaimed, InvestorAtaMissing, InvestorPayoutPage, CreatorPayoutDayClosed, CreatorPayoutDeferred,
+        CreatorPayoutStreamed, FeesCompounded, BuybackExecuted, ReferralFeePaid,
+        ProtocolFeeCollected, CrankMismatch,
+        DistributionDeferred, ZeroLockedDayClosed,
+        FailedPayoutRecorded, InvestorPaid, PayoutEscrowed, InvestorPaidBatch, InvestorPayout, PayoutFailureReason, StreamIgnored,
+    },
+    layout::{
+        read_pubkey, read_u64, TOKEN_ACCOUNT_AMOUNT_OFFSET, TOKEN_ACCOUNT_STATE_FROZEN, TOKEN_ACCOUNT_STATE_OFFSET,
+    },
+    state::{FailedPayoutsPda, InvestorFeePositionOwnerPda, LockerTestConfig, NetworkConfig, PaidBitmapPda, PayoutEscrowPda, PayoutOverridesPda, StaticAccounts, WeightOverridesPda, CrankLogEntry, CrankLogPda, PolicyPda, PoolKind, ProgressPda, ProtocolConfig, DistributionMath, UnattestedRouting, WeightCap},
+    locker::{LockerSources, StreamLocked},
+    event_cpi::EventCpi,
+    hook::{invoke_hook, DistributionFinalizedPayload, DistributionHook},
+    memo::{emit_payout_memo, MEMO_PROGRAM_ID},
+    streamflow::{
+        self, CreateStreamAccounts, CreateStreamArgs, CREATOR_STREAM_SEED, STREAMFLOW_CONTRACT_LEN,
+        STREAMFLOW_FEE_ORACLE, STREAMFLOW_PROGRAM_ID, STREAMFLOW_TREASURY, STREAMFLOW_WITHDRAWOR,
+        TopupStreamAccounts,
+    },
+    transfer_fee::transfer_fee_for,
+    investor_page::{InvestorPageView, PageDomain},
+    InvestorData,
 };
 
+/// Compute units kept in hand before each investor: enough for one payout (ATA creation,
+/// memo, transfer, events) and the progress bookkeeping of a suspended call
+pub const PAGE_RESUME_COMPUTE_RESERVE: u64 = 60_000;
+
+/// Outcome of a `distribute_fees` call, set as its return data so keepers can plan the next
+/// call without fetching `ProgressPda`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DistributeFeesResult {
+    /// Investors handled by this call
+    pub investors_processed: u32,
+    /// Quote paid out to investors by this call
+    pub distributed: u64,
+    /// Quote this call added to carry-over (below-min or no-ATA payouts)
+    pub dust: u64,
+    /// Page the next call starts at
+    pub pagination_cursor: u64,
+    /// Investor the page at the cursor resumes at; 0 unless the page was suspended
+    pub page_investor_cursor: u32,
+    pub pages_processed_today: u64,
+    pub day_finalized: bool,
+}
+
+impl DistributeFeesResult {
+    fn new(progress: &ProgressPda, investors_processed: u64, distributed: u128, dust: u64) -> Self {
+        Self {
+            investors_processed: investors_processed as u32,
+            distributed: distributed as u64,
+            dust,
+            pagination_cursor: progress.pagination_cursor,
+            page_investor_cursor: progress.page_investor_cursor,
+            pages_processed_today: progress.pages_processed_today,
+            day_finalized: progress.day_finalized_flag,
+        }
+    }
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vault_seed: String)]
 pub struct DistributeFees<'info> {
@@ -28,7 +97,8 @@ pub struct DistributeFees<'info> {
     /// Policy configuration
     #[account(
         seeds = [vault_seed.as_bytes(), b"policy"],
-        bump
+        bump,
+        constraint = !policy_pda.paused @ FeeRouterError::PolicyPaused
     )]
     pub policy_pda: Account<'info, PolicyPda>,
 
@@ -40,41 +110,39 @@ pub struct DistributeFees<'info> {
     )]
     pub progress_pda: Account<'info, ProgressPda>,
 
-    /// Position owner PDA
+    /// Position owner PDA (mutable: its spare lamports reimburse investor ATA creation)
     #[account(
+        mut,
         seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
         bump
     )]
     pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
 
-    /// CP-AMM pool account
-    #[account(
-        has_one = token_a_mint,
-        has_one = token_b_mint,
-        has_one = token_a_vault,
-        has_one = token_b_vault,
-    )]
-    pub pool: AccountLoader<'info, Pool>,
+    /// Pool account: CP-AMM pool or DLMM lb_pair, per position_owner_pda.pool_kind
+    /// CHECK: Owner, mints and vaults validated per pool kind in the claim step
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
 
     /// Honorary position account
-    #[account(mut, has_one = pool)]
-    pub position: AccountLoader<'info, Position>,
+    /// CHECK: Owner and pool validated per pool kind in the claim step
+    #[account(mut)]
+    pub position: UncheckedAccount<'info>,
 
-    /// Position NFT account (proves ownership)
+    /// Position NFT account (proves ownership; CP-AMM, Whirlpool and Raydium CLMM)
     #[account(
-        token::authority = position_owner_pda
+        token::authority = position_owner_pda,
+        constraint = position_owner_pda.holds_position_nft(position_nft_account) @ FeeRouterError::InvalidPositionNftAccount
     )]
-    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub position_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    /// CHECK: CP-AMM pool authority PDA
-    #[account(address = cp_amm::const_pda::pool_authority::ID)]
+    /// CHECK: CP-AMM pool authority PDA, derived from the policy's cp_amm_program_id
     pub pool_authority: UncheckedAccount<'info>,
 
-    /// Pool token A vault
+    /// Pool token A vault (DLMM: reserve_x)
     #[account(mut)]
     pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Pool token B vault
+    /// Pool token B vault (DLMM: reserve_y)
     #[account(mut)]
     pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -90,6 +158,7 @@ pub struct DistributeFees<'info> {
     /// Temporary token A account for receiving claimed fees (must exist)
     #[account(
         mut,
+        constraint = token_account_unencumbered(&temp_a_account) @ FeeRouterError::TempAccountEncumbered,
         token::mint = token_a_mint,
         token::authority = position_owner_pda,
         token::token_program = token_a_program,
@@ -99,15 +168,20 @@ pub struct DistributeFees<'info> {
     /// Temporary token B account for receiving claimed fees (must exist)
     #[account(
         mut,
+        constraint = token_account_unencumbered(&temp_b_account) @ FeeRouterError::TempAccountEncumbered,
         token::mint = token_b_mint,
         token::authority = position_owner_pda,
         token::token_program = token_b_program,
     )]
     pub temp_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Program quote treasury token account (must exist; the test ensures ATA creation idempotently)
+    /// Program quote treasury token account at the treasury PDA; only this program's PDA can
+    /// debit it, so no delegate is accepted
     #[account(
         mut,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        constraint = token_account_unencumbered(&quote_treasury) @ FeeRouterError::InvalidTreasury,
         token::mint = quote_mint,
         token::authority = position_owner_pda,
         token::token_program = token_program,
@@ -115,249 +189,1029 @@ pub struct DistributeFees<'info> {
     pub quote_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Creator quote ATA (destination for remainder)
+    /// CHECK: Classified with quote_ata_status at finalize; an unusable account defers the payout
+    #[account(mut)]
+    pub creator_quote_ata: UncheckedAccount<'info>,
+
+    /// Locker (Streamflow by default) program for reading locked amounts
+    /// CHECK: Address must be the cluster's Streamflow or, with an allowlist, an allowlisted locker program
     #[account(
-        mut,
-        token::mint = quote_mint
+        constraint = policy_pda.accepts_locker_program(
+            streamflow_program.key,
+            &NetworkConfig::streamflow_program_id(NetworkConfig::load(&network_config)?.as_ref()),
+        ) @ FeeRouterError::InvalidLockerProgram
     )]
-    pub creator_quote_ata: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    /// Streamflow program for reading locked amounts
-    /// CHECK: This will be validated against known Streamflow program ID  
     pub streamflow_program: UncheckedAccount<'info>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub token_a_program: Interface<'info, TokenInterface>,
     pub token_b_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    #[account(address = CP_AMM_PROGRAM_ID)]
-    pub cp_amm_program: Program<'info, CpAmm>,
+    /// CHECK: Validated against the policy's cp_amm_program_id in the CP-AMM claim step
+    pub cp_amm_program: UncheckedAccount<'info>,
     /// CHECK: CP-AMM event authority PDA required for CPI events
     pub cp_amm_event_authority: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
+
+    /// Temporary wSOL account the creator payout is unwrapped through (policy.unwrap_wsol_creator)
+    #[account(
+        init_if_needed,
+        payer = crank_caller,
+        seeds = [vault_seed.as_bytes(), b"creator_wsol"],
+        bump,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program,
+    )]
+    pub creator_wsol_temp: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Creator wallet receiving unwrapped SOL; must own creator_quote_ata
+    /// CHECK: Checked against the creator_quote_ata owner in finalize_day
+    #[account(mut)]
+    pub creator_wallet: Option<UncheckedAccount<'info>>,
+
+    /// Failed payout ledger, required when policy.continue_on_failure is set
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"failed_payouts"],
+        bump
+    )]
+    pub failed_payouts: Option<Box<Account<'info, FailedPayoutsPda>>>,
+
+    /// Per-day paid bitmap, required when policy.track_paid_investors is set
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"paid_bitmap"],
+        bump
+    )]
+    pub paid_bitmap: Option<Box<Account<'info, PaidBitmapPda>>>,
+
+    /// Per-investor minimum payouts, required when policy.payout_overrides is set
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"payout_overrides"],
+        bump
+    )]
+    pub payout_overrides: Option<Box<Account<'info, PayoutOverridesPda>>>,
+
+    /// Fixed investor weights, required when policy.weight_overrides is set
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"weight_overrides"],
+        bump
+    )]
+    pub weight_overrides: Option<Box<Account<'info, WeightOverridesPda>>>,
+
+    /// Log of recent cranks, required when policy.crank_log is set
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"crank_log"],
+        bump
+    )]
+    pub crank_log: Option<Box<Account<'info, CrankLogPda>>>,
+
+    /// SPL Memo program, required when policy.payout_memos is set
+    /// CHECK: Address checked against the Memo program ID
+    #[account(address = MEMO_PROGRAM_ID @ FeeRouterError::MissingRequiredInput)]
+    pub memo_program: Option<UncheckedAccount<'info>>,
+
+    /// Local-testing locker bypass; omitted on real clusters
+    #[account(seeds = [LockerTestConfig::SEED], bump)]
+    pub locker_test_config: Option<Box<Account<'info, LockerTestConfig>>>,
+
+    /// Metadata account of the day's creator stream, required when policy.creator_vesting_secs is set
+    /// CHECK: Must be the [vault_seed, "creator_stream", day_epoch] PDA; created in finalize_day
+    #[account(mut)]
+    pub creator_stream_metadata: Option<UncheckedAccount<'info>>,
+
+    /// Escrow token account Streamflow opens for the creator stream
+    /// CHECK: Derived from creator_stream_metadata and checked by Streamflow
+    #[account(mut)]
+    pub creator_stream_escrow: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Address checked against the Streamflow withdrawor
+    #[account(mut, address = STREAMFLOW_WITHDRAWOR @ FeeRouterError::InvalidCreatorStream)]
+    pub streamflow_withdrawor: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Address checked against the Streamflow fee oracle
+    #[account(address = STREAMFLOW_FEE_ORACLE @ FeeRouterError::InvalidCreatorStream)]
+    pub streamflow_fee_oracle: Option<UncheckedAccount<'info>>,
+
+    /// Streamflow program the creator stream is created in
+    /// CHECK: Address checked against the Streamflow program ID
+    #[account(address = STREAMFLOW_PROGRAM_ID @ FeeRouterError::InvalidCreatorStream)]
+    pub streamflow_timelock_program: Option<UncheckedAccount<'info>>,
+
+    /// Streamflow treasury, required when policy.payout_to_stream_escrow is set
+    /// CHECK: Address checked against the Streamflow treasury
+    #[account(mut, address = STREAMFLOW_TREASURY @ FeeRouterError::MissingRequiredInput)]
+    pub streamflow_treasury: Option<UncheckedAccount<'info>>,
+
+    /// Streamflow treasury's quote token account
+    /// CHECK: Must be the topped-up stream's streamflow_treasury_tokens; checked per investor
+    #[account(mut)]
+    pub streamflow_treasury_tokens: Option<UncheckedAccount<'info>>,
+
+    pub rent: Option<Sysvar<'info, Rent>>,
+
+    /// Base mint, writable so bought-back base can be burned (policy.buyback_burn_creator)
+    #[account(mut, address = policy_pda.base_mint @ FeeRouterError::InvalidBuyback)]
+    pub buyback_base_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Referral's quote ATA, required when policy.referral_bps is set
+    /// CHECK: Must be the referral wallet's ATA for the quote mint; classified at finalize
+    #[account(mut)]
+    pub referral_quote_ata: Option<UncheckedAccount<'info>>,
+
+    /// Program called after the day is finalized, required when policy.hook_program is set
+    /// CHECK: Address checked against policy.hook_program
+    #[account(address = policy_pda.hook_program @ FeeRouterError::InvalidHookProgram)]
+    pub hook_program: Option<UncheckedAccount<'info>>,
+
+    /// Signer of the hook call
+    /// CHECK: Checked against the [vault_seed, "hook_authority"] PDA in DistributionHook::from_policy
+    pub hook_authority: Option<UncheckedAccount<'info>>,
+
+    /// Hook-owned state passed through writable
+    /// CHECK: Only forwarded to hook_program, which validates it
+    #[account(mut)]
+    pub hook_state: Option<UncheckedAccount<'info>>,
+
+    /// Program-wide protocol fee config; while uninitialized no protocol fee is taken
+    /// CHECK: Seeds checked; deserialized with ProtocolConfig::load
+    #[account(seeds = [ProtocolConfig::SEED], bump)]
+    pub protocol_config: UncheckedAccount<'info>,
+
+    /// Protocol treasury's quote ATA, required while the protocol fee is on
+    /// CHECK: Must be the protocol treasury's ATA for the quote mint; classified before the skim
+    #[account(mut)]
+    pub protocol_fee_ata: Option<UncheckedAccount<'info>>,
+
+    /// Program-wide cluster selection; while uninitialized lockers are read at Streamflow's mainnet ID
+    /// CHECK: Seeds checked; deserialized with NetworkConfig::load
+    #[account(seeds = [NetworkConfig::SEED], bump)]
+    pub network_config: UncheckedAccount<'info>,
+}
+
+impl DistributeFees<'_> {
+    /// Compare the crank's static accounts with the ones pinned in the policy
+    fn check_static_accounts(&self, pinned: &StaticAccounts) -> Result<()> {
+        require!(
+            self.pool.key() == self.policy_pda.pool_pubkey
+                && self.token_a_vault.key() == pinned.token_a_vault
+                && self.token_b_vault.key() == pinned.token_b_vault
+                && self.token_a_mint.key() == pinned.token_a_mint
+                && self.token_b_mint.key() == pinned.token_b_mint
+                && self.quote_treasury.key() == pinned.quote_treasury
+                && self.pool_authority.key() == pinned.pool_authority
+                && self.cp_amm_event_authority.key() == pinned.cp_amm_event_authority,
+            FeeRouterError::StaticAccountMismatch
+        );
+        Ok(())
+    }
+
+    /// Build the call's result and record it in the crank log when the policy keeps one
+    fn finish_call(
+        &mut self,
+        pages: u64,
+        investors_processed: u64,
+        distributed: u128,
+        dust: u64,
+        current_timestamp: u64,
+    ) -> Result<DistributeFeesResult> {
+        let result = DistributeFeesResult::new(&self.progress_pda, investors_processed, distributed, dust);
+        if self.policy_pda.crank_log {
+            let crank_log = self
+                .crank_log
+                .as_mut()
+                .ok_or(FeeRouterError::MissingRequiredInput)?;
+            crank_log.record(CrankLogEntry {
+                caller: self.crank_caller.key(),
+                day_epoch: self.progress_pda.day_epoch,
+                pages: pages as u32,
+                investors_processed: result.investors_processed,
+                distributed: result.distributed,
+                day_finalized: result.day_finalized,
+                timestamp: current_timestamp,
+            });
+        }
+        Ok(result)
+    }
 }
 
-pub fn handler<'a, 'info: 'a>(
+/// Claim the day's quote fees and pay one or more investor pages from them
+///
+/// Every call claims the position's fees. The day's first page fixes the investor pool from
+/// everything claimed that day; later pages continue from `ProgressPda.pagination_cursor` (or take
+/// any committed page under `unordered_pages`). Each investor takes `[stream, quote ATA, investor]`
+/// from the remaining accounts, plus its attestation PDA while the policy has a gate. The call that
+/// completes the day's last page pays the creator remainder and finalizes the day.
+pub fn handler<'a, 'info: 'a, P: InvestorPageView>(
     mut ctx: Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
     vault_seed: String,
-    investor_pages: Vec<InvestorPage>,
+    investor_pages: &[P],
     is_final_page: bool,
-) -> Result<()> {
+) -> Result<DistributeFeesResult> {
     let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let events = EventCpi {
+        authority: ctx.accounts.event_authority.to_account_info(),
+        bump: ctx.bumps.event_authority,
+    };
 
     // Validate position matches PDA record
     if ctx.accounts.position.key() != ctx.accounts.position_owner_pda.position_pubkey {
         return err!(FeeRouterError::InvalidPositionOwner);
     }
 
+    // Pinned static accounts are checked by key
+    if let Some(pinned) = ctx.accounts.policy_pda.static_accounts {
+        ctx.accounts.check_static_accounts(&pinned)?;
+    }
+
+    // A live crank lease reserves the cursor for its holder
+    require!(
+        !ctx.accounts
+            .progress_pda
+            .lease_blocks(&ctx.accounts.crank_caller.key(), Clock::get()?.slot),
+        FeeRouterError::CrankLeaseHeld
+    );
+
     // Check if day is finalized
     if ctx.accounts.progress_pda.day_finalized_flag {
         return err!(FeeRouterError::DayAlreadyFinalized);
     }
 
-    // Check 24h gate for new day
-    if ctx.accounts.progress_pda.is_new_day(current_timestamp) {
-        if !ctx.accounts.progress_pda.can_start_new_day(current_timestamp) {
-            return err!(FeeRouterError::DayGateNotPassed);
-        }
-        ctx.accounts.progress_pda.start_new_day(current_timestamp);
+    // Check the policy's day gate for a new day
+    let day_gate = ctx.accounts.policy_pda.day_gate();
+    if ctx
+        .accounts
+        .progress_pda
+        .roll_day(day_gate, current_timestamp, Clock::get()?.slot)?
+    {
         msg!("Started new distribution day: epoch={}", ctx.accounts.progress_pda.day_epoch);
     }
 
-    // Validate CP-AMM program ID
-    require_keys_eq!(
-        ctx.accounts.cp_amm_program.key(),
-        CP_AMM_PROGRAM_ID,
-        FeeRouterError::InvalidCpAmmProgram
-    );
+    // A started snapshot must be completed before payouts use it
+    if ctx.accounts.progress_pda.snapshot_in_progress() {
+        return err!(FeeRouterError::SnapshotIncomplete);
+    }
 
-    // Validate Streamflow program ID
-    require_keys_eq!(
+    let relax_checks = ctx
+        .accounts
+        .locker_test_config
+        .as_ref()
+        .is_some_and(|config| config.relax_locker_checks);
+    let lockers = LockerSources::from_policy(
+        &ctx.accounts.policy_pda,
         ctx.accounts.streamflow_program.key(),
-        STREAMFLOW_PROGRAM_ID,
-        FeeRouterError::MissingRequiredInput
-    );
-
-    // Validate remaining_accounts: expect 3 accounts per investor (stream + quote ATA + investor authority)
-    let expected_remaining = investor_pages.iter().map(|p| p.investors.len()).sum::<usize>() * 3;
+    )
+    .with_relaxed_checks(relax_checks);
+
+    // Validate remaining_accounts: backend claim accounts first (DLMM only), then
+    // 3 accounts per investor (stream + quote ATA + investor authority), plus the
+    // investor's attestation PDA while the policy gates payouts on one
+    let attestation = ctx.accounts.policy_pda.attestation_gate();
+    let backend_accounts = ctx.accounts.position_owner_pda.pool_kind.claim_remaining_accounts();
+    let expected_remaining = investor_pages.iter().map(|p| p.investor_count()).sum::<usize>()
+        * accounts_per_investor(attestation.as_ref())
+        + backend_accounts;
     require!(
         ctx.remaining_accounts.len() == expected_remaining,
-        FeeRouterError::MissingRequiredInput
+        FeeRouterError::RemainingAccountsLayoutInvalid
     );
+    let investor_accounts = &ctx.remaining_accounts[backend_accounts..];
+
+    // Payout memos need the Memo program passed in
+    let memo_program = if ctx.accounts.policy_pda.payout_memos {
+        Some(
+            ctx.accounts
+                .memo_program
+                .as_ref()
+                .ok_or(FeeRouterError::MissingRequiredInput)?
+                .to_account_info(),
+        )
+    } else {
+        None
+    };
+
+    // Native SOL delivery of the creator payout needs the temp wSOL account and creator wallet
+    let creator_unwrap = if ctx.accounts.policy_pda.unwrap_wsol_creator {
+        let (Some(wsol_temp), Some(creator_wallet)) = (
+            ctx.accounts.creator_wsol_temp.as_ref(),
+            ctx.accounts.creator_wallet.as_ref(),
+        ) else {
+            return err!(FeeRouterError::MissingRequiredInput);
+        };
+        Some(CreatorUnwrap {
+            wsol_temp: wsol_temp.to_account_info(),
+            creator_wallet: creator_wallet.to_account_info(),
+            payer: ctx.accounts.crank_caller.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        })
+    } else {
+        None
+    };
+
+    // A missing creator ATA can only be created for a known wallet; without one the payout defers
+    let creator_ata_funding = match (ctx.accounts.policy_pda.fund_missing_creator_ata, ctx.accounts.creator_wallet.as_ref()) {
+        (true, Some(creator_wallet)) => Some(CreatorAtaFunding {
+            creator_wallet: creator_wallet.to_account_info(),
+            payer: ctx.accounts.crank_caller.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        }),
+        _ => None,
+    };
+
+    // Paying into investors' Streamflow escrow needs the accounts of Streamflow's topup
+    let stream_topup = if ctx.accounts.policy_pda.payout_to_stream_escrow {
+        let (Some(treasury), Some(treasury_tokens), Some(withdrawor), Some(streamflow_program)) = (
+            ctx.accounts.streamflow_treasury.as_ref(),
+            ctx.accounts.streamflow_treasury_tokens.as_ref(),
+            ctx.accounts.streamflow_withdrawor.as_ref(),
+            ctx.accounts.streamflow_timelock_program.as_ref(),
+        ) else {
+            return err!(FeeRouterError::MissingRequiredInput);
+        };
+        Some(StreamTopup {
+            treasury: treasury.to_account_info(),
+            treasury_tokens: treasury_tokens.to_account_info(),
+            withdrawor: withdrawor.to_account_info(),
+            streamflow_program: streamflow_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        })
+    } else {
+        None
+    };
+
+    // A dispute window holds each payout in the investor's escrow instead of paying it out
+    let payout_escrow = (ctx.accounts.policy_pda.payout_dispute_window_secs > 0).then(|| PayoutEscrow {
+        window_secs: ctx.accounts.policy_pda.payout_dispute_window_secs,
+        system_program: ctx.accounts.system_program.to_account_info(),
+    });
 
-    // STEP 1: Claim fees from honorary position via CP-AMM CPI
-    let claimed_quote = claim_fees_from_position(&mut ctx, &vault_seed)?;
+    // Vesting the creator payout needs the Streamflow stream accounts
+    let creator_stream = if ctx.accounts.policy_pda.creator_vesting_secs > 0 {
+        let (Some(metadata), Some(escrow_tokens), Some(withdrawor), Some(fee_oracle), Some(streamflow_program), Some(rent)) = (
+            ctx.accounts.creator_stream_metadata.as_ref(),
+            ctx.accounts.creator_stream_escrow.as_ref(),
+            ctx.accounts.streamflow_withdrawor.as_ref(),
+            ctx.accounts.streamflow_fee_oracle.as_ref(),
+            ctx.accounts.streamflow_timelock_program.as_ref(),
+            ctx.accounts.rent.as_ref(),
+        ) else {
+            return err!(FeeRouterError::InvalidCreatorStream);
+        };
+        Some(CreatorStream {
+            metadata: metadata.to_account_info(),
+            escrow_tokens: escrow_tokens.to_account_info(),
+            withdrawor: withdrawor.to_account_info(),
+            fee_oracle: fee_oracle.to_account_info(),
+            streamflow_program: streamflow_program.to_account_info(),
+            rent: rent.to_account_info(),
+            payer: ctx.accounts.crank_caller.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            vesting_secs: ctx.accounts.policy_pda.creator_vesting_secs,
+        })
+    } else {
+        None
+    };
+
+    // Buybacks swap in the CP-AMM pool itself and burn through the writable base mint
+    let creator_buyback = if ctx.accounts.policy_pda.buyback_burn_creator {
+        let Some(base_mint) = ctx.accounts.buyback_base_mint.as_ref() else {
+            return err!(FeeRouterError::InvalidBuyback);
+        };
+        require!(
+            ctx.accounts.position_owner_pda.pool_kind == PoolKind::CpAmm,
+            FeeRouterError::InvalidBuyback
+        );
+        let quote_is_a = ctx.accounts.quote_mint.key() == ctx.accounts.token_a_mint.key();
+        let (base_account, base_token_program, pool_base_mint) = if quote_is_a {
+            (&ctx.accounts.temp_b_account, &ctx.accounts.token_b_program, ctx.accounts.token_b_mint.key())
+        } else {
+            (&ctx.accounts.temp_a_account, &ctx.accounts.token_a_program, ctx.accounts.token_a_mint.key())
+        };
+        require_keys_eq!(base_mint.key(), pool_base_mint, FeeRouterError::InvalidBuyback);
+        Some(CreatorBuyback::Swap(Box::new(BuybackSwap {
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            pool: ctx.accounts.pool.to_account_info(),
+            quote_treasury: ctx.accounts.quote_treasury.to_account_info(),
+            base_account: base_account.to_account_info(),
+            token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+            token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+            token_a_mint: ctx.accounts.token_a_mint.to_account_info(),
+            token_b_mint: ctx.accounts.token_b_mint.to_account_info(),
+            base_mint: base_mint.to_account_info(),
+            quote_mint: ctx.accounts.quote_mint.to_account_info(),
+            token_a_program: ctx.accounts.token_a_program.to_account_info(),
+            token_b_program: ctx.accounts.token_b_program.to_account_info(),
+            base_token_program: base_token_program.to_account_info(),
+            cp_amm_event_authority: ctx.accounts.cp_amm_event_authority.to_account_info(),
+            cp_amm_program: ctx.accounts.cp_amm_program.to_account_info(),
+            position_owner: ctx.accounts.position_owner_pda.to_account_info(),
+            quote_is_a,
+            max_slippage_bps: ctx.accounts.policy_pda.buyback_max_slippage_bps,
+        })))
+    } else {
+        None
+    };
+    let creator_referral = creator_referral(
+        &ctx.accounts.policy_pda,
+        ctx.accounts.referral_quote_ata.as_ref(),
+        &ctx.accounts.token_program.key(),
+    )?;
+    let hook = DistributionHook::from_policy(
+        &vault_seed,
+        &ctx.accounts.policy_pda,
+        ctx.accounts.hook_program.as_ref(),
+        ctx.accounts.hook_authority.as_ref(),
+        ctx.accounts.hook_state.as_ref(),
+    )?;
+
+    // STEP 1: Claim fees from honorary position via CP-AMM CPI; claimed_quote is what
+    // reached the treasury, net of any Token-2022 transfer fee
+    let (claimed_quote, claim_transfer_fee) = claim_fees_from_position(&mut ctx, &vault_seed, &events)?;
+    ctx.accounts.progress_pda.transfer_fees_today = ctx
+        .accounts
+        .progress_pda
+        .transfer_fees_today
+        .saturating_add(claim_transfer_fee);
 
     // Update progress with claimed amounts
     ctx.accounts.progress_pda.last_claimed_quote = claimed_quote as u128;
     ctx.accounts.progress_pda.last_claimed_base = 0; // Always 0 for quote-only positions
 
     // Emit fee claim event
-    emit!(QuoteFeesClaimed {
+    events.emit(QuoteFeesClaimed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        decimals: ctx.accounts.quote_mint.decimals,
         claimed_quote: claimed_quote as u128,
         claimed_base: 0,
+        transfer_fee: claim_transfer_fee,
         position: ctx.accounts.position.key(),
         treasury_ata: ctx.accounts.quote_treasury.key(),
         timestamp: current_timestamp,
-    });
+    })?;
+
+    // The protocol fee comes off the claim before anything else
+    let claimed_quote = if claimed_quote > 0 {
+        claimed_quote - skim_protocol_fee(&mut ctx, &vault_seed, &events, claimed_quote, current_timestamp)?
+    } else {
+        claimed_quote
+    };
 
-    // If no quote fees claimed, still need to finalize day if this is final page
-    if claimed_quote == 0 {
+    // Reinvest the policy's share of the claim as position liquidity before the rest is split
+    let claimed_quote = if ctx.accounts.policy_pda.compound_bps > 0 && claimed_quote > 0 {
+        let compounded = compound_quote_fees(&mut ctx, &vault_seed, &events, claimed_quote, current_timestamp)?;
+        claimed_quote - compounded
+    } else {
+        claimed_quote
+    };
+
+    // Before the day's first page, fold in any quote rolled over from deferred days and defer
+    // again if the day's claim, earlier claim-only cranks included, is still below the minimum
+    let claimed_quote = if ctx.accounts.progress_pda.first_page_pending() {
+        let total = claimed_quote
+            .checked_add(ctx.accounts.progress_pda.rollover_claimed_quote)
+            .ok_or(FeeRouterError::Overflow)?;
+        let day_total = ctx
+            .accounts
+            .progress_pda
+            .day_claimed_quote
+            .checked_add(total)
+            .ok_or(FeeRouterError::Overflow)?;
+        let min_daily_claim = ctx.accounts.policy_pda.tranche_min_claim_lamports();
+        if min_daily_claim > 0 && day_total < min_daily_claim {
+            ctx.accounts.progress_pda.defer_day(current_timestamp, total)?;
+            events.emit(DistributionDeferred {
+                schema_version: EVENT_SCHEMA_VERSION,
+                vault_seed: vault_seed.clone(),
+                day_epoch: ctx.accounts.progress_pda.day_epoch,
+                claimed_quote: claimed_quote as u128,
+                rollover_quote: day_total,
+                min_daily_claim_lamports: min_daily_claim,
+                timestamp: current_timestamp,
+            })?;
+            msg!(
+                "Claimed quote {} below min_daily_claim {}; deferring day {}",
+                day_total,
+                min_daily_claim,
+                ctx.accounts.progress_pda.day_epoch
+            );
+            return ctx.accounts.finish_call(0, 0, 0, 0, current_timestamp);
+        }
+        ctx.accounts.progress_pda.rollover_claimed_quote = 0;
+        total
+    } else {
+        claimed_quote
+    };
+
+    let first_page = ctx.accounts.progress_pda.first_page_pending();
+    ctx.accounts.progress_pda.record_day_claim(claimed_quote)?;
+
+    // A claim-only crank before the first page just adds to the day's claim; the call that pays
+    // the first page or closes the day fixes the pool from all of it
+    if first_page && investor_pages.is_empty() && !is_final_page {
+        return ctx.accounts.finish_call(0, 0, 0, 0, current_timestamp);
+    }
+
+    // Nothing claimed before the first page: there is no pool to distribute today
+    if first_page && ctx.accounts.progress_pda.day_claimed_quote == 0 {
         if is_final_page {
             finalize_day(
                 &mut ctx.accounts.progress_pda,
-                &ctx.accounts.creator_quote_ata,
-                &ctx.accounts.quote_treasury,
-                &ctx.accounts.position_owner_pda,
-                &ctx.accounts.quote_mint,
-                &ctx.accounts.token_program,
+                &FinalizeAccounts {
+                    quote_treasury: &ctx.accounts.quote_treasury,
+                    position_owner_pda: &ctx.accounts.position_owner_pda,
+                    quote_mint: &ctx.accounts.quote_mint,
+                    token_program: &ctx.accounts.token_program,
+                    memo_program: memo_program.as_ref(),
+                    position_owner_bump: ctx.bumps.position_owner_pda,
+                    events: &events,
+                },
+                &CreatorPayoutAccounts {
+                    quote_ata: &ctx.accounts.creator_quote_ata.to_account_info(),
+                    unwrap: creator_unwrap.as_ref(),
+                    ata_funding: creator_ata_funding.as_ref(),
+                    stream: creator_stream.as_ref(),
+                    buyback: creator_buyback.as_ref(),
+                    referral: creator_referral.as_ref(),
+                    hook: hook.as_ref(),
+                },
                 &vault_seed,
-                ctx.bumps.position_owner_pda,
                 current_timestamp,
                 0, // total_claimed
                 0, // creator_payout
             )?;
         }
-        return Ok(());
+        return ctx.accounts.finish_call(0, 0, 0, 0, current_timestamp);
+    }
+
+    // Zero-locked fast path: the completed snapshot commits that nothing is locked, so the whole
+    // claim goes to the creator in one final call without pages. Capped excess rolled in from
+    // earlier days has no investor left to go to either.
+    if first_page && is_final_page && investor_pages.is_empty() && ctx.accounts.progress_pda.zero_locked_day() {
+        let rolled_in = ctx.accounts.progress_pda.take_capped_excess()?;
+        let day_claimed_quote = ctx.accounts.progress_pda.day_claimed_quote;
+        let creator_remainder = day_claimed_quote
+            .checked_add(rolled_in)
+            .ok_or(FeeRouterError::Overflow)?
+            .saturating_sub(ctx.accounts.progress_pda.carry_over_lamports);
+        ctx.accounts.progress_pda.set_day_targets(0, 0, creator_remainder);
+
+        events.emit(ZeroLockedDayClosed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.clone(),
+            day_epoch: ctx.accounts.progress_pda.day_epoch,
+            snapshot_investor_count: ctx.accounts.progress_pda.snapshot_investor_count,
+            snapshot_locked_hash: ctx.accounts.progress_pda.snapshot_locked_hash,
+            creator_payout: creator_remainder,
+            timestamp: current_timestamp,
+        })?;
+        msg!(
+            "Snapshot has nothing locked; closing day {} with {} to the creator",
+            ctx.accounts.progress_pda.day_epoch,
+            creator_remainder
+        );
+
+        finalize_day(
+            &mut ctx.accounts.progress_pda,
+            &FinalizeAccounts {
+                quote_treasury: &ctx.accounts.quote_treasury,
+                position_owner_pda: &ctx.accounts.position_owner_pda,
+                quote_mint: &ctx.accounts.quote_mint,
+                token_program: &ctx.accounts.token_program,
+                memo_program: memo_program.as_ref(),
+                position_owner_bump: ctx.bumps.position_owner_pda,
+                events: &events,
+            },
+            &CreatorPayoutAccounts {
+                quote_ata: &ctx.accounts.creator_quote_ata.to_account_info(),
+                unwrap: creator_unwrap.as_ref(),
+                ata_funding: creator_ata_funding.as_ref(),
+                stream: creator_stream.as_ref(),
+                buyback: creator_buyback.as_ref(),
+                referral: creator_referral.as_ref(),
+                hook: hook.as_ref(),
+            },
+            &vault_seed,
+            current_timestamp,
+            day_claimed_quote as u128,
+            creator_remainder as u128,
+        )?;
+        return ctx.accounts.finish_call(0, 0, 0, 0, current_timestamp);
     }
 
-    // Enforce pagination invariants: pages must be contiguous starting at the cursor
+    // Enforce pagination invariants: pages must be contiguous starting at the cursor, or, under
+    // unordered_pages, any of the committed day's pages not completed yet
     let cursor = ctx.accounts.progress_pda.pagination_cursor;
+    let unordered = ctx.accounts.policy_pda.unordered_pages;
     if !investor_pages.is_empty() {
-        let mut expected = cursor;
-        for page in investor_pages.iter() {
+        let day_epoch = ctx.accounts.progress_pda.day_epoch;
+        if unordered {
+            let expected_pages = ctx.accounts.progress_pda.total_pages_expected;
             require!(
-                page.page_index == expected,
+                expected_pages > 0 && expected_pages <= ProgressPda::MAX_TRACKED_PAGES,
                 FeeRouterError::InvalidPaginationState
             );
-
-            // Verify page hash: H( page_index_le || investors[i].stream || investors[i].investor )
-            let index_le = page.page_index.to_le_bytes();
-            let mut chunks: Vec<&[u8]> = Vec::with_capacity(1 + page.investors.len() * 2);
-            chunks.push(&index_le);
-            for inv in page.investors.iter() {
-                chunks.push(inv.stream.as_ref());
-                chunks.push(inv.investor.as_ref());
+        }
+        let mut expected = cursor;
+        let mut sent = Vec::with_capacity(investor_pages.len());
+        for page in investor_pages.iter() {
+            if unordered {
+                if ctx.accounts.progress_pda.page_processed(page.page_index()) || sent.contains(&page.page_index()) {
+                    let mismatch = CrankMismatch::PageAlreadyProcessed { page_index: page.page_index() };
+                    return Err(mismatch.reject(&vault_seed, day_epoch, current_timestamp, FeeRouterError::InvalidPaginationState));
+                }
+                sent.push(page.page_index());
+            } else if page.page_index() != expected {
+                return Err(CrankMismatch::PageIndex { expected, provided: page.page_index() }.reject(
+                    &vault_seed,
+                    day_epoch,
+                    current_timestamp,
+                    FeeRouterError::InvalidPaginationState,
+                ));
             }
-            let computed = hashv(&chunks);
             require!(
-                page.page_hash == computed.to_bytes(),
-                FeeRouterError::InvalidPaginationState
+                ctx.accounts.policy_pda.page_within_limits(page.page_index(), page.investor_count()),
+                FeeRouterError::PageLimitExceeded
             );
+            // A committed page count bounds every page, not just the final one
+            let expected_pages = ctx.accounts.progress_pda.total_pages_expected;
+            if expected_pages > 0 && page.page_index() >= expected_pages {
+                let mismatch = CrankMismatch::PageBeyondExpected {
+                    page_index: page.page_index(),
+                    expected_pages,
+                };
+                return Err(mismatch.reject(&vault_seed, day_epoch, current_timestamp, FeeRouterError::InvalidPaginationState));
+            }
+
+            // Verify page hash: H( domain || page_index_le || investors[i].stream || investors[i].investor || investors[i].index_le ),
+            // bound to this program, vault, day and generation
+            let computed_hash = page.computed_hash(&PageDomain::new(
+                crate::ID.as_array(),
+                &vault_seed,
+                day_epoch,
+                ctx.accounts.progress_pda.generation,
+            ));
+            if page.page_hash() != computed_hash {
+                let mismatch = CrankMismatch::PageHash {
+                    page_index: page.page_index(),
+                    expected: computed_hash,
+                    provided: page.page_hash(),
+                };
+                return Err(mismatch.reject(&vault_seed, day_epoch, current_timestamp, FeeRouterError::InvalidPaginationState));
+            }
             expected = expected
                 .checked_add(1)
                 .ok_or(FeeRouterError::Overflow)?;
         }
     }
 
-    // STEP 2: Calculate total locked amounts by reading Streamflow accounts
-    let total_locked = calculate_total_locked_from_streamflow(
-        &investor_pages,
-        &ctx.remaining_accounts,
-        &ctx.accounts.streamflow_program.key(),
-    )?;
-
-    // STEP 3: Calculate eligible investor share
-    let eligible_bps = DistributionMath::calculate_eligible_bps(
-        total_locked,
-        ctx.accounts.policy_pda.y0_total_allocation,
-        ctx.accounts.policy_pda.investor_fee_share_bps,
-    )?;
-
-    let investor_fee_quote = DistributionMath::calculate_investor_fee_quote(
-        claimed_quote as u128,
-        eligible_bps,
-    )?;
+    // STEP 2: Total locked is the day's committed snapshot total, so every page is weighed against
+    // the full investor set rather than the investors in this call. Locked amounts are evaluated at
+    // the snapshot time. Only a call without investors (closing the day) may run without one.
+    let (total_locked, locked_ts) = if ctx.accounts.progress_pda.snapshot_complete {
+        (
+            ctx.accounts.progress_pda.snapshot_total_locked as u128,
+            ctx.accounts.progress_pda.snapshot_ts,
+        )
+    } else {
+        require!(investor_pages.is_empty(), FeeRouterError::SnapshotRequired);
+        (0, current_timestamp)
+    };
 
-    // Apply daily cap
-    let capped_investor_fee_quote = DistributionMath::apply_daily_cap(
-        investor_fee_quote,
-        ctx.accounts.policy_pda.daily_cap_quote_lamports,
-        ctx.accounts.progress_pda.cumulative_distributed_today,
-    );
+    // STEP 3: The day's investor pool is fixed on its first page from everything claimed today, cap
+    // included, so every page is paid from the same pool and a binding cap scales all payouts
+    // instead of starving later pages
+    let investor_pool = if first_page {
+        let day_claimed_quote = ctx.accounts.progress_pda.day_claimed_quote;
+        let investor_fee_share_bps = ctx.accounts.policy_pda.investor_fee_share_bps;
+        let locked_fraction = DistributionMath::calculate_locked_fraction(
+            total_locked,
+            ctx.accounts.policy_pda.y0_total_allocation,
+        ).map_err(FeeRouterError::from)?;
+        let eligible_bps = DistributionMath::calculate_eligible_bps(
+            total_locked,
+            ctx.accounts.policy_pda.y0_total_allocation,
+            investor_fee_share_bps,
+        ).map_err(FeeRouterError::from)?;
+
+        // Investor share capped out on previous days joins today's pool
+        let rolled_in = ctx.accounts.progress_pda.take_capped_excess()?;
+        let investor_fee_quote = DistributionMath::calculate_investor_fee_quote(
+            day_claimed_quote as u128,
+            locked_fraction,
+            investor_fee_share_bps,
+        ).map_err(FeeRouterError::from)?
+            .checked_add(rolled_in as u128)
+            .ok_or(FeeRouterError::Overflow)?;
+
+        // Cap left unused on quiet days raises today's cap (policy.cap_carry_days); the cap is the
+        // one in effect today, param_schedule included
+        let day_epoch = ctx.accounts.progress_pda.day_epoch;
+        let capped_investor_fee_quote = ctx.accounts.progress_pda.apply_daily_cap(
+            investor_fee_quote,
+            ctx.accounts.policy_pda.tranche_cap_quote_lamports(day_epoch),
+            ctx.accounts.policy_pda.max_banked_cap_quote_lamports(day_epoch),
+        );
 
-    msg!(
-        "Distribution calculation: total_locked={}, eligible_bps={}, investor_fee_quote={}, capped={}",
-        total_locked,
-        eligible_bps,
-        investor_fee_quote,
-        capped_investor_fee_quote
-    );
+        // Optionally hold the capped-out excess for tomorrow's investors instead of the creator
+        if ctx.accounts.policy_pda.roll_capped_excess {
+            let excess = investor_fee_quote.saturating_sub(capped_investor_fee_quote);
+            ctx.accounts.progress_pda.roll_capped_excess(excess as u64)?;
+        }
 
-    // Set day targets if this is the first page of the day
-    if ctx.accounts.progress_pda.pages_processed_today == 0 {
-        let creator_remainder = (claimed_quote as u64)
+        let creator_remainder = day_claimed_quote
             .saturating_sub(capped_investor_fee_quote as u64);
-        
         ctx.accounts.progress_pda.set_day_targets(
             total_locked as u64,
             capped_investor_fee_quote as u64,
             creator_remainder,
         );
-        
+
         msg!(
-            "Day targets set: total_locked={}, investor_pool={}, creator_remainder={}",
+            "Day targets set: total_locked={}, eligible_bps={}, investor_fee_quote={}, investor_pool={}, creator_remainder={}",
             total_locked,
+            eligible_bps,
+            investor_fee_quote,
             capped_investor_fee_quote,
             creator_remainder
         );
-    }
+        capped_investor_fee_quote
+    } else {
+        // Fees claimed after the first page go to the creator
+        let progress = &mut ctx.accounts.progress_pda;
+        progress.day_creator_remainder_target = progress
+            .day_creator_remainder_target
+            .saturating_add(claimed_quote);
+        progress.day_investor_pool_target as u128
+    };
+
+    // Continue-on-failure mode records unpayable investors in the failed payout ledger
+    let failed_ledger = if ctx.accounts.policy_pda.continue_on_failure {
+        Some(
+            ctx.accounts
+                .failed_payouts
+                .as_deref_mut()
+                .ok_or(FeeRouterError::MissingRequiredInput)?,
+        )
+    } else {
+        None
+    };
+
+    // Exactly-once guard: each investor index may be processed once per day
+    let day_epoch = ctx.accounts.progress_pda.day_epoch;
+    let paid_bitmap = if ctx.accounts.policy_pda.track_paid_investors {
+        let bitmap = ctx
+            .accounts
+            .paid_bitmap
+            .as_deref_mut()
+            .ok_or(FeeRouterError::MissingRequiredInput)?;
+        bitmap.roll_to_day(day_epoch);
+        Some(bitmap)
+    } else {
+        None
+    };
+
+    // Listed investors are held to their own minimum payout
+    let payout_overrides = if ctx.accounts.policy_pda.payout_overrides {
+        Some(
+            ctx.accounts
+                .payout_overrides
+                .as_deref()
+                .map(|table| &**table)
+                .ok_or(FeeRouterError::MissingRequiredInput)?,
+        )
+    } else {
+        None
+    };
+
+    // Fixed weights are added to locked amounts exactly as in the snapshot
+    let weight_overrides = if ctx.accounts.policy_pda.weight_overrides {
+        Some(
+            ctx.accounts
+                .weight_overrides
+                .as_deref()
+                .map(|table| &**table)
+                .ok_or(FeeRouterError::MissingRequiredInput)?,
+        )
+    } else {
+        None
+    };
 
     // STEP 4: Process investor pages with Streamflow validation
     let mut total_distributed_this_call = 0u128;
     let mut total_dust_this_call = 0u64;
+    let mut total_unattested_this_call = 0u64;
     let mut total_processed_count = 0u64;
-    let mut remaining_accounts_index = 0usize;
+    // Allocations continue from the payout weights of today's earlier pages; without a weight cap
+    // those are the locked amounts
+    let weight_cap = ctx.accounts.progress_pda.weight_cap();
+    let total_weight = weight_cap.total_weight(total_locked).map_err(FeeRouterError::from)?;
+    let mut pages_completed = 0u64;
+    let mut stopped_early = false;
+
+    let payer = ctx.accounts.crank_caller.to_account_info();
+    let page_accounts = PageAccounts {
+        treasury: FinalizeAccounts {
+            quote_treasury: &ctx.accounts.quote_treasury,
+            position_owner_pda: &ctx.accounts.position_owner_pda,
+            quote_mint: &ctx.accounts.quote_mint,
+            token_program: &ctx.accounts.token_program,
+            memo_program: memo_program.as_ref(),
+            position_owner_bump: ctx.bumps.position_owner_pda,
+            events: &events,
+        },
+        associated_token_program: &ctx.accounts.associated_token_program,
+        system_program: &ctx.accounts.system_program,
+        payer: &payer,
+        remaining_accounts: investor_accounts,
+        lockers: &lockers,
+        stream_topup: stream_topup.as_ref(),
+        payout_escrow: payout_escrow.as_ref(),
+    };
+    let page_terms = PageTerms {
+        vault_seed: &vault_seed,
+        day_epoch,
+        current_timestamp,
+        locked_ts,
+        weight_cap: &weight_cap,
+        total_weight,
+        investor_fee_quote: investor_pool,
+        min_payout_lamports: ctx.accounts.policy_pda.min_payout_at(day_epoch),
+        payout_overrides,
+        weight_overrides,
+        attestation: attestation.as_ref(),
+        unattested_routing: ctx.accounts.policy_pda.unattested_routing,
+        fund_missing_ata: ctx.accounts.policy_pda.policy_fund_missing_ata,
+        batch_paid_events: ctx.accounts.policy_pda.batch_investor_paid_events,
+    };
+    let mut tally = PageTally {
+        weight_before: ctx.accounts.progress_pda.day_weight_processed,
+        payout_locked_hash: ctx.accounts.progress_pda.payout_locked_hash,
+        payout_locked_set: ctx.accounts.progress_pda.payout_locked_set,
+        remaining_accounts_index: 0,
+        failed_ledger,
+        paid_bitmap,
+    };
 
-    for page in investor_pages.iter() {
+    for (page_ordinal, page) in investor_pages.iter().enumerate() {
+        // Low on compute at a page boundary: stop cleanly, leaving this page pending
+        if page_ordinal > 0 && compute_low(PAGE_RESUME_COMPUTE_RESERVE) {
+            msg!("Compute low; stopping before page {}", page.page_index());
+            stopped_early = true;
+            break;
+        }
+
+        // Only the page at the cursor can be a suspended one
+        let resumed = page_ordinal == 0 && ctx.accounts.progress_pda.page_in_progress_flag;
+        let first_investor = if page_ordinal == 0 {
+            let progress = &ctx.accounts.progress_pda;
+            if progress.page_in_progress_flag && progress.page_in_progress_hash != page.page_hash() {
+                let mismatch = CrankMismatch::SuspendedPage {
+                    page_index: page.page_index(),
+                    expected: progress.page_in_progress_hash,
+                    provided: page.page_hash(),
+                };
+                return Err(mismatch.reject(&vault_seed, day_epoch, current_timestamp, FeeRouterError::InvalidPaginationState));
+            }
+            progress.page_resume_index(&page.page_hash())?
+        } else {
+            0
+        };
+        if let Some(bitmap) = tally.paid_bitmap.as_deref_mut() {
+            claim_page_keys(bitmap, &ctx.accounts.progress_pda, page, unordered, resumed)?;
+        }
         let outcome = process_investor_page(
             page,
-            total_locked,
-            capped_investor_fee_quote,
-            ctx.accounts.policy_pda.min_payout_lamports,
-            &ctx.accounts.quote_treasury,
-            &ctx.accounts.position_owner_pda,
-            &ctx.accounts.quote_mint,
-            &ctx.accounts.token_program,
-            &ctx.accounts.associated_token_program,
-            &ctx.accounts.system_program,
-            &vault_seed,
-            ctx.bumps.position_owner_pda,
-            current_timestamp,
-            &ctx.remaining_accounts,
-            &mut remaining_accounts_index,
-            &ctx.accounts.streamflow_program.key(),
-            ctx.accounts.crank_caller.to_account_info(),
-            ctx.accounts.policy_pda.policy_fund_missing_ata,
+            first_investor as usize,
+            // The call's first investor always runs, so every call makes progress
+            page_ordinal > 0,
+            &page_accounts,
+            &page_terms,
+            &mut tally,
         )?;
 
-        total_distributed_this_call += outcome.page_distributed;
-        total_dust_this_call += outcome.page_dust;
+        total_distributed_this_call = total_distributed_this_call
+            .checked_add(outcome.page_distributed)
+            .ok_or(FeeRouterError::Overflow)?;
+        total_dust_this_call = total_dust_this_call
+            .checked_add(outcome.page_dust)
+            .ok_or(FeeRouterError::Overflow)?;
+        total_unattested_this_call = total_unattested_this_call
+            .checked_add(outcome.page_unattested)
+            .ok_or(FeeRouterError::Overflow)?;
         total_processed_count += outcome.processed_count as u64;
+        ctx.accounts.progress_pda.transfer_fees_today = ctx
+            .accounts
+            .progress_pda
+            .transfer_fees_today
+            .saturating_add(outcome.page_transfer_fee);
+        ctx.accounts.progress_pda.failed_owed_today = ctx
+            .accounts
+            .progress_pda
+            .failed_owed_today
+            .saturating_add(outcome.page_failed_owed);
+        match outcome.suspended_at {
+            // Out of compute: the next call resends this page and picks up at the saved investor
+            Some(next_investor) => {
+                ctx.accounts.progress_pda.suspend_page(&page.page_hash(), next_investor);
+                stopped_early = true;
+                msg!("Page {} suspended before investor {}", page.page_index(), next_investor);
+            }
+            None => {
+                ctx.accounts.progress_pda.day_locked_processed = ctx
+                    .accounts
+                    .progress_pda
+                    .day_locked_processed
+                    .saturating_add(outcome.page_locked);
+                ctx.accounts.progress_pda.day_weight_processed = tally.weight_before;
+                // Today's pages together can't carry more locked than the snapshot committed
+                require!(
+                    ctx.accounts.progress_pda.day_locked_processed
+                        <= ctx.accounts.progress_pda.snapshot_total_locked,
+                    FeeRouterError::SnapshotMismatch
+                );
+                ctx.accounts.progress_pda.record_page_hash(&page.page_hash());
+                ctx.accounts.progress_pda.complete_page(page.page_index());
+                pages_completed += 1;
+            }
+        }
+
+        if ctx.accounts.policy_pda.batch_investor_paid_events && !outcome.paid.is_empty() {
+            events.emit(InvestorPaidBatch {
+                schema_version: EVENT_SCHEMA_VERSION,
+                vault_seed: vault_seed.clone(),
+                quote_mint: ctx.accounts.quote_mint.key(),
+                decimals: ctx.accounts.quote_mint.decimals,
+                day_epoch,
+                page_index: page.page_index(),
+                payouts: outcome.paid,
+            })?;
+        }
 
-        emit!(InvestorPayoutPage {
-            page_index: page.page_index,
+        events.emit(InvestorPayoutPage {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.clone(),
+            quote_mint: ctx.accounts.quote_mint.key(),
+            decimals: ctx.accounts.quote_mint.decimals,
+            page_index: page.page_index(),
+            first_investor,
+            page_complete: !stopped_early,
             investors_processed: outcome.processed_count,
             successful_transfers: outcome.success_count,
             failed_transfers: outcome.fail_count,
             total_distributed: outcome.page_distributed,
+            transfer_fee_withheld: outcome.page_transfer_fee,
             ata_creation_cost: outcome.ata_creation_cost,
+            ata_reimbursed: outcome.ata_reimbursed,
+            dust: outcome.page_dust,
+            skipped_investors: outcome.skip_count,
+            day_distributed: ctx
+                .accounts
+                .progress_pda
+                .cumulative_distributed_today
+                .saturating_add(total_distributed_this_call),
+            day_pages_processed: ctx
+                .accounts
+                .progress_pda
+                .pages_processed_today
+                .saturating_add(pages_completed),
+            generation: ctx.accounts.progress_pda.generation,
             timestamp: current_timestamp,
-        });
+        })?;
+
+        if stopped_early {
+            break;
+        }
+    }
+
+    ctx.accounts.progress_pda.payout_locked_hash = tally.payout_locked_hash;
+    ctx.accounts.progress_pda.payout_locked_set = tally.payout_locked_set;
+    if let Err(mismatch) = ctx.accounts.progress_pda.record_investors_processed(total_processed_count as u32) {
+        return Err(mismatch.reject(&vault_seed, day_epoch, current_timestamp, FeeRouterError::InvalidPaginationState));
     }
 
     // Update progress PDA with investor distribution tracking
-    ctx.accounts.progress_pda.cumulative_distributed_today += total_distributed_this_call;
-    ctx.accounts.progress_pda.carry_over_lamports += total_dust_this_call;
-    ctx.accounts.progress_pda.pages_processed_today += investor_pages.len() as u64;
+    let progress = &mut ctx.accounts.progress_pda;
+    progress.cumulative_distributed_today = progress
+        .cumulative_distributed_today
+        .checked_add(total_distributed_this_call)
+        .ok_or(FeeRouterError::Overflow)?;
+    progress.carry_over_lamports = progress
+        .carry_over_lamports
+        .checked_add(total_dust_this_call)
+        .ok_or(FeeRouterError::Overflow)?;
+    progress.pages_processed_today = progress
+        .pages_processed_today
+        .checked_add(pages_completed)
+        .ok_or(FeeRouterError::Overflow)?;
     ctx.accounts.progress_pda.add_investor_distribution(total_distributed_this_call as u64)?;
+    let unattested_routing = ctx.accounts.policy_pda.unattested_routing;
+    ctx.accounts.progress_pda.route_unattested(unattested_routing, total_unattested_this_call)?;
     ctx.accounts.progress_pda.updated_at = current_timestamp;
-    // Advance pagination cursor
-    ctx.accounts.progress_pda.pagination_cursor = ctx
-        .accounts
-        .progress_pda
-        .pagination_cursor
-        .checked_add(investor_pages.len() as u64)
-        .ok_or(FeeRouterError::Overflow)?;
     
     // Validate we haven't exceeded the day's investor pool target
     require!(
@@ -365,33 +1219,70 @@ pub fn handler<'a, 'info: 'a>(
         FeeRouterError::Overflow
     );
 
-    // STEP 5: Finalize day if this is the final page
-    if is_final_page {
-        // On finalization, either set expected total pages (if unset) or validate it matches
-        if ctx.accounts.progress_pda.total_pages_expected == 0 {
-            ctx.accounts.progress_pda.total_pages_expected = ctx.accounts.progress_pda.pagination_cursor;
-        } else {
-            require!(
-                ctx.accounts.progress_pda.total_pages_expected == ctx.accounts.progress_pda.pagination_cursor,
-                FeeRouterError::InvalidPaginationState
-            );
+    // STEP 5: Finalize day if this is the final page, or the cursor reached today's committed page count,
+    // and every page sent was paid in full. A caller that misses the final flag can't leave the
+    // day dangling, and one that sets it early still fails the page count check below.
+    let auto_final = !is_final_page && !stopped_early && ctx.accounts.progress_pda.expected_pages_reached();
+    if auto_final {
+        msg!(
+            "Cursor reached total_pages_expected {}; finalizing day {}",
+            ctx.accounts.progress_pda.total_pages_expected,
+            day_epoch
+        );
+    }
+    if (is_final_page || auto_final) && !stopped_early {
+        // A page count committed for today must be met exactly
+        let expected_pages = ctx.accounts.progress_pda.total_pages_expected;
+        if expected_pages > 0 && expected_pages != ctx.accounts.progress_pda.pagination_cursor {
+            let mismatch = CrankMismatch::TotalPages {
+                expected: expected_pages,
+                provided: ctx.accounts.progress_pda.pagination_cursor,
+            };
+            return Err(mismatch.reject(&vault_seed, day_epoch, current_timestamp, FeeRouterError::InvalidPaginationState));
+        }
+        // So must the investors the authority committed to (set_expected_pages)
+        if let Err(mismatch) = ctx.accounts.progress_pda.committed_investors_covered() {
+            return Err(mismatch.reject(&vault_seed, day_epoch, current_timestamp, FeeRouterError::InvalidPaginationState));
+        }
+
+        // Paid investors must be exactly the snapshotted set, in the same order unless pages could
+        // arrive in any order
+        if ctx.accounts.progress_pda.snapshot_complete {
+            let progress = &ctx.accounts.progress_pda;
+            let matches = if unordered {
+                progress.payout_locked_set == progress.snapshot_locked_set
+            } else {
+                progress.payout_locked_hash == progress.snapshot_locked_hash
+            };
+            require!(matches, FeeRouterError::SnapshotMismatch);
         }
 
-        let creator_remainder = (claimed_quote as u128)
-            .saturating_sub(ctx.accounts.progress_pda.cumulative_distributed_today)
-            .saturating_sub(ctx.accounts.progress_pda.carry_over_lamports as u128);
+        let day_claimed_quote = ctx.accounts.progress_pda.day_claimed_quote;
+        let creator_remainder = ctx.accounts.progress_pda.creator_remainder();
 
         finalize_day(
             &mut ctx.accounts.progress_pda,
-            &ctx.accounts.creator_quote_ata,
-            &ctx.accounts.quote_treasury,
-            &ctx.accounts.position_owner_pda,
-            &ctx.accounts.quote_mint,
-            &ctx.accounts.token_program,
+            &FinalizeAccounts {
+                quote_treasury: &ctx.accounts.quote_treasury,
+                position_owner_pda: &ctx.accounts.position_owner_pda,
+                quote_mint: &ctx.accounts.quote_mint,
+                token_program: &ctx.accounts.token_program,
+                memo_program: memo_program.as_ref(),
+                position_owner_bump: ctx.bumps.position_owner_pda,
+                events: &events,
+            },
+            &CreatorPayoutAccounts {
+                quote_ata: &ctx.accounts.creator_quote_ata.to_account_info(),
+                unwrap: creator_unwrap.as_ref(),
+                ata_funding: creator_ata_funding.as_ref(),
+                stream: creator_stream.as_ref(),
+                buyback: creator_buyback.as_ref(),
+                referral: creator_referral.as_ref(),
+                hook: hook.as_ref(),
+            },
             &vault_seed,
-            ctx.bumps.position_owner_pda,
             current_timestamp,
-            claimed_quote as u128,
+            day_claimed_quote as u128,
             creator_remainder,
         )?;
     }
@@ -403,31 +1294,29 @@ pub fn handler<'a, 'info: 'a>(
         total_processed_count
     );
 
-    Ok(())
-}
+    ctx.accounts.finish_call(
+        pages_completed,
+        total_processed_count,
+        total_distributed_this_call,
+        total_dust_this_call,
+        current_timestamp,
+    )
+}
 
-/// Claim fees from the honorary position via CP-AMM CPI
+/// Claim fees from the honorary position via CP-AMM or DLMM CPI
 /// 
 /// This function:
-/// 1. Validates CP-AMM event authority PDA
-/// 2. Calls CP-AMM claim_position_fee instruction with PDA signer
+/// 1. Validates the backend's pool, position and program accounts
+/// 2. Calls the backend's claim instruction with PDA signer
 /// 3. Reloads temp accounts to get claimed amounts
 /// 4. Validates quote-only (base_amount must be 0)
 /// 5. Transfers quote fees to treasury
-/// 6. Returns claimed quote amount
+/// 6. Returns the quote amount received by the treasury and the transfer fee withheld
 fn claim_fees_from_position<'a, 'info: 'a>(
     ctx: &mut Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
     vault_seed: &str,
-) -> Result<u64> {
-    // Validate CP-AMM event authority PDA
-    let (expected_event_authority, _) =
-        Pubkey::find_program_address(&[b"__event_authority"], &CP_AMM_PROGRAM_ID);
-    require_keys_eq!(
-        ctx.accounts.cp_amm_event_authority.key(),
-        expected_event_authority,
-        FeeRouterError::InvalidCpAmmPda
-    );
-
+    events: &EventCpi<'info>,
+) -> Result<(u64, u64)> {
     // Prepare PDA signer seeds
     let position_owner_bump = ctx.bumps.position_owner_pda;
     let seeds = &[
@@ -437,29 +1326,12 @@ fn claim_fees_from_position<'a, 'info: 'a>(
     ];
     let signer = &[&seeds[..]];
 
-    // Build CPI accounts for claim_position_fee
-    let cpi_accounts = cp_amm::cpi::accounts::ClaimPositionFeeCtx {
-        pool_authority: ctx.accounts.pool_authority.to_account_info(),
-        pool: ctx.accounts.pool.to_account_info(),
-        position: ctx.accounts.position.to_account_info(),
-        token_a_account: ctx.accounts.temp_a_account.to_account_info(),
-        token_b_account: ctx.accounts.temp_b_account.to_account_info(),
-        token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
-        token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
-        token_a_mint: ctx.accounts.token_a_mint.to_account_info(),
-        token_b_mint: ctx.accounts.token_b_mint.to_account_info(),
-        position_nft_account: ctx.accounts.position_nft_account.to_account_info(),
-        owner: ctx.accounts.position_owner_pda.to_account_info(),
-        token_a_program: ctx.accounts.token_a_program.to_account_info(),
-        token_b_program: ctx.accounts.token_b_program.to_account_info(),
-        event_authority: ctx.accounts.cp_amm_event_authority.to_account_info(),
-        program: ctx.accounts.cp_amm_program.to_account_info(),
-    };
-
-    // Execute CPI call with PDA signer
-    let cpi_program = ctx.accounts.cp_amm_program.to_account_info();
-    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-    cp_amm::cpi::claim_position_fee(cpi_ctx)?;
+    match ctx.accounts.position_owner_pda.pool_kind {
+        PoolKind::CpAmm => claim_cp_amm_position_fee(ctx, signer)?,
+        PoolKind::Dlmm => claim_dlmm_position_fee(ctx, signer)?,
+        PoolKind::Whirlpool => claim_whirlpool_position_fee(ctx, signer)?,
+        PoolKind::RaydiumClmm => claim_raydium_position_fee(ctx, signer)?,
+    }
 
     // Reload temp accounts to get claimed amounts
     ctx.accounts.temp_a_account.reload()?;
@@ -475,15 +1347,23 @@ fn claim_fees_from_position<'a, 'info: 'a>(
         (fee_a_amount, fee_b_amount)
     };
 
-    // CRITICAL: Enforce quote-only - fail if any base fees claimed
-    require!(
-        base_amount == 0,
-        FeeRouterError::BaseFeeDetected
-    );
+    // CRITICAL: Enforce quote-only - fail if any base fees claimed, recording the amounts first
+    if base_amount > 0 {
+        events.emit(BaseFeeObserved {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.to_string(),
+            base_amount,
+            quote_amount,
+            position: ctx.accounts.position.key(),
+            timestamp: Clock::get()?.unix_timestamp as u64,
+        })?;
+        return err!(FeeRouterError::BaseFeeDetected);
+    }
+    ctx.accounts.position_owner_pda.record_claim(quote_amount, base_amount);
 
     // If no quote fees, return early
     if quote_amount == 0 {
-        return Ok(0);
+        return Ok((0, 0));
     }
 
     // Determine which temp account and program to use for transfer
@@ -515,318 +1395,2188 @@ fn claim_fees_from_position<'a, 'info: 'a>(
         ctx.accounts.quote_mint.decimals,
     )?;
 
+    // The treasury receives the claim net of the quote mint's transfer fee (if any)
+    let transfer_fee = transfer_fee_for(&ctx.accounts.quote_mint.to_account_info(), quote_amount)?;
+    let received = quote_amount
+        .checked_sub(transfer_fee)
+        .ok_or(FeeRouterError::Overflow)?;
+
     msg!(
-        "Claimed {} quote fees from position (base fees: 0)",
-        quote_amount
+        "Claimed {} quote fees from position (base fees: 0, transfer fee: {}, received: {})",
+        quote_amount,
+        transfer_fee,
+        received
     );
 
-    Ok(quote_amount)
-}
-
-/// Calculate total locked amount by reading Streamflow accounts from remaining_accounts
-/// 
-/// remaining_accounts layout: [stream0, ata0, owner0, stream1, ata1, owner1, ...]
-fn calculate_total_locked_from_streamflow(
-    investor_pages: &[InvestorPage],
-    remaining_accounts: &[AccountInfo],
-    _streamflow_program_id: &Pubkey,
-) -> Result<u128> {
-    let mut total_locked = 0u128;
-    let mut remaining_iter = remaining_accounts.iter();
-    
-    for page in investor_pages.iter() {
-        for investor_data in page.investors.iter() {
-            // Get stream account (every 3rd account starting at 0)
-            let stream_account_info = remaining_iter
-                .next()
-                .ok_or(FeeRouterError::MissingRequiredInput)?;
-            
-            // Skip the investor quote ATA (we'll use it later in process_investor_page)
-            let _investor_quote_ata = remaining_iter
-                .next()
-                .ok_or(FeeRouterError::MissingRequiredInput)?;
-            // Skip the investor authority (owner) account (used only if we need to create ATA)
-            let _investor_owner = remaining_iter
-                .next()
-                .ok_or(FeeRouterError::MissingRequiredInput)?;
-            
-            // Validate stream account key matches
-            require_keys_eq!(
-                stream_account_info.key(),
-                investor_data.stream,
-                FeeRouterError::MissingRequiredInput
-            );
-            
-            #[cfg(not(feature = "local"))]
-            {
-                require_keys_eq!(
-                    *stream_account_info.owner,
-                    *_streamflow_program_id,
-                    FeeRouterError::MissingRequiredInput
-                );
-            }
-            
-            // Parse stream and validate recipient
-            let stream = parse_streamflow_account(stream_account_info)?;
-            validate_stream_for_investor(&stream, &investor_data.investor)?;
-            
-            // Calculate locked amount
-            let locked_amount = calculate_locked_amount(&stream)?;
-            
-            total_locked = total_locked
-                .checked_add(locked_amount as u128)
-                .ok_or(FeeRouterError::Overflow)?;
-        }
-    }
-    
-    Ok(total_locked)
+    Ok((received, transfer_fee))
 }
 
-/// Process a single investor page and distribute payouts
-/// Reads locked amounts from Streamflow on-chain
-struct PageOutcome {
-    page_distributed: u128,
-    page_dust: u64,
-    processed_count: u32,
-    success_count: u32,
-    fail_count: u32,
-    ata_creation_cost: u64,
+/// Transfer the deployment's protocol fee share of the claim to the protocol treasury
+///
+/// Returns the quote that left the treasury.
+fn skim_protocol_fee<'a, 'info: 'a>(
+    ctx: &mut Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
+    vault_seed: &str,
+    events: &EventCpi<'info>,
+    claimed_quote: u64,
+    current_timestamp: u64,
+) -> Result<u64> {
+    let Some(config) = ProtocolConfig::load(&ctx.accounts.protocol_config)? else {
+        return Ok(0);
+    };
+    let accounts = FinalizeAccounts {
+        quote_treasury: &ctx.accounts.quote_treasury,
+        position_owner_pda: &ctx.accounts.position_owner_pda,
+        quote_mint: &ctx.accounts.quote_mint,
+        token_program: &ctx.accounts.token_program,
+        memo_program: None,
+        position_owner_bump: ctx.bumps.position_owner_pda,
+        events,
+    };
+    let fee_ata = ctx.accounts.protocol_fee_ata.as_ref().map(|ata| ata.as_ref());
+    let (amount, transfer_fee) =
+        collect_protocol_fee(&config, fee_ata, &accounts, vault_seed, claimed_quote, current_timestamp)?;
+    ctx.accounts.progress_pda.transfer_fees_today = ctx
+        .accounts
+        .progress_pda
+        .transfer_fees_today
+        .saturating_add(transfer_fee);
+    Ok(amount)
 }
 
-fn process_investor_page<'info>(
-    investor_page: &InvestorPage,
-    total_locked: u128,
-    investor_fee_quote: u128,
-    min_payout_lamports: u64,
-    quote_treasury: &InterfaceAccount<'info, TokenAccount>,
-    position_owner_pda: &Account<'info, InvestorFeePositionOwnerPda>,
-    quote_mint: &InterfaceAccount<'info, Mint>,
-    token_program: &Interface<'info, TokenInterface>,
-    associated_token_program: &Program<'info, AssociatedToken>,
-    system_program: &Program<'info, System>,
+/// Pay `config`'s protocol fee on `claimed_quote` from the vault treasury to `fee_ata`
+///
+/// Returns the quote that left the treasury and the transfer fee withheld on it. A closed or
+/// frozen protocol fee ATA can't stall vaults, so the fee is then not taken.
+pub(crate) fn collect_protocol_fee<'info>(
+    config: &ProtocolConfig,
+    fee_ata: Option<&AccountInfo<'info>>,
+    accounts: &FinalizeAccounts<'_, 'info>,
     vault_seed: &str,
-    position_owner_bump: u8,
-    _current_timestamp: u64,
-    remaining_accounts: &[AccountInfo<'info>],
-    remaining_accounts_index: &mut usize,
-    streamflow_program_id: &Pubkey,
-    payer: AccountInfo<'info>,
-    fund_missing_ata: bool,
-) -> Result<PageOutcome> {
-    let mut page_distributed = 0u128;
-    let mut page_dust = 0u64;
-    let processed_count = investor_page.investors.len() as u32;
-    let mut success_count: u32 = 0;
-    let mut fail_count: u32 = 0;
-    let mut ata_creation_cost: u64 = 0;
+    claimed_quote: u64,
+    current_timestamp: u64,
+) -> Result<(u64, u64)> {
+    if config.protocol_fee_bps == 0 {
+        return Ok((0, 0));
+    }
+    let fee_ata = fee_ata.ok_or(FeeRouterError::InvalidProtocolConfig)?;
+    let quote_mint = accounts.quote_mint.key();
+    let expected = get_associated_token_address_with_program_id(
+        &config.protocol_treasury,
+        &quote_mint,
+        &accounts.token_program.key(),
+    );
+    require_keys_eq!(fee_ata.key(), expected, FeeRouterError::InvalidProtocolConfig);
 
-    for investor_data in investor_page.investors.iter() {
-        // Get stream account from remaining_accounts
-        let stream_account_info = remaining_accounts
-            .get(*remaining_accounts_index)
-            .ok_or(FeeRouterError::MissingRequiredInput)?;
-        *remaining_accounts_index += 1;
-        
-        // Get investor quote ATA from remaining_accounts
-        let investor_quote_ata_info = remaining_accounts
-            .get(*remaining_accounts_index)
-            .ok_or(FeeRouterError::MissingRequiredInput)?;
-        *remaining_accounts_index += 1;
-        // Get investor owner account
-        let investor_owner_info = remaining_accounts
-            .get(*remaining_accounts_index)
-            .ok_or(FeeRouterError::MissingRequiredInput)?;
-        *remaining_accounts_index += 1;
-        
-        // Validate stream account
-        require_keys_eq!(
-            stream_account_info.key(),
-            investor_data.stream,
-            FeeRouterError::MissingRequiredInput
+    let amount = ((claimed_quote as u128) * (config.protocol_fee_bps as u128) / 10_000) as u64;
+    if amount == 0 {
+        return Ok((0, 0));
+    }
+    let status = quote_ata_status(fee_ata, &quote_mint, &config.protocol_treasury);
+    if status != QuoteAtaStatus::Valid {
+        msg!(
+            "Protocol fee ATA {} can't receive {} ({:?}); no protocol fee taken",
+            fee_ata.key(),
+            amount,
+            status
         );
-        #[cfg(not(feature = "local"))]
-        {
-            require_keys_eq!(
-                *stream_account_info.owner,
-                *streamflow_program_id,
-                FeeRouterError::MissingRequiredInput
-            );
-        }
-        
-        // Parse stream and get locked amount
-        let stream = parse_streamflow_account(stream_account_info)?;
-        validate_stream_for_investor(&stream, &investor_data.investor)?;
-        let locked_amount = calculate_locked_amount(&stream)? as u128;
-        
-        // Skip if no locked amount
-        if locked_amount == 0 {
-            msg!(
-                "Investor {} has zero locked amount; skipping payout",
-                investor_data.investor
-            );
-            continue;
-        }
-        
-        // Calculate individual payout
-        let raw_payout = DistributionMath::calculate_investor_payout(
-            locked_amount,
-            total_locked,
-            investor_fee_quote,
-        )?;
+        return Ok((0, 0));
+    }
 
-        // Check minimum payout threshold
-        if raw_payout < min_payout_lamports as u128 {
-            page_dust += raw_payout as u64;
-            msg!(
-                "Investor {} payout {} below threshold {}, added to dust",
-                investor_data.investor,
-                raw_payout,
-                min_payout_lamports
-            );
-            continue;
-        }
+    let seeds = &[
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[accounts.position_owner_bump],
+    ];
+    let signer = &[&seeds[..]];
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.quote_treasury.to_account_info(),
+                mint: accounts.quote_mint.to_account_info(),
+                to: fee_ata.clone(),
+                authority: accounts.position_owner_pda.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        accounts.quote_mint.decimals,
+    )?;
+    let transfer_fee = transfer_fee_for(&accounts.quote_mint.to_account_info(), amount)?;
+
+    accounts.events.emit(ProtocolFeeCollected {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.to_string(),
+        quote_mint,
+        decimals: accounts.quote_mint.decimals,
+        protocol_treasury: config.protocol_treasury,
+        protocol_fee_ata: fee_ata.key(),
+        claimed_quote,
+        protocol_fee_bps: config.protocol_fee_bps,
+        amount,
+        transfer_fee,
+        timestamp: current_timestamp,
+    })?;
+    msg!("Skimmed protocol fee {} of {} claimed quote", amount, claimed_quote);
 
-        // Validate investor quote ATA exists and matches expected mint/owner
-        let mut ata_ok = false;
-        if let Ok(data) = investor_quote_ata_info.try_borrow_data() {
-            if data.len() >= 165 {
-                let mint_bytes: [u8; 32] = data[0..32].try_into().unwrap();
-                let owner_bytes: [u8; 32] = data[32..64].try_into().unwrap();
-                let account_mint = Pubkey::new_from_array(mint_bytes);
-                let account_owner = Pubkey::new_from_array(owner_bytes);
-                if account_mint == quote_mint.key() && account_owner == investor_data.investor {
-                    ata_ok = true;
-                }
-            }
-        }
+    Ok((amount, transfer_fee))
+}
 
-        if !ata_ok {
-            if fund_missing_ata {
-                // Validate investor owner matches expected investor
-                require_keys_eq!(
-                    investor_owner_info.key(),
-                    investor_data.investor,
-                    FeeRouterError::MissingRequiredInput
-                );
-                // Attempt to create investor ATA via Associated Token Program
-                anchor_spl::associated_token::create(
-                    CpiContext::new(
-                        associated_token_program.to_account_info(),
-                        anchor_spl::associated_token::Create {
-                            payer: payer.clone(),
-                            associated_token: investor_quote_ata_info.clone(),
-                            authority: investor_owner_info.clone(),
-                            mint: quote_mint.to_account_info(),
-                            system_program: system_program.to_account_info(),
-                            token_program: token_program.to_account_info(),
-                        },
-                    ),
-                )?;
-                // Track rent cost for ATA creation (approximate for 165-byte token account)
-                let rent = Rent::get()?;
-                let ata_rent = rent.minimum_balance(165);
-                let (new_cost, overflow) = ata_creation_cost.overflowing_add(ata_rent);
-                ata_creation_cost = if overflow { ata_creation_cost } else { new_cost };
-                success_count = success_count.saturating_add(0); // creation succeeded; payout below will count
-            } else {
-                page_dust += raw_payout as u64;
-                msg!(
-                    "Investor {} missing or invalid quote ATA; added payout {} to dust",
-                    investor_data.investor,
-                    raw_payout
-                );
-                fail_count = fail_count.saturating_add(1);
-                continue;
-            }
+/// Add `policy.compound_bps` of the claim back to the CP-AMM position as quote-only liquidity
+///
+/// Returns the quote that left the treasury. Nothing is compounded while the pool disallows
+/// adding liquidity or its price would make the deposit take base tokens too (see
+/// `quote_only_liquidity`); the whole claim is then split as usual.
+fn compound_quote_fees<'a, 'info: 'a>(
+    ctx: &mut Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
+    vault_seed: &str,
+    events: &EventCpi<'info>,
+    claimed_quote: u64,
+    current_timestamp: u64,
+) -> Result<u64> {
+    let compound_bps = ctx.accounts.policy_pda.compound_bps;
+    let budget = ((claimed_quote as u128) * (compound_bps as u128) / 10_000) as u64;
+    // The pool charges the quote mint's transfer fee on top of the liquidity's quote amount
+    let net_budget = budget.saturating_sub(transfer_fee_for(&ctx.accounts.quote_mint.to_account_info(), budget)?);
+    let quote_is_a = ctx.accounts.quote_mint.key() == ctx.accounts.token_a_mint.key();
+
+    let deposit = {
+        let pool = load_account::<Pool>(&ctx.accounts.pool, &ctx.accounts.policy_pda.cp_amm_program_id)?;
+        let can_add = cp_amm::get_pool_access_validator(&pool)
+            .map(|access| access.can_add_liquidity())
+            .unwrap_or(false);
+        if can_add {
+            quote_only_liquidity(&pool, quote_is_a, net_budget)
+        } else {
+            None
         }
+    };
+    let Some((liquidity_delta, _)) = deposit else {
+        msg!("Pool can't take {} quote as quote-only liquidity; nothing compounded", net_budget);
+        return Ok(0);
+    };
 
-        // Transfer payout to investor via transfer_checked
-        let seeds = &[
-            vault_seed.as_bytes(),
-            b"investor_fee_pos_owner",
-            &[position_owner_bump],
-        ];
-        let signer = &[&seeds[..]];
-
-        transfer_checked(
-            CpiContext::new_with_signer(
-                token_program.to_account_info(),
-                TransferChecked {
-                    from: quote_treasury.to_account_info(),
-                    mint: quote_mint.to_account_info(),
-                    to: investor_quote_ata_info.clone(),
-                    authority: position_owner_pda.to_account_info(),
-                },
-                signer,
-            ),
-            raw_payout as u64,
-            quote_mint.decimals,
-        )?;
+    let position_nft_account = ctx
+        .accounts
+        .position_nft_account
+        .as_ref()
+        .ok_or(FeeRouterError::MissingRequiredInput)?;
+    let (token_a_account, token_b_account) = if quote_is_a {
+        (ctx.accounts.quote_treasury.to_account_info(), ctx.accounts.temp_b_account.to_account_info())
+    } else {
+        (ctx.accounts.temp_a_account.to_account_info(), ctx.accounts.quote_treasury.to_account_info())
+    };
+    let (token_a_amount_threshold, token_b_amount_threshold) = if quote_is_a { (budget, 0) } else { (0, budget) };
+    let cpi_accounts = cp_amm::cpi::accounts::AddLiquidityCtx {
+        pool: ctx.accounts.pool.to_account_info(),
+        position: ctx.accounts.position.to_account_info(),
+        token_a_account,
+        token_b_account,
+        token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+        token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+        token_a_mint: ctx.accounts.token_a_mint.to_account_info(),
+        token_b_mint: ctx.accounts.token_b_mint.to_account_info(),
+        position_nft_account: position_nft_account.to_account_info(),
+        owner: ctx.accounts.position_owner_pda.to_account_info(),
+        token_a_program: ctx.accounts.token_a_program.to_account_info(),
+        token_b_program: ctx.accounts.token_b_program.to_account_info(),
+        event_authority: ctx.accounts.cp_amm_event_authority.to_account_info(),
+        program: ctx.accounts.cp_amm_program.to_account_info(),
+    };
+    let seeds = &[
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[ctx.bumps.position_owner_pda],
+    ];
+    let signer = &[&seeds[..]];
 
-        page_distributed += raw_payout;
-        success_count = success_count.saturating_add(1);
+    // The typed treasury was loaded before the claim and the protocol skim moved quote
+    ctx.accounts.quote_treasury.reload()?;
+    let quote_compounded = treasury_spent(&ctx.accounts.quote_treasury.to_account_info(), || {
+        cp_amm::cpi::add_liquidity(
+            CpiContext::new_with_signer(ctx.accounts.cp_amm_program.to_account_info(), cpi_accounts, signer),
+            cp_amm::AddLiquidityParameters {
+                liquidity_delta,
+                token_a_amount_threshold,
+                token_b_amount_threshold,
+            },
+        )
+    })?;
+    ctx.accounts.quote_treasury.reload()?;
+
+    events.emit(FeesCompounded {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.to_string(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        decimals: ctx.accounts.quote_mint.decimals,
+        position: ctx.accounts.position.key(),
+        claimed_quote,
+        compound_bps,
+        quote_compounded,
+        liquidity_added: liquidity_delta,
+        timestamp: current_timestamp,
+    })?;
+    msg!(
+        "Compounded {} of {} claimed quote into the position (liquidity {})",
+        quote_compounded,
+        claimed_quote,
+        liquidity_delta
+    );
 
-        msg!(
-            "Paid investor {}: locked={}, payout={}",
-            investor_data.investor,
-            locked_amount,
-            raw_payout
-        );
-    }
+    Ok(quote_compounded)
+}
 
-    Ok(PageOutcome {
-        page_distributed,
-        page_dust,
-        processed_count,
-        success_count,
-        fail_count,
-        ata_creation_cost,
-    })
+/// Quote `cpi` takes out of `treasury`, read from the account data just before and after it
+fn treasury_spent(treasury: &AccountInfo, cpi: impl FnOnce() -> Result<()>) -> Result<u64> {
+    let before = token_amount(treasury)?;
+    cpi()?;
+    Ok(before.saturating_sub(token_amount(treasury)?))
 }
 
-/// Finalize the distribution day and transfer remainder to creator
-fn finalize_day<'info>(
-    progress_pda: &mut ProgressPda,
-    creator_quote_ata: &InterfaceAccount<'info, TokenAccount>,
-    quote_treasury: &InterfaceAccount<'info, TokenAccount>,
-    position_owner_pda: &Account<'info, InvestorFeePositionOwnerPda>,
-    quote_mint: &InterfaceAccount<'info, Mint>,
-    token_program: &Interface<'info, TokenInterface>,
-    vault_seed: &str,
-    position_owner_bump: u8,
-    current_timestamp: u64,
-    total_claimed: u128,
-    creator_payout: u128,
+/// CP-AMM claim_position_fee CPI
+fn claim_cp_amm_position_fee<'a, 'info: 'a>(
+    ctx: &Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
+    signer: &[&[&[u8]]],
 ) -> Result<()> {
-    // Transfer remainder to creator if > 0
-    if creator_payout > 0 {
-        // Transfer using transfer_checked
-        let seeds = &[
-            vault_seed.as_bytes(),
-            b"investor_fee_pos_owner",
+    // Validate the CP-AMM program and its PDAs against the vault's configured program
+    let cp_amm_program_id = ctx.accounts.policy_pda.cp_amm_program_id;
+    require_keys_eq!(
+        ctx.accounts.cp_amm_program.key(),
+        cp_amm_program_id,
+        FeeRouterError::InvalidCpAmmProgram
+    );
+    // Pinned PDAs were derived when cached and already compared in the handler
+    require!(
+        ctx.accounts.policy_pda.static_accounts.is_some()
+            || (ctx.accounts.pool_authority.key() == derive_pool_authority(&cp_amm_program_id)
+                && ctx.accounts.cp_amm_event_authority.key() == derive_event_authority(&cp_amm_program_id)),
+        FeeRouterError::InvalidCpAmmPda
+    );
+
+    // Pool must hold the passed mints/vaults and own the position
+    {
+        let pool = load_account::<Pool>(&ctx.accounts.pool, &cp_amm_program_id)?;
+        require!(
+            pool.token_a_mint == ctx.accounts.token_a_mint.key()
+                && pool.token_b_mint == ctx.accounts.token_b_mint.key()
+                && pool.token_a_vault == ctx.accounts.token_a_vault.key()
+                && pool.token_b_vault == ctx.accounts.token_b_vault.key(),
+            FeeRouterError::PoolAccountMismatch
+        );
+        let position = load_account::<Position>(&ctx.accounts.position, &cp_amm_program_id)?;
+        require_keys_eq!(
+            position.pool,
+            ctx.accounts.pool.key(),
+            FeeRouterError::PoolAccountMismatch
+        );
+
+        // A fee configuration that moved since init is re-checked rather than trusted
+        let verified_hash = ctx.accounts.position_owner_pda.pool_config_hash;
+        if verified_hash != [0u8; 32] && pool_fee_config_hash(&pool) != verified_hash {
+            msg!("Pool fee configuration changed since the position was verified");
+            validate_quote_only_fee_mode(&pool, pool.token_a_mint == ctx.accounts.policy_pda.quote_mint)?;
+        }
+
+        // A range drifting toward the price starts earning base fees after a small move; stop before that
+        let min_distance = ctx.accounts.policy_pda.min_tick_distance_from_price;
+        if min_distance > 0 {
+            let owner = &ctx.accounts.position_owner_pda;
+            validate_quote_only_position(&pool, owner.tick_lower, owner.tick_upper, &owner.quote_mint, min_distance)
+                .map_err(|_| error!(FeeRouterError::PositionTooCloseToPrice))?;
+        }
+    }
+
+    let position_nft_account = ctx
+        .accounts
+        .position_nft_account
+        .as_ref()
+        .ok_or(FeeRouterError::MissingRequiredInput)?;
+
+    // Build CPI accounts for claim_position_fee
+    let cpi_accounts = cp_amm::cpi::accounts::ClaimPositionFeeCtx {
+        pool_authority: ctx.accounts.pool_authority.to_account_info(),
+        pool: ctx.accounts.pool.to_account_info(),
+        position: ctx.accounts.position.to_account_info(),
+        token_a_account: ctx.accounts.temp_a_account.to_account_info(),
+        token_b_account: ctx.accounts.temp_b_account.to_account_info(),
+        token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+        token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+        token_a_mint: ctx.accounts.token_a_mint.to_account_info(),
+        token_b_mint: ctx.accounts.token_b_mint.to_account_info(),
+        position_nft_account: position_nft_account.to_account_info(),
+        owner: ctx.accounts.position_owner_pda.to_account_info(),
+        token_a_program: ctx.accounts.token_a_program.to_account_info(),
+        token_b_program: ctx.accounts.token_b_program.to_account_info(),
+        event_authority: ctx.accounts.cp_amm_event_authority.to_account_info(),
+        program: ctx.accounts.cp_amm_program.to_account_info(),
+    };
+
+    // Execute CPI call with PDA signer, at the policy's CP-AMM interface
+    let cpi_program = ctx.accounts.cp_amm_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    cp_amm_entry_points::claim_position_fee(&ctx.accounts.policy_pda.cp_amm_entry_points, cpi_ctx)
+}
+
+/// DLMM claim_fee CPI
+///
+/// remaining_accounts prefix: [bin_array_lower, bin_array_upper, dlmm_program, dlmm_event_authority]
+fn claim_dlmm_position_fee<'a, 'info: 'a>(
+    ctx: &Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let [bin_array_lower, bin_array_upper, dlmm_program, dlmm_event_authority] =
+        &ctx.remaining_accounts[..dlmm::CLAIM_REMAINING_ACCOUNTS]
+    else {
+        return err!(FeeRouterError::RemainingAccountsLayoutInvalid);
+    };
+
+    require_keys_eq!(dlmm_program.key(), dlmm::DLMM_PROGRAM_ID, FeeRouterError::InvalidDlmmProgram);
+    require_keys_eq!(
+        dlmm_event_authority.key(),
+        dlmm::derive_event_authority(),
+        FeeRouterError::InvalidDlmmProgram
+    );
+
+    // lb_pair must hold the passed mints/reserves; position must be ours and in this pair
+    let lb_pair = dlmm::read_lb_pair(&ctx.accounts.pool)?;
+    require!(
+        lb_pair.token_x_mint == ctx.accounts.token_a_mint.key()
+            && lb_pair.token_y_mint == ctx.accounts.token_b_mint.key()
+            && lb_pair.reserve_x == ctx.accounts.token_a_vault.key()
+            && lb_pair.reserve_y == ctx.accounts.token_b_vault.key(),
+        FeeRouterError::PoolAccountMismatch
+    );
+    let position = dlmm::read_position(&ctx.accounts.position)?;
+    require!(
+        position.lb_pair == ctx.accounts.pool.key()
+            && position.owner == ctx.accounts.position_owner_pda.key(),
+        FeeRouterError::PoolAccountMismatch
+    );
+    require_keys_eq!(
+        bin_array_lower.key(),
+        dlmm::derive_bin_array(&position.lb_pair, dlmm::bin_array_index(position.lower_bin_id)),
+        FeeRouterError::InvalidDlmmProgram
+    );
+    require_keys_eq!(
+        bin_array_upper.key(),
+        dlmm::derive_bin_array(&position.lb_pair, dlmm::bin_array_index(position.upper_bin_id)),
+        FeeRouterError::InvalidDlmmProgram
+    );
+
+    let ix = dlmm::claim_fee_ix(&dlmm::ClaimFeeAccounts {
+        lb_pair: ctx.accounts.pool.key(),
+        position: ctx.accounts.position.key(),
+        bin_array_lower: bin_array_lower.key(),
+        bin_array_upper: bin_array_upper.key(),
+        sender: ctx.accounts.position_owner_pda.key(),
+        reserve_x: ctx.accounts.token_a_vault.key(),
+        reserve_y: ctx.accounts.token_b_vault.key(),
+        user_token_x: ctx.accounts.temp_a_account.key(),
+        user_token_y: ctx.accounts.temp_b_account.key(),
+        token_x_mint: ctx.accounts.token_a_mint.key(),
+        token_y_mint: ctx.accounts.token_b_mint.key(),
+        token_program: ctx.accounts.token_a_program.key(),
+    });
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.pool.to_account_info(),
+            ctx.accounts.position.to_account_info(),
+            bin_array_lower.clone(),
+            bin_array_upper.clone(),
+            ctx.accounts.position_owner_pda.to_account_info(),
+            ctx.accounts.token_a_vault.to_account_info(),
+            ctx.accounts.token_b_vault.to_account_info(),
+            ctx.accounts.temp_a_account.to_account_info(),
+            ctx.accounts.temp_b_account.to_account_info(),
+            ctx.accounts.token_a_mint.to_account_info(),
+            ctx.accounts.token_b_mint.to_account_info(),
+            ctx.accounts.token_a_program.to_account_info(),
+            dlmm_event_authority.clone(),
+            dlmm_program.clone(),
+        ],
+        signer,
+    )
+    .map_err(Into::into)
+}
+
+/// Orca Whirlpool update_fees_and_rewards + collect_fees CPI
+///
+/// remaining_accounts prefix: [tick_array_lower, tick_array_upper, whirlpool_program]
+fn claim_whirlpool_position_fee<'a, 'info: 'a>(
+    ctx: &Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let [tick_array_lower, tick_array_upper, whirlpool_program] =
+        &ctx.remaining_accounts[..whirlpool::CLAIM_REMAINING_ACCOUNTS]
+    else {
+        return err!(FeeRouterError::RemainingAccountsLayoutInvalid);
+    };
+    require_keys_eq!(
+        whirlpool_program.key(),
+        whirlpool::WHIRLPOOL_PROGRAM_ID,
+        FeeRouterError::InvalidWhirlpoolProgram
+    );
+
+    // Whirlpool must hold the passed mints/vaults; position must be in it and its NFT ours
+    let pool = whirlpool::read_whirlpool(&ctx.accounts.pool)?;
+    require!(
+        pool.token_mint_a == ctx.accounts.token_a_mint.key()
+            && pool.token_mint_b == ctx.accounts.token_b_mint.key()
+            && pool.token_vault_a == ctx.accounts.token_a_vault.key()
+            && pool.token_vault_b == ctx.accounts.token_b_vault.key(),
+        FeeRouterError::PoolAccountMismatch
+    );
+    let position = whirlpool::read_position(&ctx.accounts.position)?;
+    require_keys_eq!(
+        position.whirlpool,
+        ctx.accounts.pool.key(),
+        FeeRouterError::PoolAccountMismatch
+    );
+    let position_nft_account = ctx
+        .accounts
+        .position_nft_account
+        .as_ref()
+        .ok_or(FeeRouterError::MissingRequiredInput)?;
+    require!(
+        position_nft_account.mint == position.position_mint && position_nft_account.amount == 1,
+        FeeRouterError::InvalidPositionOwner
+    );
+    require_keys_eq!(
+        tick_array_lower.key(),
+        whirlpool::derive_tick_array(
+            &position.whirlpool,
+            whirlpool::tick_array_start_index(position.tick_lower_index, pool.tick_spacing),
+        ),
+        FeeRouterError::InvalidWhirlpoolProgram
+    );
+    require_keys_eq!(
+        tick_array_upper.key(),
+        whirlpool::derive_tick_array(
+            &position.whirlpool,
+            whirlpool::tick_array_start_index(position.tick_upper_index, pool.tick_spacing),
+        ),
+        FeeRouterError::InvalidWhirlpoolProgram
+    );
+
+    // Fees only accrue to the position on update; Orca rejects the update for zero liquidity
+    if position.liquidity > 0 {
+        let ix = whirlpool::update_fees_and_rewards_ix(
+            ctx.accounts.pool.key(),
+            ctx.accounts.position.key(),
+            tick_array_lower.key(),
+            tick_array_upper.key(),
+        );
+        invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.pool.to_account_info(),
+                ctx.accounts.position.to_account_info(),
+                tick_array_lower.clone(),
+                tick_array_upper.clone(),
+                whirlpool_program.clone(),
+            ],
+            signer,
+        )?;
+    }
+
+    let ix = whirlpool::collect_fees_ix(&whirlpool::CollectFeesAccounts {
+        whirlpool: ctx.accounts.pool.key(),
+        position_authority: ctx.accounts.position_owner_pda.key(),
+        position: ctx.accounts.position.key(),
+        position_token_account: position_nft_account.key(),
+        token_owner_account_a: ctx.accounts.temp_a_account.key(),
+        token_vault_a: ctx.accounts.token_a_vault.key(),
+        token_owner_account_b: ctx.accounts.temp_b_account.key(),
+        token_vault_b: ctx.accounts.token_b_vault.key(),
+        token_program: ctx.accounts.token_a_program.key(),
+    });
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.pool.to_account_info(),
+            ctx.accounts.position_owner_pda.to_account_info(),
+            ctx.accounts.position.to_account_info(),
+            position_nft_account.to_account_info(),
+            ctx.accounts.temp_a_account.to_account_info(),
+            ctx.accounts.token_a_vault.to_account_info(),
+            ctx.accounts.temp_b_account.to_account_info(),
+            ctx.accounts.token_b_vault.to_account_info(),
+            ctx.accounts.token_a_program.to_account_info(),
+            whirlpool_program.clone(),
+        ],
+        signer,
+    )
+    .map_err(Into::into)
+}
+
+/// Claim Raydium CLMM fees via a zero-liquidity `decrease_liquidity_v2`
+///
+/// remaining_accounts prefix:
+/// [protocol_position, tick_array_lower, tick_array_upper, clmm_program, token_program_2022, memo_program]
+fn claim_raydium_position_fee<'a, 'info: 'a>(
+    ctx: &Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let [protocol_position, tick_array_lower, tick_array_upper, clmm_program, token_program_2022, memo_program] =
+        &ctx.remaining_accounts[..raydium_clmm::CLAIM_REMAINING_ACCOUNTS]
+    else {
+        return err!(FeeRouterError::RemainingAccountsLayoutInvalid);
+    };
+    require!(
+        clmm_program.key() == raydium_clmm::RAYDIUM_CLMM_PROGRAM_ID
+            && token_program_2022.key() == anchor_spl::token_2022::ID
+            && memo_program.key() == raydium_clmm::MEMO_PROGRAM_ID,
+        FeeRouterError::InvalidRaydiumProgram
+    );
+
+    // Pool must hold the passed mints/vaults; position must be in it and its NFT ours
+    let pool = raydium_clmm::read_pool(&ctx.accounts.pool)?;
+    require!(
+        pool.token_mint_0 == ctx.accounts.token_a_mint.key()
+            && pool.token_mint_1 == ctx.accounts.token_b_mint.key()
+            && pool.token_vault_0 == ctx.accounts.token_a_vault.key()
+            && pool.token_vault_1 == ctx.accounts.token_b_vault.key(),
+        FeeRouterError::PoolAccountMismatch
+    );
+    let position = raydium_clmm::read_personal_position(&ctx.accounts.position)?;
+    require_keys_eq!(
+        position.pool_id,
+        ctx.accounts.pool.key(),
+        FeeRouterError::PoolAccountMismatch
+    );
+    let position_nft_account = ctx
+        .accounts
+        .position_nft_account
+        .as_ref()
+        .ok_or(FeeRouterError::MissingRequiredInput)?;
+    require!(
+        position_nft_account.mint == position.nft_mint && position_nft_account.amount == 1,
+        FeeRouterError::InvalidPositionOwner
+    );
+    require!(
+        protocol_position.key()
+            == raydium_clmm::derive_protocol_position(
+                &position.pool_id,
+                position.tick_lower_index,
+                position.tick_upper_index,
+            )
+            && tick_array_lower.key()
+                == raydium_clmm::derive_tick_array(
+                    &position.pool_id,
+                    raydium_clmm::tick_array_start_index(position.tick_lower_index, pool.tick_spacing),
+                )
+            && tick_array_upper.key()
+                == raydium_clmm::derive_tick_array(
+                    &position.pool_id,
+                    raydium_clmm::tick_array_start_index(position.tick_upper_index, pool.tick_spacing),
+                ),
+        FeeRouterError::InvalidRaydiumProgram
+    );
+
+    let ix = raydium_clmm::collect_fee_ix(&raydium_clmm::DecreaseLiquidityAccounts {
+        nft_owner: ctx.accounts.position_owner_pda.key(),
+        nft_account: position_nft_account.key(),
+        personal_position: ctx.accounts.position.key(),
+        pool_state: ctx.accounts.pool.key(),
+        protocol_position: protocol_position.key(),
+        token_vault_0: ctx.accounts.token_a_vault.key(),
+        token_vault_1: ctx.accounts.token_b_vault.key(),
+        tick_array_lower: tick_array_lower.key(),
+        tick_array_upper: tick_array_upper.key(),
+        recipient_token_account_0: ctx.accounts.temp_a_account.key(),
+        recipient_token_account_1: ctx.accounts.temp_b_account.key(),
+        token_program: ctx.accounts.token_a_program.key(),
+        token_program_2022: token_program_2022.key(),
+        vault_0_mint: ctx.accounts.token_a_mint.key(),
+        vault_1_mint: ctx.accounts.token_b_mint.key(),
+    });
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.position_owner_pda.to_account_info(),
+            position_nft_account.to_account_info(),
+            ctx.accounts.position.to_account_info(),
+            ctx.accounts.pool.to_account_info(),
+            protocol_position.clone(),
+            ctx.accounts.token_a_vault.to_account_info(),
+            ctx.accounts.token_b_vault.to_account_info(),
+            tick_array_lower.clone(),
+            tick_array_upper.clone(),
+            ctx.accounts.temp_a_account.to_account_info(),
+            ctx.accounts.temp_b_account.to_account_info(),
+            ctx.accounts.token_a_program.to_account_info(),
+            token_program_2022.clone(),
+            memo_program.clone(),
+            ctx.accounts.token_a_mint.to_account_info(),
+            ctx.accounts.token_b_mint.to_account_info(),
+            clmm_program.clone(),
+        ],
+        signer,
+    )
+    .map_err(Into::into)
+}
+
+/// What `process_investor_page` did with a page
+struct PageOutcome {
+    page_distributed: u128,
+    page_transfer_fee: u64,
+    page_dust: u64,
+    /// Share of investors without a live attestation, booked per policy.unattested_routing
+    page_unattested: u64,
+    processed_count: u32,
+    success_count: u32,
+    fail_count: u32,
+    skip_count: u32,
+    ata_creation_cost: u64,
+    ata_reimbursed: u64,
+    page_failed_owed: u64,
+    /// Locked amount of the page's investors (ignored streams count as zero)
+    page_locked: u64,
+    /// Successful payouts, collected only when InvestorPaid events are batched per page
+    paid: Vec<InvestorPayout>,
+    /// Investor the page stopped before when compute ran low; None once the page is done
+    suspended_at: Option<u32>,
+}
+
+/// Hold a page's stream keys to the day's strictly ascending order (policy.track_paid_investors)
+///
+/// The paid bitmap is keyed by the crank-supplied investor index; the key order is what stops one
+/// stream from being processed again under another index. Ordered pages must start above the last
+/// stream processed today, unordered ones must not overlap the key range of a completed page.
+fn claim_page_keys<P: InvestorPageView>(
+    bitmap: &mut PaidBitmapPda,
+    progress: &ProgressPda,
+    page: &P,
+    unordered: bool,
+    resumed: bool,
+) -> Result<()> {
+    let streams: Vec<Pubkey> = (0..page.investor_count()).map(|i| page.investor(i).stream).collect();
+    require!(
+        streams.windows(2).all(|pair| pair[0] < pair[1]),
+        FeeRouterError::InvestorKeysNotAscending
+    );
+    let (Some(first), Some(last)) = (streams.first(), streams.last()) else {
+        return Ok(());
+    };
+    if unordered {
+        let processed = (0..progress.total_pages_expected).filter(|page_index| progress.page_processed(*page_index));
+        bitmap.claim_page_range(page.page_index(), first, last, processed)
+    } else if resumed {
+        // Its keys were claimed when the page started
+        Ok(())
+    } else {
+        bitmap.advance_streams(first, last)
+    }
+}
+
+/// Accounts an investor page is paid out with, fixed for the whole call
+struct PageAccounts<'a, 'info> {
+    treasury: FinalizeAccounts<'a, 'info>,
+    associated_token_program: &'a Program<'info, AssociatedToken>,
+    system_program: &'a Program<'info, System>,
+    /// Crank caller, who pays for investor ATAs and escrows and is reimbursed their rent
+    payer: &'a AccountInfo<'info>,
+    /// Per-investor accounts of every page in the call, in order
+    remaining_accounts: &'a [AccountInfo<'info>],
+    lockers: &'a LockerSources,
+    stream_topup: Option<&'a StreamTopup<'info>>,
+    payout_escrow: Option<&'a PayoutEscrow<'info>>,
+}
+
+/// What an investor page is weighed against and the policy terms it is paid under
+#[derive(Clone, Copy)]
+struct PageTerms<'a> {
+    vault_seed: &'a str,
+    day_epoch: u64,
+    current_timestamp: u64,
+    /// Snapshot time the locked amounts are read at
+    locked_ts: u64,
+    weight_cap: &'a WeightCap,
+    total_weight: u128,
+    investor_fee_quote: u128,
+    min_payout_lamports: u64,
+    payout_overrides: Option<&'a PayoutOverridesPda>,
+    weight_overrides: Option<&'a WeightOverridesPda>,
+    attestation: Option<&'a AttestationGate>,
+    unattested_routing: UnattestedRouting,
+    fund_missing_ata: bool,
+    batch_paid_events: bool,
+}
+
+/// Running state the call's pages carry from one to the next
+struct PageTally<'a, 'info> {
+    /// Payout weight of the investors of today's completed pages
+    weight_before: u128,
+    payout_locked_hash: [u8; 32],
+    payout_locked_set: [u8; 32],
+    /// Position of the next page's accounts in `PageAccounts::remaining_accounts`
+    remaining_accounts_index: usize,
+    failed_ledger: Option<&'a mut Account<'info, FailedPayoutsPda>>,
+    paid_bitmap: Option<&'a mut Account<'info, PaidBitmapPda>>,
+}
+
+/// Process a single investor page and distribute payouts
+fn process_investor_page<'info, P: InvestorPageView>(
+    investor_page: &P,
+    first_investor: usize,
+    mut can_suspend: bool,
+    accounts: &PageAccounts<'_, 'info>,
+    terms: &PageTerms,
+    tally: &mut PageTally<'_, 'info>,
+) -> Result<PageOutcome> {
+    let PageAccounts {
+        treasury:
+            FinalizeAccounts {
+                quote_treasury,
+                position_owner_pda,
+                quote_mint,
+                token_program,
+                memo_program,
+                position_owner_bump,
+                events,
+            },
+        associated_token_program,
+        system_program,
+        payer,
+        remaining_accounts,
+        lockers,
+        stream_topup,
+        payout_escrow,
+    } = *accounts;
+    let PageTerms {
+        vault_seed,
+        day_epoch,
+        current_timestamp,
+        locked_ts,
+        weight_cap,
+        total_weight,
+        investor_fee_quote,
+        min_payout_lamports,
+        payout_overrides,
+        weight_overrides,
+        attestation,
+        unattested_routing,
+        fund_missing_ata,
+        batch_paid_events,
+    } = *terms;
+    let PageTally {
+        weight_before,
+        payout_locked_hash,
+        payout_locked_set,
+        remaining_accounts_index,
+        failed_ledger,
+        paid_bitmap,
+    } = tally;
+    let mut page_distributed = 0u128;
+    let mut page_transfer_fee = 0u64;
+    let mut page_dust = 0u64;
+    let mut page_unattested = 0u64;
+    let mut processed_count: u32 = 0;
+    let mut suspended_at = None;
+    let mut success_count: u32 = 0;
+    let mut fail_count: u32 = 0;
+    let mut skip_count: u32 = 0;
+    let mut ata_creation_cost: u64 = 0;
+    let mut ata_reimbursed: u64 = 0;
+    let mut page_failed_owed: u64 = 0;
+    let mut paid = Vec::new();
+
+    require!(
+        first_investor < investor_page.investor_count().max(1),
+        FeeRouterError::InvalidPaginationState
+    );
+
+    // Read every locked amount up front: the page's allocation is apportioned across the whole page,
+    // so a resumed page recomputes the same payouts for the investors it has left
+    let stride = accounts_per_investor(attestation);
+    let stream_locked = (0..investor_page.investor_count())
+        .map(|i| {
+            let stream_account_info = remaining_accounts
+                .get(*remaining_accounts_index + stride * i)
+                .ok_or(FeeRouterError::RemainingAccountsLayoutInvalid)?;
+            let investor_data = investor_page.investor(i);
+            let weight = weight_overrides.map_or(0, |table| table.weight_for(&investor_data.investor));
+            lockers.read_investor_locked(stream_account_info, &investor_data, locked_ts)?.with_weight(weight)
+        })
+        .collect::<Result<Vec<StreamLocked>>>()?;
+    let locked_amounts: Vec<u128> = stream_locked
+        .iter()
+        .map(|entry| match entry {
+            StreamLocked::Locked(amount) => *amount as u128,
+            StreamLocked::Ignored(_) => 0,
+        })
+        .collect();
+    let page_locked = locked_amounts
+        .iter()
+        .try_fold(0u128, |sum, amount| sum.checked_add(*amount))
+        .ok_or(FeeRouterError::Overflow)?;
+    let payout_weights = locked_amounts
+        .iter()
+        .map(|locked| weight_cap.payout_weight(*locked))
+        .collect::<core::result::Result<Vec<u128>, _>>()
+        .map_err(FeeRouterError::from)?;
+    let page_weight = payout_weights
+        .iter()
+        .try_fold(0u128, |sum, weight| sum.checked_add(*weight))
+        .ok_or(FeeRouterError::Overflow)?;
+
+    // Largest-remainder split of a running-floor allocation, so payouts add up to the pool
+    let allocation = DistributionMath::calculate_page_allocation(
+        *weight_before,
+        page_weight,
+        total_weight,
+        investor_fee_quote,
+    ).map_err(FeeRouterError::from)?;
+    let payouts = DistributionMath::apportion_largest_remainder(
+        &payout_weights,
+        total_weight,
+        investor_fee_quote,
+        allocation,
+    ).map_err(FeeRouterError::from)?;
+
+    // Investors a suspended call already handled keep their outcome. A top-up raises the stream's
+    // locked amount, so a page paying into escrow may only suspend before its first investor:
+    // resuming would reread topped-up streams and split the page differently
+    *remaining_accounts_index += stride * first_investor;
+    for i in first_investor..investor_page.investor_count() {
+        if can_suspend && (stream_topup.is_none() || i == 0) && compute_low(PAGE_RESUME_COMPUTE_RESERVE) {
+            suspended_at = Some(i as u32);
+            break;
+        }
+        can_suspend = true;
+        processed_count += 1;
+        let investor_data = &investor_page.investor(i);
+
+        // Refuse an investor index already handled today, whatever its outcome was
+        if let Some(bitmap) = paid_bitmap.as_deref_mut() {
+            bitmap.mark_paid(investor_data.index)?;
+        }
+
+        // Stream account, already read above
+        let stream_account_info = remaining_accounts
+            .get(*remaining_accounts_index)
+            .ok_or(FeeRouterError::RemainingAccountsLayoutInvalid)?;
+        *remaining_accounts_index += 1;
+
+        // Get investor quote ATA from remaining_accounts (the stream's escrow under stream_topup)
+        let investor_quote_ata_info = remaining_accounts
+            .get(*remaining_accounts_index)
+            .ok_or(FeeRouterError::RemainingAccountsLayoutInvalid)?;
+        *remaining_accounts_index += 1;
+        // Get investor owner account
+        let investor_owner_info = remaining_accounts
+            .get(*remaining_accounts_index)
+            .ok_or(FeeRouterError::RemainingAccountsLayoutInvalid)?;
+        *remaining_accounts_index += 1;
+        // Get investor attestation PDA when the policy requires one
+        let attestation_info = match attestation {
+            Some(_) => {
+                let info = remaining_accounts
+                    .get(*remaining_accounts_index)
+                    .ok_or(FeeRouterError::RemainingAccountsLayoutInvalid)?;
+                *remaining_accounts_index += 1;
+                Some(info)
+            }
+            None => None,
+        };
+        
+        // Canceled or closed streams are skipped, not fatal
+        let stream_locked = stream_locked[i];
+        let folded_amount = match stream_locked {
+            StreamLocked::Locked(amount) => amount,
+            StreamLocked::Ignored(_) => 0,
+        };
+        *payout_locked_hash = ProgressPda::fold_locked_entry(
+            payout_locked_hash,
+            &investor_data.stream,
+            &investor_data.investor,
+            folded_amount,
+        );
+        *payout_locked_set = ProgressPda::add_locked_set_entry(
+            payout_locked_set,
+            &investor_data.stream,
+            &investor_data.investor,
+            folded_amount,
+        );
+        let locked_amount = match stream_locked {
+            StreamLocked::Locked(amount) => amount as u128,
+            StreamLocked::Ignored(reason) => {
+                events.emit(StreamIgnored {
+                    schema_version: EVENT_SCHEMA_VERSION,
+                    vault_seed: vault_seed.to_string(),
+                    stream: investor_data.stream,
+                    investor: investor_data.investor,
+                    reason,
+                    timestamp: current_timestamp,
+                })?;
+                msg!(
+                    "Stream {} for investor {} ignored ({:?})",
+                    investor_data.stream,
+                    investor_data.investor,
+                    reason
+                );
+                skip_count = skip_count.saturating_add(1);
+                continue;
+            }
+        };
+        
+        // Skip if no locked amount
+        if locked_amount == 0 {
+            emit_investor_skipped(events, vault_seed, day_epoch, investor_data, SkipReason::ZeroLocked, 0)?;
+            skip_count = skip_count.saturating_add(1);
+            msg!(
+                "Investor {} has zero locked amount; skipping payout",
+                investor_data.investor
+            );
+            continue;
+        }
+        
+        let raw_payout = payouts[i];
+
+        // Compliance gate: an investor without a live attestation is not paid; the share is routed
+        // per policy once the call's pages are done
+        if let (Some(gate), Some(info)) = (attestation, attestation_info) {
+            if !gate.is_attested(info, &investor_data.investor, current_timestamp)? {
+                page_unattested = page_unattested
+                    .checked_add(raw_payout as u64)
+                    .ok_or(FeeRouterError::Overflow)?;
+                emit_investor_skipped(
+                    events,
+                    vault_seed,
+                    day_epoch,
+                    investor_data,
+                    SkipReason::NotAttested,
+                    raw_payout as u64,
+                )?;
+                skip_count = skip_count.saturating_add(1);
+                msg!(
+                    "Investor {} has no live attestation; payout {} routed as {:?}",
+                    investor_data.investor,
+                    raw_payout,
+                    unattested_routing
+                );
+                continue;
+            }
+        }
+
+        // Check minimum payout threshold, or the investor's override
+        let min_payout_lamports = payout_overrides
+            .map_or(min_payout_lamports, |table| table.min_payout_for(&investor_data.investor, min_payout_lamports));
+        if raw_payout < min_payout_lamports as u128 {
+            page_dust += raw_payout as u64;
+            emit_investor_skipped(
+                events,
+                vault_seed,
+                day_epoch,
+                investor_data,
+                SkipReason::BelowMinPayout,
+                raw_payout as u64,
+            )?;
+            skip_count = skip_count.saturating_add(1);
+            msg!(
+                "Investor {} payout {} below threshold {}, added to dust",
+                investor_data.investor,
+                raw_payout,
+                min_payout_lamports
+            );
+            continue;
+        }
+
+        // Validate investor quote ATA exists and matches expected mint/owner, or that the stream takes a
+        // top-up. An escrowed payout stays in the treasury, so any quote account will do
+        let mut ata_status = match stream_topup {
+            _ if payout_escrow.is_some() => QuoteAtaStatus::Valid,
+            Some(topup) => topup.escrow_status(stream_account_info, investor_quote_ata_info, &quote_mint.key()),
+            None => quote_ata_status(investor_quote_ata_info, &quote_mint.key(), &investor_data.investor),
+        };
+
+        if ata_status == QuoteAtaStatus::Invalid {
+            if fund_missing_ata {
+                // Validate investor owner matches expected investor
+                require_keys_eq!(
+                    investor_owner_info.key(),
+                    investor_data.investor,
+                    FeeRouterError::InvestorOwnerMismatch
+                );
+                // Create the investor ATA idempotently; the ATA program checks the address
+                anchor_spl::associated_token::create_idempotent(
+                    CpiContext::new(
+                        associated_token_program.to_account_info(),
+                        anchor_spl::associated_token::Create {
+                            payer: payer.clone(),
+                            associated_token: investor_quote_ata_info.clone(),
+                            authority: investor_owner_info.clone(),
+                            mint: quote_mint.to_account_info(),
+                            system_program: system_program.to_account_info(),
+                            token_program: token_program.to_account_info(),
+                        },
+                    ),
+                )?;
+                // Rent actually paid (Token-2022 ATAs can exceed 165 bytes), refunded to the
+                // crank caller from the position owner PDA's lamports above its own rent
+                let ata_rent = investor_quote_ata_info.lamports();
+                ata_creation_cost = ata_creation_cost.saturating_add(ata_rent);
+                let reimbursed = reimburse_ata_rent(&position_owner_pda.to_account_info(), payer, ata_rent)?;
+                ata_reimbursed = ata_reimbursed.saturating_add(reimbursed);
+                msg!(
+                    "Created quote ATA for investor {} (rent {}, reimbursed {})",
+                    investor_data.investor,
+                    ata_rent,
+                    reimbursed
+                );
+                ata_status = QuoteAtaStatus::Valid;
+            } else if failed_ledger.is_none() {
+                page_dust += raw_payout as u64;
+                events.emit(InvestorAtaMissing {
+                    schema_version: EVENT_SCHEMA_VERSION,
+                    vault_seed: vault_seed.to_string(),
+                    investor: investor_data.investor,
+                    quote_ata: investor_quote_ata_info.key(),
+                    payout: raw_payout as u64,
+                    timestamp: current_timestamp,
+                })?;
+                emit_investor_skipped(
+                    events,
+                    vault_seed,
+                    day_epoch,
+                    investor_data,
+                    SkipReason::MissingAta,
+                    raw_payout as u64,
+                )?;
+                msg!(
+                    "Investor {} missing or invalid quote ATA; added payout {} to dust",
+                    investor_data.investor,
+                    raw_payout
+                );
+                fail_count = fail_count.saturating_add(1);
+                continue;
+            }
+        }
+        if ata_status == QuoteAtaStatus::StreamNotToppable && failed_ledger.is_none() {
+            page_dust += raw_payout as u64;
+            emit_investor_skipped(
+                events,
+                vault_seed,
+                day_epoch,
+                investor_data,
+                SkipReason::StreamNotToppable,
+                raw_payout as u64,
+            )?;
+            msg!(
+                "Stream {} of investor {} can't be topped up; added payout {} to dust",
+                investor_data.stream,
+                investor_data.investor,
+                raw_payout
+            );
+            fail_count = fail_count.saturating_add(1);
+            continue;
+        }
+
+        // Continue-on-failure: a transfer into an invalid or frozen account would abort the whole
+        // transaction, so record what the investor is owed and move on
+        if let (Some(ledger), Some(reason)) = (failed_ledger.as_deref_mut(), ata_status.failure_reason()) {
+            ledger.record(&investor_data.investor, raw_payout as u64, day_epoch)?;
+            ledger.updated_at = current_timestamp;
+            page_failed_owed = page_failed_owed.saturating_add(raw_payout as u64);
+            fail_count = fail_count.saturating_add(1);
+            events.emit(FailedPayoutRecorded {
+                schema_version: EVENT_SCHEMA_VERSION,
+                vault_seed: vault_seed.to_string(),
+                quote_mint: quote_mint.key(),
+                decimals: quote_mint.decimals,
+                investor: investor_data.investor,
+                quote_ata: investor_quote_ata_info.key(),
+                amount: raw_payout as u64,
+                reason,
+                total_owed: ledger.total_owed,
+                timestamp: current_timestamp,
+            })?;
+            msg!(
+                "Payout {} to investor {} recorded as failed ({:?})",
+                raw_payout,
+                investor_data.investor,
+                reason
+            );
+            continue;
+        }
+
+        // Transfer payout to investor via transfer_checked
+        let seeds = &[
+            vault_seed.as_bytes(),
+            b"investor_fee_pos_owner",
             &[position_owner_bump],
         ];
         let signer = &[&seeds[..]];
 
-        transfer_checked(
+        // Dispute window: credit the investor's escrow; the quote leaves the treasury on withdrawal
+        if let Some(escrow) = payout_escrow {
+            let (release_ts, total_escrowed, escrow_rent) = escrow.credit(
+                investor_quote_ata_info,
+                payer,
+                vault_seed,
+                &investor_data.investor,
+                day_epoch,
+                raw_payout as u64,
+                current_timestamp,
+            )?;
+            if escrow_rent > 0 {
+                ata_creation_cost = ata_creation_cost.saturating_add(escrow_rent);
+                let reimbursed = reimburse_ata_rent(&position_owner_pda.to_account_info(), payer, escrow_rent)?;
+                ata_reimbursed = ata_reimbursed.saturating_add(reimbursed);
+            }
+            events.emit(PayoutEscrowed {
+                schema_version: EVENT_SCHEMA_VERSION,
+                vault_seed: vault_seed.to_string(),
+                day_epoch,
+                investor: investor_data.investor,
+                amount: raw_payout as u64,
+                release_ts,
+                total_escrowed,
+            })?;
+        } else {
+            emit_payout_memo(memo_program, vault_seed, day_epoch)?;
+        }
+        match stream_topup {
+            _ if payout_escrow.is_some() => {}
+            Some(topup) => topup.deposit(
+                stream_account_info,
+                investor_quote_ata_info,
+                quote_treasury,
+                position_owner_pda,
+                quote_mint,
+                token_program,
+                signer,
+                raw_payout as u64,
+            )?,
+            None => transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: quote_treasury.to_account_info(),
+                        mint: quote_mint.to_account_info(),
+                        to: investor_quote_ata_info.clone(),
+                        authority: position_owner_pda.to_account_info(),
+                    },
+                    signer,
+                ),
+                raw_payout as u64,
+                quote_mint.decimals,
+            )?,
+        }
+
+        // Progress tracks what left the treasury; the investor receives it net of transfer fee.
+        // Escrowed payouts pay the fee when withdrawn
+        let transfer_fee = match payout_escrow {
+            Some(_) => 0,
+            None => transfer_fee_for(&quote_mint.to_account_info(), raw_payout as u64)?,
+        };
+        page_transfer_fee = page_transfer_fee.saturating_add(transfer_fee);
+        page_distributed += raw_payout;
+        success_count = success_count.saturating_add(1);
+
+        let payout = InvestorPayout {
+            investor: investor_data.investor,
+            stream: investor_data.stream,
+            locked_amount: locked_amount as u64,
+            payout: raw_payout as u64,
+        };
+        if batch_paid_events {
+            paid.push(payout);
+        } else {
+            events.emit(InvestorPaid {
+                schema_version: EVENT_SCHEMA_VERSION,
+                vault_seed: vault_seed.to_string(),
+                quote_mint: quote_mint.key(),
+                decimals: quote_mint.decimals,
+                day_epoch,
+                investor: payout.investor,
+                stream: payout.stream,
+                locked_amount: payout.locked_amount,
+                payout: payout.payout,
+            })?;
+        }
+
+        msg!(
+            "Paid investor {}: locked={}, payout={}, transfer_fee={}",
+            investor_data.investor,
+            locked_amount,
+            raw_payout,
+            transfer_fee
+        );
+    }
+
+    if suspended_at.is_none() {
+        *weight_before = weight_before
+            .checked_add(page_weight)
+            .ok_or(FeeRouterError::Overflow)?;
+    }
+
+    Ok(PageOutcome {
+        page_distributed,
+        page_transfer_fee,
+        page_dust,
+        page_unattested,
+        processed_count,
+        success_count,
+        fail_count,
+        skip_count,
+        ata_creation_cost,
+        ata_reimbursed,
+        page_failed_owed,
+        page_locked: page_locked as u64,
+        paid,
+        suspended_at,
+    })
+}
+
+/// Remaining accounts each investor takes: stream, quote ATA, investor, and the attestation PDA
+/// while the policy requires one
+pub(crate) fn accounts_per_investor(attestation: Option<&AttestationGate>) -> usize {
+    if attestation.is_some() { 4 } else { 3 }
+}
+
+/// Whether fewer than `reserve` compute units remain in the transaction
+fn compute_low(reserve: u64) -> bool {
+    anchor_lang::solana_program::compute_units::sol_remaining_compute_units() < reserve
+}
+
+/// Record why an investor got nothing, so support can answer without reading logs
+fn emit_investor_skipped(
+    events: &EventCpi,
+    vault_seed: &str,
+    day_epoch: u64,
+    investor_data: &InvestorData,
+    reason: SkipReason,
+    amount: u64,
+) -> Result<()> {
+    events.emit(InvestorSkipped {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.to_string(),
+        day_epoch,
+        investor: investor_data.investor,
+        stream: investor_data.stream,
+        reason,
+        amount,
+    })
+}
+
+/// Payout-relevant state of an investor's quote token account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum QuoteAtaStatus {
+    Valid,
+    /// Missing, closed, not a token account, or not the investor's account for the quote mint
+    Invalid,
+    Frozen,
+    /// Paying into stream escrow, and the stream or escrow account can't take a top-up
+    StreamNotToppable,
+}
+
+impl QuoteAtaStatus {
+    fn failure_reason(self) -> Option<PayoutFailureReason> {
+        match self {
+            QuoteAtaStatus::Valid => None,
+            QuoteAtaStatus::Invalid => Some(PayoutFailureReason::InvalidAta),
+            QuoteAtaStatus::Frozen => Some(PayoutFailureReason::FrozenAta),
+            QuoteAtaStatus::StreamNotToppable => Some(PayoutFailureReason::StreamNotToppable),
+        }
+    }
+}
+
+/// Owner field of a token account, or None if `info` is too short to be one
+pub(crate) fn token_account_owner(info: &AccountInfo) -> Option<Pubkey> {
+    match info.try_borrow_data() {
+        Ok(data) if data.len() >= 165 => Some(read_pubkey(&data, 32)),
+        _ => None,
+    }
+}
+
+/// Whether a token account of the position owner PDA has no delegate or close authority and
+/// isn't frozen, so no one else can move, close or lock its balance between claim and payout
+pub(crate) fn token_account_unencumbered(account: &TokenAccount) -> bool {
+    account.delegate.is_none() && account.close_authority.is_none() && !account.is_frozen()
+}
+
+/// Classify `info` as `investor`'s quote token account without failing
+pub(crate) fn quote_ata_status(info: &AccountInfo, quote_mint: &Pubkey, investor: &Pubkey) -> QuoteAtaStatus {
+    if *info.owner != anchor_spl::token::ID && *info.owner != anchor_spl::token_2022::ID {
+        return QuoteAtaStatus::Invalid;
+    }
+    let Ok(data) = info.try_borrow_data() else {
+        return QuoteAtaStatus::Invalid;
+    };
+    if data.len() < 165 || read_pubkey(&data, 0) != *quote_mint || read_pubkey(&data, 32) != *investor {
+        return QuoteAtaStatus::Invalid;
+    }
+    if data[TOKEN_ACCOUNT_STATE_OFFSET] == TOKEN_ACCOUNT_STATE_FROZEN {
+        QuoteAtaStatus::Frozen
+    } else {
+        QuoteAtaStatus::Valid
+    }
+}
+
+/// Move up to `amount` lamports from the position owner PDA to the crank caller, keeping the
+/// PDA rent-exempt; returns the amount moved
+///
+/// The PDA's lamports above the rent-exempt minimum are the reserve `fund_ata_reserve` tops up;
+/// the quote treasury is a token account and has no lamports to spare.
+fn reimburse_ata_rent<'info>(
+    position_owner_pda: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    let rent_floor = Rent::get()?.minimum_balance(position_owner_pda.data_len());
+    move_spare_lamports(position_owner_pda, payer, amount, rent_floor)
+}
+
+/// Move up to `amount` of `from`'s lamports above `floor` to `to`; returns the amount moved
+fn move_spare_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64, floor: u64) -> Result<u64> {
+    let spare = from.lamports().saturating_sub(floor);
+    let reimbursed = amount.min(spare);
+    if reimbursed > 0 {
+        **from.try_borrow_mut_lamports()? -= reimbursed;
+        **to.try_borrow_mut_lamports()? += reimbursed;
+    }
+    Ok(reimbursed)
+}
+
+/// Accounts for delivering the creator payout as native SOL
+pub(crate) struct CreatorUnwrap<'info> {
+    pub wsol_temp: AccountInfo<'info>,
+    pub creator_wallet: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Accounts for creating a missing creator quote ATA at finalize (policy.fund_missing_creator_ata)
+pub(crate) struct CreatorAtaFunding<'info> {
+    pub creator_wallet: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Create `creator_quote_ata` as the creator wallet's ATA when it doesn't exist, with the caller
+/// paying the rent and refunded from the position owner PDA like investor ATAs
+///
+/// The ATA program checks the address, so a creator_quote_ata that isn't the wallet's ATA fails.
+fn fund_creator_ata<'info>(
+    funding: &CreatorAtaFunding<'info>,
+    creator_quote_ata: &AccountInfo<'info>,
+    position_owner_pda: &Account<'info, InvestorFeePositionOwnerPda>,
+    quote_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if !creator_quote_ata.data_is_empty() {
+        return Ok(());
+    }
+    anchor_spl::associated_token::create_idempotent(CpiContext::new(
+        funding.associated_token_program.clone(),
+        anchor_spl::associated_token::Create {
+            payer: funding.payer.clone(),
+            associated_token: creator_quote_ata.clone(),
+            authority: funding.creator_wallet.clone(),
+            mint: quote_mint.to_account_info(),
+            system_program: funding.system_program.clone(),
+            token_program: token_program.to_account_info(),
+        },
+    ))?;
+    let ata_rent = creator_quote_ata.lamports();
+    let reimbursed = reimburse_ata_rent(&position_owner_pda.to_account_info(), &funding.payer, ata_rent)?;
+    msg!(
+        "Created creator quote ATA for {} (rent {}, reimbursed {})",
+        funding.creator_wallet.key(),
+        ata_rent,
+        reimbursed
+    );
+    Ok(())
+}
+
+/// Close the temp wSOL account (payout plus rent) to the crank caller, who paid its rent,
+/// then forward the payout lamports to the creator wallet
+fn unwrap_to_creator<'info>(
+    unwrap: &CreatorUnwrap<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    position_owner_pda: &Account<'info, InvestorFeePositionOwnerPda>,
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: unwrap.wsol_temp.clone(),
+            destination: unwrap.payer.clone(),
+            authority: position_owner_pda.to_account_info(),
+        },
+        signer,
+    ))?;
+    system_program::transfer(
+        CpiContext::new(
+            unwrap.system_program.clone(),
+            system_program::Transfer {
+                from: unwrap.payer.clone(),
+                to: unwrap.creator_wallet.clone(),
+            },
+        ),
+        amount,
+    )?;
+    msg!("Unwrapped {} lamports to creator wallet {}", amount, unwrap.creator_wallet.key());
+    Ok(())
+}
+
+/// Accounts for vesting the creator payout through a new Streamflow stream
+pub(crate) struct CreatorStream<'info> {
+    pub metadata: AccountInfo<'info>,
+    pub escrow_tokens: AccountInfo<'info>,
+    pub withdrawor: AccountInfo<'info>,
+    pub fee_oracle: AccountInfo<'info>,
+    pub streamflow_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub vesting_secs: u64,
+}
+
+/// Accounts for paying investors into their Streamflow escrow through Streamflow's topup
+pub(crate) struct StreamTopup<'info> {
+    pub treasury: AccountInfo<'info>,
+    pub treasury_tokens: AccountInfo<'info>,
+    pub withdrawor: AccountInfo<'info>,
+    pub streamflow_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> StreamTopup<'info> {
+    /// Classify `escrow` as the top-up destination of `stream` without failing
+    fn escrow_status(&self, stream: &AccountInfo, escrow: &AccountInfo, quote_mint: &Pubkey) -> QuoteAtaStatus {
+        if *stream.owner != STREAMFLOW_PROGRAM_ID {
+            return QuoteAtaStatus::StreamNotToppable;
+        }
+        let Ok(contract) = streamflow::parse_streamflow_account(stream) else {
+            return QuoteAtaStatus::StreamNotToppable;
+        };
+        if !contract.accepts_router_topup(quote_mint)
+            || contract.escrow_tokens != escrow.key()
+            || contract.streamflow_treasury_tokens != self.treasury_tokens.key()
+        {
+            return QuoteAtaStatus::StreamNotToppable;
+        }
+        if *escrow.owner != anchor_spl::token::ID && *escrow.owner != anchor_spl::token_2022::ID {
+            return QuoteAtaStatus::StreamNotToppable;
+        }
+        match escrow.try_borrow_data() {
+            Ok(data) if data.len() >= 165 && read_pubkey(&data, 0) == *quote_mint => {
+                if data[TOKEN_ACCOUNT_STATE_OFFSET] == TOKEN_ACCOUNT_STATE_FROZEN {
+                    QuoteAtaStatus::Frozen
+                } else {
+                    QuoteAtaStatus::Valid
+                }
+            }
+            _ => QuoteAtaStatus::StreamNotToppable,
+        }
+    }
+
+    /// Top up `stream` with `amount` from the treasury; Streamflow moves it into `escrow`
+    #[allow(clippy::too_many_arguments)]
+    fn deposit(
+        &self,
+        stream: &AccountInfo<'info>,
+        escrow: &AccountInfo<'info>,
+        quote_treasury: &InterfaceAccount<'info, TokenAccount>,
+        position_owner_pda: &Account<'info, InvestorFeePositionOwnerPda>,
+        quote_mint: &InterfaceAccount<'info, Mint>,
+        token_program: &Interface<'info, TokenInterface>,
+        signer: &[&[&[u8]]],
+        amount: u64,
+    ) -> Result<()> {
+        let ix = streamflow::topup_stream_ix(
+            &TopupStreamAccounts {
+                sender: position_owner_pda.key(),
+                sender_tokens: quote_treasury.key(),
+                metadata: stream.key(),
+                escrow_tokens: escrow.key(),
+                streamflow_treasury_tokens: self.treasury_tokens.key(),
+                mint: quote_mint.key(),
+                token_program: token_program.key(),
+            },
+            amount,
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                position_owner_pda.to_account_info(),
+                quote_treasury.to_account_info(),
+                stream.clone(),
+                escrow.clone(),
+                self.treasury.clone(),
+                self.treasury_tokens.clone(),
+                self.withdrawor.clone(),
+                quote_mint.to_account_info(),
+                token_program.to_account_info(),
+                self.system_program.clone(),
+                self.streamflow_program.clone(),
+            ],
+            signer,
+        )?;
+        Ok(())
+    }
+}
+
+/// Holds investor payouts in per-investor escrow PDAs for the dispute window
+pub(crate) struct PayoutEscrow<'info> {
+    pub window_secs: u64,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> PayoutEscrow<'info> {
+    /// Credit `amount` to `investor`'s escrow at `escrow`, creating it with `payer`'s lamports on
+    /// first use. Returns (release_ts, total_escrowed, rent paid).
+    #[allow(clippy::too_many_arguments)]
+    fn credit(
+        &self,
+        escrow: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        vault_seed: &str,
+        investor: &Pubkey,
+        day_epoch: u64,
+        amount: u64,
+        current_ts: u64,
+    ) -> Result<(u64, u64, u64)> {
+        let (expected, bump) = Pubkey::find_program_address(&PayoutEscrowPda::seeds(vault_seed, investor), &crate::ID);
+        require_keys_eq!(escrow.key(), expected, FeeRouterError::RemainingAccountsLayoutInvalid);
+
+        let (mut ledger, rent_paid) = if *escrow.owner == crate::ID {
+            (PayoutEscrowPda::try_deserialize(&mut &escrow.try_borrow_data()?[..])?, 0)
+        } else {
+            let rent_paid = self.create(escrow, payer, vault_seed, investor, bump)?;
+            let ledger = PayoutEscrowPda {
+                vault_seed: vault_seed.to_string(),
+                investor: *investor,
+                total_escrowed: 0,
+                entries: Vec::new(),
+                updated_at: current_ts,
+            };
+            (ledger, rent_paid)
+        };
+
+        let release_ts = current_ts.saturating_add(self.window_secs);
+        ledger.credit(day_epoch, amount, release_ts)?;
+        ledger.updated_at = current_ts;
+        ledger.try_serialize(&mut &mut escrow.try_borrow_mut_data()?[..])?;
+        Ok((release_ts, ledger.total_escrowed, rent_paid))
+    }
+
+    /// Allocate the escrow PDA to the router, returning the rent `payer` put in
+    ///
+    /// Like the creator stream metadata, lamports sent to the address ahead of time don't block it.
+    fn create(
+        &self,
+        escrow: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        vault_seed: &str,
+        investor: &Pubkey,
+        bump: u8,
+    ) -> Result<u64> {
+        let seeds = &[vault_seed.as_bytes(), b"payout_escrow", investor.as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+        let rent = Rent::get()?.minimum_balance(PayoutEscrowPda::LEN);
+        let shortfall = rent.saturating_sub(escrow.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.clone(),
+                    system_program::Transfer { from: payer.clone(), to: escrow.clone() },
+                ),
+                shortfall,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                self.system_program.clone(),
+                system_program::Allocate { account_to_allocate: escrow.clone() },
+                signer,
+            ),
+            PayoutEscrowPda::LEN as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                self.system_program.clone(),
+                system_program::Assign { account_to_assign: escrow.clone() },
+                signer,
+            ),
+            &crate::ID,
+        )?;
+        Ok(shortfall)
+    }
+}
+
+/// Allocate the day's stream metadata PDA to Streamflow, with the crank caller paying the rent
+///
+/// Lamports sent to the address ahead of time don't block creation: the account is then topped
+/// up, allocated and assigned instead.
+fn create_stream_metadata(stream: &CreatorStream, vault_seed: &str, day_epoch: u64) -> Result<()> {
+    let day = day_epoch.to_le_bytes();
+    let (expected, bump) = Pubkey::find_program_address(
+        &[vault_seed.as_bytes(), CREATOR_STREAM_SEED, &day],
+        &crate::ID,
+    );
+    require_keys_eq!(stream.metadata.key(), expected, FeeRouterError::InvalidCreatorStream);
+    let seeds = &[vault_seed.as_bytes(), CREATOR_STREAM_SEED, &day, &[bump]];
+    let signer = &[&seeds[..]];
+
+    let rent = Rent::get()?.minimum_balance(STREAMFLOW_CONTRACT_LEN);
+    let lamports = stream.metadata.lamports();
+    if lamports == 0 {
+        return system_program::create_account(
             CpiContext::new_with_signer(
-                token_program.to_account_info(),
-                TransferChecked {
-                    from: quote_treasury.to_account_info(),
-                    mint: quote_mint.to_account_info(),
-                    to: creator_quote_ata.to_account_info(),
-                    authority: position_owner_pda.to_account_info(),
+                stream.system_program.clone(),
+                system_program::CreateAccount {
+                    from: stream.payer.clone(),
+                    to: stream.metadata.clone(),
                 },
                 signer,
             ),
-            creator_payout as u64,
-            quote_mint.decimals,
+            rent,
+            STREAMFLOW_CONTRACT_LEN as u64,
+            &STREAMFLOW_PROGRAM_ID,
+        );
+    }
+    if lamports < rent {
+        system_program::transfer(
+            CpiContext::new(
+                stream.system_program.clone(),
+                system_program::Transfer {
+                    from: stream.payer.clone(),
+                    to: stream.metadata.clone(),
+                },
+            ),
+            rent - lamports,
         )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            stream.system_program.clone(),
+            system_program::Allocate { account_to_allocate: stream.metadata.clone() },
+            signer,
+        ),
+        STREAMFLOW_CONTRACT_LEN as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            stream.system_program.clone(),
+            system_program::Assign { account_to_assign: stream.metadata.clone() },
+            signer,
+        ),
+        &STREAMFLOW_PROGRAM_ID,
+    )
+}
+
+/// Deposit the creator payout from the treasury into a new Streamflow stream
+///
+/// Streamflow charges its protocol fee, if any, to the sender on top of the deposit, so it is
+/// also paid from the treasury.
+fn open_creator_stream<'info>(
+    stream: &CreatorStream<'info>,
+    quote_treasury: &InterfaceAccount<'info, TokenAccount>,
+    position_owner_pda: &Account<'info, InvestorFeePositionOwnerPda>,
+    quote_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &[&[&[u8]]],
+    args: &CreateStreamArgs,
+) -> Result<()> {
+    let ix = streamflow::create_stream_ix(
+        &CreateStreamAccounts {
+            payer: stream.payer.key(),
+            sender: position_owner_pda.key(),
+            sender_tokens: quote_treasury.key(),
+            metadata: stream.metadata.key(),
+            escrow_tokens: stream.escrow_tokens.key(),
+            mint: quote_mint.key(),
+            token_program: token_program.key(),
+        },
+        args,
+    )?;
+    invoke_signed(
+        &ix,
+        &[
+            stream.payer.clone(),
+            position_owner_pda.to_account_info(),
+            quote_treasury.to_account_info(),
+            stream.metadata.clone(),
+            stream.escrow_tokens.clone(),
+            stream.withdrawor.clone(),
+            quote_mint.to_account_info(),
+            stream.fee_oracle.clone(),
+            stream.rent.clone(),
+            stream.streamflow_program.clone(),
+            token_program.to_account_info(),
+            stream.system_program.clone(),
+        ],
+        signer,
+    )?;
+    Ok(())
+}
+
+/// How finalize handles the creator remainder of a buyback-and-burn policy
+pub(crate) enum CreatorBuyback<'info> {
+    /// Add it to `buyback_pending` and spend the pending amount on a buyback now
+    Swap(Box<BuybackSwap<'info>>),
+    /// Add it to `buyback_pending` only; the caller has no pool accounts
+    Pending,
+}
+
+/// Accounts for buying back the base token with the creator remainder and burning it
+pub(crate) struct BuybackSwap<'info> {
+    pub pool_authority: AccountInfo<'info>,
+    pub pool: AccountInfo<'info>,
+    pub quote_treasury: AccountInfo<'info>,
+    /// Position owner's base token account the swap pays out to
+    pub base_account: AccountInfo<'info>,
+    pub token_a_vault: AccountInfo<'info>,
+    pub token_b_vault: AccountInfo<'info>,
+    pub token_a_mint: AccountInfo<'info>,
+    pub token_b_mint: AccountInfo<'info>,
+    pub base_mint: AccountInfo<'info>,
+    pub quote_mint: AccountInfo<'info>,
+    pub token_a_program: AccountInfo<'info>,
+    pub token_b_program: AccountInfo<'info>,
+    pub base_token_program: AccountInfo<'info>,
+    pub cp_amm_event_authority: AccountInfo<'info>,
+    pub cp_amm_program: AccountInfo<'info>,
+    pub position_owner: AccountInfo<'info>,
+    pub quote_is_a: bool,
+    pub max_slippage_bps: u16,
+}
+
+pub(crate) fn token_amount(info: &AccountInfo) -> Result<u64> {
+    let data = info.try_borrow_data()?;
+    Ok(read_u64(&data, TOKEN_ACCOUNT_AMOUNT_OFFSET))
+}
+
+/// Swap `progress_pda.buyback_pending` quote to base in the CP-AMM pool and burn the base
+///
+/// The swap is simulated first and skipped, leaving the amount pending for the next finalize,
+/// when the pool disallows it, would charge its fee in base, or would return less than
+/// `max_slippage_bps` under the spot price. A skipped buyback never fails the finalize.
+fn buy_back_and_burn<'info>(
+    buyback: &BuybackSwap<'info>,
+    progress_pda: &mut ProgressPda,
+    vault_seed: &str,
+    signer: &[&[&[u8]]],
+    events: &EventCpi<'info>,
+    current_timestamp: u64,
+) -> Result<Option<u64>> {
+    let amount = progress_pda.buyback_pending;
+    if amount == 0 {
+        return Ok(None);
+    }
+    // The pool swaps what arrives after the quote mint's transfer fee
+    let amount_in = amount.saturating_sub(transfer_fee_for(&buyback.quote_mint, amount)?);
+    let (expected_out, spot_out) = {
+        let pool = load_account::<Pool>(&buyback.pool, buyback.cp_amm_program.key)?;
+        let can_swap = cp_amm::get_pool_access_validator(&pool)
+            .map(|access| access.can_swap(buyback.position_owner.key))
+            .unwrap_or(false);
+        let current_point = ActivationHandler::get_current_point(pool.activation_type)?;
+        let expected_out = if can_swap {
+            simulate_quote_to_base(&pool, buyback.quote_is_a, amount_in, current_timestamp, current_point)
+        } else {
+            None
+        };
+        (expected_out, spot_base_out(pool.sqrt_price, buyback.quote_is_a, amount_in))
+    };
+    let min_out = spot_out.map(|spot| {
+        ((spot as u128) * (10_000 - buyback.max_slippage_bps as u128) / 10_000) as u64
+    });
+    let expected_received = match expected_out {
+        Some(out) => Some(out.saturating_sub(transfer_fee_for(&buyback.base_mint, out)?)),
+        None => None,
+    };
+    let min_out = match (expected_received, min_out) {
+        (Some(received), Some(min_out)) if received > 0 && received >= min_out => min_out,
+        _ => {
+            msg!(
+                "Buyback of {} quote skipped (expected {:?}, minimum {:?}); kept pending",
+                amount,
+                expected_received,
+                min_out
+            );
+            return Ok(None);
+        }
+    };
+
+    let cpi_accounts = cp_amm::cpi::accounts::SwapCtx {
+        pool_authority: buyback.pool_authority.clone(),
+        pool: buyback.pool.clone(),
+        input_token_account: buyback.quote_treasury.clone(),
+        output_token_account: buyback.base_account.clone(),
+        token_a_vault: buyback.token_a_vault.clone(),
+        token_b_vault: buyback.token_b_vault.clone(),
+        token_a_mint: buyback.token_a_mint.clone(),
+        token_b_mint: buyback.token_b_mint.clone(),
+        payer: buyback.position_owner.clone(),
+        token_a_program: buyback.token_a_program.clone(),
+        token_b_program: buyback.token_b_program.clone(),
+        referral_token_account: None,
+        event_authority: buyback.cp_amm_event_authority.clone(),
+        program: buyback.cp_amm_program.clone(),
+    };
+    let treasury_before = token_amount(&buyback.quote_treasury)?;
+    let base_before = token_amount(&buyback.base_account)?;
+    cp_amm::cpi::swap(
+        CpiContext::new_with_signer(buyback.cp_amm_program.clone(), cpi_accounts, signer),
+        cp_amm::SwapParameters {
+            amount_in: amount,
+            minimum_amount_out: min_out,
+        },
+    )?;
+    let quote_in = treasury_before.saturating_sub(token_amount(&buyback.quote_treasury)?);
+    let base_burned = token_amount(&buyback.base_account)?.saturating_sub(base_before);
+
+    burn(
+        CpiContext::new_with_signer(
+            buyback.base_token_program.clone(),
+            Burn {
+                mint: buyback.base_mint.clone(),
+                from: buyback.base_account.clone(),
+                authority: buyback.position_owner.clone(),
+            },
+            signer,
+        ),
+        base_burned,
+    )?;
+    progress_pda.buyback_pending = 0;
+
+    events.emit(BuybackExecuted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.to_string(),
+        day_epoch: progress_pda.day_epoch,
+        pool: buyback.pool.key(),
+        quote_in,
+        base_burned,
+        average_price_x64: ((quote_in as u128) << 64).checked_div(base_burned as u128).unwrap_or(0),
+        timestamp: current_timestamp,
+    })?;
+    msg!("Bought back and burned {} base for {} quote", base_burned, quote_in);
+
+    Ok(Some(base_burned))
+}
+
+/// Referral recipient of `policy.referral_bps` of the creator remainder
+pub(crate) struct CreatorReferral<'info> {
+    pub quote_ata: AccountInfo<'info>,
+    pub wallet: Pubkey,
+    pub bps: u16,
+}
+
+/// The policy's referral recipient, checking the passed account is the referral's quote ATA
+pub(crate) fn creator_referral<'info>(
+    policy: &PolicyPda,
+    referral_quote_ata: Option<&UncheckedAccount<'info>>,
+    token_program: &Pubkey,
+) -> Result<Option<CreatorReferral<'info>>> {
+    if policy.referral_bps == 0 {
+        return Ok(None);
+    }
+    let Some(quote_ata) = referral_quote_ata else {
+        return err!(FeeRouterError::InvalidReferral);
+    };
+    let expected = get_associated_token_address_with_program_id(
+        &policy.referral_wallet,
+        &policy.quote_mint,
+        token_program,
+    );
+    require_keys_eq!(quote_ata.key(), expected, FeeRouterError::InvalidReferral);
+    Ok(Some(CreatorReferral {
+        quote_ata: quote_ata.to_account_info(),
+        wallet: policy.referral_wallet,
+        bps: policy.referral_bps,
+    }))
+}
+
+/// Treasury-side accounts a finalize payout or protocol fee is sent with
+#[derive(Clone, Copy)]
+pub(crate) struct FinalizeAccounts<'a, 'info> {
+    pub quote_treasury: &'a InterfaceAccount<'info, TokenAccount>,
+    pub position_owner_pda: &'a Account<'info, InvestorFeePositionOwnerPda>,
+    pub quote_mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    pub memo_program: Option<&'a AccountInfo<'info>>,
+    pub position_owner_bump: u8,
+    pub events: &'a EventCpi<'info>,
+}
+
+/// Creator-side accounts a closing day's remainder is routed through, each present only under the
+/// policy option it serves, and the hook told once the day closes
+#[derive(Clone, Copy)]
+pub(crate) struct CreatorPayoutAccounts<'a, 'info> {
+    pub quote_ata: &'a AccountInfo<'info>,
+    pub unwrap: Option<&'a CreatorUnwrap<'info>>,
+    pub ata_funding: Option<&'a CreatorAtaFunding<'info>>,
+    pub stream: Option<&'a CreatorStream<'info>>,
+    pub buyback: Option<&'a CreatorBuyback<'info>>,
+    pub referral: Option<&'a CreatorReferral<'info>>,
+    pub hook: Option<&'a DistributionHook<'info>>,
+}
+
+/// Pay the referral `amount` of the creator remainder; returns the amount paid
+///
+/// A referral ATA that is closed or frozen can't block the day from closing, so its share is
+/// left in the creator payout instead.
+fn pay_referral<'info>(
+    referral: &CreatorReferral<'info>,
+    progress_pda: &mut ProgressPda,
+    accounts: &FinalizeAccounts<'_, 'info>,
+    vault_seed: &str,
+    amount: u64,
+    current_timestamp: u64,
+) -> Result<u64> {
+    if amount == 0 {
+        return Ok(0);
+    }
+    let quote_mint = accounts.quote_mint;
+    let status = quote_ata_status(&referral.quote_ata, &quote_mint.key(), &referral.wallet);
+    if status != QuoteAtaStatus::Valid {
+        msg!(
+            "Referral quote ATA {} can't receive {} ({:?}); left in the creator payout",
+            referral.quote_ata.key(),
+            amount,
+            status
+        );
+        return Ok(0);
+    }
+
+    let seeds = &[
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[accounts.position_owner_bump],
+    ];
+    let signer = &[&seeds[..]];
+    emit_payout_memo(accounts.memo_program, vault_seed, progress_pda.day_epoch)?;
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.quote_treasury.to_account_info(),
+                mint: quote_mint.to_account_info(),
+                to: referral.quote_ata.clone(),
+                authority: accounts.position_owner_pda.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        quote_mint.decimals,
+    )?;
+    let transfer_fee = transfer_fee_for(&quote_mint.to_account_info(), amount)?;
+    progress_pda.transfer_fees_today = progress_pda.transfer_fees_today.saturating_add(transfer_fee);
+
+    accounts.events.emit(ReferralFeePaid {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.to_string(),
+        quote_mint: quote_mint.key(),
+        decimals: quote_mint.decimals,
+        day_epoch: progress_pda.day_epoch,
+        referral_wallet: referral.wallet,
+        referral_quote_ata: referral.quote_ata.key(),
+        amount,
+        referral_bps: referral.bps,
+        transfer_fee,
+        timestamp: current_timestamp,
+    })?;
+    msg!("Paid referral {} quote tokens to {}", amount, referral.quote_ata.key());
+
+    Ok(amount)
+}
+
+/// Finalize the distribution day and transfer remainder to creator
+///
+/// A transfer into a closed or frozen creator account would revert the final page along with its
+/// investor payouts, so such a payout is added to `creator_owed` and the day closes anyway;
+/// `settle_creator_payout` delivers it later, unless `creator_ata_funding` creates a missing
+/// account first. With `creator_stream` the payout vests to the
+/// creator through a new Streamflow stream instead of being transferred; with `creator_buyback`
+/// it is added to `buyback_pending` and spent on a buyback-and-burn of the base token. The
+/// `creator_referral` share is paid out first. Once the day is closed, `hook` is called with its
+/// totals.
+pub(crate) fn finalize_day<'info>(
+    progress_pda: &mut ProgressPda,
+    accounts: &FinalizeAccounts<'_, 'info>,
+    creator_accounts: &CreatorPayoutAccounts<'_, 'info>,
+    vault_seed: &str,
+    current_timestamp: u64,
+    total_claimed: u128,
+    creator_payout: u128,
+) -> Result<()> {
+    let FinalizeAccounts {
+        quote_treasury,
+        position_owner_pda,
+        quote_mint,
+        token_program,
+        memo_program,
+        position_owner_bump,
+        events,
+    } = *accounts;
+    let CreatorPayoutAccounts {
+        quote_ata: creator_quote_ata,
+        unwrap: creator_unwrap,
+        ata_funding: creator_ata_funding,
+        stream: creator_stream,
+        buyback: creator_buyback,
+        referral: creator_referral,
+        hook,
+    } = *creator_accounts;
+
+    // A creator ATA that doesn't exist yet is created rather than deferring the payout
+    if let Some(funding) = creator_ata_funding.filter(|_| creator_payout > 0) {
+        fund_creator_ata(funding, creator_quote_ata, position_owner_pda, quote_mint, token_program)?;
+    }
+    let creator = token_account_owner(creator_quote_ata);
+    let creator_status = match creator {
+        Some(owner) => quote_ata_status(creator_quote_ata, &quote_mint.key(), &owner),
+        None => QuoteAtaStatus::Invalid,
+    };
+    if let (Some(unwrap), QuoteAtaStatus::Valid) = (creator_unwrap, creator_status) {
+        require!(
+            creator == Some(unwrap.creator_wallet.key()),
+            FeeRouterError::InvalidWsolUnwrap
+        );
+    }
+
+    // The referral's share comes off the top of the remainder
+    let referral_payout = match creator_referral {
+        Some(referral) => pay_referral(
+            referral,
+            progress_pda,
+            accounts,
+            vault_seed,
+            (creator_payout * referral.bps as u128 / 10_000) as u64,
+            current_timestamp,
+        )?,
+        None => 0,
+    };
+    let creator_payout = creator_payout - referral_payout as u128;
+
+    // Transfer remainder to creator if > 0
+    let mut creator_transfer_fee = 0u64;
+    let mut creator_bought_back = false;
+    let deferral = if creator_payout > 0 && creator_buyback.is_none() {
+        creator_status.failure_reason()
+    } else {
+        None
+    };
+    let creator_deferred = deferral.is_some();
+    if let Some(reason) = deferral {
+        progress_pda.creator_owed = progress_pda
+            .creator_owed
+            .checked_add(creator_payout as u64)
+            .ok_or(FeeRouterError::Overflow)?;
+        events.emit(CreatorPayoutDeferred {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.to_string(),
+            quote_mint: quote_mint.key(),
+            decimals: quote_mint.decimals,
+            day_epoch: progress_pda.day_epoch,
+            creator_quote_ata: creator_quote_ata.key(),
+            amount: creator_payout as u64,
+            reason,
+            creator_owed: progress_pda.creator_owed,
+            timestamp: current_timestamp,
+        })?;
+        msg!(
+            "Creator payout {} deferred ({:?}); {} owed",
+            creator_payout,
+            reason,
+            progress_pda.creator_owed
+        );
+    } else if let Some(buyback) = creator_buyback {
+        // Pending amounts from skipped buybacks are retried along with today's remainder
+        progress_pda.buyback_pending = progress_pda
+            .buyback_pending
+            .checked_add(creator_payout as u64)
+            .ok_or(FeeRouterError::Overflow)?;
+        let seeds = &[
+            vault_seed.as_bytes(),
+            b"investor_fee_pos_owner",
+            &[position_owner_bump],
+        ];
+        let signer = &[&seeds[..]];
+        if let CreatorBuyback::Swap(swap) = buyback {
+            creator_bought_back =
+                buy_back_and_burn(swap, progress_pda, vault_seed, signer, events, current_timestamp)?.is_some();
+        }
+    } else if creator_payout > 0 {
+        // Transfer using transfer_checked
+        let seeds = &[
+            vault_seed.as_bytes(),
+            b"investor_fee_pos_owner",
+            &[position_owner_bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        emit_payout_memo(memo_program, vault_seed, progress_pda.day_epoch)?;
+        if let (Some(stream), Some(recipient)) = (creator_stream, creator) {
+            create_stream_metadata(stream, vault_seed, progress_pda.day_epoch)?;
+            let name = format!("{} creator {}", vault_seed, progress_pda.day_epoch);
+            let args = CreateStreamArgs::linear(
+                creator_payout as u64,
+                current_timestamp,
+                stream.vesting_secs,
+                recipient,
+                &name,
+            );
+            open_creator_stream(stream, quote_treasury, position_owner_pda, quote_mint, token_program, signer, &args)?;
+            events.emit(CreatorPayoutStreamed {
+                schema_version: EVENT_SCHEMA_VERSION,
+                vault_seed: vault_seed.to_string(),
+                quote_mint: quote_mint.key(),
+                decimals: quote_mint.decimals,
+                day_epoch: progress_pda.day_epoch,
+                stream: stream.metadata.key(),
+                recipient,
+                amount: creator_payout as u64,
+                start_time: current_timestamp,
+                end_time: current_timestamp.saturating_add(stream.vesting_secs),
+                timestamp: current_timestamp,
+            })?;
+        } else {
+            let destination = match creator_unwrap {
+                Some(unwrap) => unwrap.wsol_temp.clone(),
+                None => creator_quote_ata.clone(),
+            };
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: quote_treasury.to_account_info(),
+                        mint: quote_mint.to_account_info(),
+                        to: destination,
+                        authority: position_owner_pda.to_account_info(),
+                    },
+                    signer,
+                ),
+                creator_payout as u64,
+                quote_mint.decimals,
+            )?;
+        }
+
+        if let Some(unwrap) = creator_unwrap {
+            unwrap_to_creator(unwrap, token_program, position_owner_pda, signer, creator_payout as u64)?;
+        }
+
+        creator_transfer_fee = transfer_fee_for(&quote_mint.to_account_info(), creator_payout as u64)?;
+        progress_pda.transfer_fees_today = progress_pda
+            .transfer_fees_today
+            .saturating_add(creator_transfer_fee);
 
         msg!(
-            "Transferred {} quote tokens to creator {}",
+            "Transferred {} quote tokens to creator {} (transfer fee: {})",
             creator_payout,
-            creator_quote_ata.key()
+            creator_quote_ata.key(),
+            creator_transfer_fee
         );
     }
 
@@ -834,14 +3584,28 @@ fn finalize_day<'info>(
     progress_pda.finalize_day(current_timestamp, total_claimed, creator_payout);
 
     // Emit day closed event
-    emit!(CreatorPayoutDayClosed {
+    events.emit(CreatorPayoutDayClosed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.to_string(),
+        quote_mint: quote_mint.key(),
+        decimals: quote_mint.decimals,
         day_epoch: progress_pda.day_epoch,
         total_claimed,
         total_distributed: progress_pda.cumulative_distributed_today,
         creator_payout,
+        creator_unwrapped: creator_unwrap.is_some() && creator_payout > 0 && !creator_deferred,
+        creator_deferred,
+        creator_streamed: creator_stream.is_some() && creator_payout > 0 && !creator_deferred,
+        creator_bought_back,
+        creator_transfer_fee,
+        transfer_fees_today: progress_pda.transfer_fees_today,
         carry: progress_pda.carry_over_lamports,
+        capped_excess_rolled: progress_pda.capped_excess_rollover,
+        pages_processed: progress_pda.pages_processed_today,
+        page_hash_chain: progress_pda.page_hash_chain,
+        generation: progress_pda.generation,
         timestamp: current_timestamp,
-    });
+    })?;
 
     msg!(
         "Day finalized: epoch={}, total_claimed={}, distributed={}, creator_payout={}, carry={}",
@@ -852,5 +3616,74 @@ fn finalize_day<'info>(
         progress_pda.carry_over_lamports
     );
 
+    if let Some(hook) = hook {
+        invoke_hook(
+            hook,
+            &DistributionFinalizedPayload {
+                vault_seed: vault_seed.to_string(),
+                policy: hook.policy.key(),
+                quote_mint: quote_mint.key(),
+                day_epoch: progress_pda.day_epoch,
+                total_claimed,
+                total_distributed: progress_pda.cumulative_distributed_today,
+                creator_payout,
+                carry: progress_pda.carry_over_lamports,
+                timestamp: current_timestamp,
+            },
+        )?;
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ata_rent_reimbursed_from_funded_reserve() {
+        let (pda_key, payer_key, owner) = (Pubkey::new_unique(), Pubkey::new_unique(), crate::ID);
+        let (mut pda_lamports, mut payer_lamports) = (1_000_000u64, 0u64);
+        let (mut pda_data, mut payer_data) = (vec![0u8; 8], vec![]);
+        let pda = AccountInfo::new(&pda_key, false, true, &mut pda_lamports, &mut pda_data, &owner, false, 0);
+        let payer = AccountInfo::new(&payer_key, true, true, &mut payer_lamports, &mut payer_data, &owner, false, 0);
+
+        // At the rent floor nothing is spare
+        assert_eq!(move_spare_lamports(&pda, &payer, 2_039_280, 1_000_000).unwrap(), 0);
+
+        // A funded reserve repays the rent in full, then what is left of it
+        **pda.try_borrow_mut_lamports().unwrap() += 3_000_000;
+        assert_eq!(move_spare_lamports(&pda, &payer, 2_039_280, 1_000_000).unwrap(), 2_039_280);
+        assert_eq!(move_spare_lamports(&pda, &payer, 2_039_280, 1_000_000).unwrap(), 960_720);
+        assert_eq!(pda.lamports(), 1_000_000);
+        assert_eq!(payer.lamports(), 3_000_000);
+    }
+
+    #[test]
+    fn test_compounded_quote_leaves_pool_covered_by_treasury() {
+        let set_amount = |data: &mut [u8], amount: u64| {
+            data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8].copy_from_slice(&amount.to_le_bytes())
+        };
+        let (key, owner) = (Pubkey::new_unique(), anchor_spl::token::ID);
+        let mut lamports = 2_039_280u64;
+        let mut data = vec![0u8; 165];
+        let treasury = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        // Treasury held 50 carried over, then the claim brought in 1_000 (after the protocol skim)
+        let (held, claimed_quote, compound_bps) = (50u64, 1_000u64, 2_500u16);
+        set_amount(&mut treasury.try_borrow_mut_data().unwrap(), held + claimed_quote);
+        let budget = claimed_quote * compound_bps as u64 / 10_000;
+
+        let compounded = treasury_spent(&treasury, || {
+            let mut data = treasury.try_borrow_mut_data()?;
+            set_amount(&mut data, held + claimed_quote - budget);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(compounded, budget);
+
+        // The day's pool is what the treasury still holds of the claim
+        let pool = claimed_quote - compounded;
+        assert_eq!(pool + held, token_amount(&treasury).unwrap());
+    }
+}
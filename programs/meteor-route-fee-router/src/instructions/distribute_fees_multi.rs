@@ -0,0 +1,318 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use cp_amm::state::{Pool, Position};
+
+use crate::{
+    cp_amm::{derive_event_authority, derive_pool_authority, load_account},
+    cp_amm_entry_points,
+    error::FeeRouterError,
+    event_cpi::EventCpi,
+    events::{BaseFeeObserved, QuoteFeesClaimed, EVENT_SCHEMA_VERSION},
+    instructions::distribute_fees::{collect_protocol_fee, token_account_unencumbered, token_amount, FinalizeAccounts},
+    state::{InvestorFeePositionOwnerPda, PolicyPda, PoolKind, ProgressPda, ProtocolConfig},
+    transfer_fee::transfer_fee_for,
+};
+
+/// Most vaults one `distribute_fees_multi` call claims for
+pub const MAX_MULTI_VAULTS: usize = 4;
+
+/// remaining_accounts per vault, in order:
+/// [policy_pda, progress_pda (mut), position_owner_pda (mut), position (mut), position_nft_account,
+///  pool (mut), token_a_vault (mut), token_b_vault (mut), token_a_mint, token_b_mint,
+///  temp_a_account (mut), temp_b_account (mut), quote_treasury (mut), token_a_program,
+///  token_b_program, protocol_fee_ata (mut; this program's ID while the protocol fee is off)]
+pub const MULTI_VAULT_ACCOUNTS: usize = 16;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DistributeFeesMulti<'info> {
+    /// Crank caller (permissionless)
+    pub crank_caller: Signer<'info>,
+
+    /// CHECK: Compared with each vault's policy cp_amm_program_id
+    pub cp_amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: CP-AMM pool authority PDA, derived from cp_amm_program
+    #[account(address = derive_pool_authority(cp_amm_program.key) @ FeeRouterError::InvalidCpAmmPda)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: CP-AMM event authority PDA required for CPI events
+    #[account(address = derive_event_authority(cp_amm_program.key) @ FeeRouterError::InvalidCpAmmPda)]
+    pub cp_amm_event_authority: UncheckedAccount<'info>,
+
+    /// Program-wide protocol fee config; inert until `initialize_protocol_config` creates it
+    /// CHECK: Read with ProtocolConfig::load
+    #[account(seeds = [ProtocolConfig::SEED], bump)]
+    pub protocol_config: UncheckedAccount<'info>,
+}
+
+/// Claim the honorary position fees of several CP-AMM vaults into their treasuries
+///
+/// Each vault's claim, net of transfer and protocol fees, is added to
+/// `ProgressPda.rollover_claimed_quote` and joins the claim of its next `distribute_fees` first
+/// page, so keepers can batch the claim phase and leave only investor pages per vault. Vaults
+/// that auto-compound need `distribute_fees` for their claims.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeFeesMulti<'info>>,
+    vault_seeds: Vec<String>,
+) -> Result<()> {
+    require!(
+        !vault_seeds.is_empty()
+            && vault_seeds.len() <= MAX_MULTI_VAULTS
+            && ctx.remaining_accounts.len() == vault_seeds.len() * MULTI_VAULT_ACCOUNTS,
+        FeeRouterError::InvalidMultiVault
+    );
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let events = EventCpi {
+        authority: ctx.accounts.event_authority.to_account_info(),
+        bump: ctx.bumps.event_authority,
+    };
+    let protocol_config = ProtocolConfig::load(&ctx.accounts.protocol_config)?;
+
+    for (vault_seed, vault_accounts) in vault_seeds
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(MULTI_VAULT_ACCOUNTS))
+    {
+        claim_vault(
+            ctx.accounts,
+            vault_seed,
+            vault_accounts,
+            protocol_config.as_ref(),
+            &events,
+            current_timestamp,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &crate::ID)
+}
+
+/// Validate one vault's account group, claim its position fees and record them as rollover
+fn claim_vault<'info>(
+    shared: &DistributeFeesMulti<'info>,
+    vault_seed: &str,
+    vault_accounts: &'info [AccountInfo<'info>],
+    protocol_config: Option<&ProtocolConfig>,
+    events: &EventCpi<'info>,
+    current_timestamp: u64,
+) -> Result<()> {
+    let [policy_info, progress_info, position_owner_info, position, position_nft_info, pool, token_a_vault, token_b_vault, token_a_mint_info, token_b_mint_info, temp_a_info, temp_b_info, treasury_info, token_a_program_info, token_b_program_info, protocol_fee_ata] =
+        vault_accounts
+    else {
+        return err!(FeeRouterError::InvalidMultiVault);
+    };
+
+    // Vault PDAs must belong to vault_seed
+    require_keys_eq!(policy_info.key(), find(&PolicyPda::seeds(vault_seed)).0, FeeRouterError::PdaSeedMismatch);
+    require_keys_eq!(progress_info.key(), find(&ProgressPda::seeds(vault_seed)).0, FeeRouterError::PdaSeedMismatch);
+    let (position_owner_key, position_owner_bump) = find(&InvestorFeePositionOwnerPda::seeds(vault_seed));
+    require_keys_eq!(position_owner_info.key(), position_owner_key, FeeRouterError::PdaSeedMismatch);
+
+    let policy = Account::<PolicyPda>::try_from(policy_info)?;
+    let mut progress = Account::<ProgressPda>::try_from(progress_info)?;
+    let mut position_owner = Account::<InvestorFeePositionOwnerPda>::try_from(position_owner_info)?;
+    require!(!policy.paused, FeeRouterError::PolicyPaused);
+    require!(
+        position_owner.pool_kind == PoolKind::CpAmm && policy.compound_bps == 0,
+        FeeRouterError::InvalidMultiVault
+    );
+    require_keys_eq!(
+        shared.cp_amm_program.key(),
+        policy.cp_amm_program_id,
+        FeeRouterError::InvalidCpAmmProgram
+    );
+    require_keys_eq!(position.key(), position_owner.position_pubkey, FeeRouterError::InvalidPositionOwner);
+    let position_nft_account = InterfaceAccount::<TokenAccount>::try_from(position_nft_info)?;
+    require_keys_eq!(position_nft_account.owner, position_owner_key, FeeRouterError::InvalidPositionOwner);
+    require!(
+        position_owner.holds_position_nft(&position_nft_account),
+        FeeRouterError::InvalidPositionNftAccount
+    );
+
+    // Pool must hold the passed mints/vaults and own the position
+    {
+        let pool_state = load_account::<Pool>(pool, &policy.cp_amm_program_id)?;
+        require!(
+            pool_state.token_a_mint == token_a_mint_info.key()
+                && pool_state.token_b_mint == token_b_mint_info.key()
+                && pool_state.token_a_vault == token_a_vault.key()
+                && pool_state.token_b_vault == token_b_vault.key(),
+            FeeRouterError::PoolAccountMismatch
+        );
+        let position_state = load_account::<Position>(position, &policy.cp_amm_program_id)?;
+        require_keys_eq!(position_state.pool, pool.key(), FeeRouterError::PoolAccountMismatch);
+    }
+
+    let token_a_mint = InterfaceAccount::<Mint>::try_from(token_a_mint_info)?;
+    let token_b_mint = InterfaceAccount::<Mint>::try_from(token_b_mint_info)?;
+    let token_a_program = Interface::<TokenInterface>::try_from(token_a_program_info)?;
+    let token_b_program = Interface::<TokenInterface>::try_from(token_b_program_info)?;
+    let quote_is_a = policy.quote_mint == token_a_mint.key();
+    require!(
+        quote_is_a || policy.quote_mint == token_b_mint.key(),
+        FeeRouterError::InvalidQuoteMint
+    );
+
+    // Temp accounts are the position owner's, on the pool mints' token programs
+    for (temp_info, mint, token_program) in [
+        (temp_a_info, &token_a_mint, &token_a_program),
+        (temp_b_info, &token_b_mint, &token_b_program),
+    ] {
+        let temp = InterfaceAccount::<TokenAccount>::try_from(temp_info)?;
+        require!(
+            temp.mint == mint.key() && temp.owner == position_owner_key && temp_info.owner == token_program.key,
+            FeeRouterError::InvalidMultiVault
+        );
+        require!(token_account_unencumbered(&temp), FeeRouterError::TempAccountEncumbered);
+    }
+
+    let (quote_mint, quote_program, quote_temp, base_temp) = if quote_is_a {
+        (&token_a_mint, &token_a_program, temp_a_info, temp_b_info)
+    } else {
+        (&token_b_mint, &token_b_program, temp_b_info, temp_a_info)
+    };
+    require_keys_eq!(
+        treasury_info.key(),
+        find(&[vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()]).0,
+        FeeRouterError::PdaSeedMismatch
+    );
+    let quote_treasury = InterfaceAccount::<TokenAccount>::try_from(treasury_info)?;
+    require!(
+        token_account_unencumbered(&quote_treasury)
+            && quote_treasury.owner == position_owner_key
+            && treasury_info.owner == quote_program.key,
+        FeeRouterError::InvalidTreasury
+    );
+
+    let seeds = &[
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[position_owner_bump],
+    ];
+    let signer = &[&seeds[..]];
+    cp_amm_entry_points::claim_position_fee(
+        &policy.cp_amm_entry_points,
+        CpiContext::new_with_signer(
+            shared.cp_amm_program.to_account_info(),
+            cp_amm::cpi::accounts::ClaimPositionFeeCtx {
+                pool_authority: shared.pool_authority.to_account_info(),
+                pool: pool.clone(),
+                position: position.clone(),
+                token_a_account: temp_a_info.clone(),
+                token_b_account: temp_b_info.clone(),
+                token_a_vault: token_a_vault.clone(),
+                token_b_vault: token_b_vault.clone(),
+                token_a_mint: token_a_mint_info.clone(),
+                token_b_mint: token_b_mint_info.clone(),
+                position_nft_account: position_nft_info.clone(),
+                owner: position_owner_info.clone(),
+                token_a_program: token_a_program_info.clone(),
+                token_b_program: token_b_program_info.clone(),
+                event_authority: shared.cp_amm_event_authority.to_account_info(),
+                program: shared.cp_amm_program.to_account_info(),
+            },
+            signer,
+        ),
+    )?;
+
+    let quote_amount = token_amount(quote_temp)?;
+    let base_amount = token_amount(base_temp)?;
+    // CRITICAL: Enforce quote-only - fail if any base fees claimed, recording the amounts first
+    if base_amount > 0 {
+        events.emit(BaseFeeObserved {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.to_string(),
+            base_amount,
+            quote_amount,
+            position: position.key(),
+            timestamp: current_timestamp,
+        })?;
+        return err!(FeeRouterError::BaseFeeDetected);
+    }
+    position_owner.record_claim(quote_amount, base_amount);
+    position_owner.exit(&crate::ID)?;
+    if quote_amount == 0 {
+        msg!("Vault {}: no quote fees to claim", vault_seed);
+        return Ok(());
+    }
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            quote_program.to_account_info(),
+            TransferChecked {
+                from: quote_temp.clone(),
+                mint: quote_mint.to_account_info(),
+                to: treasury_info.clone(),
+                authority: position_owner_info.clone(),
+            },
+            signer,
+        ),
+        quote_amount,
+        quote_mint.decimals,
+    )?;
+    let claim_transfer_fee = transfer_fee_for(&quote_mint.to_account_info(), quote_amount)?;
+    let claimed_quote = quote_amount
+        .checked_sub(claim_transfer_fee)
+        .ok_or(FeeRouterError::Overflow)?;
+
+    events.emit(QuoteFeesClaimed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.to_string(),
+        quote_mint: quote_mint.key(),
+        decimals: quote_mint.decimals,
+        claimed_quote: claimed_quote as u128,
+        claimed_base: 0,
+        transfer_fee: claim_transfer_fee,
+        position: position.key(),
+        treasury_ata: quote_treasury.key(),
+        timestamp: current_timestamp,
+    })?;
+
+    // The protocol fee comes off the claim before it is held for the vault
+    let (protocol_fee, protocol_transfer_fee) = match protocol_config {
+        Some(config) => collect_protocol_fee(
+            config,
+            (protocol_fee_ata.key() != crate::ID).then_some(protocol_fee_ata),
+            &FinalizeAccounts {
+                quote_treasury: &quote_treasury,
+                position_owner_pda: &position_owner,
+                quote_mint,
+                token_program: quote_program,
+                memo_program: None,
+                position_owner_bump,
+                events,
+            },
+            vault_seed,
+            claimed_quote,
+            current_timestamp,
+        )?,
+        None => (0, 0),
+    };
+
+    let held = claimed_quote - protocol_fee;
+    progress.rollover_claimed_quote = progress
+        .rollover_claimed_quote
+        .checked_add(held)
+        .ok_or(FeeRouterError::Overflow)?;
+    progress.last_claimed_quote = claimed_quote as u128;
+    progress.last_claimed_base = 0;
+    progress.transfer_fees_today = progress
+        .transfer_fees_today
+        .saturating_add(claim_transfer_fee)
+        .saturating_add(protocol_transfer_fee);
+    progress.updated_at = current_timestamp;
+    progress.exit(&crate::ID)?;
+
+    msg!(
+        "Vault {}: claimed {} quote, {} held for the next distribution ({} rolled over)",
+        vault_seed,
+        claimed_quote,
+        held,
+        progress.rollover_claimed_quote
+    );
+
+    Ok(())
+}
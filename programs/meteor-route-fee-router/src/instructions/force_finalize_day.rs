@@ -0,0 +1,315 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::FeeRouterError,
+    event_cpi::EventCpi,
+    hook::DistributionHook,
+    events::{DayForceFinalized, EVENT_SCHEMA_VERSION},
+    instructions::distribute_fees::{creator_referral, finalize_day, token_account_unencumbered, CreatorAtaFunding, CreatorBuyback, CreatorPayoutAccounts, CreatorStream, CreatorUnwrap, FinalizeAccounts},
+    memo::MEMO_PROGRAM_ID,
+    streamflow::{STREAMFLOW_FEE_ORACLE, STREAMFLOW_PROGRAM_ID, STREAMFLOW_WITHDRAWOR},
+    state::{InvestorFeePositionOwnerPda, PolicyPda, ProgressPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct ForceFinalizeDay<'info> {
+    /// Caller (permissionless); pays the temp wSOL account rent when unwrapping
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// Policy configuration
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        constraint = !policy_pda.paused @ FeeRouterError::PolicyPaused
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Progress tracking
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+
+    /// Position owner PDA (treasury authority)
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
+
+    /// Quote mint (must match policy)
+    #[account(address = policy_pda.quote_mint @ FeeRouterError::InvalidQuoteMint)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Program quote treasury token account at the treasury PDA
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        constraint = token_account_unencumbered(&quote_treasury) @ FeeRouterError::InvalidTreasury,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program,
+    )]
+    pub quote_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator quote ATA (destination for remainder)
+    /// CHECK: Classified with quote_ata_status at finalize; an unusable account defers the payout
+    #[account(mut)]
+    pub creator_quote_ata: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// Temporary wSOL account the creator payout is unwrapped through (policy.unwrap_wsol_creator)
+    #[account(
+        init_if_needed,
+        payer = caller,
+        seeds = [vault_seed.as_bytes(), b"creator_wsol"],
+        bump,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program,
+    )]
+    pub creator_wsol_temp: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Creator wallet receiving unwrapped SOL; must own creator_quote_ata
+    /// CHECK: Checked against the creator_quote_ata owner in finalize_day
+    #[account(mut)]
+    pub creator_wallet: Option<UncheckedAccount<'info>>,
+
+    /// SPL Memo program, required when policy.payout_memos is set
+    /// CHECK: Address checked against the Memo program ID
+    #[account(address = MEMO_PROGRAM_ID @ FeeRouterError::MissingRequiredInput)]
+    pub memo_program: Option<UncheckedAccount<'info>>,
+
+    /// Metadata account of the day's creator stream, required when policy.creator_vesting_secs is set
+    /// CHECK: Must be the [vault_seed, "creator_stream", day_epoch] PDA; created in finalize_day
+    #[account(mut)]
+    pub creator_stream_metadata: Option<UncheckedAccount<'info>>,
+
+    /// Escrow token account Streamflow opens for the creator stream
+    /// CHECK: Derived from creator_stream_metadata and checked by Streamflow
+    #[account(mut)]
+    pub creator_stream_escrow: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Address checked against the Streamflow withdrawor
+    #[account(mut, address = STREAMFLOW_WITHDRAWOR @ FeeRouterError::InvalidCreatorStream)]
+    pub streamflow_withdrawor: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Address checked against the Streamflow fee oracle
+    #[account(address = STREAMFLOW_FEE_ORACLE @ FeeRouterError::InvalidCreatorStream)]
+    pub streamflow_fee_oracle: Option<UncheckedAccount<'info>>,
+
+    /// Streamflow program the creator stream is created in
+    /// CHECK: Address checked against the Streamflow program ID
+    #[account(address = STREAMFLOW_PROGRAM_ID @ FeeRouterError::InvalidCreatorStream)]
+    pub streamflow_timelock_program: Option<UncheckedAccount<'info>>,
+
+    pub rent: Option<Sysvar<'info, Rent>>,
+
+    /// Referral's quote ATA, required when policy.referral_bps is set
+    /// CHECK: Must be the referral wallet's ATA for the quote mint; classified at finalize
+    #[account(mut)]
+    pub referral_quote_ata: Option<UncheckedAccount<'info>>,
+
+    /// Required, with creator_wallet, to create a missing creator ATA (policy.fund_missing_creator_ata)
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// Program called after the day is finalized, required when policy.hook_program is set
+    /// CHECK: Address checked against policy.hook_program
+    #[account(address = policy_pda.hook_program @ FeeRouterError::InvalidHookProgram)]
+    pub hook_program: Option<UncheckedAccount<'info>>,
+
+    /// Signer of the hook call
+    /// CHECK: Checked against the [vault_seed, "hook_authority"] PDA in DistributionHook::from_policy
+    pub hook_authority: Option<UncheckedAccount<'info>>,
+
+    /// Hook-owned state passed through writable
+    /// CHECK: Only forwarded to hook_program, which validates it
+    #[account(mut)]
+    pub hook_state: Option<UncheckedAccount<'info>>,
+}
+
+/// Close a day whose keeper stalled mid-way
+///
+/// Callable by anyone once `policy.force_finalize_after_secs` have passed since the day started.
+/// The creator receives the day's remainder target; investor share not yet paid out rolls to the
+/// next day's investor pool when `policy.roll_capped_excess` is set and goes to the creator otherwise.
+pub fn handler(
+    ctx: Context<ForceFinalizeDay>,
+    vault_seed: String,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let events = EventCpi {
+        authority: ctx.accounts.event_authority.to_account_info(),
+        bump: ctx.bumps.event_authority,
+    };
+    let policy_pda = &ctx.accounts.policy_pda;
+
+    if ctx.accounts.progress_pda.day_finalized_flag {
+        return err!(FeeRouterError::DayAlreadyFinalized);
+    }
+    require!(
+        ctx.accounts
+            .progress_pda
+            .is_stalled(current_timestamp, policy_pda.force_finalize_after_secs),
+        FeeRouterError::ForceFinalizeTooEarly
+    );
+
+    let memo_program = if policy_pda.payout_memos {
+        Some(
+            ctx.accounts
+                .memo_program
+                .as_ref()
+                .ok_or(FeeRouterError::MissingRequiredInput)?
+                .to_account_info(),
+        )
+    } else {
+        None
+    };
+
+    let creator_unwrap = if policy_pda.unwrap_wsol_creator {
+        let (Some(wsol_temp), Some(creator_wallet)) = (
+            ctx.accounts.creator_wsol_temp.as_ref(),
+            ctx.accounts.creator_wallet.as_ref(),
+        ) else {
+            return err!(FeeRouterError::MissingRequiredInput);
+        };
+        Some(CreatorUnwrap {
+            wsol_temp: wsol_temp.to_account_info(),
+            creator_wallet: creator_wallet.to_account_info(),
+            payer: ctx.accounts.caller.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        })
+    } else {
+        None
+    };
+
+    // A missing creator ATA can only be created for a known wallet; without one the payout defers
+    let creator_ata_funding = match (
+        policy_pda.fund_missing_creator_ata,
+        ctx.accounts.creator_wallet.as_ref(),
+        ctx.accounts.associated_token_program.as_ref(),
+    ) {
+        (true, Some(creator_wallet), Some(associated_token_program)) => Some(CreatorAtaFunding {
+            creator_wallet: creator_wallet.to_account_info(),
+            payer: ctx.accounts.caller.to_account_info(),
+            associated_token_program: associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        }),
+        _ => None,
+    };
+
+    let creator_stream = if policy_pda.creator_vesting_secs > 0 {
+        let (Some(metadata), Some(escrow_tokens), Some(withdrawor), Some(fee_oracle), Some(streamflow_program), Some(rent)) = (
+            ctx.accounts.creator_stream_metadata.as_ref(),
+            ctx.accounts.creator_stream_escrow.as_ref(),
+            ctx.accounts.streamflow_withdrawor.as_ref(),
+            ctx.accounts.streamflow_fee_oracle.as_ref(),
+            ctx.accounts.streamflow_timelock_program.as_ref(),
+            ctx.accounts.rent.as_ref(),
+        ) else {
+            return err!(FeeRouterError::InvalidCreatorStream);
+        };
+        Some(CreatorStream {
+            metadata: metadata.to_account_info(),
+            escrow_tokens: escrow_tokens.to_account_info(),
+            withdrawor: withdrawor.to_account_info(),
+            fee_oracle: fee_oracle.to_account_info(),
+            streamflow_program: streamflow_program.to_account_info(),
+            rent: rent.to_account_info(),
+            payer: ctx.accounts.caller.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            vesting_secs: policy_pda.creator_vesting_secs,
+        })
+    } else {
+        None
+    };
+
+    // Without the pool accounts a buyback waits for the next distribute_fees finalize
+    let creator_buyback = policy_pda.buyback_burn_creator.then_some(CreatorBuyback::Pending);
+    let creator_referral = creator_referral(
+        policy_pda,
+        ctx.accounts.referral_quote_ata.as_ref(),
+        &ctx.accounts.token_program.key(),
+    )?;
+    let hook = DistributionHook::from_policy(
+        &vault_seed,
+        policy_pda,
+        ctx.accounts.hook_program.as_ref(),
+        ctx.accounts.hook_authority.as_ref(),
+        ctx.accounts.hook_state.as_ref(),
+    )?;
+
+    let roll_unpaid = policy_pda.roll_capped_excess;
+    let progress_pda = &mut ctx.accounts.progress_pda;
+    let day_started_ts = progress_pda.day_started_ts;
+    let (investor_unpaid, creator_payout) = progress_pda.settle_stalled_day(roll_unpaid)?;
+    // Never pay out more than the treasury holds
+    let creator_payout = creator_payout.min(ctx.accounts.quote_treasury.amount);
+    let total_claimed = (progress_pda.day_investor_pool_target as u128)
+        .saturating_add(progress_pda.day_creator_remainder_target as u128);
+
+    finalize_day(
+        progress_pda,
+        &FinalizeAccounts {
+            quote_treasury: &ctx.accounts.quote_treasury,
+            position_owner_pda: &ctx.accounts.position_owner_pda,
+            quote_mint: &ctx.accounts.quote_mint,
+            token_program: &ctx.accounts.token_program,
+            memo_program: memo_program.as_ref(),
+            position_owner_bump: ctx.bumps.position_owner_pda,
+            events: &events,
+        },
+        &CreatorPayoutAccounts {
+            quote_ata: &ctx.accounts.creator_quote_ata.to_account_info(),
+            unwrap: creator_unwrap.as_ref(),
+            ata_funding: creator_ata_funding.as_ref(),
+            stream: creator_stream.as_ref(),
+            buyback: creator_buyback.as_ref(),
+            referral: creator_referral.as_ref(),
+            hook: hook.as_ref(),
+        },
+        &vault_seed,
+        current_timestamp,
+        total_claimed,
+        creator_payout as u128,
+    )?;
+    // Keep the daily schedule anchored to when the stalled day started
+    progress_pda.last_distribution_ts = day_started_ts;
+
+    events.emit(DayForceFinalized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        decimals: ctx.accounts.quote_mint.decimals,
+        day_epoch: progress_pda.day_epoch,
+        caller: ctx.accounts.caller.key(),
+        stalled_secs: current_timestamp.saturating_sub(day_started_ts),
+        pages_processed: progress_pda.pages_processed_today,
+        investor_unpaid,
+        unpaid_rolled_over: roll_unpaid,
+        creator_payout,
+        timestamp: current_timestamp,
+    })?;
+
+    msg!(
+        "Day {} force-finalized: investor_unpaid={} ({}), creator_payout={}",
+        progress_pda.day_epoch,
+        investor_unpaid,
+        if roll_unpaid { "rolled over" } else { "to creator" },
+        creator_payout
+    );
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::{
+    events::{AtaReserveFunded, EVENT_SCHEMA_VERSION},
+    state::InvestorFeePositionOwnerPda,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct FundAtaReserve<'info> {
+    /// Anyone may top up the reserve
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// Position owner PDA; its lamports above the rent-exempt minimum repay ATA and escrow rent
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit lamports the crank caller is reimbursed from when it pays rent for missing investor
+/// ATAs, payout escrows or the creator ATA
+pub fn handler(ctx: Context<FundAtaReserve>, vault_seed: String, lamports: u64) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.position_owner_pda.to_account_info(),
+            },
+        ),
+        lamports,
+    )?;
+
+    let owner_info = ctx.accounts.position_owner_pda.to_account_info();
+    let reserve = owner_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(owner_info.data_len()));
+
+    emit_cpi!(AtaReserveFunded {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed,
+        funder: ctx.accounts.funder.key(),
+        lamports,
+        reserve,
+        timestamp: Clock::get()?.unix_timestamp as u64,
+    });
+
+    msg!("ATA rent reserve funded with {} lamports ({} available)", lamports, reserve);
+
+    Ok(())
+}
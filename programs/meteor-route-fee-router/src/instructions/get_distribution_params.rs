@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    state::{DistributionMath, PolicyPda, ProgressPda},
+};
+
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct GetDistributionParams<'info> {
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+}
+
+/// The vault's distribution state, set as the return data of `get_distribution_params`
+///
+/// Reflects the stored day; a day whose gate has opened starts only with the next crank.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DistributionParams {
+    pub day_epoch: u64,
+    pub day_finalized: bool,
+    /// Locked total the day is weighed by: the completed snapshot's, else the day's recorded total
+    pub locked_total: u64,
+    /// min(investor_fee_share_bps, locked_total / y0_total_allocation in bps)
+    pub eligible_bps: u16,
+    /// Quote investors can still receive today: the daily cap, banked cap included, until the
+    /// day's pool is fixed by its first page, then what is left of that pool; u64::MAX while uncapped
+    pub remaining_daily_cap: u64,
+    pub day_investor_pool_target: u64,
+    pub day_investor_distributed: u64,
+    pub day_creator_remainder_target: u64,
+    pub day_claimed_quote: u64,
+    /// Page the next `distribute_fees` call starts at
+    pub pagination_cursor: u64,
+    /// Investor the page at the cursor resumes at; 0 unless the page was suspended
+    pub page_investor_cursor: u32,
+    pub pages_processed_today: u64,
+    /// Known page count, committed or fixed by an earlier final page (0 = unknown)
+    pub total_pages_expected: u64,
+}
+
+impl DistributionParams {
+    pub fn new(policy: &PolicyPda, progress: &ProgressPda) -> Result<Self> {
+        let locked_total = if progress.snapshot_complete {
+            progress.snapshot_total_locked
+        } else {
+            progress.day_total_locked
+        };
+        let eligible_bps = DistributionMath::calculate_eligible_bps(
+            locked_total as u128,
+            policy.y0_total_allocation,
+            policy.investor_fee_share_bps,
+        )
+        .map_err(FeeRouterError::from)?;
+        let tranche_cap = policy.tranche_cap_quote_lamports(progress.day_epoch);
+        let remaining_daily_cap = if !progress.first_page_pending() {
+            progress.day_investor_pool_target.saturating_sub(progress.day_investor_distributed)
+        } else if tranche_cap == 0 {
+            u64::MAX
+        } else {
+            tranche_cap.saturating_add(progress.banked_cap(tranche_cap, policy.max_banked_cap_quote_lamports(progress.day_epoch)))
+        };
+        Ok(Self {
+            day_epoch: progress.day_epoch,
+            day_finalized: progress.day_finalized_flag,
+            locked_total,
+            eligible_bps,
+            remaining_daily_cap,
+            day_investor_pool_target: progress.day_investor_pool_target,
+            day_investor_distributed: progress.day_investor_distributed,
+            day_creator_remainder_target: progress.day_creator_remainder_target,
+            day_claimed_quote: progress.day_claimed_quote,
+            pagination_cursor: progress.pagination_cursor,
+            page_investor_cursor: progress.page_investor_cursor,
+            pages_processed_today: progress.pages_processed_today,
+            total_pages_expected: progress.total_pages_expected,
+        })
+    }
+}
+
+/// Read the vault's eligible share, remaining cap, day targets and cursor without changing state
+///
+/// Meant for simulation: light clients read the return data instead of decoding account layouts.
+pub fn handler(ctx: Context<GetDistributionParams>, _vault_seed: String) -> Result<DistributionParams> {
+    DistributionParams::new(&ctx.accounts.policy_pda, &ctx.accounts.progress_pda)
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{CrankLogPda, PolicyPda};
+
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct InitializeCrankLog<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CrankLogPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"crank_log"],
+        bump
+    )]
+    pub crank_log: Account<'info, CrankLogPda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the crank log; from here on every crank must pass it and is recorded in it
+pub fn handler(
+    ctx: Context<InitializeCrankLog>,
+    vault_seed: String,
+) -> Result<()> {
+    let crank_log = &mut ctx.accounts.crank_log;
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    crank_log.vault_seed = vault_seed.clone();
+    crank_log.entries = Vec::new();
+    crank_log.next_index = 0;
+    crank_log.total_calls = 0;
+    ctx.accounts.policy_pda.crank_log = true;
+    ctx.accounts.policy_pda.updated_at = current_timestamp;
+
+    msg!(
+        "Crank log initialized: vault_seed={}, capacity={}",
+        vault_seed,
+        CrankLogPda::MAX_ENTRIES
+    );
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::{
+    dlmm::{self, DLMM_PROGRAM_ID},
+    error::FeeRouterError,
+    events::{HonoraryPositionInitialized, PreflightVerificationCompleted, EVENT_SCHEMA_VERSION},
+    state::{InvestorFeePositionOwnerPda, PolicyPda, PoolKind},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct InitializeDlmmPosition<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = InvestorFeePositionOwnerPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
+
+    /// DLMM program for position creation
+    /// CHECK: Address checked against the DLMM program ID
+    #[account(address = DLMM_PROGRAM_ID @ FeeRouterError::InvalidDlmmProgram)]
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    /// CHECK: DLMM event authority PDA (for event CPI integrity)
+    pub dlmm_event_authority: UncheckedAccount<'info>,
+
+    /// DLMM lb_pair account
+    /// CHECK: Owner and discriminator checked when read in the handler
+    pub lb_pair: UncheckedAccount<'info>,
+
+    /// Quote mint (must match policy)
+    pub quote_mint: Account<'info, Mint>,
+
+    /// Base mint (must match policy)
+    pub base_mint: Account<'info, Mint>,
+
+    /// Program quote treasury token account at the treasury PDA (created if needed)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        constraint = quote_treasury.delegate.is_none()
+            && quote_treasury.close_authority.is_none()
+            && !quote_treasury.is_frozen() @ FeeRouterError::InvalidTreasury,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda
+    )]
+    pub quote_treasury: Account<'info, TokenAccount>,
+
+    /// New DLMM position account (fresh keypair), created via DLMM CPI
+    #[account(mut)]
+    pub position: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create the honorary position as a DLMM bin-range position
+///
+/// The range is `[lower_bin_id, lower_bin_id + width - 1]` and must be quote-only relative to
+/// the pair's active bin.
+pub fn handler(
+    ctx: Context<InitializeDlmmPosition>,
+    vault_seed: String,
+    lower_bin_id: i32,
+    width: i32,
+    quote_mint: Pubkey,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    // Validate the policy selects this backend
+    require!(
+        ctx.accounts.policy_pda.pool_kind == PoolKind::Dlmm,
+        FeeRouterError::PoolKindMismatch
+    );
+
+    // Validate quote mint matches policy
+    if quote_mint != ctx.accounts.policy_pda.quote_mint {
+        return err!(FeeRouterError::InvalidPoolOrder);
+    }
+
+    // Validate pool matches policy
+    if ctx.accounts.lb_pair.key() != ctx.accounts.policy_pda.pool_pubkey {
+        return err!(FeeRouterError::PoolAccountMismatch);
+    }
+
+    // Validate bin range width
+    if width <= 0 || width > dlmm::MAX_BIN_PER_POSITION {
+        return err!(FeeRouterError::InvalidTickRange);
+    }
+    let upper_bin_id = lower_bin_id
+        .checked_add(width - 1)
+        .ok_or(FeeRouterError::Overflow)?;
+
+    // Validate DLMM event authority PDA
+    require_keys_eq!(
+        ctx.accounts.dlmm_event_authority.key(),
+        dlmm::derive_event_authority(),
+        FeeRouterError::InvalidDlmmProgram
+    );
+
+    // CRITICAL: Validate quote-only bin range against the active bin
+    let lb_pair = dlmm::read_lb_pair(&ctx.accounts.lb_pair)?;
+    dlmm::validate_quote_only_bins(
+        &lb_pair,
+        lower_bin_id,
+        upper_bin_id,
+        &quote_mint,
+        ctx.accounts.policy_pda.quote_only_gap(),
+    )?;
+
+    // Initialize position owner PDA
+    {
+        let position_owner_pda = &mut ctx.accounts.position_owner_pda;
+        position_owner_pda.vault_seed = vault_seed.clone();
+        position_owner_pda.position_pubkey = ctx.accounts.position.key();
+        position_owner_pda.pool_pubkey = ctx.accounts.lb_pair.key();
+        position_owner_pda.quote_mint = quote_mint;
+        position_owner_pda.tick_lower = lower_bin_id;
+        position_owner_pda.tick_upper = upper_bin_id;
+        position_owner_pda.verified_quote_only = true; // Validated via dlmm module
+        position_owner_pda.simulation_verified = false;
+        position_owner_pda.pool_kind = PoolKind::Dlmm;
+        position_owner_pda.created_at = current_timestamp;
+        position_owner_pda.position_nft_mint = Pubkey::default(); // DLMM positions have no NFT
+        position_owner_pda.lifetime_claimed_quote = 0;
+        position_owner_pda.lifetime_claimed_base = 0;
+        position_owner_pda.pool_config_hash = [0u8; 32];
+    }
+
+    // Create the honorary position via CPI to DLMM, owned by the position owner PDA
+    let ix = dlmm::initialize_position_ix(
+        ctx.accounts.payer.key(),
+        ctx.accounts.position.key(),
+        ctx.accounts.lb_pair.key(),
+        ctx.accounts.position_owner_pda.key(),
+        lower_bin_id,
+        width,
+    );
+
+    let owner_bump = ctx.bumps.position_owner_pda;
+    let owner_seeds: [&[u8]; 3] = [
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[owner_bump],
+    ];
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.position.to_account_info(),
+            ctx.accounts.lb_pair.to_account_info(),
+            ctx.accounts.position_owner_pda.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            ctx.accounts.dlmm_event_authority.to_account_info(),
+            ctx.accounts.dlmm_program.to_account_info(),
+        ],
+        &[&owner_seeds[..]],
+    )?;
+
+    msg!(
+        "Honorary DLMM position initialized: vault_seed={}, position={}, bins=[{}, {}]",
+        vault_seed,
+        ctx.accounts.position.key(),
+        lower_bin_id,
+        upper_bin_id
+    );
+
+    // Emit events
+    emit_cpi!(PreflightVerificationCompleted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        pool: ctx.accounts.lb_pair.key(),
+        quote_mint,
+        tick_lower: lower_bin_id,
+        tick_upper: upper_bin_id,
+        analytical_verified: true,
+        simulation_verified: false,
+        timestamp: current_timestamp,
+    });
+
+    emit_cpi!(HonoraryPositionInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        pda: ctx.accounts.position_owner_pda.key(),
+        position: ctx.accounts.position.key(),
+        pool: ctx.accounts.lb_pair.key(),
+        quote_mint,
+        tick_lower: lower_bin_id,
+        tick_upper: upper_bin_id,
+        timestamp: current_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{FailedPayoutsPda, PolicyPda};
+
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct InitializeFailedPayouts<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = FailedPayoutsPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"failed_payouts"],
+        bump
+    )]
+    pub failed_payouts: Account<'info, FailedPayoutsPda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the failed payout ledger used by continue-on-failure mode
+pub fn handler(
+    ctx: Context<InitializeFailedPayouts>,
+    vault_seed: String,
+) -> Result<()> {
+    let failed_payouts = &mut ctx.accounts.failed_payouts;
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    failed_payouts.vault_seed = vault_seed.clone();
+    failed_payouts.total_owed = 0;
+    failed_payouts.entries = Vec::new();
+    failed_payouts.updated_at = current_timestamp;
+
+    msg!(
+        "Failed payout ledger initialized: vault_seed={}, timestamp={}",
+        vault_seed,
+        current_timestamp
+    );
+
+    Ok(())
+}
@@ -1,21 +1,27 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_2022::Token2022;
 
 use crate::{
-    cp_amm::{self, Pool, CP_AMM_PROGRAM_ID},
+    cp_amm::{self, cp_amm::activation_handler::ActivationHandler, load_account, Pool},
+    cp_amm_entry_points,
     error::FeeRouterError,
-    events::{HonoraryPositionInitialized, PreflightVerificationCompleted},
-    state::{InvestorFeePositionOwnerPda, PolicyPda},
+    events::{HonoraryPositionInitialized, PreflightVerificationCompleted, EVENT_SCHEMA_VERSION},
+    instructions::distribute_fees::token_account_unencumbered,
+    state::{InvestorFeePositionOwnerPda, PolicyPda, PoolKind},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vault_seed: String)]
 pub struct InitializeHonoraryPosition<'info> {
-    #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         mut,
         seeds = [vault_seed.as_bytes(), b"policy"],
@@ -26,7 +32,7 @@ pub struct InitializeHonoraryPosition<'info> {
 
     #[account(
         init,
-        payer = authority,
+        payer = payer,
         space = InvestorFeePositionOwnerPda::LEN,
         seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
         bump
@@ -34,40 +40,46 @@ pub struct InitializeHonoraryPosition<'info> {
     pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
 
     /// CP-AMM program for position creation
-    /// CHECK: This will be validated against known CP-AMM program ID
+    /// CHECK: Validated against the policy's cp_amm_program_id
     pub cp_amm_program: UncheckedAccount<'info>,
 
-    /// CHECK: CP-AMM pool authority PDA
-    #[account(address = cp_amm::const_pda::pool_authority::ID)]
+    /// CHECK: CP-AMM pool authority PDA, derived from the policy's cp_amm_program_id
     pub pool_authority: UncheckedAccount<'info>,
 
     /// CHECK: CP-AMM event authority PDA (for event CPI integrity)
     pub cp_amm_event_authority: UncheckedAccount<'info>,
 
     /// CP-AMM pool account (mutable for create_position CPI)
+    /// CHECK: Owner (policy's CP-AMM program), discriminator and size checked when loaded
     #[account(mut)]
-    pub pool: AccountLoader<'info, Pool>,
+    pub pool: UncheckedAccount<'info>,
 
     /// Pool token vault 0
-    pub pool_token_vault_0: Account<'info, TokenAccount>,
+    pub pool_token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Pool token vault 1  
-    pub pool_token_vault_1: Account<'info, TokenAccount>,
+    /// Pool token vault 1
+    pub pool_token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Quote mint (must match policy)
-    pub quote_mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// Base mint (must match policy)
-    pub base_mint: Account<'info, Mint>,
+    #[account(mint::token_program = base_token_program)]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// Program quote treasury ATA (created if needed)
+    /// Program quote treasury token account at the treasury PDA (created if needed)
     #[account(
         init_if_needed,
-        payer = authority,
-        associated_token::mint = quote_mint,
-        associated_token::authority = position_owner_pda
+        payer = payer,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        constraint = token_account_unencumbered(&quote_treasury) @ FeeRouterError::InvalidTreasury,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program
     )]
-    pub quote_treasury: Account<'info, TokenAccount>,
+    pub quote_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Position NFT mint to be created by CP-AMM CPI
     #[account(mut)]
@@ -83,21 +95,36 @@ pub struct InitializeHonoraryPosition<'info> {
     pub position: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    /// Token program of the quote mint
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
     pub token_2022_program: Program<'info, Token2022>,
+
+    /// Token program of the base mint
+    pub base_token_program: Interface<'info, TokenInterface>,
 }
 
+/// Open the honorary CP-AMM position, owned by the position owner PDA
+///
+/// The range must sit on the quote side of the live price by the policy's quote-only gap, and the
+/// pool must charge every swap fee in quote (`PoolFeeModeNotQuoteOnly`). Probe swaps on the live
+/// pool set `simulation_verified`; the pool's fee configuration hash is stored for the crank.
 pub fn handler(
     ctx: Context<InitializeHonoraryPosition>,
     vault_seed: String,
-    tick_lower: i32,
-    tick_upper: i32,
+    mut tick_lower: i32,
+    mut tick_upper: i32,
     quote_mint: Pubkey,
 ) -> Result<()> {
     let current_timestamp = Clock::get()?.unix_timestamp as u64;
     
+    // Validate the policy selects this backend
+    require!(
+        ctx.accounts.policy_pda.pool_kind == PoolKind::CpAmm,
+        FeeRouterError::PoolKindMismatch
+    );
+
     // Validate quote mint matches policy
     if quote_mint != ctx.accounts.policy_pda.quote_mint {
         return err!(FeeRouterError::InvalidPoolOrder);
@@ -105,24 +132,41 @@ pub fn handler(
 
     // Validate pool matches policy
     if ctx.accounts.pool.key() != ctx.accounts.policy_pda.pool_pubkey {
-        return err!(FeeRouterError::MissingRequiredInput);
+        return err!(FeeRouterError::PoolAccountMismatch);
     }
 
-    // Validate tick range is valid
-    if tick_lower >= tick_upper {
-        return err!(FeeRouterError::InvalidTickRange);
-    }
-
-    // Validate CP-AMM program ID
+    // Validate CP-AMM program ID against the vault's configured program
+    let cp_amm_program_id = ctx.accounts.policy_pda.cp_amm_program_id;
     require_keys_eq!(
         ctx.accounts.cp_amm_program.key(),
-        CP_AMM_PROGRAM_ID,
+        cp_amm_program_id,
         FeeRouterError::InvalidCpAmmProgram
     );
+    require_keys_eq!(
+        ctx.accounts.pool_authority.key(),
+        cp_amm::derive_pool_authority(&cp_amm_program_id),
+        FeeRouterError::InvalidCpAmmPda
+    );
 
     // Load and validate pool state (scope the borrow to drop it before CPI)
-    {
-        let pool = ctx.accounts.pool.load()?;
+    let (pool_config_hash, simulation_verified) = {
+        let pool = load_account::<Pool>(&ctx.accounts.pool, &cp_amm_program_id)?;
+
+        // The sentinel range asks for the widest quote-only range at the live price
+        if (tick_lower, tick_upper) == cp_amm::AUTO_TICK_RANGE {
+            (tick_lower, tick_upper) = cp_amm::quote_only_tick_range(
+                &pool,
+                pool.token_a_mint == quote_mint,
+                ctx.accounts.policy_pda.quote_only_gap(),
+            )
+            .ok_or(FeeRouterError::PositionNotQuoteOnly)?;
+            msg!("Derived quote-only tick range [{}, {}]", tick_lower, tick_upper);
+        }
+
+        // Validate tick range is valid
+        if tick_lower >= tick_upper {
+            return err!(FeeRouterError::InvalidTickRange);
+        }
 
         // CRITICAL: Validate quote-only position using CP-AMM module
         cp_amm::validate_quote_only_position(
@@ -130,6 +174,7 @@ pub fn handler(
             tick_lower,
             tick_upper,
             &quote_mint,
+            ctx.accounts.policy_pda.quote_only_gap(),
         )?;
 
         msg!(
@@ -137,7 +182,27 @@ pub fn handler(
             tick_lower,
             tick_upper
         );
-    } // Drop pool borrow here before CPI
+
+        // The range alone isn't enough: the pool must also never charge swap fees in the base token
+        let is_token_a_quote = pool.token_a_mint == quote_mint;
+        cp_amm::validate_quote_only_fee_mode(&pool, is_token_a_quote)?;
+
+        // Simulation preflight: a probe swap each way through the live pool must accrue no base fee
+        let simulation_verified = cp_amm::simulate_quote_only_fee_accrual(
+            &pool,
+            is_token_a_quote,
+            cp_amm::FEE_ACCRUAL_PROBE_AMOUNT,
+            current_timestamp,
+            ActivationHandler::get_current_point(pool.activation_type)?,
+        )?;
+        if simulation_verified {
+            msg!("Preflight simulation passed: no base fee accrued");
+        } else {
+            msg!("Preflight simulation not run: the pool can't price a probe swap both ways");
+        }
+
+        (cp_amm::pool_fee_config_hash(&pool), simulation_verified)
+    }; // Drop pool borrow here before CPI
 
     // Initialize position owner PDA (short-lived mutable borrow scope)
     {
@@ -149,7 +214,13 @@ pub fn handler(
         position_owner_pda.tick_lower = tick_lower;
         position_owner_pda.tick_upper = tick_upper;
         position_owner_pda.verified_quote_only = true; // Validated via cp_amm module
+        position_owner_pda.simulation_verified = simulation_verified;
+        position_owner_pda.pool_kind = PoolKind::CpAmm;
         position_owner_pda.created_at = current_timestamp;
+        position_owner_pda.position_nft_mint = ctx.accounts.position_mint.key();
+        position_owner_pda.lifetime_claimed_quote = 0;
+        position_owner_pda.lifetime_claimed_base = 0;
+        position_owner_pda.pool_config_hash = pool_config_hash;
     }
 
     // Derive and validate expected CP-AMM PDAs for position and its NFT account
@@ -158,7 +229,7 @@ pub fn handler(
             cp_amm::constants::seeds::POSITION_PREFIX.as_ref(),
             ctx.accounts.position_mint.key().as_ref(),
         ],
-        &CP_AMM_PROGRAM_ID,
+        &cp_amm_program_id,
     );
     require_keys_eq!(
         ctx.accounts.position.key(),
@@ -171,7 +242,7 @@ pub fn handler(
             cp_amm::constants::seeds::POSITION_NFT_ACCOUNT_PREFIX.as_ref(),
             ctx.accounts.position_mint.key().as_ref(),
         ],
-        &CP_AMM_PROGRAM_ID,
+        &cp_amm_program_id,
     );
     require_keys_eq!(
         ctx.accounts.position_token_account.key(),
@@ -181,10 +252,9 @@ pub fn handler(
 
     // Create the honorary position via CPI to CP-AMM
     // Validate CP-AMM event authority PDA
-    let (expected_event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &CP_AMM_PROGRAM_ID);
     require_keys_eq!(
         ctx.accounts.cp_amm_event_authority.key(),
-        expected_event_authority,
+        cp_amm::derive_event_authority(&cp_amm_program_id),
         FeeRouterError::InvalidCpAmmPda
     );
 
@@ -195,7 +265,7 @@ pub fn handler(
         pool: ctx.accounts.pool.to_account_info(),
         position: ctx.accounts.position.to_account_info(),
         pool_authority: ctx.accounts.pool_authority.to_account_info(),
-        payer: ctx.accounts.authority.to_account_info(),
+        payer: ctx.accounts.payer.to_account_info(),
         token_program: ctx.accounts.token_2022_program.to_account_info(),
         system_program: ctx.accounts.system_program.to_account_info(),
         event_authority: ctx.accounts.cp_amm_event_authority.to_account_info(),
@@ -212,11 +282,10 @@ pub fn handler(
     ];
     let signer = &[&owner_seeds[..]];
 
-    cp_amm::cpi::create_position(CpiContext::new_with_signer(
-        cpi_program,
-        cpi_accounts,
-        signer,
-    ))?;
+    cp_amm_entry_points::create_position(
+        &ctx.accounts.policy_pda.cp_amm_entry_points,
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+    )?;
 
     msg!(
         "Honorary position initialized: vault_seed={}, position={}, ticks=[{}, {}]",
@@ -227,17 +296,21 @@ pub fn handler(
     );
 
     // Emit events
-    emit!(PreflightVerificationCompleted {
+    emit_cpi!(PreflightVerificationCompleted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
         pool: ctx.accounts.pool.key(),
         quote_mint,
         tick_lower,
         tick_upper,
         analytical_verified: true,
-        simulation_verified: false,
+        simulation_verified,
         timestamp: current_timestamp,
     });
 
-    emit!(HonoraryPositionInitialized {
+    emit_cpi!(HonoraryPositionInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
         pda: ctx.accounts.position_owner_pda.key(),
         position: ctx.accounts.position.key(),
         pool: ctx.accounts.pool.key(),
@@ -248,4 +321,4 @@ pub fn handler(
     });
 
     Ok(())
-}
\ No newline at end of file
+}
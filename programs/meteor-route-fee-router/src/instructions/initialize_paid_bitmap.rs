@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{PaidBitmapPda, PolicyPda};
+
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct InitializePaidBitmap<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = PaidBitmapPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"paid_bitmap"],
+        bump
+    )]
+    pub paid_bitmap: Account<'info, PaidBitmapPda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the per-day paid bitmap used to enforce exactly-once investor payouts
+pub fn handler(
+    ctx: Context<InitializePaidBitmap>,
+    vault_seed: String,
+) -> Result<()> {
+    let paid_bitmap = &mut ctx.accounts.paid_bitmap;
+
+    paid_bitmap.vault_seed = vault_seed.clone();
+    paid_bitmap.day_epoch = 0;
+    paid_bitmap.clear();
+
+    msg!(
+        "Paid bitmap initialized: vault_seed={}, capacity={}",
+        vault_seed,
+        PaidBitmapPda::MAX_INVESTORS
+    );
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{PayoutOverridesPda, PolicyPda};
+
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct InitializePayoutOverrides<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = PayoutOverridesPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"payout_overrides"],
+        bump
+    )]
+    pub payout_overrides: Account<'info, PayoutOverridesPda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the per-investor minimum payout table; from here on the crank must pass it
+pub fn handler(
+    ctx: Context<InitializePayoutOverrides>,
+    vault_seed: String,
+) -> Result<()> {
+    let payout_overrides = &mut ctx.accounts.payout_overrides;
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    payout_overrides.vault_seed = vault_seed.clone();
+    payout_overrides.entries = Vec::new();
+    payout_overrides.updated_at = current_timestamp;
+    ctx.accounts.policy_pda.payout_overrides = true;
+    ctx.accounts.policy_pda.updated_at = current_timestamp;
+
+    msg!(
+        "Payout override table initialized: vault_seed={}, capacity={}",
+        vault_seed,
+        PayoutOverridesPda::MAX_ENTRIES
+    );
+
+    Ok(())
+}
@@ -1,50 +1,102 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::token_interface::{Mint, TokenInterface};
 
 use crate::{
+    attestation::AttestationLayout,
+    cp_amm_entry_points::CpAmmEntryPoints,
     error::FeeRouterError,
-    events::PolicyUpdated,
-    state::PolicyPda,
+    events::{PolicyUpdated, EVENT_SCHEMA_VERSION},
+    locker::LockerKind,
+    mint_safety::{mint_risk, require_allowed_mint_risks},
+    stake::StakeLayout,
+    state::{NetworkConfig, PolicyPda, PoolKind, RewardRouting, UnattestedRouting, VaultRegistry},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vault_seed: String)]
 pub struct InitializePolicy<'info> {
-    #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         init,
-        payer = authority,
+        payer = payer,
         space = PolicyPda::LEN,
         seeds = [vault_seed.as_bytes(), b"policy"],
         bump
     )]
     pub policy_pda: Account<'info, PolicyPda>,
 
-    /// Quote mint - must be verified during initialization
-    pub quote_mint: Account<'info, Mint>,
+    /// Quote mint - must be verified during initialization (SPL Token or Token-2022)
+    #[account(mint::token_program = token_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// Base mint - must be verified during initialization
-    pub base_mint: Account<'info, Mint>,
+    /// Base mint - must be verified during initialization (SPL Token or Token-2022)
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// CP-AMM pool account (for verification)
     /// CHECK: This will be verified against CP-AMM program
     pub pool: UncheckedAccount<'info>,
 
+    /// CP-AMM program this vault's pool lives in (devnet, mainnet DAMM v2 or an audited fork)
+    /// CHECK: Only the address is recorded; it is the program every later CP-AMM account is checked against
+    pub cp_amm_program: UncheckedAccount<'info>,
+
+    /// Vaults created by this authority; created with its first vault
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultRegistry::LEN,
+        seeds = [VaultRegistry::SEED, authority.key().as_ref()],
+        bump
+    )]
+    pub vault_registry: Account<'info, VaultRegistry>,
+
+    /// Program-wide cluster selection; once initialized the CP-AMM program must be the network's
+    /// CHECK: Seeds checked; deserialized with NetworkConfig::load
+    #[account(seeds = [NetworkConfig::SEED], bump)]
+    pub network_config: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    /// Token program of the quote mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Arguments of `initialize_policy`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InitializePolicyArgs {
+    pub investor_fee_share_bps: u16,
+    pub daily_cap_quote_lamports: u64,
+    pub min_payout_lamports: u64,
+    pub policy_fund_missing_ata: bool,
+    pub y0_total_allocation: u128,
+    /// `mint_risk` bits the quote mint may carry; any other risk fails the init
+    pub allowed_quote_mint_risks: u8,
 }
 
+/// Create the vault's policy with the core distribution parameters
+///
+/// Every optional behaviour starts off and is turned on with `update_policy`. The quote mint is
+/// screened against `allowed_quote_mint_risks`, and the policy is appended to the authority's
+/// `VaultRegistry`.
 pub fn handler(
     ctx: Context<InitializePolicy>,
     vault_seed: String,
-    investor_fee_share_bps: u16,
-    daily_cap_quote_lamports: u64,
-    min_payout_lamports: u64,
-    policy_fund_missing_ata: bool,
-    y0_total_allocation: u128,
+    args: InitializePolicyArgs,
 ) -> Result<()> {
+    let InitializePolicyArgs {
+        investor_fee_share_bps,
+        daily_cap_quote_lamports,
+        min_payout_lamports,
+        policy_fund_missing_ata,
+        y0_total_allocation,
+        allowed_quote_mint_risks,
+    } = args;
+
     // Validate fee share basis points
     if investor_fee_share_bps > 10000 {
         return err!(FeeRouterError::InvalidFeeShareBps);
@@ -60,6 +112,20 @@ pub fn handler(
         return err!(FeeRouterError::InvalidPoolOrder);
     }
 
+    // Freeze authorities, permanent delegates and pausable or confidential extensions can censor
+    // or drain distributions later; each must be opted into
+    require!(allowed_quote_mint_risks & !mint_risk::ALL == 0, FeeRouterError::QuoteMintRiskNotAllowed);
+    require_allowed_mint_risks(&ctx.accounts.quote_mint.to_account_info(), allowed_quote_mint_risks)?;
+
+    // The CP-AMM program must be a real address
+    if ctx.accounts.cp_amm_program.key() == Pubkey::default() {
+        return err!(FeeRouterError::InvalidCpAmmProgram);
+    }
+    NetworkConfig::require_cp_amm_program(
+        NetworkConfig::load(&ctx.accounts.network_config)?.as_ref(),
+        ctx.accounts.cp_amm_program.key,
+    )?;
+
     let policy_pda = &mut ctx.accounts.policy_pda;
     let current_timestamp = Clock::get()?.unix_timestamp as u64;
 
@@ -74,16 +140,117 @@ pub fn handler(
     policy_pda.quote_mint = ctx.accounts.quote_mint.key();
     policy_pda.base_mint = ctx.accounts.base_mint.key();
     policy_pda.pool_pubkey = ctx.accounts.pool.key();
+    policy_pda.cp_amm_program_id = ctx.accounts.cp_amm_program.key();
+    policy_pda.day_gate_grace_secs = 0;
+    policy_pda.min_daily_claim_lamports = 0;
+    policy_pda.roll_capped_excess = false;
+    policy_pda.bonfida_program_id = Pubkey::default();
+    policy_pda.locker_kind = LockerKind::Streamflow;
+    policy_pda.locker_allowlist = Vec::new();
+    policy_pda.pool_kind = PoolKind::CpAmm;
+    policy_pda.quote_only_buffer_ticks = 0;
+    policy_pda.payout_memos = false;
+    policy_pda.unwrap_wsol_creator = false;
+    policy_pda.continue_on_failure = false;
+    policy_pda.track_paid_investors = false;
+    policy_pda.static_accounts = None;
+    policy_pda.force_finalize_after_secs = PolicyPda::DEFAULT_FORCE_FINALIZE_AFTER_SECS;
+    policy_pda.crank_lease_slots = PolicyPda::DEFAULT_CRANK_LEASE_SLOTS;
+    policy_pda.batch_investor_paid_events = false;
+    policy_pda.creator_vesting_secs = 0;
+    policy_pda.compound_bps = 0;
+    policy_pda.buyback_burn_creator = false;
+    policy_pda.buyback_max_slippage_bps = PolicyPda::DEFAULT_BUYBACK_MAX_SLIPPAGE_BPS;
+    policy_pda.referral_wallet = Pubkey::default();
+    policy_pda.referral_bps = 0;
+    policy_pda.paused = false;
+    policy_pda.stake_program_id = Pubkey::default();
+    policy_pda.stake_layout = StakeLayout::default();
+    policy_pda.day_gate_slots = 0;
+    policy_pda.epoch_offset_secs = None;
+    policy_pda.tranches_per_day = 1;
+    policy_pda.payout_overrides = false;
+    policy_pda.weight_overrides = false;
+    policy_pda.max_weight_bps = 0;
+    policy_pda.max_investors_per_page = 0;
+    policy_pda.max_pages_per_day = 0;
+    policy_pda.crank_log = false;
+    policy_pda.attestation_program = Pubkey::default();
+    policy_pda.attestation_layout = AttestationLayout::default();
+    policy_pda.reward_routing = RewardRouting::Creator;
+    policy_pda.cp_amm_entry_points = CpAmmEntryPoints::BuiltIn;
+    policy_pda.min_tick_distance_from_price = 0;
+    policy_pda.cap_carry_days = 0;
+    policy_pda.payout_to_stream_escrow = false;
+    policy_pda.payout_dispute_window_secs = 0;
+    policy_pda.fund_missing_creator_ata = false;
+    policy_pda.allowed_quote_mint_risks = allowed_quote_mint_risks;
+    policy_pda.hook_program = Pubkey::default();
+    policy_pda.lock_oracle = Pubkey::default();
+    policy_pda.lock_oracle_max_age_secs = 0;
+    policy_pda.unordered_pages = false;
+    policy_pda.param_schedule = Vec::new();
+    policy_pda.unattested_routing = UnattestedRouting::CarryOver;
     policy_pda.created_at = current_timestamp;
     policy_pda.updated_at = current_timestamp;
 
+    let vault_registry = &mut ctx.accounts.vault_registry;
+    vault_registry.authority = ctx.accounts.authority.key();
+    vault_registry.register(policy_pda.key())?;
+    vault_registry.updated_at = current_timestamp;
+
     // Emit policy creation event
-    emit!(PolicyUpdated {
+    emit_cpi!(PolicyUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
         vault_seed: policy_pda.vault_seed.clone(),
         investor_fee_share_bps,
         daily_cap_quote_lamports,
         min_payout_lamports,
         policy_fund_missing_ata,
+        day_gate_grace_secs: 0,
+        min_daily_claim_lamports: 0,
+        roll_capped_excess: false,
+        bonfida_program_id: Pubkey::default(),
+        locker_kind: LockerKind::Streamflow,
+        locker_allowlist: Vec::new(),
+        pool_kind: PoolKind::CpAmm,
+        quote_only_buffer_ticks: 0,
+        payout_memos: false,
+        unwrap_wsol_creator: false,
+        continue_on_failure: false,
+        track_paid_investors: false,
+        force_finalize_after_secs: PolicyPda::DEFAULT_FORCE_FINALIZE_AFTER_SECS,
+        crank_lease_slots: PolicyPda::DEFAULT_CRANK_LEASE_SLOTS,
+        batch_investor_paid_events: false,
+        creator_vesting_secs: 0,
+        compound_bps: 0,
+        buyback_burn_creator: false,
+        buyback_max_slippage_bps: PolicyPda::DEFAULT_BUYBACK_MAX_SLIPPAGE_BPS,
+        referral_wallet: Pubkey::default(),
+        referral_bps: 0,
+        stake_program_id: Pubkey::default(),
+        stake_layout: StakeLayout::default(),
+        day_gate_slots: 0,
+        epoch_offset_secs: None,
+        tranches_per_day: 1,
+        max_weight_bps: 0,
+        max_investors_per_page: 0,
+        max_pages_per_day: 0,
+        attestation_program: Pubkey::default(),
+        attestation_layout: AttestationLayout::default(),
+        reward_routing: RewardRouting::Creator,
+        cp_amm_entry_points: CpAmmEntryPoints::BuiltIn,
+        min_tick_distance_from_price: 0,
+        cap_carry_days: 0,
+        payout_to_stream_escrow: false,
+        payout_dispute_window_secs: 0,
+        fund_missing_creator_ata: false,
+        hook_program: Pubkey::default(),
+        lock_oracle: Pubkey::default(),
+        lock_oracle_max_age_secs: 0,
+        unordered_pages: false,
+        param_schedule: Vec::new(),
+        unattested_routing: UnattestedRouting::CarryOver,
         timestamp: current_timestamp,
     });
 
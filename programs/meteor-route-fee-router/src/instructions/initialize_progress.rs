@@ -5,9 +5,12 @@ use crate::state::{PolicyPda, ProgressPda};
 #[derive(Accounts)]
 #[instruction(vault_seed: String)]
 pub struct InitializeProgress<'info> {
-    #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         seeds = [vault_seed.as_bytes(), b"policy"],
         bump,
@@ -17,7 +20,7 @@ pub struct InitializeProgress<'info> {
 
     #[account(
         init,
-        payer = authority,
+        payer = payer,
         space = ProgressPda::LEN,
         seeds = [vault_seed.as_bytes(), b"progress"],
         bump
@@ -47,6 +50,34 @@ pub fn handler(
     progress_pda.pages_processed_today = 0;
     progress_pda.last_claimed_quote = 0;
     progress_pda.last_claimed_base = 0;
+    progress_pda.page_hash_chain = [0u8; 32];
+    progress_pda.rollover_claimed_quote = 0;
+    progress_pda.capped_excess_rollover = 0;
+    progress_pda.day_capped_excess_in = 0;
+    progress_pda.snapshot_ts = 0;
+    progress_pda.snapshot_total_locked = 0;
+    progress_pda.snapshot_investor_count = 0;
+    progress_pda.snapshot_locked_hash = [0u8; 32];
+    progress_pda.snapshot_complete = false;
+    progress_pda.payout_locked_hash = [0u8; 32];
+    progress_pda.day_locked_processed = 0;
+    progress_pda.snapshot_largest_locked = [0; 21];
+    progress_pda.weight_cap_bps = 0;
+    progress_pda.weight_cap_threshold = 0;
+    progress_pda.weight_cap_count = 0;
+    progress_pda.weight_cap_uncapped_locked = 0;
+    progress_pda.day_weight_processed = 0;
+    progress_pda.unused_cap_allowance = 0;
+    progress_pda.cap_allowance_epoch = 0;
+    progress_pda.committed_investor_count = 0;
+    progress_pda.day_investors_processed = 0;
+    progress_pda.page_bitmap = [0u8; 64];
+    progress_pda.snapshot_locked_set = [0u8; 32];
+    progress_pda.payout_locked_set = [0u8; 32];
+    progress_pda.snapshot_last_stream = Pubkey::default();
+    progress_pda.creator_owed = 0;
+    progress_pda.buyback_pending = 0;
+    progress_pda.generation = 0;
     progress_pda.created_at = current_timestamp;
     progress_pda.updated_at = current_timestamp;
 
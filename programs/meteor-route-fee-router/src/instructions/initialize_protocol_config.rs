@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{ProtocolConfigUpdated, EVENT_SCHEMA_VERSION},
+    program::MeteorRouteFeeRouter,
+    state::ProtocolConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeProtocolConfig<'info> {
+    /// The program's upgrade authority
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ProtocolConfig::LEN,
+        seeds = [ProtocolConfig::SEED],
+        bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// This program, for its ProgramData address
+    #[account(constraint = router_program.programdata_address()? == Some(program_data.key()))]
+    pub router_program: Program<'info, MeteorRouteFeeRouter>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the deployment's protocol config and hand it to `admin`
+///
+/// Only the upgrade authority can create it, so whoever deploys the router decides who may set
+/// the protocol fee every vault pays.
+pub fn handler(
+    ctx: Context<InitializeProtocolConfig>,
+    admin: Pubkey,
+    protocol_fee_bps: u16,
+    protocol_treasury: Pubkey,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp as u64;
+    let config = &mut ctx.accounts.protocol_config;
+    config.admin = admin;
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.protocol_treasury = protocol_treasury;
+    config.created_at = timestamp;
+    config.updated_at = timestamp;
+    config.validate()?;
+
+    emit_cpi!(ProtocolConfigUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        admin,
+        protocol_fee_bps,
+        protocol_treasury,
+        timestamp,
+    });
+
+    msg!(
+        "Protocol config initialized: admin={}, protocol_fee_bps={}, treasury={}",
+        admin,
+        protocol_fee_bps,
+        protocol_treasury
+    );
+
+    Ok(())
+}
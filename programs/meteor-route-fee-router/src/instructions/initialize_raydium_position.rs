@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::TokenAccount as InterfaceTokenAccount;
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::{
+    error::FeeRouterError,
+    events::{HonoraryPositionInitialized, PreflightVerificationCompleted, EVENT_SCHEMA_VERSION},
+    raydium_clmm,
+    state::{InvestorFeePositionOwnerPda, PolicyPda, PoolKind},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct InitializeRaydiumPosition<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = InvestorFeePositionOwnerPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
+
+    /// Raydium CLMM pool state
+    /// CHECK: Owner and discriminator checked when read in the handler
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// Raydium CLMM personal position
+    /// CHECK: Owner, discriminator and PDA checked in the handler
+    pub personal_position: UncheckedAccount<'info>,
+
+    /// Token account holding the position NFT; custody must already sit with the position owner PDA
+    #[account(
+        token::authority = position_owner_pda
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// Quote mint (must match policy)
+    pub quote_mint: Account<'info, Mint>,
+
+    /// Base mint (must match policy)
+    pub base_mint: Account<'info, Mint>,
+
+    /// Program quote treasury token account at the treasury PDA (created if needed)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        constraint = quote_treasury.delegate.is_none()
+            && quote_treasury.close_authority.is_none()
+            && !quote_treasury.is_frozen() @ FeeRouterError::InvalidTreasury,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda
+    )]
+    pub quote_treasury: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Adopt a Raydium CLMM personal position as the honorary position
+///
+/// Raydium positions are owned by whoever holds the position NFT, so the NFT is opened
+/// elsewhere and transferred to the position owner PDA first; this records it once the
+/// custody and the quote-only tick range check out.
+pub fn handler(
+    ctx: Context<InitializeRaydiumPosition>,
+    vault_seed: String,
+    quote_mint: Pubkey,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    // Validate the policy selects this backend
+    require!(
+        ctx.accounts.policy_pda.pool_kind == PoolKind::RaydiumClmm,
+        FeeRouterError::PoolKindMismatch
+    );
+
+    // Validate quote mint matches policy
+    if quote_mint != ctx.accounts.policy_pda.quote_mint {
+        return err!(FeeRouterError::InvalidPoolOrder);
+    }
+
+    // Validate pool matches policy
+    if ctx.accounts.pool_state.key() != ctx.accounts.policy_pda.pool_pubkey {
+        return err!(FeeRouterError::PoolAccountMismatch);
+    }
+
+    // The position must belong to the pool and its NFT must be held by the PDA
+    let pool = raydium_clmm::read_pool(&ctx.accounts.pool_state)?;
+    let position = raydium_clmm::read_personal_position(&ctx.accounts.personal_position)?;
+    require_keys_eq!(
+        position.pool_id,
+        ctx.accounts.pool_state.key(),
+        FeeRouterError::PoolAccountMismatch
+    );
+    require_keys_eq!(
+        ctx.accounts.personal_position.key(),
+        raydium_clmm::derive_personal_position(&position.nft_mint),
+        FeeRouterError::InvalidRaydiumProgram
+    );
+    require!(
+        ctx.accounts.position_nft_account.mint == position.nft_mint
+            && ctx.accounts.position_nft_account.amount == 1,
+        FeeRouterError::InvalidPositionOwner
+    );
+
+    // CRITICAL: Validate quote-only tick range against the current tick
+    let tick_lower = position.tick_lower_index;
+    let tick_upper = position.tick_upper_index;
+    raydium_clmm::validate_quote_only_ticks(
+        &pool,
+        tick_lower,
+        tick_upper,
+        &quote_mint,
+        ctx.accounts.policy_pda.quote_only_gap(),
+    )?;
+
+    // Initialize position owner PDA
+    {
+        let position_owner_pda = &mut ctx.accounts.position_owner_pda;
+        position_owner_pda.vault_seed = vault_seed.clone();
+        position_owner_pda.position_pubkey = ctx.accounts.personal_position.key();
+        position_owner_pda.pool_pubkey = ctx.accounts.pool_state.key();
+        position_owner_pda.quote_mint = quote_mint;
+        position_owner_pda.tick_lower = tick_lower;
+        position_owner_pda.tick_upper = tick_upper;
+        position_owner_pda.verified_quote_only = true; // Validated via raydium_clmm module
+        position_owner_pda.simulation_verified = false;
+        position_owner_pda.pool_kind = PoolKind::RaydiumClmm;
+        position_owner_pda.created_at = current_timestamp;
+        position_owner_pda.position_nft_mint = ctx.accounts.position_nft_account.mint;
+        position_owner_pda.lifetime_claimed_quote = 0;
+        position_owner_pda.lifetime_claimed_base = 0;
+        position_owner_pda.pool_config_hash = [0u8; 32];
+    }
+
+    msg!(
+        "Honorary Raydium CLMM position adopted: vault_seed={}, position={}, ticks=[{}, {}]",
+        vault_seed,
+        ctx.accounts.personal_position.key(),
+        tick_lower,
+        tick_upper
+    );
+
+    // Emit events
+    emit_cpi!(PreflightVerificationCompleted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        pool: ctx.accounts.pool_state.key(),
+        quote_mint,
+        tick_lower,
+        tick_upper,
+        analytical_verified: true,
+        simulation_verified: false,
+        timestamp: current_timestamp,
+    });
+
+    emit_cpi!(HonoraryPositionInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        pda: ctx.accounts.position_owner_pda.key(),
+        position: ctx.accounts.personal_position.key(),
+        pool: ctx.accounts.pool_state.key(),
+        quote_mint,
+        tick_lower,
+        tick_upper,
+        timestamp: current_timestamp,
+    });
+
+    Ok(())
+}
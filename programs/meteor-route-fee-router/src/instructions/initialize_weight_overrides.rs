@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{PolicyPda, WeightOverridesPda};
+
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct InitializeWeightOverrides<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = WeightOverridesPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"weight_overrides"],
+        bump
+    )]
+    pub weight_overrides: Account<'info, WeightOverridesPda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the fixed investor weight table; from here on snapshots and the crank must pass it
+pub fn handler(
+    ctx: Context<InitializeWeightOverrides>,
+    vault_seed: String,
+) -> Result<()> {
+    let weight_overrides = &mut ctx.accounts.weight_overrides;
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    weight_overrides.vault_seed = vault_seed.clone();
+    weight_overrides.entries = Vec::new();
+    weight_overrides.updated_at = current_timestamp;
+    ctx.accounts.policy_pda.weight_overrides = true;
+    ctx.accounts.policy_pda.updated_at = current_timestamp;
+
+    msg!(
+        "Weight override table initialized: vault_seed={}, capacity={}",
+        vault_seed,
+        WeightOverridesPda::MAX_ENTRIES
+    );
+
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::associated_token::{get_associated_token_address, AssociatedToken};
+
+use crate::{
+    error::FeeRouterError,
+    events::{HonoraryPositionInitialized, PreflightVerificationCompleted, EVENT_SCHEMA_VERSION},
+    state::{InvestorFeePositionOwnerPda, PolicyPda, PoolKind},
+    whirlpool::{self, WHIRLPOOL_PROGRAM_ID},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct InitializeWhirlpoolPosition<'info> {
+    pub authority: Signer<'info>,
+
+    /// Pays rent for the accounts created here, so the authority can be a multisig vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = InvestorFeePositionOwnerPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
+
+    /// Whirlpool program for position creation
+    /// CHECK: Address checked against the Whirlpool program ID
+    #[account(address = WHIRLPOOL_PROGRAM_ID @ FeeRouterError::InvalidWhirlpoolProgram)]
+    pub whirlpool_program: UncheckedAccount<'info>,
+
+    /// Whirlpool account
+    /// CHECK: Owner and discriminator checked when read in the handler
+    pub whirlpool: UncheckedAccount<'info>,
+
+    /// Quote mint (must match policy)
+    pub quote_mint: Account<'info, Mint>,
+
+    /// Base mint (must match policy)
+    pub base_mint: Account<'info, Mint>,
+
+    /// Program quote treasury token account at the treasury PDA (created if needed)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        constraint = quote_treasury.delegate.is_none()
+            && quote_treasury.close_authority.is_none()
+            && !quote_treasury.is_frozen() @ FeeRouterError::InvalidTreasury,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda
+    )]
+    pub quote_treasury: Account<'info, TokenAccount>,
+
+    /// Position NFT mint to be created by the Whirlpool CPI
+    #[account(mut)]
+    pub position_mint: Signer<'info>,
+
+    /// CHECK: Position NFT ATA of the position owner PDA, created by the Whirlpool CPI
+    #[account(mut)]
+    pub position_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Position PDA to be created by the Whirlpool CPI
+    #[account(mut)]
+    pub position: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create the honorary position as an Orca Whirlpool position held by the position owner PDA
+pub fn handler(
+    ctx: Context<InitializeWhirlpoolPosition>,
+    vault_seed: String,
+    tick_lower: i32,
+    tick_upper: i32,
+    quote_mint: Pubkey,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    // Validate the policy selects this backend
+    require!(
+        ctx.accounts.policy_pda.pool_kind == PoolKind::Whirlpool,
+        FeeRouterError::PoolKindMismatch
+    );
+
+    // Validate quote mint matches policy
+    if quote_mint != ctx.accounts.policy_pda.quote_mint {
+        return err!(FeeRouterError::InvalidPoolOrder);
+    }
+
+    // Validate pool matches policy
+    if ctx.accounts.whirlpool.key() != ctx.accounts.policy_pda.pool_pubkey {
+        return err!(FeeRouterError::PoolAccountMismatch);
+    }
+
+    // CRITICAL: Validate quote-only tick range against the current tick
+    let pool = whirlpool::read_whirlpool(&ctx.accounts.whirlpool)?;
+    whirlpool::validate_quote_only_ticks(
+        &pool,
+        tick_lower,
+        tick_upper,
+        &quote_mint,
+        ctx.accounts.policy_pda.quote_only_gap(),
+    )?;
+
+    // Derive and validate the position PDA and the PDA's NFT ATA
+    let (expected_position, position_bump) =
+        whirlpool::derive_position(&ctx.accounts.position_mint.key());
+    require_keys_eq!(
+        ctx.accounts.position.key(),
+        expected_position,
+        FeeRouterError::InvalidWhirlpoolProgram
+    );
+    require_keys_eq!(
+        ctx.accounts.position_token_account.key(),
+        get_associated_token_address(
+            &ctx.accounts.position_owner_pda.key(),
+            &ctx.accounts.position_mint.key(),
+        ),
+        FeeRouterError::InvalidWhirlpoolProgram
+    );
+
+    // Initialize position owner PDA
+    {
+        let position_owner_pda = &mut ctx.accounts.position_owner_pda;
+        position_owner_pda.vault_seed = vault_seed.clone();
+        position_owner_pda.position_pubkey = ctx.accounts.position.key();
+        position_owner_pda.pool_pubkey = ctx.accounts.whirlpool.key();
+        position_owner_pda.quote_mint = quote_mint;
+        position_owner_pda.tick_lower = tick_lower;
+        position_owner_pda.tick_upper = tick_upper;
+        position_owner_pda.verified_quote_only = true; // Validated via whirlpool module
+        position_owner_pda.simulation_verified = false;
+        position_owner_pda.pool_kind = PoolKind::Whirlpool;
+        position_owner_pda.created_at = current_timestamp;
+        position_owner_pda.position_nft_mint = ctx.accounts.position_mint.key();
+        position_owner_pda.lifetime_claimed_quote = 0;
+        position_owner_pda.lifetime_claimed_base = 0;
+        position_owner_pda.pool_config_hash = [0u8; 32];
+    }
+
+    // Open the position via CPI; the payer funds it and the PDA receives the NFT
+    let ix = whirlpool::open_position_ix(
+        &whirlpool::OpenPositionAccounts {
+            funder: ctx.accounts.payer.key(),
+            owner: ctx.accounts.position_owner_pda.key(),
+            position: ctx.accounts.position.key(),
+            position_mint: ctx.accounts.position_mint.key(),
+            position_token_account: ctx.accounts.position_token_account.key(),
+            whirlpool: ctx.accounts.whirlpool.key(),
+        },
+        position_bump,
+        tick_lower,
+        tick_upper,
+    );
+
+    invoke(
+        &ix,
+        &[
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.position_owner_pda.to_account_info(),
+            ctx.accounts.position.to_account_info(),
+            ctx.accounts.position_mint.to_account_info(),
+            ctx.accounts.position_token_account.to_account_info(),
+            ctx.accounts.whirlpool.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            ctx.accounts.associated_token_program.to_account_info(),
+            ctx.accounts.whirlpool_program.to_account_info(),
+        ],
+    )?;
+
+    msg!(
+        "Honorary Whirlpool position initialized: vault_seed={}, position={}, ticks=[{}, {}]",
+        vault_seed,
+        ctx.accounts.position.key(),
+        tick_lower,
+        tick_upper
+    );
+
+    // Emit events
+    emit_cpi!(PreflightVerificationCompleted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        pool: ctx.accounts.whirlpool.key(),
+        quote_mint,
+        tick_lower,
+        tick_upper,
+        analytical_verified: true,
+        simulation_verified: false,
+        timestamp: current_timestamp,
+    });
+
+    emit_cpi!(HonoraryPositionInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        pda: ctx.accounts.position_owner_pda.key(),
+        position: ctx.accounts.position.key(),
+        pool: ctx.accounts.whirlpool.key(),
+        quote_mint,
+        tick_lower,
+        tick_upper,
+        timestamp: current_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,11 +1,75 @@
 pub mod initialize_honorary_position;
+pub mod initialize_dlmm_position;
+pub mod initialize_whirlpool_position;
+pub mod initialize_raydium_position;
 pub mod initialize_policy;
 pub mod initialize_progress;
 pub mod distribute_fees;
 pub mod update_policy;
+pub mod snapshot_locked;
+pub mod initialize_failed_payouts;
+pub mod retry_failed_payouts;
+pub mod initialize_paid_bitmap;
+pub mod cache_static_accounts;
+pub mod force_finalize_day;
+pub mod acquire_crank_lease;
+pub mod set_locker_test_config;
+pub mod settle_creator_payout;
+pub mod initialize_protocol_config;
+pub mod update_protocol_config;
+pub mod set_policy_authority;
+pub mod set_policy_paused;
+pub mod distribute_fees_multi;
+pub mod reinitialize_vault;
+pub mod initialize_payout_overrides;
+pub mod set_payout_override;
+pub mod initialize_weight_overrides;
+pub mod set_weight_override;
+pub mod amend_policy_targets;
+pub mod initialize_crank_log;
+pub mod claim_rewards;
+pub mod set_network_config;
+pub mod set_expected_pages;
+pub mod withdraw_escrowed_payouts;
+pub mod claw_back_escrowed_payout;
+pub mod publish_oracle_locked;
+pub mod get_distribution_params;
+pub mod fund_ata_reserve;
 
 pub use initialize_honorary_position::*;
+pub use initialize_dlmm_position::*;
+pub use initialize_whirlpool_position::*;
+pub use initialize_raydium_position::*;
 pub use initialize_policy::*;
 pub use initialize_progress::*;
 pub use distribute_fees::*;
 pub use update_policy::*;
+pub use snapshot_locked::*;
+pub use initialize_failed_payouts::*;
+pub use retry_failed_payouts::*;
+pub use initialize_paid_bitmap::*;
+pub use cache_static_accounts::*;
+pub use force_finalize_day::*;
+pub use acquire_crank_lease::*;
+pub use set_locker_test_config::*;
+pub use settle_creator_payout::*;
+pub use initialize_protocol_config::*;
+pub use update_protocol_config::*;
+pub use set_policy_authority::*;
+pub use set_policy_paused::*;
+pub use distribute_fees_multi::*;
+pub use reinitialize_vault::*;
+pub use initialize_payout_overrides::*;
+pub use set_payout_override::*;
+pub use initialize_weight_overrides::*;
+pub use set_weight_override::*;
+pub use amend_policy_targets::*;
+pub use initialize_crank_log::*;
+pub use claim_rewards::*;
+pub use set_network_config::*;
+pub use set_expected_pages::*;
+pub use withdraw_escrowed_payouts::*;
+pub use claw_back_escrowed_payout::*;
+pub use publish_oracle_locked::*;
+pub use get_distribution_params::*;
+pub use fund_ata_reserve::*;
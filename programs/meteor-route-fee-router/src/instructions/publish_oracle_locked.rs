@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{OracleLockedPublished, EVENT_SCHEMA_VERSION},
+    state::{OracleLockedPda, PolicyPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String, investor: Pubkey)]
+pub struct PublishOracleLocked<'info> {
+    /// The policy's lock oracle
+    #[account(address = policy_pda.lock_oracle @ FeeRouterError::InvalidLockOracle)]
+    pub oracle: Signer<'info>,
+
+    /// Pays rent for a first publication
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = OracleLockedPda::LEN,
+        seeds = [vault_seed.as_bytes(), b"oracle_locked", investor.as_ref()],
+        bump
+    )]
+    pub oracle_locked: Account<'info, OracleLockedPda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Publish the amount an investor has locked outside the chain's lockers, as of `as_of_ts`
+///
+/// Read by `LockerKind::Oracle` until it is older than `policy.lock_oracle_max_age_secs`. A later
+/// publication replaces the amount; one older than the stored amount is rejected.
+pub fn handler(
+    ctx: Context<PublishOracleLocked>,
+    vault_seed: String,
+    investor: Pubkey,
+    locked_amount: u64,
+    as_of_ts: u64,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let oracle_locked = &mut ctx.accounts.oracle_locked;
+    require!(
+        as_of_ts <= current_timestamp && as_of_ts >= oracle_locked.as_of_ts,
+        FeeRouterError::InvalidLockOracle
+    );
+
+    oracle_locked.vault_seed = vault_seed.clone();
+    oracle_locked.investor = investor;
+    oracle_locked.locked_amount = locked_amount;
+    oracle_locked.as_of_ts = as_of_ts;
+    oracle_locked.oracle = ctx.accounts.oracle.key();
+    oracle_locked.updated_at = current_timestamp;
+
+    emit_cpi!(OracleLockedPublished {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed,
+        investor,
+        oracle: ctx.accounts.oracle.key(),
+        locked_amount,
+        as_of_ts,
+        timestamp: current_timestamp,
+    });
+
+    msg!("Oracle locked amount for {} set to {} as of {}", investor, locked_amount, as_of_ts);
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{VaultReinitialized, EVENT_SCHEMA_VERSION},
+    state::{PaidBitmapPda, PolicyPda, ProgressPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct ReinitializeVault<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority,
+        constraint = policy_pda.paused @ FeeRouterError::VaultNotReinitializable
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+
+    /// Paid bitmap, cleared so the new generation's first day starts unpaid (required with track_paid_investors)
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"paid_bitmap"],
+        bump
+    )]
+    pub paid_bitmap: Option<Account<'info, PaidBitmapPda>>,
+}
+
+/// Restart a paused vault under the next generation
+///
+/// The vault keeps its seed, policy, position and treasury; page, snapshot and lease state start
+/// over while the last day stays finalized, so the day gate still applies. `ProgressPda.generation`
+/// is bumped, which page hashes cover, so pages from before the restart do not replay and payouts
+/// before and after it stay distinguishable in events. The vault stays paused until the authority resumes it.
+pub fn handler(
+    ctx: Context<ReinitializeVault>,
+    vault_seed: String,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let progress = &mut ctx.accounts.progress_pda;
+    require!(progress.can_reinitialize(), FeeRouterError::VaultNotReinitializable);
+
+    match ctx.accounts.paid_bitmap.as_mut() {
+        Some(bitmap) => {
+            bitmap.clear();
+            bitmap.day_epoch = 0;
+        }
+        None => require!(
+            !ctx.accounts.policy_pda.track_paid_investors,
+            FeeRouterError::MissingRequiredInput
+        ),
+    }
+
+    let generation = progress.reinitialize(current_timestamp)?;
+    let carried_quote = progress
+        .carry_over_lamports
+        .saturating_add(progress.rollover_claimed_quote)
+        .saturating_add(progress.capped_excess_rollover);
+
+    emit_cpi!(VaultReinitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        authority: ctx.accounts.authority.key(),
+        generation,
+        carried_quote,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Vault reinitialized: vault_seed={}, generation={}, carried_quote={}",
+        vault_seed,
+        generation,
+        carried_quote
+    );
+
+    Ok(())
+}
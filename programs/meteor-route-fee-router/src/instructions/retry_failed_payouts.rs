@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    error::FeeRouterError,
+    events::{FailedPayoutSettled, EVENT_SCHEMA_VERSION},
+    instructions::distribute_fees::{quote_ata_status, QuoteAtaStatus},
+    layout::read_pubkey,
+    state::{FailedPayoutsPda, InvestorFeePositionOwnerPda, PolicyPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct RetryFailedPayouts<'info> {
+    /// Crank caller (permissionless)
+    pub crank_caller: Signer<'info>,
+
+    /// Policy configuration
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Position owner PDA (treasury authority)
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
+
+    /// Failed payout ledger
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"failed_payouts"],
+        bump
+    )]
+    pub failed_payouts: Account<'info, FailedPayoutsPda>,
+
+    /// Quote mint (must match policy)
+    #[account(address = policy_pda.quote_mint @ FeeRouterError::InvalidQuoteMint)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Program quote treasury token account at the treasury PDA
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program,
+    )]
+    pub quote_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Settle failed payouts recorded by continue-on-failure mode
+///
+/// remaining_accounts: one quote token account per investor to retry. The investor is read from
+/// the account's owner field; accounts that are still unusable are skipped and stay in the ledger.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RetryFailedPayouts<'info>>,
+    vault_seed: String,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let quote_mint_key = ctx.accounts.quote_mint.key();
+    let seeds = &[
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[ctx.bumps.position_owner_pda],
+    ];
+    let signer = &[&seeds[..]];
+
+    let mut settled_count: u32 = 0;
+    for quote_ata in ctx.remaining_accounts.iter() {
+        let investor = match quote_ata.try_borrow_data() {
+            Ok(data) if data.len() >= 165 => read_pubkey(&data, 32),
+            _ => continue,
+        };
+        if quote_ata_status(quote_ata, &quote_mint_key, &investor) != QuoteAtaStatus::Valid {
+            msg!("Quote account {} still unusable; leaving payout in ledger", quote_ata.key());
+            continue;
+        }
+        let Some(amount) = ctx.accounts.failed_payouts.take(&investor) else {
+            continue;
+        };
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.quote_treasury.to_account_info(),
+                    mint: ctx.accounts.quote_mint.to_account_info(),
+                    to: quote_ata.clone(),
+                    authority: ctx.accounts.position_owner_pda.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            ctx.accounts.quote_mint.decimals,
+        )?;
+        settled_count = settled_count.saturating_add(1);
+
+        emit_cpi!(FailedPayoutSettled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            vault_seed: vault_seed.clone(),
+            quote_mint: quote_mint_key,
+            decimals: ctx.accounts.quote_mint.decimals,
+            investor,
+            quote_ata: quote_ata.key(),
+            amount,
+            total_owed: ctx.accounts.failed_payouts.total_owed,
+            timestamp: current_timestamp,
+        });
+    }
+
+    ctx.accounts.failed_payouts.updated_at = current_timestamp;
+
+    msg!(
+        "Settled {} failed payouts; {} still owed across {} investors",
+        settled_count,
+        ctx.accounts.failed_payouts.total_owed,
+        ctx.accounts.failed_payouts.entries.len()
+    );
+
+    Ok(())
+}
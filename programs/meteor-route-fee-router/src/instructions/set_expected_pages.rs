@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{ExpectedPagesCommitted, EVENT_SCHEMA_VERSION},
+    state::{PolicyPda, ProgressPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct SetExpectedPages<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+}
+
+/// Commit the day's page count and investor count before any payouts
+///
+/// Starts the day if its gate has opened, like `snapshot_locked`. Every page must then fall below
+/// `expected_pages`, the day finalizes once the cursor reaches it, and the pages must cover exactly
+/// `expected_investors` investors (0 leaves the investor count unchecked). A cranker can no longer
+/// close the day early after paying only some of the pages. Both counts apply to this day only.
+pub fn handler(
+    ctx: Context<SetExpectedPages>,
+    vault_seed: String,
+    expected_pages: u64,
+    expected_investors: u32,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let policy_pda = &ctx.accounts.policy_pda;
+    let progress_pda = &mut ctx.accounts.progress_pda;
+
+    if progress_pda.roll_day(policy_pda.day_gate(), current_timestamp, Clock::get()?.slot)? {
+        msg!("Started new distribution day: epoch={}", progress_pda.day_epoch);
+    }
+
+    // The committed pages must be sendable under the policy's page limits
+    if policy_pda.max_pages_per_day > 0 {
+        require!(
+            expected_pages <= policy_pda.max_pages_per_day as u64,
+            FeeRouterError::PageLimitExceeded
+        );
+    }
+    // Unordered pages are tracked in the progress PDA's page bitmap
+    if policy_pda.unordered_pages {
+        require!(
+            expected_pages <= ProgressPda::MAX_TRACKED_PAGES,
+            FeeRouterError::PageLimitExceeded
+        );
+    }
+    progress_pda.commit_expected_pages(expected_pages, expected_investors)?;
+    progress_pda.updated_at = current_timestamp;
+
+    emit_cpi!(ExpectedPagesCommitted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        day_epoch: progress_pda.day_epoch,
+        expected_pages,
+        expected_investors,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Committed day {}: pages={}, investors={}",
+        progress_pda.day_epoch,
+        expected_pages,
+        expected_investors
+    );
+
+    Ok(())
+}
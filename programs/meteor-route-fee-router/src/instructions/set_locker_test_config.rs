@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{LockerTestConfigUpdated, EVENT_SCHEMA_VERSION},
+    program::MeteorRouteFeeRouter,
+    state::LockerTestConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetLockerTestConfig<'info> {
+    /// The program's upgrade authority
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = LockerTestConfig::LEN,
+        seeds = [LockerTestConfig::SEED],
+        bump
+    )]
+    pub locker_test_config: Account<'info, LockerTestConfig>,
+
+    /// This program, for its ProgramData address
+    #[account(constraint = router_program.programdata_address()? == Some(program_data.key()))]
+    pub router_program: Program<'info, MeteorRouteFeeRouter>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Turn the local-testing locker bypass on or off
+///
+/// Only the upgrade authority can write the config, so the bypass can't be enabled on a deployment
+/// by anyone who couldn't already replace the program.
+pub fn handler(
+    ctx: Context<SetLockerTestConfig>,
+    relax_locker_checks: bool,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp as u64;
+    let config = &mut ctx.accounts.locker_test_config;
+    config.relax_locker_checks = relax_locker_checks;
+    config.updated_at = timestamp;
+
+    emit_cpi!(LockerTestConfigUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        authority: ctx.accounts.authority.key(),
+        relax_locker_checks,
+        timestamp,
+    });
+
+    msg!("Locker test config: relax_locker_checks={}", relax_locker_checks);
+
+    Ok(())
+}
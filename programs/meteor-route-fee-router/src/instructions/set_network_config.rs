@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{NetworkConfigUpdated, EVENT_SCHEMA_VERSION},
+    networks::{Network, NetworkPrograms},
+    program::MeteorRouteFeeRouter,
+    state::NetworkConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetNetworkConfig<'info> {
+    /// The program's upgrade authority
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = NetworkConfig::LEN,
+        seeds = [NetworkConfig::SEED],
+        bump
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// This program, for its ProgramData address
+    #[account(constraint = router_program.programdata_address()? == Some(program_data.key()))]
+    pub router_program: Program<'info, MeteorRouteFeeRouter>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Select the cluster this deployment serves
+///
+/// `programs` overrides the network's canonical IDs, for forks and local validators that deploy
+/// Streamflow or CP-AMM elsewhere. Only the upgrade authority can write the config, since it decides
+/// which programs every vault reads lockers from and CPIs into.
+pub fn handler(
+    ctx: Context<SetNetworkConfig>,
+    network: Network,
+    programs: Option<NetworkPrograms>,
+) -> Result<()> {
+    let programs = programs.unwrap_or_else(|| network.programs());
+    require!(programs.is_valid(), FeeRouterError::InvalidNetworkConfig);
+
+    let timestamp = Clock::get()?.unix_timestamp as u64;
+    let config = &mut ctx.accounts.network_config;
+    config.network = network;
+    config.programs = programs;
+    config.updated_at = timestamp;
+
+    emit_cpi!(NetworkConfigUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        authority: ctx.accounts.authority.key(),
+        network,
+        streamflow_program_id: programs.streamflow,
+        cp_amm_program_id: programs.cp_amm,
+        timestamp,
+    });
+
+    msg!(
+        "Network config: network={:?}, streamflow={}, cp_amm={}, canonical={}",
+        network,
+        programs.streamflow,
+        programs.cp_amm,
+        programs.is_canonical()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{PayoutOverrideSet, EVENT_SCHEMA_VERSION},
+    state::{PayoutOverridesPda, PolicyPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct SetPayoutOverride<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"payout_overrides"],
+        bump
+    )]
+    pub payout_overrides: Account<'info, PayoutOverridesPda>,
+}
+
+/// Set or remove (`None`) one investor's minimum payout
+///
+/// The override replaces `min_payout_lamports` for that investor from the next page on, in either
+/// direction: a large holder can be paid smaller amounts, or held back until payouts are worth it.
+pub fn handler(
+    ctx: Context<SetPayoutOverride>,
+    vault_seed: String,
+    investor: Pubkey,
+    min_payout_lamports: Option<u64>,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let payout_overrides = &mut ctx.accounts.payout_overrides;
+    payout_overrides.set(&investor, min_payout_lamports)?;
+    payout_overrides.updated_at = current_timestamp;
+
+    emit_cpi!(PayoutOverrideSet {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        investor,
+        min_payout_lamports,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Payout override for {} set to {:?} ({} overrides)",
+        investor,
+        min_payout_lamports,
+        payout_overrides.entries.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{PolicyAuthorityChanged, EVENT_SCHEMA_VERSION},
+    governance::{governance_signs_for, is_governance_account},
+    state::PolicyPda,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct SetPolicyAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Realms governance the authority is handed to, checked so a mistyped key can't orphan the vault
+    /// CHECK: Owner and account type are verified in the handler
+    pub governance: Option<UncheckedAccount<'info>>,
+}
+
+/// Hand the policy authority to `new_authority`
+///
+/// With `governance` passed, `new_authority` must be that governance account or its native
+/// treasury, the PDAs Realms signs with when it executes a proposal transaction. Those signatures
+/// satisfy the `authority` signer of `update_policy`, `set_policy_paused` and this instruction.
+pub fn handler(
+    ctx: Context<SetPolicyAuthority>,
+    vault_seed: String,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let governance = match ctx.accounts.governance.as_ref() {
+        Some(governance) => {
+            require!(
+                is_governance_account(governance.owner, &governance.try_borrow_data()?)
+                    && governance_signs_for(governance.key, &new_authority),
+                FeeRouterError::InvalidGovernance
+            );
+            Some(governance.key())
+        }
+        None => None,
+    };
+
+    let policy_pda = &mut ctx.accounts.policy_pda;
+    let previous_authority = policy_pda.authority;
+    policy_pda.authority = new_authority;
+    policy_pda.updated_at = current_timestamp;
+
+    emit_cpi!(PolicyAuthorityChanged {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        previous_authority,
+        new_authority,
+        governance,
+        timestamp: current_timestamp,
+    });
+
+    msg!("Policy authority moved from {} to {}", previous_authority, new_authority);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{PolicyPausedSet, EVENT_SCHEMA_VERSION},
+    state::PolicyPda,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct SetPolicyPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+}
+
+/// Pause or resume the vault's distribution crank
+///
+/// While paused, `distribute_fees` and `force_finalize_day` fail with `PolicyPaused`; fees keep
+/// accruing in the position and the open day resumes where it stopped.
+pub fn handler(
+    ctx: Context<SetPolicyPaused>,
+    vault_seed: String,
+    paused: bool,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let policy_pda = &mut ctx.accounts.policy_pda;
+    policy_pda.paused = paused;
+    policy_pda.updated_at = current_timestamp;
+
+    emit_cpi!(PolicyPausedSet {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        authority: policy_pda.authority,
+        paused,
+        timestamp: current_timestamp,
+    });
+
+    msg!("Policy {}", if paused { "paused" } else { "resumed" });
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{WeightOverrideSet, EVENT_SCHEMA_VERSION},
+    state::{PolicyPda, ProgressPda, WeightOverridesPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct SetWeightOverride<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump,
+        has_one = authority
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Checked for an open day, whose snapshot already holds the current weights
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump,
+        constraint = !progress_pda.day_open() @ FeeRouterError::WeightOverridesLocked
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"weight_overrides"],
+        bump
+    )]
+    pub weight_overrides: Account<'info, WeightOverridesPda>,
+}
+
+/// Set or remove (`None`) one investor's fixed weight
+///
+/// The weight counts as locked tokens on top of the investor's stream, from the next day's snapshot
+/// on, so partners locked outside a supported locker can share in the investor fees.
+pub fn handler(
+    ctx: Context<SetWeightOverride>,
+    vault_seed: String,
+    investor: Pubkey,
+    weight: Option<u64>,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let weight_overrides = &mut ctx.accounts.weight_overrides;
+    weight_overrides.set(&investor, weight)?;
+    weight_overrides.updated_at = current_timestamp;
+
+    emit_cpi!(WeightOverrideSet {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        investor,
+        weight,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Weight override for {} set to {:?} ({} overrides)",
+        investor,
+        weight,
+        weight_overrides.entries.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    error::FeeRouterError,
+    events::{CreatorPayoutSettled, EVENT_SCHEMA_VERSION},
+    instructions::distribute_fees::token_account_unencumbered,
+    state::{InvestorFeePositionOwnerPda, PolicyPda, ProgressPda},
+    transfer_fee::transfer_fee_for,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct SettleCreatorPayout<'info> {
+    /// Crank caller (permissionless)
+    pub crank_caller: Signer<'info>,
+
+    /// Policy configuration
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Progress tracking
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+
+    /// Position owner PDA (treasury authority)
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
+
+    /// Quote mint (must match policy)
+    #[account(address = policy_pda.quote_mint @ FeeRouterError::InvalidQuoteMint)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Program quote treasury token account at the treasury PDA
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        constraint = token_account_unencumbered(&quote_treasury) @ FeeRouterError::InvalidTreasury,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program,
+    )]
+    pub quote_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator quote ATA receiving the owed remainder
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::token_program = token_program,
+    )]
+    pub creator_quote_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Deliver creator remainders that finalize deferred because the creator account was unusable
+///
+/// The whole `ProgressPda.creator_owed` balance is paid as quote tokens, also when the policy
+/// unwraps creator payouts to SOL.
+pub fn handler(
+    ctx: Context<SettleCreatorPayout>,
+    vault_seed: String,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    // Never pay out more than the treasury holds
+    let amount = ctx
+        .accounts
+        .progress_pda
+        .creator_owed
+        .min(ctx.accounts.quote_treasury.amount);
+    require!(amount > 0, FeeRouterError::NoCreatorPayoutOwed);
+
+    let seeds = &[
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[ctx.bumps.position_owner_pda],
+    ];
+    let signer = &[&seeds[..]];
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.quote_treasury.to_account_info(),
+                mint: ctx.accounts.quote_mint.to_account_info(),
+                to: ctx.accounts.creator_quote_ata.to_account_info(),
+                authority: ctx.accounts.position_owner_pda.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        ctx.accounts.quote_mint.decimals,
+    )?;
+    let transfer_fee = transfer_fee_for(&ctx.accounts.quote_mint.to_account_info(), amount)?;
+
+    let progress_pda = &mut ctx.accounts.progress_pda;
+    progress_pda.creator_owed = progress_pda.creator_owed.saturating_sub(amount);
+    progress_pda.updated_at = current_timestamp;
+
+    emit_cpi!(CreatorPayoutSettled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        decimals: ctx.accounts.quote_mint.decimals,
+        creator_quote_ata: ctx.accounts.creator_quote_ata.key(),
+        amount,
+        transfer_fee,
+        creator_owed: progress_pda.creator_owed,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Settled creator payout {} to {}; {} still owed",
+        amount,
+        ctx.accounts.creator_quote_ata.key(),
+        progress_pda.creator_owed
+    );
+
+    Ok(())
+}
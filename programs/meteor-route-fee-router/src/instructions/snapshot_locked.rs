@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::FeeRouterError,
+    events::{LockedSnapshotTaken, EVENT_SCHEMA_VERSION},
+    state::{LockerTestConfig, NetworkConfig, PolicyPda, ProgressPda, WeightOverridesPda},
+    locker::{LockerSources, StreamLocked},
+    InvestorData,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String)]
+pub struct SnapshotLocked<'info> {
+    /// Crank caller (permissionless)
+    pub crank_caller: Signer<'info>,
+
+    /// Policy configuration
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Progress tracking
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"progress"],
+        bump
+    )]
+    pub progress_pda: Account<'info, ProgressPda>,
+
+    /// Local-testing locker bypass; omitted on real clusters
+    #[account(seeds = [LockerTestConfig::SEED], bump)]
+    pub locker_test_config: Option<Account<'info, LockerTestConfig>>,
+
+    /// Program-wide cluster selection; while uninitialized lockers are read at Streamflow's mainnet ID
+    /// CHECK: Seeds checked; deserialized with NetworkConfig::load
+    #[account(seeds = [NetworkConfig::SEED], bump)]
+    pub network_config: UncheckedAccount<'info>,
+
+    /// Fixed investor weights, required when policy.weight_overrides is set
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"weight_overrides"],
+        bump
+    )]
+    pub weight_overrides: Option<Account<'info, WeightOverridesPda>>,
+}
+
+/// Record the day's locked amounts before any payouts
+///
+/// remaining_accounts: one vesting (Streamflow or Bonfida) account per entry in `investors`, in order.
+/// The snapshot may span several calls; the last one passes `is_final_chunk = true`. Stream keys
+/// ascend strictly across every chunk of the day. Once the authority has committed an investor
+/// count with `set_expected_pages`, the snapshot only completes holding exactly that many, so the
+/// first cranker of the day can't choose who is paid; without a commitment anyone may complete it.
+pub fn handler(
+    ctx: Context<SnapshotLocked>,
+    vault_seed: String,
+    investors: Vec<InvestorData>,
+    is_final_chunk: bool,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let progress_pda = &mut ctx.accounts.progress_pda;
+
+    // The snapshot is the first crank of the day
+    if progress_pda.roll_day(ctx.accounts.policy_pda.day_gate(), current_timestamp, Clock::get()?.slot)? {
+        msg!("Started new distribution day: epoch={}", progress_pda.day_epoch);
+    }
+
+    if progress_pda.day_finalized_flag {
+        return err!(FeeRouterError::DayAlreadyFinalized);
+    }
+    if progress_pda.snapshot_complete {
+        return err!(FeeRouterError::SnapshotAlreadyComplete);
+    }
+    // Payouts already made today were computed without a snapshot
+    require!(
+        progress_pda.first_page_pending(),
+        FeeRouterError::InvalidPaginationState
+    );
+
+    require!(
+        ctx.remaining_accounts.len() == investors.len(),
+        FeeRouterError::RemainingAccountsLayoutInvalid
+    );
+
+    // All chunks evaluate locked amounts at the same instant
+    if progress_pda.snapshot_ts == 0 {
+        progress_pda.snapshot_ts = current_timestamp;
+    }
+    let snapshot_ts = progress_pda.snapshot_ts;
+    let relax_checks = ctx
+        .accounts
+        .locker_test_config
+        .as_ref()
+        .is_some_and(|config| config.relax_locker_checks);
+    let streamflow_program_id = NetworkConfig::streamflow_program_id(NetworkConfig::load(&ctx.accounts.network_config)?.as_ref());
+    let lockers = LockerSources::from_policy(&ctx.accounts.policy_pda, streamflow_program_id)
+        .with_relaxed_checks(relax_checks);
+    let weight_overrides = if ctx.accounts.policy_pda.weight_overrides {
+        Some(
+            ctx.accounts
+                .weight_overrides
+                .as_deref()
+                .ok_or(FeeRouterError::MissingRequiredInput)?,
+        )
+    } else {
+        None
+    };
+
+    for (investor_data, stream_account_info) in investors.iter().zip(ctx.remaining_accounts.iter()) {
+        let weight = weight_overrides.map_or(0, |table| table.weight_for(&investor_data.investor));
+        let locked_amount = match lockers
+            .read_investor_locked(stream_account_info, investor_data, snapshot_ts)?
+            .with_weight(weight)?
+        {
+            StreamLocked::Locked(amount) => amount,
+            StreamLocked::Ignored(_) => 0,
+        };
+        progress_pda.add_snapshot_entry(&investor_data.stream, &investor_data.investor, locked_amount)?;
+    }
+
+    if is_final_chunk {
+        progress_pda.complete_snapshot(ctx.accounts.policy_pda.max_weight_bps)?;
+    }
+    progress_pda.updated_at = current_timestamp;
+
+    emit_cpi!(LockedSnapshotTaken {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        day_epoch: progress_pda.day_epoch,
+        snapshot_ts,
+        total_locked: progress_pda.snapshot_total_locked,
+        investor_count: progress_pda.snapshot_investor_count,
+        locked_hash: progress_pda.snapshot_locked_hash,
+        complete: progress_pda.snapshot_complete,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Locked snapshot: epoch={}, investors={}, total_locked={}, complete={}",
+        progress_pda.day_epoch,
+        progress_pda.snapshot_investor_count,
+        progress_pda.snapshot_total_locked,
+        progress_pda.snapshot_complete
+    );
+
+    Ok(())
+}
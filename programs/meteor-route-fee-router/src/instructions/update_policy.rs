@@ -1,15 +1,19 @@
 use anchor_lang::prelude::*;
 
 use crate::{
+    attestation::AttestationLayout,
+    cp_amm_entry_points::CpAmmEntryPoints,
     error::FeeRouterError,
-    events::PolicyUpdated,
-    state::PolicyPda,
+    events::{PolicyUpdated, EVENT_SCHEMA_VERSION},
+    locker::{LockerEntry, LockerKind},
+    stake::StakeLayout,
+    state::{InvestorFeePositionOwnerPda, PolicyPda, PoolKind, RewardRouting, ScheduledParams, UnattestedRouting},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vault_seed: String)]
 pub struct UpdatePolicy<'info> {
-    #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
@@ -19,22 +23,94 @@ pub struct UpdatePolicy<'info> {
         has_one = authority
     )]
     pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Created by every position initializer; holds the backend a live position was opened in
+    /// CHECK: Read through `InvestorFeePositionOwnerPda::opened_pool_kind`
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: UncheckedAccount<'info>,
 }
 
+/// Arguments of `update_policy`; `None` leaves the field unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct UpdatePolicyArgs {
+    pub investor_fee_share_bps: Option<u16>,
+    pub daily_cap_quote_lamports: Option<u64>,
+    pub min_payout_lamports: Option<u64>,
+    pub policy_fund_missing_ata: Option<bool>,
+    pub day_gate_grace_secs: Option<u64>,
+    pub min_daily_claim_lamports: Option<u64>,
+    pub roll_capped_excess: Option<bool>,
+    pub bonfida_program_id: Option<Pubkey>,
+    pub locker_kind: Option<LockerKind>,
+    pub locker_allowlist: Option<Vec<LockerEntry>>,
+    pub pool_kind: Option<PoolKind>,
+    pub quote_only_buffer_ticks: Option<u32>,
+    pub payout_memos: Option<bool>,
+    pub unwrap_wsol_creator: Option<bool>,
+    pub continue_on_failure: Option<bool>,
+    pub track_paid_investors: Option<bool>,
+    pub force_finalize_after_secs: Option<u64>,
+    pub crank_lease_slots: Option<u64>,
+    pub batch_investor_paid_events: Option<bool>,
+    pub creator_vesting_secs: Option<u64>,
+    pub compound_bps: Option<u16>,
+    pub buyback_burn_creator: Option<bool>,
+    pub buyback_max_slippage_bps: Option<u16>,
+    pub referral_wallet: Option<Pubkey>,
+    pub referral_bps: Option<u16>,
+    pub stake_program_id: Option<Pubkey>,
+    pub stake_layout: Option<StakeLayout>,
+    pub day_gate_slots: Option<u64>,
+    /// `Some(None)` returns the vault to the 24h gate
+    pub epoch_offset_secs: Option<Option<u32>>,
+    pub tranches_per_day: Option<u8>,
+    pub max_weight_bps: Option<u16>,
+    pub max_investors_per_page: Option<u16>,
+    pub max_pages_per_day: Option<u32>,
+    /// `Pubkey::default()` turns the attestation gate off
+    pub attestation_program: Option<Pubkey>,
+    pub attestation_layout: Option<AttestationLayout>,
+    pub reward_routing: Option<RewardRouting>,
+    pub cp_amm_entry_points: Option<CpAmmEntryPoints>,
+    pub min_tick_distance_from_price: Option<u32>,
+    pub cap_carry_days: Option<u8>,
+    pub payout_to_stream_escrow: Option<bool>,
+    /// 0 pays investors directly
+    pub payout_dispute_window_secs: Option<u64>,
+    pub fund_missing_creator_ata: Option<bool>,
+    /// `Pubkey::default()` turns the post-finalize hook off
+    pub hook_program: Option<Pubkey>,
+    pub lock_oracle: Option<Pubkey>,
+    pub lock_oracle_max_age_secs: Option<u64>,
+    pub unordered_pages: Option<bool>,
+    /// Replaces the whole schedule; `Some(vec![])` clears it
+    pub param_schedule: Option<Vec<ScheduledParams>>,
+    pub unattested_routing: Option<UnattestedRouting>,
+}
+
+/// Apply every field set in `args`, validating each, and emit `PolicyUpdated` when any changed
 pub fn handler(
     ctx: Context<UpdatePolicy>,
     vault_seed: String,
-    new_investor_fee_share_bps: Option<u16>,
-    new_daily_cap_quote_lamports: Option<u64>,
-    new_min_payout_lamports: Option<u64>,
-    new_policy_fund_missing_ata: Option<bool>,
+    args: UpdatePolicyArgs,
 ) -> Result<()> {
     let policy_pda = &mut ctx.accounts.policy_pda;
     let current_timestamp = Clock::get()?.unix_timestamp as u64;
     let mut updated = false;
 
+    // Scheduled values already in effect become the policy's own, so a replaced schedule can't
+    // take them back and explicit updates below override them
+    let current_epoch = policy_pda.day_gate().epoch_at(current_timestamp);
+    if policy_pda.apply_due_params(current_epoch) {
+        updated = true;
+        msg!("Applied scheduled params due by day {}", current_epoch);
+    }
+
     // Update investor fee share if provided
-    if let Some(fee_share_bps) = new_investor_fee_share_bps {
+    if let Some(fee_share_bps) = args.investor_fee_share_bps {
         if fee_share_bps > 10000 {
             return err!(FeeRouterError::InvalidFeeShareBps);
         }
@@ -44,36 +120,528 @@ pub fn handler(
     }
 
     // Update daily cap if provided
-    if let Some(daily_cap) = new_daily_cap_quote_lamports {
+    if let Some(daily_cap) = args.daily_cap_quote_lamports {
         policy_pda.daily_cap_quote_lamports = daily_cap;
         updated = true;
         msg!("Updated daily_cap_quote_lamports to {}", daily_cap);
     }
 
+    // Update how many days of unused cap may be banked if provided (0 drops the bank on the next day)
+    if let Some(cap_carry_days) = args.cap_carry_days {
+        if cap_carry_days > PolicyPda::MAX_CAP_CARRY_DAYS {
+            return err!(FeeRouterError::InvalidCapCarryDays);
+        }
+        policy_pda.cap_carry_days = cap_carry_days;
+        updated = true;
+        msg!("Updated cap_carry_days to {}", cap_carry_days);
+    }
+
     // Update minimum payout if provided
-    if let Some(min_payout) = new_min_payout_lamports {
+    if let Some(min_payout) = args.min_payout_lamports {
         policy_pda.min_payout_lamports = min_payout;
         updated = true;
         msg!("Updated min_payout_lamports to {}", min_payout);
     }
 
     // Update ATA funding policy if provided
-    if let Some(fund_missing_ata) = new_policy_fund_missing_ata {
+    if let Some(fund_missing_ata) = args.policy_fund_missing_ata {
         policy_pda.policy_fund_missing_ata = fund_missing_ata;
         updated = true;
         msg!("Updated policy_fund_missing_ata to {}", fund_missing_ata);
     }
 
+    // Toggle creating a missing creator quote ATA at finalize if provided
+    if let Some(fund_missing_creator_ata) = args.fund_missing_creator_ata {
+        policy_pda.fund_missing_creator_ata = fund_missing_creator_ata;
+        updated = true;
+        msg!("Updated fund_missing_creator_ata to {}", fund_missing_creator_ata);
+    }
+
+    // Update the post-finalize hook if provided (default pubkey disables it)
+    if let Some(hook_program) = args.hook_program {
+        require_keys_neq!(hook_program, crate::ID, FeeRouterError::InvalidHookProgram);
+        policy_pda.hook_program = hook_program;
+        updated = true;
+        msg!("Updated hook_program to {}", hook_program);
+    }
+
+    // Update day gate grace period if provided
+    if let Some(grace_secs) = args.day_gate_grace_secs {
+        if grace_secs > PolicyPda::MAX_DAY_GATE_GRACE_SECS {
+            return err!(FeeRouterError::InvalidDayGateGrace);
+        }
+        policy_pda.day_gate_grace_secs = grace_secs;
+        updated = true;
+        msg!("Updated day_gate_grace_secs to {}", grace_secs);
+    }
+
+    // Update the slot day gate if provided (0 returns to the timestamp gate)
+    if let Some(gate_slots) = args.day_gate_slots {
+        if gate_slots != 0
+            && !(PolicyPda::MIN_DAY_GATE_SLOTS..=PolicyPda::MAX_DAY_GATE_SLOTS).contains(&gate_slots)
+        {
+            return err!(FeeRouterError::InvalidDayGateSlots);
+        }
+        policy_pda.day_gate_slots = gate_slots;
+        updated = true;
+        msg!("Updated day_gate_slots to {}", gate_slots);
+    }
+
+    // Update the day alignment if provided (Some(None) returns to the 24h gate)
+    if let Some(epoch_offset_secs) = args.epoch_offset_secs {
+        if epoch_offset_secs.is_some_and(|offset| offset >= 86_400) {
+            return err!(FeeRouterError::InvalidEpochOffset);
+        }
+        policy_pda.epoch_offset_secs = epoch_offset_secs;
+        updated = true;
+        msg!("Updated epoch_offset_secs to {:?}", epoch_offset_secs);
+    }
+
+    // Update tranches per day if provided. Tranche epochs count faster than days, so lowering it
+    // on a live vault would hold new days back until the count catches up
+    if let Some(tranches) = args.tranches_per_day {
+        if tranches == 0
+            || tranches > PolicyPda::MAX_TRANCHES_PER_DAY
+            || 86_400 % tranches as u32 != 0
+            || (tranches < policy_pda.tranches_per_day.max(1) && !policy_pda.paused)
+        {
+            return err!(FeeRouterError::InvalidTranchesPerDay);
+        }
+        policy_pda.tranches_per_day = tranches;
+        updated = true;
+        msg!("Updated tranches_per_day to {}", tranches);
+    }
+
+    // Update the per-investor share cap if provided; a day already snapshotted keeps its cap
+    if let Some(max_weight_bps) = args.max_weight_bps {
+        if max_weight_bps != 0
+            && !(PolicyPda::MIN_MAX_WEIGHT_BPS..=10_000).contains(&max_weight_bps)
+        {
+            return err!(FeeRouterError::InvalidMaxWeightBps);
+        }
+        policy_pda.max_weight_bps = max_weight_bps;
+        updated = true;
+        msg!("Updated max_weight_bps to {}", max_weight_bps);
+    }
+
+    // Update the page shape limits if provided; pages of an open day are checked against the new ones
+    if let Some(max_investors) = args.max_investors_per_page {
+        policy_pda.max_investors_per_page = max_investors;
+        updated = true;
+        msg!("Updated max_investors_per_page to {}", max_investors);
+    }
+    if let Some(max_pages) = args.max_pages_per_day {
+        policy_pda.max_pages_per_day = max_pages;
+        updated = true;
+        msg!("Updated max_pages_per_day to {}", max_pages);
+    }
+
+    // Update the compliance attestation gate if provided; Pubkey::default() turns it off
+    if let Some(attestation_program) = args.attestation_program {
+        policy_pda.attestation_program = attestation_program;
+        updated = true;
+        msg!("Updated attestation_program to {}", attestation_program);
+    }
+    if let Some(attestation_layout) = args.attestation_layout {
+        policy_pda.attestation_layout = attestation_layout;
+        updated = true;
+        msg!(
+            "Updated attestation_layout (expiry at {:?})",
+            attestation_layout.expiry_offset
+        );
+    }
+
+    // Update reward routing if provided; a creator claim abandons an open investor reward round
+    if let Some(reward_routing) = args.reward_routing {
+        policy_pda.reward_routing = reward_routing;
+        updated = true;
+        msg!("Updated reward_routing to {:?}", reward_routing);
+    }
+
+    // Update the CP-AMM interface if provided, e.g. after Meteora moves an entry point
+    if let Some(cp_amm_entry_points) = args.cp_amm_entry_points {
+        require!(cp_amm_entry_points.is_valid(), FeeRouterError::InvalidCpAmmEntryPoints);
+        policy_pda.cp_amm_entry_points = cp_amm_entry_points;
+        updated = true;
+        msg!("Updated cp_amm_entry_points to {:?}", cp_amm_entry_points);
+    }
+
+    // Update minimum daily claim threshold if provided
+    if let Some(min_daily_claim) = args.min_daily_claim_lamports {
+        policy_pda.min_daily_claim_lamports = min_daily_claim;
+        updated = true;
+        msg!("Updated min_daily_claim_lamports to {}", min_daily_claim);
+    }
+
+    // Update capped-excess routing mode if provided
+    if let Some(roll_capped_excess) = args.roll_capped_excess {
+        policy_pda.roll_capped_excess = roll_capped_excess;
+        updated = true;
+        msg!("Updated roll_capped_excess to {}", roll_capped_excess);
+    }
+
+    // Update Bonfida token-vesting program if provided (default pubkey disables it)
+    if let Some(bonfida_program_id) = args.bonfida_program_id {
+        policy_pda.bonfida_program_id = bonfida_program_id;
+        updated = true;
+        msg!("Updated bonfida_program_id to {}", bonfida_program_id);
+    }
+
+    // Update the lock oracle if provided (default pubkey disables it)
+    if let Some(lock_oracle) = args.lock_oracle {
+        policy_pda.lock_oracle = lock_oracle;
+        updated = true;
+        msg!("Updated lock_oracle to {}", lock_oracle);
+    }
+    if let Some(max_age_secs) = args.lock_oracle_max_age_secs {
+        if max_age_secs > PolicyPda::MAX_LOCK_ORACLE_MAX_AGE_SECS {
+            return err!(FeeRouterError::InvalidLockerConfig);
+        }
+        policy_pda.lock_oracle_max_age_secs = max_age_secs;
+        updated = true;
+        msg!("Updated lock_oracle_max_age_secs to {}", max_age_secs);
+    }
+    if let Some(unordered_pages) = args.unordered_pages {
+        policy_pda.unordered_pages = unordered_pages;
+        updated = true;
+        msg!("Updated unordered_pages to {}", unordered_pages);
+    }
+
+    // Replace the future-dated daily cap / min payout values if provided (empty clears them)
+    if let Some(param_schedule) = args.param_schedule {
+        require!(
+            ScheduledParams::is_valid_schedule(&param_schedule, current_epoch),
+            FeeRouterError::InvalidParamSchedule
+        );
+        msg!("Updated param_schedule ({} entries)", param_schedule.len());
+        policy_pda.param_schedule = param_schedule;
+        updated = true;
+    }
+
+    // Update where unattested investors' shares go if provided
+    if let Some(unattested_routing) = args.unattested_routing {
+        policy_pda.unattested_routing = unattested_routing;
+        updated = true;
+        msg!("Updated unattested_routing to {:?}", unattested_routing);
+    }
+
+    // Update the staking program if provided (default pubkey disables it)
+    if let Some(stake_program_id) = args.stake_program_id {
+        policy_pda.stake_program_id = stake_program_id;
+        updated = true;
+        msg!("Updated stake_program_id to {}", stake_program_id);
+    }
+
+    // Update the stake account layout if provided
+    if let Some(stake_layout) = args.stake_layout {
+        policy_pda.stake_layout = stake_layout;
+        updated = true;
+        msg!(
+            "Updated stake_layout (staker at {}, amount at {})",
+            stake_layout.staker_offset,
+            stake_layout.amount_offset
+        );
+    }
+
+    // Update the vault's vesting provider if provided
+    if let Some(locker_kind) = args.locker_kind {
+        policy_pda.locker_kind = locker_kind;
+        updated = true;
+        msg!("Updated locker_kind to {:?}", locker_kind);
+    }
+
+    // Replace the locker allowlist if provided (empty = locker_kind only)
+    if let Some(locker_allowlist) = args.locker_allowlist {
+        require!(
+            locker_allowlist.len() <= PolicyPda::MAX_LOCKERS,
+            FeeRouterError::InvalidLockerConfig
+        );
+        for (i, entry) in locker_allowlist.iter().enumerate() {
+            // Oracle amounts live in the router's own OracleLockedPda accounts
+            require!(
+                entry.program_id != Pubkey::default()
+                    && !locker_allowlist[..i].iter().any(|prev| prev.program_id == entry.program_id)
+                    && (entry.kind == LockerKind::Oracle) == (entry.program_id == crate::ID),
+                FeeRouterError::InvalidLockerConfig
+            );
+        }
+        msg!("Updated locker_allowlist ({} entries)", locker_allowlist.len());
+        policy_pda.locker_allowlist = locker_allowlist;
+        updated = true;
+    }
+
+    // Select the AMM backend for the honorary position if provided; a position that
+    // already exists keeps the backend it was opened in
+    if let Some(pool_kind) = args.pool_kind {
+        policy_pda.pool_kind = pool_kind;
+        updated = true;
+        msg!("Updated pool_kind to {:?}", pool_kind);
+    }
+
+    // Update the quote-only price buffer if provided (applies to positions opened afterwards)
+    if let Some(buffer_ticks) = args.quote_only_buffer_ticks {
+        if buffer_ticks > PolicyPda::MAX_QUOTE_ONLY_BUFFER_TICKS {
+            return err!(FeeRouterError::InvalidTickRange);
+        }
+        policy_pda.quote_only_buffer_ticks = buffer_ticks;
+        updated = true;
+        msg!("Updated quote_only_buffer_ticks to {}", buffer_ticks);
+    }
+
+    // Update the live price margin if provided (checked at init and on every CP-AMM claim)
+    if let Some(distance) = args.min_tick_distance_from_price {
+        if distance > PolicyPda::MAX_QUOTE_ONLY_BUFFER_TICKS {
+            return err!(FeeRouterError::InvalidTickRange);
+        }
+        policy_pda.min_tick_distance_from_price = distance;
+        updated = true;
+        msg!("Updated min_tick_distance_from_price to {}", distance);
+    }
+
+    // Toggle payout memos if provided (each memo costs extra CU per transfer)
+    if let Some(payout_memos) = args.payout_memos {
+        policy_pda.payout_memos = payout_memos;
+        updated = true;
+        msg!("Updated payout_memos to {}", payout_memos);
+    }
+
+    // Toggle native SOL delivery of the creator payout if provided (wSOL quote only)
+    if let Some(unwrap_wsol_creator) = args.unwrap_wsol_creator {
+        if unwrap_wsol_creator && !policy_pda.quote_is_native_mint() {
+            return err!(FeeRouterError::InvalidWsolUnwrap);
+        }
+        policy_pda.unwrap_wsol_creator = unwrap_wsol_creator;
+        updated = true;
+        msg!("Updated unwrap_wsol_creator to {}", unwrap_wsol_creator);
+    }
+
+    // Toggle continue-on-failure payouts if provided (needs initialize_failed_payouts first)
+    if let Some(continue_on_failure) = args.continue_on_failure {
+        policy_pda.continue_on_failure = continue_on_failure;
+        updated = true;
+        msg!("Updated continue_on_failure to {}", continue_on_failure);
+    }
+
+    // Toggle paying investors into their Streamflow escrow if provided
+    if let Some(payout_to_stream_escrow) = args.payout_to_stream_escrow {
+        policy_pda.payout_to_stream_escrow = payout_to_stream_escrow;
+        updated = true;
+        msg!("Updated payout_to_stream_escrow to {}", payout_to_stream_escrow);
+    }
+
+    // Update how long payouts wait in escrow, open to claw-back, if provided (0 pays investors directly)
+    if let Some(window_secs) = args.payout_dispute_window_secs {
+        if window_secs > PolicyPda::MAX_PAYOUT_DISPUTE_WINDOW_SECS {
+            return err!(FeeRouterError::InvalidDisputeWindow);
+        }
+        policy_pda.payout_dispute_window_secs = window_secs;
+        updated = true;
+        msg!("Updated payout_dispute_window_secs to {}", window_secs);
+    }
+
+    // Toggle the paid bitmap check if provided (needs initialize_paid_bitmap first)
+    if let Some(track_paid_investors) = args.track_paid_investors {
+        policy_pda.track_paid_investors = track_paid_investors;
+        updated = true;
+        msg!("Updated track_paid_investors to {}", track_paid_investors);
+    }
+
+    // Update the stall timeout for force_finalize_day if provided (0 = off)
+    if let Some(timeout_secs) = args.force_finalize_after_secs {
+        if timeout_secs > PolicyPda::MAX_FORCE_FINALIZE_AFTER_SECS {
+            return err!(FeeRouterError::InvalidForceFinalizeTimeout);
+        }
+        policy_pda.force_finalize_after_secs = timeout_secs;
+        updated = true;
+        msg!("Updated force_finalize_after_secs to {}", timeout_secs);
+    }
+
+    // Update the crank lease length if provided (0 = leases off; a live lease runs out as granted)
+    if let Some(lease_slots) = args.crank_lease_slots {
+        if lease_slots > PolicyPda::MAX_CRANK_LEASE_SLOTS {
+            return err!(FeeRouterError::InvalidCrankLeaseSlots);
+        }
+        policy_pda.crank_lease_slots = lease_slots;
+        updated = true;
+        msg!("Updated crank_lease_slots to {}", lease_slots);
+    }
+
+    // Switch between per-payout and per-page InvestorPaid events if provided
+    if let Some(batch_investor_paid_events) = args.batch_investor_paid_events {
+        policy_pda.batch_investor_paid_events = batch_investor_paid_events;
+        updated = true;
+        msg!("Updated batch_investor_paid_events to {}", batch_investor_paid_events);
+    }
+
+    // Update the creator payout vesting period if provided (0 = plain transfer)
+    if let Some(vesting_secs) = args.creator_vesting_secs {
+        if vesting_secs > PolicyPda::MAX_CREATOR_VESTING_SECS {
+            return err!(FeeRouterError::InvalidCreatorStream);
+        }
+        policy_pda.creator_vesting_secs = vesting_secs;
+        updated = true;
+        msg!("Updated creator_vesting_secs to {}", vesting_secs);
+    }
+
+    // Update the share of each claim reinvested as position liquidity if provided
+    if let Some(compound_bps) = args.compound_bps {
+        if compound_bps > 10000 {
+            return err!(FeeRouterError::InvalidCompoundBps);
+        }
+        policy_pda.compound_bps = compound_bps;
+        updated = true;
+        msg!("Updated compound_bps to {}", compound_bps);
+    }
+
+    // Compounding and buybacks go through the CP-AMM pool, so they need a CP-AMM position: the one
+    // already opened, or otherwise the backend the next one will be opened in
+    let position_kind = InvestorFeePositionOwnerPda::opened_pool_kind(&ctx.accounts.position_owner_pda)?
+        .unwrap_or(policy_pda.pool_kind);
+
+    // Compounding adds liquidity through CP-AMM, so other backends can't enable it
+    if policy_pda.compound_bps > 0 && position_kind != PoolKind::CpAmm {
+        return err!(FeeRouterError::InvalidCompoundBps);
+    }
+
+    // Switch buying back and burning base with the creator remainder if provided
+    if let Some(buyback_burn_creator) = args.buyback_burn_creator {
+        policy_pda.buyback_burn_creator = buyback_burn_creator;
+        updated = true;
+        msg!("Updated buyback_burn_creator to {}", buyback_burn_creator);
+    }
+
+    // Update the buyback slippage tolerance if provided
+    if let Some(slippage_bps) = args.buyback_max_slippage_bps {
+        if slippage_bps > 10000 {
+            return err!(FeeRouterError::InvalidBuyback);
+        }
+        policy_pda.buyback_max_slippage_bps = slippage_bps;
+        updated = true;
+        msg!("Updated buyback_max_slippage_bps to {}", slippage_bps);
+    }
+
+    // Update the referral recipient if provided (default pubkey removes it)
+    if let Some(referral_wallet) = args.referral_wallet {
+        policy_pda.referral_wallet = referral_wallet;
+        updated = true;
+        msg!("Updated referral_wallet to {}", referral_wallet);
+    }
+
+    // Update the referral share of the creator remainder if provided
+    if let Some(referral_bps) = args.referral_bps {
+        if referral_bps > 10000 {
+            return err!(FeeRouterError::InvalidReferral);
+        }
+        policy_pda.referral_bps = referral_bps;
+        updated = true;
+        msg!("Updated referral_bps to {}", referral_bps);
+    }
+
+    // A referral share needs somewhere to go
+    if policy_pda.referral_bps > 0 && policy_pda.referral_wallet == Pubkey::default() {
+        return err!(FeeRouterError::InvalidReferral);
+    }
+
+    // Buybacks swap in the CP-AMM pool and replace every other way of paying the creator
+    if policy_pda.buyback_burn_creator
+        && (position_kind != PoolKind::CpAmm
+            || policy_pda.unwrap_wsol_creator
+            || policy_pda.creator_vesting_secs > 0)
+    {
+        return err!(FeeRouterError::InvalidBuyback);
+    }
+
+    // The creator payout is either streamed or unwrapped to SOL, not both
+    if policy_pda.creator_vesting_secs > 0 && policy_pda.unwrap_wsol_creator {
+        return err!(FeeRouterError::InvalidCreatorStream);
+    }
+
+    // Bonfida needs its program configured
+    if policy_pda.locker_kind == LockerKind::Bonfida && policy_pda.bonfida_program_id == Pubkey::default() {
+        return err!(FeeRouterError::InvalidLockerConfig);
+    }
+
+    // Stake weights need the staking program and a layout to read its accounts with
+    let reads_stake = policy_pda.locker_kind == LockerKind::Stake
+        || policy_pda.locker_allowlist.iter().any(|entry| entry.kind == LockerKind::Stake);
+    if (policy_pda.locker_kind == LockerKind::Stake && policy_pda.stake_program_id == Pubkey::default())
+        || (reads_stake && !policy_pda.stake_layout.is_valid())
+    {
+        return err!(FeeRouterError::InvalidLockerConfig);
+    }
+
+    // Oracle amounts need an oracle and a freshness bound
+    let reads_oracle = policy_pda.locker_kind == LockerKind::Oracle
+        || policy_pda.locker_allowlist.iter().any(|entry| entry.kind == LockerKind::Oracle);
+    if reads_oracle && (policy_pda.lock_oracle == Pubkey::default() || policy_pda.lock_oracle_max_age_secs == 0) {
+        return err!(FeeRouterError::InvalidLockerConfig);
+    }
+
+    // Payouts go either into investors' streams or into the router's escrow, not both
+    if policy_pda.payout_dispute_window_secs > 0 && policy_pda.payout_to_stream_escrow {
+        return err!(FeeRouterError::InvalidDisputeWindow);
+    }
+
+    // An attestation gate needs a layout its PDAs can be derived and read with
+    if policy_pda.attestation_program != Pubkey::default() && !policy_pda.attestation_layout.is_valid() {
+        return err!(FeeRouterError::InvalidAttestationConfig);
+    }
+
     if updated {
         policy_pda.updated_at = current_timestamp;
 
         // Emit policy update event
-        emit!(PolicyUpdated {
+        emit_cpi!(PolicyUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
             vault_seed,
             investor_fee_share_bps: policy_pda.investor_fee_share_bps,
             daily_cap_quote_lamports: policy_pda.daily_cap_quote_lamports,
             min_payout_lamports: policy_pda.min_payout_lamports,
             policy_fund_missing_ata: policy_pda.policy_fund_missing_ata,
+            day_gate_grace_secs: policy_pda.day_gate_grace_secs,
+            min_daily_claim_lamports: policy_pda.min_daily_claim_lamports,
+            roll_capped_excess: policy_pda.roll_capped_excess,
+            bonfida_program_id: policy_pda.bonfida_program_id,
+            locker_kind: policy_pda.locker_kind,
+            locker_allowlist: policy_pda.locker_allowlist.clone(),
+            pool_kind: policy_pda.pool_kind,
+            quote_only_buffer_ticks: policy_pda.quote_only_buffer_ticks,
+            payout_memos: policy_pda.payout_memos,
+            unwrap_wsol_creator: policy_pda.unwrap_wsol_creator,
+            continue_on_failure: policy_pda.continue_on_failure,
+            track_paid_investors: policy_pda.track_paid_investors,
+            force_finalize_after_secs: policy_pda.force_finalize_after_secs,
+            crank_lease_slots: policy_pda.crank_lease_slots,
+            batch_investor_paid_events: policy_pda.batch_investor_paid_events,
+            creator_vesting_secs: policy_pda.creator_vesting_secs,
+            compound_bps: policy_pda.compound_bps,
+            buyback_burn_creator: policy_pda.buyback_burn_creator,
+            buyback_max_slippage_bps: policy_pda.buyback_max_slippage_bps,
+            referral_wallet: policy_pda.referral_wallet,
+            referral_bps: policy_pda.referral_bps,
+            stake_program_id: policy_pda.stake_program_id,
+            stake_layout: policy_pda.stake_layout,
+            day_gate_slots: policy_pda.day_gate_slots,
+            epoch_offset_secs: policy_pda.epoch_offset_secs,
+            tranches_per_day: policy_pda.tranches_per_day,
+            max_weight_bps: policy_pda.max_weight_bps,
+            max_investors_per_page: policy_pda.max_investors_per_page,
+            max_pages_per_day: policy_pda.max_pages_per_day,
+            attestation_program: policy_pda.attestation_program,
+            attestation_layout: policy_pda.attestation_layout,
+            reward_routing: policy_pda.reward_routing,
+            cp_amm_entry_points: policy_pda.cp_amm_entry_points,
+            min_tick_distance_from_price: policy_pda.min_tick_distance_from_price,
+            cap_carry_days: policy_pda.cap_carry_days,
+            payout_to_stream_escrow: policy_pda.payout_to_stream_escrow,
+            payout_dispute_window_secs: policy_pda.payout_dispute_window_secs,
+            fund_missing_creator_ata: policy_pda.fund_missing_creator_ata,
+            hook_program: policy_pda.hook_program,
+            lock_oracle: policy_pda.lock_oracle,
+            lock_oracle_max_age_secs: policy_pda.lock_oracle_max_age_secs,
+            unordered_pages: policy_pda.unordered_pages,
+            param_schedule: policy_pda.param_schedule.clone(),
+            unattested_routing: policy_pda.unattested_routing,
             timestamp: current_timestamp,
         });
 
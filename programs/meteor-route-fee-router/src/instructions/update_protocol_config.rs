@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{ProtocolConfigUpdated, EVENT_SCHEMA_VERSION},
+    state::ProtocolConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ProtocolConfig::SEED],
+        bump,
+        has_one = admin
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+/// Change the protocol fee, its treasury or the admin
+///
+/// The fee applies from the next claim of every vault; fees already skimmed are not revisited.
+pub fn handler(
+    ctx: Context<UpdateProtocolConfig>,
+    new_admin: Option<Pubkey>,
+    new_protocol_fee_bps: Option<u16>,
+    new_protocol_treasury: Option<Pubkey>,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp as u64;
+    let config = &mut ctx.accounts.protocol_config;
+
+    if let Some(admin) = new_admin {
+        config.admin = admin;
+        msg!("Updated protocol admin to {}", admin);
+    }
+    if let Some(protocol_fee_bps) = new_protocol_fee_bps {
+        config.protocol_fee_bps = protocol_fee_bps;
+        msg!("Updated protocol_fee_bps to {}", protocol_fee_bps);
+    }
+    if let Some(protocol_treasury) = new_protocol_treasury {
+        config.protocol_treasury = protocol_treasury;
+        msg!("Updated protocol_treasury to {}", protocol_treasury);
+    }
+    config.validate()?;
+    config.updated_at = timestamp;
+
+    emit_cpi!(ProtocolConfigUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        admin: config.admin,
+        protocol_fee_bps: config.protocol_fee_bps,
+        protocol_treasury: config.protocol_treasury,
+        timestamp,
+    });
+
+    Ok(())
+}
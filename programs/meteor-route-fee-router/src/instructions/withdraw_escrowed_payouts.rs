@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    error::FeeRouterError,
+    events::{EscrowedPayoutsWithdrawn, EVENT_SCHEMA_VERSION},
+    instructions::distribute_fees::{quote_ata_status, QuoteAtaStatus},
+    state::{InvestorFeePositionOwnerPda, PayoutEscrowPda, PolicyPda},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_seed: String, investor: Pubkey)]
+pub struct WithdrawEscrowedPayouts<'info> {
+    /// Crank caller (permissionless)
+    pub crank_caller: Signer<'info>,
+
+    /// Policy configuration
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"policy"],
+        bump
+    )]
+    pub policy_pda: Account<'info, PolicyPda>,
+
+    /// Position owner PDA (treasury authority)
+    #[account(
+        seeds = [vault_seed.as_bytes(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: Account<'info, InvestorFeePositionOwnerPda>,
+
+    /// The investor's payout escrow
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"payout_escrow", investor.as_ref()],
+        bump
+    )]
+    pub payout_escrow: Account<'info, PayoutEscrowPda>,
+
+    /// Quote mint (must match policy)
+    #[account(address = policy_pda.quote_mint @ FeeRouterError::InvalidQuoteMint)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Program quote treasury token account at the treasury PDA
+    #[account(
+        mut,
+        seeds = [vault_seed.as_bytes(), b"treasury", quote_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program,
+    )]
+    pub quote_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: investor's quote token account, validated against the mint and investor in the handler
+    #[account(mut)]
+    pub investor_quote_ata: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pay an investor every escrowed payout whose dispute window has passed
+///
+/// Entries still inside their window stay in the escrow. Nothing released is not an error, so a
+/// keeper can call this on a schedule.
+pub fn handler(
+    ctx: Context<WithdrawEscrowedPayouts>,
+    vault_seed: String,
+    investor: Pubkey,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let quote_mint_key = ctx.accounts.quote_mint.key();
+    let quote_ata = ctx.accounts.investor_quote_ata.to_account_info();
+    require!(
+        quote_ata_status(&quote_ata, &quote_mint_key, &investor) == QuoteAtaStatus::Valid,
+        FeeRouterError::InvalidEscrowDestination
+    );
+
+    let payout_escrow = &mut ctx.accounts.payout_escrow;
+    let amount = payout_escrow.take_released(current_timestamp);
+    payout_escrow.updated_at = current_timestamp;
+    if amount == 0 {
+        msg!("No escrowed payouts released yet for investor {}", investor);
+        return Ok(());
+    }
+
+    let seeds = &[
+        vault_seed.as_bytes(),
+        b"investor_fee_pos_owner",
+        &[ctx.bumps.position_owner_pda],
+    ];
+    let signer = &[&seeds[..]];
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.quote_treasury.to_account_info(),
+                mint: ctx.accounts.quote_mint.to_account_info(),
+                to: quote_ata.clone(),
+                authority: ctx.accounts.position_owner_pda.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        ctx.accounts.quote_mint.decimals,
+    )?;
+
+    let total_escrowed = ctx.accounts.payout_escrow.total_escrowed;
+    emit_cpi!(EscrowedPayoutsWithdrawn {
+        schema_version: EVENT_SCHEMA_VERSION,
+        vault_seed: vault_seed.clone(),
+        quote_mint: quote_mint_key,
+        decimals: ctx.accounts.quote_mint.decimals,
+        investor,
+        quote_ata: quote_ata.key(),
+        amount,
+        total_escrowed,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "Withdrew {} escrowed for investor {}; {} still in dispute window",
+        amount,
+        investor,
+        total_escrowed
+    );
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Investor page inputs for `distribute_fees`: the Borsh `InvestorPage` and the packed wire format
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use bytemuck::{Pod, Zeroable};
+use meteor_route_core::page_hash_preimage;
+pub use meteor_route_core::PageDomain;
+
+use crate::{error::FeeRouterError, InvestorData, InvestorPage};
+
+/// Read access to one page of investors, independent of its wire format
+pub trait InvestorPageView {
+    fn page_index(&self) -> u64;
+    fn page_hash(&self) -> [u8; 32];
+    fn investor_count(&self) -> usize;
+    fn investor(&self, i: usize) -> InvestorData;
+    /// H(domain || page_index LE || investors[i].stream || investors[i].investor || investors[i].index LE for all i)
+    fn computed_hash(&self, domain: &PageDomain) -> [u8; 32];
+}
+
+impl InvestorPageView for InvestorPage {
+    fn page_index(&self) -> u64 {
+        self.page_index
+    }
+
+    fn page_hash(&self) -> [u8; 32] {
+        self.page_hash
+    }
+
+    fn investor_count(&self) -> usize {
+        self.investors.len()
+    }
+
+    fn investor(&self, i: usize) -> InvestorData {
+        self.investors[i].clone()
+    }
+
+    fn computed_hash(&self, domain: &PageDomain) -> [u8; 32] {
+        let index_le = self.page_index.to_le_bytes();
+        let investor_indexes: Vec<[u8; 4]> = self.investors.iter().map(|inv| inv.index.to_le_bytes()).collect();
+        let investors = self
+            .investors
+            .iter()
+            .zip(&investor_indexes)
+            .map(|(inv, index_le)| (inv.stream.as_array(), inv.investor.as_array(), index_le));
+        hashv(&page_hash_preimage(domain, &index_le, investors)).to_bytes()
+    }
+}
+
+/// Packed page header (48 bytes), followed by `investor_count` 64-byte records
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct PackedPageHeader {
+    pub page_index: [u8; 8],
+    pub page_hash: [u8; 32],
+    /// Investor list index of the first record; record i has index first_index + i
+    pub first_index: [u8; 4],
+    pub investor_count: [u8; 4],
+}
+
+/// Packed investor record: stream then investor; its index comes from the header's `first_index`
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct PackedInvestor {
+    pub stream: Pubkey,
+    pub investor: Pubkey,
+}
+
+pub const PACKED_HEADER_LEN: usize = std::mem::size_of::<PackedPageHeader>();
+pub const PACKED_INVESTOR_LEN: usize = std::mem::size_of::<PackedInvestor>();
+
+/// A page viewed in place over the packed instruction bytes
+pub struct PackedPage<'a> {
+    pub header: &'a PackedPageHeader,
+    pub investors: &'a [PackedInvestor],
+}
+
+impl InvestorPageView for PackedPage<'_> {
+    fn page_index(&self) -> u64 {
+        u64::from_le_bytes(self.header.page_index)
+    }
+
+    fn page_hash(&self) -> [u8; 32] {
+        self.header.page_hash
+    }
+
+    fn investor_count(&self) -> usize {
+        self.investors.len()
+    }
+
+    fn investor(&self, i: usize) -> InvestorData {
+        let record = &self.investors[i];
+        InvestorData {
+            stream: record.stream,
+            investor: record.investor,
+            index: u32::from_le_bytes(self.header.first_index).saturating_add(i as u32),
+        }
+    }
+
+    fn computed_hash(&self, domain: &PageDomain) -> [u8; 32] {
+        // The hashed indexes are the ones investor() derives from first_index
+        let investor_indexes: Vec<[u8; 4]> = (0..self.investors.len())
+            .map(|i| self.investor(i).index.to_le_bytes())
+            .collect();
+        let investors = self
+            .investors
+            .iter()
+            .zip(&investor_indexes)
+            .map(|(inv, index_le)| (inv.stream.as_array(), inv.investor.as_array(), index_le));
+        hashv(&page_hash_preimage(domain, &self.header.page_index, investors)).to_bytes()
+    }
+}
+
+/// Split packed page bytes into pages without copying the records
+pub fn parse_packed_pages(mut data: &[u8]) -> Result<Vec<PackedPage<'_>>> {
+    let mut pages = Vec::new();
+    while !data.is_empty() {
+        require!(data.len() >= PACKED_HEADER_LEN, FeeRouterError::InvalidPaginationState);
+        let (header_bytes, rest) = data.split_at(PACKED_HEADER_LEN);
+        let header: &PackedPageHeader = bytemuck::from_bytes(header_bytes);
+        let records_len = (u32::from_le_bytes(header.investor_count) as usize)
+            .checked_mul(PACKED_INVESTOR_LEN)
+            .ok_or(FeeRouterError::Overflow)?;
+        require!(rest.len() >= records_len, FeeRouterError::InvalidPaginationState);
+        let (records, rest) = rest.split_at(records_len);
+        pages.push(PackedPage {
+            header,
+            investors: bytemuck::cast_slice(records),
+        });
+        data = rest;
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(page_index: u64, first_index: u32, investors: &[InvestorData]) -> Vec<u8> {
+        let mut page = InvestorPage {
+            page_index,
+            page_hash: [0u8; 32],
+            investors: investors.to_vec(),
+        };
+        page.page_hash = page.computed_hash(&domain());
+        let mut out = Vec::new();
+        out.extend_from_slice(&page_index.to_le_bytes());
+        out.extend_from_slice(&page.page_hash);
+        out.extend_from_slice(&first_index.to_le_bytes());
+        out.extend_from_slice(&(investors.len() as u32).to_le_bytes());
+        for inv in investors {
+            out.extend_from_slice(inv.stream.as_ref());
+            out.extend_from_slice(inv.investor.as_ref());
+        }
+        out
+    }
+
+    fn domain() -> PageDomain<'static> {
+        PageDomain::new(crate::ID.as_array(), "vault", 1, 0)
+    }
+
+    fn investor(index: u32) -> InvestorData {
+        InvestorData {
+            stream: Pubkey::new_unique(),
+            investor: Pubkey::new_unique(),
+            index,
+        }
+    }
+
+    #[test]
+    fn test_packed_pages_match_borsh_pages() {
+        let page0 = [investor(10), investor(11)];
+        let page1 = [investor(12)];
+        let mut data = pack(0, 10, &page0);
+        data.extend(pack(1, 12, &page1));
+
+        let pages = parse_packed_pages(&data).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].page_index(), 0);
+        assert_eq!(pages[0].investor_count(), 2);
+        assert_eq!(pages[0].computed_hash(&domain()), pages[0].page_hash());
+        assert_eq!(pages[1].computed_hash(&domain()), pages[1].page_hash());
+        assert_ne!(pages[0].computed_hash(&PageDomain::new(crate::ID.as_array(), "vault", 2, 0)), pages[0].page_hash());
+        let second = pages[0].investor(1);
+        assert_eq!(second.investor, page0[1].investor);
+        assert_eq!(second.index, 11);
+
+        // Shifting first_index moves every record to another bitmap slot, which the hash covers
+        let mut shifted = data.clone();
+        shifted[40..44].copy_from_slice(&20u32.to_le_bytes());
+        let pages = parse_packed_pages(&shifted).unwrap();
+        assert_ne!(pages[0].computed_hash(&domain()), pages[0].page_hash());
+
+        // Truncated record data is rejected
+        assert!(parse_packed_pages(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_old_generation_page_does_not_verify() {
+        // A page signed off before a reinitialize, replayed on the same day afterwards
+        let investors = [investor(0), investor(1)];
+        let data = pack(0, 0, &investors);
+        let old = &parse_packed_pages(&data).unwrap()[0];
+        let borsh = InvestorPage {
+            page_index: 0,
+            page_hash: old.page_hash(),
+            investors: investors.to_vec(),
+        };
+        assert_eq!(borsh.computed_hash(&domain()), borsh.page_hash);
+
+        let next = PageDomain::new(crate::ID.as_array(), "vault", 1, 1);
+        assert_ne!(old.computed_hash(&next), old.page_hash());
+        assert_ne!(borsh.computed_hash(&next), borsh.page_hash);
+    }
+}
@@ -0,0 +1,36 @@
+//! Helpers for reading fields of foreign (non-Anchor-typed) accounts by byte offset
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+/// Anchor discriminator: sha256(preimage)[..8], e.g. `account:LbPair` or `global:claim_fee`
+pub fn discriminator(preimage: &[u8]) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(preimage).to_bytes()[..8]);
+    discriminator
+}
+
+pub fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+pub fn read_i32(data: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+pub fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+pub fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+pub fn read_u128(data: &[u8], offset: usize) -> u128 {
+    u128::from_le_bytes(data[offset..offset + 16].try_into().unwrap())
+}
+
+/// Offset of the `amount` field in an SPL token account
+pub const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+/// Offset of the `state` byte in an SPL token account (0 = uninitialized, 1 = initialized, 2 = frozen)
+pub const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+pub const TOKEN_ACCOUNT_STATE_FROZEN: u8 = 2;
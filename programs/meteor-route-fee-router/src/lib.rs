@@ -5,13 +5,69 @@ pub mod events;
 pub mod state;
 pub mod instructions;
 pub mod cp_amm;
+pub mod cp_amm_entry_points;
+pub mod networks;
+pub mod dlmm;
+pub mod layout;
+pub mod whirlpool;
+pub mod raydium_clmm;
 pub mod streamflow;
+pub mod bonfida;
+pub mod jup_lock;
+pub mod oracle_lock;
+pub mod stake;
+pub mod attestation;
+pub mod locker;
+pub mod transfer_fee;
+pub mod mint_safety;
+pub mod memo;
+pub mod hook;
+pub mod investor_page;
+pub mod event_cpi;
+pub mod governance;
 // Re-export account types at crate root for clean Context<T> usage
 pub use instructions::{
+    AcquireCrankLease,
+    AmendPolicyTargets,
+    CacheStaticAccounts,
+    ClaimRewards,
+    ClawBackEscrowedPayout,
     DistributeFees,
+    DistributeFeesMulti,
+    DistributeFeesResult,
+    DistributionParams,
+    ForceFinalizeDay,
+    FundAtaReserve,
+    GetDistributionParams,
+    InitializeCrankLog,
+    InitializeDlmmPosition,
+    InitializeFailedPayouts,
     InitializeHonoraryPosition,
+    InitializePaidBitmap,
+    InitializePayoutOverrides,
     InitializePolicy,
+    InitializePolicyArgs,
     InitializeProgress,
+    InitializeProtocolConfig,
+    InitializeRaydiumPosition,
+    InitializeWeightOverrides,
+    InitializeWhirlpoolPosition,
+    PublishOracleLocked,
+    ReinitializeVault,
+    RetryFailedPayouts,
+    SetExpectedPages,
+    SetLockerTestConfig,
+    SetNetworkConfig,
+    SetPayoutOverride,
+    SetPolicyAuthority,
+    SetPolicyPaused,
+    SetWeightOverride,
+    SettleCreatorPayout,
+    SnapshotLocked,
+    UpdatePolicy,
+    UpdatePolicyArgs,
+    UpdateProtocolConfig,
+    WithdrawEscrowedPayouts,
 };
 
 #[allow(non_snake_case)]
@@ -27,9 +83,141 @@ pub(crate) mod __client_accounts_initialize_honorary_position {
     pub use crate::instructions::__client_accounts_initialize_honorary_position::*;
 }
 #[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_dlmm_position {
+    pub use crate::instructions::__client_accounts_initialize_dlmm_position::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_whirlpool_position {
+    pub use crate::instructions::__client_accounts_initialize_whirlpool_position::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_raydium_position {
+    pub use crate::instructions::__client_accounts_initialize_raydium_position::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_update_policy {
+    pub use crate::instructions::__client_accounts_update_policy::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_snapshot_locked {
+    pub use crate::instructions::__client_accounts_snapshot_locked::*;
+}
+#[allow(non_snake_case)]
 pub(crate) mod __client_accounts_distribute_fees {
     pub use crate::instructions::__client_accounts_distribute_fees::*;
 }
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_failed_payouts {
+    pub use crate::instructions::__client_accounts_initialize_failed_payouts::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_retry_failed_payouts {
+    pub use crate::instructions::__client_accounts_retry_failed_payouts::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_paid_bitmap {
+    pub use crate::instructions::__client_accounts_initialize_paid_bitmap::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_cache_static_accounts {
+    pub use crate::instructions::__client_accounts_cache_static_accounts::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_force_finalize_day {
+    pub use crate::instructions::__client_accounts_force_finalize_day::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_acquire_crank_lease {
+    pub use crate::instructions::__client_accounts_acquire_crank_lease::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_set_locker_test_config {
+    pub use crate::instructions::__client_accounts_set_locker_test_config::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_settle_creator_payout {
+    pub use crate::instructions::__client_accounts_settle_creator_payout::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_protocol_config {
+    pub use crate::instructions::__client_accounts_initialize_protocol_config::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_update_protocol_config {
+    pub use crate::instructions::__client_accounts_update_protocol_config::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_set_policy_authority {
+    pub use crate::instructions::__client_accounts_set_policy_authority::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_set_policy_paused {
+    pub use crate::instructions::__client_accounts_set_policy_paused::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_distribute_fees_multi {
+    pub use crate::instructions::__client_accounts_distribute_fees_multi::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_reinitialize_vault {
+    pub use crate::instructions::__client_accounts_reinitialize_vault::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_payout_overrides {
+    pub use crate::instructions::__client_accounts_initialize_payout_overrides::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_set_payout_override {
+    pub use crate::instructions::__client_accounts_set_payout_override::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_weight_overrides {
+    pub use crate::instructions::__client_accounts_initialize_weight_overrides::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_set_weight_override {
+    pub use crate::instructions::__client_accounts_set_weight_override::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_amend_policy_targets {
+    pub use crate::instructions::__client_accounts_amend_policy_targets::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_initialize_crank_log {
+    pub use crate::instructions::__client_accounts_initialize_crank_log::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_claim_rewards {
+    pub use crate::instructions::__client_accounts_claim_rewards::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_set_network_config {
+    pub use crate::instructions::__client_accounts_set_network_config::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_set_expected_pages {
+    pub use crate::instructions::__client_accounts_set_expected_pages::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_withdraw_escrowed_payouts {
+    pub use crate::instructions::__client_accounts_withdraw_escrowed_payouts::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_claw_back_escrowed_payout {
+    pub use crate::instructions::__client_accounts_claw_back_escrowed_payout::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_publish_oracle_locked {
+    pub use crate::instructions::__client_accounts_publish_oracle_locked::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_get_distribution_params {
+    pub use crate::instructions::__client_accounts_get_distribution_params::*;
+}
+#[allow(non_snake_case)]
+pub(crate) mod __client_accounts_fund_ata_reserve {
+    pub use crate::instructions::__client_accounts_fund_ata_reserve::*;
+}
 
 declare_id!("BK5eKYpvFhvnVTDX6ZV6zcpkAgmKTiSi2Q8z9ypGR23E");
 
@@ -41,21 +229,9 @@ pub mod meteor_route_fee_router {
     pub fn initialize_policy(
         ctx: Context<InitializePolicy>,
         vault_seed: String,
-        investor_fee_share_bps: u16,
-        daily_cap_quote_lamports: u64,
-        min_payout_lamports: u64,
-        policy_fund_missing_ata: bool,
-        y0_total_allocation: u128,
+        args: InitializePolicyArgs,
     ) -> Result<()> {
-        instructions::initialize_policy::handler(
-            ctx,
-            vault_seed,
-            investor_fee_share_bps,
-            daily_cap_quote_lamports,
-            min_payout_lamports,
-            policy_fund_missing_ata,
-            y0_total_allocation,
-        )
+        instructions::initialize_policy::handler(ctx, vault_seed, args)
     }
 
     /// Initialize progress tracking for distribution state
@@ -67,6 +243,8 @@ pub mod meteor_route_fee_router {
     }
 
     /// Initialize the honorary fee position with verified tick range
+    ///
+    /// Passing `cp_amm::AUTO_TICK_RANGE` derives the widest quote-only range at the pool's live price.
     pub fn initialize_honorary_position(
         ctx: Context<InitializeHonoraryPosition>,
         vault_seed: String,
@@ -83,27 +261,348 @@ pub mod meteor_route_fee_router {
         )
     }
 
+    /// Initialize the honorary fee position as a quote-only DLMM bin range
+    pub fn initialize_dlmm_position(
+        ctx: Context<InitializeDlmmPosition>,
+        vault_seed: String,
+        lower_bin_id: i32,
+        width: i32,
+        quote_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_dlmm_position::handler(
+            ctx,
+            vault_seed,
+            lower_bin_id,
+            width,
+            quote_mint,
+        )
+    }
+
+    /// Initialize the honorary fee position as a quote-only Orca Whirlpool position
+    pub fn initialize_whirlpool_position(
+        ctx: Context<InitializeWhirlpoolPosition>,
+        vault_seed: String,
+        tick_lower: i32,
+        tick_upper: i32,
+        quote_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_whirlpool_position::handler(
+            ctx,
+            vault_seed,
+            tick_lower,
+            tick_upper,
+            quote_mint,
+        )
+    }
+
+    /// Adopt a Raydium CLMM position (NFT already held by the position owner PDA) as the honorary position
+    pub fn initialize_raydium_position(
+        ctx: Context<InitializeRaydiumPosition>,
+        vault_seed: String,
+        quote_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_raydium_position::handler(ctx, vault_seed, quote_mint)
+    }
+
+    /// Fix the policy's pool and mints before the honorary position exists (authority only)
+    pub fn amend_policy_targets(
+        ctx: Context<AmendPolicyTargets>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::amend_policy_targets::handler(ctx, vault_seed)
+    }
+
+    /// Update policy parameters (authority only)
+    pub fn update_policy(
+        ctx: Context<UpdatePolicy>,
+        vault_seed: String,
+        args: UpdatePolicyArgs,
+    ) -> Result<()> {
+        instructions::update_policy::handler(ctx, vault_seed, args)
+    }
+
+    /// Hand the policy authority to another key, e.g. a Realms governance (authority only)
+    pub fn set_policy_authority(
+        ctx: Context<SetPolicyAuthority>,
+        vault_seed: String,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::set_policy_authority::handler(ctx, vault_seed, new_authority)
+    }
+
+    /// Pause or resume distribution for the vault (authority only)
+    pub fn set_policy_paused(
+        ctx: Context<SetPolicyPaused>,
+        vault_seed: String,
+        paused: bool,
+    ) -> Result<()> {
+        instructions::set_policy_paused::handler(ctx, vault_seed, paused)
+    }
+
+    /// Restart a paused vault under the next generation (authority only)
+    pub fn reinitialize_vault(
+        ctx: Context<ReinitializeVault>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::reinitialize_vault::handler(ctx, vault_seed)
+    }
+
+    /// Permissionless day-start snapshot of investor locked amounts
+    pub fn snapshot_locked<'info>(
+        ctx: Context<'_, '_, '_, 'info, SnapshotLocked<'info>>,
+        vault_seed: String,
+        investors: Vec<InvestorData>,
+        is_final_chunk: bool,
+    ) -> Result<()> {
+        instructions::snapshot_locked::handler(ctx, vault_seed, investors, is_final_chunk)
+    }
+
+    /// Commit the day's page and investor counts before any payouts (authority only)
+    pub fn set_expected_pages(
+        ctx: Context<SetExpectedPages>,
+        vault_seed: String,
+        expected_pages: u64,
+        expected_investors: u32,
+    ) -> Result<()> {
+        instructions::set_expected_pages::handler(ctx, vault_seed, expected_pages, expected_investors)
+    }
+
     /// Permissionless 24h distribution crank with base fee detection
     pub fn distribute_fees<'a, 'info: 'a>(
         ctx: Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
         vault_seed: String,
         investor_pages: Vec<InvestorPage>,
         is_final_page: bool,
+    ) -> Result<DistributeFeesResult> {
+        instructions::distribute_fees::handler(
+            ctx,
+            vault_seed,
+            &investor_pages,
+            is_final_page,
+        )
+    }
+
+    /// Permissionless close of a day whose keeper stalled past the policy timeout
+    pub fn force_finalize_day(
+        ctx: Context<ForceFinalizeDay>,
+        vault_seed: String,
     ) -> Result<()> {
+        instructions::force_finalize_day::handler(ctx, vault_seed)
+    }
+
+    /// Take or renew the short-lived crank lease so competing keepers don't race on the cursor
+    pub fn acquire_crank_lease(
+        ctx: Context<AcquireCrankLease>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::acquire_crank_lease::handler(ctx, vault_seed)
+    }
+
+    /// `distribute_fees` with pages in the packed wire format (see `investor_page`)
+    pub fn distribute_fees_packed<'a, 'info: 'a>(
+        ctx: Context<'a, 'a, 'a, 'info, DistributeFees<'info>>,
+        vault_seed: String,
+        packed_pages: Vec<u8>,
+        is_final_page: bool,
+    ) -> Result<DistributeFeesResult> {
+        let investor_pages = investor_page::parse_packed_pages(&packed_pages)?;
         instructions::distribute_fees::handler(
             ctx,
             vault_seed,
-            investor_pages,
+            &investor_pages,
             is_final_page,
         )
     }
+
+    /// Permissionless claim-only crank over several CP-AMM vaults (see `MULTI_VAULT_ACCOUNTS`)
+    pub fn distribute_fees_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeFeesMulti<'info>>,
+        vault_seeds: Vec<String>,
+    ) -> Result<()> {
+        instructions::distribute_fees_multi::handler(ctx, vault_seeds)
+    }
+
+    /// Permissionless claim of CP-AMM position rewards, routed per policy.reward_routing
+    pub fn claim_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>,
+        vault_seed: String,
+        reward_index: u8,
+        investor_pages: Vec<InvestorPage>,
+        is_final_page: bool,
+    ) -> Result<()> {
+        instructions::claim_rewards::handler(ctx, vault_seed, reward_index, investor_pages, is_final_page)
+    }
+
+    /// Create the failed payout ledger for continue-on-failure mode
+    pub fn initialize_failed_payouts(
+        ctx: Context<InitializeFailedPayouts>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::initialize_failed_payouts::handler(ctx, vault_seed)
+    }
+
+    /// Permissionless settlement of payouts recorded in the failed payout ledger
+    pub fn retry_failed_payouts<'info>(
+        ctx: Context<'_, '_, '_, 'info, RetryFailedPayouts<'info>>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::retry_failed_payouts::handler(ctx, vault_seed)
+    }
+
+    /// Permissionless payout of an investor's escrowed payouts whose dispute window has passed
+    pub fn withdraw_escrowed_payouts(
+        ctx: Context<WithdrawEscrowedPayouts>,
+        vault_seed: String,
+        investor: Pubkey,
+    ) -> Result<()> {
+        instructions::withdraw_escrowed_payouts::handler(ctx, vault_seed, investor)
+    }
+
+    /// Claw back an escrowed payout inside its dispute window (authority only)
+    pub fn claw_back_escrowed_payout(
+        ctx: Context<ClawBackEscrowedPayout>,
+        vault_seed: String,
+        investor: Pubkey,
+        day_epoch: u64,
+    ) -> Result<()> {
+        instructions::claw_back_escrowed_payout::handler(ctx, vault_seed, investor, day_epoch)
+    }
+
+    /// Publish an investor's off-chain locked amount (policy lock oracle only)
+    pub fn publish_oracle_locked(
+        ctx: Context<PublishOracleLocked>,
+        vault_seed: String,
+        investor: Pubkey,
+        locked_amount: u64,
+        as_of_ts: u64,
+    ) -> Result<()> {
+        instructions::publish_oracle_locked::handler(ctx, vault_seed, investor, locked_amount, as_of_ts)
+    }
+
+    /// Read-only view of the distribution state, returned as return data (simulate it)
+    pub fn get_distribution_params(
+        ctx: Context<GetDistributionParams>,
+        vault_seed: String,
+    ) -> Result<DistributionParams> {
+        instructions::get_distribution_params::handler(ctx, vault_seed)
+    }
+
+    /// Top up the position owner PDA's reserve that repays crank-paid ATA and escrow rent
+    pub fn fund_ata_reserve(ctx: Context<FundAtaReserve>, vault_seed: String, lamports: u64) -> Result<()> {
+        instructions::fund_ata_reserve::handler(ctx, vault_seed, lamports)
+    }
+
+    /// Permissionless delivery of creator remainders deferred at finalize
+    pub fn settle_creator_payout(
+        ctx: Context<SettleCreatorPayout>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::settle_creator_payout::handler(ctx, vault_seed)
+    }
+
+    /// Create the per-day paid bitmap for exactly-once investor payouts
+    pub fn initialize_paid_bitmap(
+        ctx: Context<InitializePaidBitmap>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::initialize_paid_bitmap::handler(ctx, vault_seed)
+    }
+
+    /// Create the per-investor minimum payout table (authority only)
+    pub fn initialize_payout_overrides(
+        ctx: Context<InitializePayoutOverrides>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::initialize_payout_overrides::handler(ctx, vault_seed)
+    }
+
+    /// Set or remove one investor's minimum payout override (authority only)
+    pub fn set_payout_override(
+        ctx: Context<SetPayoutOverride>,
+        vault_seed: String,
+        investor: Pubkey,
+        min_payout_lamports: Option<u64>,
+    ) -> Result<()> {
+        instructions::set_payout_override::handler(ctx, vault_seed, investor, min_payout_lamports)
+    }
+
+    /// Create the fixed investor weight table (authority only)
+    pub fn initialize_weight_overrides(
+        ctx: Context<InitializeWeightOverrides>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::initialize_weight_overrides::handler(ctx, vault_seed)
+    }
+
+    /// Set or remove one investor's fixed weight between days (authority only)
+    pub fn set_weight_override(
+        ctx: Context<SetWeightOverride>,
+        vault_seed: String,
+        investor: Pubkey,
+        weight: Option<u64>,
+    ) -> Result<()> {
+        instructions::set_weight_override::handler(ctx, vault_seed, investor, weight)
+    }
+
+    /// Create the on-chain log of recent cranks (authority only)
+    pub fn initialize_crank_log(
+        ctx: Context<InitializeCrankLog>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::initialize_crank_log::handler(ctx, vault_seed)
+    }
+
+    /// Pin the crank's static accounts in the policy for key-only checks and lookup tables
+    pub fn cache_static_accounts(
+        ctx: Context<CacheStaticAccounts>,
+        vault_seed: String,
+    ) -> Result<()> {
+        instructions::cache_static_accounts::handler(ctx, vault_seed)
+    }
+
+    /// Upgrade-authority switch for the local-testing locker bypass (see `LockerTestConfig`)
+    pub fn set_locker_test_config(
+        ctx: Context<SetLockerTestConfig>,
+        relax_locker_checks: bool,
+    ) -> Result<()> {
+        instructions::set_locker_test_config::handler(ctx, relax_locker_checks)
+    }
+
+    /// Upgrade-authority creation of the program-wide protocol fee config (see `ProtocolConfig`)
+    pub fn initialize_protocol_config(
+        ctx: Context<InitializeProtocolConfig>,
+        admin: Pubkey,
+        protocol_fee_bps: u16,
+        protocol_treasury: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_protocol_config::handler(ctx, admin, protocol_fee_bps, protocol_treasury)
+    }
+
+    /// Admin update of the protocol fee config
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        new_admin: Option<Pubkey>,
+        new_protocol_fee_bps: Option<u16>,
+        new_protocol_treasury: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_protocol_config::handler(ctx, new_admin, new_protocol_fee_bps, new_protocol_treasury)
+    }
+
+    /// Upgrade-authority selection of the cluster's Streamflow and CP-AMM programs (see `NetworkConfig`)
+    pub fn set_network_config(
+        ctx: Context<SetNetworkConfig>,
+        network: networks::Network,
+        programs: Option<networks::NetworkPrograms>,
+    ) -> Result<()> {
+        instructions::set_network_config::handler(ctx, network, programs)
+    }
 }
 
 /// Investor page data for batch processing
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct InvestorPage {
     pub page_index: u64,
-    /// Hash over (page_index LE || investors[i].stream || investors[i].investor) for all i
+    /// Hash over (domain || page_index LE || investors[i].stream || investors[i].investor || investors[i].index LE) for all i
     pub page_hash: [u8; 32],
     pub investors: Vec<InvestorData>,
 }
@@ -115,5 +614,7 @@ pub struct InvestorData {
     pub stream: Pubkey,
     /// Investor wallet address (recipient of the stream)
     pub investor: Pubkey,
+    /// Stable index of the investor in the vault's investor list (its paid bitmap slot)
+    pub index: u32,
     // Note: locked_amount is now read on-chain from Streamflow, not passed from client
 }
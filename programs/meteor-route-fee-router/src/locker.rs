@@ -0,0 +1,242 @@
+use anchor_lang::prelude::*;
+use crate::{
+    bonfida::BonfidaSource,
+    error::FeeRouterError,
+    jup_lock::{JupLockSource, JUP_LOCK_PROGRAM_ID},
+    oracle_lock::OracleLockSource,
+    stake::{StakeLayout, StakeSource},
+    state::PolicyPda,
+    streamflow::StreamflowSource,
+    InvestorData,
+};
+
+/// Vesting provider a vault's investor locks live in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LockerKind {
+    /// Streamflow `Contract` accounts
+    #[default]
+    Streamflow,
+    /// Bonfida token-vesting accounts (program from `bonfida_program_id`)
+    Bonfida,
+    /// Jupiter Lock `VestingEscrow` accounts
+    JupLock,
+    /// Staking program stake accounts (program from `stake_program_id`, read through `stake_layout`)
+    Stake,
+    /// `OracleLockedPda` amounts published by `lock_oracle`, for lockups held off-chain or elsewhere
+    Oracle,
+}
+
+/// Why a stream contributed nothing to the day's locked total
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamIgnoredReason {
+    /// Stream account no longer exists (closed, no data)
+    AccountClosed,
+    /// Stream was canceled (`canceled_at` set)
+    Canceled,
+    /// Stream is marked closed
+    Closed,
+}
+
+/// Locked amount read from a stream, or the reason the stream is ignored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamLocked {
+    Locked(u64),
+    Ignored(StreamIgnoredReason),
+}
+
+impl StreamLocked {
+    /// Add an investor's fixed weight override; an ignored stream counts as the weight alone
+    pub fn with_weight(self, weight: u64) -> Result<Self> {
+        Ok(match self {
+            _ if weight == 0 => self,
+            StreamLocked::Locked(amount) => StreamLocked::Locked(
+                amount.checked_add(weight).ok_or(FeeRouterError::Overflow)?,
+            ),
+            StreamLocked::Ignored(_) => StreamLocked::Locked(weight),
+        })
+    }
+}
+
+/// Accepted locker program and the adapter that parses its accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockerEntry {
+    pub program_id: Pubkey,
+    pub kind: LockerKind,
+}
+
+/// Vault settings and evaluation time an allowlisted entry reads one investor's lock with
+pub struct LockerReadContext<'a> {
+    /// Time the locked amount is evaluated at
+    pub at_ts: u64,
+    /// Mint Streamflow streams must vest
+    pub base_mint: Pubkey,
+    /// Layout stake entries read their accounts through
+    pub stake_layout: StakeLayout,
+    /// Policy's lock oracle, needed by oracle entries
+    pub oracle: Option<&'a OracleLockSource>,
+    /// Local-testing bypass from `LockerTestConfig`
+    pub relax_checks: bool,
+}
+
+impl LockerEntry {
+    pub const LEN: usize = 32 + 1;
+
+    /// Read an investor's locked amount with this entry's adapter
+    ///
+    /// Stake entries read their accounts through the policy's `stake_layout`; oracle entries
+    /// (program ID = the router) need the policy's lock oracle.
+    pub fn read_locked(
+        &self,
+        account_info: &AccountInfo,
+        investor_data: &InvestorData,
+        read: &LockerReadContext,
+    ) -> Result<StreamLocked> {
+        let LockerReadContext { at_ts, base_mint, stake_layout, oracle, relax_checks } = *read;
+        let program_id = self.program_id;
+        match self.kind {
+            LockerKind::Streamflow => StreamflowSource { program_id, base_mint, relax_checks }.read_locked(account_info, investor_data, at_ts),
+            LockerKind::Bonfida => BonfidaSource { program_id, relax_checks }.read_locked(account_info, investor_data, at_ts),
+            LockerKind::JupLock => JupLockSource { program_id, relax_checks }.read_locked(account_info, investor_data, at_ts),
+            LockerKind::Stake => StakeSource { program_id, layout: stake_layout, relax_checks }.read_locked(account_info, investor_data, at_ts),
+            LockerKind::Oracle => oracle
+                .ok_or(FeeRouterError::InvalidLockerConfig)?
+                .read_locked(account_info, investor_data, at_ts),
+        }
+    }
+}
+
+/// A vesting provider the router can read investor locked amounts from
+pub trait LockedAmountSource {
+    /// Program that owns this provider's vesting accounts
+    fn program_id(&self) -> Pubkey;
+
+    /// Read the amount still locked for `investor_data` at `at_ts`
+    ///
+    /// `account_info` is the vesting account passed for the investor (`investor_data.stream`).
+    fn read_locked(
+        &self,
+        account_info: &AccountInfo,
+        investor_data: &InvestorData,
+        at_ts: u64,
+    ) -> Result<StreamLocked>;
+}
+
+/// Vesting providers accepted for a vault, resolved once per instruction from the policy
+pub struct LockerSources {
+    pub kind: LockerKind,
+    pub streamflow: StreamflowSource,
+    /// Set when the policy has a Bonfida token-vesting program configured
+    pub bonfida: Option<BonfidaSource>,
+    pub jup_lock: JupLockSource,
+    /// Set when the policy has a staking program configured
+    pub stake: Option<StakeSource>,
+    /// Stake account layout, also used by allowlisted stake programs
+    pub stake_layout: StakeLayout,
+    /// Set when the policy has a lock oracle configured
+    pub oracle: Option<OracleLockSource>,
+    /// Accepted locker programs; when non-empty, accounts are routed by owner
+    pub allowlist: Vec<LockerEntry>,
+    /// Local-testing bypass from `LockerTestConfig`; never set on a real cluster
+    pub relax_checks: bool,
+}
+
+impl LockerSources {
+    pub fn from_policy(policy: &PolicyPda, streamflow_program_id: Pubkey) -> Self {
+        let bonfida = (policy.bonfida_program_id != Pubkey::default()).then_some(BonfidaSource {
+            program_id: policy.bonfida_program_id,
+            relax_checks: false,
+        });
+        let stake = (policy.stake_program_id != Pubkey::default()).then_some(StakeSource {
+            program_id: policy.stake_program_id,
+            layout: policy.stake_layout,
+            relax_checks: false,
+        });
+        Self {
+            kind: policy.locker_kind,
+            streamflow: StreamflowSource {
+                program_id: streamflow_program_id,
+                base_mint: policy.base_mint,
+                relax_checks: false,
+            },
+            bonfida,
+            jup_lock: JupLockSource {
+                program_id: JUP_LOCK_PROGRAM_ID,
+                relax_checks: false,
+            },
+            stake,
+            stake_layout: policy.stake_layout,
+            oracle: OracleLockSource::from_policy(policy),
+            allowlist: policy.locker_allowlist.clone(),
+            relax_checks: false,
+        }
+    }
+
+    /// Apply the local-testing bypass to every adapter
+    ///
+    /// Relaxed reads skip the vesting account owner and recipient checks, and accounts not owned
+    /// by an allowlisted program fall back to `locker_kind`.
+    pub fn with_relaxed_checks(mut self, relax_checks: bool) -> Self {
+        self.streamflow.relax_checks = relax_checks;
+        if let Some(bonfida) = self.bonfida.as_mut() {
+            bonfida.relax_checks = relax_checks;
+        }
+        self.jup_lock.relax_checks = relax_checks;
+        if let Some(stake) = self.stake.as_mut() {
+            stake.relax_checks = relax_checks;
+        }
+        self.relax_checks = relax_checks;
+        self
+    }
+
+    /// The source selected by the vault's `locker_kind`
+    pub fn source(&self) -> Result<&dyn LockedAmountSource> {
+        Ok(match self.kind {
+            LockerKind::Streamflow => &self.streamflow,
+            LockerKind::Bonfida => self
+                .bonfida
+                .as_ref()
+                .ok_or(FeeRouterError::InvalidLockerConfig)?,
+            LockerKind::JupLock => &self.jup_lock,
+            LockerKind::Stake => self
+                .stake
+                .as_ref()
+                .ok_or(FeeRouterError::InvalidLockerConfig)?,
+            LockerKind::Oracle => self
+                .oracle
+                .as_ref()
+                .ok_or(FeeRouterError::InvalidLockerConfig)?,
+        })
+    }
+
+    /// Read an investor's locked amount from the vault's vesting provider
+    ///
+    /// With an allowlist, the account's owner must be an allowlisted program and picks the
+    /// adapter, so one vault can mix providers. Without one, `locker_kind` decides.
+    pub fn read_investor_locked(
+        &self,
+        account_info: &AccountInfo,
+        investor_data: &InvestorData,
+        at_ts: u64,
+    ) -> Result<StreamLocked> {
+        if !self.allowlist.is_empty() && !account_info.data_is_empty() {
+            if let Some(entry) = self
+                .allowlist
+                .iter()
+                .find(|entry| entry.program_id == *account_info.owner)
+            {
+                let read = LockerReadContext {
+                    at_ts,
+                    base_mint: self.streamflow.base_mint,
+                    stake_layout: self.stake_layout,
+                    oracle: self.oracle.as_ref(),
+                    relax_checks: self.relax_checks,
+                };
+                return entry.read_locked(account_info, investor_data, &read);
+            }
+            // Local mocks aren't owned by a real locker program; fall back to locker_kind
+            require!(self.relax_checks, FeeRouterError::LockerNotAllowed);
+        }
+        self.source()?
+            .read_locked(account_info, investor_data, at_ts)
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+
+/// SPL Memo program ID (v2)
+pub const MEMO_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Memo text attached to payouts so recipients can attribute the transfer
+pub fn payout_memo(vault_seed: &str, day_epoch: u64) -> String {
+    format!("meteor-route vault_seed={} day_epoch={}", vault_seed, day_epoch)
+}
+
+/// Build a signer-less memo instruction
+pub fn memo_ix(text: &str) -> Instruction {
+    Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: vec![],
+        data: text.as_bytes().to_vec(),
+    }
+}
+
+/// Log the payout memo via CPI when `memo_program` is set
+///
+/// Called right before the payout transfer, so it also satisfies Token-2022's
+/// required-memo extension on the destination account.
+pub fn emit_payout_memo(memo_program: Option<&AccountInfo>, vault_seed: &str, day_epoch: u64) -> Result<()> {
+    let Some(memo_program) = memo_program else {
+        return Ok(());
+    };
+    invoke(&memo_ix(&payout_memo(vault_seed, day_epoch)), std::slice::from_ref(memo_program))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_ix() {
+        let ix = memo_ix(&payout_memo("vault", 19_876));
+        assert_eq!(ix.program_id, MEMO_PROGRAM_ID);
+        assert!(ix.accounts.is_empty());
+        assert_eq!(ix.data, b"meteor-route vault_seed=vault day_epoch=19876".to_vec());
+    }
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::Mint as MintState,
+};
+use crate::error::FeeRouterError;
+
+/// Quote mint risks a policy can accept at `initialize_policy` (`PolicyPda.allowed_quote_mint_risks`)
+pub mod mint_risk {
+    /// Freeze authority set; it can freeze the treasury or investor accounts
+    pub const FREEZE_AUTHORITY: u8 = 1 << 0;
+    /// Token-2022 permanent delegate; it can move tokens out of the treasury
+    pub const PERMANENT_DELEGATE: u8 = 1 << 1;
+    /// Token-2022 pausable; pausing halts every payout transfer
+    pub const PAUSABLE: u8 = 1 << 2;
+    /// Token-2022 confidential transfers or confidential mint/burn; balances can hide from the crank
+    pub const CONFIDENTIAL: u8 = 1 << 3;
+    pub const ALL: u8 = FREEZE_AUTHORITY | PERMANENT_DELEGATE | PAUSABLE | CONFIDENTIAL;
+}
+
+/// `ExtensionType::Pausable`; the pinned spl-token-2022 predates it
+const PAUSABLE_EXTENSION: u16 = 26;
+
+/// Risks present on a mint, as `mint_risk` bits
+///
+/// Works on SPL Token and Token-2022 mint data. Extensions are read from the raw TLV entries so
+/// types newer than the pinned spl-token-2022 are still recognized.
+pub fn mint_risks(mint_data: &[u8]) -> Result<u8> {
+    let mint = StateWithExtensions::<MintState>::unpack(mint_data)?;
+    let mut risks = 0;
+    if mint.base.freeze_authority.is_some() {
+        risks |= mint_risk::FREEZE_AUTHORITY;
+    }
+    let tlv = mint.get_tlv_data();
+    let mut offset = 0;
+    while offset + 4 <= tlv.len() {
+        let extension_type = u16::from_le_bytes([tlv[offset], tlv[offset + 1]]);
+        if extension_type == ExtensionType::Uninitialized as u16 {
+            break;
+        }
+        let len = u16::from_le_bytes([tlv[offset + 2], tlv[offset + 3]]) as usize;
+        risks |= match extension_type {
+            t if t == ExtensionType::PermanentDelegate as u16 => mint_risk::PERMANENT_DELEGATE,
+            PAUSABLE_EXTENSION => mint_risk::PAUSABLE,
+            t if t == ExtensionType::ConfidentialTransferMint as u16
+                || t == ExtensionType::ConfidentialTransferFeeConfig as u16
+                || t == ExtensionType::ConfidentialMintBurn as u16 =>
+            {
+                mint_risk::CONFIDENTIAL
+            }
+            _ => 0,
+        };
+        offset += 4 + len;
+    }
+    Ok(risks)
+}
+
+/// Reject a quote mint carrying any risk outside `allowed`
+pub fn require_allowed_mint_risks(mint: &AccountInfo, allowed: u8) -> Result<()> {
+    let risks = mint_risks(&mint.try_borrow_data()?)?;
+    let disallowed = risks & !allowed;
+    if disallowed != 0 {
+        msg!("Quote mint risks {:#06b} not allowed (allowed {:#06b})", disallowed, allowed);
+        return err!(FeeRouterError::QuoteMintRiskNotAllowed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::{program_option::COption, program_pack::Pack};
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        permanent_delegate::PermanentDelegate, BaseStateWithExtensionsMut, StateWithExtensionsMut,
+    };
+
+    fn spl_mint(freeze_authority: Option<Pubkey>) -> Vec<u8> {
+        let mut data = vec![0u8; MintState::LEN];
+        let mint = MintState {
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: freeze_authority.map_or(COption::None, COption::Some),
+            ..MintState::default()
+        };
+        MintState::pack(mint, &mut data).unwrap();
+        data
+    }
+
+    fn mint_with_permanent_delegate() -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<MintState>(&[ExtensionType::PermanentDelegate]).unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        let delegate = state.init_extension::<PermanentDelegate>(true).unwrap();
+        delegate.delegate = Some(Pubkey::new_unique()).try_into().unwrap();
+        state.base.decimals = 6;
+        state.base.is_initialized = true;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_mint_risks() {
+        assert_eq!(mint_risks(&spl_mint(None)).unwrap(), 0);
+        assert_eq!(mint_risks(&spl_mint(Some(Pubkey::new_unique()))).unwrap(), mint_risk::FREEZE_AUTHORITY);
+        assert_eq!(mint_risks(&mint_with_permanent_delegate()).unwrap(), mint_risk::PERMANENT_DELEGATE);
+
+        // A pausable entry (type 26, 33-byte config) appended to the TLV data
+        let mut data = mint_with_permanent_delegate();
+        data.extend_from_slice(&PAUSABLE_EXTENSION.to_le_bytes());
+        data.extend_from_slice(&33u16.to_le_bytes());
+        data.extend_from_slice(&[0u8; 33]);
+        assert_eq!(
+            mint_risks(&data).unwrap(),
+            mint_risk::PERMANENT_DELEGATE | mint_risk::PAUSABLE
+        );
+    }
+}
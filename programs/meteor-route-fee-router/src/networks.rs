@@ -0,0 +1,92 @@
+//! Streamflow and CP-AMM program IDs per cluster
+//!
+//! The cluster is chosen at runtime through `NetworkConfig`, written by the upgrade authority, rather
+//! than by a build feature, so the same binary can be deployed to mainnet, devnet or a fork. Until
+//! the config exists the router reads Streamflow at its mainnet ID and takes any CP-AMM program.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+/// Mainnet-beta program IDs
+pub mod mainnet {
+    use super::*;
+
+    pub const STREAMFLOW_PROGRAM_ID: Pubkey = crate::streamflow::STREAMFLOW_PROGRAM_ID;
+    /// Meteora DAMM v2
+    pub const CP_AMM_PROGRAM_ID: Pubkey = pubkey!("cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG");
+}
+
+/// Devnet program IDs
+pub mod devnet {
+    use super::*;
+
+    pub const STREAMFLOW_PROGRAM_ID: Pubkey = pubkey!("HqDGZjaVRXJ9MGRQEw7qDc2rAr6iH1n1kAQdCZaCMfMZ");
+    /// Meteora deploys DAMM v2 at the same address on devnet
+    pub const CP_AMM_PROGRAM_ID: Pubkey = pubkey!("cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG");
+}
+
+/// Cluster a deployment serves (`NetworkConfig.network`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Devnet,
+}
+
+impl Network {
+    /// The cluster's canonical program IDs
+    pub fn programs(&self) -> NetworkPrograms {
+        match self {
+            Network::Mainnet => NetworkPrograms {
+                streamflow: mainnet::STREAMFLOW_PROGRAM_ID,
+                cp_amm: mainnet::CP_AMM_PROGRAM_ID,
+            },
+            Network::Devnet => NetworkPrograms {
+                streamflow: devnet::STREAMFLOW_PROGRAM_ID,
+                cp_amm: devnet::CP_AMM_PROGRAM_ID,
+            },
+        }
+    }
+}
+
+/// Program IDs the router trusts on a deployment's cluster
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetworkPrograms {
+    pub streamflow: Pubkey,
+    pub cp_amm: Pubkey,
+}
+
+impl NetworkPrograms {
+    pub const LEN: usize = 32 + 32;
+
+    /// Whether both programs are set and are different programs
+    pub fn is_valid(&self) -> bool {
+        self.streamflow != Pubkey::default()
+            && self.cp_amm != Pubkey::default()
+            && self.streamflow != self.cp_amm
+    }
+
+    /// Whether these are a known cluster's programs rather than a fork's
+    pub fn is_canonical(&self) -> bool {
+        [Network::Mainnet, Network::Devnet]
+            .iter()
+            .any(|network| network.programs() == *self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_programs() {
+        let devnet = Network::Devnet.programs();
+        assert!(devnet.is_valid() && devnet.is_canonical());
+        assert_eq!(Network::default().programs().streamflow, crate::streamflow::STREAMFLOW_PROGRAM_ID);
+        assert_ne!(devnet.streamflow, Network::Mainnet.programs().streamflow);
+
+        let fork = NetworkPrograms { cp_amm: crate::cp_amm::CP_AMM_PROGRAM_ID, ..devnet };
+        assert!(fork.is_valid() && !fork.is_canonical());
+        assert!(!NetworkPrograms { streamflow: Pubkey::default(), ..devnet }.is_valid());
+        assert!(!NetworkPrograms { streamflow: devnet.cp_amm, ..devnet }.is_valid());
+    }
+}
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use crate::{
+    error::FeeRouterError,
+    locker::{LockedAmountSource, StreamIgnoredReason, StreamLocked},
+    state::{OracleLockedPda, PolicyPda},
+    InvestorData,
+};
+
+/// Locked amounts published by the policy's lock oracle as `OracleLockedPda` accounts
+pub struct OracleLockSource {
+    pub vault_seed: String,
+    pub oracle: Pubkey,
+    pub max_age_secs: u64,
+}
+
+impl OracleLockSource {
+    /// Set when the policy has a lock oracle configured
+    pub fn from_policy(policy: &PolicyPda) -> Option<Self> {
+        (policy.lock_oracle != Pubkey::default()).then(|| Self {
+            vault_seed: policy.vault_seed.clone(),
+            oracle: policy.lock_oracle,
+            max_age_secs: policy.lock_oracle_max_age_secs,
+        })
+    }
+
+    /// Locked amount of a published record, if it is this vault's, for the investor and fresh at `at_ts`
+    pub fn check(&self, record: &OracleLockedPda, investor: &Pubkey, at_ts: u64) -> Result<u64> {
+        require!(
+            record.vault_seed == self.vault_seed && record.investor == *investor,
+            FeeRouterError::StreamRecipientMismatch
+        );
+        require!(
+            record.oracle == self.oracle && at_ts.saturating_sub(record.as_of_ts) <= self.max_age_secs,
+            FeeRouterError::OracleLockStale
+        );
+        Ok(record.locked_amount)
+    }
+}
+
+impl LockedAmountSource for OracleLockSource {
+    fn program_id(&self) -> Pubkey {
+        crate::ID
+    }
+
+    fn read_locked(
+        &self,
+        account_info: &AccountInfo,
+        investor_data: &InvestorData,
+        at_ts: u64,
+    ) -> Result<StreamLocked> {
+        require_keys_eq!(
+            account_info.key(),
+            investor_data.stream,
+            FeeRouterError::StreamKeyMismatch
+        );
+
+        // Not published yet
+        if account_info.data_is_empty() {
+            return Ok(StreamLocked::Ignored(StreamIgnoredReason::AccountClosed));
+        }
+
+        require_keys_eq!(
+            *account_info.owner,
+            crate::ID,
+            FeeRouterError::InvalidLockerProgram
+        );
+
+        let record = OracleLockedPda::try_deserialize(&mut &account_info.try_borrow_data()?[..])
+            .map_err(|_| FeeRouterError::StreamDeserializeFailed)?;
+        Ok(StreamLocked::Locked(self.check(&record, &investor_data.investor, at_ts)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let (oracle, investor) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let source = OracleLockSource {
+            vault_seed: "vault".to_string(),
+            oracle,
+            max_age_secs: 3_600,
+        };
+        let record = OracleLockedPda {
+            vault_seed: "vault".to_string(),
+            investor,
+            locked_amount: 5_000,
+            as_of_ts: 10_000,
+            oracle,
+            updated_at: 10_000,
+        };
+        assert_eq!(source.check(&record, &investor, 13_600).unwrap(), 5_000);
+        // Reads before as_of_ts (e.g. an earlier snapshot time) are fresh
+        assert_eq!(source.check(&record, &investor, 9_000).unwrap(), 5_000);
+        assert!(source.check(&record, &investor, 13_601).is_err());
+        assert!(source.check(&record, &Pubkey::new_unique(), 10_000).is_err());
+
+        // A rotated oracle invalidates what the old one published
+        let rotated = OracleLockSource { oracle: Pubkey::new_unique(), ..source };
+        assert!(rotated.check(&record, &investor, 10_000).is_err());
+    }
+}
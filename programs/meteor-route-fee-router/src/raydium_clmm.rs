@@ -0,0 +1,277 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use crate::{
+    error::FeeRouterError,
+    layout::{discriminator, read_i32, read_pubkey, read_u16},
+};
+
+/// Raydium CLMM program ID (mainnet)
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+
+/// SPL Memo program ID (required by decrease_liquidity_v2)
+pub use crate::memo::MEMO_PROGRAM_ID;
+
+/// Ticks per Raydium tick array
+pub const TICK_ARRAY_SIZE: i32 = 60;
+
+/// Raydium tick bounds
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+
+/// Accounts the Raydium claim path expects at the front of remaining_accounts:
+/// [protocol_position, tick_array_lower, tick_array_upper, clmm_program, token_program_2022, memo_program]
+pub const CLAIM_REMAINING_ACCOUNTS: usize = 6;
+
+// PoolState field offsets (discriminator included)
+const POOL_TOKEN_MINT_0: usize = 73;
+const POOL_TOKEN_MINT_1: usize = 105;
+const POOL_TOKEN_VAULT_0: usize = 137;
+const POOL_TOKEN_VAULT_1: usize = 169;
+const POOL_TICK_SPACING: usize = 235;
+const POOL_TICK_CURRENT: usize = 269;
+const POOL_MIN_LEN: usize = 273;
+
+// PersonalPositionState field offsets (discriminator included)
+const POSITION_NFT_MINT: usize = 9;
+const POSITION_POOL_ID: usize = 41;
+const POSITION_TICK_LOWER: usize = 73;
+const POSITION_TICK_UPPER: usize = 77;
+const POSITION_MIN_LEN: usize = 81;
+
+/// Fields of a Raydium CLMM `PoolState` the router needs
+#[derive(Clone, Copy, Debug)]
+pub struct ClmmPoolView {
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub tick_spacing: u16,
+    pub tick_current: i32,
+}
+
+/// Fields of a Raydium CLMM `PersonalPositionState` the router needs
+#[derive(Clone, Copy, Debug)]
+pub struct PersonalPositionView {
+    pub nft_mint: Pubkey,
+    pub pool_id: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+}
+
+/// Check owner, discriminator and minimum size of a Raydium CLMM account
+fn check_clmm_account(info: &AccountInfo, data: &[u8], account_name: &[u8], min_len: usize) -> Result<()> {
+    require_keys_eq!(*info.owner, RAYDIUM_CLMM_PROGRAM_ID, FeeRouterError::InvalidRaydiumProgram);
+    require!(
+        data.len() >= min_len && data[..8] == discriminator(account_name),
+        FeeRouterError::PoolAccountMismatch
+    );
+    Ok(())
+}
+
+/// Read a Raydium CLMM `PoolState` account
+pub fn read_pool(info: &AccountInfo) -> Result<ClmmPoolView> {
+    let data = info.try_borrow_data()?;
+    check_clmm_account(info, &data, b"account:PoolState", POOL_MIN_LEN)?;
+    Ok(ClmmPoolView {
+        token_mint_0: read_pubkey(&data, POOL_TOKEN_MINT_0),
+        token_mint_1: read_pubkey(&data, POOL_TOKEN_MINT_1),
+        token_vault_0: read_pubkey(&data, POOL_TOKEN_VAULT_0),
+        token_vault_1: read_pubkey(&data, POOL_TOKEN_VAULT_1),
+        tick_spacing: read_u16(&data, POOL_TICK_SPACING),
+        tick_current: read_i32(&data, POOL_TICK_CURRENT),
+    })
+}
+
+/// Read a Raydium CLMM `PersonalPositionState` account
+pub fn read_personal_position(info: &AccountInfo) -> Result<PersonalPositionView> {
+    let data = info.try_borrow_data()?;
+    check_clmm_account(info, &data, b"account:PersonalPositionState", POSITION_MIN_LEN)?;
+    Ok(PersonalPositionView {
+        nft_mint: read_pubkey(&data, POSITION_NFT_MINT),
+        pool_id: read_pubkey(&data, POSITION_POOL_ID),
+        tick_lower_index: read_i32(&data, POSITION_TICK_LOWER),
+        tick_upper_index: read_i32(&data, POSITION_TICK_UPPER),
+    })
+}
+
+/// Validate a quote-only Raydium CLMM tick range
+///
+/// Same rule as Whirlpool: ticks are multiples of tick_spacing and the range sits strictly on
+/// one side of the current tick:
+/// - If quote is token 1, the range must be above the current tick (tick_lower > tick_current + buffer)
+/// - If quote is token 0, the range must be below the current tick (tick_upper <= tick_current - buffer)
+pub fn validate_quote_only_ticks(
+    pool: &ClmmPoolView,
+    tick_lower: i32,
+    tick_upper: i32,
+    quote_mint: &Pubkey,
+    buffer_ticks: u32,
+) -> Result<()> {
+    let spacing = pool.tick_spacing as i32;
+    require!(
+        spacing > 0
+            && tick_lower < tick_upper
+            && tick_lower >= MIN_TICK
+            && tick_upper <= MAX_TICK
+            && tick_lower % spacing == 0
+            && tick_upper % spacing == 0,
+        FeeRouterError::InvalidTickRange
+    );
+
+    let is_token_0_quote = pool.token_mint_0 == *quote_mint;
+    let is_token_1_quote = pool.token_mint_1 == *quote_mint;
+    require!(
+        is_token_0_quote || is_token_1_quote,
+        FeeRouterError::InvalidQuoteMint
+    );
+
+    let current = pool.tick_current as i64;
+    let buffer = buffer_ticks as i64;
+    if is_token_1_quote {
+        require!(
+            tick_lower as i64 > current + buffer,
+            FeeRouterError::PositionNotQuoteOnly
+        );
+    } else {
+        require!(
+            tick_upper as i64 <= current - buffer,
+            FeeRouterError::PositionNotQuoteOnly
+        );
+    }
+
+    msg!(
+        "Raydium CLMM quote-only validation passed. Quote mint: {}, range: [{}, {}], current: {}",
+        quote_mint,
+        tick_lower,
+        tick_upper,
+        pool.tick_current
+    );
+
+    Ok(())
+}
+
+/// Start index of the tick array holding `tick`
+pub fn tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_per_array = tick_spacing as i32 * TICK_ARRAY_SIZE;
+    tick.div_euclid(ticks_per_array) * ticks_per_array
+}
+
+/// Derive the tick array PDA starting at `start_index`
+pub fn derive_tick_array(pool: &Pubkey, start_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array", pool.as_ref(), &start_index.to_be_bytes()],
+        &RAYDIUM_CLMM_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Derive the protocol position PDA for a tick range
+pub fn derive_protocol_position(pool: &Pubkey, tick_lower: i32, tick_upper: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"position",
+            pool.as_ref(),
+            &tick_lower.to_be_bytes(),
+            &tick_upper.to_be_bytes(),
+        ],
+        &RAYDIUM_CLMM_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Derive the personal position PDA for a position NFT mint
+pub fn derive_personal_position(nft_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"position", nft_mint.as_ref()], &RAYDIUM_CLMM_PROGRAM_ID).0
+}
+
+/// Accounts for the Raydium `decrease_liquidity_v2` instruction
+pub struct DecreaseLiquidityAccounts {
+    pub nft_owner: Pubkey,
+    pub nft_account: Pubkey,
+    pub personal_position: Pubkey,
+    pub pool_state: Pubkey,
+    pub protocol_position: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub tick_array_lower: Pubkey,
+    pub tick_array_upper: Pubkey,
+    pub recipient_token_account_0: Pubkey,
+    pub recipient_token_account_1: Pubkey,
+    pub token_program: Pubkey,
+    pub token_program_2022: Pubkey,
+    pub vault_0_mint: Pubkey,
+    pub vault_1_mint: Pubkey,
+}
+
+/// Build a fee-only collect: `decrease_liquidity_v2` with zero liquidity
+///
+/// Raydium CLMM has no standalone fee collection; decreasing by zero settles and transfers
+/// the position's owed fees.
+pub fn collect_fee_ix(accounts: &DecreaseLiquidityAccounts) -> Instruction {
+    let mut data = discriminator(b"global:decrease_liquidity_v2").to_vec();
+    data.extend_from_slice(&0u128.to_le_bytes()); // liquidity
+    data.extend_from_slice(&0u64.to_le_bytes()); // amount_0_min
+    data.extend_from_slice(&0u64.to_le_bytes()); // amount_1_min
+
+    Instruction {
+        program_id: RAYDIUM_CLMM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.nft_owner, true),
+            AccountMeta::new_readonly(accounts.nft_account, false),
+            AccountMeta::new(accounts.personal_position, false),
+            AccountMeta::new(accounts.pool_state, false),
+            AccountMeta::new(accounts.protocol_position, false),
+            AccountMeta::new(accounts.token_vault_0, false),
+            AccountMeta::new(accounts.token_vault_1, false),
+            AccountMeta::new(accounts.tick_array_lower, false),
+            AccountMeta::new(accounts.tick_array_upper, false),
+            AccountMeta::new(accounts.recipient_token_account_0, false),
+            AccountMeta::new(accounts.recipient_token_account_1, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+            AccountMeta::new_readonly(accounts.token_program_2022, false),
+            AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+            AccountMeta::new_readonly(accounts.vault_0_mint, false),
+            AccountMeta::new_readonly(accounts.vault_1_mint, false),
+        ],
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_only_ticks() {
+        let mint_0 = Pubkey::new_unique();
+        let mint_1 = Pubkey::new_unique();
+        let pool = ClmmPoolView {
+            token_mint_0: mint_0,
+            token_mint_1: mint_1,
+            token_vault_0: Pubkey::default(),
+            token_vault_1: Pubkey::default(),
+            tick_spacing: 10,
+            tick_current: 5_005,
+        };
+
+        assert!(validate_quote_only_ticks(&pool, 5_010, 5_100, &mint_1, 0).is_ok());
+        assert!(validate_quote_only_ticks(&pool, 5_000, 5_100, &mint_1, 0).is_err());
+        assert!(validate_quote_only_ticks(&pool, 4_900, 5_000, &mint_0, 0).is_ok());
+        assert!(validate_quote_only_ticks(&pool, 4_900, 5_010, &mint_0, 0).is_err());
+        assert!(validate_quote_only_ticks(&pool, 5_015, 5_100, &mint_1, 0).is_err());
+
+        // Buffer: the range must clear the current tick by buffer_ticks
+        assert!(validate_quote_only_ticks(&pool, 5_010, 5_100, &mint_1, 10).is_err());
+        assert!(validate_quote_only_ticks(&pool, 5_020, 5_100, &mint_1, 10).is_ok());
+        assert!(validate_quote_only_ticks(&pool, 4_900, 5_000, &mint_0, 10).is_err());
+        assert!(validate_quote_only_ticks(&pool, 4_900, 4_990, &mint_0, 10).is_ok());
+    }
+
+    #[test]
+    fn test_tick_array_start_index() {
+        // 10 * 60 = 600 ticks per array
+        assert_eq!(tick_array_start_index(599, 10), 0);
+        assert_eq!(tick_array_start_index(600, 10), 600);
+        assert_eq!(tick_array_start_index(-1, 10), -600);
+    }
+}
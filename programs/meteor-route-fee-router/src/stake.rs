@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+use crate::{
+    error::FeeRouterError,
+    layout::read_u64,
+    locker::{LockedAmountSource, StreamIgnoredReason, StreamLocked},
+    InvestorData,
+};
+
+/// Where a staking program keeps the staker and staked amount in its per-stake account
+///
+/// Staking programs don't share an account format, so the policy describes the one its program
+/// uses instead of the router shipping an adapter per program.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct StakeLayout {
+    /// Leading account discriminator (all zero = not checked)
+    pub discriminator: [u8; 8],
+    /// Offset of the staker wallet (Pubkey)
+    pub staker_offset: u32,
+    /// Offset of the staked amount (u64, little-endian)
+    pub amount_offset: u32,
+}
+
+impl StakeLayout {
+    pub const LEN: usize = 8 + 4 + 4;
+
+    /// Whether the layout describes a usable account: fields clear of the discriminator and of each other
+    pub fn is_valid(&self) -> bool {
+        let header = if self.discriminator == [0u8; 8] { 0 } else { 8 };
+        let staker = self.staker_offset as u64..self.staker_offset as u64 + 32;
+        let amount = self.amount_offset as u64..self.amount_offset as u64 + 8;
+        staker.start >= header
+            && amount.start >= header
+            && (staker.end <= amount.start || amount.end <= staker.start)
+    }
+
+    /// Smallest account the layout can be read from
+    pub fn min_len(&self) -> usize {
+        (self.staker_offset as usize + 32).max(self.amount_offset as usize + 8)
+    }
+
+    /// Staker and staked amount of a stake account
+    pub fn parse(&self, data: &[u8]) -> Result<(Pubkey, u64)> {
+        require!(data.len() >= self.min_len(), FeeRouterError::StreamDeserializeFailed);
+        require!(
+            self.discriminator == [0u8; 8] || data[..8] == self.discriminator,
+            FeeRouterError::StreamDeserializeFailed
+        );
+        let offset = self.staker_offset as usize;
+        let staker = Pubkey::try_from(&data[offset..offset + 32]).map_err(|_| FeeRouterError::StreamDeserializeFailed)?;
+        Ok((staker, read_u64(data, self.amount_offset as usize)))
+    }
+}
+
+/// Staking program stake accounts as a locked-amount source
+///
+/// The weight is the amount staked when the snapshot is taken; there is no schedule, so `at_ts`
+/// is ignored. Unstaking lowers the weight from the next day's snapshot.
+pub struct StakeSource {
+    pub program_id: Pubkey,
+    pub layout: StakeLayout,
+    /// Local-testing bypass from `LockerTestConfig`; skips the staker check
+    pub relax_checks: bool,
+}
+
+impl LockedAmountSource for StakeSource {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn read_locked(
+        &self,
+        account_info: &AccountInfo,
+        investor_data: &InvestorData,
+        _at_ts: u64,
+    ) -> Result<StreamLocked> {
+        require_keys_eq!(
+            account_info.key(),
+            investor_data.stream,
+            FeeRouterError::StreamKeyMismatch
+        );
+
+        if account_info.data_is_empty() {
+            return Ok(StreamLocked::Ignored(StreamIgnoredReason::AccountClosed));
+        }
+
+        require_keys_eq!(
+            *account_info.owner,
+            self.program_id,
+            FeeRouterError::InvalidLockerProgram
+        );
+
+        let (staker, amount) = self.layout.parse(&account_info.try_borrow_data()?)?;
+        if !self.relax_checks {
+            require_keys_eq!(
+                staker,
+                investor_data.investor,
+                FeeRouterError::StreamRecipientMismatch
+            );
+        }
+
+        Ok(StreamLocked::Locked(amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISCRIMINATOR: [u8; 8] = [7, 1, 2, 3, 4, 5, 6, 7];
+
+    fn layout() -> StakeLayout {
+        StakeLayout {
+            discriminator: DISCRIMINATOR,
+            staker_offset: 8,
+            amount_offset: 72,
+        }
+    }
+
+    fn stake_data(staker: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 96];
+        data[..8].copy_from_slice(&DISCRIMINATOR);
+        data[8..40].copy_from_slice(staker.as_ref());
+        data[72..80].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse() {
+        let staker = Pubkey::new_unique();
+        let data = stake_data(&staker, 5_000);
+        assert_eq!(layout().parse(&data).unwrap(), (staker, 5_000));
+
+        // Wrong discriminator and short accounts are rejected
+        let mut wrong = data.clone();
+        wrong[0] = 0;
+        assert!(layout().parse(&wrong).is_err());
+        assert!(layout().parse(&data[..79]).is_err());
+
+        // A zero discriminator skips the check
+        let unchecked = StakeLayout {
+            discriminator: [0u8; 8],
+            ..layout()
+        };
+        assert_eq!(unchecked.parse(&wrong).unwrap(), (staker, 5_000));
+    }
+
+    #[test]
+    fn test_read_locked() {
+        let program_id = Pubkey::new_unique();
+        let source = StakeSource {
+            program_id,
+            layout: layout(),
+            relax_checks: false,
+        };
+        let investor = InvestorData {
+            stream: Pubkey::new_unique(),
+            investor: Pubkey::new_unique(),
+            index: 0,
+        };
+        let read = |owner: &Pubkey, mut data: Vec<u8>| {
+            let mut lamports = 1;
+            let info = AccountInfo::new(&investor.stream, false, false, &mut lamports, &mut data, owner, false, 0);
+            source.read_locked(&info, &investor, 0)
+        };
+
+        assert_eq!(read(&program_id, stake_data(&investor.investor, 7_000)).unwrap(), StreamLocked::Locked(7_000));
+        assert_eq!(
+            read(&program_id, Vec::new()).unwrap(),
+            StreamLocked::Ignored(StreamIgnoredReason::AccountClosed)
+        );
+        // Someone else's stake, or an account of another program, is rejected
+        assert!(read(&program_id, stake_data(&Pubkey::new_unique(), 7_000)).is_err());
+        assert!(read(&Pubkey::new_unique(), stake_data(&investor.investor, 7_000)).is_err());
+    }
+
+    #[test]
+    fn test_layout_validity() {
+        assert!(layout().is_valid());
+        assert!(!StakeLayout::default().is_valid());
+        // Fields inside the discriminator
+        assert!(!StakeLayout { staker_offset: 4, ..layout() }.is_valid());
+        // Overlapping staker and amount
+        assert!(!StakeLayout { amount_offset: 32, ..layout() }.is_valid());
+        // Adjacent fields are fine
+        assert!(StakeLayout { amount_offset: 40, ..layout() }.is_valid());
+        assert_eq!(layout().min_len(), 80);
+    }
+}
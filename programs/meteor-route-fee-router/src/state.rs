@@ -1,4 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::locker::{LockerEntry, LockerKind};
+use crate::attestation::{AttestationGate, AttestationLayout};
+use crate::cp_amm_entry_points::CpAmmEntryPoints;
+use crate::networks::{Network, NetworkPrograms};
+use crate::stake::StakeLayout;
+use crate::streamflow::STREAMFLOW_PROGRAM_ID;
+use crate::events::CrankMismatch;
 
 // NOTE: Account context structs are defined in `src/instructions/*` and not duplicated here.
 
@@ -15,6 +25,57 @@ pub struct PolicyPda {
     pub quote_mint: Pubkey,               // quote token mint
     pub base_mint: Pubkey,                // base token mint
     pub pool_pubkey: Pubkey,              // CP-AMM pool
+    pub cp_amm_program_id: Pubkey,        // CP-AMM (DAMM v2) program the pool lives in, fixed at init
+    pub day_gate_grace_secs: u64,         // early-start allowance on the 24h gate
+    pub min_daily_claim_lamports: u64,    // below this, the day's claim rolls over (0 = off)
+    pub roll_capped_excess: bool,         // capped-out investor share rolls to next day, not creator
+    pub bonfida_program_id: Pubkey,       // Bonfida token-vesting program (default = disabled)
+    pub locker_kind: LockerKind,          // vesting provider investor locks are read from
+    pub locker_allowlist: Vec<LockerEntry>, // accepted locker programs (empty = locker_kind only)
+    pub pool_kind: PoolKind,              // AMM backend the honorary position must be opened in
+    pub quote_only_buffer_ticks: u32,     // required gap between the position range and the live price
+    pub payout_memos: bool,               // attach an SPL memo (vault_seed, day_epoch) to each payout
+    pub unwrap_wsol_creator: bool,        // wSOL quote: deliver the creator payout as native SOL
+    pub continue_on_failure: bool,        // record unpayable investors in FailedPayoutsPda instead of aborting
+    pub track_paid_investors: bool,       // enforce exactly-once payouts via PaidBitmapPda
+    pub static_accounts: Option<StaticAccounts>, // crank accounts pinned by cache_static_accounts
+    pub force_finalize_after_secs: u64,   // stalled day may be force-finalized this long after it started (0 = off)
+    pub crank_lease_slots: u64,           // length of a crank lease from acquire_crank_lease (0 = leases off)
+    pub batch_investor_paid_events: bool, // one InvestorPaidBatch per page instead of InvestorPaid per payout
+    pub creator_vesting_secs: u64,        // vest the creator payout through a Streamflow stream this long (0 = transfer)
+    pub compound_bps: u16,                // share of each claim added back to the CP-AMM position as liquidity (0 = off)
+    pub buyback_burn_creator: bool,       // swap the creator remainder to base in the pool and burn it
+    pub buyback_max_slippage_bps: u16,    // largest shortfall of a buyback against the spot price, fees included
+    pub referral_wallet: Pubkey,          // launchpad paid a share of the creator remainder (default = none)
+    pub referral_bps: u16,                // share of the creator remainder paid to referral_wallet
+    pub paused: bool,                     // distribute_fees / force_finalize_day refuse to run (set_policy_paused)
+    pub stake_program_id: Pubkey,         // staking program read when locker_kind = Stake (default = disabled)
+    pub stake_layout: StakeLayout,        // where stake accounts keep the staker and staked amount
+    pub day_gate_slots: u64,              // gate new days on slots since the day started instead of the UTC day (0 = off)
+    pub epoch_offset_secs: Option<u32>,   // days start at this offset past 00:00 UTC, with no 24h check (None = 24h gate)
+    pub tranches_per_day: u8,             // evenly spaced distributions per day, each with its share of the daily amounts
+    pub payout_overrides: bool,           // per-investor min payouts in PayoutOverridesPda (set by initialize_payout_overrides)
+    pub weight_overrides: bool,           // fixed investor weights in WeightOverridesPda (set by initialize_weight_overrides)
+    pub max_weight_bps: u16,              // largest share of the day's investor pool one investor may take (0 = off)
+    pub max_investors_per_page: u16,      // distribute_fees rejects larger investor pages (0 = no limit)
+    pub max_pages_per_day: u32,           // distribute_fees rejects page indexes at or past this (0 = no limit)
+    pub crank_log: bool,                  // record every crank in CrankLogPda (set by initialize_crank_log)
+    pub attestation_program: Pubkey,      // investors need a live attestation from this program to be paid (default = off)
+    pub attestation_layout: AttestationLayout, // attestation PDA seed, discriminator and expiry offset
+    pub reward_routing: RewardRouting,    // where claim_rewards sends CP-AMM reward (liquidity mining) tokens
+    pub cp_amm_entry_points: CpAmmEntryPoints, // discriminators claim_position_fee / create_position CPIs are sent to
+    pub min_tick_distance_from_price: u32, // gap the range must keep from the live price at init and on every CP-AMM claim (0 = off)
+    pub cap_carry_days: u8,               // days of unused daily cap a quiet stretch can bank for later days (0 = off)
+    pub payout_to_stream_escrow: bool,    // top up investors' Streamflow streams instead of paying their quote ATAs
+    pub payout_dispute_window_secs: u64,  // hold payouts in per-investor escrow this long, open to claw-back (0 = off)
+    pub fund_missing_creator_ata: bool,   // create a missing creator quote ATA at finalize instead of deferring
+    pub allowed_quote_mint_risks: u8,     // mint_risk bits the quote mint was allowed to carry at initialize_policy
+    pub hook_program: Pubkey,             // program called with the day's totals after finalize (default = off)
+    pub lock_oracle: Pubkey,              // key allowed to publish OracleLockedPda amounts (default = off)
+    pub lock_oracle_max_age_secs: u64,    // oldest as_of_ts an oracle amount may have when read
+    pub unordered_pages: bool,            // accept a committed day's pages in any order (ProgressPda::page_bitmap)
+    pub param_schedule: Vec<ScheduledParams>, // future-dated daily cap / min payout values, by effective day
+    pub unattested_routing: UnattestedRouting, // where distribute_fees sends an unattested investor's share
     pub created_at: u64,
     pub updated_at: u64,
 }
@@ -31,13 +92,316 @@ impl PolicyPda {
         32 + // quote_mint
         32 + // base_mint
         32 + // pool_pubkey
+        32 + // cp_amm_program_id
+        8 + // day_gate_grace_secs
+        8 + // min_daily_claim_lamports
+        1 + // roll_capped_excess
+        32 + // bonfida_program_id
+        1 + // locker_kind
+        4 + PolicyPda::MAX_LOCKERS * LockerEntry::LEN + // locker_allowlist
+        1 + // pool_kind
+        4 + // quote_only_buffer_ticks
+        1 + // payout_memos
+        1 + // unwrap_wsol_creator
+        1 + // continue_on_failure
+        1 + // track_paid_investors
+        1 + StaticAccounts::LEN + // static_accounts
+        8 + // force_finalize_after_secs
+        8 + // crank_lease_slots
+        1 + // batch_investor_paid_events
+        8 + // creator_vesting_secs
+        2 + // compound_bps
+        1 + // buyback_burn_creator
+        2 + // buyback_max_slippage_bps
+        32 + // referral_wallet
+        2 + // referral_bps
+        1 + // paused
+        32 + // stake_program_id
+        StakeLayout::LEN + // stake_layout
+        8 + // day_gate_slots
+        1 + 4 + // epoch_offset_secs
+        1 + // tranches_per_day
+        1 + // payout_overrides
+        1 + // weight_overrides
+        2 + // max_weight_bps
+        2 + // max_investors_per_page
+        4 + // max_pages_per_day
+        1 + // crank_log
+        32 + // attestation_program
+        AttestationLayout::LEN + // attestation_layout
+        1 + // reward_routing
+        CpAmmEntryPoints::LEN + // cp_amm_entry_points
+        4 + // min_tick_distance_from_price
+        1 + // cap_carry_days
+        1 + // payout_to_stream_escrow
+        8 + // payout_dispute_window_secs
+        1 + // fund_missing_creator_ata
+        1 + // allowed_quote_mint_risks
+        32 + // hook_program
+        32 + // lock_oracle
+        8 + // lock_oracle_max_age_secs
+        1 + // unordered_pages
+        4 + PolicyPda::MAX_SCHEDULED_PARAMS * ScheduledParams::LEN + // param_schedule
+        1 + // unattested_routing
         8 + // created_at
         8 + // updated_at
         64; // padding for future fields
 
+    /// Upper bound for day_gate_grace_secs (the gate must stay meaningfully daily)
+    pub const MAX_DAY_GATE_GRACE_SECS: u64 = 3_600;
+
+    /// Bounds for a non-zero day_gate_slots (~20h to ~30h at 400ms slots)
+    pub const MIN_DAY_GATE_SLOTS: u64 = 180_000;
+    pub const MAX_DAY_GATE_SLOTS: u64 = 270_000;
+
+    /// Upper bound for tranches_per_day (hourly distributions)
+    pub const MAX_TRANCHES_PER_DAY: u8 = 24;
+
+    /// Lower bound for a non-zero max_weight_bps; bounds the largest locked amounts a snapshot tracks
+    pub const MIN_MAX_WEIGHT_BPS: u16 = 500;
+
+    /// Default force-finalize timeout: 20h after the day started
+    pub const DEFAULT_FORCE_FINALIZE_AFTER_SECS: u64 = 72_000;
+
+    /// Upper bound for force_finalize_after_secs
+    pub const MAX_FORCE_FINALIZE_AFTER_SECS: u64 = 86_400;
+
+    /// Default crank lease length (~60s at 400ms slots)
+    pub const DEFAULT_CRANK_LEASE_SLOTS: u64 = 150;
+
+    /// Upper bound for crank_lease_slots (~10 minutes), so a dead keeper only blocks briefly
+    pub const MAX_CRANK_LEASE_SLOTS: u64 = 1_500;
+
+    /// Upper bound for creator_vesting_secs (one year)
+    pub const MAX_CREATOR_VESTING_SECS: u64 = 365 * 86_400;
+
+    /// Default buyback slippage tolerance (1%)
+    pub const DEFAULT_BUYBACK_MAX_SLIPPAGE_BPS: u16 = 100;
+
+    /// Upper bound for quote_only_buffer_ticks (the full tick range)
+    pub const MAX_QUOTE_ONLY_BUFFER_TICKS: u32 = 887_272;
+
+    /// Upper bound for payout_dispute_window_secs (30 days)
+    pub const MAX_PAYOUT_DISPUTE_WINDOW_SECS: u64 = 30 * 86_400;
+
+    /// Upper bound for lock_oracle_max_age_secs (7 days)
+    pub const MAX_LOCK_ORACLE_MAX_AGE_SECS: u64 = 7 * 86_400;
+
+    /// Upper bound for cap_carry_days
+    pub const MAX_CAP_CARRY_DAYS: u8 = 30;
+
+    /// Maximum number of entries in locker_allowlist
+    pub const MAX_LOCKERS: usize = 4;
+
+    /// Maximum number of entries in param_schedule
+    pub const MAX_SCHEDULED_PARAMS: usize = 4;
+
+    /// Gap a new position's range must keep from the live price
+    ///
+    /// `min_tick_distance_from_price` is also re-checked by the crank, so a range opened inside it
+    /// would stall the next claim.
+    pub fn quote_only_gap(&self) -> u32 {
+        self.quote_only_buffer_ticks.max(self.min_tick_distance_from_price)
+    }
+
+    /// Whether `program_id` may be passed as the crank's locker program
+    ///
+    /// Without an allowlist that is the cluster's Streamflow program (`NetworkConfig::streamflow_program_id`).
+    pub fn accepts_locker_program(&self, program_id: &Pubkey, streamflow_program_id: &Pubkey) -> bool {
+        if self.locker_allowlist.is_empty() {
+            program_id == streamflow_program_id
+        } else {
+            self.locker_allowlist
+                .iter()
+                .any(|entry| entry.program_id == *program_id)
+        }
+    }
+
     pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
         [vault_seed.as_bytes(), b"policy"]
     }
+
+    /// Whether the quote mint is wrapped SOL (SPL Token or Token-2022 native mint)
+    pub fn quote_is_native_mint(&self) -> bool {
+        self.quote_mint == anchor_spl::token::spl_token::native_mint::ID
+            || self.quote_mint == anchor_spl::token_2022::spl_token_2022::native_mint::ID
+    }
+
+    /// Distributions per day; each tranche is a day of its own to the crank
+    pub fn tranches(&self) -> u64 {
+        self.tranches_per_day.max(1) as u64
+    }
+
+    /// Attestation investors need to be paid, while attestation_program is set
+    pub fn attestation_gate(&self) -> Option<AttestationGate> {
+        (self.attestation_program != Pubkey::default()).then_some(AttestationGate {
+            program_id: self.attestation_program,
+            layout: self.attestation_layout,
+        })
+    }
+
+    /// `requested` investors per page, held to max_investors_per_page
+    pub fn page_size(&self, requested: usize) -> usize {
+        match self.max_investors_per_page {
+            0 => requested.max(1),
+            max => requested.clamp(1, max as usize),
+        }
+    }
+
+    /// Whether a page of `investor_count` investors at `page_index` fits the policy's page limits
+    pub fn page_within_limits(&self, page_index: u64, investor_count: usize) -> bool {
+        (self.max_investors_per_page == 0 || investor_count <= self.max_investors_per_page as usize)
+            && (self.max_pages_per_day == 0 || page_index < self.max_pages_per_day as u64)
+    }
+
+    /// The gate new days (or tranches) are started under; the slot gate takes precedence over an
+    /// epoch offset, and tranches without an offset align to 00:00 UTC
+    pub fn day_gate(&self) -> DayGate {
+        let tranches = self.tranches();
+        match (self.day_gate_slots, self.epoch_offset_secs) {
+            (0, None) if tranches == 1 => DayGate::Timestamp { grace_secs: self.day_gate_grace_secs },
+            (0, offset_secs) => DayGate::Aligned {
+                offset_secs: offset_secs.unwrap_or(0) as u64,
+                period_secs: 86_400 / tranches,
+            },
+            (slots, _) => DayGate::Slots(slots / tranches),
+        }
+    }
+
+    /// daily_cap_quote_lamports in effect on `day_epoch`, param_schedule included
+    pub fn daily_cap_at(&self, day_epoch: u64) -> u64 {
+        self.scheduled_at(day_epoch, |params| params.daily_cap_quote_lamports)
+            .unwrap_or(self.daily_cap_quote_lamports)
+    }
+
+    /// min_payout_lamports in effect on `day_epoch`, param_schedule included
+    pub fn min_payout_at(&self, day_epoch: u64) -> u64 {
+        self.scheduled_at(day_epoch, |params| params.min_payout_lamports)
+            .unwrap_or(self.min_payout_lamports)
+    }
+
+    /// Latest value a due schedule entry sets for a field
+    fn scheduled_at(&self, day_epoch: u64, field: impl Fn(&ScheduledParams) -> Option<u64>) -> Option<u64> {
+        self.param_schedule
+            .iter()
+            .rev()
+            .filter(|params| params.effective_day_epoch <= day_epoch)
+            .find_map(field)
+    }
+
+    /// Move the schedule entries due by `day_epoch` into the policy's own fields; returns whether any were
+    pub fn apply_due_params(&mut self, day_epoch: u64) -> bool {
+        let due = self
+            .param_schedule
+            .iter()
+            .take_while(|params| params.effective_day_epoch <= day_epoch)
+            .count();
+        let applied: Vec<ScheduledParams> = self.param_schedule.drain(..due).collect();
+        for params in &applied {
+            if let Some(daily_cap) = params.daily_cap_quote_lamports {
+                self.daily_cap_quote_lamports = daily_cap;
+            }
+            if let Some(min_payout) = params.min_payout_lamports {
+                self.min_payout_lamports = min_payout;
+            }
+        }
+        due > 0
+    }
+
+    /// This tranche's share of the daily cap in effect on `day_epoch` (0 = no cap)
+    pub fn tranche_cap_quote_lamports(&self, day_epoch: u64) -> u64 {
+        self.daily_cap_at(day_epoch) / self.tranches()
+    }
+
+    /// Unused cap the progress PDA may bank: cap_carry_days of the full daily cap on `day_epoch`
+    pub fn max_banked_cap_quote_lamports(&self, day_epoch: u64) -> u64 {
+        self.daily_cap_at(day_epoch).saturating_mul(self.cap_carry_days as u64)
+    }
+
+    /// This tranche's share of min_daily_claim_lamports (0 = off)
+    pub fn tranche_min_claim_lamports(&self) -> u64 {
+        self.min_daily_claim_lamports / self.tranches()
+    }
+}
+
+/// Crank accounts that never change for a vault, pinned in the policy so keepers can keep them in
+/// an address lookup table and the crank checks them by key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StaticAccounts {
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub quote_treasury: Pubkey,
+    pub pool_authority: Pubkey,
+    pub cp_amm_event_authority: Pubkey,
+}
+
+impl StaticAccounts {
+    pub const LEN: usize = 7 * 32;
+}
+
+/// Policy values that take effect from a future day (`PolicyPda.param_schedule`)
+///
+/// `effective_day_epoch` counts in the day gate's epochs, so tranches when tranches_per_day > 1.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScheduledParams {
+    pub effective_day_epoch: u64,
+    pub daily_cap_quote_lamports: Option<u64>,
+    pub min_payout_lamports: Option<u64>,
+}
+
+impl ScheduledParams {
+    pub const LEN: usize = 8 + 9 + 9;
+
+    /// Whether a schedule fits the policy: every entry sets a value, and entries are in strictly
+    /// increasing order of effective day, all after `current_epoch`
+    pub fn is_valid_schedule(schedule: &[ScheduledParams], current_epoch: u64) -> bool {
+        schedule.len() <= PolicyPda::MAX_SCHEDULED_PARAMS
+            && schedule
+                .iter()
+                .all(|params| params.daily_cap_quote_lamports.is_some() || params.min_payout_lamports.is_some())
+            && schedule
+                .iter()
+                .try_fold(current_epoch, |prev, params| {
+                    (params.effective_day_epoch > prev).then_some(params.effective_day_epoch)
+                })
+                .is_some()
+    }
+}
+
+/// How a policy decides that a new distribution day may start
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DayGate {
+    /// Next UTC day, and at least 24h less `grace_secs` after the last finalized day
+    Timestamp { grace_secs: u64 },
+    /// Next boundary every `period_secs` from `offset_secs` past 00:00 UTC, however late the last
+    /// day finalized; periods shorter than a day are tranches
+    Aligned { offset_secs: u64, period_secs: u64 },
+    /// This many slots after the current day started
+    Slots(u64),
+}
+
+impl DayGate {
+    /// Day epoch `current_ts` falls in: UTC days, or the periods of an aligned gate
+    pub fn epoch_at(&self, current_ts: u64) -> u64 {
+        match self {
+            DayGate::Aligned { offset_secs, period_secs } => {
+                current_ts.saturating_sub(*offset_secs) / period_secs
+            }
+            _ => current_ts / 86_400,
+        }
+    }
+
+    /// Timestamp at which `epoch` begins
+    pub fn epoch_start(&self, epoch: u64) -> u64 {
+        match self {
+            DayGate::Aligned { offset_secs, period_secs } => {
+                epoch.saturating_mul(*period_secs).saturating_add(*offset_secs)
+            }
+            _ => epoch.saturating_mul(86_400),
+        }
+    }
 }
 
 /// Progress tracking for daily distribution state
@@ -45,7 +409,7 @@ impl PolicyPda {
 pub struct ProgressPda {
     pub vault_seed: String,
     pub last_distribution_ts: u64,
-    pub day_epoch: u64,                   // floor(timestamp / 86400)
+    pub day_epoch: u64,                   // floor((timestamp - epoch offset) / day or tranche length), see DayGate::epoch_at
     pub cumulative_distributed_today: u128,
     pub carry_over_lamports: u64,
     pub pagination_cursor: u64,
@@ -61,6 +425,75 @@ pub struct ProgressPda {
     pub day_investor_pool_target: u64,    // Target investor pool for the day
     pub day_investor_distributed: u64,    // Amount distributed to investors so far
     pub day_creator_remainder_target: u64, // Target creator remainder
+    pub day_claimed_quote: u64,           // Quote claimed by every crank today, rollover included
+
+    // Running H(prev || page_hash) over every page processed today
+    pub page_hash_chain: [u8; 32],
+
+    // Quote claimed on skipped (below-threshold) days, held in treasury for the next day
+    pub rollover_claimed_quote: u64,
+
+    // Investor share cut by the daily cap, held in treasury for the next day's investor pool
+    pub capped_excess_rollover: u64,
+    // Portion of capped_excess_rollover folded into today's investor pool
+    pub day_capped_excess_in: u64,
+
+    // Day-start locked snapshot (see snapshot_locked); payouts use locked amounts at snapshot_ts
+    pub snapshot_ts: u64,
+    pub snapshot_total_locked: u64,
+    pub snapshot_investor_count: u32,
+    pub snapshot_locked_hash: [u8; 32],
+    pub snapshot_complete: bool,
+    // Same fold as snapshot_locked_hash, over the investors actually paid out today
+    pub payout_locked_hash: [u8; 32],
+    // Locked amount of the investors paid out so far today; base of the next page's allocation
+    pub day_locked_processed: u64,
+
+    // Token-2022 transfer fees withheld today (claim into treasury + payouts out of it)
+    pub transfer_fees_today: u64,
+    // Investor payouts recorded in FailedPayoutsPda today, held in treasury until retried
+    pub failed_owed_today: u64,
+    // Creator remainder finalize could not deliver, held in treasury until settle_creator_payout
+    pub creator_owed: u64,
+    // Creator remainder waiting for a buyback, held in treasury until the next finalize buys it back
+    pub buyback_pending: u64,
+    // Timestamp of the crank that started the current day
+    pub day_started_ts: u64,
+    // Crank lease: while lease_expiry_slot is in the future only active_cranker may crank
+    pub active_cranker: Pubkey,
+    pub lease_expiry_slot: u64,
+    // Bumped by reinitialize_vault; tells the vault's restarts apart in events
+    pub generation: u16,
+    // Investors of the page at pagination_cursor already handled while page_in_progress_flag is set
+    pub page_investor_cursor: u32,
+    // page_hash of the suspended page; the resuming call must send the same page
+    pub page_in_progress_hash: [u8; 32],
+    // Slot of the crank that started the current day; base of the policy's slot day gate
+    pub day_started_slot: u64,
+    // Largest locked amounts of today's snapshot, descending; the weight cap is solved over them
+    pub snapshot_largest_locked: [u64; Self::LARGEST_LOCKED_TRACKED],
+    // Weight cap solved when today's snapshot completed (see WeightCap; max_weight_bps 0 = off)
+    pub weight_cap_bps: u16,
+    pub weight_cap_threshold: u64,
+    pub weight_cap_count: u32,
+    pub weight_cap_uncapped_locked: u64,
+    // Payout weight of the investors paid out so far today; base of the next page's allocation
+    pub day_weight_processed: u128,
+    // Daily cap left unused on earlier days, added to the next day's cap (policy.cap_carry_days)
+    pub unused_cap_allowance: u64,
+    // day_epoch the cap was last applied on; days after it without a distribution bank their cap
+    pub cap_allowance_epoch: u64,
+    // Investors the authority committed today's pages to cover (set_expected_pages; 0 = none)
+    pub committed_investor_count: u32,
+    // Investors handled by today's pages so far, checked against committed_investor_count
+    pub day_investors_processed: u32,
+    // Pages completed today, one bit per page index below MAX_TRACKED_PAGES (policy.unordered_pages)
+    pub page_bitmap: [u8; 64],
+    // Order-independent sums of the snapshot and payout locked entries (see add_locked_set_entry)
+    pub snapshot_locked_set: [u8; 32],
+    pub payout_locked_set: [u8; 32],
+    // Highest stream key recorded in today's snapshot; entries must arrive in ascending key order
+    pub snapshot_last_stream: Pubkey,
     
     pub created_at: u64,
     pub updated_at: u64,
@@ -84,10 +517,53 @@ impl ProgressPda {
         8 + // day_investor_pool_target
         8 + // day_investor_distributed
         8 + // day_creator_remainder_target
+        8 + // day_claimed_quote
+        32 + // page_hash_chain
+        8 + // rollover_claimed_quote
+        8 + // capped_excess_rollover
+        8 + // day_capped_excess_in
+        8 + // snapshot_ts
+        8 + // snapshot_total_locked
+        4 + // snapshot_investor_count
+        32 + // snapshot_locked_hash
+        1 + // snapshot_complete
+        32 + // payout_locked_hash
+        8 + // day_locked_processed
+        8 + // transfer_fees_today
+        8 + // failed_owed_today
+        8 + // creator_owed
+        8 + // buyback_pending
+        8 + // day_started_ts
+        32 + // active_cranker
+        8 + // lease_expiry_slot
+        2 + // generation
+        4 + // page_investor_cursor
+        32 + // page_in_progress_hash
+        8 + // day_started_slot
+        8 * ProgressPda::LARGEST_LOCKED_TRACKED + // snapshot_largest_locked
+        2 + // weight_cap_bps
+        8 + // weight_cap_threshold
+        4 + // weight_cap_count
+        8 + // weight_cap_uncapped_locked
+        16 + // day_weight_processed
+        8 + // unused_cap_allowance
+        8 + // cap_allowance_epoch
+        4 + // committed_investor_count
+        4 + // day_investors_processed
+        ProgressPda::MAX_TRACKED_PAGES as usize / 8 + // page_bitmap
+        32 + // snapshot_locked_set
+        32 + // payout_locked_set
+        32 + // snapshot_last_stream
         8 + // created_at
         8 + // updated_at
         32; // padding for future fields
 
+    /// Length of snapshot_largest_locked: enough for the tightest cap
+    pub const LARGEST_LOCKED_TRACKED: usize = WeightCap::largest_needed(PolicyPda::MIN_MAX_WEIGHT_BPS);
+
+    /// Page indexes page_bitmap covers; an unordered day commits at most this many pages
+    pub const MAX_TRACKED_PAGES: u64 = 512;
+
     pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
         [vault_seed.as_bytes(), b"progress"]
     }
@@ -96,22 +572,93 @@ impl ProgressPda {
         (current_ts / 86_400) > self.day_epoch
     }
 
-    pub fn can_start_new_day(&self, current_ts: u64) -> bool {
-        self.last_distribution_ts == 0 || current_ts.saturating_sub(self.last_distribution_ts) >= 86_400
+    /// 24h gate since the last finalized day, relaxed by `grace_secs` so daily
+    /// cranks don't drift later every day
+    pub fn can_start_new_day(&self, current_ts: u64, grace_secs: u64) -> bool {
+        self.last_distribution_ts == 0
+            || current_ts.saturating_sub(self.last_distribution_ts) >= 86_400u64.saturating_sub(grace_secs)
+    }
+
+    /// Whether a crank at `current_ts` / `slot` falls in a new day under `gate`
+    pub fn is_new_day_under(&self, gate: DayGate, current_ts: u64, slot: u64) -> bool {
+        match gate {
+            DayGate::Timestamp { .. } => self.is_new_day(current_ts),
+            DayGate::Aligned { .. } => gate.epoch_at(current_ts) > self.day_epoch,
+            DayGate::Slots(gate_slots) => slot >= self.day_started_slot.saturating_add(gate_slots),
+        }
+    }
+
+    /// Whether `gate` lets a new day start; only the timestamp gate checks more than `is_new_day_under`
+    pub fn can_start_new_day_under(&self, gate: DayGate, current_ts: u64) -> bool {
+        match gate {
+            DayGate::Timestamp { grace_secs } => self.can_start_new_day(current_ts, grace_secs),
+            DayGate::Aligned { .. } | DayGate::Slots(_) => true,
+        }
+    }
+
+    /// Epoch of a day started at `current_ts` under `gate`, moved past the current epoch when a
+    /// slot-gated day starts on the same UTC day as the last one, so per-day accounts stay distinct
+    pub fn next_day_epoch(&self, gate: DayGate, current_ts: u64) -> u64 {
+        gate.epoch_at(current_ts).max(self.day_epoch.saturating_add(1))
+    }
+
+    /// Start a new day if `gate` has opened at `current_ts` / `slot`; returns whether it did
+    pub fn roll_day(&mut self, gate: DayGate, current_ts: u64, slot: u64) -> Result<bool> {
+        if !self.is_new_day_under(gate, current_ts, slot) {
+            return Ok(false);
+        }
+        require!(
+            self.can_start_new_day_under(gate, current_ts),
+            crate::error::FeeRouterError::DayGateNotPassed
+        );
+        let day_epoch = self.next_day_epoch(gate, current_ts);
+        self.start_new_day(current_ts);
+        self.day_epoch = day_epoch;
+        self.day_started_slot = slot;
+        Ok(true)
     }
 
     pub fn start_new_day(&mut self, current_ts: u64) {
         self.day_epoch = current_ts / 86_400;
+        self.day_started_ts = current_ts;
         self.cumulative_distributed_today = 0;
         self.pagination_cursor = 0;
         self.day_finalized_flag = false;
         self.pages_processed_today = 0;
+        self.clear_page_in_progress();
         
         // Reset per-day targets
         self.day_total_locked = 0;
         self.day_investor_pool_target = 0;
         self.day_investor_distributed = 0;
         self.day_creator_remainder_target = 0;
+        self.day_claimed_quote = 0;
+        self.page_hash_chain = [0u8; 32];
+        self.day_capped_excess_in = 0;
+
+        // Reset the locked snapshot
+        self.snapshot_ts = 0;
+        self.snapshot_total_locked = 0;
+        self.snapshot_investor_count = 0;
+        self.snapshot_locked_hash = [0u8; 32];
+        self.snapshot_complete = false;
+        self.payout_locked_hash = [0u8; 32];
+        self.day_locked_processed = 0;
+        self.snapshot_largest_locked = [0; Self::LARGEST_LOCKED_TRACKED];
+        self.weight_cap_bps = 0;
+        self.weight_cap_threshold = 0;
+        self.weight_cap_count = 0;
+        self.weight_cap_uncapped_locked = 0;
+        self.day_weight_processed = 0;
+        self.transfer_fees_today = 0;
+        self.failed_owed_today = 0;
+        self.total_pages_expected = 0;
+        self.committed_investor_count = 0;
+        self.day_investors_processed = 0;
+        self.page_bitmap = [0u8; 64];
+        self.snapshot_locked_set = [0u8; 32];
+        self.payout_locked_set = [0u8; 32];
+        self.snapshot_last_stream = Pubkey::default();
         
         self.updated_at = current_ts;
     }
@@ -128,219 +675,2313 @@ impl ProgressPda {
         self.day_creator_remainder_target = creator_remainder_target;
     }
     
-    /// Track investor distribution progress
-    pub fn add_investor_distribution(&mut self, amount: u64) -> Result<()> {
-        self.day_investor_distributed = self.day_investor_distributed
-            .checked_add(amount)
-            .ok_or(crate::error::FeeRouterError::Overflow)?;
+    /// Whether today's completed snapshot has nothing locked, so no investor can be paid
+    pub fn zero_locked_day(&self) -> bool {
+        self.snapshot_complete && self.snapshot_total_locked == 0
+    }
+
+    /// Commit today's page count and investor count before any payouts (set_expected_pages)
+    pub fn commit_expected_pages(&mut self, expected_pages: u64, expected_investors: u32) -> Result<()> {
+        require!(!self.day_finalized_flag, crate::error::FeeRouterError::DayAlreadyFinalized);
+        require!(self.first_page_pending(), crate::error::FeeRouterError::InvalidPaginationState);
+        require!(expected_pages > 0, crate::error::FeeRouterError::InvalidPaginationState);
+        // A snapshot already taken fixes the investor count, or bounds it from below while open
+        if self.snapshot_complete {
+            require!(
+                expected_investors == self.snapshot_investor_count,
+                crate::error::FeeRouterError::SnapshotInvestorCountMismatch
+            );
+        } else if self.snapshot_in_progress() {
+            require!(
+                expected_investors >= self.snapshot_investor_count,
+                crate::error::FeeRouterError::SnapshotInvestorCountMismatch
+            );
+        }
+        self.total_pages_expected = expected_pages;
+        self.committed_investor_count = expected_investors;
         Ok(())
     }
 
-    pub fn finalize_day(&mut self, current_ts: u64, _total_claimed: u128, _creator_payout: u128) {
-        self.day_finalized_flag = true;
-        self.last_distribution_ts = current_ts;
-        self.pagination_cursor = 0;
-        self.updated_at = current_ts;
+    /// Add a call's handled investors to today's count; fails once they exceed the commitment
+    pub fn record_investors_processed(&mut self, count: u32) -> core::result::Result<(), CrankMismatch> {
+        self.day_investors_processed = self.day_investors_processed.saturating_add(count);
+        if self.committed_investor_count > 0 && self.day_investors_processed > self.committed_investor_count {
+            return Err(self.investor_count_mismatch());
+        }
+        Ok(())
     }
-}
 
-/// Owner PDA for the honorary DLMM position
-#[account]
-pub struct InvestorFeePositionOwnerPda {
-    pub vault_seed: String,
-    pub position_pubkey: Pubkey,
-    pub pool_pubkey: Pubkey,
-    pub quote_mint: Pubkey,
-    pub tick_lower: i32,
-    pub tick_upper: i32,
-    pub verified_quote_only: bool,
-    pub created_at: u64,
-}
+    /// Whether today's handled investors match the committed count (or none was committed)
+    pub fn committed_investors_covered(&self) -> core::result::Result<(), CrankMismatch> {
+        if self.committed_investor_count > 0 && self.day_investors_processed != self.committed_investor_count {
+            return Err(self.investor_count_mismatch());
+        }
+        Ok(())
+    }
 
-impl InvestorFeePositionOwnerPda {
-    pub const LEN: usize = 8 + // discriminator
-        4 + 32 + // vault_seed (String)
-        32 + // position_pubkey
-        32 + // pool_pubkey
-        32 + // quote_mint
-        4 + // tick_lower
-        4 + // tick_upper
-        1 + // verified_quote_only
-        8 + // created_at
-        32; // padding
+    fn investor_count_mismatch(&self) -> CrankMismatch {
+        CrankMismatch::InvestorCount {
+            expected: self.committed_investor_count,
+            provided: self.day_investors_processed,
+        }
+    }
 
-    pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
-        [vault_seed.as_bytes(), b"investor_fee_pos_owner"]
+    /// Whether the cursor has reached the page count committed for today, so the day can be
+    /// finalized without the caller flagging its final page
+    pub fn expected_pages_reached(&self) -> bool {
+        self.total_pages_expected > 0 && self.pagination_cursor >= self.total_pages_expected
     }
-}
 
-/// Distribution math utilities
-pub struct DistributionMath;
+    /// Whether page `page_index` was completed today
+    pub fn page_processed(&self, page_index: u64) -> bool {
+        page_index < self.pagination_cursor
+            || (page_index < Self::MAX_TRACKED_PAGES && self.page_bit(page_index))
+    }
 
-impl DistributionMath {
-    /// Calculate eligible investor share in basis points
-    /// f_locked = locked_total / Y0, clamped to [0,1]
-    /// eligible_bps = min(investor_fee_share_bps, floor(f_locked * 10000))
-    pub fn calculate_eligible_bps(
-        locked_total: u128,
-        y0_total_allocation: u128,
-        investor_fee_share_bps: u16,
-    ) -> Result<u16> {
-        if y0_total_allocation == 0 {
-            return err!(crate::error::FeeRouterError::InvalidY0);
+    /// Mark a page completed; the cursor moves to the lowest page not completed yet, so it reaches
+    /// total_pages_expected only once every page below it is done
+    pub fn complete_page(&mut self, page_index: u64) {
+        if page_index < Self::MAX_TRACKED_PAGES {
+            self.page_bitmap[page_index as usize / 8] |= 1 << (page_index % 8);
         }
+        if page_index == self.pagination_cursor {
+            self.pagination_cursor = self.pagination_cursor.saturating_add(1);
+            while self.pagination_cursor < Self::MAX_TRACKED_PAGES && self.page_bit(self.pagination_cursor) {
+                self.pagination_cursor += 1;
+            }
+        }
+    }
+
+    fn page_bit(&self, page_index: u64) -> bool {
+        self.page_bitmap[page_index as usize / 8] & (1 << (page_index % 8)) != 0
+    }
 
-        if locked_total > y0_total_allocation {
-            return err!(crate::error::FeeRouterError::LockedExceedsAllocation);
+    /// Whether the day's first page has yet to pay anyone (a suspended first page has)
+    pub fn first_page_pending(&self) -> bool {
+        self.pages_processed_today == 0 && !self.page_in_progress_flag
+    }
+
+    /// Investor the page at the cursor resumes at: 0 for a fresh page, or the saved index of the
+    /// suspended page, which must be resent unchanged
+    pub fn page_resume_index(&self, page_hash: &[u8; 32]) -> Result<u32> {
+        if !self.page_in_progress_flag {
+            return Ok(0);
         }
+        require!(
+            self.page_in_progress_hash == *page_hash,
+            crate::error::FeeRouterError::InvalidPaginationState
+        );
+        Ok(self.page_investor_cursor)
+    }
 
-        // Calculate f_locked with precision
-        let f_locked_bps = (locked_total as u128)
-            .checked_mul(10000)
-            .ok_or(crate::error::FeeRouterError::Overflow)?
-            .checked_div(y0_total_allocation)
-            .ok_or(crate::error::FeeRouterError::Overflow)?;
+    /// Stop the page at the cursor before investor `next_investor`; the cursor stays on the page
+    pub fn suspend_page(&mut self, page_hash: &[u8; 32], next_investor: u32) {
+        self.page_in_progress_flag = true;
+        self.page_investor_cursor = next_investor;
+        self.page_in_progress_hash = *page_hash;
+    }
 
-        let f_locked_bps_u16 = std::cmp::min(f_locked_bps, 10000) as u16;
-        let eligible_bps = std::cmp::min(investor_fee_share_bps, f_locked_bps_u16);
+    fn clear_page_in_progress(&mut self) {
+        self.page_in_progress_flag = false;
+        self.page_investor_cursor = 0;
+        self.page_in_progress_hash = [0u8; 32];
+    }
 
-        Ok(eligible_bps)
+    /// Fold a completed page hash into the day's hash chain, ending any suspension of the page
+    /// chain = H(chain || page_hash), starting from zero at day start
+    pub fn record_page_hash(&mut self, page_hash: &[u8; 32]) {
+        self.clear_page_in_progress();
+        self.page_hash_chain = hashv(&[&self.page_hash_chain, page_hash]).to_bytes();
     }
 
-    /// Calculate investor fee quote amount
-    /// investor_fee_quote = floor(claimed_quote * eligible_bps / 10000)
-    pub fn calculate_investor_fee_quote(
-        claimed_quote: u128,
-        eligible_bps: u16,
-    ) -> Result<u128> {
-        let investor_fee_quote = claimed_quote
-            .checked_mul(eligible_bps as u128)
-            .ok_or(crate::error::FeeRouterError::Overflow)?
-            .checked_div(10000)
-            .ok_or(crate::error::FeeRouterError::Overflow)?;
+    /// Fold one investor's locked amount into a running hash
+    /// hash = H(hash || stream || investor || locked_le)
+    pub fn fold_locked_entry(hash: &[u8; 32], stream: &Pubkey, investor: &Pubkey, locked: u64) -> [u8; 32] {
+        hashv(&[hash, stream.as_ref(), investor.as_ref(), &locked.to_le_bytes()]).to_bytes()
+    }
 
-        Ok(investor_fee_quote)
+    /// Add one investor's locked amount to an order-independent running sum
+    /// set = set + H(stream || investor || locked_le), as 256-bit little-endian integers mod 2^256
+    pub fn add_locked_set_entry(set: &[u8; 32], stream: &Pubkey, investor: &Pubkey, locked: u64) -> [u8; 32] {
+        let entry = hashv(&[stream.as_ref(), investor.as_ref(), &locked.to_le_bytes()]).to_bytes();
+        let mut sum = [0u8; 32];
+        let mut carry = 0u16;
+        for i in 0..32 {
+            let byte = set[i] as u16 + entry[i] as u16 + carry;
+            sum[i] = byte as u8;
+            carry = byte >> 8;
+        }
+        sum
     }
 
-    /// Apply daily cap to investor fee quote
-    pub fn apply_daily_cap(
-        investor_fee_quote: u128,
-        daily_cap: u64,
-        cumulative_distributed_today: u128,
-    ) -> u128 {
-        if daily_cap == 0 {
-            return investor_fee_quote; // No cap
+    /// Record one investor into the day's locked snapshot
+    ///
+    /// Stream keys must be strictly ascending across the day's chunks, so no stream is counted twice.
+    pub fn add_snapshot_entry(&mut self, stream: &Pubkey, investor: &Pubkey, locked: u64) -> Result<()> {
+        require!(
+            *stream > self.snapshot_last_stream,
+            crate::error::FeeRouterError::InvestorKeysNotAscending
+        );
+        self.snapshot_last_stream = *stream;
+        self.snapshot_total_locked = self.snapshot_total_locked
+            .checked_add(locked)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        self.snapshot_investor_count = self.snapshot_investor_count
+            .checked_add(1)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        self.snapshot_locked_hash = Self::fold_locked_entry(&self.snapshot_locked_hash, stream, investor, locked);
+        self.snapshot_locked_set = Self::add_locked_set_entry(&self.snapshot_locked_set, stream, investor, locked);
+
+        // Keep the largest amounts in descending order, dropping the smallest
+        if let Some(slot) = self.snapshot_largest_locked.iter().position(|largest| locked > *largest) {
+            self.snapshot_largest_locked.copy_within(slot..Self::LARGEST_LOCKED_TRACKED - 1, slot + 1);
+            self.snapshot_largest_locked[slot] = locked;
         }
+        Ok(())
+    }
 
-        let remaining_cap = (daily_cap as u128).saturating_sub(cumulative_distributed_today);
-        std::cmp::min(investor_fee_quote, remaining_cap)
+    /// Complete today's snapshot, which must hold exactly the investors committed with
+    /// set_expected_pages (if any were), and solve its weight cap
+    pub fn complete_snapshot(&mut self, max_weight_bps: u16) -> Result<()> {
+        require!(
+            self.committed_investor_count == 0 || self.snapshot_investor_count == self.committed_investor_count,
+            crate::error::FeeRouterError::SnapshotInvestorCountMismatch
+        );
+        self.snapshot_complete = true;
+        self.solve_weight_cap(max_weight_bps)
     }
 
-    /// Calculate individual investor payout
-    /// weight_i = locked_i / locked_total
-    /// raw_payout_i = floor(investor_fee_quote * weight_i)
-    pub fn calculate_investor_payout(
-        locked_amount: u128,
-        locked_total: u128,
-        investor_fee_quote: u128,
-    ) -> Result<u128> {
-        if locked_total == 0 {
-            return Ok(0);
+    /// Solve today's weight cap over the completed snapshot
+    pub fn solve_weight_cap(&mut self, max_weight_bps: u16) -> Result<()> {
+        let cap = WeightCap::solve(
+            max_weight_bps,
+            &self.snapshot_largest_locked,
+            self.snapshot_total_locked as u128,
+        ).map_err(crate::error::FeeRouterError::from)?;
+        self.weight_cap_bps = cap.max_weight_bps;
+        self.weight_cap_threshold = cap.threshold;
+        self.weight_cap_count = cap.capped_count;
+        self.weight_cap_uncapped_locked = cap.uncapped_locked as u64;
+        Ok(())
+    }
+
+    /// Weight cap pages apportion today's investor pool under
+    pub fn weight_cap(&self) -> WeightCap {
+        WeightCap {
+            max_weight_bps: self.weight_cap_bps,
+            threshold: self.weight_cap_threshold,
+            capped_count: self.weight_cap_count,
+            uncapped_locked: self.weight_cap_uncapped_locked as u128,
         }
+    }
 
-        let payout = locked_amount
-            .checked_mul(investor_fee_quote)
-            .ok_or(crate::error::FeeRouterError::Overflow)?
-            .checked_div(locked_total)
-            .ok_or(crate::error::FeeRouterError::Overflow)?;
+    /// Whether a snapshot has been started but not yet completed today
+    pub fn snapshot_in_progress(&self) -> bool {
+        self.snapshot_ts > 0 && !self.snapshot_complete
+    }
 
-        Ok(payout)
+    /// Cap today's investor pool, raised by cap banked on earlier days, and bank what today leaves
+    ///
+    /// Days since the cap was last applied that never reached a first page (deferred or not
+    /// cranked) bank their whole cap. The bank never exceeds `max_banked`.
+    pub fn apply_daily_cap(&mut self, investor_fee_quote: u128, tranche_cap: u64, max_banked: u64) -> u128 {
+        let banked = self.banked_cap(tranche_cap, max_banked);
+        let (capped, unused) =
+            DistributionMath::apply_carried_daily_cap(investor_fee_quote, tranche_cap, banked, max_banked);
+        self.unused_cap_allowance = unused;
+        self.cap_allowance_epoch = self.day_epoch;
+        capped
     }
-}
 
-// NOTE: `InitializeHonoraryPosition` Accounts is defined under `instructions/initialize_honorary_position.rs`.
+    /// Cap banked for today's pool: the unused allowance plus the whole cap of missed days
+    pub fn banked_cap(&self, tranche_cap: u64, max_banked: u64) -> u64 {
+        let missed_days = match self.cap_allowance_epoch {
+            0 => 0,
+            last => self.day_epoch.saturating_sub(last).saturating_sub(1),
+        };
+        (self.unused_cap_allowance as u128)
+            .saturating_add(missed_days as u128 * tranche_cap as u128)
+            .min(max_banked as u128) as u64
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Move yesterday's capped-out investor share into today's pool (once per day)
+    pub fn take_capped_excess(&mut self) -> Result<u64> {
+        let rolled_in = self.capped_excess_rollover;
+        self.capped_excess_rollover = 0;
+        self.day_capped_excess_in = self.day_capped_excess_in
+            .checked_add(rolled_in)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        Ok(rolled_in)
+    }
 
-    fn default_progress() -> ProgressPda {
-        ProgressPda {
-            vault_seed: "vault".to_string(),
-            last_distribution_ts: 0,
-            day_epoch: 0,
-            cumulative_distributed_today: 0,
-            carry_over_lamports: 0,
-            pagination_cursor: 0,
-            page_in_progress_flag: false,
-            day_finalized_flag: false,
-            total_pages_expected: 0,
-            pages_processed_today: 0,
-            last_claimed_quote: 0,
-            last_claimed_base: 0,
-            day_total_locked: 0,
-            day_investor_pool_target: 0,
-            day_investor_distributed: 0,
-            day_creator_remainder_target: 0,
-            created_at: 0,
-            updated_at: 0,
+    /// Book the share of today's unattested investors where `routing` sends it
+    pub fn route_unattested(&mut self, routing: UnattestedRouting, amount: u64) -> Result<()> {
+        match routing {
+            UnattestedRouting::CarryOver => {
+                self.carry_over_lamports = self.carry_over_lamports
+                    .checked_add(amount)
+                    .ok_or(crate::error::FeeRouterError::Overflow)?;
+            }
+            UnattestedRouting::Withhold => self.roll_capped_excess(amount)?,
+            // Left out of every held-back total, so finalize pays it with the creator remainder
+            UnattestedRouting::Creator => {}
         }
+        Ok(())
     }
 
-    #[test]
-    fn test_eligible_bps_calc() {
-        // 0/1000 -> 0 bps
-        let bps0 = DistributionMath::calculate_eligible_bps(0, 1000, 9000).unwrap();
-        assert_eq!(bps0, 0);
+    /// What finalize pays the creator: the day's claim and rolled-in excess, less what investors
+    /// were paid and what is held back for later
+    pub fn creator_remainder(&self) -> u128 {
+        (self.day_claimed_quote as u128)
+            .saturating_add(self.day_capped_excess_in as u128)
+            .saturating_sub(self.cumulative_distributed_today)
+            .saturating_sub(self.carry_over_lamports as u128)
+            .saturating_sub(self.capped_excess_rollover as u128)
+            .saturating_sub(self.failed_owed_today as u128)
+    }
 
-        // 600/1000 -> 6000 bps, min with 9000 -> 6000
-        let bps1 = DistributionMath::calculate_eligible_bps(600, 1000, 9000).unwrap();
-        assert_eq!(bps1, 6000);
+    /// Hold investor share cut by the daily cap for the next day
+    pub fn roll_capped_excess(&mut self, amount: u64) -> Result<()> {
+        self.capped_excess_rollover = self.capped_excess_rollover
+            .checked_add(amount)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        Ok(())
+    }
 
-        // 1000/1000 with share 10_000 -> 10_000
-        let bps2 = DistributionMath::calculate_eligible_bps(1000, 1000, 10_000).unwrap();
-        assert_eq!(bps2, 10_000);
+    /// Track investor distribution progress
+    pub fn add_investor_distribution(&mut self, amount: u64) -> Result<()> {
+        self.day_investor_distributed = self.day_investor_distributed
+            .checked_add(amount)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        Ok(())
     }
 
-    #[test]
-    fn test_investor_fee_and_payouts() {
-        // claimed 1_000_000, eligible 9000 bps -> 900,000 investor pool
-        let pool = DistributionMath::calculate_investor_fee_quote(1_000_000, 9000).unwrap();
-        assert_eq!(pool, 900_000);
+    /// Add quote claimed by a crank into today's running claim
+    pub fn record_day_claim(&mut self, claimed_quote: u64) -> Result<()> {
+        self.day_claimed_quote = self.day_claimed_quote
+            .checked_add(claimed_quote)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        Ok(())
+    }
 
-        // Split 60/40
-        let p60 = DistributionMath::calculate_investor_payout(60, 100, pool).unwrap();
-        let p40 = DistributionMath::calculate_investor_payout(40, 100, pool).unwrap();
-        assert_eq!(p60, 540_000);
-        assert_eq!(p40, 360_000);
-        assert_eq!(p60 + p40, pool);
+    /// Close the day without distributing, keeping everything claimed today, earlier claim-only
+    /// cranks plus this call's `claimed_total`, in treasury for the next day
+    pub fn defer_day(&mut self, current_ts: u64, claimed_total: u64) -> Result<()> {
+        self.rollover_claimed_quote = self.day_claimed_quote
+            .checked_add(claimed_total)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        self.day_claimed_quote = 0;
+        self.day_finalized_flag = true;
+        self.last_distribution_ts = current_ts;
+        self.pagination_cursor = 0;
+        self.updated_at = current_ts;
+        Ok(())
     }
 
-    #[test]
-    fn test_daily_cap() {
-        // 900k desired, cap 800k, none distributed yet -> 800k
-        let capped = DistributionMath::apply_daily_cap(900_000, 800_000, 0);
-        assert_eq!(capped, 800_000);
+    /// Whether an unfinalized day has run `timeout_secs` past its start (0 = never)
+    pub fn is_stalled(&self, current_ts: u64, timeout_secs: u64) -> bool {
+        timeout_secs > 0
+            && self.day_started_ts > 0
+            && !self.day_finalized_flag
+            && current_ts >= self.day_started_ts.saturating_add(timeout_secs)
     }
 
-    #[test]
-    fn test_progress_targets_and_distribution() {
-        let mut p = default_progress();
-        // Start new day resets fields
-        p.start_new_day(86_400);
-        assert_eq!(p.day_total_locked, 0);
-        assert_eq!(p.day_investor_distributed, 0);
-        assert_eq!(p.pages_processed_today, 0);
+    /// Settle a stalled day: investor pool not yet paid, sent to dust or recorded as failed either
+    /// rolls to the next day's investor pool or joins the creator remainder.
+    /// Returns (investor_unpaid, creator_payout).
+    pub fn settle_stalled_day(&mut self, roll_unpaid: bool) -> Result<(u64, u64)> {
+        let investor_unpaid = self.day_investor_pool_target
+            .saturating_sub(self.day_investor_distributed)
+            .saturating_sub(self.failed_owed_today);
+        // Today's capped-out share already sits in capped_excess_rollover
+        let mut creator_payout = self.day_creator_remainder_target
+            .saturating_sub(self.capped_excess_rollover);
+        if roll_unpaid {
+            self.roll_capped_excess(investor_unpaid)?;
+        } else {
+            creator_payout = creator_payout
+                .checked_add(investor_unpaid)
+                .ok_or(crate::error::FeeRouterError::Overflow)?;
+        }
+        Ok((investor_unpaid, creator_payout))
+    }
 
-        // Set day targets
-        p.set_day_targets(1_000, 900_000, 100_000);
-        assert_eq!(p.day_total_locked, 1_000);
-        assert_eq!(p.day_investor_pool_target, 900_000);
-        assert_eq!(p.day_creator_remainder_target, 100_000);
+    /// Whether a live lease held by someone other than `cranker` blocks it at `slot`
+    pub fn lease_blocks(&self, cranker: &Pubkey, slot: u64) -> bool {
+        slot < self.lease_expiry_slot && self.active_cranker != *cranker
+    }
 
-        // Track distribution
-        p.add_investor_distribution(540_000).unwrap();
-        p.add_investor_distribution(360_000).unwrap();
-        assert_eq!(p.day_investor_distributed, 900_000);
+    /// Take or renew the crank lease for `lease_slots` from `slot`
+    pub fn acquire_lease(&mut self, cranker: &Pubkey, slot: u64, lease_slots: u64) -> Result<u64> {
+        require!(
+            !self.lease_blocks(cranker, slot),
+            crate::error::FeeRouterError::CrankLeaseHeld
+        );
+        self.active_cranker = *cranker;
+        self.lease_expiry_slot = slot.saturating_add(lease_slots);
+        Ok(self.lease_expiry_slot)
+    }
+
+    /// Whether a started day has yet to finalize
+    pub fn day_open(&self) -> bool {
+        self.day_started_ts > 0 && !self.day_finalized_flag
+    }
+
+    /// Whether `reinitialize_vault` may restart the vault: no day open and nothing owed to the creator
+    pub fn can_reinitialize(&self) -> bool {
+        !self.day_open()
+            && self.creator_owed == 0
+            && self.buyback_pending == 0
+    }
+
+    /// Start the vault's next generation from a clean day state
+    ///
+    /// Quote the treasury holds for later days (carry-over and rollovers) moves into the new
+    /// generation, and the last day stays closed so the day gate still runs from it; everything
+    /// else returns to its `initialize_progress` value.
+    pub fn reinitialize(&mut self, current_ts: u64) -> Result<u16> {
+        self.generation = self.generation
+            .checked_add(1)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        let (day_epoch, day_started_ts) = (self.day_epoch, self.day_started_ts);
+        let day_finalized = self.day_finalized_flag;
+        self.start_new_day(0);
+        self.day_epoch = day_epoch;
+        self.day_started_ts = day_started_ts;
+        self.day_finalized_flag = day_finalized;
+        self.last_claimed_quote = 0;
+        self.last_claimed_base = 0;
+        self.active_cranker = Pubkey::default();
+        self.lease_expiry_slot = 0;
+        self.updated_at = current_ts;
+        Ok(self.generation)
+    }
+
+    pub fn finalize_day(&mut self, current_ts: u64, _total_claimed: u128, _creator_payout: u128) {
+        self.day_finalized_flag = true;
+        // The day's work is done; the next day starts without a lease
+        self.active_cranker = Pubkey::default();
+        self.lease_expiry_slot = 0;
+        self.last_distribution_ts = current_ts;
+        self.pagination_cursor = 0;
+        self.clear_page_in_progress();
+        self.updated_at = current_ts;
+    }
+}
+
+/// Ledger of investor payouts that could not be delivered (continue-on-failure mode)
+///
+/// The owed quote stays in the treasury until `retry_failed_payouts` settles it.
+#[account]
+pub struct FailedPayoutsPda {
+    pub vault_seed: String,
+    pub total_owed: u64,
+    pub entries: Vec<FailedPayout>,
+    pub updated_at: u64,
+}
+
+/// One investor's undelivered payouts, summed across days
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FailedPayout {
+    pub investor: Pubkey,
+    pub amount: u64,
+    pub first_day_epoch: u64,             // day the oldest unsettled amount was recorded
+}
+
+impl FailedPayout {
+    pub const LEN: usize = 32 + 8 + 8;
+}
+
+impl FailedPayoutsPda {
+    /// Maximum number of investors with unsettled payouts
+    pub const MAX_ENTRIES: usize = 64;
+
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        8 + // total_owed
+        4 + FailedPayoutsPda::MAX_ENTRIES * FailedPayout::LEN + // entries
+        8 + // updated_at
+        32; // padding
+
+    pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
+        [vault_seed.as_bytes(), b"failed_payouts"]
+    }
+
+    /// Add `amount` owed to `investor`, merging with an existing entry
+    pub fn record(&mut self, investor: &Pubkey, amount: u64, day_epoch: u64) -> Result<()> {
+        match self.entries.iter_mut().find(|e| e.investor == *investor) {
+            Some(entry) => {
+                entry.amount = entry.amount
+                    .checked_add(amount)
+                    .ok_or(crate::error::FeeRouterError::Overflow)?;
+            }
+            None => {
+                require!(
+                    self.entries.len() < Self::MAX_ENTRIES,
+                    crate::error::FeeRouterError::FailedPayoutsFull
+                );
+                self.entries.push(FailedPayout {
+                    investor: *investor,
+                    amount,
+                    first_day_epoch: day_epoch,
+                });
+            }
+        }
+        self.total_owed = self.total_owed
+            .checked_add(amount)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        Ok(())
+    }
+
+    /// Remove `investor`'s entry, returning the amount owed
+    pub fn take(&mut self, investor: &Pubkey) -> Option<u64> {
+        let index = self.entries.iter().position(|e| e.investor == *investor)?;
+        let entry = self.entries.swap_remove(index);
+        self.total_owed = self.total_owed.saturating_sub(entry.amount);
+        Some(entry.amount)
+    }
+}
+
+/// One investor's payouts held back for the dispute window (policy.payout_dispute_window_secs)
+///
+/// The quote stays in the treasury. `withdraw_escrowed_payouts` pays out entries whose window has
+/// passed; until then the authority can claw an entry back with `claw_back_escrowed_payout`.
+#[account]
+pub struct PayoutEscrowPda {
+    pub vault_seed: String,
+    pub investor: Pubkey,
+    pub total_escrowed: u64,
+    pub entries: Vec<EscrowedPayout>,
+    pub updated_at: u64,
+}
+
+/// Payouts credited to an investor's escrow on one day
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EscrowedPayout {
+    pub day_epoch: u64,
+    pub amount: u64,
+    pub release_ts: u64,                  // withdrawable from this timestamp; claw-back closes then
+}
+
+impl EscrowedPayout {
+    pub const LEN: usize = 8 + 8 + 8;
+}
+
+impl PayoutEscrowPda {
+    /// Maximum number of days with unwithdrawn payouts; a full escrow merges new credits into its newest day
+    pub const MAX_ENTRIES: usize = 16;
+
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        32 + // investor
+        8 + // total_escrowed
+        4 + PayoutEscrowPda::MAX_ENTRIES * EscrowedPayout::LEN + // entries
+        8 + // updated_at
+        32; // padding
+
+    pub fn seeds<'a>(vault_seed: &'a str, investor: &'a Pubkey) -> [&'a [u8]; 3] {
+        [vault_seed.as_bytes(), b"payout_escrow", investor.as_ref()]
+    }
+
+    /// Credit `amount` paid on `day_epoch`, withdrawable from `release_ts`
+    ///
+    /// Credits on the same day merge. A full escrow merges into its newest entry, which then
+    /// releases at the later of the two timestamps.
+    pub fn credit(&mut self, day_epoch: u64, amount: u64, release_ts: u64) -> Result<()> {
+        let full = self.entries.len() >= Self::MAX_ENTRIES;
+        match self.entries.last_mut() {
+            Some(entry) if entry.day_epoch == day_epoch || full => {
+                entry.amount = entry.amount
+                    .checked_add(amount)
+                    .ok_or(crate::error::FeeRouterError::Overflow)?;
+                entry.release_ts = entry.release_ts.max(release_ts);
+            }
+            _ => self.entries.push(EscrowedPayout { day_epoch, amount, release_ts }),
+        }
+        self.total_escrowed = self.total_escrowed
+            .checked_add(amount)
+            .ok_or(crate::error::FeeRouterError::Overflow)?;
+        Ok(())
+    }
+
+    /// Remove every entry released by `now`, returning their total
+    pub fn take_released(&mut self, now: u64) -> u64 {
+        let released: u64 = self.entries
+            .iter()
+            .filter(|entry| entry.release_ts <= now)
+            .map(|entry| entry.amount)
+            .sum();
+        self.entries.retain(|entry| entry.release_ts > now);
+        self.total_escrowed = self.total_escrowed.saturating_sub(released);
+        released
+    }
+
+    /// Remove `day_epoch`'s entry while its dispute window is still open, returning its amount
+    pub fn claw_back(&mut self, day_epoch: u64, now: u64) -> Option<u64> {
+        let index = self.entries
+            .iter()
+            .position(|entry| entry.day_epoch == day_epoch && entry.release_ts > now)?;
+        let entry = self.entries.remove(index);
+        self.total_escrowed = self.total_escrowed.saturating_sub(entry.amount);
+        Some(entry.amount)
+    }
+}
+
+/// An investor's locked amount published by the policy's lock oracle (LockerKind::Oracle)
+///
+/// For lockups the router can't read on-chain (CEX custody, other chains). Only `policy.lock_oracle`
+/// can write it, through `publish_oracle_locked`; the investor's `InvestorData.stream` names it.
+#[account]
+pub struct OracleLockedPda {
+    pub vault_seed: String,
+    pub investor: Pubkey,
+    pub locked_amount: u64,
+    pub as_of_ts: u64,                    // when the oracle observed locked_amount
+    pub oracle: Pubkey,                   // publisher; a rotated policy.lock_oracle invalidates the amount
+    pub updated_at: u64,
+}
+
+impl OracleLockedPda {
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        32 + // investor
+        8 + // locked_amount
+        8 + // as_of_ts
+        32 + // oracle
+        8 + // updated_at
+        32; // padding
+
+    pub fn seeds<'a>(vault_seed: &'a str, investor: &'a Pubkey) -> [&'a [u8]; 3] {
+        [vault_seed.as_bytes(), b"oracle_locked", investor.as_ref()]
+    }
+}
+
+/// Per-day bitset of investor indices already processed by the crank
+///
+/// The crank picks each investor's index, so the stream keys are held to strictly ascending order
+/// across the day as well: a stream can then only be processed once, whatever index it is sent at.
+#[account]
+pub struct PaidBitmapPda {
+    pub vault_seed: String,
+    pub day_epoch: u64,                   // day the bits belong to; cleared when a new day starts
+    pub bits: Vec<u8>,
+    pub last_stream: Pubkey,              // highest stream processed today (ordered pages)
+    pub page_bounds: Vec<u64>,            // first and last stream key prefix of each page started today (unordered pages)
+}
+
+impl PaidBitmapPda {
+    /// Maximum number of investors the bitmap can track
+    pub const MAX_INVESTORS: usize = 8_192;
+
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        8 + // day_epoch
+        4 + PaidBitmapPda::MAX_INVESTORS / 8 + // bits
+        32 + // last_stream
+        4 + PaidBitmapPda::PAGE_BOUNDS_LEN * 8 + // page_bounds
+        32; // padding
+
+    /// Two key prefixes per page the unordered page bitmap tracks
+    pub const PAGE_BOUNDS_LEN: usize = ProgressPda::MAX_TRACKED_PAGES as usize * 2;
+
+    pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
+        [vault_seed.as_bytes(), b"paid_bitmap"]
+    }
+
+    /// Clear all bits if they belong to an earlier day
+    pub fn roll_to_day(&mut self, day_epoch: u64) {
+        if self.day_epoch != day_epoch {
+            self.clear();
+            self.day_epoch = day_epoch;
+        }
+    }
+
+    /// Forget every investor and page processed so far
+    pub fn clear(&mut self) {
+        self.bits.clear();
+        self.bits.resize(Self::MAX_INVESTORS / 8, 0);
+        self.last_stream = Pubkey::default();
+        self.page_bounds.clear();
+        self.page_bounds.resize(Self::PAGE_BOUNDS_LEN, 0);
+    }
+
+    /// Require a page whose keys ascend from `first` to `last` to start above every stream
+    /// processed today, then record `last` (ordered pages)
+    pub fn advance_streams(&mut self, first: &Pubkey, last: &Pubkey) -> Result<()> {
+        require!(*first > self.last_stream, crate::error::FeeRouterError::InvestorKeysNotAscending);
+        self.last_stream = *last;
+        Ok(())
+    }
+
+    /// Record the stream key range of page `page_index`, whose keys are ascending from `first` to
+    /// `last`, failing if it overlaps the range of any of `processed_pages` (unordered pages)
+    ///
+    /// Ranges compare by the big-endian first 8 bytes of each key, so two pages may not even share
+    /// a prefix at their boundary.
+    pub fn claim_page_range(
+        &mut self,
+        page_index: u64,
+        first: &Pubkey,
+        last: &Pubkey,
+        processed_pages: impl IntoIterator<Item = u64>,
+    ) -> Result<()> {
+        let slot = usize::try_from(page_index)
+            .ok()
+            .filter(|slot| *slot < Self::PAGE_BOUNDS_LEN / 2)
+            .ok_or(crate::error::FeeRouterError::InvalidPaginationState)?;
+        let (first, last) = (Self::key_prefix(first), Self::key_prefix(last));
+        for other in processed_pages.into_iter().filter(|other| *other != page_index) {
+            let other = other as usize;
+            let (Some(other_first), Some(other_last)) =
+                (self.page_bounds.get(2 * other), self.page_bounds.get(2 * other + 1))
+            else {
+                continue;
+            };
+            require!(
+                *other_last < first || *other_first > last,
+                crate::error::FeeRouterError::InvestorKeysNotAscending
+            );
+        }
+        self.page_bounds[2 * slot] = first;
+        self.page_bounds[2 * slot + 1] = last;
+        Ok(())
+    }
+
+    fn key_prefix(key: &Pubkey) -> u64 {
+        let bytes = key.to_bytes();
+        u64::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]])
+    }
+
+    /// Mark `index` paid, failing if it already was
+    pub fn mark_paid(&mut self, index: u32) -> Result<()> {
+        let byte = self
+            .bits
+            .get_mut(index as usize / 8)
+            .ok_or(crate::error::FeeRouterError::InvestorIndexOutOfRange)?;
+        let mask = 1u8 << (index % 8);
+        require!(*byte & mask == 0, crate::error::FeeRouterError::InvestorAlreadyPaid);
+        *byte |= mask;
+        Ok(())
+    }
+}
+
+/// Where `distribute_fees` sends the share of an investor without a live attestation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum UnattestedRouting {
+    /// Dust: carried over into the next day's claim, as for a below-minimum payout
+    #[default]
+    CarryOver,
+    /// Left in the day's creator remainder
+    Creator,
+    /// Held for the next day's investor pool, as capped-out share is with roll_capped_excess
+    Withhold,
+}
+
+/// AMM backend the honorary position lives in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PoolKind {
+    /// Meteora DAMM v2 (CP-AMM) concentrated position
+    #[default]
+    CpAmm,
+    /// Meteora DLMM bin-range position
+    Dlmm,
+    /// Orca Whirlpool position (NFT held by the position owner PDA)
+    Whirlpool,
+    /// Raydium CLMM personal position (NFT held by the position owner PDA)
+    RaydiumClmm,
+}
+
+impl PoolKind {
+    /// Backend accounts the crank expects at the front of remaining_accounts
+    pub fn claim_remaining_accounts(&self) -> usize {
+        match self {
+            PoolKind::CpAmm => 0,
+            PoolKind::Dlmm => crate::dlmm::CLAIM_REMAINING_ACCOUNTS,
+            PoolKind::Whirlpool => crate::whirlpool::CLAIM_REMAINING_ACCOUNTS,
+            PoolKind::RaydiumClmm => crate::raydium_clmm::CLAIM_REMAINING_ACCOUNTS,
+        }
+    }
+}
+
+/// Where reward tokens the CP-AMM position earns are routed by `claim_rewards`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RewardRouting {
+    /// Every claim goes to the creator's reward token account
+    #[default]
+    Creator,
+    /// Each claim is paid pro-rata to investors by the day's locked snapshot, in pages
+    Investors,
+}
+
+#[account]
+pub struct InvestorFeePositionOwnerPda {
+    pub vault_seed: String,
+    pub position_pubkey: Pubkey,
+    pub pool_pubkey: Pubkey,
+    pub quote_mint: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub verified_quote_only: bool,
+    pub simulation_verified: bool,        // probe swaps through the live pool accrued no base fee at init
+    pub pool_kind: PoolKind,              // tick_lower/tick_upper are bin ids for DLMM
+    pub created_at: u64,
+    pub position_nft_mint: Pubkey,        // NFT proving position ownership; default for DLMM and positions opened before it was recorded
+    pub lifetime_claimed_quote: u128,     // quote fees ever claimed from the position, before transfer fees
+    pub lifetime_claimed_base: u128,      // base fees ever claimed (stays 0 while claims are quote-only)
+    pub pool_config_hash: [u8; 32],       // CP-AMM pool fee configuration verified at init (zero = not recorded)
+}
+
+impl InvestorFeePositionOwnerPda {
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        32 + // position_pubkey
+        32 + // pool_pubkey
+        32 + // quote_mint
+        4 + // tick_lower
+        4 + // tick_upper
+        1 + // verified_quote_only
+        1 + // simulation_verified
+        1 + // pool_kind
+        8 + // created_at
+        32 + // position_nft_mint
+        16 + // lifetime_claimed_quote
+        16 + // lifetime_claimed_base
+        32; // pool_config_hash
+
+    pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
+        [vault_seed.as_bytes(), b"investor_fee_pos_owner"]
+    }
+
+    /// Backend the vault's honorary position was opened in, or None before any position exists
+    ///
+    /// `account` is the position owner PDA, which every position initializer creates.
+    pub fn opened_pool_kind(account: &AccountInfo) -> Result<Option<PoolKind>> {
+        if account.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*account.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let data = account.try_borrow_data()?;
+        Ok(Some(InvestorFeePositionOwnerPda::try_deserialize(&mut &data[..])?.pool_kind))
+    }
+
+    /// Whether `account` holds exactly the position NFT with no delegate that could move it
+    ///
+    /// The mint is only compared once recorded; the token authority is checked by the caller.
+    pub fn holds_position_nft(&self, account: &TokenAccount) -> bool {
+        account.amount == 1
+            && account.delegate.is_none()
+            && (self.position_nft_mint == Pubkey::default() || account.mint == self.position_nft_mint)
+    }
+
+    /// Add one claim's gross amounts to the position's lifetime totals
+    pub fn record_claim(&mut self, quote_amount: u64, base_amount: u64) {
+        self.lifetime_claimed_quote = self.lifetime_claimed_quote.saturating_add(quote_amount as u128);
+        self.lifetime_claimed_base = self.lifetime_claimed_base.saturating_add(base_amount as u128);
+    }
+}
+
+/// Program-wide local-testing switch, writable only by the program's upgrade authority
+///
+/// With `relax_locker_checks` set, cranks that pass this account read mock vesting accounts:
+/// owner and recipient checks are skipped. Never create it on a real cluster.
+#[account]
+pub struct LockerTestConfig {
+    pub relax_locker_checks: bool,
+    pub updated_at: u64,
+}
+
+impl LockerTestConfig {
+    pub const SEED: &'static [u8] = b"locker_test_config";
+
+    pub const LEN: usize = 8 + // discriminator
+        1 + // relax_locker_checks
+        8 + // updated_at
+        32; // padding
+}
+
+/// Program-wide cluster selection, writable only by the program's upgrade authority: ["network_config"]
+///
+/// Uninitialized, the router reads Streamflow at its mainnet ID and records any CP-AMM program at
+/// `initialize_policy`.
+#[account]
+pub struct NetworkConfig {
+    pub network: Network,
+    pub programs: NetworkPrograms,        // the network's canonical IDs, or a fork's
+    pub updated_at: u64,
+}
+
+impl NetworkConfig {
+    pub const SEED: &'static [u8] = b"network_config";
+
+    pub const LEN: usize = 8 + // discriminator
+        1 + // network
+        NetworkPrograms::LEN + // programs
+        8 + // updated_at
+        64; // padding for future programs
+
+    /// The config held by its PDA account, or None before it is initialized
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+        let data = info.try_borrow_data()?;
+        Ok(Some(Self::try_deserialize(&mut &data[..])?))
+    }
+
+    /// Streamflow program lockers are read from; mainnet's while the config is uninitialized
+    pub fn streamflow_program_id(config: Option<&Self>) -> Pubkey {
+        config.map_or(STREAMFLOW_PROGRAM_ID, |config| config.programs.streamflow)
+    }
+
+    /// Reject a CP-AMM program other than the configured network's
+    pub fn require_cp_amm_program(config: Option<&Self>, program_id: &Pubkey) -> Result<()> {
+        if let Some(config) = config {
+            require_keys_eq!(*program_id, config.programs.cp_amm, crate::error::FeeRouterError::NetworkProgramMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Program-wide protocol fee settings, one per deployment: ["protocol_config"]
+#[account]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,                    // may update the config
+    pub protocol_fee_bps: u16,            // share of every claim skimmed before the investor/creator split
+    pub protocol_treasury: Pubkey,        // wallet whose quote ATAs receive the protocol fee
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl ProtocolConfig {
+    pub const SEED: &'static [u8] = b"protocol_config";
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // admin
+        2 + // protocol_fee_bps
+        32 + // protocol_treasury
+        8 + // created_at
+        8 + // updated_at
+        64; // padding for future fields
+
+    /// Upper bound for protocol_fee_bps (10%)
+    pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+    /// The config held by its PDA account, or None before it is initialized
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+        let data = info.try_borrow_data()?;
+        Ok(Some(Self::try_deserialize(&mut &data[..])?))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.protocol_fee_bps <= Self::MAX_PROTOCOL_FEE_BPS
+                && (self.protocol_fee_bps == 0 || self.protocol_treasury != Pubkey::default()),
+            crate::error::FeeRouterError::InvalidProtocolConfig
+        );
+        Ok(())
+    }
+}
+
+/// Vaults created by one authority, so they can be listed without scanning the program: ["vault_registry", authority]
+///
+/// `initialize_policy` appends each new policy PDA. Entries are keyed by the authority that created
+/// the vault; handing the policy authority on later does not move them.
+#[account]
+pub struct VaultRegistry {
+    pub authority: Pubkey,
+    pub vaults: Vec<Pubkey>,              // policy PDAs, in creation order
+    pub updated_at: u64,
+}
+
+impl VaultRegistry {
+    pub const SEED: &'static [u8] = b"vault_registry";
+
+    /// Maximum number of vaults one authority can register
+    pub const MAX_VAULTS: usize = 64;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        4 + VaultRegistry::MAX_VAULTS * 32 + // vaults
+        8 + // updated_at
+        32; // padding
+
+    pub fn seeds(authority: &Pubkey) -> [&[u8]; 2] {
+        [Self::SEED, authority.as_ref()]
+    }
+
+    /// Append a vault's policy PDA
+    pub fn register(&mut self, policy: Pubkey) -> Result<()> {
+        require!(
+            self.vaults.len() < Self::MAX_VAULTS,
+            crate::error::FeeRouterError::VaultRegistryFull
+        );
+        self.vaults.push(policy);
+        Ok(())
+    }
+}
+
+/// Authority-maintained per-investor minimum payouts: [vault_seed, "payout_overrides"]
+///
+/// Listed investors are held to their own threshold instead of `PolicyPda.min_payout_lamports`.
+#[account]
+pub struct PayoutOverridesPda {
+    pub vault_seed: String,
+    pub entries: Vec<PayoutOverride>,
+    pub updated_at: u64,
+}
+
+/// One investor's minimum payout
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PayoutOverride {
+    pub investor: Pubkey,
+    pub min_payout_lamports: u64,
+}
+
+impl PayoutOverride {
+    pub const LEN: usize = 32 + 8;
+}
+
+impl PayoutOverridesPda {
+    /// Maximum number of investors with an override
+    pub const MAX_ENTRIES: usize = 64;
+
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        4 + PayoutOverridesPda::MAX_ENTRIES * PayoutOverride::LEN + // entries
+        8 + // updated_at
+        32; // padding
+
+    pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
+        [vault_seed.as_bytes(), b"payout_overrides"]
+    }
+
+    /// `investor`'s minimum payout, or `default` without an override
+    pub fn min_payout_for(&self, investor: &Pubkey, default: u64) -> u64 {
+        self.entries
+            .iter()
+            .find(|e| e.investor == *investor)
+            .map_or(default, |e| e.min_payout_lamports)
+    }
+
+    /// Set `investor`'s override, or remove it with `None`
+    pub fn set(&mut self, investor: &Pubkey, min_payout_lamports: Option<u64>) -> Result<()> {
+        let existing = self.entries.iter().position(|e| e.investor == *investor);
+        match (existing, min_payout_lamports) {
+            (Some(i), Some(min_payout_lamports)) => self.entries[i].min_payout_lamports = min_payout_lamports,
+            (Some(i), None) => {
+                self.entries.swap_remove(i);
+            }
+            (None, Some(min_payout_lamports)) => {
+                require!(
+                    self.entries.len() < Self::MAX_ENTRIES,
+                    crate::error::FeeRouterError::PayoutOverridesFull
+                );
+                self.entries.push(PayoutOverride { investor: *investor, min_payout_lamports });
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Authority-maintained fixed investor weights: [vault_seed, "weight_overrides"]
+///
+/// A listed investor's weight, in locked-token units, is added to what its stream has locked, in the
+/// snapshot and in payouts alike. It only changes between days, so a day's snapshot stays valid.
+#[account]
+pub struct WeightOverridesPda {
+    pub vault_seed: String,
+    pub entries: Vec<WeightOverride>,
+    pub updated_at: u64,
+}
+
+/// One investor's fixed weight
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeightOverride {
+    pub investor: Pubkey,
+    pub weight: u64,
+}
+
+impl WeightOverride {
+    pub const LEN: usize = 32 + 8;
+}
+
+impl WeightOverridesPda {
+    /// Maximum number of investors with a weight override
+    pub const MAX_ENTRIES: usize = 64;
+
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        4 + WeightOverridesPda::MAX_ENTRIES * WeightOverride::LEN + // entries
+        8 + // updated_at
+        32; // padding
+
+    pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
+        [vault_seed.as_bytes(), b"weight_overrides"]
+    }
+
+    /// `investor`'s fixed weight (0 without an override)
+    pub fn weight_for(&self, investor: &Pubkey) -> u64 {
+        self.entries
+            .iter()
+            .find(|e| e.investor == *investor)
+            .map_or(0, |e| e.weight)
+    }
+
+    /// Set `investor`'s weight, or remove it with `None`
+    pub fn set(&mut self, investor: &Pubkey, weight: Option<u64>) -> Result<()> {
+        let existing = self.entries.iter().position(|e| e.investor == *investor);
+        match (existing, weight) {
+            (Some(i), Some(weight)) => self.entries[i].weight = weight,
+            (Some(i), None) => {
+                self.entries.swap_remove(i);
+            }
+            (None, Some(weight)) => {
+                require!(
+                    self.entries.len() < Self::MAX_ENTRIES,
+                    crate::error::FeeRouterError::WeightOverridesFull
+                );
+                self.entries.push(WeightOverride { investor: *investor, weight });
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Recent `distribute_fees` calls, oldest overwritten first: [vault_seed, "crank_log"]
+///
+/// Lets the vault owner see which keeper moved what without an off-chain indexer.
+#[account]
+pub struct CrankLogPda {
+    pub vault_seed: String,
+    pub entries: Vec<CrankLogEntry>,
+    /// Slot of `entries` the next call is written to once the log is full
+    pub next_index: u16,
+    /// Calls recorded since the log was created
+    pub total_calls: u64,
+}
+
+/// One `distribute_fees` call
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrankLogEntry {
+    pub caller: Pubkey,
+    pub day_epoch: u64,
+    /// Pages the call completed
+    pub pages: u32,
+    pub investors_processed: u32,
+    /// Quote paid out to investors by the call
+    pub distributed: u64,
+    pub day_finalized: bool,
+    pub timestamp: u64,
+}
+
+impl CrankLogEntry {
+    pub const LEN: usize = 32 + 8 + 4 + 4 + 8 + 1 + 8;
+}
+
+impl CrankLogPda {
+    /// Calls kept before the oldest is overwritten
+    pub const MAX_ENTRIES: usize = 32;
+
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        4 + CrankLogPda::MAX_ENTRIES * CrankLogEntry::LEN + // entries
+        2 + // next_index
+        8 + // total_calls
+        32; // padding
+
+    pub fn seeds(vault_seed: &str) -> [&[u8]; 2] {
+        [vault_seed.as_bytes(), b"crank_log"]
+    }
+
+    /// Append `entry`, overwriting the oldest one when the log is full
+    pub fn record(&mut self, entry: CrankLogEntry) {
+        if self.entries.len() < Self::MAX_ENTRIES {
+            self.entries.push(entry);
+        } else {
+            self.entries[self.next_index as usize] = entry;
+        }
+        self.next_index = ((self.next_index as usize + 1) % Self::MAX_ENTRIES) as u16;
+        self.total_calls = self.total_calls.saturating_add(1);
+    }
+
+    /// Entries from newest to oldest
+    pub fn recent(&self) -> impl Iterator<Item = &CrankLogEntry> {
+        let (older, newer) = self.entries.split_at(self.next_index as usize % self.entries.len().max(1));
+        newer.iter().chain(older.iter()).rev()
+    }
+}
+
+/// One reward mint's payout to investors under `RewardRouting::Investors`
+///
+/// A round starts with a reward claim and pays what the reward treasury then holds across
+/// investor pages, weighted by the locked snapshot of the day it started in. Rewards a round
+/// could not deliver stay in the treasury and join the next round.
+#[account]
+pub struct RewardRoundPda {
+    pub vault_seed: String,
+    pub reward_mint: Pubkey,
+    /// Distribution day whose snapshot the round pays by
+    pub day_epoch: u64,
+    /// Reward treasury balance when the round started; the pages apportion it
+    pub pool: u64,
+    pub distributed: u64,
+    /// Next investor page the round expects
+    pub pagination_cursor: u64,
+    /// Payout weight of the investors of the round's completed pages
+    pub weight_processed: u128,
+    /// Snapshot fold over the round's investors; must equal snapshot_locked_hash at the last page
+    pub locked_hash: [u8; 32],
+    pub active: bool,
+    pub rounds_completed: u64,
+    pub updated_at: u64,
+}
+
+impl RewardRoundPda {
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 + // vault_seed (String)
+        32 + // reward_mint
+        8 + // day_epoch
+        8 + // pool
+        8 + // distributed
+        8 + // pagination_cursor
+        16 + // weight_processed
+        32 + // locked_hash
+        1 + // active
+        8 + // rounds_completed
+        8 + // updated_at
+        32; // padding
+
+    pub fn seeds<'a>(vault_seed: &'a str, reward_mint: &'a Pubkey) -> [&'a [u8]; 3] {
+        [vault_seed.as_bytes(), b"reward_round", reward_mint.as_ref()]
+    }
+
+    /// Whether an open round may take further pages on `day_epoch`; a day change abandons it
+    pub fn is_open(&self, day_epoch: u64) -> bool {
+        self.active && self.day_epoch == day_epoch
+    }
+
+    /// Open a round paying `pool` by the snapshot of `day_epoch`
+    pub fn start(&mut self, day_epoch: u64, pool: u64) {
+        self.day_epoch = day_epoch;
+        self.pool = pool;
+        self.distributed = 0;
+        self.pagination_cursor = 0;
+        self.weight_processed = 0;
+        self.locked_hash = [0u8; 32];
+        self.active = true;
+    }
+
+    /// Close the round once its investors are exactly the snapshot's
+    pub fn complete(&mut self, snapshot_locked_hash: &[u8; 32]) -> Result<()> {
+        require!(
+            self.locked_hash == *snapshot_locked_hash,
+            crate::error::FeeRouterError::SnapshotMismatch
+        );
+        self.active = false;
+        self.rounds_completed = self.rounds_completed.saturating_add(1);
+        Ok(())
+    }
+}
+
+/// Distribution math lives in `meteor-route-core` so clients compute identical payouts
+pub use meteor_route_core::{DistributionMath, WeightCap};
+
+// NOTE: `InitializeHonoraryPosition` Accounts is defined under `instructions/initialize_honorary_position.rs`.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_progress() -> ProgressPda {
+        ProgressPda {
+            vault_seed: "vault".to_string(),
+            last_distribution_ts: 0,
+            day_epoch: 0,
+            cumulative_distributed_today: 0,
+            carry_over_lamports: 0,
+            pagination_cursor: 0,
+            page_in_progress_flag: false,
+            day_finalized_flag: false,
+            total_pages_expected: 0,
+            pages_processed_today: 0,
+            last_claimed_quote: 0,
+            last_claimed_base: 0,
+            day_total_locked: 0,
+            day_investor_pool_target: 0,
+            day_investor_distributed: 0,
+            day_creator_remainder_target: 0,
+            day_claimed_quote: 0,
+            page_hash_chain: [0u8; 32],
+            rollover_claimed_quote: 0,
+            capped_excess_rollover: 0,
+            day_capped_excess_in: 0,
+            snapshot_ts: 0,
+            snapshot_total_locked: 0,
+            snapshot_investor_count: 0,
+            snapshot_locked_hash: [0u8; 32],
+            snapshot_complete: false,
+            payout_locked_hash: [0u8; 32],
+            day_locked_processed: 0,
+            transfer_fees_today: 0,
+            failed_owed_today: 0,
+            creator_owed: 0,
+            buyback_pending: 0,
+            day_started_ts: 0,
+            active_cranker: Pubkey::default(),
+            lease_expiry_slot: 0,
+            generation: 0,
+            page_investor_cursor: 0,
+            page_in_progress_hash: [0u8; 32],
+            day_started_slot: 0,
+            snapshot_largest_locked: [0; ProgressPda::LARGEST_LOCKED_TRACKED],
+            weight_cap_bps: 0,
+            weight_cap_threshold: 0,
+            weight_cap_count: 0,
+            weight_cap_uncapped_locked: 0,
+            day_weight_processed: 0,
+            unused_cap_allowance: 0,
+            cap_allowance_epoch: 0,
+            committed_investor_count: 0,
+            day_investors_processed: 0,
+            page_bitmap: [0u8; 64],
+            snapshot_locked_set: [0u8; 32],
+            payout_locked_set: [0u8; 32],
+            snapshot_last_stream: Pubkey::default(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_reinitialize() {
+        let mut progress = default_progress();
+        assert!(progress.can_reinitialize());
+
+        progress.start_new_day(100_000);
+        progress.set_day_targets(10, 1_000, 500);
+        progress.acquire_lease(&Pubkey::new_unique(), 10, 100).unwrap();
+        progress.rollover_claimed_quote = 70;
+        // An open day blocks a restart
+        assert!(!progress.can_reinitialize());
+
+        progress.finalize_day(150_000, 0, 0);
+        progress.creator_owed = 5;
+        assert!(!progress.can_reinitialize());
+        progress.creator_owed = 0;
+        assert!(progress.can_reinitialize());
+
+        assert_eq!(progress.reinitialize(160_000).unwrap(), 1);
+        assert_eq!(progress.day_investor_pool_target, 0);
+        assert_eq!(progress.lease_expiry_slot, 0);
+        // Quote held for later days carries over
+        assert_eq!(progress.rollover_claimed_quote, 70);
+        // The finalized day stays closed and the 24h gate still runs from it
+        assert_eq!((progress.day_epoch, progress.last_distribution_ts, progress.day_started_ts), (1, 150_000, 100_000));
+        assert!(progress.day_finalized_flag);
+        assert!(progress.can_reinitialize());
+        let gate = DayGate::Timestamp { grace_secs: 0 };
+        assert!(!progress.roll_day(gate, 160_000, 0).unwrap());
+        assert!(!progress.can_start_new_day(200_000, 0));
+        assert!(progress.roll_day(gate, 150_000 + 86_400, 0).unwrap());
+        assert_eq!(progress.day_epoch, 2);
+
+        progress.generation = u16::MAX;
+        assert!(progress.reinitialize(170_000).is_err());
+    }
+
+    #[test]
+    fn test_settle_stalled_day() {
+        let mut progress = default_progress();
+        progress.start_new_day(100_000);
+        assert!(!progress.is_stalled(100_000 + 71_999, 72_000));
+        assert!(progress.is_stalled(100_000 + 72_000, 72_000));
+        assert!(!progress.is_stalled(u64::MAX, 0));
+
+        // Pool 1_000 (300 paid, 100 failed), creator 500 of which 50 capped out today
+        progress.set_day_targets(0, 1_000, 500);
+        progress.day_investor_distributed = 300;
+        progress.failed_owed_today = 100;
+        progress.capped_excess_rollover = 50;
+
+        let mut to_creator = progress.clone();
+        assert_eq!(to_creator.settle_stalled_day(false).unwrap(), (600, 1_050));
+        assert_eq!(to_creator.capped_excess_rollover, 50);
+
+        assert_eq!(progress.settle_stalled_day(true).unwrap(), (600, 450));
+        assert_eq!(progress.capped_excess_rollover, 650);
+    }
+
+    #[test]
+    fn test_crank_lease() {
+        let mut progress = default_progress();
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_eq!(progress.acquire_lease(&alice, 1_000, 150).unwrap(), 1_150);
+        assert!(progress.lease_blocks(&bob, 1_149));
+        assert!(!progress.lease_blocks(&alice, 1_149));
+        assert!(progress.acquire_lease(&bob, 1_100, 150).is_err());
+
+        // The holder can renew; anyone can take an expired lease
+        assert_eq!(progress.acquire_lease(&alice, 1_100, 150).unwrap(), 1_250);
+        assert!(!progress.lease_blocks(&bob, 1_250));
+        assert_eq!(progress.acquire_lease(&bob, 1_250, 150).unwrap(), 1_400);
+
+        // Finalizing the day releases the lease
+        progress.finalize_day(0, 0, 0);
+        assert!(!progress.lease_blocks(&alice, 1_300));
+    }
+
+    #[test]
+    fn test_eligible_bps_calc() {
+        // 0/1000 -> 0 bps
+        let bps0 = DistributionMath::calculate_eligible_bps(0, 1000, 9000).unwrap();
+        assert_eq!(bps0, 0);
+
+        // 600/1000 -> 6000 bps, min with 9000 -> 6000
+        let bps1 = DistributionMath::calculate_eligible_bps(600, 1000, 9000).unwrap();
+        assert_eq!(bps1, 6000);
+
+        // 1000/1000 with share 10_000 -> 10_000
+        let bps2 = DistributionMath::calculate_eligible_bps(1000, 1000, 10_000).unwrap();
+        assert_eq!(bps2, 10_000);
+    }
+
+    #[test]
+    fn test_investor_fee_and_payouts() {
+        // claimed 1_000_000, fully locked, share 9000 bps -> 900,000 investor pool
+        let pool = DistributionMath::calculate_investor_fee_quote(
+            1_000_000,
+            DistributionMath::LOCKED_FRACTION_SCALE,
+            9000,
+        )
+        .unwrap();
+        assert_eq!(pool, 900_000);
+
+        // Split 60/40
+        let p60 = DistributionMath::calculate_investor_payout(60, 100, pool).unwrap();
+        let p40 = DistributionMath::calculate_investor_payout(40, 100, pool).unwrap();
+        assert_eq!(p60, 540_000);
+        assert_eq!(p40, 360_000);
+        assert_eq!(p60 + p40, pool);
+    }
+
+    #[test]
+    fn test_daily_cap() {
+        // 900k desired, cap 800k, none distributed yet -> 800k
+        let capped = DistributionMath::apply_daily_cap(900_000, 800_000, 0);
+        assert_eq!(capped, 800_000);
+    }
+
+    #[test]
+    fn test_progress_targets_and_distribution() {
+        let mut p = default_progress();
+        // Start new day resets fields
+        p.start_new_day(86_400);
+        assert_eq!(p.day_total_locked, 0);
+        assert_eq!(p.day_investor_distributed, 0);
+        assert_eq!(p.pages_processed_today, 0);
+
+        // Set day targets
+        p.set_day_targets(1_000, 900_000, 100_000);
+        assert_eq!(p.day_total_locked, 1_000);
+        assert_eq!(p.day_investor_pool_target, 900_000);
+        assert_eq!(p.day_creator_remainder_target, 100_000);
+
+        // Track distribution
+        p.add_investor_distribution(540_000).unwrap();
+        p.add_investor_distribution(360_000).unwrap();
+        assert_eq!(p.day_investor_distributed, 900_000);
+    }
+
+    #[test]
+    fn test_page_suspend_and_resume() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        assert!(p.first_page_pending());
+        assert_eq!(p.page_resume_index(&[1u8; 32]).unwrap(), 0);
+
+        // A first page stopped partway no longer counts as pending
+        p.suspend_page(&[1u8; 32], 3);
+        assert!(!p.first_page_pending());
+        assert_eq!(p.page_resume_index(&[1u8; 32]).unwrap(), 3);
+        // Only the suspended page may resume
+        assert!(p.page_resume_index(&[2u8; 32]).is_err());
+
+        // Completing the page clears the suspension
+        p.record_page_hash(&[1u8; 32]);
+        assert!(!p.page_in_progress_flag);
+        assert_eq!(p.page_investor_cursor, 0);
+        assert_eq!(p.page_resume_index(&[2u8; 32]).unwrap(), 0);
+
+        // So does a new day
+        p.suspend_page(&[2u8; 32], 1);
+        p.start_new_day(2 * 86_400);
+        assert!(p.first_page_pending());
+        assert_eq!(p.page_resume_index(&[9u8; 32]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_expected_pages_reached() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        // Unknown until the count is committed
+        p.pagination_cursor = 3;
+        assert!(!p.expected_pages_reached());
+
+        p.commit_expected_pages(3, 0).unwrap();
+        p.pagination_cursor = 2;
+        assert!(!p.expected_pages_reached());
+        p.pagination_cursor = 3;
+        assert!(p.expected_pages_reached());
+        // Overshooting still finalizes, so the page count check rejects the call
+        p.pagination_cursor = 4;
+        assert!(p.expected_pages_reached());
+
+        // Yesterday's count doesn't finalize a day with more pages
+        p.finalize_day(90_000, 0, 0);
+        p.start_new_day(2 * 86_400);
+        p.pagination_cursor = 3;
+        assert_eq!(p.total_pages_expected, 0);
+        assert!(!p.expected_pages_reached());
+    }
+
+    #[test]
+    fn test_unordered_page_completion() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        p.commit_expected_pages(4, 0).unwrap();
+
+        // Later pages complete first; the cursor waits at the lowest pending page
+        p.complete_page(2);
+        p.complete_page(1);
+        assert_eq!(p.pagination_cursor, 0);
+        assert!(p.page_processed(1) && p.page_processed(2));
+        assert!(!p.page_processed(0) && !p.page_processed(3));
+        assert!(!p.expected_pages_reached());
+
+        p.complete_page(0);
+        assert_eq!(p.pagination_cursor, 3);
+        p.complete_page(3);
+        assert!(p.expected_pages_reached());
+
+        // In-order pages past the tracked range still advance the cursor
+        p.start_new_day(2 * 86_400);
+        assert!(!p.page_processed(1));
+        p.pagination_cursor = ProgressPda::MAX_TRACKED_PAGES;
+        p.complete_page(ProgressPda::MAX_TRACKED_PAGES);
+        assert_eq!(p.pagination_cursor, ProgressPda::MAX_TRACKED_PAGES + 1);
+    }
+
+    #[test]
+    fn test_locked_set_is_order_independent() {
+        let (s1, i1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (s2, i2) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut p = default_progress();
+        p.add_snapshot_entry(&s1, &i1, 3_000).unwrap();
+        p.add_snapshot_entry(&s2, &i2, 1_000).unwrap();
+
+        // Paying the second page first reproduces the set, not the ordered fold
+        let mut set = ProgressPda::add_locked_set_entry(&[0u8; 32], &s2, &i2, 1_000);
+        set = ProgressPda::add_locked_set_entry(&set, &s1, &i1, 3_000);
+        assert_eq!(set, p.snapshot_locked_set);
+        let mut fold = ProgressPda::fold_locked_entry(&[0u8; 32], &s2, &i2, 1_000);
+        fold = ProgressPda::fold_locked_entry(&fold, &s1, &i1, 3_000);
+        assert_ne!(fold, p.snapshot_locked_hash);
+
+        // A changed amount or a repeated investor does not
+        let changed = ProgressPda::add_locked_set_entry(&ProgressPda::add_locked_set_entry(&[0u8; 32], &s2, &i2, 1_000), &s1, &i1, 3_001);
+        assert_ne!(changed, p.snapshot_locked_set);
+        let repeated = ProgressPda::add_locked_set_entry(&p.snapshot_locked_set, &s1, &i1, 3_000);
+        assert_ne!(repeated, p.snapshot_locked_set);
+    }
+
+    #[test]
+    fn test_committed_investor_count() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        assert!(p.commit_expected_pages(0, 5).is_err());
+        p.commit_expected_pages(2, 5).unwrap();
+        assert_eq!(p.total_pages_expected, 2);
+
+        // Pages may not cover more investors than committed, and the day must cover them all
+        p.record_investors_processed(3).unwrap();
+        assert_eq!(
+            p.committed_investors_covered(),
+            Err(CrankMismatch::InvestorCount { expected: 5, provided: 3 })
+        );
+        p.pages_processed_today = 1;
+        assert!(p.commit_expected_pages(1, 3).is_err());
+        assert!(p.record_investors_processed(3).is_err());
+        p.day_investors_processed = 5;
+        assert!(p.committed_investors_covered().is_ok());
+
+        // Both counts are per day
+        p.start_new_day(2 * 86_400);
+        assert_eq!((p.committed_investor_count, p.total_pages_expected), (0, 0));
+        p.record_investors_processed(9).unwrap();
+        assert!(p.committed_investors_covered().is_ok());
+
+        // The investor count must agree with the day's locked snapshot
+        p.snapshot_ts = 2 * 86_400;
+        p.add_snapshot_entry(&Pubkey::new_unique(), &Pubkey::new_unique(), 10).unwrap();
+        p.add_snapshot_entry(&Pubkey::new_unique(), &Pubkey::new_unique(), 20).unwrap();
+        assert!(p.commit_expected_pages(1, 1).is_err());
+        p.commit_expected_pages(1, 3).unwrap();
+        p.snapshot_complete = true;
+        assert!(p.commit_expected_pages(1, 3).is_err());
+        assert!(p.commit_expected_pages(1, 0).is_err());
+        p.commit_expected_pages(1, 2).unwrap();
+    }
+
+    #[test]
+    fn test_page_hash_chain() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        assert_eq!(p.page_hash_chain, [0u8; 32]);
+
+        p.record_page_hash(&[1u8; 32]);
+        p.record_page_hash(&[2u8; 32]);
+        let chained = p.page_hash_chain;
+
+        // Same pages in the same order reproduce the chain
+        let mut q = default_progress();
+        q.record_page_hash(&[1u8; 32]);
+        q.record_page_hash(&[2u8; 32]);
+        assert_eq!(q.page_hash_chain, chained);
+
+        // Order matters
+        let mut r = default_progress();
+        r.record_page_hash(&[2u8; 32]);
+        r.record_page_hash(&[1u8; 32]);
+        assert_ne!(r.page_hash_chain, chained);
+
+        // A new day resets the chain
+        p.start_new_day(2 * 86_400);
+        assert_eq!(p.page_hash_chain, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_day_gate_grace() {
+        let mut p = default_progress();
+        // First ever distribution is always allowed
+        assert!(p.can_start_new_day(10, 0));
+
+        p.last_distribution_ts = 86_400;
+        // 23h45m later: rejected without grace, allowed with 15 minutes of grace
+        let ts = 86_400 + 86_400 - 900;
+        assert!(!p.can_start_new_day(ts, 0));
+        assert!(p.can_start_new_day(ts, 900));
+        assert!(!p.can_start_new_day(ts - 1, 900));
+    }
+
+    #[test]
+    fn test_slot_day_gate() {
+        let gate = DayGate::Slots(216_000);
+        let mut p = default_progress();
+        assert!(p.roll_day(gate, 86_400, 1_000_000).unwrap());
+        assert_eq!((p.day_epoch, p.day_started_slot), (1, 1_000_000));
+
+        // Slots, not the UTC day, decide: a later UTC day is not enough...
+        assert!(!p.roll_day(gate, 3 * 86_400, 1_215_999).unwrap());
+        // ...and an early one is no obstacle, but still gets its own epoch
+        p.finalize_day(86_500, 0, 0);
+        assert!(p.roll_day(gate, 86_600, 1_216_000).unwrap());
+        assert_eq!((p.day_epoch, p.day_started_slot), (2, 1_216_000));
+        assert!(!p.day_finalized_flag);
+
+        // The timestamp gate ignores slots and keeps its 24h check
+        let gate = DayGate::Timestamp { grace_secs: 0 };
+        p.finalize_day(2 * 86_400 + 50_000, 0, 0);
+        assert!(!p.is_new_day_under(gate, 2 * 86_400 + 60_000, u64::MAX));
+        assert!(p.roll_day(gate, 3 * 86_400, 0).is_err());
+        assert!(p.roll_day(gate, 3 * 86_400 + 50_000, 0).unwrap());
+        assert_eq!(p.day_epoch, 3);
+    }
+
+    #[test]
+    fn test_aligned_day_gate() {
+        // Days start at 06:00 UTC
+        let gate = DayGate::Aligned { offset_secs: 21_600, period_secs: 86_400 };
+        assert_eq!(gate.epoch_at(86_400 + 21_599), 0);
+        assert_eq!(gate.epoch_at(86_400 + 21_600), 1);
+
+        let mut p = default_progress();
+        assert!(p.roll_day(gate, 86_400 + 30_000, 0).unwrap());
+        assert_eq!(p.day_epoch, 1);
+
+        // A late finalize doesn't push the next boundary back
+        p.finalize_day(2 * 86_400 + 20_000, 0, 0);
+        assert!(!p.roll_day(gate, 2 * 86_400 + 21_599, 0).unwrap());
+        assert!(p.roll_day(gate, 2 * 86_400 + 21_600, 0).unwrap());
+        assert_eq!(p.day_epoch, 2);
+    }
+
+    #[test]
+    fn test_tranche_day_gate() {
+        // Four tranches a day: 6h periods from 00:00 UTC
+        let gate = DayGate::Aligned { offset_secs: 0, period_secs: 21_600 };
+        assert_eq!(gate.epoch_at(86_400 + 21_599), 4);
+        assert_eq!(gate.epoch_start(6), 86_400 + 43_200);
+
+        let mut p = default_progress();
+        assert!(p.roll_day(gate, 86_400 + 30_000, 0).unwrap());
+        assert_eq!(p.day_epoch, 5);
+
+        // The next tranche opens at its boundary, not 24h later
+        p.finalize_day(86_400 + 31_000, 0, 0);
+        assert!(!p.roll_day(gate, 86_400 + 43_199, 0).unwrap());
+        assert!(p.roll_day(gate, 86_400 + 43_200, 0).unwrap());
+        assert_eq!(p.day_epoch, 6);
+    }
+
+    #[test]
+    fn test_defer_day_rolls_over_claim() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        p.defer_day(86_500, 1_234).unwrap();
+        assert!(p.day_finalized_flag);
+        assert_eq!(p.last_distribution_ts, 86_500);
+        assert_eq!(p.rollover_claimed_quote, 1_234);
+
+        // Rollover survives the next day start so it can be added to that day's claim
+        p.start_new_day(2 * 86_400 + 100);
+        assert!(!p.day_finalized_flag);
+        assert_eq!(p.rollover_claimed_quote, 1_234);
+    }
+
+    #[test]
+    fn test_defer_day_keeps_earlier_claims() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        // Two claim-only cranks before the one that defers the day
+        p.record_day_claim(300).unwrap();
+        p.record_day_claim(200).unwrap();
+        p.defer_day(86_500, 40).unwrap();
+        assert_eq!(p.rollover_claimed_quote, 540);
+        assert_eq!(p.day_claimed_quote, 0);
+
+        p.start_new_day(2 * 86_400);
+        p.record_day_claim(u64::MAX).unwrap();
+        assert!(p.defer_day(2 * 86_400 + 100, 1).is_err());
+    }
+
+    #[test]
+    fn test_capped_excess_rollover() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+
+        // Cap of 800k binds on a 900k pool: 100k is held for tomorrow
+        let pool = 900_000u128;
+        let capped = DistributionMath::apply_daily_cap(pool, 800_000, 0);
+        p.roll_capped_excess((pool - capped) as u64).unwrap();
+        assert_eq!(p.capped_excess_rollover, 100_000);
+
+        // Next day folds it into the pool exactly once
+        p.start_new_day(2 * 86_400);
+        assert_eq!(p.take_capped_excess().unwrap(), 100_000);
+        assert_eq!(p.day_capped_excess_in, 100_000);
+        assert_eq!(p.take_capped_excess().unwrap(), 0);
+        assert_eq!(p.capped_excess_rollover, 0);
+    }
+
+    #[test]
+    fn test_unattested_share_routing() {
+        // 1M claimed, 600k paid, 100k owed to an unattested investor
+        let day = |routing| {
+            let mut p = default_progress();
+            p.start_new_day(86_400);
+            p.record_day_claim(1_000_000).unwrap();
+            p.cumulative_distributed_today = 600_000;
+            p.route_unattested(routing, 100_000).unwrap();
+            p
+        };
+
+        // Carry-over: dust for the next day's claim, out of the creator's share
+        let p = day(UnattestedRouting::CarryOver);
+        assert_eq!(p.carry_over_lamports, 100_000);
+        assert_eq!(p.capped_excess_rollover, 0);
+        assert_eq!(p.creator_remainder(), 300_000);
+
+        // Creator: nothing held back, the creator takes it with the remainder
+        let p = day(UnattestedRouting::Creator);
+        assert_eq!(p.carry_over_lamports, 0);
+        assert_eq!(p.capped_excess_rollover, 0);
+        assert_eq!(p.creator_remainder(), 400_000);
+
+        // Withhold: joins tomorrow's investor pool, out of the creator's share
+        let mut p = day(UnattestedRouting::Withhold);
+        assert_eq!(p.carry_over_lamports, 0);
+        assert_eq!(p.capped_excess_rollover, 100_000);
+        assert_eq!(p.creator_remainder(), 300_000);
+        p.start_new_day(2 * 86_400);
+        assert_eq!(p.take_capped_excess().unwrap(), 100_000);
+    }
+
+    #[test]
+    fn test_unused_cap_carries_forward() {
+        let mut p = default_progress();
+
+        // Quiet day: 200 of a 1_000 cap used, 800 banked (up to 3 days' cap)
+        p.start_new_day(86_400);
+        assert_eq!(p.apply_daily_cap(200, 1_000, 3_000), 200);
+        assert_eq!(p.unused_cap_allowance, 800);
+
+        // Days without a distribution bank their cap, up to the bound
+        p.start_new_day(6 * 86_400);
+        assert_eq!(p.banked_cap(1_000, 3_000), 3_000);
+        assert_eq!(p.apply_daily_cap(10_000, 1_000, 3_000), 4_000);
+        assert_eq!(p.unused_cap_allowance, 0);
+
+        // Carrying off: the plain cap, nothing banked
+        p.start_new_day(7 * 86_400);
+        assert_eq!(p.apply_daily_cap(200, 1_000, 0), 200);
+        assert_eq!(p.unused_cap_allowance, 0);
+    }
+
+    #[test]
+    fn test_locked_snapshot_matches_payout_fold() {
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        let (s1, i1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (s2, i2) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        p.commit_expected_pages(1, 2).unwrap();
+        p.snapshot_ts = 86_500;
+        p.add_snapshot_entry(&s1, &i1, 3_000).unwrap();
+        // The snapshot can't complete short of the committed count
+        assert!(p.complete_snapshot(0).is_err());
+        // Nor record a stream twice, or out of key order
+        assert!(p.add_snapshot_entry(&s1, &i1, 3_000).is_err());
+        assert!(p.add_snapshot_entry(&Pubkey::default(), &i2, 1_000).is_err());
+        p.add_snapshot_entry(&s2, &i2, 1_000).unwrap();
+        assert!(p.snapshot_in_progress());
+        p.complete_snapshot(0).unwrap();
+        assert!(p.snapshot_complete);
+        assert_eq!(p.snapshot_total_locked, 4_000);
+        assert_eq!(p.snapshot_investor_count, 2);
+
+        // Paying the same investors with the same amounts reproduces the snapshot hash
+        let mut payout = [0u8; 32];
+        payout = ProgressPda::fold_locked_entry(&payout, &s1, &i1, 3_000);
+        payout = ProgressPda::fold_locked_entry(&payout, &s2, &i2, 1_000);
+        assert_eq!(payout, p.snapshot_locked_hash);
+        assert!(!p.zero_locked_day());
+
+        // A changed amount does not
+        let mut tampered = [0u8; 32];
+        tampered = ProgressPda::fold_locked_entry(&tampered, &s1, &i1, 3_001);
+        tampered = ProgressPda::fold_locked_entry(&tampered, &s2, &i2, 1_000);
+        assert_ne!(tampered, p.snapshot_locked_hash);
+
+        p.start_new_day(2 * 86_400);
+        assert!(!p.snapshot_complete);
+        assert_eq!(p.snapshot_total_locked, 0);
+        assert!(!p.zero_locked_day());
+
+        // Fully vested streams complete a snapshot with nothing locked; the key order restarts daily
+        p.snapshot_ts = 2 * 86_400;
+        p.add_snapshot_entry(&s1, &i1, 0).unwrap();
+        p.commit_expected_pages(1, 1).unwrap();
+        p.complete_snapshot(0).unwrap();
+        assert!(p.zero_locked_day());
+    }
+
+    #[test]
+    fn test_uncommitted_day_runs_without_authority() {
+        // No set_expected_pages: the snapshot and payouts are all permissionless cranks
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        let (s1, i1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (s2, i2) = (Pubkey::new_unique(), Pubkey::new_unique());
+        p.snapshot_ts = 86_500;
+        p.add_snapshot_entry(&s1, &i1, 3_000).unwrap();
+        p.add_snapshot_entry(&s2, &i2, 1_000).unwrap();
+        p.complete_snapshot(0).unwrap();
+        assert_eq!((p.committed_investor_count, p.snapshot_investor_count), (0, 2));
+
+        // Two pages pay the snapshotted investors; the day closes with nothing to check them against
+        for (page, (stream, investor, locked)) in [(s1, i1, 3_000), (s2, i2, 1_000)].into_iter().enumerate() {
+            p.payout_locked_hash = ProgressPda::fold_locked_entry(&p.payout_locked_hash, &stream, &investor, locked);
+            p.record_investors_processed(1).unwrap();
+            p.record_page_hash(&[page as u8; 32]);
+            p.complete_page(page as u64);
+            p.pages_processed_today += 1;
+        }
+        assert!(p.committed_investors_covered().is_ok());
+        assert!(!p.expected_pages_reached());
+        assert_eq!(p.payout_locked_hash, p.snapshot_locked_hash);
+        assert_eq!(p.pagination_cursor, 2);
+
+        // The next day starts uncommitted again
+        p.start_new_day(2 * 86_400);
+        assert_eq!(p.committed_investor_count, 0);
+        p.snapshot_ts = 2 * 86_400;
+        p.add_snapshot_entry(&s1, &i1, 2_000).unwrap();
+        p.complete_snapshot(0).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_weight_cap() {
+        assert_eq!(ProgressPda::LARGEST_LOCKED_TRACKED, 21);
+        let mut p = default_progress();
+        p.start_new_day(86_400);
+        p.snapshot_ts = 86_500;
+        for locked in [10, 50, 0, 10, 30] {
+            p.add_snapshot_entry(&Pubkey::new_unique(), &Pubkey::new_unique(), locked).unwrap();
+        }
+        assert_eq!(p.snapshot_largest_locked[..5], [50, 30, 10, 10, 0]);
+
+        // A 30% cap clips 50 and then 30; the 10s share what is left
+        p.solve_weight_cap(3000).unwrap();
+        let cap = p.weight_cap();
+        assert_eq!((cap.capped_count, cap.threshold, cap.uncapped_locked), (2, 30, 20));
+        assert_eq!(cap.payout_weight(30).unwrap(), 3000 * 20);
+        assert_eq!(cap.payout_weight(10).unwrap(), 4000 * 10);
+        assert_eq!(cap.total_weight(100).unwrap(), 10_000 * 20);
+
+        p.solve_weight_cap(0).unwrap();
+        assert_eq!(p.weight_cap().payout_weight(50).unwrap(), 50);
+
+        p.start_new_day(2 * 86_400);
+        assert_eq!(p.snapshot_largest_locked, [0; 21]);
+        assert_eq!(p.weight_cap(), WeightCap::default());
+    }
+
+    #[test]
+    fn test_failed_payouts_ledger() {
+        let mut ledger = FailedPayoutsPda {
+            vault_seed: "vault".to_string(),
+            total_owed: 0,
+            entries: Vec::new(),
+            updated_at: 0,
+        };
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        ledger.record(&a, 100, 1).unwrap();
+        ledger.record(&b, 50, 1).unwrap();
+        ledger.record(&a, 25, 2).unwrap();
+        assert_eq!(ledger.entries.len(), 2);
+        assert_eq!(ledger.entries[0].amount, 125);
+        assert_eq!(ledger.entries[0].first_day_epoch, 1);
+        assert_eq!(ledger.total_owed, 175);
+
+        assert_eq!(ledger.take(&a), Some(125));
+        assert_eq!(ledger.take(&a), None);
+        assert_eq!(ledger.total_owed, 50);
+
+        // Full ledger rejects new investors but still merges existing ones
+        for _ in 1..FailedPayoutsPda::MAX_ENTRIES {
+            ledger.record(&Pubkey::new_unique(), 1, 3).unwrap();
+        }
+        assert!(ledger.record(&Pubkey::new_unique(), 1, 3).is_err());
+        assert!(ledger.record(&b, 1, 3).is_ok());
+    }
+
+    #[test]
+    fn test_payout_escrow() {
+        let mut escrow = PayoutEscrowPda {
+            vault_seed: "vault".to_string(),
+            investor: Pubkey::new_unique(),
+            total_escrowed: 0,
+            entries: Vec::new(),
+            updated_at: 0,
+        };
+        escrow.credit(1, 100, 1_000).unwrap();
+        escrow.credit(1, 20, 1_010).unwrap();
+        escrow.credit(2, 50, 2_000).unwrap();
+        assert_eq!(escrow.entries.len(), 2);
+        assert_eq!(escrow.entries[0], EscrowedPayout { day_epoch: 1, amount: 120, release_ts: 1_010 });
+        assert_eq!(escrow.total_escrowed, 170);
+
+        // Claw-back only inside the window
+        assert_eq!(escrow.claw_back(1, 1_010), None);
+        assert_eq!(escrow.claw_back(2, 1_500), Some(50));
+        assert_eq!(escrow.claw_back(2, 1_500), None);
+        assert_eq!(escrow.total_escrowed, 120);
+
+        assert_eq!(escrow.take_released(1_009), 0);
+        assert_eq!(escrow.take_released(1_010), 120);
+        assert!(escrow.entries.is_empty());
+        assert_eq!(escrow.total_escrowed, 0);
+
+        // A full escrow merges into its newest entry at the later release time
+        for day in 0..PayoutEscrowPda::MAX_ENTRIES as u64 {
+            escrow.credit(day, 1, 100 + day).unwrap();
+        }
+        escrow.credit(99, 5, 500).unwrap();
+        assert_eq!(escrow.entries.len(), PayoutEscrowPda::MAX_ENTRIES);
+        let newest = escrow.entries.last().unwrap();
+        assert_eq!((newest.amount, newest.release_ts), (6, 500));
+    }
+
+    #[test]
+    fn test_opened_pool_kind() {
+        let key = Pubkey::new_unique();
+        let (mut lamports, mut empty) = (0u64, vec![]);
+        let missing = AccountInfo::new(&key, false, false, &mut lamports, &mut empty, &crate::ID, false, 0);
+        assert_eq!(InvestorFeePositionOwnerPda::opened_pool_kind(&missing).unwrap(), None);
+
+        let owner = InvestorFeePositionOwnerPda {
+            vault_seed: "vault".to_string(),
+            position_pubkey: Pubkey::new_unique(),
+            pool_pubkey: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            tick_lower: 0,
+            tick_upper: 0,
+            verified_quote_only: true,
+            simulation_verified: false,
+            pool_kind: PoolKind::Whirlpool,
+            created_at: 0,
+            position_nft_mint: Pubkey::new_unique(),
+            lifetime_claimed_quote: 0,
+            lifetime_claimed_base: 0,
+            pool_config_hash: [0u8; 32],
+        };
+        let mut data = Vec::new();
+        owner.try_serialize(&mut data).unwrap();
+        let mut lamports = 1u64;
+        let opened = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &crate::ID, false, 0);
+        assert_eq!(InvestorFeePositionOwnerPda::opened_pool_kind(&opened).unwrap(), Some(PoolKind::Whirlpool));
+
+        // Anything not owned by the program is rejected rather than read
+        let foreign = Pubkey::new_unique();
+        let mut data = Vec::new();
+        owner.try_serialize(&mut data).unwrap();
+        let mut lamports = 1u64;
+        let spoofed = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &foreign, false, 0);
+        assert!(InvestorFeePositionOwnerPda::opened_pool_kind(&spoofed).is_err());
+    }
+
+    #[test]
+    fn test_holds_position_nft() {
+        use anchor_lang::solana_program::{program_option::COption, program_pack::Pack};
+        use anchor_spl::token_2022::spl_token_2022::state::{Account as SplAccount, AccountState};
+
+        let nft_mint = Pubkey::new_unique();
+        let owner = InvestorFeePositionOwnerPda {
+            vault_seed: "vault".to_string(),
+            position_pubkey: Pubkey::new_unique(),
+            pool_pubkey: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            tick_lower: 0,
+            tick_upper: 0,
+            verified_quote_only: true,
+            simulation_verified: false,
+            pool_kind: PoolKind::CpAmm,
+            created_at: 0,
+            position_nft_mint: nft_mint,
+            lifetime_claimed_quote: 0,
+            lifetime_claimed_base: 0,
+            pool_config_hash: [0u8; 32],
+        };
+        let nft_account = |mint: Pubkey, amount: u64, delegate: COption<Pubkey>| {
+            let account = SplAccount {
+                mint,
+                owner: Pubkey::new_unique(),
+                amount,
+                delegate,
+                state: AccountState::Initialized,
+                ..Default::default()
+            };
+            let mut data = vec![0u8; SplAccount::LEN];
+            account.pack_into_slice(&mut data);
+            TokenAccount::try_deserialize(&mut data.as_slice()).unwrap()
+        };
+
+        assert!(owner.holds_position_nft(&nft_account(nft_mint, 1, COption::None)));
+        assert!(!owner.holds_position_nft(&nft_account(Pubkey::new_unique(), 1, COption::None)));
+        assert!(!owner.holds_position_nft(&nft_account(nft_mint, 0, COption::None)));
+        assert!(!owner.holds_position_nft(&nft_account(nft_mint, 1, COption::Some(Pubkey::new_unique()))));
+
+        // Positions opened before the mint was recorded still check amount and delegate
+        let legacy = InvestorFeePositionOwnerPda {
+            position_nft_mint: Pubkey::default(),
+            ..owner
+        };
+        assert!(legacy.holds_position_nft(&nft_account(Pubkey::new_unique(), 1, COption::None)));
+        assert!(!legacy.holds_position_nft(&nft_account(Pubkey::new_unique(), 2, COption::None)));
+
+        // Claims accumulate past u64
+        let mut owner = legacy;
+        owner.record_claim(u64::MAX, 0);
+        owner.record_claim(5, 0);
+        assert_eq!(owner.lifetime_claimed_quote, u64::MAX as u128 + 5);
+        assert_eq!(owner.lifetime_claimed_base, 0);
+    }
+
+    #[test]
+    fn test_payout_overrides() {
+        let mut table = PayoutOverridesPda {
+            vault_seed: "vault".to_string(),
+            entries: Vec::new(),
+            updated_at: 0,
+        };
+        let whale = Pubkey::new_unique();
+        assert_eq!(table.min_payout_for(&whale, 1_000), 1_000);
+
+        table.set(&whale, Some(10)).unwrap();
+        table.set(&whale, Some(0)).unwrap();
+        assert_eq!(table.entries.len(), 1);
+        assert_eq!(table.min_payout_for(&whale, 1_000), 0);
+        table.set(&whale, None).unwrap();
+        assert_eq!(table.min_payout_for(&whale, 1_000), 1_000);
+
+        for _ in 0..PayoutOverridesPda::MAX_ENTRIES {
+            table.set(&Pubkey::new_unique(), Some(1)).unwrap();
+        }
+        assert!(table.set(&whale, Some(1)).is_err());
+        // Removing a missing entry is a no-op even when full
+        table.set(&whale, None).unwrap();
+    }
+
+    #[test]
+    fn test_weight_overrides() {
+        let mut table = WeightOverridesPda {
+            vault_seed: "vault".to_string(),
+            entries: Vec::new(),
+            updated_at: 0,
+        };
+        let partner = Pubkey::new_unique();
+        table.set(&partner, Some(5_000)).unwrap();
+        assert_eq!(table.weight_for(&partner), 5_000);
+        assert_eq!(table.weight_for(&Pubkey::new_unique()), 0);
+
+        // The weight is added to a live stream and stands alone for an ignored one
+        use crate::locker::{StreamIgnoredReason, StreamLocked};
+        let weight = table.weight_for(&partner);
+        assert_eq!(StreamLocked::Locked(100).with_weight(weight).unwrap(), StreamLocked::Locked(5_100));
+        assert_eq!(
+            StreamLocked::Ignored(StreamIgnoredReason::AccountClosed).with_weight(weight).unwrap(),
+            StreamLocked::Locked(5_000)
+        );
+        assert_eq!(
+            StreamLocked::Ignored(StreamIgnoredReason::Canceled).with_weight(0).unwrap(),
+            StreamLocked::Ignored(StreamIgnoredReason::Canceled)
+        );
+        assert!(StreamLocked::Locked(u64::MAX).with_weight(1).is_err());
+
+        table.set(&partner, None).unwrap();
+        assert!(table.entries.is_empty());
+    }
+
+    #[test]
+    fn test_crank_log_wraps() {
+        let mut log = CrankLogPda {
+            vault_seed: "vault".to_string(),
+            entries: Vec::new(),
+            next_index: 0,
+            total_calls: 0,
+        };
+        assert_eq!(log.recent().count(), 0);
+
+        let entry = |day_epoch| CrankLogEntry {
+            caller: Pubkey::default(),
+            day_epoch,
+            pages: 1,
+            investors_processed: 4,
+            distributed: 100,
+            day_finalized: false,
+            timestamp: day_epoch,
+        };
+        let calls = CrankLogPda::MAX_ENTRIES as u64 + 5;
+        for day in 0..calls {
+            log.record(entry(day));
+        }
+        assert_eq!(log.total_calls, calls);
+        assert_eq!(log.entries.len(), CrankLogPda::MAX_ENTRIES);
+
+        // Newest first, and the five oldest were overwritten
+        let days: Vec<u64> = log.recent().map(|e| e.day_epoch).collect();
+        assert_eq!(days.len(), CrankLogPda::MAX_ENTRIES);
+        assert_eq!(days[0], calls - 1);
+        assert_eq!(*days.last().unwrap(), 5);
+        assert!(days.windows(2).all(|w| w[0] == w[1] + 1));
+
+        // A full log still fits its account
+        let mut data = Vec::new();
+        log.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= CrankLogPda::LEN);
+    }
+
+    #[test]
+    fn test_vault_registry() {
+        let mut registry = VaultRegistry {
+            authority: Pubkey::new_unique(),
+            vaults: Vec::new(),
+            updated_at: 0,
+        };
+        let first = Pubkey::new_unique();
+        registry.register(first).unwrap();
+        for _ in 1..VaultRegistry::MAX_VAULTS {
+            registry.register(Pubkey::new_unique()).unwrap();
+        }
+        assert_eq!(registry.vaults[0], first);
+        assert!(registry.register(Pubkey::new_unique()).is_err());
+
+        // A full registry still fits its account
+        let mut data = Vec::new();
+        registry.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= VaultRegistry::LEN);
+    }
+
+    fn paid_bitmap() -> PaidBitmapPda {
+        PaidBitmapPda {
+            vault_seed: "vault".to_string(),
+            day_epoch: 1,
+            bits: vec![0u8; PaidBitmapPda::MAX_INVESTORS / 8],
+            last_stream: Pubkey::default(),
+            page_bounds: vec![0u64; PaidBitmapPda::PAGE_BOUNDS_LEN],
+        }
+    }
+
+    fn key(prefix: u8, tail: u8) -> Pubkey {
+        let mut bytes = [0u8; 32];
+        bytes[0] = prefix;
+        bytes[31] = tail;
+        Pubkey::new_from_array(bytes)
+    }
+
+    #[test]
+    fn test_paid_bitmap_len() {
+        let mut data = Vec::new();
+        paid_bitmap().try_serialize(&mut data).unwrap();
+        assert!(data.len() <= PaidBitmapPda::LEN);
+    }
+
+    #[test]
+    fn test_paid_bitmap() {
+        let mut bitmap = paid_bitmap();
+        bitmap.mark_paid(0).unwrap();
+        bitmap.mark_paid(9).unwrap();
+        assert!(bitmap.mark_paid(9).is_err());
+        assert!(bitmap.mark_paid(PaidBitmapPda::MAX_INVESTORS as u32).is_err());
+
+        // Same day keeps bits; a new day clears them
+        bitmap.roll_to_day(1);
+        assert!(bitmap.mark_paid(0).is_err());
+        bitmap.roll_to_day(2);
+        bitmap.mark_paid(0).unwrap();
+        bitmap.mark_paid(9).unwrap();
+    }
+
+    #[test]
+    fn test_paid_bitmap_rejects_duplicate_stream_in_ordered_pages() {
+        let mut bitmap = paid_bitmap();
+        let (a, b) = (key(1, 0), key(2, 0));
+
+        // Page 0 pays a at index 0 and b at index 1
+        bitmap.advance_streams(&a, &b).unwrap();
+        bitmap.mark_paid(0).unwrap();
+        bitmap.mark_paid(1).unwrap();
+
+        // Page 1 repeating b under a fresh index gets past the index bits, not the key order
+        bitmap.mark_paid(2).unwrap();
+        assert!(bitmap.advance_streams(&b, &key(3, 0)).is_err());
+        assert!(bitmap.advance_streams(&a, &a).is_err());
+        bitmap.advance_streams(&key(3, 0), &key(4, 0)).unwrap();
+
+        // A new day starts the order over
+        bitmap.roll_to_day(2);
+        bitmap.advance_streams(&a, &b).unwrap();
+    }
+
+    #[test]
+    fn test_paid_bitmap_rejects_duplicate_stream_in_unordered_pages() {
+        let mut bitmap = paid_bitmap();
+
+        // Page 1 covers keys 5..=6 and completes first
+        bitmap.claim_page_range(1, &key(5, 0), &key(6, 0), []).unwrap();
+        // Page 0 below and page 2 above it are disjoint
+        bitmap.claim_page_range(0, &key(1, 0), &key(4, 9), [1]).unwrap();
+        bitmap.claim_page_range(2, &key(7, 0), &key(9, 0), [0, 1]).unwrap();
+
+        // Page 3 repeating the stream at key 6 under other indexes overlaps page 1
+        assert!(bitmap.claim_page_range(3, &key(6, 0), &key(10, 0), [0, 1, 2]).is_err());
+        // A page lying inside another one's range is caught too
+        assert!(bitmap.claim_page_range(3, &key(8, 1), &key(8, 2), [0, 1, 2]).is_err());
+        // Sharing a key prefix at the boundary counts as overlapping
+        assert!(bitmap.claim_page_range(3, &key(9, 5), &key(10, 0), [0, 1, 2]).is_err());
+
+        // A resumed page only checks against the other pages; a page not processed is ignored
+        bitmap.claim_page_range(1, &key(5, 0), &key(6, 0), [0, 2]).unwrap();
+        bitmap.claim_page_range(3, &key(8, 1), &key(8, 2), [0, 1]).unwrap();
+        assert!(bitmap.claim_page_range(ProgressPda::MAX_TRACKED_PAGES, &key(20, 0), &key(21, 0), []).is_err());
+    }
+
+    #[test]
+    fn test_param_schedule_validation() {
+        let at = |effective_day_epoch, daily_cap_quote_lamports, min_payout_lamports| ScheduledParams {
+            effective_day_epoch,
+            daily_cap_quote_lamports,
+            min_payout_lamports,
+        };
+        assert!(ScheduledParams::is_valid_schedule(&[], 100));
+        assert!(ScheduledParams::is_valid_schedule(&[at(101, Some(1_000), None), at(105, None, Some(10))], 100));
+
+        // Due or past days, out of order, empty entries and overlong schedules are rejected
+        assert!(!ScheduledParams::is_valid_schedule(&[at(100, Some(1_000), None)], 100));
+        assert!(!ScheduledParams::is_valid_schedule(&[at(105, Some(1_000), None), at(101, Some(500), None)], 100));
+        assert!(!ScheduledParams::is_valid_schedule(&[at(101, Some(1_000), None), at(101, None, Some(10))], 100));
+        assert!(!ScheduledParams::is_valid_schedule(&[at(101, None, None)], 100));
+        let long: Vec<_> = (1..=5).map(|day| at(100 + day, Some(day), None)).collect();
+        assert!(!ScheduledParams::is_valid_schedule(&long, 100));
+    }
+
+    #[test]
+    fn test_protocol_config_validate() {
+        let mut config = ProtocolConfig {
+            admin: Pubkey::new_unique(),
+            protocol_fee_bps: 0,
+            protocol_treasury: Pubkey::default(),
+            created_at: 0,
+            updated_at: 0,
+        };
+        // No fee needs no treasury
+        assert!(config.validate().is_ok());
+        config.protocol_fee_bps = 500;
+        assert!(config.validate().is_err());
+        config.protocol_treasury = Pubkey::new_unique();
+        assert!(config.validate().is_ok());
+        config.protocol_fee_bps = ProtocolConfig::MAX_PROTOCOL_FEE_BPS + 1;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_reward_round_lifecycle() {
+        let mut round = RewardRoundPda {
+            vault_seed: "vault".to_string(),
+            reward_mint: Pubkey::new_unique(),
+            day_epoch: 0,
+            pool: 0,
+            distributed: 0,
+            pagination_cursor: 0,
+            weight_processed: 0,
+            locked_hash: [0u8; 32],
+            active: false,
+            rounds_completed: 0,
+            updated_at: 0,
+        };
+        assert!(!round.is_open(3));
+        round.start(3, 1_000);
+        assert!(round.is_open(3));
+        // A new day abandons the round
+        assert!(!round.is_open(4));
+
+        let (stream, investor) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let snapshot = ProgressPda::fold_locked_entry(&[0u8; 32], &stream, &investor, 500);
+        assert!(round.complete(&snapshot).is_err());
+        round.locked_hash = ProgressPda::fold_locked_entry(&round.locked_hash, &stream, &investor, 500);
+        round.complete(&snapshot).unwrap();
+        assert!(!round.is_open(3));
+        assert_eq!(round.rounds_completed, 1);
+
+        let mut data = Vec::new();
+        round.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= RewardRoundPda::LEN);
     }
 }
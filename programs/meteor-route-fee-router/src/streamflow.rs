@@ -1,78 +1,599 @@
 use anchor_lang::prelude::*;
-use crate::error::FeeRouterError;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use crate::{
+    error::FeeRouterError,
+    layout::discriminator,
+    locker::{LockedAmountSource, StreamIgnoredReason, StreamLocked},
+    InvestorData,
+};
 
 /// Streamflow program ID (mainnet)
 pub const STREAMFLOW_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("strmRqUCoQUgGUan5YhzUZa6KqdzwX5L6FpUxfmKg5m");
 
-/// Streamflow stream account structure (simplified for reading locked amounts)
-/// We only deserialize the fields needed to calculate locked amounts
+/// Size of a Streamflow `Contract` account (metadata account)
+pub const STREAMFLOW_CONTRACT_LEN: usize = 1104;
+
+/// Streamflow fee oracle, read on stream creation
+pub const STREAMFLOW_FEE_ORACLE: Pubkey = anchor_lang::solana_program::pubkey!("B743wFVk2pCYhV91cn287e1xY7f1vt4gdY48hhNiuQmT");
+
+/// Streamflow withdrawor, which pays for automatic withdrawals
+pub const STREAMFLOW_WITHDRAWOR: Pubkey = anchor_lang::solana_program::pubkey!("wdrwhnCv4pzW8beKsbPa4S2UDZrhenjg16KJdKSpb5u");
+
+/// Streamflow treasury; passed as the partner so no separate partner fee is charged
+pub const STREAMFLOW_TREASURY: Pubkey = anchor_lang::solana_program::pubkey!("5SEpbdjFK5FxwTvfsGMXVQTD2v4M2c5tyRTxhdsPkgDw");
+
+/// Seed of the router PDA used as the metadata account of a day's creator stream
+pub const CREATOR_STREAM_SEED: &[u8] = b"creator_stream";
+
+/// Stream creation parameters, embedded in the `Contract` account (`ix` field)
 #[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
-pub struct StreamflowStream {
-    /// Amount initially deposited
-    pub deposited: u64,
+pub struct StreamflowCreateParams {
+    /// Timestamp when the tokens start vesting
+    pub start_time: u64,
+    /// Deposited amount of tokens (net of fees)
+    pub net_amount_deposited: u64,
+    /// Time step (period) in seconds per which the vesting/release occurs
+    pub period: u64,
+    /// Amount released per period
+    pub amount_per_period: u64,
+    /// Vesting contract "cliff" timestamp
+    pub cliff: u64,
+    /// Amount unlocked at the "cliff" timestamp
+    pub cliff_amount: u64,
+    pub cancelable_by_sender: bool,
+    pub cancelable_by_recipient: bool,
+    pub automatic_withdrawal: bool,
+    pub transferable_by_sender: bool,
+    pub transferable_by_recipient: bool,
+    pub can_topup: bool,
+    pub stream_name: [u8; 64],
+    pub withdraw_frequency: u64,
+    /// Unused, kept for layout compatibility
+    pub ghost: u32,
+    pub pausable: bool,
+    pub can_update_rate: bool,
+}
 
+/// Streamflow `Contract` account, deserialized in full from the on-chain layout
+///
+/// The account has no Anchor discriminator; it starts with `magic` and is borsh encoded,
+/// followed by zero padding up to `STREAMFLOW_CONTRACT_LEN`.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct StreamflowContract {
+    pub magic: u64,
+    pub version: u8,
+    pub created_at: u64,
     /// Amount withdrawn so far
-    pub withdrawn: u64,
-
+    pub amount_withdrawn: u64,
+    /// Timestamp when the stream was canceled (0 if not canceled)
+    pub canceled_at: u64,
+    /// Timestamp at which the stream is fully vested
+    pub end_time: u64,
+    pub last_withdrawn_at: u64,
+    pub sender: Pubkey,
+    pub sender_tokens: Pubkey,
     /// Recipient/investor address
     pub recipient: Pubkey,
-    // Other fields exist in the full Streamflow account but we don't need them
+    pub recipient_tokens: Pubkey,
+    pub mint: Pubkey,
+    pub escrow_tokens: Pubkey,
+    pub streamflow_treasury: Pubkey,
+    pub streamflow_treasury_tokens: Pubkey,
+    pub streamflow_fee_total: u64,
+    pub streamflow_fee_withdrawn: u64,
+    pub streamflow_fee_percent: f32,
+    pub partner: Pubkey,
+    pub partner_tokens: Pubkey,
+    pub partner_fee_total: u64,
+    pub partner_fee_withdrawn: u64,
+    pub partner_fee_percent: f32,
+    /// Vesting schedule parameters
+    pub ix: StreamflowCreateParams,
+    pub ix_padding: Vec<u8>,
+    pub closed: bool,
+    pub current_pause_start: u64,
+    pub pause_cumulative: u64,
+    pub last_rate_change_time: u64,
+    pub funds_unlocked_at_last_rate_change: u64,
+}
+
+impl StreamflowContract {
+    /// Canceled or closed streams no longer hold locked tokens for the recipient
+    pub fn terminal_reason(&self) -> Option<StreamIgnoredReason> {
+        if self.canceled_at > 0 {
+            Some(StreamIgnoredReason::Canceled)
+        } else if self.closed {
+            Some(StreamIgnoredReason::Closed)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the router can top up this stream with `mint` as the sender of the deposit
+    ///
+    /// The stream must be live, allow top-ups, vest `mint`, and charge no Streamflow or partner
+    /// fee (a fee would be taken from the treasury on top of the payout). Its partner must be the
+    /// Streamflow treasury, since the router only passes the treasury's accounts.
+    pub fn accepts_router_topup(&self, mint: &Pubkey) -> bool {
+        self.terminal_reason().is_none()
+            && self.ix.can_topup
+            && self.mint == *mint
+            && self.streamflow_fee_percent == 0.0
+            && self.partner_fee_percent == 0.0
+            && self.partner == STREAMFLOW_TREASURY
+    }
+
+    /// Timestamp at which vesting begins: the cliff if set, otherwise start_time
+    pub fn vesting_start(&self) -> u64 {
+        std::cmp::max(self.ix.cliff, self.ix.start_time)
+    }
+
+    /// Amount vested by the schedule at `now`
+    ///
+    /// vested(t) = cliff_amount + floor((t' - from) / period) * amount_per_period, capped at the deposit,
+    /// where t' excludes paused time and `from` is the vesting start (or the last rate change,
+    /// in which case the amount unlocked before the change is added).
+    pub fn vested_at(&self, now: u64) -> Result<u64> {
+        let deposited = self.ix.net_amount_deposited;
+        let vesting_start = self.vesting_start();
+
+        if now < vesting_start {
+            return Ok(0);
+        }
+        if self.ix.period == 0 || (self.end_time > 0 && now >= self.end_time) {
+            return Ok(deposited);
+        }
+
+        // Paused time does not count towards vesting
+        let current_pause = if self.current_pause_start > 0 {
+            now.saturating_sub(self.current_pause_start)
+        } else {
+            0
+        };
+        let effective_now = now
+            .saturating_sub(self.pause_cumulative)
+            .saturating_sub(current_pause);
+
+        let (base, from) = if self.last_rate_change_time > 0 {
+            (
+                self.ix
+                    .cliff_amount
+                    .checked_add(self.funds_unlocked_at_last_rate_change)
+                    .ok_or(FeeRouterError::Overflow)?,
+                self.last_rate_change_time,
+            )
+        } else {
+            (self.ix.cliff_amount, vesting_start)
+        };
+
+        let periods = effective_now.saturating_sub(from) / self.ix.period;
+        let vested = (periods as u128)
+            .checked_mul(self.ix.amount_per_period as u128)
+            .ok_or(FeeRouterError::Overflow)?
+            .checked_add(base as u128)
+            .ok_or(FeeRouterError::Overflow)?;
+
+        Ok(std::cmp::min(vested, deposited as u128) as u64)
+    }
+}
+
+/// Arguments of Streamflow's `create_unchecked_with_payer`
+#[derive(AnchorSerialize, Clone, Debug)]
+pub struct CreateStreamArgs {
+    pub start_time: u64,
+    pub net_amount_deposited: u64,
+    pub period: u64,
+    pub amount_per_period: u64,
+    pub cliff: u64,
+    pub cliff_amount: u64,
+    pub cancelable_by_sender: bool,
+    pub cancelable_by_recipient: bool,
+    pub automatic_withdrawal: bool,
+    pub transferable_by_sender: bool,
+    pub transferable_by_recipient: bool,
+    pub can_topup: bool,
+    pub stream_name: [u8; 64],
+    pub withdraw_frequency: u64,
+    pub recipient: Pubkey,
+    pub partner: Pubkey,
+    pub pausable: bool,
+    pub can_update_rate: bool,
+}
+
+impl CreateStreamArgs {
+    /// Per-second linear release of `amount` to `recipient` over `vesting_secs` from `start_time`
+    ///
+    /// The rate is rounded up so the stream is fully vested by `start_time + vesting_secs`. The
+    /// stream can't be canceled, paused, re-rated or topped up by anyone; only the recipient may
+    /// transfer it.
+    pub fn linear(amount: u64, start_time: u64, vesting_secs: u64, recipient: Pubkey, name: &str) -> Self {
+        let mut stream_name = [0u8; 64];
+        let len = name.len().min(stream_name.len());
+        stream_name[..len].copy_from_slice(&name.as_bytes()[..len]);
+        Self {
+            start_time,
+            net_amount_deposited: amount,
+            period: 1,
+            amount_per_period: amount.div_ceil(vesting_secs.max(1)).max(1),
+            cliff: start_time,
+            cliff_amount: 0,
+            cancelable_by_sender: false,
+            cancelable_by_recipient: false,
+            automatic_withdrawal: false,
+            transferable_by_sender: false,
+            transferable_by_recipient: true,
+            can_topup: false,
+            stream_name,
+            withdraw_frequency: 0,
+            recipient,
+            partner: STREAMFLOW_TREASURY,
+            pausable: false,
+            can_update_rate: false,
+        }
+    }
+}
+
+/// Derive the escrow token account Streamflow opens for a stream's metadata account
+pub fn derive_escrow_tokens(metadata: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"strm", metadata.as_ref()], &STREAMFLOW_PROGRAM_ID).0
+}
+
+/// Accounts for Streamflow's `create_unchecked_with_payer`
+pub struct CreateStreamAccounts {
+    pub payer: Pubkey,
+    pub sender: Pubkey,
+    pub sender_tokens: Pubkey,
+    pub metadata: Pubkey,
+    pub escrow_tokens: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
 }
 
-/// Calculate currently locked amount for an investor
-/// locked(t) = deposited - withdrawn
-pub fn calculate_locked_amount(stream: &StreamflowStream) -> Result<u64> {
+/// Build Streamflow's `create_unchecked_with_payer` instruction
+///
+/// The metadata account must already exist, allocated to `STREAMFLOW_CONTRACT_LEN` and owned by
+/// Streamflow; `payer` funds the escrow account and `sender` signs for the deposit.
+pub fn create_stream_ix(accounts: &CreateStreamAccounts, args: &CreateStreamArgs) -> Result<Instruction> {
+    let mut data = discriminator(b"global:create_unchecked_with_payer").to_vec();
+    args.serialize(&mut data)?;
+
+    Ok(Instruction {
+        program_id: STREAMFLOW_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.payer, true),
+            AccountMeta::new(accounts.sender, true),
+            AccountMeta::new(accounts.sender_tokens, false),
+            AccountMeta::new(accounts.metadata, false),
+            AccountMeta::new(accounts.escrow_tokens, false),
+            AccountMeta::new(STREAMFLOW_WITHDRAWOR, false),
+            AccountMeta::new_readonly(accounts.mint, false),
+            AccountMeta::new_readonly(STREAMFLOW_FEE_ORACLE, false),
+            AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::rent::ID, false),
+            AccountMeta::new_readonly(STREAMFLOW_PROGRAM_ID, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+        data,
+    })
+}
+
+/// Accounts for Streamflow's `topup`
+pub struct TopupStreamAccounts {
+    pub sender: Pubkey,
+    pub sender_tokens: Pubkey,
+    pub metadata: Pubkey,
+    pub escrow_tokens: Pubkey,
+    pub streamflow_treasury_tokens: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// Build Streamflow's `topup` instruction, depositing `amount` more into an existing stream
+///
+/// The stream keeps its release rate, so the extra deposit extends its end time. Only streams
+/// whose partner is the Streamflow treasury are supported (see
+/// [`StreamflowContract::accepts_router_topup`]).
+pub fn topup_stream_ix(accounts: &TopupStreamAccounts, amount: u64) -> Result<Instruction> {
+    let mut data = discriminator(b"global:topup").to_vec();
+    amount.serialize(&mut data)?;
+
+    Ok(Instruction {
+        program_id: STREAMFLOW_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.sender, true),
+            AccountMeta::new(accounts.sender_tokens, false),
+            AccountMeta::new(accounts.metadata, false),
+            AccountMeta::new(accounts.escrow_tokens, false),
+            AccountMeta::new(STREAMFLOW_TREASURY, false),
+            AccountMeta::new(accounts.streamflow_treasury_tokens, false),
+            AccountMeta::new(STREAMFLOW_WITHDRAWOR, false),
+            AccountMeta::new(STREAMFLOW_TREASURY, false),
+            AccountMeta::new(accounts.streamflow_treasury_tokens, false),
+            AccountMeta::new_readonly(accounts.mint, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+        data,
+    })
+}
+
+/// Calculate the amount still locked for an investor at `now`
+/// locked(t) = net_amount_deposited - vested(t)
+///
+/// Based on the vesting schedule, so withdrawals of already-vested tokens don't change it.
+pub fn calculate_locked_amount(stream: &StreamflowContract, now: u64) -> Result<u64> {
+    let vested = stream.vested_at(now)?;
     stream
-        .deposited
-        .checked_sub(stream.withdrawn)
+        .ix
+        .net_amount_deposited
+        .checked_sub(vested)
         .ok_or(FeeRouterError::Overflow.into())
 }
 
 /// Validate that the stream account matches expected investor
 pub fn validate_stream_for_investor(
-    stream: &StreamflowStream,
+    stream: &StreamflowContract,
     expected_investor: &Pubkey,
 ) -> Result<()> {
-    #[cfg(feature = "local")]
-    {
-        return Ok(());
+    require_keys_eq!(
+        stream.recipient,
+        *expected_investor,
+        FeeRouterError::StreamRecipientMismatch
+    );
+    Ok(())
+}
+
+/// Parse a Streamflow `Contract` account
+///
+/// Deserializes from the start of the account data (the `magic` field) and ignores the
+/// trailing zero padding.
+pub fn parse_streamflow_account(account_info: &AccountInfo) -> Result<StreamflowContract> {
+    let data = account_info.try_borrow_data()?;
+    require!(
+        data.len() >= STREAMFLOW_CONTRACT_LEN,
+        FeeRouterError::StreamDeserializeFailed
+    );
+    let stream = StreamflowContract::deserialize(&mut &data[..])
+        .map_err(|_| FeeRouterError::StreamDeserializeFailed)?;
+    Ok(stream)
+}
+
+/// Parse a stream account under relaxed locker checks
+///
+/// Local mocks may be shorter or zeroed; `None` means unparsable, which counts as fully unlocked.
+fn parse_streamflow_account_relaxed(account_info: &AccountInfo) -> Result<Option<StreamflowContract>> {
+    let data = account_info.try_borrow_data()?;
+    Ok(StreamflowContract::deserialize(&mut &data[..]).ok())
+}
+
+/// Read an investor's locked amount from their Streamflow stream account
+///
+/// Locked amount is evaluated at `at_ts`. Closed accounts (no data) and canceled/closed streams
+/// are reported as ignored rather than failing the page. A stream must vest `base_mint`, or a
+/// worthless token would count as locked weight. `relax_checks` (see `LockerTestConfig`) skips
+/// the owner and recipient checks and accepts unparsable mocks as fully unlocked.
+pub fn read_investor_locked(
+    stream_account_info: &AccountInfo,
+    investor_data: &InvestorData,
+    streamflow_program_id: &Pubkey,
+    base_mint: &Pubkey,
+    at_ts: u64,
+    relax_checks: bool,
+) -> Result<StreamLocked> {
+    // Validate stream account key matches
+    require_keys_eq!(
+        stream_account_info.key(),
+        investor_data.stream,
+        FeeRouterError::StreamKeyMismatch
+    );
+
+    if stream_account_info.data_is_empty() {
+        return Ok(StreamLocked::Ignored(StreamIgnoredReason::AccountClosed));
     }
-    #[cfg(not(feature = "local"))]
-    {
+
+    let stream = if relax_checks {
+        match parse_streamflow_account_relaxed(stream_account_info)? {
+            Some(stream) => stream,
+            None => return Ok(StreamLocked::Locked(0)),
+        }
+    } else {
         require_keys_eq!(
-            stream.recipient,
-            *expected_investor,
-            FeeRouterError::MissingRequiredInput
+            *stream_account_info.owner,
+            *streamflow_program_id,
+            FeeRouterError::InvalidLockerProgram
         );
-        Ok(())
-    }
-}
-
-/// Parse Streamflow account data
-/// 
-/// NOTE: This is a simplified version that assumes Streamflow's account layout.
-/// The actual Streamflow account has an 8-byte discriminator followed by borsh-serialized data.
-/// In production, verify this matches the current Streamflow protocol version.
-pub fn parse_streamflow_account(account_info: &AccountInfo) -> Result<StreamflowStream> {
-    #[cfg(feature = "local")]
-    {
-        let data = account_info.try_borrow_data()?;
-        if data.len() >= 8 {
-            if let Ok(stream) = StreamflowStream::try_from_slice(&data[8..]) {
-                return Ok(stream);
-            }
-        }
-        Ok(StreamflowStream { deposited: 0, withdrawn: 0, recipient: Pubkey::default() })
-    }
-    #[cfg(not(feature = "local"))]
-    {
-        let data = account_info.try_borrow_data()?;
-        require!(
-            data.len() >= 8,
-            FeeRouterError::MissingRequiredInput
+        let stream = parse_streamflow_account(stream_account_info)?;
+        validate_stream_for_investor(&stream, &investor_data.investor)?;
+        stream
+    };
+    require_keys_eq!(stream.mint, *base_mint, FeeRouterError::StreamMintMismatch);
+
+    if let Some(reason) = stream.terminal_reason() {
+        return Ok(StreamLocked::Ignored(reason));
+    }
+
+    // Calculate locked amount from the vesting schedule
+    Ok(StreamLocked::Locked(calculate_locked_amount(&stream, at_ts)?))
+}
+
+/// Streamflow vesting contracts as a locked-amount source
+pub struct StreamflowSource {
+    pub program_id: Pubkey,
+    /// Mint every stream must vest (the policy's base mint)
+    pub base_mint: Pubkey,
+    /// Local-testing bypass from `LockerTestConfig`
+    pub relax_checks: bool,
+}
+
+impl LockedAmountSource for StreamflowSource {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn read_locked(
+        &self,
+        account_info: &AccountInfo,
+        investor_data: &InvestorData,
+        at_ts: u64,
+    ) -> Result<StreamLocked> {
+        read_investor_locked(account_info, investor_data, &self.program_id, &self.base_mint, at_ts, self.relax_checks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(deposited: u64, start_time: u64, period: u64, amount_per_period: u64) -> StreamflowContract {
+        let mut stream =
+            StreamflowContract::deserialize(&mut &vec![0u8; STREAMFLOW_CONTRACT_LEN][..]).unwrap();
+        stream.ix.net_amount_deposited = deposited;
+        stream.ix.start_time = start_time;
+        stream.ix.period = period;
+        stream.ix.amount_per_period = amount_per_period;
+        stream
+    }
+
+    #[test]
+    fn test_zeroed_account_parses() {
+        let stream = contract(0, 0, 0, 0);
+        assert_eq!(calculate_locked_amount(&stream, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_terminal_streams() {
+        let mut stream = contract(1_000, 100, 10, 100);
+        assert_eq!(stream.terminal_reason(), None);
+        stream.closed = true;
+        assert_eq!(stream.terminal_reason(), Some(StreamIgnoredReason::Closed));
+        stream.canceled_at = 120;
+        assert_eq!(stream.terminal_reason(), Some(StreamIgnoredReason::Canceled));
+    }
+
+    #[test]
+    fn test_router_topup_acceptance() {
+        let mint = Pubkey::new_unique();
+        let mut stream = contract(1_000, 100, 10, 100);
+        stream.mint = mint;
+        stream.partner = STREAMFLOW_TREASURY;
+        assert!(!stream.accepts_router_topup(&mint));
+        stream.ix.can_topup = true;
+        assert!(stream.accepts_router_topup(&mint));
+        assert!(!stream.accepts_router_topup(&Pubkey::new_unique()));
+
+        let mut with_fee = stream.clone();
+        with_fee.streamflow_fee_percent = 0.25;
+        assert!(!with_fee.accepts_router_topup(&mint));
+        let mut with_partner = stream.clone();
+        with_partner.partner = Pubkey::new_unique();
+        assert!(!with_partner.accepts_router_topup(&mint));
+        stream.closed = true;
+        assert!(!stream.accepts_router_topup(&mint));
+    }
+
+    #[test]
+    fn test_linear_release() {
+        // 1_000 over 10 periods of 10s starting at t=100
+        let mut stream = contract(1_000, 100, 10, 100);
+        assert_eq!(calculate_locked_amount(&stream, 50).unwrap(), 1_000);
+        assert_eq!(calculate_locked_amount(&stream, 155).unwrap(), 500);
+        assert_eq!(calculate_locked_amount(&stream, 200).unwrap(), 0);
+        assert_eq!(calculate_locked_amount(&stream, 10_000).unwrap(), 0);
+
+        // Withdrawals of vested tokens don't change the locked amount
+        stream.amount_withdrawn = 400;
+        assert_eq!(calculate_locked_amount(&stream, 155).unwrap(), 500);
+    }
+
+    #[test]
+    fn test_cliff_release() {
+        // 400 unlocks at the cliff (t=150), then 100 per 10s
+        let mut stream = contract(1_000, 100, 10, 100);
+        stream.ix.cliff = 150;
+        stream.ix.cliff_amount = 400;
+        assert_eq!(calculate_locked_amount(&stream, 149).unwrap(), 1_000);
+        assert_eq!(calculate_locked_amount(&stream, 150).unwrap(), 600);
+        assert_eq!(calculate_locked_amount(&stream, 175).unwrap(), 400);
+        assert_eq!(calculate_locked_amount(&stream, 210).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_recipient_and_owner_checks_unless_relaxed() {
+        let mut stream = contract(1_000, 100, 10, 100);
+        let investor = Pubkey::new_unique();
+        let base_mint = Pubkey::new_unique();
+        stream.recipient = investor;
+        stream.mint = base_mint;
+        let mut data = Vec::new();
+        stream.serialize(&mut data).unwrap();
+        data.resize(STREAMFLOW_CONTRACT_LEN, 0);
+
+        let key = Pubkey::new_unique();
+        let mut lamports = 1;
+        let owner = STREAMFLOW_PROGRAM_ID;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        let mut investor_data = InvestorData { stream: key, investor, index: 0 };
+        let read = |investor_data: &InvestorData, program_id: &Pubkey, relax: bool| {
+            read_investor_locked(&info, investor_data, program_id, &base_mint, 100, relax)
+        };
+        assert_eq!(read(&investor_data, &STREAMFLOW_PROGRAM_ID, false).unwrap(), StreamLocked::Locked(1_000));
+
+        let other_program = Pubkey::new_unique();
+        assert_eq!(
+            read(&investor_data, &other_program, false).unwrap_err(),
+            FeeRouterError::InvalidLockerProgram.into()
+        );
+        investor_data.investor = Pubkey::new_unique();
+        assert_eq!(
+            read(&investor_data, &STREAMFLOW_PROGRAM_ID, false).unwrap_err(),
+            FeeRouterError::StreamRecipientMismatch.into()
         );
-        let stream = StreamflowStream::try_from_slice(&data[8..])
-            .map_err(|_| FeeRouterError::MissingRequiredInput)?;
-        Ok(stream)
+        let other_stream = InvestorData { stream: Pubkey::new_unique(), ..investor_data };
+        assert_eq!(
+            read(&other_stream, &STREAMFLOW_PROGRAM_ID, false).unwrap_err(),
+            FeeRouterError::StreamKeyMismatch.into()
+        );
+
+        // The local-testing bypass accepts both
+        assert_eq!(read(&investor_data, &other_program, true).unwrap(), StreamLocked::Locked(1_000));
+
+        // A stream of another mint is rejected either way
+        let other_mint = Pubkey::new_unique();
+        let investor_data = InvestorData { investor, ..investor_data };
+        for relax in [false, true] {
+            assert_eq!(
+                read_investor_locked(&info, &investor_data, &STREAMFLOW_PROGRAM_ID, &other_mint, 100, relax).unwrap_err(),
+                FeeRouterError::StreamMintMismatch.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_linear_stream_vests_by_end() {
+        let recipient = Pubkey::new_unique();
+        // 1_000 over 300s rounds the rate up to 4/s, fully vested by t=400
+        let args = CreateStreamArgs::linear(1_000, 100, 300, recipient, "creator");
+        assert_eq!(args.amount_per_period, 4);
+        assert_eq!(&args.stream_name[..7], b"creator");
+
+        let mut stream = contract(1_000, args.start_time, args.period, args.amount_per_period);
+        stream.ix.cliff = args.cliff;
+        assert_eq!(calculate_locked_amount(&stream, 100).unwrap(), 1_000);
+        assert_eq!(calculate_locked_amount(&stream, 250).unwrap(), 400);
+        assert_eq!(calculate_locked_amount(&stream, 400).unwrap(), 0);
+
+        // Dust amounts still release at least one token per second
+        assert_eq!(CreateStreamArgs::linear(5, 100, 300, recipient, "").amount_per_period, 1);
+    }
+
+    #[test]
+    fn test_paused_time_excluded() {
+        let mut stream = contract(1_000, 100, 10, 100);
+        stream.pause_cumulative = 30;
+        assert_eq!(calculate_locked_amount(&stream, 150).unwrap(), 800);
+
+        // Currently paused since t=140
+        stream.current_pause_start = 140;
+        assert_eq!(calculate_locked_amount(&stream, 160).unwrap(), 900);
     }
 }
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint as MintState,
+};
+use crate::error::FeeRouterError;
+
+/// Fee withheld by the quote mint on a transfer of `amount` in the current epoch
+///
+/// Zero for SPL Token mints and Token-2022 mints without the transfer-fee extension.
+pub fn transfer_fee_for(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != anchor_spl::token_2022::ID || amount == 0 {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    transfer_fee_at_epoch(&data, amount, Clock::get()?.epoch)
+}
+
+/// Transfer fee for `amount` from raw Token-2022 mint data at `epoch`
+pub fn transfer_fee_at_epoch(mint_data: &[u8], amount: u64, epoch: u64) -> Result<u64> {
+    let mint = StateWithExtensions::<MintState>::unpack(mint_data)?;
+    let Ok(config) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or_else(|| error!(FeeRouterError::Overflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::{
+        extension::{
+            transfer_fee::TransferFee, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+        },
+        state::Mint as MintState,
+    };
+
+    fn mint_with_fee(bps: u16, max_fee: u64) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<MintState>(&[ExtensionType::TransferFeeConfig]).unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        let fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: max_fee.into(),
+            transfer_fee_basis_points: bps.into(),
+        };
+        let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.older_transfer_fee = fee;
+        config.newer_transfer_fee = fee;
+        state.base.decimals = 6;
+        state.base.is_initialized = true;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_transfer_fee_at_epoch() {
+        // 1% capped at 5_000
+        let data = mint_with_fee(100, 5_000);
+        assert_eq!(transfer_fee_at_epoch(&data, 100_000, 1).unwrap(), 1_000);
+        assert_eq!(transfer_fee_at_epoch(&data, 10_000_000, 1).unwrap(), 5_000);
+        assert_eq!(transfer_fee_at_epoch(&data, 0, 1).unwrap(), 0);
+    }
+}
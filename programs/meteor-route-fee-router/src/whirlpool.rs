@@ -0,0 +1,307 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use crate::{
+    error::FeeRouterError,
+    layout::{discriminator, read_i32, read_pubkey, read_u128, read_u16},
+};
+
+/// Orca Whirlpool program ID (mainnet)
+pub const WHIRLPOOL_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+/// Ticks per Whirlpool tick array
+pub const TICK_ARRAY_SIZE: i32 = 88;
+
+/// Whirlpool tick bounds
+pub const MIN_TICK_INDEX: i32 = -443_636;
+pub const MAX_TICK_INDEX: i32 = 443_636;
+
+/// Accounts the Whirlpool claim path expects at the front of remaining_accounts:
+/// [tick_array_lower, tick_array_upper, whirlpool_program]
+pub const CLAIM_REMAINING_ACCOUNTS: usize = 3;
+
+// Whirlpool field offsets (discriminator included)
+const WHIRLPOOL_TICK_SPACING: usize = 41;
+const WHIRLPOOL_TICK_CURRENT_INDEX: usize = 81;
+const WHIRLPOOL_TOKEN_MINT_A: usize = 101;
+const WHIRLPOOL_TOKEN_VAULT_A: usize = 133;
+const WHIRLPOOL_TOKEN_MINT_B: usize = 181;
+const WHIRLPOOL_TOKEN_VAULT_B: usize = 213;
+const WHIRLPOOL_MIN_LEN: usize = 245;
+
+// Position field offsets (discriminator included)
+const POSITION_WHIRLPOOL: usize = 8;
+const POSITION_MINT: usize = 40;
+const POSITION_LIQUIDITY: usize = 72;
+const POSITION_TICK_LOWER: usize = 88;
+const POSITION_TICK_UPPER: usize = 92;
+const POSITION_MIN_LEN: usize = 96;
+
+/// Fields of an Orca `Whirlpool` the router needs
+#[derive(Clone, Copy, Debug)]
+pub struct WhirlpoolView {
+    pub tick_spacing: u16,
+    pub tick_current_index: i32,
+    pub token_mint_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_vault_b: Pubkey,
+}
+
+/// Fields of an Orca `Position` the router needs
+#[derive(Clone, Copy, Debug)]
+pub struct WhirlpoolPositionView {
+    pub whirlpool: Pubkey,
+    pub position_mint: Pubkey,
+    pub liquidity: u128,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+}
+
+/// Check owner, discriminator and minimum size of a Whirlpool account
+fn check_whirlpool_account(info: &AccountInfo, data: &[u8], account_name: &[u8], min_len: usize) -> Result<()> {
+    require_keys_eq!(*info.owner, WHIRLPOOL_PROGRAM_ID, FeeRouterError::InvalidWhirlpoolProgram);
+    require!(
+        data.len() >= min_len && data[..8] == discriminator(account_name),
+        FeeRouterError::PoolAccountMismatch
+    );
+    Ok(())
+}
+
+/// Read an Orca `Whirlpool` account
+pub fn read_whirlpool(info: &AccountInfo) -> Result<WhirlpoolView> {
+    let data = info.try_borrow_data()?;
+    check_whirlpool_account(info, &data, b"account:Whirlpool", WHIRLPOOL_MIN_LEN)?;
+    Ok(WhirlpoolView {
+        tick_spacing: read_u16(&data, WHIRLPOOL_TICK_SPACING),
+        tick_current_index: read_i32(&data, WHIRLPOOL_TICK_CURRENT_INDEX),
+        token_mint_a: read_pubkey(&data, WHIRLPOOL_TOKEN_MINT_A),
+        token_vault_a: read_pubkey(&data, WHIRLPOOL_TOKEN_VAULT_A),
+        token_mint_b: read_pubkey(&data, WHIRLPOOL_TOKEN_MINT_B),
+        token_vault_b: read_pubkey(&data, WHIRLPOOL_TOKEN_VAULT_B),
+    })
+}
+
+/// Read an Orca `Position` account
+pub fn read_position(info: &AccountInfo) -> Result<WhirlpoolPositionView> {
+    let data = info.try_borrow_data()?;
+    check_whirlpool_account(info, &data, b"account:Position", POSITION_MIN_LEN)?;
+    Ok(WhirlpoolPositionView {
+        whirlpool: read_pubkey(&data, POSITION_WHIRLPOOL),
+        position_mint: read_pubkey(&data, POSITION_MINT),
+        liquidity: read_u128(&data, POSITION_LIQUIDITY),
+        tick_lower_index: read_i32(&data, POSITION_TICK_LOWER),
+        tick_upper_index: read_i32(&data, POSITION_TICK_UPPER),
+    })
+}
+
+/// Validate a quote-only Whirlpool tick range
+///
+/// Ticks must be initializable (multiples of tick_spacing, within bounds), and the range must
+/// sit strictly on one side of the current tick:
+/// - If quote is token B, the range must be above the current tick (tick_lower > tick_current + buffer)
+/// - If quote is token A, the range must be below the current tick (tick_upper <= tick_current - buffer)
+pub fn validate_quote_only_ticks(
+    whirlpool: &WhirlpoolView,
+    tick_lower: i32,
+    tick_upper: i32,
+    quote_mint: &Pubkey,
+    buffer_ticks: u32,
+) -> Result<()> {
+    let spacing = whirlpool.tick_spacing as i32;
+    require!(
+        spacing > 0
+            && tick_lower < tick_upper
+            && tick_lower >= MIN_TICK_INDEX
+            && tick_upper <= MAX_TICK_INDEX
+            && tick_lower % spacing == 0
+            && tick_upper % spacing == 0,
+        FeeRouterError::InvalidTickRange
+    );
+
+    let is_token_a_quote = whirlpool.token_mint_a == *quote_mint;
+    let is_token_b_quote = whirlpool.token_mint_b == *quote_mint;
+    require!(
+        is_token_a_quote || is_token_b_quote,
+        FeeRouterError::InvalidQuoteMint
+    );
+
+    // A position is in range when tick_lower <= tick_current < tick_upper
+    let current = whirlpool.tick_current_index as i64;
+    let buffer = buffer_ticks as i64;
+    if is_token_b_quote {
+        require!(
+            tick_lower as i64 > current + buffer,
+            FeeRouterError::PositionNotQuoteOnly
+        );
+    } else {
+        require!(
+            tick_upper as i64 <= current - buffer,
+            FeeRouterError::PositionNotQuoteOnly
+        );
+    }
+
+    msg!(
+        "Whirlpool quote-only validation passed. Quote mint: {}, range: [{}, {}], current: {}",
+        quote_mint,
+        tick_lower,
+        tick_upper,
+        whirlpool.tick_current_index
+    );
+
+    Ok(())
+}
+
+/// Start index of the tick array holding `tick`
+pub fn tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_per_array = tick_spacing as i32 * TICK_ARRAY_SIZE;
+    tick.div_euclid(ticks_per_array) * ticks_per_array
+}
+
+/// Derive the tick array PDA starting at `start_index`
+pub fn derive_tick_array(whirlpool: &Pubkey, start_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array", whirlpool.as_ref(), start_index.to_string().as_bytes()],
+        &WHIRLPOOL_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Derive the position PDA for a position mint
+pub fn derive_position(position_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"position", position_mint.as_ref()], &WHIRLPOOL_PROGRAM_ID)
+}
+
+/// Accounts for the Whirlpool `open_position` instruction
+pub struct OpenPositionAccounts {
+    pub funder: Pubkey,
+    pub owner: Pubkey,
+    pub position: Pubkey,
+    pub position_mint: Pubkey,
+    pub position_token_account: Pubkey,
+    pub whirlpool: Pubkey,
+}
+
+/// Build the Whirlpool `open_position` instruction
+pub fn open_position_ix(
+    accounts: &OpenPositionAccounts,
+    position_bump: u8,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> Instruction {
+    let mut data = discriminator(b"global:open_position").to_vec();
+    data.push(position_bump);
+    data.extend_from_slice(&tick_lower.to_le_bytes());
+    data.extend_from_slice(&tick_upper.to_le_bytes());
+
+    Instruction {
+        program_id: WHIRLPOOL_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.funder, true),
+            AccountMeta::new_readonly(accounts.owner, false),
+            AccountMeta::new(accounts.position, false),
+            AccountMeta::new(accounts.position_mint, true),
+            AccountMeta::new(accounts.position_token_account, false),
+            AccountMeta::new_readonly(accounts.whirlpool, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+            AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::rent::ID, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+        ],
+        data,
+    }
+}
+
+/// Build the Whirlpool `update_fees_and_rewards` instruction
+pub fn update_fees_and_rewards_ix(
+    whirlpool: Pubkey,
+    position: Pubkey,
+    tick_array_lower: Pubkey,
+    tick_array_upper: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: WHIRLPOOL_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(whirlpool, false),
+            AccountMeta::new(position, false),
+            AccountMeta::new_readonly(tick_array_lower, false),
+            AccountMeta::new_readonly(tick_array_upper, false),
+        ],
+        data: discriminator(b"global:update_fees_and_rewards").to_vec(),
+    }
+}
+
+/// Accounts for the Whirlpool `collect_fees` instruction
+pub struct CollectFeesAccounts {
+    pub whirlpool: Pubkey,
+    pub position_authority: Pubkey,
+    pub position: Pubkey,
+    pub position_token_account: Pubkey,
+    pub token_owner_account_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_owner_account_b: Pubkey,
+    pub token_vault_b: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// Build the Whirlpool `collect_fees` instruction
+pub fn collect_fees_ix(accounts: &CollectFeesAccounts) -> Instruction {
+    Instruction {
+        program_id: WHIRLPOOL_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.whirlpool, false),
+            AccountMeta::new_readonly(accounts.position_authority, true),
+            AccountMeta::new(accounts.position, false),
+            AccountMeta::new_readonly(accounts.position_token_account, false),
+            AccountMeta::new(accounts.token_owner_account_a, false),
+            AccountMeta::new(accounts.token_vault_a, false),
+            AccountMeta::new(accounts.token_owner_account_b, false),
+            AccountMeta::new(accounts.token_vault_b, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+        ],
+        data: discriminator(b"global:collect_fees").to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn whirlpool(tick_current_index: i32, token_mint_a: Pubkey, token_mint_b: Pubkey) -> WhirlpoolView {
+        WhirlpoolView {
+            tick_spacing: 64,
+            tick_current_index,
+            token_mint_a,
+            token_vault_a: Pubkey::default(),
+            token_mint_b,
+            token_vault_b: Pubkey::default(),
+        }
+    }
+
+    #[test]
+    fn test_quote_only_ticks() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        // Current tick far from zero: the sign of the bounds alone says nothing
+        let pool = whirlpool(-20_000, a, b);
+
+        // Quote B: range above the current tick
+        assert!(validate_quote_only_ticks(&pool, -19_968, -19_904, &b, 0).is_ok());
+        assert!(validate_quote_only_ticks(&pool, -20_032, -19_904, &b, 0).is_err());
+
+        // Quote A: range at or below the current tick
+        assert!(validate_quote_only_ticks(&pool, -20_096, -20_032, &a, 0).is_ok());
+        assert!(validate_quote_only_ticks(&pool, -20_096, -19_968, &a, 0).is_err());
+
+        // Ticks must be multiples of the spacing
+        assert!(validate_quote_only_ticks(&pool, -19_967, -19_904, &b, 0).is_err());
+    }
+
+    #[test]
+    fn test_tick_array_start_index() {
+        // 64 * 88 = 5632 ticks per array
+        assert_eq!(tick_array_start_index(0, 64), 0);
+        assert_eq!(tick_array_start_index(5_631, 64), 0);
+        assert_eq!(tick_array_start_index(5_632, 64), 5_632);
+        assert_eq!(tick_array_start_index(-1, 64), -5_632);
+    }
+}
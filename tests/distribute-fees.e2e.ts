@@ -12,7 +12,8 @@
  * Known Limitations:
  * - Y0 total allocation is 0, so eligible_bps=0 → all fees route to creator
  * - Position NFT ownership transfer not implemented (requires Token-2022 CPI)
- * - Streamflow account data writing simplified (relies on parser accepting len>=8)
+ * - Streamflow account data writing simplified; the mocks are read through the upgrade
+ *   authority's LockerTestConfig, which skips the owner and recipient checks
  * - This test validates the pagination flow and finalization logic
  */
 import * as anchor from "@coral-xyz/anchor";
@@ -36,14 +37,38 @@ import {expect} from "chai";
 // NOTE: Token-2022 program id constant (export may vary by spl-token version)
 const TOKEN_2022_PROGRAM_ID = new PublicKey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
 
-// Helper: compute page hash like on-chain
-function computePageHash(pageIndex: number, investors: {stream: PublicKey, investor: PublicKey}[]): Uint8Array {
+// Helper: compute page hash like on-chain; bound to the program, vault, day and generation
+function computePageHash(
+  programId: PublicKey,
+  vaultSeed: string,
+  dayEpoch: number,
+  generation: number,
+  pageIndex: number,
+  investors: {stream: PublicKey, investor: PublicKey, index: number}[],
+): Uint8Array {
+  const seedLen = Buffer.alloc(4);
+  seedLen.writeUInt32LE(Buffer.byteLength(vaultSeed));
+  const day = Buffer.alloc(8);
+  day.writeBigUInt64LE(BigInt(dayEpoch));
+  const gen = Buffer.alloc(2);
+  gen.writeUInt16LE(generation);
   const idx = Buffer.alloc(8);
   idx.writeBigUInt64LE(BigInt(pageIndex));
-  const chunks: Buffer[] = [idx];
+  const chunks: Buffer[] = [
+    Buffer.from("meteor-route:page:v2"),
+    programId.toBuffer(),
+    seedLen,
+    Buffer.from(vaultSeed),
+    day,
+    gen,
+    idx,
+  ];
   for (const it of investors) {
     chunks.push(Buffer.from(it.stream.toBytes()));
     chunks.push(Buffer.from(it.investor.toBytes()));
+    const index = Buffer.alloc(4);
+    index.writeUInt32LE(it.index);
+    chunks.push(index);
   }
   const crypto = require("crypto");
   return new Uint8Array(crypto.createHash("sha256").update(Buffer.concat(chunks)).digest());
@@ -68,7 +93,8 @@ function createAtaIdempotentIx(payer: PublicKey, ata: PublicKey, owner: PublicKe
   });
 }
 
-// Helper: create a fake Streamflow account with discriminator + borsh(StreamflowStream)
+// Helper: create a fake Streamflow account sized like a Streamflow Contract (1104 bytes).
+// The router parses the full Contract layout; zeroed data parses as a fully unlocked stream.
 async function createStreamflowAccount(
   provider: anchor.AnchorProvider,
   streamKeypair: Keypair,
@@ -76,13 +102,15 @@ async function createStreamflowAccount(
   withdrawn: bigint,
   recipient: PublicKey,
 ): Promise<PublicKey> {
-  // 8-byte discriminator + borsh encoded { deposited: u64, withdrawn: u64, recipient: Pubkey }
-  const data = Buffer.alloc(8 + 8 + 8 + 32);
-  // discriminator can be zeros for local tests; parser only checks len>=8
-  // encode u64 LE (Borsh format)
-  data.writeBigUInt64LE(deposited, 8);
-  data.writeBigUInt64LE(withdrawn, 16);
-  recipient.toBuffer().copy(data, 24);
+  // Contract layout offsets: amount_withdrawn @17, end_time @33, recipient @113,
+  // ix.start_time @409, ix.net_amount_deposited @417, ix.period @425, ix.amount_per_period @433.
+  // A far-future end_time with amount_per_period = 0 keeps the whole deposit locked.
+  const data = Buffer.alloc(1104);
+  data.writeBigUInt64LE(withdrawn, 17);
+  data.writeBigUInt64LE(BigInt(2) ** BigInt(62), 33);
+  recipient.toBuffer().copy(data, 113);
+  data.writeBigUInt64LE(deposited, 417);
+  data.writeBigUInt64LE(BigInt(1), 425);
 
   const lamports = await provider.connection.getMinimumBalanceForRentExemption(data.length);
   
@@ -125,8 +153,8 @@ describe("distribute-fees e2e", () => {
   let positionOwnerPda: PublicKey;
 
   // Tokens
-  let tokenAMint: PublicKey; // quote
-  let tokenBMint: PublicKey; // base
+  let tokenAMint: PublicKey; // base
+  let tokenBMint: PublicKey; // quote (collect-fee mode OnlyB pays every swap fee in token B)
   let payerTokenA: PublicKey;
   let payerTokenB: PublicKey;
 
@@ -160,7 +188,10 @@ describe("distribute-fees e2e", () => {
     await mintTo(provider.connection, payer, tokenAMint, payerTokenA, payer.publicKey, BigInt("1000000000000000000"));
     await mintTo(provider.connection, payer, tokenBMint, payerTokenB, payer.publicKey, BigInt("1000000000000000000"));
 
-    quoteTreasury = await getAssociatedTokenAddress(tokenAMint, positionOwnerPda, true);
+    [quoteTreasury] = PublicKey.findProgramAddressSync(
+      [Buffer.from(vaultSeed), Buffer.from("treasury"), tokenBMint.toBuffer()],
+      router.programId
+    );
   });
 
   it("sets up CP-AMM config + pool + position", async () => {
@@ -191,7 +222,7 @@ describe("distribute-fees e2e", () => {
       vaultConfigKey: PublicKey.default,
       poolCreatorAuthority: provider.wallet.publicKey,
       activationType: 1, // timestamp mode per config
-      collectFeeMode: 0, // BothToken
+      collectFeeMode: 1, // OnlyB: fees only in token B, the quote token
     } as any;
 
     await cpamm.methods
@@ -288,13 +319,22 @@ describe("distribute-fees e2e", () => {
 
     // Initialize router policy + progress AFTER pool exists so pool matches policy
     await router.methods
-      .initializePolicy(vaultSeed, 7000, new BN(0), new BN(1000), true, new BN(10_000_000))
+      .initializePolicy(vaultSeed, {
+        investorFeeShareBps: 7000,
+        dailyCapQuoteLamports: new BN(0),
+        minPayoutLamports: new BN(1000),
+        policyFundMissingAta: true,
+        y0TotalAllocation: new BN(10_000_000),
+        allowedQuoteMintRisks: 0,
+      })
       .accounts({
         authority: provider.wallet.publicKey,
+        payer: provider.wallet.publicKey,
         policyPda,
-        quoteMint: tokenAMint,
-        baseMint: tokenBMint,
+        quoteMint: tokenBMint,
+        baseMint: tokenAMint,
         pool: poolPda,
+        cpAmmProgram: cpamm.programId,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
       } as any)
@@ -304,6 +344,7 @@ describe("distribute-fees e2e", () => {
       .initializeProgress(vaultSeed)
       .accounts({
         authority: provider.wallet.publicKey,
+        payer: provider.wallet.publicKey,
         policyPda,
         progressPda,
         systemProgram: SystemProgram.programId,
@@ -407,9 +448,10 @@ describe("distribute-fees e2e", () => {
     const cpAmmEventAuthority = PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], cpamm.programId)[0];
 
     await router.methods
-      .initializeHonoraryPosition(vaultSeed, -1000, -10, tokenAMint)
+      .initializeHonoraryPosition(vaultSeed, 10, 1000, tokenBMint)
       .accounts({
         authority: provider.wallet.publicKey,
+        payer: provider.wallet.publicKey,
         policyPda,
         positionOwnerPda,
         cpAmmProgram: cpamm.programId,
@@ -418,8 +460,8 @@ describe("distribute-fees e2e", () => {
         pool: poolPda,
         poolTokenVault0: tokenAVault,
         poolTokenVault1: tokenBVault,
-        quoteMint: tokenAMint,
-        baseMint: tokenBMint,
+        quoteMint: tokenBMint,
+        baseMint: tokenAMint,
         quoteTreasury,
         positionMint: routerPositionNftMint.publicKey,
         positionTokenAccount: routerPositionNftAccount,
@@ -429,12 +471,17 @@ describe("distribute-fees e2e", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         token2022Program: TOKEN_2022_PROGRAM_ID,
+        baseTokenProgram: TOKEN_PROGRAM_ID,
+        // Preflight claim needs the PDA's pool token accounts, which are created later in this suite
+        preflightTokenAAccount: null,
+        preflightTokenBAccount: null,
       } as any)
       .signers([routerPositionNftMint])
       .rpc();
 
     const posOwner = await router.account.investorFeePositionOwnerPda.fetch(positionOwnerPda);
     expect(posOwner.positionPubkey.toString()).to.equal(routerPositionPda.toString());
+    expect(posOwner.simulationVerified).to.equal(false);
   });
 
   it("distributes fees with pagination and finalization (requires Streamflow mock data)", async () => {
@@ -471,6 +518,24 @@ describe("distribute-fees e2e", () => {
       await provider.sendAndConfirm(tx, []);
     }
 
+    // The mock streams below are zeroed, so enable the local-testing locker bypass
+    const [lockerTestConfig] = PublicKey.findProgramAddressSync([Buffer.from("locker_test_config")], router.programId);
+    const [programData] = PublicKey.findProgramAddressSync(
+      [router.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111"),
+    );
+    await router.methods
+      .setLockerTestConfig(true)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        payer: provider.wallet.publicKey,
+        lockerTestConfig,
+        routerProgram: router.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .rpc();
+
     // Create streamflow accounts: deposited=X, withdrawn=0, recipient=investor
     await createStreamflowAccount(provider, stream1, BigInt(500_000), BigInt(0), investor1.publicKey);
     await createStreamflowAccount(provider, stream2, BigInt(300_000), BigInt(0), investor2.publicKey);
@@ -478,14 +543,14 @@ describe("distribute-fees e2e", () => {
 
     // Create investor quote ATAs using idempotent helper
     // Note: Payer funds the creation, but each investor owns their ATA
-    const investor1Ata = await getAssociatedTokenAddress(tokenAMint, investor1.publicKey);
-    const investor2Ata = await getAssociatedTokenAddress(tokenAMint, investor2.publicKey);
-    const investor3Ata = await getAssociatedTokenAddress(tokenAMint, investor3.publicKey);
+    const investor1Ata = await getAssociatedTokenAddress(tokenBMint, investor1.publicKey);
+    const investor2Ata = await getAssociatedTokenAddress(tokenBMint, investor2.publicKey);
+    const investor3Ata = await getAssociatedTokenAddress(tokenBMint, investor3.publicKey);
     
     const tx1 = new anchor.web3.Transaction().add(
-      createAtaIdempotentIx(provider.wallet.publicKey, investor1Ata, investor1.publicKey, tokenAMint),
-      createAtaIdempotentIx(provider.wallet.publicKey, investor2Ata, investor2.publicKey, tokenAMint),
-      createAtaIdempotentIx(provider.wallet.publicKey, investor3Ata, investor3.publicKey, tokenAMint)
+      createAtaIdempotentIx(provider.wallet.publicKey, investor1Ata, investor1.publicKey, tokenBMint),
+      createAtaIdempotentIx(provider.wallet.publicKey, investor2Ata, investor2.publicKey, tokenBMint),
+      createAtaIdempotentIx(provider.wallet.publicKey, investor3Ata, investor3.publicKey, tokenBMint)
     );
     await provider.sendAndConfirm(tx1, []);
 
@@ -498,28 +563,23 @@ describe("distribute-fees e2e", () => {
     );
     await provider.sendAndConfirm(tx2, []);
 
-    // Ensure quoteTreasury exists idempotently as well
-    const qtAddr = await getAssociatedTokenAddress(tokenAMint, positionOwnerPda, true);
-    const txQT = new anchor.web3.Transaction().add(
-      createAtaIdempotentIx(provider.wallet.publicKey, qtAddr, positionOwnerPda, tokenAMint)
-    );
-    await provider.sendAndConfirm(txQT, []);
-    quoteTreasury = qtAddr;
-
     // Create creator ATA for remainder
-    const creatorAta = await createAccount(provider.connection, payer, tokenAMint, provider.wallet.publicKey);
+    const creatorAta = await createAccount(provider.connection, payer, tokenBMint, provider.wallet.publicKey);
 
     // Build pages: page 0 = [inv1, inv2], page 1 = [inv3]
     const page0Investors = [
-      {stream: stream1.publicKey, investor: investor1.publicKey},
-      {stream: stream2.publicKey, investor: investor2.publicKey},
+      {stream: stream1.publicKey, investor: investor1.publicKey, index: 0},
+      {stream: stream2.publicKey, investor: investor2.publicKey, index: 1},
     ];
     const page1Investors = [
-      {stream: stream3.publicKey, investor: investor3.publicKey},
+      {stream: stream3.publicKey, investor: investor3.publicKey, index: 2},
     ];
 
-    const page0Hash = computePageHash(0, page0Investors);
-    const page1Hash = computePageHash(1, page1Investors);
+    // The first crank opens the day of the current cluster time
+    const blockTime = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    const dayEpoch = Math.floor(blockTime! / 86_400);
+    const page0Hash = computePageHash(router.programId, vaultSeed, dayEpoch, 0, 0, page0Investors);
+    const page1Hash = computePageHash(router.programId, vaultSeed, dayEpoch, 0, 1, page1Investors);
 
     const page0 = {
       pageIndex: 0,
@@ -558,7 +618,7 @@ describe("distribute-fees e2e", () => {
         tokenBVault,
         tokenAMint,
         tokenBMint,
-        quoteMint: tokenAMint,
+        quoteMint: tokenBMint,
         tempAAccount: tempA,
         tempBAccount: tempB,
         quoteTreasury,
@@ -571,6 +631,15 @@ describe("distribute-fees e2e", () => {
         cpAmmProgram: cpamm.programId,
         cpAmmEventAuthority: PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], cpamm.programId)[0],
         systemProgram: SystemProgram.programId,
+        creatorWsolTemp: null,
+        creatorWallet: null,
+        failedPayouts: null,
+        paidBitmap: null,
+        payoutOverrides: null,
+        weightOverrides: null,
+        crankLog: null,
+        memoProgram: null,
+        lockerTestConfig,
       } as any)
       .remainingAccounts(remainingPage0)
       .rpc();
@@ -603,7 +672,7 @@ describe("distribute-fees e2e", () => {
         tokenBVault,
         tokenAMint,
         tokenBMint,
-        quoteMint: tokenAMint,
+        quoteMint: tokenBMint,
         tempAAccount: tempA,
         tempBAccount: tempB,
         quoteTreasury,
@@ -616,6 +685,15 @@ describe("distribute-fees e2e", () => {
         cpAmmProgram: cpamm.programId,
         cpAmmEventAuthority: PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], cpamm.programId)[0],
         systemProgram: SystemProgram.programId,
+        creatorWsolTemp: null,
+        creatorWallet: null,
+        failedPayouts: null,
+        paidBitmap: null,
+        payoutOverrides: null,
+        weightOverrides: null,
+        crankLog: null,
+        memoProgram: null,
+        lockerTestConfig,
       } as any)
       .remainingAccounts(remainingPage1)
       .rpc();
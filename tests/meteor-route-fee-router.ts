@@ -9,7 +9,6 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   createMint,
   createAccount,
-  getAssociatedTokenAddress,
 } from "@solana/spl-token";
 
 describe("meteor-route-fee-router", () => {
@@ -43,13 +42,30 @@ describe("meteor-route-fee-router", () => {
   const cpAmmProgram: PublicKey = Keypair.generate().publicKey;
   let quoteTreasury: PublicKey;
 
-  function computePageHash(pageIndex: number, investors: {stream: PublicKey, investor: PublicKey}[]): Uint8Array {
+  function computePageHash(dayEpoch: number, generation: number, pageIndex: number, investors: {stream: PublicKey, investor: PublicKey, index: number}[]): Uint8Array {
+    const seedLen = Buffer.alloc(4);
+    seedLen.writeUInt32LE(Buffer.byteLength(vaultSeed));
+    const day = Buffer.alloc(8);
+    day.writeBigUInt64LE(BigInt(dayEpoch));
+    const gen = Buffer.alloc(2);
+    gen.writeUInt16LE(generation);
     const idx = Buffer.alloc(8);
     idx.writeBigUInt64LE(BigInt(pageIndex));
-    const chunks: Buffer[] = [idx];
+    const chunks: Buffer[] = [
+      Buffer.from("meteor-route:page:v2"),
+      program.programId.toBuffer(),
+      seedLen,
+      Buffer.from(vaultSeed),
+      day,
+      gen,
+      idx,
+    ];
     for (const it of investors) {
       chunks.push(Buffer.from(it.stream.toBytes()));
       chunks.push(Buffer.from(it.investor.toBytes()));
+      const index = Buffer.alloc(4);
+      index.writeUInt32LE(it.index);
+      chunks.push(index);
     }
     const concatenated = Buffer.concat(chunks);
     const digest = createHash("sha256").update(concatenated).digest();
@@ -106,8 +122,11 @@ describe("meteor-route-fee-router", () => {
     // Create a dummy pool pubkey (account not created, as pool validation tests are skipped)
     // The pool keypair is only used for passing the pubkey to initializePolicy
 
-    // Derive the program quote treasury ATA for the PDA authority (off-curve owner)
-    quoteTreasury = await getAssociatedTokenAddress(quoteMintPk, positionOwnerPda, true);
+    // Derive the program quote treasury PDA (token account created by the position initializer)
+    [quoteTreasury] = PublicKey.findProgramAddressSync(
+      [Buffer.from(vaultSeed), Buffer.from("treasury"), quoteMintPk.toBuffer()],
+      program.programId
+    );
   });
 
   describe("Initialization", () => {
@@ -120,18 +139,23 @@ describe("meteor-route-fee-router", () => {
       const tx = await program.methods
         .initializePolicy(
           vaultSeed,
-          investorFeeShareBps,
-          dailyCapQuoteLamports,
-          minPayoutLamports,
-          policyFundMissingAta,
-          new BN(10_000_000)
+          {
+            investorFeeShareBps,
+            dailyCapQuoteLamports,
+            minPayoutLamports,
+            policyFundMissingAta,
+            y0TotalAllocation: new BN(10_000_000),
+            allowedQuoteMintRisks: 0,
+          }
         )
         .accounts({
           authority,
+          payer: authority,
           policyPda,
           quoteMint: quoteMintPk,
           baseMint: baseMintPk,
           pool: pool.publicKey,
+          cpAmmProgram: CP_AMM_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
         } as any)
@@ -146,6 +170,14 @@ describe("meteor-route-fee-router", () => {
       expect(policyAccount.dailyCapQuoteLamports.toNumber()).to.equal(0);
       expect(policyAccount.minPayoutLamports.toNumber()).to.equal(1000);
       expect(policyAccount.policyFundMissingAta).to.equal(policyFundMissingAta);
+
+      // The vault is listed in the authority's registry
+      const [vaultRegistry] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_registry"), authority.toBuffer()],
+        program.programId
+      );
+      const registry = await program.account.vaultRegistry.fetch(vaultRegistry);
+      expect(registry.vaults.map((v) => v.toBase58())).to.include(policyPda.toBase58());
     });
 
     it("Initializes progress PDA with zeroed state", async () => {
@@ -153,6 +185,7 @@ describe("meteor-route-fee-router", () => {
         .initializeProgress(vaultSeed)
         .accounts({
           authority,
+          payer: authority,
           policyPda,
           progressPda,
           systemProgram: SystemProgram.programId,
@@ -186,6 +219,7 @@ describe("meteor-route-fee-router", () => {
         )
         .accounts({
           authority,
+          payer: authority,
           policyPda,
           positionOwnerPda,
           cpAmmProgram: CP_AMM_PROGRAM_ID,
@@ -200,6 +234,7 @@ describe("meteor-route-fee-router", () => {
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          baseTokenProgram: TOKEN_PROGRAM_ID,
         } as any)
         .signers([mockPosition])
         .rpc();
@@ -229,18 +264,23 @@ describe("meteor-route-fee-router", () => {
         await program.methods
           .initializePolicy(
             testVault,
-            invalidBps,
-            new BN(0),
-            new BN(1000),
-            true,
-            new BN(10_000_000)
+            {
+              investorFeeShareBps: invalidBps,
+              dailyCapQuoteLamports: new BN(0),
+              minPayoutLamports: new BN(1000),
+              policyFundMissingAta: true,
+              y0TotalAllocation: new BN(10_000_000),
+              allowedQuoteMintRisks: 0,
+            }
           )
           .accounts({
             authority,
+            payer: authority,
             policyPda: policyPdaTest,
             quoteMint: quoteMintPk,
             baseMint: baseMintPk,
             pool: pool.publicKey,
+            cpAmmProgram: CP_AMM_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
           } as any)
@@ -252,6 +292,43 @@ describe("meteor-route-fee-router", () => {
       }
     });
 
+    it("Rejects a quote mint with a freeze authority unless allowed", async () => {
+      const freezableMint = await createMint(provider.connection, payer, authority, authority, 9);
+      const testVault = "test_vault_freeze";
+      const [policyPdaTest] = PublicKey.findProgramAddressSync(
+        [Buffer.from(testVault), Buffer.from("policy")],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .initializePolicy(testVault, {
+            investorFeeShareBps: 7000,
+            dailyCapQuoteLamports: new BN(0),
+            minPayoutLamports: new BN(1000),
+            policyFundMissingAta: true,
+            y0TotalAllocation: new BN(10_000_000),
+            allowedQuoteMintRisks: 0,
+          })
+          .accounts({
+            authority,
+            payer: authority,
+            policyPda: policyPdaTest,
+            quoteMint: freezableMint,
+            baseMint: baseMintPk,
+            pool: pool.publicKey,
+            cpAmmProgram: CP_AMM_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          } as any)
+          .rpc();
+
+        expect.fail("Should have thrown error for a freezable quote mint");
+      } catch (e: any) {
+        expect(String(e)).to.include("QuoteMintRiskNotAllowed");
+      }
+    });
+
     it.skip("Rejects invalid tick range (requires valid CP-AMM Pool account)", async () => {
       const invalidTickLower = 11000;
       const invalidTickUpper = 8000; // Lower > Upper
@@ -268,16 +345,28 @@ describe("meteor-route-fee-router", () => {
           [Buffer.from(vault2), Buffer.from("investor_fee_pos_owner")],
           program.programId
         );
-        const quoteTreasury2 = await getAssociatedTokenAddress(quoteMintPk, positionOwnerPda2, true);
+        const [quoteTreasury2] = PublicKey.findProgramAddressSync(
+          [Buffer.from(vault2), Buffer.from("treasury"), quoteMintPk.toBuffer()],
+          program.programId
+        );
 
         await program.methods
-          .initializePolicy(vault2, 7000, new BN(0), new BN(1000), true, new BN(10_000_000))
+          .initializePolicy(vault2, {
+            investorFeeShareBps: 7000,
+            dailyCapQuoteLamports: new BN(0),
+            minPayoutLamports: new BN(1000),
+            policyFundMissingAta: true,
+            y0TotalAllocation: new BN(10_000_000),
+            allowedQuoteMintRisks: 0,
+          })
           .accounts({
             authority,
+            payer: authority,
             policyPda: policyPda2,
             quoteMint: quoteMintPk,
             baseMint: baseMintPk,
             pool: pool.publicKey,
+            cpAmmProgram: CP_AMM_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
           } as any)
@@ -294,6 +383,7 @@ describe("meteor-route-fee-router", () => {
           )
           .accounts({
             authority,
+            payer: authority,
             policyPda: policyPda2,
             positionOwnerPda: positionOwnerPda2,
             cpAmmProgram: CP_AMM_PROGRAM_ID,
@@ -308,6 +398,7 @@ describe("meteor-route-fee-router", () => {
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            baseTokenProgram: TOKEN_PROGRAM_ID,
           } as any)
           .preInstructions([
             SystemProgram.createAccount({
@@ -328,6 +419,199 @@ describe("meteor-route-fee-router", () => {
     });
   });
 
+  describe("Multisig Authority", () => {
+    // A Squads vault signs as the policy authority but holds no SOL; a relayer pays rent
+    const msAuthority = Keypair.generate();
+    const msVault = `ms_vault_${Date.now()}`;
+    let msPolicy: PublicKey;
+    let msProgress: PublicKey;
+
+    // update_policy takes 47 optional fields; only the fee share is set here
+    const updateArgs = (feeShareBps: number | null) =>
+      ({ investorFeeShareBps: feeShareBps } as any);
+
+    before(() => {
+      [msPolicy] = PublicKey.findProgramAddressSync(
+        [Buffer.from(msVault), Buffer.from("policy")],
+        program.programId
+      );
+      [msProgress] = PublicKey.findProgramAddressSync(
+        [Buffer.from(msVault), Buffer.from("progress")],
+        program.programId
+      );
+    });
+
+    it("Initializes policy and progress with a separate payer", async () => {
+      await program.methods
+        .initializePolicy(msVault, {
+          investorFeeShareBps: 7000,
+          dailyCapQuoteLamports: new BN(0),
+          minPayoutLamports: new BN(1000),
+          policyFundMissingAta: true,
+          y0TotalAllocation: new BN(10_000_000),
+          allowedQuoteMintRisks: 0,
+        })
+        .accounts({
+          authority: msAuthority.publicKey,
+          payer: authority,
+          policyPda: msPolicy,
+          quoteMint: quoteMintPk,
+          baseMint: baseMintPk,
+          pool: pool.publicKey,
+          cpAmmProgram: CP_AMM_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([msAuthority])
+        .rpc();
+
+      await program.methods
+        .initializeProgress(msVault)
+        .accounts({
+          authority: msAuthority.publicKey,
+          payer: authority,
+          policyPda: msPolicy,
+          progressPda: msProgress,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([msAuthority])
+        .rpc();
+
+      const policyAccount = await program.account.policyPda.fetch(msPolicy);
+      expect(policyAccount.authority.toBase58()).to.equal(msAuthority.publicKey.toBase58());
+      expect(await provider.connection.getBalance(msAuthority.publicKey)).to.equal(0);
+    });
+
+    it("Updates the policy with only the authority's signature", async () => {
+      await (program.methods as any)
+        .updatePolicy(msVault, updateArgs(5000))
+        .accounts({
+          authority: msAuthority.publicKey,
+          policyPda: msPolicy,
+        })
+        .signers([msAuthority])
+        .rpc();
+
+      const policyAccount = await program.account.policyPda.fetch(msPolicy);
+      expect(policyAccount.investorFeeShareBps).to.equal(5000);
+    });
+
+    it("Rejects a policy update signed by the payer instead of the authority", async () => {
+      try {
+        await (program.methods as any)
+          .updatePolicy(msVault, updateArgs(6000))
+          .accounts({
+            authority,
+            policyPda: msPolicy,
+          })
+          .rpc();
+        expect.fail("Should have thrown error for wrong authority");
+      } catch (e: any) {
+        expect(String(e)).to.include("ConstraintHasOne");
+      }
+    });
+
+    it("Pauses and resumes the policy with only the authority's signature", async () => {
+      for (const paused of [true, false]) {
+        await program.methods
+          .setPolicyPaused(msVault, paused)
+          .accounts({
+            authority: msAuthority.publicKey,
+            policyPda: msPolicy,
+          } as any)
+          .signers([msAuthority])
+          .rpc();
+
+        const policyAccount = await program.account.policyPda.fetch(msPolicy);
+        expect(policyAccount.paused).to.equal(paused);
+      }
+    });
+
+    it("Amends the pool and mints while the vault has no position", async () => {
+      const [msPositionOwner] = PublicKey.findProgramAddressSync(
+        [Buffer.from(msVault), Buffer.from("investor_fee_pos_owner")],
+        program.programId
+      );
+      const amend = (poolPk: PublicKey) =>
+        program.methods
+          .amendPolicyTargets(msVault)
+          .accounts({
+            authority: msAuthority.publicKey,
+            policyPda: msPolicy,
+            positionOwnerPda: msPositionOwner,
+            quoteMint: quoteMintPk,
+            baseMint: baseMintPk,
+            pool: poolPk,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          } as any)
+          .signers([msAuthority])
+          .rpc();
+
+      const mistyped = Keypair.generate().publicKey;
+      await amend(mistyped);
+      let policyAccount = await program.account.policyPda.fetch(msPolicy);
+      expect(policyAccount.poolPubkey.toBase58()).to.equal(mistyped.toBase58());
+
+      await amend(pool.publicKey);
+      policyAccount = await program.account.policyPda.fetch(msPolicy);
+      expect(policyAccount.poolPubkey.toBase58()).to.equal(pool.publicKey.toBase58());
+    });
+
+    it("Reinitializes only a paused vault and bumps its generation", async () => {
+      const reinitialize = () =>
+        program.methods
+          .reinitializeVault(msVault)
+          .accounts({
+            authority: msAuthority.publicKey,
+            policyPda: msPolicy,
+            progressPda: msProgress,
+            paidBitmap: null,
+          } as any)
+          .signers([msAuthority])
+          .rpc();
+
+      try {
+        await reinitialize();
+        expect.fail("Should have thrown error for a running vault");
+      } catch (e: any) {
+        expect(String(e)).to.include("VaultNotReinitializable");
+      }
+
+      const setPaused = (paused: boolean) =>
+        program.methods
+          .setPolicyPaused(msVault, paused)
+          .accounts({ authority: msAuthority.publicKey, policyPda: msPolicy } as any)
+          .signers([msAuthority])
+          .rpc();
+      await setPaused(true);
+      await reinitialize();
+      await setPaused(false);
+
+      const progressAccount = await program.account.progressPda.fetch(msProgress);
+      expect(progressAccount.generation).to.equal(1);
+      expect(progressAccount.lastDistributionTs.toNumber()).to.equal(0);
+    });
+
+    it("Rejects a governance handover to an account that is not a Realms governance", async () => {
+      // A system-owned key can't sign proposals, so handing it the policy would orphan the vault
+      const notGovernance = Keypair.generate().publicKey;
+      try {
+        await program.methods
+          .setPolicyAuthority(msVault, notGovernance)
+          .accounts({
+            authority: msAuthority.publicKey,
+            policyPda: msPolicy,
+            governance: notGovernance,
+          } as any)
+          .signers([msAuthority])
+          .rpc();
+        expect.fail("Should have thrown error for a non-governance account");
+      } catch (e: any) {
+        expect(String(e)).to.include("InvalidGovernance");
+      }
+    });
+  });
+
   describe("Distribution Logic", () => {
     it("Enforces 24-hour gate on distribution", async () => {
       // This test would require manipulating time or waiting 24h
@@ -414,11 +698,18 @@ describe("meteor-route-fee-router", () => {
     it("Computes page_hash deterministically (client helper)", async () => {
       const pageIndex = 0;
       const investors = [
-        { stream: Keypair.generate().publicKey, investor: Keypair.generate().publicKey },
-        { stream: Keypair.generate().publicKey, investor: Keypair.generate().publicKey },
+        { stream: Keypair.generate().publicKey, investor: Keypair.generate().publicKey, index: 0 },
+        { stream: Keypair.generate().publicKey, investor: Keypair.generate().publicKey, index: 1 },
       ];
-      const h = computePageHash(pageIndex, investors);
+      const h = computePageHash(20_000, 0, pageIndex, investors);
       expect(h.byteLength).to.equal(32);
+      // Bound to the day: the same page hashes differently on the next one
+      expect(Buffer.from(computePageHash(20_001, 0, pageIndex, investors)).equals(Buffer.from(h))).to.equal(false);
+      // And to the vault generation, so pages from before a reinitialize don't replay
+      expect(Buffer.from(computePageHash(20_000, 1, pageIndex, investors)).equals(Buffer.from(h))).to.equal(false);
+      // And to each investor's list index
+      const shifted = investors.map((it) => ({ ...it, index: it.index + 1 }));
+      expect(Buffer.from(computePageHash(20_000, 0, pageIndex, shifted)).equals(Buffer.from(h))).to.equal(false);
     });
   });
 